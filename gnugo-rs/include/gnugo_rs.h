@@ -0,0 +1,55 @@
+/* Copyright (C) 2026 wood&zulu_ai
+ * License: GPL-3.0-or-later
+ *
+ * C API of gnugo-rs. Build the library with
+ *   cargo rustc --release --lib --features cdylib --crate-type cdylib
+ *
+ * Colors and vertices follow GNU Go's board.h: EMPTY 0, WHITE 1, BLACK 2;
+ * (i, j) is 0-based with i the row from the top; (-1, -1) is a pass.
+ * Functions returning int use 1 for success/true and 0 for failure/false.
+ */
+
+#ifndef GNUGO_RS_H
+#define GNUGO_RS_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define GG_EMPTY 0
+#define GG_WHITE 1
+#define GG_BLACK 2
+#define GG_PASS (-1)
+
+typedef struct GgBoard GgBoard;
+
+GgBoard *gg_board_new(int size);
+void gg_board_free(GgBoard *board);
+int gg_board_size(const GgBoard *board);
+void gg_clear_board(GgBoard *board);
+
+void gg_set_komi(GgBoard *board, float komi);
+float gg_get_komi(const GgBoard *board);
+/* 1 to 10 like GNU Go's --level; new boards play at 5 */
+int gg_set_level(GgBoard *board, int level);
+
+int gg_get_color(const GgBoard *board, int i, int j);
+int gg_to_move(const GgBoard *board);
+int gg_is_legal(const GgBoard *board, int i, int j, int color);
+int gg_play(GgBoard *board, int i, int j, int color);
+int gg_genmove(GgBoard *board, int color, int *i, int *j);
+int gg_undo(GgBoard *board);
+
+int gg_captures(const GgBoard *board, int color);
+int gg_countlib(const GgBoard *board, int i, int j);
+/* Returns Black minus White minus komi */
+float gg_score(const GgBoard *board, float *black, float *white);
+
+int gg_load_sgf(GgBoard *board, const char *path);
+const char *gg_version(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* GNUGO_RS_H */
@@ -0,0 +1,52 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Analyze subcommand: writes the engine's view of a game record as JSON,
+//! with the fuseki and joseki recognized in its opening
+
+use std::fs;
+
+use crate::cli::{flag_value, parse_flag};
+use crate::engine::analysis::{analyze, DEFAULT_CANDIDATES};
+use crate::engine::game::Game;
+use crate::json::JsonValue;
+use crate::sgf::opening::{annotate, recognize};
+use crate::sgf::SGFHandler;
+
+/// Runs the analyze subcommand
+pub fn run(args: &[String]) -> Result<(), String> {
+    let path = args
+        .iter()
+        .find(|a| a.ends_with(".sgf"))
+        .ok_or("Usage: gnugo_rs analyze game.sgf [--candidates N] [--output FILE] [--annotate FILE]")?;
+    let count = parse_flag(args, "--candidates", DEFAULT_CANDIDATES)?;
+
+    let handler = SGFHandler::new();
+    let tree = handler.load_file(path)?;
+    let mut game = Game::new(19);
+    handler.apply_to_game(&tree, &mut game)?;
+
+    let events = recognize(&tree)?;
+    if let Some(annotated) = flag_value(args, "--annotate") {
+        let mut tree = tree.clone();
+        annotate(&mut tree, &events);
+        fs::write(annotated, handler.tree_to_sgf(&tree)).map_err(|e| format!("Cannot write '{}': {}", annotated, e))?;
+    }
+
+    let mut json = analyze(&game, count).to_json();
+    if let JsonValue::Object(pairs) = &mut json {
+        let openings = events
+            .iter()
+            .map(|e| JsonValue::object(vec![("move", e.move_number.into()), ("description", e.description.clone().into())]))
+            .collect();
+        pairs.push(("openings".to_string(), JsonValue::Array(openings)));
+    }
+    let json = json.to_string();
+    match flag_value(args, "--output") {
+        Some(output) => fs::write(output, json + "\n").map_err(|e| format!("Cannot write '{}': {}", output, e)),
+        None => {
+            println!("{}", json);
+            Ok(())
+        }
+    }
+}
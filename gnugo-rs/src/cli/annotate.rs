@@ -0,0 +1,106 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Annotate subcommand: reviews a game record and comments on the moves
+//! where the evaluation drops the most
+
+use std::fs;
+
+use crate::cli::{flag_value, parse_flag};
+use crate::engine::board::Stone;
+use crate::gtp::format_move;
+use crate::sgf::review::review_moves;
+use crate::sgf::{append_comment, for_each_main_line_node, is_move_node, SGFHandler, SGFProperty, SGFTree};
+
+/// Evaluation loss that counts as a mistake unless `--threshold` says otherwise
+pub const DEFAULT_THRESHOLD: i32 = 10;
+
+const USAGE: &str = "Usage: gnugo_rs annotate game.sgf [--threshold N] [--output FILE]";
+
+/// A move that lost more than the threshold
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mistake {
+    /// Move nodes played so far, passes included
+    pub move_number: usize,
+    pub color: Stone,
+    /// `None` for a pass
+    pub played: Option<(usize, usize)>,
+    /// Evaluation after the engine's move minus after the played one,
+    /// from the mover's point of view
+    pub loss: i32,
+    pub best: (usize, usize),
+}
+
+impl Mistake {
+    /// SGF comment describing the mistake
+    pub fn comment(&self, size: usize) -> String {
+        let player = if self.color == Stone::Black { "Black" } else { "White" };
+        let played = self.played.map_or("pass".to_string(), |(x, y)| format_move(x, y, size));
+        format!(
+            "{} {} loses {} by the engine's evaluation; it prefers {}",
+            player,
+            played,
+            self.loss,
+            format_move(self.best.0, self.best.1, size)
+        )
+    }
+}
+
+/// Replays the main line of `tree` and returns the moves whose evaluation
+/// falls more than `threshold` below the engine's preferred move
+pub fn review(tree: &SGFTree, threshold: i32) -> Result<Vec<Mistake>, String> {
+    Ok(review_moves(tree)?
+        .into_iter()
+        .filter_map(|r| {
+            let best = r.best?;
+            (r.loss > threshold && Some(best) != r.played).then_some(Mistake {
+                move_number: r.move_number,
+                color: r.color,
+                played: r.played,
+                loss: r.loss,
+                best,
+            })
+        })
+        .collect())
+}
+
+/// Comments on each mistake and marks it as a bad move (`BM`)
+pub fn annotate(tree: &mut SGFTree, mistakes: &[Mistake], size: usize) {
+    for_each_main_line_node(tree, |move_number, node| {
+        if !is_move_node(node) {
+            return;
+        }
+        if let Some(mistake) = mistakes.iter().find(|m| m.move_number == move_number) {
+            append_comment(node, &mistake.comment(size));
+            node.properties.insert("BM".to_string(), vec![SGFProperty::Double(1)]);
+        }
+    });
+}
+
+/// Runs the annotate subcommand
+pub fn run(args: &[String]) -> Result<(), String> {
+    let path = args.iter().find(|a| a.ends_with(".sgf")).ok_or(USAGE)?;
+    let threshold = parse_flag(args, "--threshold", DEFAULT_THRESHOLD)?;
+
+    let handler = SGFHandler::new();
+    let mut tree = handler.load_file(path)?;
+    let mistakes = review(&tree, threshold)?;
+    let size = match tree.root.properties.get("SZ").and_then(|v| v.first()) {
+        Some(SGFProperty::Number(n)) => *n as usize,
+        _ => 19,
+    };
+    annotate(&mut tree, &mistakes, size);
+
+    let sgf = handler.tree_to_sgf(&tree);
+    match flag_value(args, "--output") {
+        Some(output) => {
+            fs::write(output, sgf).map_err(|e| format!("Cannot write '{}': {}", output, e))?;
+            println!("{} move{} annotated", mistakes.len(), if mistakes.len() == 1 { "" } else { "s" });
+            Ok(())
+        }
+        None => {
+            print!("{}", sgf);
+            Ok(())
+        }
+    }
+}
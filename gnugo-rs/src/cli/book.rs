@@ -0,0 +1,40 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Book subcommand: builds an opening book from a directory of game records
+
+use std::path::{Path, PathBuf};
+
+use crate::cli::{flag_value, parse_flag};
+use crate::sgf::book::{OpeningBook, BOOK_MOVES};
+use crate::sgf::SGFHandler;
+
+const USAGE: &str = "Usage: gnugo_rs book DIR --output FILE [--moves N]";
+
+/// Runs the book subcommand. Records that fail to load are reported and
+/// left out of the book
+pub fn run(args: &[String]) -> Result<(), String> {
+    let dir = args.first().filter(|a| !a.starts_with("--")).ok_or(USAGE)?;
+    let output = flag_value(args, "--output").ok_or(USAGE)?;
+    let moves = parse_flag(args, "--moves", BOOK_MOVES)?;
+
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map_err(|e| format!("Cannot read directory '{}': {}", dir, e))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("sgf")))
+        .collect();
+    paths.sort();
+
+    let handler = SGFHandler::new();
+    let mut book = OpeningBook::new();
+    let mut games = 0;
+    for path in &paths {
+        match handler.load_file(path).and_then(|tree| book.add_game(&tree, moves)) {
+            Ok(()) => games += 1,
+            Err(e) => eprintln!("{}: {}", path.display(), e),
+        }
+    }
+    book.save(Path::new(output))?;
+    println!("{} games, {} positions written to {}", games, book.len(), output);
+    Ok(())
+}
@@ -0,0 +1,239 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! CGOS subcommand: plays rated games on a Computer Go Server
+//!
+//! CGOS speaks a line protocol. The server sends `protocol`, `username` and
+//! `password` prompts, then `setup`, `play`, `genmove` and `gameover`
+//! commands. Only `genmove` and the login prompts expect an answer; after
+//! `gameover` the client sends `ready` to be paired again.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::thread;
+use std::time::Duration;
+
+use crate::cli::tournament::{engine_from_spec, EngineMove, MatchEngine};
+use crate::cli::{flag_value, parse_flag};
+use crate::config::{Config, TimeSettings};
+use crate::engine::board::Stone;
+use crate::engine::rules::GameRules;
+use crate::gtp::{format_move, parse_gtp_move};
+
+/// Identification sent in answer to the `protocol` prompt
+const CLIENT_ID: &str = "e1 gnugo_rs";
+
+/// Longest wait between reconnection attempts
+const MAX_BACKOFF: Duration = Duration::from_secs(120);
+
+/// Protocol state for one connection
+pub struct CgosSession<'a> {
+    engine: &'a mut dyn MatchEngine,
+    username: String,
+    password: String,
+    /// Stop after this many games; 0 plays forever
+    max_games: usize,
+    games_played: usize,
+    /// Board size of the game in progress
+    size: Option<usize>,
+    /// Last `Error:` line from the server
+    pub last_error: Option<String>,
+}
+
+impl<'a> CgosSession<'a> {
+    pub fn new(engine: &'a mut dyn MatchEngine, username: &str, password: &str, max_games: usize) -> Self {
+        CgosSession {
+            engine,
+            username: username.to_string(),
+            password: password.to_string(),
+            max_games,
+            games_played: 0,
+            size: None,
+            last_error: None,
+        }
+    }
+
+    /// Whether the requested number of games has been played
+    pub fn finished(&self) -> bool {
+        self.max_games > 0 && self.games_played >= self.max_games
+    }
+
+    pub fn games_played(&self) -> usize {
+        self.games_played
+    }
+
+    /// Handles one server line and returns the reply to send, if any
+    pub fn handle_line(&mut self, line: &str) -> Result<Option<String>, String> {
+        let line = line.trim();
+        if let Some(message) = line.strip_prefix("Error:") {
+            self.last_error = Some(message.trim().to_string());
+            eprintln!("CGOS error: {}", message.trim());
+            return Ok(None);
+        }
+
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((&command, args)) = words.split_first() else {
+            return Ok(None);
+        };
+
+        match command {
+            "protocol" => Ok(Some(CLIENT_ID.to_string())),
+            "username" => Ok(Some(self.username.clone())),
+            "password" => Ok(Some(self.password.clone())),
+            "info" => {
+                println!("CGOS: {}", args.join(" "));
+                Ok(None)
+            }
+            "setup" => self.setup(args).map(|_| None),
+            "play" => {
+                let [color, vertex, ..] = args else {
+                    return Err(format!("malformed play: {}", line));
+                };
+                let color = parse_color(color)?;
+                let point = self.parse_vertex(vertex)?;
+                self.engine.play(color, point).map_err(|e| e.to_string())?;
+                Ok(None)
+            }
+            "genmove" => {
+                let [color, rest @ ..] = args else {
+                    return Err(format!("malformed genmove: {}", line));
+                };
+                let color = parse_color(color)?;
+                let clock = rest
+                    .first()
+                    .and_then(|ms| ms.parse::<u64>().ok())
+                    .map(|ms| (Duration::from_millis(ms), 0));
+                // The server waits for an answer, so a failed engine resigns
+                let reply = match self.engine.genmove(color, clock, None) {
+                    Ok(EngineMove::Play(x, y)) => format_move(x, y, self.size.unwrap_or(19)),
+                    Ok(EngineMove::Pass) => "pass".to_string(),
+                    Ok(EngineMove::Resign) => "resign".to_string(),
+                    Err(e) => {
+                        eprintln!("CGOS: genmove failed, resigning: {}", e);
+                        "resign".to_string()
+                    }
+                };
+                Ok(Some(reply))
+            }
+            "gameover" => {
+                // gameover <date> <result> [<error>]
+                println!("Game over: {}", args.get(1).copied().unwrap_or("?"));
+                self.size = None;
+                self.games_played += 1;
+                if self.finished() {
+                    Ok(None)
+                } else {
+                    Ok(Some("ready".to_string()))
+                }
+            }
+            _ => {
+                eprintln!("CGOS: ignoring '{}'", line);
+                Ok(None)
+            }
+        }
+    }
+
+    /// `setup <id> <size> <komi> <time_ms> <white> <black> [<move> <time_ms>]...`
+    /// The trailing moves replay a game in progress after a reconnect.
+    fn setup(&mut self, args: &[&str]) -> Result<(), String> {
+        if args.len() < 6 {
+            return Err(format!("malformed setup: {}", args.join(" ")));
+        }
+        let size: usize = args[1].parse().map_err(|_| format!("bad board size '{}'", args[1]))?;
+        let komi: f32 = args[2].parse().map_err(|_| format!("bad komi '{}'", args[2]))?;
+        let time_ms: u64 = args[3].parse().unwrap_or(0);
+        println!("Game {}: {} (W) vs {} (B), {}x{}, komi {}", args[0], args[4], args[5], size, size, komi);
+
+        let time = TimeSettings { main_time: time_ms / 1000, byo_yomi_time: 0, byo_yomi_stones: 0 };
+        self.engine.new_game(size, komi, GameRules::default(), &time).map_err(|e| e.to_string())?;
+        self.size = Some(size);
+
+        let mut color = Stone::Black;
+        for pair in args[6..].chunks(2) {
+            let point = self.parse_vertex(pair[0])?;
+            self.engine.play(color, point).map_err(|e| e.to_string())?;
+            color = if color == Stone::Black { Stone::White } else { Stone::Black };
+        }
+        Ok(())
+    }
+
+    fn parse_vertex(&self, vertex: &str) -> Result<Option<(usize, usize)>, String> {
+        if vertex.eq_ignore_ascii_case("pass") {
+            return Ok(None);
+        }
+        let size = self.size.ok_or("move received before setup")?;
+        parse_gtp_move(vertex, size)
+            .map(Some)
+            .ok_or_else(|| format!("bad vertex '{}'", vertex))
+    }
+}
+
+fn parse_color(name: &str) -> Result<Stone, String> {
+    match name.to_lowercase().as_str() {
+        "b" | "black" => Ok(Stone::Black),
+        "w" | "white" => Ok(Stone::White),
+        _ => Err(format!("bad color '{}'", name)),
+    }
+}
+
+/// Runs one connection until the server closes it or the session finishes
+fn serve_connection(address: &str, session: &mut CgosSession) -> io::Result<()> {
+    let stream = TcpStream::connect(address)?;
+    let mut writer = stream.try_clone()?;
+    let reader = BufReader::new(stream);
+
+    for line in reader.lines() {
+        let line = line?;
+        match session.handle_line(&line) {
+            Ok(Some(reply)) => {
+                writeln!(writer, "{}", reply)?;
+                writer.flush()?;
+            }
+            Ok(None) => {}
+            Err(e) => eprintln!("CGOS protocol error: {}", e),
+        }
+        if session.finished() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Runs the cgos subcommand
+pub fn run(args: &[String], config: &Config) -> Result<(), String> {
+    let usage = "Usage: gnugo_rs cgos --server HOST:PORT --user NAME --password PW [--engine SPEC] [--games N]";
+    let address = flag_value(args, "--server").ok_or(usage)?;
+    let username = flag_value(args, "--user").ok_or(usage)?;
+    let password = flag_value(args, "--password").ok_or(usage)?;
+    let max_games: usize = parse_flag(args, "--games", 0)?;
+    let mut engine = engine_from_spec(flag_value(args, "--engine").unwrap_or("internal"), config.strength, config.seed)?;
+
+    let mut session = CgosSession::new(engine.as_mut(), username, password, max_games);
+    let mut backoff = Duration::from_secs(5);
+
+    loop {
+        let before = session.games_played();
+        match serve_connection(address, &mut session) {
+            Ok(()) => eprintln!("CGOS: connection closed"),
+            Err(e) => eprintln!("CGOS: connection failed: {}", e),
+        }
+        if session.finished() {
+            break;
+        }
+        // A server that rejects the login before any game would reject every retry
+        if before == 0 && session.games_played() == 0 {
+            if let Some(error) = &session.last_error {
+                return Err(format!("server refused login: {}", error));
+            }
+        }
+        if session.games_played() > before {
+            backoff = Duration::from_secs(5);
+        }
+        eprintln!("CGOS: reconnecting in {}s", backoff.as_secs());
+        thread::sleep(backoff);
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+
+    println!("Played {} games", session.games_played());
+    Ok(())
+}
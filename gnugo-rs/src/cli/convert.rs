@@ -0,0 +1,29 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Convert subcommand: rewrites GIB, NGF or SGF records as SGF
+
+use std::fs;
+
+use crate::cli::flag_value;
+use crate::sgf::SGFHandler;
+
+/// Runs the convert subcommand
+pub fn run(args: &[String]) -> Result<(), String> {
+    let usage = "Usage: gnugo_rs convert game.gib|game.ngf|game.sgf [--output FILE]";
+    let output = flag_value(args, "--output");
+    let input = args
+        .iter()
+        .find(|a| !a.starts_with("--") && Some(a.as_str()) != output)
+        .ok_or(usage)?;
+
+    let handler = SGFHandler::new();
+    let sgf = handler.tree_to_sgf(&handler.load_file(input)?);
+    match output {
+        Some(path) => fs::write(path, sgf).map_err(|e| format!("Cannot write '{}': {}", path, e)),
+        None => {
+            print!("{}", sgf);
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,42 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Fuseki subcommand: builds a whole-board fuseki database from a
+//! directory of game records
+
+use std::path::{Path, PathBuf};
+
+use crate::cli::{flag_value, parse_flag};
+use crate::patterns::fuseki::{FusekiDatabase, FUSEKI_MOVES};
+use crate::sgf::SGFHandler;
+
+const USAGE: &str = "Usage: gnugo_rs fuseki DIR --output FILE [--moves N]";
+
+/// Runs the fuseki subcommand. Records that fail to load are reported and
+/// left out, as are games on boards the database has no openings for
+pub fn run(args: &[String]) -> Result<(), String> {
+    let dir = args.first().filter(|a| !a.starts_with("--")).ok_or(USAGE)?;
+    let output = flag_value(args, "--output").ok_or(USAGE)?;
+    let moves = parse_flag(args, "--moves", FUSEKI_MOVES)?;
+
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map_err(|e| format!("Cannot read directory '{}': {}", dir, e))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("sgf")))
+        .collect();
+    paths.sort();
+
+    let handler = SGFHandler::new();
+    let mut fuseki = FusekiDatabase::new();
+    let (mut games, mut skipped) = (0, 0);
+    for path in &paths {
+        match handler.load_file(path).and_then(|tree| fuseki.add_game(&tree, moves)) {
+            Ok(true) => games += 1,
+            Ok(false) => skipped += 1,
+            Err(e) => eprintln!("{}: {}", path.display(), e),
+        }
+    }
+    fuseki.save(Path::new(output))?;
+    println!("{} games, {} skipped for their board size, {} positions written to {}", games, skipped, fuseki.len(), output);
+    Ok(())
+}
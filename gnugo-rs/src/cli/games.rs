@@ -0,0 +1,54 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Games subcommand: queries the archive of finished games
+
+use crate::cli::{flag_value, parse_flag};
+use crate::config::Config;
+use crate::sgf::archive::{Archive, ArchiveEntry};
+
+const USAGE: &str =
+    "Usage: gnugo_rs games list [--player NAME] [--mode MODE] [--size N] [--since YYYY-MM-DD] [--archive DIR]";
+
+/// Entries matching the `games list` filters in `args`
+pub fn filter(entries: Vec<ArchiveEntry>, args: &[String]) -> Result<Vec<ArchiveEntry>, String> {
+    let player = flag_value(args, "--player").map(str::to_lowercase);
+    let mode = flag_value(args, "--mode");
+    let size: Option<usize> = match flag_value(args, "--size") {
+        Some(_) => Some(parse_flag(args, "--size", 0)?),
+        None => None,
+    };
+    let since = flag_value(args, "--since");
+
+    Ok(entries
+        .into_iter()
+        .filter(|e| {
+            player.as_ref().is_none_or(|p| e.black.to_lowercase().contains(p) || e.white.to_lowercase().contains(p))
+        })
+        .filter(|e| mode.is_none_or(|m| e.mode == m))
+        .filter(|e| size.is_none_or(|s| e.size == s))
+        .filter(|e| since.is_none_or(|d| e.date.as_str() >= d))
+        .collect())
+}
+
+/// Runs the games subcommand; the archive directory comes from `config`
+pub fn run(args: &[String], config: &Config) -> Result<(), String> {
+    if args.first().map(String::as_str) != Some("list") {
+        return Err(USAGE.to_string());
+    }
+    let dir = config
+        .archive_dir
+        .as_ref()
+        .ok_or("No game archive configured; set [archive] dir in the config or pass --archive DIR")?;
+    let entries = filter(Archive::new(dir).entries()?, &args[1..])?;
+
+    println!("{:<10}  {:<8}  {:<10}  {:>4}  {:<16}  {:<16}  {:<8}  File", "Date", "Time", "Mode", "Size", "Black", "White", "Result");
+    for e in &entries {
+        println!(
+            "{:<10}  {:<8}  {:<10}  {:>4}  {:<16}  {:<16}  {:<8}  {}",
+            e.date, e.time, e.mode, e.size, e.black, e.white, e.result, e.file
+        );
+    }
+    println!("{} game{}", entries.len(), if entries.len() == 1 { "" } else { "s" });
+    Ok(())
+}
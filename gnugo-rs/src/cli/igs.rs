@@ -0,0 +1,412 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! IGS subcommand: plays or observes games on an IGS-compatible Go server
+//!
+//! After login the client switches the server to client mode, where every
+//! line starts with a message code:
+//!
+//! - `1 N` prompt; N is 5 when idle, 6 while playing, 7 while scoring
+//! - `9 ...` information, including match requests and game results
+//! - `15 Game N I: white (caps time stones) vs black (caps time stones)`
+//!   followed by `15 12(W): D4` move lines
+//! - `7 [N] white [rk] vs. black [rk] (moves size handicap komi byo type)`
+//!   answering `games N`
+//!
+//! Vertices are in GTP notation. The bot moves when the server prompts while
+//! it is its turn, so the whole batch of move lines has been read first.
+
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::thread;
+use std::time::Duration;
+
+use crate::cli::tournament::{engine_from_spec, EngineMove, MatchEngine};
+use crate::cli::{flag_value, parse_flag};
+use crate::config::{Config, TimeSettings};
+use crate::engine::board::Stone;
+use crate::engine::rules::GameRules;
+use crate::gtp::{format_move, parse_gtp_move};
+
+/// Longest wait between reconnection attempts
+const MAX_BACKOFF: Duration = Duration::from_secs(120);
+
+/// Largest board the engine plays on
+const MAX_SIZE: usize = 25;
+
+/// The game being played or observed
+struct IgsGame {
+    id: u32,
+    size: usize,
+    /// Our color, `None` when observing
+    color: Option<Stone>,
+    /// Moves replayed into the engine so far
+    moves: usize,
+    /// Our time and byo-yomi stones from the last game header
+    clock: Option<(Duration, u32)>,
+}
+
+/// Protocol state for one connection
+pub struct IgsSession<'a> {
+    engine: &'a mut dyn MatchEngine,
+    username: String,
+    password: String,
+    /// Game to observe instead of accepting matches
+    observe: Option<u32>,
+    /// Stop after this many games; 0 plays forever
+    max_games: usize,
+    games_played: usize,
+    logged_in: bool,
+    /// Board size of the match we agreed to
+    match_size: Option<usize>,
+    game: Option<IgsGame>,
+    /// Game the following move lines belong to
+    header_game: Option<u32>,
+}
+
+impl<'a> IgsSession<'a> {
+    pub fn new(engine: &'a mut dyn MatchEngine, username: &str, password: &str, max_games: usize) -> Self {
+        IgsSession {
+            engine,
+            username: username.to_string(),
+            password: password.to_string(),
+            observe: None,
+            max_games,
+            games_played: 0,
+            logged_in: false,
+            match_size: None,
+            game: None,
+            header_game: None,
+        }
+    }
+
+    /// Observes game `id` instead of playing
+    pub fn observe(mut self, id: u32) -> Self {
+        self.observe = Some(id);
+        self
+    }
+
+    /// Whether the requested number of games has been played or observed
+    pub fn finished(&self) -> bool {
+        self.max_games > 0 && self.games_played >= self.max_games
+    }
+
+    pub fn games_played(&self) -> usize {
+        self.games_played
+    }
+
+    /// Moves of the current game replayed so far
+    pub fn moves(&self) -> Option<usize> {
+        self.game.as_ref().map(|g| g.moves)
+    }
+
+    /// Handles one server line and returns the commands to send
+    pub fn handle_line(&mut self, line: &str) -> Result<Vec<String>, String> {
+        let line = line.trim_end();
+        if !self.logged_in {
+            return self.login(line.trim());
+        }
+
+        let Some((code, rest)) = line.trim_start().split_once(' ') else {
+            return Ok(Vec::new());
+        };
+        match code {
+            "1" => self.prompt(rest.trim()),
+            "7" => self.game_info(rest),
+            "9" | "21" => self.info(rest.trim()),
+            "15" => self.game_line(rest.trim()),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    fn login(&mut self, line: &str) -> Result<Vec<String>, String> {
+        if line.starts_with("Login:") {
+            return Ok(vec![self.username.clone()]);
+        }
+        if line.starts_with("Password:") {
+            return Ok(vec![self.password.clone()]);
+        }
+        if line.contains("Invalid password") || line.contains("guest account") {
+            return Err(format!("login refused: {}", line));
+        }
+        // The first prompt after the password: switch to client mode
+        if line.starts_with("#>") || line.starts_with("1 ") {
+            self.logged_in = true;
+            println!("IGS: logged in as {}", self.username);
+            let mut commands = vec!["toggle client true".to_string(), "toggle quiet true".to_string()];
+            if let Some(id) = self.observe {
+                commands.push(format!("games {}", id));
+            }
+            return Ok(commands);
+        }
+        Ok(Vec::new())
+    }
+
+    /// `1 6` while playing: move if it is our turn; `1 7`: accept the score
+    fn prompt(&mut self, state: &str) -> Result<Vec<String>, String> {
+        match state {
+            "6" => self.maybe_move(),
+            "7" if self.game.as_ref().is_some_and(|g| g.color.is_some()) => Ok(vec!["done".to_string()]),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    fn info(&mut self, text: &str) -> Result<Vec<String>, String> {
+        // "Use <match alice B 9 10 10> or <decline alice> to respond."
+        if let Some(command) = text.strip_prefix("Use <match ").and_then(|t| t.split('>').next()) {
+            return Ok(vec![self.answer_match(command)]);
+        }
+        // "Creating match [12] with alice."
+        if let Some(rest) = text.strip_prefix("Creating match [") {
+            let id = rest.split(']').next().and_then(|id| id.trim().parse().ok()).ok_or("bad match id")?;
+            let size = self.match_size.take().unwrap_or(19);
+            self.start_game(id, size, 6.5)?;
+            println!("IGS: game {} started", id);
+            return Ok(Vec::new());
+        }
+        // "{Game 12: alice vs bot : W+Resign}"
+        if let Some(rest) = text.strip_prefix("{Game ") {
+            let id: Option<u32> = rest.split(':').next().and_then(|id| id.trim().parse().ok());
+            if id.is_some() && id == self.game.as_ref().map(|g| g.id) {
+                println!("IGS: {}", text);
+                self.game = None;
+                self.games_played += 1;
+            }
+        }
+        Ok(Vec::new())
+    }
+
+    /// Accepts even matches on supported boards while no game is running
+    fn answer_match(&mut self, command: &str) -> String {
+        let words: Vec<&str> = command.split_whitespace().collect();
+        let opponent = words.first().copied().unwrap_or("");
+        let size = words.get(2).and_then(|s| s.parse::<usize>().ok());
+
+        match size {
+            Some(size) if (2..=MAX_SIZE).contains(&size) && self.game.is_none() && self.observe.is_none() => {
+                println!("IGS: accepting {}x{} match with {}", size, size, opponent);
+                self.match_size = Some(size);
+                format!("match {}", command)
+            }
+            _ => {
+                println!("IGS: declining match with {}", opponent);
+                format!("decline {}", opponent)
+            }
+        }
+    }
+
+    /// `[12] alice [ 3k*] vs. bot [ NR ] ( 0 19 0 6.5 10 I) ( 1)` answering
+    /// `games N`: sets up the observed game
+    fn game_info(&mut self, rest: &str) -> Result<Vec<String>, String> {
+        let Some(observe) = self.observe else { return Ok(Vec::new()) };
+        let Some(id) = rest.trim().strip_prefix('[').and_then(|r| r.split(']').next()) else {
+            return Ok(Vec::new());
+        };
+        if id.trim().parse() != Ok(observe) || self.game.is_some() {
+            return Ok(Vec::new());
+        }
+        let stats = rest
+            .split('(')
+            .nth(1)
+            .and_then(|s| s.split(')').next())
+            .ok_or("malformed games line")?;
+        let fields: Vec<&str> = stats.split_whitespace().collect();
+        let size = fields.get(1).and_then(|s| s.parse().ok()).ok_or("bad board size")?;
+        let komi = fields.get(3).and_then(|s| s.parse().ok()).unwrap_or(6.5);
+        self.start_game(observe, size, komi)?;
+        self.game.as_mut().unwrap().color = None;
+        println!("IGS: observing game {} ({}x{}, komi {})", observe, size, size, komi);
+        Ok(vec![format!("observe {}", observe)])
+    }
+
+    fn start_game(&mut self, id: u32, size: usize, komi: f32) -> Result<(), String> {
+        self.engine.new_game(size, komi, GameRules::default(), &TimeSettings::default()).map_err(|e| e.to_string())?;
+        self.game = Some(IgsGame { id, size, color: None, moves: 0, clock: None });
+        Ok(())
+    }
+
+    /// Game header or move line
+    fn game_line(&mut self, text: &str) -> Result<Vec<String>, String> {
+        if let Some(header) = text.strip_prefix("Game ") {
+            return self.header(header);
+        }
+        // "12(W): D4"
+        let Some((number, rest)) = text.split_once('(') else { return Ok(Vec::new()) };
+        let Ok(number) = number.trim().parse::<usize>() else { return Ok(Vec::new()) };
+        let Some(game) = self.game.as_mut().filter(|g| Some(g.id) == self.header_game) else {
+            return Ok(Vec::new());
+        };
+        if number < game.moves {
+            return Ok(Vec::new());
+        }
+        let color = if rest.starts_with('W') { Stone::White } else { Stone::Black };
+        let vertex = rest.split_once(':').map_or("", |(_, v)| v.trim());
+        let point = if vertex.eq_ignore_ascii_case("pass") {
+            None
+        } else if vertex.starts_with("Handicap") {
+            return Err(format!("handicap stones are not supported: {}", vertex));
+        } else {
+            Some(parse_gtp_move(vertex, game.size).ok_or_else(|| format!("bad vertex '{}'", vertex))?)
+        };
+        self.engine.play(color, point).map_err(|e| e.to_string())?;
+        game.moves = number + 1;
+        if game.color.is_none() {
+            println!("IGS: game {} move {}: {} {}", game.id, number + 1, if color == Stone::Black { "B" } else { "W" }, vertex);
+        }
+        Ok(Vec::new())
+    }
+
+    /// `12 I: alice (0 598 -1) vs bot (0 600 -1)`, white first
+    fn header(&mut self, header: &str) -> Result<Vec<String>, String> {
+        let id: u32 = header.split_whitespace().next().and_then(|id| id.parse().ok()).ok_or("bad game header")?;
+        self.header_game = Some(id);
+        let username = self.username.clone();
+        let Some(game) = self.game.as_mut().filter(|g| g.id == id) else { return Ok(Vec::new()) };
+        if self.observe.is_some() {
+            return Ok(Vec::new());
+        }
+
+        let players = header.split_once(':').map_or("", |(_, p)| p);
+        let Some((white, black)) = players.split_once(" vs ") else { return Ok(Vec::new()) };
+        let (color, ours) = if white.trim_start().starts_with(&format!("{} ", username)) {
+            (Stone::White, white)
+        } else if black.trim_start().starts_with(&format!("{} ", username)) {
+            (Stone::Black, black)
+        } else {
+            return Ok(Vec::new());
+        };
+        game.color = Some(color);
+
+        // "(captures seconds stones)"; stones is -1 outside byo-yomi
+        let fields: Vec<i64> = ours
+            .split('(')
+            .nth(1)
+            .and_then(|s| s.split(')').next())
+            .map(|s| s.split_whitespace().filter_map(|f| f.parse().ok()).collect())
+            .unwrap_or_default();
+        if let [_, seconds, stones] = fields[..] {
+            game.clock = Some((Duration::from_secs(seconds.max(0) as u64), stones.max(0) as u32));
+        }
+        Ok(Vec::new())
+    }
+
+    /// Generates and sends a move when it is our turn
+    fn maybe_move(&mut self) -> Result<Vec<String>, String> {
+        let Some(game) = self.game.as_mut() else { return Ok(Vec::new()) };
+        let Some(color) = game.color else { return Ok(Vec::new()) };
+        let to_move = if game.moves % 2 == 0 { Stone::Black } else { Stone::White };
+        if to_move != color {
+            return Ok(Vec::new());
+        }
+
+        let deadline = game.clock.map(|(time, _)| time);
+        let reply = self.engine.genmove(color, game.clock, deadline).map_err(|e| e.to_string())?;
+        // The engine has played the move; skip the server's echo of it
+        game.moves += 1;
+        Ok(vec![match reply {
+            EngineMove::Play(x, y) => format_move(x, y, game.size),
+            EngineMove::Pass => "pass".to_string(),
+            EngineMove::Resign => "resign".to_string(),
+        }])
+    }
+}
+
+/// Drops telnet option negotiation (`IAC` sequences) from server output
+fn strip_telnet(bytes: &[u8]) -> Vec<u8> {
+    const IAC: u8 = 255;
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == IAC {
+            // IAC IAC is a literal 255; other commands take one option byte
+            match bytes.get(i + 1) {
+                Some(&IAC) => out.push(IAC),
+                Some(251..=254) => i += 1,
+                _ => {}
+            }
+            i += 2;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    out
+}
+
+/// Runs one connection until the server closes it or the session finishes
+fn serve_connection(address: &str, session: &mut IgsSession) -> Result<(), String> {
+    session.logged_in = false;
+    let mut stream = TcpStream::connect(address).map_err(|e| format!("connection failed: {}", e))?;
+    let mut writer = stream.try_clone().map_err(|e| e.to_string())?;
+    let mut pending = String::new();
+    let mut buffer = [0u8; 4096];
+
+    loop {
+        let read = stream.read(&mut buffer).map_err(|e| e.to_string())?;
+        if read == 0 {
+            return Err("connection closed".to_string());
+        }
+        pending.push_str(&String::from_utf8_lossy(&strip_telnet(&buffer[..read])));
+
+        // Login prompts arrive without a newline
+        let mut lines: Vec<String> = Vec::new();
+        while let Some(end) = pending.find('\n') {
+            lines.push(pending[..end].trim_end_matches('\r').to_string());
+            pending.drain(..=end);
+        }
+        let partial = pending.trim();
+        if partial.starts_with("Login:") || partial.starts_with("Password:") || partial.starts_with("#>") {
+            lines.push(std::mem::take(&mut pending));
+        }
+
+        for line in lines {
+            let commands = session.handle_line(&line)?;
+            for command in commands {
+                write!(writer, "{}\r\n", command).map_err(|e| e.to_string())?;
+            }
+            writer.flush().map_err(|e: io::Error| e.to_string())?;
+            if session.finished() {
+                writeln!(writer, "quit").ok();
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Runs the igs subcommand
+pub fn run(args: &[String], config: &Config) -> Result<(), String> {
+    let usage = "Usage: gnugo_rs igs --user NAME --password PW [--server HOST:PORT] [--engine SPEC] [--games N] [--observe GAME]";
+    let username = flag_value(args, "--user").ok_or(usage)?;
+    let password = flag_value(args, "--password").ok_or(usage)?;
+    let address = flag_value(args, "--server").unwrap_or("igs.joyjoy.net:6969");
+    let max_games: usize = parse_flag(args, "--games", 0)?;
+    let observe: Option<u32> = match flag_value(args, "--observe") {
+        Some(id) => Some(id.parse().map_err(|_| format!("Invalid game number '{}'", id))?),
+        None => None,
+    };
+    let mut engine = engine_from_spec(flag_value(args, "--engine").unwrap_or("internal"), config.strength, config.seed)?;
+
+    let mut session = IgsSession::new(engine.as_mut(), username, password, max_games);
+    if let Some(id) = observe {
+        session = session.observe(id);
+    }
+    let mut backoff = Duration::from_secs(5);
+
+    loop {
+        let before = session.games_played();
+        match serve_connection(address, &mut session) {
+            Ok(()) => break,
+            Err(e) if e.starts_with("login refused") => return Err(e),
+            Err(e) => eprintln!("IGS: {}", e),
+        }
+        if session.games_played() > before {
+            backoff = Duration::from_secs(5);
+        }
+        eprintln!("IGS: reconnecting in {}s", backoff.as_secs());
+        thread::sleep(backoff);
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+
+    println!("Played {} games", session.games_played());
+    Ok(())
+}
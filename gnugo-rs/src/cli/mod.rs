@@ -0,0 +1,61 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Command line subcommands for gnugo-rs
+
+#[cfg(feature = "native")]
+pub mod analyze;
+#[cfg(feature = "native")]
+pub mod annotate;
+#[cfg(feature = "native")]
+pub mod book;
+#[cfg(feature = "native")]
+pub mod cgos;
+#[cfg(feature = "native")]
+pub mod convert;
+#[cfg(feature = "native")]
+pub mod fuseki;
+#[cfg(feature = "native")]
+pub mod games;
+#[cfg(feature = "native")]
+pub mod igs;
+#[cfg(feature = "native")]
+pub mod ogs;
+#[cfg(feature = "native")]
+pub mod patterns;
+#[cfg(feature = "native")]
+pub mod score;
+#[cfg(feature = "native")]
+pub mod selfplay;
+#[cfg(feature = "native")]
+pub mod solve;
+#[cfg(feature = "native")]
+pub mod statistics;
+#[cfg(feature = "native")]
+pub mod strength;
+#[cfg(feature = "native")]
+pub mod svg;
+#[cfg(feature = "native")]
+pub mod tournament;
+#[cfg(feature = "native")]
+pub mod tune;
+
+use std::str::FromStr;
+
+/// Returns the value following `flag` in `args`, if present
+pub fn flag_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|a| a == flag)
+        .and_then(|i| args.get(i + 1))
+        .map(|s| s.as_str())
+}
+
+/// Parses the value following `flag`, falling back to `default` when absent
+pub fn parse_flag<T: FromStr>(args: &[String], flag: &str, default: T) -> Result<T, String> {
+    match flag_value(args, flag) {
+        Some(value) => value
+            .parse()
+            .map_err(|_| format!("Invalid value '{}' for {}", value, flag)),
+        None => Ok(default),
+    }
+}
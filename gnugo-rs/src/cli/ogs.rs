@@ -0,0 +1,613 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! OGS subcommand: fields the engine as a bot on online-go.com
+//!
+//! The realtime API is a WebSocket carrying JSON arrays: `[command, data, id]`
+//! from the client, `[event, data]` and `[id, result, error]` from the
+//! server. The bot authenticates with its API key, answers `challenge`
+//! notifications through the REST API, connects to every `active_game` and
+//! moves whenever it is its turn. Vertices are `[x, y]` from the top-left,
+//! `[-1, -1]` being a pass; moves are sent as SGF points.
+//!
+//! The TLS transport is built with the `ogs` feature; the protocol logic in
+//! [`OgsSession`] is always available.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::cli::tournament::{EngineMove, MatchEngine};
+use crate::config::TimeSettings;
+use crate::engine::board::Stone;
+use crate::engine::rules::GameRules;
+use crate::json::JsonValue;
+use crate::sgf::format_sgf_point;
+
+/// Largest board the engine plays on
+const MAX_SIZE: usize = 25;
+
+/// What the transport should do on behalf of the session
+#[derive(Debug, Clone, PartialEq)]
+pub enum OgsAction {
+    /// Socket frame to send
+    Send(JsonValue),
+    /// REST call: method, path under `/api/v1/` and JSON body
+    Rest(&'static str, String, JsonValue),
+}
+
+/// Creates an engine for each new game
+pub type EngineFactory = Box<dyn FnMut() -> Result<Box<dyn MatchEngine>, String>>;
+
+/// One game the bot is connected to
+struct OgsGame {
+    engine: Box<dyn MatchEngine>,
+    size: usize,
+    color: Stone,
+    /// Color of the next move
+    to_move: Stone,
+    /// Moves replayed into the engine so far
+    moves: usize,
+    phase: String,
+    /// Dead stones as proposed by the server, concatenated SGF points
+    removed: String,
+    /// Our remaining time and byo-yomi stones
+    clock: Option<(Duration, u32)>,
+}
+
+/// Protocol state for one bot account
+pub struct OgsSession {
+    username: String,
+    api_key: String,
+    /// Most games played at the same time
+    max_games: usize,
+    new_engine: EngineFactory,
+    player_id: Option<usize>,
+    auth_request: Option<usize>,
+    next_request: usize,
+    games: HashMap<usize, OgsGame>,
+    games_finished: usize,
+}
+
+impl OgsSession {
+    pub fn new(username: &str, api_key: &str, max_games: usize, new_engine: EngineFactory) -> Self {
+        OgsSession {
+            username: username.to_string(),
+            api_key: api_key.to_string(),
+            max_games: max_games.max(1),
+            new_engine,
+            player_id: None,
+            auth_request: None,
+            next_request: 1,
+            games: HashMap::new(),
+            games_finished: 0,
+        }
+    }
+
+    pub fn games_finished(&self) -> usize {
+        self.games_finished
+    }
+
+    pub fn active_games(&self) -> usize {
+        self.games.len()
+    }
+
+    /// The `authenticate` request that opens every connection. Games are
+    /// forgotten; the server announces them again after login.
+    pub fn login(&mut self) -> OgsAction {
+        self.games.clear();
+        let id = self.request_id();
+        self.auth_request = Some(id);
+        self.frame(
+            "authenticate",
+            JsonValue::object(vec![
+                ("jwt", "".into()),
+                ("bot_username", self.username.as_str().into()),
+                ("bot_apikey", self.api_key.as_str().into()),
+            ]),
+            Some(id),
+        )
+    }
+
+    /// Keepalive frame, to be sent every few seconds
+    pub fn ping(&self, millis: u64) -> OgsAction {
+        self.frame("net/ping", JsonValue::object(vec![("client", (millis as f64).into())]), None)
+    }
+
+    /// Handles one frame from the server and returns what to send back
+    pub fn handle_message(&mut self, text: &str) -> Result<Vec<OgsAction>, String> {
+        let message = JsonValue::parse(text)?;
+        let items = message.as_array().ok_or("frame is not an array")?;
+        let data = items.get(1).cloned().unwrap_or(JsonValue::Null);
+
+        match items.first() {
+            Some(JsonValue::Number(id)) => self.handle_response(*id as usize, &data, items.get(2)),
+            Some(JsonValue::String(event)) => self.handle_event(event, &data),
+            _ => Err("frame has no event name".to_string()),
+        }
+    }
+
+    fn handle_response(&mut self, id: usize, data: &JsonValue, error: Option<&JsonValue>) -> Result<Vec<OgsAction>, String> {
+        if self.auth_request != Some(id) {
+            return Ok(Vec::new());
+        }
+        self.auth_request = None;
+        if let Some(error) = error.filter(|e| **e != JsonValue::Null) {
+            return Err(format!("authentication failed: {}", error));
+        }
+        let player_id = data.get("id").and_then(|v| v.as_usize()).ok_or("authentication failed")?;
+        println!("OGS: logged in as {} (id {})", self.username, player_id);
+        self.player_id = Some(player_id);
+        Ok(Vec::new())
+    }
+
+    fn handle_event(&mut self, event: &str, data: &JsonValue) -> Result<Vec<OgsAction>, String> {
+        match event {
+            "notification" if data.get("type").and_then(|t| t.as_str()) == Some("challenge") => {
+                Ok(vec![self.answer_challenge(data)])
+            }
+            "active_game" => {
+                let id = data.get("id").and_then(|v| v.as_usize()).ok_or("active_game without id")?;
+                let finished = data.get("phase").and_then(|p| p.as_str()) == Some("finished");
+                if finished || self.games.contains_key(&id) {
+                    return Ok(Vec::new());
+                }
+                Ok(vec![self.frame(
+                    "game/connect",
+                    JsonValue::object(vec![("game_id", id.into()), ("chat", false.into())]),
+                    None,
+                )])
+            }
+            _ => match parse_game_event(event) {
+                Some((id, "gamedata")) => self.gamedata(id, data),
+                Some((id, "move")) => self.server_move(id, data),
+                Some((id, "clock")) => {
+                    self.clock(id, data);
+                    Ok(Vec::new())
+                }
+                Some((id, "phase")) => self.phase(id, data.as_str().unwrap_or("")),
+                Some((id, "removed_stones")) => {
+                    if let (Some(game), Some(removed)) =
+                        (self.games.get_mut(&id), data.get("all_removed").and_then(|r| r.as_str()))
+                    {
+                        game.removed = removed.to_string();
+                    }
+                    Ok(Vec::new())
+                }
+                _ => Ok(Vec::new()),
+            },
+        }
+    }
+
+    /// Accepts square, even challenges on supported sizes while there is room
+    fn answer_challenge(&self, data: &JsonValue) -> OgsAction {
+        let number = |key: &str| data.get(key).and_then(|v| v.as_usize()).unwrap_or(0);
+        let challenge_id = number("challenge_id");
+        let (width, height) = (number("width"), number("height"));
+        let challenger = data
+            .get("user")
+            .and_then(|u| u.get("username"))
+            .and_then(|n| n.as_str())
+            .unwrap_or("?");
+
+        let refusal = if width != height || !(2..=MAX_SIZE).contains(&width) {
+            Some(format!("unsupported board {}x{}", width, height))
+        } else if data.get("handicap").and_then(|v| v.as_f64()).unwrap_or(0.0) != 0.0 {
+            Some("handicap games are not supported".to_string())
+        } else if self.games.len() >= self.max_games {
+            Some("too many games in progress".to_string())
+        } else {
+            None
+        };
+
+        let path = format!("me/challenges/{}", challenge_id);
+        match refusal {
+            Some(reason) => {
+                println!("OGS: declining challenge from {}: {}", challenger, reason);
+                OgsAction::Rest("DELETE", path, self.rest_auth())
+            }
+            None => {
+                println!("OGS: accepting {}x{} challenge from {}", width, height, challenger);
+                OgsAction::Rest("POST", path + "/accept", self.rest_auth())
+            }
+        }
+    }
+
+    /// Full game state, sent on connect: sets up an engine and replays the moves
+    fn gamedata(&mut self, id: usize, data: &JsonValue) -> Result<Vec<OgsAction>, String> {
+        let player_id = self.player_id.ok_or("game data before login")?;
+        let size = data.get("width").and_then(|v| v.as_usize()).ok_or("gamedata without width")?;
+        if !(2..=MAX_SIZE).contains(&size) {
+            return Err(format!("unsupported board size {} in game {}", size, id));
+        }
+        let komi = data.get("komi").and_then(|v| v.as_f64()).unwrap_or(6.5) as f32;
+        let player = |color: &str| {
+            data.get("players")
+                .and_then(|p| p.get(color))
+                .and_then(|p| p.get("id"))
+                .and_then(|v| v.as_usize())
+        };
+        let color = if player("black") == Some(player_id) {
+            Stone::Black
+        } else if player("white") == Some(player_id) {
+            Stone::White
+        } else {
+            return Err(format!("not a player in game {}", id));
+        };
+
+        let mut engine = (self.new_engine)()?;
+        engine.new_game(size, komi, GameRules::default(), &TimeSettings::default()).map_err(|e| e.to_string())?;
+
+        let mut to_move = match data.get("initial_player").and_then(|p| p.as_str()) {
+            Some("white") => Stone::White,
+            _ => Stone::Black,
+        };
+        let moves = data.get("moves").and_then(|m| m.as_array()).unwrap_or(&[]);
+        for entry in moves {
+            engine.play(to_move, parse_point(entry, size)?).map_err(|e| e.to_string())?;
+            to_move = opponent(to_move);
+        }
+
+        println!("OGS: game {} on {}x{}, playing {}", id, size, size, if color == Stone::Black { "black" } else { "white" });
+        let mut game = OgsGame {
+            engine,
+            size,
+            color,
+            to_move,
+            moves: moves.len(),
+            phase: data.get("phase").and_then(|p| p.as_str()).unwrap_or("play").to_string(),
+            removed: data.get("removed").and_then(|r| r.as_str()).unwrap_or("").to_string(),
+            clock: None,
+        };
+        if let Some(clock) = data.get("clock") {
+            game.clock = parse_clock(clock, color);
+        }
+        self.games.insert(id, game);
+
+        match self.games[&id].phase.as_str() {
+            "stone removal" => Ok(vec![self.accept_removal(id)]),
+            _ => self.maybe_move(id),
+        }
+    }
+
+    /// `{move_number, move: [x, y, ms]}`; our own moves are echoed back too
+    fn server_move(&mut self, id: usize, data: &JsonValue) -> Result<Vec<OgsAction>, String> {
+        let Some(game) = self.games.get_mut(&id) else { return Ok(Vec::new()) };
+        let number = data.get("move_number").and_then(|n| n.as_usize()).unwrap_or(game.moves + 1);
+        if number <= game.moves {
+            return Ok(Vec::new());
+        }
+        let point = parse_point(data.get("move").ok_or("move event without move")?, game.size)?;
+        game.engine.play(game.to_move, point).map_err(|e| e.to_string())?;
+        game.moves = number;
+        game.to_move = opponent(game.to_move);
+        self.maybe_move(id)
+    }
+
+    fn clock(&mut self, id: usize, data: &JsonValue) {
+        if let Some(game) = self.games.get_mut(&id) {
+            if let Some(clock) = parse_clock(data, game.color) {
+                game.clock = Some(clock);
+            }
+        }
+    }
+
+    fn phase(&mut self, id: usize, phase: &str) -> Result<Vec<OgsAction>, String> {
+        let Some(game) = self.games.get_mut(&id) else { return Ok(Vec::new()) };
+        game.phase = phase.to_string();
+        match phase {
+            "stone removal" => Ok(vec![self.accept_removal(id)]),
+            "finished" => {
+                self.games.remove(&id);
+                self.games_finished += 1;
+                println!("OGS: game {} finished", id);
+                Ok(vec![self.frame("game/disconnect", JsonValue::object(vec![("game_id", id.into())]), None)])
+            }
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Generates a move if it is our turn
+    fn maybe_move(&mut self, id: usize) -> Result<Vec<OgsAction>, String> {
+        let Some(game) = self.games.get_mut(&id) else { return Ok(Vec::new()) };
+        if game.phase != "play" || game.to_move != game.color {
+            return Ok(Vec::new());
+        }
+        let deadline = game.clock.map(|(time, _)| time);
+        let reply = game.engine.genmove(game.color, game.clock, deadline).map_err(|e| e.to_string())?;
+        let vertex = match reply {
+            EngineMove::Play(x, y) => format_sgf_point(x - 1, y - 1),
+            EngineMove::Pass => "..".to_string(),
+            EngineMove::Resign => {
+                println!("OGS: resigning game {}", id);
+                return Ok(vec![self.frame("game/resign", self.game_ref(id), None)]);
+            }
+        };
+        // The engine has played the move; skip the server's echo of it
+        game.moves += 1;
+        game.to_move = opponent(game.color);
+        let mut data = self.game_ref(id);
+        if let JsonValue::Object(pairs) = &mut data {
+            pairs.push(("move".to_string(), vertex.into()));
+        }
+        Ok(vec![self.frame("game/move", data, None)])
+    }
+
+    /// Agrees with the server's dead stones so the game can be scored
+    fn accept_removal(&self, id: usize) -> OgsAction {
+        let removed = self.games.get(&id).map_or("", |g| g.removed.as_str());
+        let mut data = self.game_ref(id);
+        if let JsonValue::Object(pairs) = &mut data {
+            pairs.push(("stones".to_string(), removed.into()));
+            pairs.push(("strict_seki_mode".to_string(), false.into()));
+        }
+        self.frame("game/removed_stones/accept", data, None)
+    }
+
+    fn game_ref(&self, id: usize) -> JsonValue {
+        JsonValue::object(vec![
+            ("game_id", id.into()),
+            ("player_id", self.player_id.unwrap_or(0).into()),
+        ])
+    }
+
+    /// Credentials the bot API expects in REST bodies
+    fn rest_auth(&self) -> JsonValue {
+        JsonValue::object(vec![
+            ("apikey", self.api_key.as_str().into()),
+            ("bot_id", self.player_id.unwrap_or(0).into()),
+            ("player_id", self.player_id.unwrap_or(0).into()),
+            ("username", self.username.as_str().into()),
+        ])
+    }
+
+    fn request_id(&mut self) -> usize {
+        self.next_request += 1;
+        self.next_request - 1
+    }
+
+    fn frame(&self, command: &str, data: JsonValue, id: Option<usize>) -> OgsAction {
+        let mut items = vec![command.into(), data];
+        if let Some(id) = id {
+            items.push(id.into());
+        }
+        OgsAction::Send(JsonValue::Array(items))
+    }
+}
+
+fn opponent(color: Stone) -> Stone {
+    if color == Stone::Black { Stone::White } else { Stone::Black }
+}
+
+/// Splits `game/<id>/<event>`
+fn parse_game_event(event: &str) -> Option<(usize, &str)> {
+    let rest = event.strip_prefix("game/")?;
+    let (id, name) = rest.split_once('/')?;
+    Some((id.parse().ok()?, name))
+}
+
+/// `[x, y, ...]` from the top-left, `[-1, -1]` for a pass
+fn parse_point(value: &JsonValue, size: usize) -> Result<Option<(usize, usize)>, String> {
+    let items = value.as_array().ok_or("move is not an array")?;
+    let coord = |i: usize| items.get(i).and_then(|v| v.as_f64()).ok_or("malformed move");
+    let (x, y) = (coord(0)?, coord(1)?);
+    if x < 0.0 || y < 0.0 {
+        return Ok(None);
+    }
+    let (x, y) = (x as usize + 1, y as usize + 1);
+    if x > size || y > size {
+        return Err(format!("move [{}, {}] off the board", x - 1, y - 1));
+    }
+    Ok(Some((x, y)))
+}
+
+/// Our time left from a clock object: main time, else the current
+/// byo-yomi period (one stone) or Canadian block
+fn parse_clock(clock: &JsonValue, color: Stone) -> Option<(Duration, u32)> {
+    let key = if color == Stone::Black { "black_time" } else { "white_time" };
+    let time = clock.get(key)?;
+    // Times too large for a Duration, or infinite, are no clock at all
+    let duration = |seconds: f64| Duration::try_from_secs_f64(seconds.max(0.0)).ok();
+    if let Some(seconds) = time.as_f64() {
+        return Some((duration(seconds)?, 0));
+    }
+    let field = |name: &str| time.get(name).and_then(|v| v.as_f64()).unwrap_or(0.0).max(0.0);
+    let thinking = field("thinking_time");
+    if thinking > 0.0 {
+        Some((duration(thinking)?, 0))
+    } else if field("period_time") > 0.0 {
+        Some((duration(field("period_time"))?, 1))
+    } else if field("block_time") > 0.0 {
+        Some((duration(field("block_time"))?, field("moves_left") as u32))
+    } else {
+        Some((Duration::ZERO, 0))
+    }
+}
+
+#[cfg(feature = "ogs")]
+mod transport {
+    use std::io::{self, Read, Write};
+    use std::net::TcpStream;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use tungstenite::stream::MaybeTlsStream;
+    use tungstenite::WebSocket;
+
+    use crate::json::JsonValue;
+
+    pub type Socket = WebSocket<MaybeTlsStream<TcpStream>>;
+
+    /// Opens the realtime socket of `server` ("https://online-go.com")
+    pub fn connect(server: &str, read_timeout: Duration) -> Result<Socket, String> {
+        let url = if let Some(host) = server.strip_prefix("https://") {
+            format!("wss://{}/", host.trim_end_matches('/'))
+        } else if let Some(host) = server.strip_prefix("http://") {
+            format!("ws://{}/", host.trim_end_matches('/'))
+        } else {
+            return Err(format!("server must be an http:// or https:// URL, not '{}'", server));
+        };
+        let (socket, _) = tungstenite::connect(url.as_str()).map_err(|e| format!("{}: {}", url, e))?;
+        let tcp = match socket.get_ref() {
+            MaybeTlsStream::Plain(tcp) => tcp,
+            MaybeTlsStream::Rustls(tls) => tls.get_ref(),
+            _ => return Err("unsupported stream".to_string()),
+        };
+        tcp.set_read_timeout(Some(read_timeout)).map_err(|e| e.to_string())?;
+        Ok(socket)
+    }
+
+    /// Sends a JSON request to `server` and returns the status code
+    pub fn rest(server: &str, method: &str, path: &str, body: &JsonValue) -> Result<u16, String> {
+        let (tls, host) = if let Some(host) = server.strip_prefix("https://") {
+            (true, host.trim_end_matches('/'))
+        } else if let Some(host) = server.strip_prefix("http://") {
+            (false, host.trim_end_matches('/'))
+        } else {
+            return Err(format!("bad server URL '{}'", server));
+        };
+        let address = if host.contains(':') { host.to_string() } else { format!("{}:{}", host, if tls { 443 } else { 80 }) };
+        let tcp = TcpStream::connect(&address).map_err(|e| format!("{}: {}", address, e))?;
+        tcp.set_read_timeout(Some(Duration::from_secs(30))).map_err(|e| e.to_string())?;
+
+        let body = body.to_string();
+        let request = format!(
+            "{} /api/v1/{} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            method, path, host, body.len(), body
+        );
+        let response = if tls {
+            let name = host.split(':').next().unwrap_or(host).to_string();
+            let name = rustls::pki_types::ServerName::try_from(name).map_err(|e| e.to_string())?;
+            let roots = rustls::RootCertStore { roots: webpki_roots::TLS_SERVER_ROOTS.to_vec() };
+            let config = rustls::ClientConfig::builder().with_root_certificates(roots).with_no_client_auth();
+            let connection = rustls::ClientConnection::new(Arc::new(config), name).map_err(|e| e.to_string())?;
+            exchange(rustls::StreamOwned::new(connection, tcp), &request)
+        } else {
+            exchange(tcp, &request)
+        }
+        .map_err(|e| format!("{} {}: {}", method, path, e))?;
+
+        response
+            .split_whitespace()
+            .nth(1)
+            .and_then(|code| code.parse().ok())
+            .ok_or_else(|| format!("{} {}: malformed response", method, path))
+    }
+
+    fn exchange<S: Read + Write>(mut stream: S, request: &str) -> io::Result<String> {
+        stream.write_all(request.as_bytes())?;
+        stream.flush()?;
+        let mut response = Vec::new();
+        match stream.read_to_end(&mut response) {
+            Ok(_) => {}
+            // Servers often close without a TLS close_notify
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof && !response.is_empty() => {}
+            Err(e) => return Err(e),
+        }
+        Ok(String::from_utf8_lossy(&response).into_owned())
+    }
+}
+
+/// Runs the ogs subcommand
+#[cfg(feature = "ogs")]
+pub fn run(args: &[String], config: &crate::config::Config) -> Result<(), String> {
+    use std::io;
+    use std::thread;
+    use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+    use crate::cli::tournament::engine_from_spec;
+    use crate::cli::{flag_value, parse_flag};
+
+    const PING_INTERVAL: Duration = Duration::from_secs(20);
+    const MAX_BACKOFF: Duration = Duration::from_secs(120);
+
+    let usage = "Usage: gnugo_rs ogs --user NAME --apikey KEY [--engine SPEC] [--concurrent N] [--games N] [--server URL]";
+    let username = flag_value(args, "--user").ok_or(usage)?;
+    let api_key = flag_value(args, "--apikey").ok_or(usage)?;
+    let server = flag_value(args, "--server").unwrap_or("https://online-go.com");
+    let concurrent: usize = parse_flag(args, "--concurrent", 1)?;
+    let max_games: usize = parse_flag(args, "--games", 0)?;
+    let spec = flag_value(args, "--engine").unwrap_or("internal").to_string();
+    let (level, seed) = (config.strength, config.seed);
+
+    let mut session = OgsSession::new(username, api_key, concurrent, Box::new(move || engine_from_spec(&spec, level, seed)));
+    let mut backoff = Duration::from_secs(5);
+
+    loop {
+        let before = session.games_finished();
+        let mut socket = match transport::connect(server, Duration::from_secs(1)) {
+            Ok(socket) => socket,
+            Err(e) => {
+                eprintln!("OGS: connection failed: {}", e);
+                thread::sleep(backoff);
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+                continue;
+            }
+        };
+
+        let mut pending = vec![session.login()];
+        let mut last_ping = Instant::now();
+        let outcome: Result<(), String> = loop {
+            let mut send_error = None;
+            for action in pending.drain(..) {
+                match action {
+                    OgsAction::Send(frame) => {
+                        if let Err(e) = socket.send(tungstenite::Message::text(frame.to_string())) {
+                            send_error = Some(e.to_string());
+                            break;
+                        }
+                    }
+                    OgsAction::Rest(method, path, body) => match transport::rest(server, method, &path, &body) {
+                        Ok(code) if code < 300 => {}
+                        Ok(code) => eprintln!("OGS: {} {} returned {}", method, path, code),
+                        Err(e) => eprintln!("OGS: {}", e),
+                    },
+                }
+            }
+            if let Some(e) = send_error {
+                break Err(e);
+            }
+            if max_games > 0 && session.games_finished() >= max_games {
+                break Ok(());
+            }
+            if last_ping.elapsed() >= PING_INTERVAL {
+                let millis = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis() as u64);
+                pending.push(session.ping(millis));
+                last_ping = Instant::now();
+                continue;
+            }
+
+            match socket.read() {
+                Ok(tungstenite::Message::Text(text)) => match session.handle_message(&text) {
+                    Ok(actions) => pending = actions,
+                    Err(e) if e.starts_with("authentication failed") => return Err(e),
+                    Err(e) => eprintln!("OGS protocol error: {}", e),
+                },
+                Ok(tungstenite::Message::Close(_)) => break Err("connection closed".to_string()),
+                Ok(_) => {}
+                Err(tungstenite::Error::Io(e))
+                    if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => {}
+                Err(e) => break Err(e.to_string()),
+            }
+        };
+
+        match outcome {
+            Ok(()) => break,
+            Err(e) => eprintln!("OGS: {}", e),
+        }
+        if session.games_finished() > before {
+            backoff = Duration::from_secs(5);
+        }
+        eprintln!("OGS: reconnecting in {}s", backoff.as_secs());
+        thread::sleep(backoff);
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+
+    println!("Played {} games", session.games_finished());
+    Ok(())
+}
+
+/// Runs the ogs subcommand
+#[cfg(not(feature = "ogs"))]
+pub fn run(_args: &[String], _config: &crate::config::Config) -> Result<(), String> {
+    Err("OGS support not enabled. Build with '--features ogs'".to_string())
+}
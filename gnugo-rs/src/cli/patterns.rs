@@ -0,0 +1,17 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Patterns subcommand: loads the pattern databases and reports what it found
+
+use crate::config::Config;
+use crate::patterns::PatternMatcher;
+
+/// Runs the patterns subcommand; `--patterns` arrives through `config`
+pub fn run(config: &Config) -> Result<(), String> {
+    let report = PatternMatcher::new().reload_databases(&config.pattern_dir);
+    println!("{}", report.to_text());
+    match report.errors() {
+        0 => Ok(()),
+        n => Err(format!("{} database{} failed to load", n, if n == 1 { "" } else { "s" })),
+    }
+}
@@ -0,0 +1,52 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Score subcommand: replays a finished game record and counts the result
+
+use crate::config::Config;
+use crate::engine::board::Stone;
+use crate::engine::final_status::FinalStatus;
+use crate::engine::game::Game;
+use crate::engine::scoring::{final_score, format_result};
+use crate::gtp::format_move;
+use crate::sgf::{SGFHandler, SGFProperty};
+
+/// Runs the score subcommand; `--ruleset` arrives through `config`
+pub fn run(args: &[String], config: &Config) -> Result<(), String> {
+    let path = args
+        .iter()
+        .find(|a| a.ends_with(".sgf"))
+        .ok_or("Usage: gnugo_rs score game.sgf [--ruleset chinese|japanese]")?;
+    let rule = config.ruleset.scoring;
+
+    let handler = SGFHandler::new();
+    let tree = handler.load_file(path)?;
+    let mut game = Game::new(19);
+    handler.apply_to_game(&tree, &mut game)?;
+
+    let board = &game.board;
+    let size = board.size();
+    let dead = FinalStatus::analyze(board).dead();
+    let (black, white) = final_score(board, rule, &dead);
+    let margin = black as f32 - white as f32 - game.komi;
+
+    let list = |color: Stone| -> String {
+        let stones: Vec<String> = dead
+            .iter()
+            .filter(|&&(x, y)| board.get_stone(x, y) == color)
+            .map(|&(x, y)| format_move(x, y, size))
+            .collect();
+        if stones.is_empty() { "none".to_string() } else { stones.join(" ") }
+    };
+
+    println!("Ruleset: {}", rule.name());
+    println!("Dead black stones: {}", list(Stone::Black));
+    println!("Dead white stones: {}", list(Stone::White));
+    println!("Black: {}  White: {} + {} komi", black, white, game.komi);
+    println!("Result: {}", format_result(margin));
+    if let Some(SGFProperty::Text(recorded)) = tree.root.properties.get("RE").and_then(|v| v.first()) {
+        println!("Recorded result: {}", recorded);
+    }
+
+    Ok(())
+}
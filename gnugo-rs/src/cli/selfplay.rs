@@ -0,0 +1,222 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Self-play subcommand: the engine plays against itself for strength tracking
+
+use std::fs;
+use std::path::PathBuf;
+
+use crate::cli::{flag_value, parse_flag};
+use crate::config::Config;
+use crate::engine::ai::{AIDifficulty, AI};
+use crate::engine::board::Stone;
+use crate::engine::game::{Game, GameStatus};
+use crate::engine::scoring::Score;
+use crate::sgf::archive::Archive;
+use crate::sgf::record_to_sgf;
+
+/// Self-play configuration
+#[derive(Debug, Clone)]
+pub struct SelfPlayConfig {
+    pub games: usize,
+    pub size: usize,
+    pub komi: f32,
+    pub max_moves: usize,
+    pub sgf_dir: Option<PathBuf>,
+    /// Engine A, black in even-numbered games
+    pub engine_a: AIDifficulty,
+    /// Engine B, black in odd-numbered games
+    pub engine_b: AIDifficulty,
+}
+
+impl SelfPlayConfig {
+    /// Builds a configuration from command line arguments, taking komi and
+    /// levels from `defaults` when not given
+    pub fn from_args(args: &[String], defaults: &Config) -> Result<Self, String> {
+        let size = parse_flag(args, "--size", 9)?;
+        if !(2..=25).contains(&size) {
+            return Err(format!("Unsupported board size {}", size));
+        }
+        let level = |flag: &str| -> Result<AIDifficulty, String> {
+            match flag_value(args, flag) {
+                Some(name) => AIDifficulty::from_name(name)
+                    .ok_or_else(|| format!("Unknown level '{}' for {}", name, flag)),
+                None => Ok(defaults.level),
+            }
+        };
+
+        Ok(SelfPlayConfig {
+            games: parse_flag(args, "--games", 10)?,
+            size,
+            komi: parse_flag(args, "--komi", defaults.komi)?,
+            max_moves: parse_flag(args, "--max-moves", size * size * 3)?,
+            sgf_dir: flag_value(args, "--sgf-dir").map(PathBuf::from),
+            engine_a: level("--level-a")?,
+            engine_b: level("--level-b")?,
+        })
+    }
+}
+
+/// Result of a single self-play game
+#[derive(Debug, Clone)]
+pub struct SelfPlayGame {
+    pub moves: Vec<(Stone, Option<(usize, usize)>)>,
+    /// Black area minus white area minus komi
+    pub margin: f32,
+    pub resigned: Option<Stone>,
+    /// Ended without result by a repeated position, as in triple ko
+    pub void: bool,
+}
+
+impl SelfPlayGame {
+    /// Winning color, `None` for jigo or no result
+    pub fn winner(&self) -> Option<Stone> {
+        if self.void {
+            return None;
+        }
+        if let Some(loser) = self.resigned {
+            return Some(if loser == Stone::Black { Stone::White } else { Stone::Black });
+        }
+        if self.margin > 0.0 {
+            Some(Stone::Black)
+        } else if self.margin < 0.0 {
+            Some(Stone::White)
+        } else {
+            None
+        }
+    }
+
+    /// SGF style result string such as "B+3.5"
+    pub fn result_string(&self) -> String {
+        if self.void {
+            return "Void".to_string();
+        }
+        match (self.winner(), self.resigned) {
+            (Some(Stone::Black), Some(_)) => "B+R".to_string(),
+            (Some(Stone::White), Some(_)) => "W+R".to_string(),
+            (Some(Stone::Black), None) => format!("B+{}", self.margin),
+            (Some(Stone::White), None) => format!("W+{}", -self.margin),
+            _ => "0".to_string(),
+        }
+    }
+}
+
+/// Plays one game between two engines
+pub fn play_game(size: usize, komi: f32, max_moves: usize, black: &AI, white: &AI) -> SelfPlayGame {
+    let mut game = Game::new(size);
+    game.komi = komi;
+    let mut moves = Vec::new();
+
+    while !game.is_game_over() && moves.len() < max_moves {
+        let color = game.current_player();
+        let engine = if color == Stone::Black { black } else { white };
+
+        let played = match engine.get_game_move(&game, color) {
+            Some((x, y)) if game.make_move(x, y).is_ok() => Some((x, y)),
+            _ => {
+                // No acceptable move: treat as a pass
+                if game.pass().is_err() {
+                    break;
+                }
+                None
+            }
+        };
+        moves.push((color, played));
+    }
+
+    let void = game.game_status() == GameStatus::NoResult;
+    SelfPlayGame {
+        moves,
+        margin: if void { 0.0 } else { Score::area(&game.board, &[], komi).margin() },
+        resigned: None,
+        void,
+    }
+}
+
+/// Runs the selfplay subcommand
+pub fn run(args: &[String], defaults: &Config) -> Result<(), String> {
+    let config = SelfPlayConfig::from_args(args, defaults)?;
+    if let Some(dir) = &config.sgf_dir {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Cannot create directory '{}': {}", dir.display(), e))?;
+    }
+
+    let archive = defaults.archive_dir.as_ref().map(Archive::new);
+    let (mut engine_a, mut engine_b) = (AI::new(config.engine_a), AI::new(config.engine_b));
+    if let Some(seed) = defaults.seed {
+        engine_a = engine_a.with_seed(seed);
+        engine_b = engine_b.with_seed(seed.wrapping_add(1));
+    }
+    let mut a_wins = 0;
+    let mut b_wins = 0;
+    let mut black_wins = 0;
+    let mut white_wins = 0;
+    let mut jigo = 0;
+    let mut voids = 0;
+    let mut total_margin = 0.0;
+    let mut total_moves = 0;
+
+    println!(
+        "Self-play: {} games on {}x{}, A={} B={}",
+        config.games, config.size, config.size,
+        config.engine_a.name(), config.engine_b.name()
+    );
+
+    for i in 0..config.games {
+        let a_is_black = i % 2 == 0;
+        let (black, white) = if a_is_black { (&engine_a, &engine_b) } else { (&engine_b, &engine_a) };
+        let result = play_game(config.size, config.komi, config.max_moves, black, white);
+
+        match result.winner() {
+            Some(winner) => {
+                if winner == Stone::Black { black_wins += 1 } else { white_wins += 1 }
+                if (winner == Stone::Black) == a_is_black { a_wins += 1 } else { b_wins += 1 }
+            }
+            None if result.void => voids += 1,
+            None => jigo += 1,
+        }
+        total_margin += result.margin;
+        total_moves += result.moves.len();
+
+        println!(
+            "Game {:>3}: A={} {:>4} moves  {}",
+            i + 1,
+            if a_is_black { "B" } else { "W" },
+            result.moves.len(),
+            result.result_string()
+        );
+
+        let (pb, pw) = if a_is_black {
+            (config.engine_a.name(), config.engine_b.name())
+        } else {
+            (config.engine_b.name(), config.engine_a.name())
+        };
+        let re = result.result_string();
+        let sgf = record_to_sgf(
+            config.size,
+            config.komi,
+            &result.moves,
+            &[("PB", pb), ("PW", pw), ("RE", &re)],
+        );
+        if let Some(dir) = &config.sgf_dir {
+            let path = dir.join(format!("selfplay-{:04}.sgf", i + 1));
+            fs::write(&path, &sgf)
+                .map_err(|e| format!("Cannot write '{}': {}", path.display(), e))?;
+        }
+        if let Some(archive) = &archive {
+            archive.save("selfplay", &sgf)?;
+        }
+    }
+
+    if config.games > 0 {
+        let games = config.games as f32;
+        println!();
+        println!("Engine A ({}) wins: {}", config.engine_a.name(), a_wins);
+        println!("Engine B ({}) wins: {}", config.engine_b.name(), b_wins);
+        println!("Black wins: {}, White wins: {}, Jigo: {}, No result: {}", black_wins, white_wins, jigo, voids);
+        println!("Average score (B-W): {:.1}", total_margin / games);
+        println!("Average game length: {:.1} moves", total_moves as f32 / games);
+    }
+
+    Ok(())
+}
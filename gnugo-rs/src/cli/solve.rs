@@ -0,0 +1,90 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Solve subcommand: reads a life-and-death problem from an SGF file
+
+use std::fs;
+
+use crate::cli::{flag_value, parse_flag};
+use crate::config::Config;
+use crate::engine::board::Stone;
+use crate::engine::game::Game;
+use crate::engine::trace::SearchTrace;
+use crate::engine::tsumego::TsumegoSolver;
+use crate::gtp::{format_move, parse_gtp_move};
+use crate::sgf::{SGFHandler, SGFNode, SGFProperty};
+
+/// Properties accepted as marking the target group
+const MARK_PROPERTIES: [&str; 4] = ["MA", "TR", "SQ", "CR"];
+
+/// Finds the first marked point along the main line, 1-based
+fn find_marked_point(node: &SGFNode) -> Option<(usize, usize)> {
+    for key in MARK_PROPERTIES {
+        if let Some(SGFProperty::Point((x, y))) = node.properties.get(key).and_then(|v| v.first()) {
+            return Some((x + 1, y + 1));
+        }
+    }
+    node.children.first().and_then(find_marked_point)
+}
+
+/// Runs the solve subcommand
+pub fn run(args: &[String], config: &Config) -> Result<(), String> {
+    let trace_path = flag_value(args, "--trace");
+    let path = args
+        .iter()
+        .find(|a| a.ends_with(".sgf") && Some(a.as_str()) != trace_path)
+        .ok_or("Usage: gnugo_rs solve problem.sgf [--target VERTEX] [--color black|white] [--max-nodes N] [--trace FILE]")?;
+
+    let handler = SGFHandler::new();
+    let tree = handler.load_file(path)?;
+    let mut game = Game::new(19);
+    handler.apply_to_game(&tree, &mut game)?;
+    let size = game.board.size();
+
+    let target = match flag_value(args, "--target") {
+        Some(vertex) => parse_gtp_move(vertex, size).ok_or_else(|| format!("Invalid vertex '{}'", vertex))?,
+        None => find_marked_point(&tree.root).ok_or("No marked group in the SGF; use --target")?,
+    };
+    let defender = game.board.get_stone(target.0, target.1);
+    if defender == Stone::Empty {
+        return Err(format!("No stone at target {}", format_move(target.0, target.1, size)));
+    }
+
+    let to_move = match flag_value(args, "--color") {
+        Some(color) => match color.to_lowercase().as_str() {
+            "black" | "b" => Stone::Black,
+            "white" | "w" => Stone::White,
+            _ => return Err(format!("Invalid color '{}'", color)),
+        },
+        None => game.current_player(),
+    };
+    let max_nodes = parse_flag(args, "--max-nodes", config.limits.nodes(config.level.profile().owl_nodes))?;
+
+    let mut solver = TsumegoSolver::new(&game.board, target, max_nodes);
+    solver.apply_limits(&config.limits);
+    if trace_path.is_some() {
+        solver.set_trace(SearchTrace::new(&game.board, to_move));
+    }
+    let result = solver.solve(&game.board, to_move);
+    if let (Some(path), Some(trace)) = (trace_path, solver.take_trace()) {
+        let sgf = handler.tree_to_sgf(&trace.to_sgf_tree());
+        fs::write(path, sgf).map_err(|e| format!("Cannot write '{}': {}", path, e))?;
+    }
+
+    let group_size = game.board.find_group(target.0, target.1).map_or(0, |g| g.positions().len());
+    println!("Target: {} ({}, {} stones)", format_move(target.0, target.1, size), defender, group_size);
+    println!("To move: {}", to_move);
+    println!("Status: {}", result.status.name());
+    let line: Vec<String> = result
+        .main_line
+        .iter()
+        .map(|(color, point)| {
+            let vertex = point.map_or("pass".to_string(), |(x, y)| format_move(x, y, size));
+            format!("{} {}", if *color == Stone::Black { "B" } else { "W" }, vertex)
+        })
+        .collect();
+    println!("Main line: {}", line.join(", "));
+    println!("Nodes: {}", result.nodes);
+
+    Ok(())
+}
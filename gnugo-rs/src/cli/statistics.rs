@@ -0,0 +1,28 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Statistics subcommand: replays a game record and prints its statistics
+
+use crate::engine::game::Game;
+use crate::sgf::SGFHandler;
+
+/// Runs the statistics subcommand
+pub fn run(args: &[String]) -> Result<(), String> {
+    let path = args
+        .iter()
+        .find(|a| a.ends_with(".sgf"))
+        .ok_or("Usage: gnugo_rs statistics game.sgf [--json]")?;
+
+    let handler = SGFHandler::new();
+    let tree = handler.load_file(path)?;
+    let mut game = Game::new(19);
+    handler.apply_to_game(&tree, &mut game)?;
+
+    let statistics = game.statistics();
+    if args.iter().any(|a| a == "--json") {
+        println!("{}", statistics.to_json());
+    } else {
+        println!("{}", statistics.to_text());
+    }
+    Ok(())
+}
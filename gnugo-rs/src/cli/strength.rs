@@ -0,0 +1,57 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Strength subcommand: estimates a player's rank from game records
+
+use crate::cli::flag_value;
+use crate::engine::board::Stone;
+use crate::sgf::review::{collect_stats, player_colors, PlayerStats};
+use crate::sgf::SGFHandler;
+
+const USAGE: &str = "Usage: gnugo_rs strength game.sgf... [--player NAME | --color black|white]";
+
+/// Prints the statistics under `title`
+fn report(title: &str, stats: &PlayerStats) {
+    println!("{} ({} moves)", title, stats.moves);
+    println!("  Engine agreement: {:.0}% (top 3: {:.0}%)", 100.0 * stats.agreement(), 100.0 * stats.top3_agreement());
+    println!("  Blunders: {} ({:.1}%)", stats.blunders, 100.0 * stats.blunder_rate());
+    println!("  Average loss: {:.1}", stats.average_loss());
+    match stats.rank() {
+        Some(rank) => println!("  Estimated rank: {}", rank),
+        None => println!("  Estimated rank: too few moves"),
+    }
+}
+
+/// Runs the strength subcommand
+pub fn run(args: &[String]) -> Result<(), String> {
+    let paths: Vec<&String> = args.iter().filter(|a| a.ends_with(".sgf")).collect();
+    if paths.is_empty() {
+        return Err(USAGE.to_string());
+    }
+    let handler = SGFHandler::new();
+    let trees = paths.iter().map(|path| handler.load_file(path)).collect::<Result<Vec<_>, _>>()?;
+
+    if let Some(player) = flag_value(args, "--player") {
+        let games = trees.iter().filter(|tree| !player_colors(tree, player).is_empty()).count();
+        if games == 0 {
+            return Err(format!("'{}' does not appear in PB or PW of any record", player));
+        }
+        let stats = collect_stats(&trees, |tree| player_colors(tree, player))?;
+        report(&format!("{}, {} game{}", player, games, if games == 1 { "" } else { "s" }), &stats);
+        return Ok(());
+    }
+
+    let colors = match flag_value(args, "--color") {
+        Some(name) => vec![match name.to_lowercase().as_str() {
+            "black" | "b" => Stone::Black,
+            "white" | "w" => Stone::White,
+            _ => return Err(USAGE.to_string()),
+        }],
+        None => vec![Stone::Black, Stone::White],
+    };
+    for color in colors {
+        let stats = collect_stats(&trees, |_| vec![color])?;
+        report(if color == Stone::Black { "Black" } else { "White" }, &stats);
+    }
+    Ok(())
+}
@@ -0,0 +1,36 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Svg subcommand: draws a position from a game record as an SVG diagram
+
+use std::fs;
+
+use crate::cli::{flag_value, parse_flag};
+use crate::sgf::SGFHandler;
+use crate::ui::svg::{render_svg, Diagram, SvgOptions};
+
+/// Runs the svg subcommand
+pub fn run(args: &[String]) -> Result<(), String> {
+    let path = args
+        .iter()
+        .find(|a| a.ends_with(".sgf"))
+        .ok_or("Usage: gnugo_rs svg game.sgf [--move N] [--numbers] [--no-coordinates] [--cell PX] [--output FILE]")?;
+    let moves = flag_value(args, "--move")
+        .map(|n| n.parse::<usize>().map_err(|_| format!("Invalid value '{}' for --move", n)))
+        .transpose()?;
+    let options = SvgOptions {
+        coordinates: !args.iter().any(|a| a == "--no-coordinates"),
+        move_numbers: args.iter().any(|a| a == "--numbers"),
+        cell_size: parse_flag(args, "--cell", SvgOptions::default().cell_size)?,
+    };
+
+    let tree = SGFHandler::new().load_file(path)?;
+    let svg = render_svg(&Diagram::from_sgf(&tree, moves)?, &options);
+    match flag_value(args, "--output") {
+        Some(output) => fs::write(output, svg).map_err(|e| format!("Cannot write '{}': {}", output, e)),
+        None => {
+            print!("{}", svg);
+            Ok(())
+        }
+    }
+}
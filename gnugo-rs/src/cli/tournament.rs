@@ -0,0 +1,481 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Match subcommand: a twogtp-style tournament between two engines
+//!
+//! Engines are given as `internal[:LEVEL]` for this crate's own AI or as the
+//! command line of an external GTP engine. Colors alternate every game.
+//! The referee keeps the authoritative board, rejects illegal moves, keeps
+//! time and scores finished games itself.
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::cli::{flag_value, parse_flag};
+use crate::config::{Config, TimeSettings};
+use crate::engine::ai::{parse_level, AIDifficulty, AI};
+use crate::engine::board::Stone;
+use crate::engine::clock::{Clock, Overtime};
+use crate::engine::final_status::FinalStatus;
+use crate::engine::game::{Game, GameStatus};
+use crate::engine::rules::GameRules;
+use crate::engine::scoring::{final_score, format_result, Score};
+use crate::gtp::client::{GtpClient, GtpError};
+use crate::gtp::{format_move, parse_gtp_move};
+use crate::sgf::archive::Archive;
+use crate::sgf::record_to_sgf;
+
+/// Extra time an engine gets beyond its clock before it is considered hung
+const GRACE: Duration = Duration::from_secs(2);
+
+/// A move chosen by an engine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EngineMove {
+    Play(usize, usize),
+    Pass,
+    Resign,
+}
+
+/// One side of a match
+pub trait MatchEngine {
+    /// Display name used in the results table and SGF
+    fn name(&self) -> &str;
+
+    /// Prepares a fresh game under `rules`, restarting the engine if it died
+    fn new_game(&mut self, size: usize, komi: f32, rules: GameRules, time: &TimeSettings) -> Result<(), GtpError>;
+
+    /// Tells the engine about a move by either side (`None` is a pass)
+    fn play(&mut self, color: Stone, point: Option<(usize, usize)>) -> Result<(), GtpError>;
+
+    /// Asks for a move. `clock` is the time and byo-yomi stones left; the
+    /// engine must answer within `deadline`.
+    fn genmove(
+        &mut self,
+        color: Stone,
+        clock: Option<(Duration, u32)>,
+        deadline: Option<Duration>,
+    ) -> Result<EngineMove, GtpError>;
+}
+
+/// This crate's AI, run in-process
+pub struct InternalEngine {
+    name: String,
+    ai: AI,
+    game: Game,
+}
+
+impl InternalEngine {
+    pub fn new(level: AIDifficulty) -> Self {
+        InternalEngine {
+            name: format!("gnugo_rs ({})", level.name()),
+            ai: AI::new(level),
+            game: Game::with_rules(19, GameRules::default()),
+        }
+    }
+
+    /// An engine playing at numeric `level`, 1 to [`crate::engine::ai::MAX_LEVEL`]
+    pub fn for_level(level: u8) -> Self {
+        InternalEngine {
+            name: format!("gnugo_rs (level {})", level),
+            ai: AI::for_level(level),
+            ..InternalEngine::new(AIDifficulty::from_level(level))
+        }
+    }
+
+    /// Makes the engine's random choices from `seed`
+    pub fn with_seed(self, seed: u64) -> Self {
+        InternalEngine { ai: self.ai.with_seed(seed), ..self }
+    }
+}
+
+impl MatchEngine for InternalEngine {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn new_game(&mut self, size: usize, komi: f32, rules: GameRules, _time: &TimeSettings) -> Result<(), GtpError> {
+        self.game = Game::with_rules(size, rules);
+        self.game.komi = komi;
+        Ok(())
+    }
+
+    fn play(&mut self, color: Stone, point: Option<(usize, usize)>) -> Result<(), GtpError> {
+        self.game.current_player = color == Stone::Black;
+        match point {
+            Some((x, y)) => self.game.make_move(x, y),
+            None => self.game.pass(),
+        }
+        .map_err(GtpError::Failure)
+    }
+
+    fn genmove(
+        &mut self,
+        color: Stone,
+        _clock: Option<(Duration, u32)>,
+        _deadline: Option<Duration>,
+    ) -> Result<EngineMove, GtpError> {
+        let choice = match self.ai.get_game_move(&self.game, color) {
+            Some((x, y)) => EngineMove::Play(x, y),
+            None => EngineMove::Pass,
+        };
+        let point = match choice {
+            EngineMove::Play(x, y) => Some((x, y)),
+            _ => None,
+        };
+        self.play(color, point)?;
+        Ok(choice)
+    }
+}
+
+/// An external engine spoken to over GTP
+pub struct ExternalEngine {
+    command_line: String,
+    name: String,
+    client: GtpClient,
+    size: usize,
+    supports_time_left: bool,
+}
+
+impl ExternalEngine {
+    /// Starts the engine and asks for its name and version
+    pub fn start(command_line: &str) -> Result<Self, GtpError> {
+        let mut client = GtpClient::from_command_line(command_line)?;
+        client.set_timeout(Some(Duration::from_secs(30)));
+        let name = match (client.name(), client.version()) {
+            (Ok(name), Ok(version)) if !version.is_empty() => format!("{} {}", name, version),
+            (Ok(name), _) => name,
+            (Err(e), _) => return Err(e),
+        };
+        let supports_time_left = client.known_command("time_left").unwrap_or(false);
+        Ok(ExternalEngine {
+            command_line: command_line.to_string(),
+            name,
+            client,
+            size: 19,
+            supports_time_left,
+        })
+    }
+}
+
+impl MatchEngine for ExternalEngine {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn new_game(&mut self, size: usize, komi: f32, _rules: GameRules, time: &TimeSettings) -> Result<(), GtpError> {
+        if !self.client.is_alive() {
+            *self = ExternalEngine::start(&self.command_line)?;
+        }
+        self.size = size;
+        self.client.boardsize(size)?;
+        self.client.clear_board()?;
+        self.client.komi(komi)?;
+        if time.main_time > 0 || time.byo_yomi_time > 0 {
+            // Not every engine implements time control; the referee keeps time regardless
+            let _ = self.client.time_settings(time.main_time, time.byo_yomi_time, time.byo_yomi_stones);
+        }
+        Ok(())
+    }
+
+    fn play(&mut self, color: Stone, point: Option<(usize, usize)>) -> Result<(), GtpError> {
+        let vertex = match point {
+            Some((x, y)) => format_move(x, y, self.size),
+            None => "pass".to_string(),
+        };
+        self.client.play(color, &vertex)
+    }
+
+    fn genmove(
+        &mut self,
+        color: Stone,
+        clock: Option<(Duration, u32)>,
+        deadline: Option<Duration>,
+    ) -> Result<EngineMove, GtpError> {
+        if let (Some((left, stones)), true) = (clock, self.supports_time_left) {
+            self.client.time_left(color, left.as_secs(), stones)?;
+        }
+        let color_name = if color == Stone::Black { "black" } else { "white" };
+        let reply = self
+            .client
+            .send_with_timeout(&format!("genmove {}", color_name), deadline.or(Some(Duration::from_secs(600))))?
+            .to_lowercase();
+        match reply.as_str() {
+            "pass" => Ok(EngineMove::Pass),
+            "resign" => Ok(EngineMove::Resign),
+            vertex => parse_gtp_move(vertex, self.size)
+                .map(|(x, y)| EngineMove::Play(x, y))
+                .ok_or_else(|| GtpError::Protocol(format!("invalid move '{}'", vertex))),
+        }
+    }
+}
+
+/// Creates an engine from `internal[:LEVEL]`, LEVEL a name or a number, or
+/// an external command line; an internal engine plays at numeric
+/// `default_level` unless given one, and makes its random choices from
+/// `seed` if given
+pub fn engine_from_spec(spec: &str, default_level: u8, seed: Option<u64>) -> Result<Box<dyn MatchEngine>, String> {
+    let internal = |level: u8| -> Box<dyn MatchEngine> {
+        let engine = InternalEngine::for_level(level);
+        Box::new(match seed {
+            Some(seed) => engine.with_seed(seed),
+            None => engine,
+        })
+    };
+    if spec == "internal" {
+        return Ok(internal(default_level));
+    }
+    if let Some(level) = spec.strip_prefix("internal:") {
+        let level = parse_level(level).ok_or_else(|| format!("Unknown level '{}'", level))?;
+        return Ok(internal(level));
+    }
+    ExternalEngine::start(spec)
+        .map(|engine| Box::new(engine) as Box<dyn MatchEngine>)
+        .map_err(|e| format!("Cannot start '{}': {}", spec, e))
+}
+
+/// Longest `color` may think about its next move before it is considered
+/// hung: the time left, a first overtime period while still in main time,
+/// and [`GRACE`]. `None` without a time limit
+pub fn deadline(clock: &Clock, color: Stone) -> Option<Duration> {
+    clock.time_left(color).map(|(left, stones)| {
+        let overtime = match clock.control().overtime {
+            Overtime::Japanese { time, .. } | Overtime::Canadian { time, .. } if stones == 0 => time,
+            _ => Duration::ZERO,
+        };
+        left.saturating_add(overtime).saturating_add(GRACE)
+    })
+}
+
+/// How a game ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ending {
+    Score,
+    Resignation,
+    Time,
+    /// Illegal move, crash or protocol error
+    Forfeit,
+    /// A position kept repeating, as in triple ko
+    NoResult,
+}
+
+/// Result of one match game
+#[derive(Debug, Clone)]
+pub struct MatchGame {
+    pub moves: Vec<(Stone, Option<(usize, usize)>)>,
+    pub winner: Option<Stone>,
+    pub ending: Ending,
+    /// Black minus White, when the game was scored
+    pub margin: f32,
+    /// What went wrong, for forfeits
+    pub note: Option<String>,
+}
+
+impl MatchGame {
+    /// SGF style result string such as "W+R" or "B+3.5"
+    pub fn result_string(&self) -> String {
+        let winner = match self.winner {
+            Some(Stone::Black) => "B",
+            Some(Stone::White) => "W",
+            _ if self.ending == Ending::NoResult => "",
+            _ => return "0".to_string(),
+        };
+        match self.ending {
+            Ending::Score => format_result(self.margin),
+            Ending::Resignation => format!("{}+R", winner),
+            Ending::Time => format!("{}+T", winner),
+            Ending::Forfeit => format!("{}+F", winner),
+            Ending::NoResult => "Void".to_string(),
+        }
+    }
+}
+
+fn opponent(color: Stone) -> Stone {
+    if color == Stone::Black { Stone::White } else { Stone::Black }
+}
+
+/// Match settings
+#[derive(Debug, Clone)]
+pub struct MatchConfig {
+    pub games: usize,
+    pub size: usize,
+    pub komi: f32,
+    pub max_moves: usize,
+    /// Ko and suicide rules of the referee's board, and how games are scored
+    pub rules: GameRules,
+    pub time: TimeSettings,
+    pub sgf_dir: Option<PathBuf>,
+}
+
+/// Referees one game between `black` and `white`
+pub fn play_game<'e>(
+    config: &MatchConfig,
+    black: &mut (dyn MatchEngine + 'e),
+    white: &mut (dyn MatchEngine + 'e),
+) -> MatchGame {
+    let mut game = Game::with_rules(config.size, config.rules);
+    game.komi = config.komi;
+    game.clock = Clock::new(config.time.time_control());
+    let mut moves = Vec::new();
+
+    let ended = |winner: Stone, ending: Ending, moves: Vec<_>, note: Option<String>| MatchGame {
+        moves,
+        winner: Some(winner),
+        ending,
+        margin: 0.0,
+        note,
+    };
+
+    for (color, engine) in [(Stone::Black, &mut *black), (Stone::White, &mut *white)] {
+        if let Err(e) = engine.new_game(config.size, config.komi, config.rules, &config.time) {
+            return ended(opponent(color), Ending::Forfeit, moves, Some(format!("{}: {}", engine.name(), e)));
+        }
+    }
+
+    while !game.is_game_over() && moves.len() < config.max_moves {
+        let color = game.current_player();
+        let (mover, other): (&mut (dyn MatchEngine + 'e), &mut (dyn MatchEngine + 'e)) = if color == Stone::Black {
+            (&mut *black, &mut *white)
+        } else {
+            (&mut *white, &mut *black)
+        };
+
+        let started = Instant::now();
+        let reply = mover.genmove(color, game.clock.time_left(color), deadline(&game.clock, color));
+        let in_time = game.clock.charge(color, started.elapsed());
+
+        let choice = match reply {
+            Err(GtpError::Timeout) => return ended(opponent(color), Ending::Time, moves, None),
+            Err(e) => return ended(opponent(color), Ending::Forfeit, moves, Some(format!("{}: {}", mover.name(), e))),
+            Ok(_) if !in_time => return ended(opponent(color), Ending::Time, moves, None),
+            Ok(choice) => choice,
+        };
+
+        let point = match choice {
+            EngineMove::Resign => return ended(opponent(color), Ending::Resignation, moves, None),
+            EngineMove::Pass => {
+                let _ = game.pass();
+                None
+            }
+            EngineMove::Play(x, y) => {
+                if let Err(e) = game.make_move(x, y) {
+                    let note = format!("{}: illegal move {} ({})", mover.name(), format_move(x, y, config.size), e);
+                    return ended(opponent(color), Ending::Forfeit, moves, Some(note));
+                }
+                Some((x, y))
+            }
+        };
+        moves.push((color, point));
+
+        if let Err(e) = other.play(color, point) {
+            return ended(color, Ending::Forfeit, moves, Some(format!("{}: {}", other.name(), e)));
+        }
+    }
+
+    if game.game_status() == GameStatus::NoResult {
+        let note = Some("repeated position".to_string());
+        return MatchGame { moves, winner: None, ending: Ending::NoResult, margin: 0.0, note };
+    }
+    let dead = FinalStatus::analyze(&game.board).dead();
+    let (black, white) = final_score(&game.board, config.rules.scoring, &dead);
+    let score = Score { black, white, komi: config.komi };
+    MatchGame { moves, winner: score.winner(), ending: Ending::Score, margin: score.margin(), note: None }
+}
+
+/// Runs the match subcommand
+pub fn run(args: &[String], defaults: &Config) -> Result<(), String> {
+    let config = MatchConfig {
+        games: parse_flag(args, "--games", 2)?,
+        size: defaults.board_size,
+        komi: defaults.komi,
+        max_moves: parse_flag(args, "--max-moves", defaults.board_size * defaults.board_size * 3)?,
+        rules: defaults.ruleset,
+        time: defaults.time,
+        sgf_dir: flag_value(args, "--sgf-dir").map(PathBuf::from),
+    };
+    if let Some(dir) = &config.sgf_dir {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Cannot create directory '{}': {}", dir.display(), e))?;
+    }
+
+    let archive = defaults.archive_dir.as_ref().map(Archive::new);
+    let seed_b = defaults.seed.map(|seed| seed.wrapping_add(1));
+    let mut engine_a = engine_from_spec(flag_value(args, "--engine-a").unwrap_or("internal"), defaults.strength, defaults.seed)?;
+    let mut engine_b = engine_from_spec(flag_value(args, "--engine-b").unwrap_or("internal"), defaults.strength, seed_b)?;
+    let name_a = engine_a.name().to_string();
+    let name_b = engine_b.name().to_string();
+
+    println!("Match: {} games on {}x{}, komi {}", config.games, config.size, config.size, config.komi);
+    println!("  A: {}", name_a);
+    println!("  B: {}", name_b);
+    println!();
+    println!("{:>4}  {:<5}  {:>8}  {:>5}  Note", "Game", "Black", "Result", "Moves");
+
+    // [as black, as white] wins for A and B
+    let mut a_wins = [0, 0];
+    let mut b_wins = [0, 0];
+    let mut jigo = 0;
+    let mut voids = 0;
+    let mut timeouts = 0;
+    let mut forfeits = 0;
+
+    for i in 0..config.games {
+        let a_is_black = i % 2 == 0;
+        let result = if a_is_black {
+            play_game(&config, engine_a.as_mut(), engine_b.as_mut())
+        } else {
+            play_game(&config, engine_b.as_mut(), engine_a.as_mut())
+        };
+
+        match result.winner {
+            Some(winner) => {
+                let a_won = (winner == Stone::Black) == a_is_black;
+                let side = if winner == Stone::Black { 0 } else { 1 };
+                if a_won { a_wins[side] += 1 } else { b_wins[side] += 1 }
+            }
+            None if result.ending == Ending::NoResult => voids += 1,
+            None => jigo += 1,
+        }
+        match result.ending {
+            Ending::Time => timeouts += 1,
+            Ending::Forfeit => forfeits += 1,
+            _ => {}
+        }
+
+        let re = result.result_string();
+        println!(
+            "{:>4}  {:<5}  {:>8}  {:>5}  {}",
+            i + 1,
+            if a_is_black { "A" } else { "B" },
+            re,
+            result.moves.len(),
+            result.note.as_deref().unwrap_or("")
+        );
+
+        let (pb, pw) = if a_is_black { (&name_a, &name_b) } else { (&name_b, &name_a) };
+        let sgf = record_to_sgf(
+            config.size,
+            config.komi,
+            &result.moves,
+            &[("PB", pb), ("PW", pw), ("RE", &re)],
+        );
+        if let Some(dir) = &config.sgf_dir {
+            let path = dir.join(format!("match-{:04}.sgf", i + 1));
+            fs::write(&path, &sgf)
+                .map_err(|e| format!("Cannot write '{}': {}", path.display(), e))?;
+        }
+        if let Some(archive) = &archive {
+            archive.save("match", &sgf)?;
+        }
+    }
+
+    if config.games > 0 {
+        println!();
+        println!("{:<4} {:>6} {:>9} {:>9}", "", "Wins", "as Black", "as White");
+        println!("{:<4} {:>6} {:>9} {:>9}", "A", a_wins[0] + a_wins[1], a_wins[0], a_wins[1]);
+        println!("{:<4} {:>6} {:>9} {:>9}", "B", b_wins[0] + b_wins[1], b_wins[0], b_wins[1]);
+        println!("Jigo: {}, No result: {}, Timeouts: {}, Forfeits: {}", jigo, voids, timeouts, forfeits);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,59 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Tune subcommand: adjusts the values of a text pattern database by how
+//! often its moves were played in a directory of game records
+
+use std::path::{Path, PathBuf};
+
+use crate::cli::flag_value;
+use crate::fileio;
+use crate::patterns::{parse_patterns, write_values, PatternTuner};
+use crate::sgf::review::Rank;
+use crate::sgf::SGFHandler;
+
+const USAGE: &str = "Usage: gnugo_rs tune DIR --database FILE --output FILE [--min-rank RANK]";
+
+/// Runs the tune subcommand. Records that fail to load are reported and
+/// left out
+pub fn run(args: &[String]) -> Result<(), String> {
+    let dir = args.first().filter(|a| !a.starts_with("--")).ok_or(USAGE)?;
+    let database = flag_value(args, "--database").ok_or(USAGE)?;
+    let output = flag_value(args, "--output").ok_or(USAGE)?;
+    let min_rank = match flag_value(args, "--min-rank") {
+        Some(rank) => Some(Rank::parse(rank).ok_or_else(|| format!("Invalid value '{}' for --min-rank", rank))?),
+        None => None,
+    };
+
+    let text = fileio::read_to_string(Path::new(database)).map_err(|e| format!("Cannot read '{}': {}", database, e))?;
+    let mut tuner = PatternTuner::new(parse_patterns(&text)?);
+    if let Some(rank) = min_rank {
+        tuner = tuner.with_min_rank(rank);
+    }
+
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map_err(|e| format!("Cannot read directory '{}': {}", dir, e))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("sgf")))
+        .collect();
+    paths.sort();
+
+    let handler = SGFHandler::new();
+    let (mut games, mut moves) = (0, 0);
+    for path in &paths {
+        match handler.load_file(path).and_then(|tree| tuner.add_game(&tree)) {
+            Ok(counted) => {
+                games += 1;
+                moves += counted;
+            }
+            Err(e) => eprintln!("{}: {}", path.display(), e),
+        }
+    }
+
+    let values = tuner.tuned_values();
+    let changed = tuner.patterns().iter().zip(&values).filter(|(p, &v)| p.value != v).count();
+    fileio::write(Path::new(output), write_values(&text, &values)?.as_bytes())
+        .map_err(|e| format!("Cannot write '{}': {}", output, e))?;
+    println!("{} games, {} moves counted, {} of {} pattern values changed, written to {}", games, moves, changed, values.len(), output);
+    Ok(())
+}
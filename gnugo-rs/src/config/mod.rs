@@ -0,0 +1,411 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! User configuration: defaults from `~/.config/gnugo-rs/config.toml`,
+//! overridden by command line flags
+//!
+//! ```toml
+//! [game]
+//! board_size = 19
+//! komi = 6.5
+//! ruleset = "chinese"    # or japanese, aga, new-zealand, ing, area, territory
+//! variant = "capture"      # Capture Go; default "standard"
+//! capture_target = 3       # stones to win Capture Go, default 1
+//!
+//! [engine]
+//! level = "intermediate"   # or a number from 1 to 10
+//! pattern_dir = "/usr/share/gnugo-rs/patterns"
+//! max_memory_mb = 256      # caches and transposition tables
+//! tt_entries = 1000000     # per search
+//! max_nodes = 500000       # positions per move or search
+//! threads = 4              # parallel searches; default one per core
+//! book = "/usr/share/gnugo-rs/book.txt"    # opening book for genmove
+//! fuseki = "/usr/share/gnugo-rs/fuseki.txt" # whole-board openings for genmove
+//! resign_threshold = 0.25  # share of the board to trail by before resigning
+//! resign_moves = 3         # moves in a row that far behind; 0 never resigns
+//! seed = 42                # reproducible random choices; default a fresh seed
+//!
+//! [display]
+//! theme = "classic"
+//! language = "zh"          # default: from LC_ALL, LC_MESSAGES or LANG
+//! accessible = false       # screen reader output, also --accessible
+//!
+//! [archive]
+//! dir = "/home/me/go/games"    # keep every finished game; also --archive DIR
+//!
+//! [time]
+//! main_time = 600
+//! byo_yomi = 30
+//! byo_yomi_stones = 25
+//! ```
+
+pub mod toml;
+
+use std::path::{Path, PathBuf};
+
+use crate::cli::flag_value;
+use crate::engine::ai::{parse_level, AIDifficulty, ResignRule, ResourceLimits, MAX_LEVEL};
+use crate::engine::clock::{Clock, TimeControl};
+use crate::engine::game::Game;
+use crate::engine::rules::{GameRules, Variant};
+use crate::fileio;
+use crate::ui::board_renderer::RenderConfig;
+use crate::ui::i18n::Language;
+use toml::TomlValue;
+
+/// Flags that take a value and override a config setting
+const VALUE_FLAGS: &[&str] = &[
+    "--config", "--size", "--komi", "--level", "--ruleset", "--patterns", "--theme", "--lang",
+    "--main-time", "--byo-yomi", "--byo-yomi-stones", "--max-memory", "--tt-size", "--max-nodes",
+    "--archive", "--variant", "--capture-target", "--threads", "--book", "--fuseki",
+    "--resign-threshold", "--resign-moves", "--seed",
+];
+
+/// Flags without a value
+const SWITCH_FLAGS: &[&str] = &["--accessible"];
+
+/// Time control in seconds; zero main time means unlimited
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TimeSettings {
+    pub main_time: u64,
+    pub byo_yomi_time: u64,
+    pub byo_yomi_stones: u32,
+}
+
+impl TimeSettings {
+    /// Canadian overtime; without overtime stones the main time is absolute
+    pub fn time_control(&self) -> TimeControl {
+        if self.byo_yomi_stones == 0 {
+            TimeControl::canadian(self.main_time, 0, 0)
+        } else {
+            TimeControl::canadian(self.main_time, self.byo_yomi_time, self.byo_yomi_stones)
+        }
+    }
+}
+
+/// Effective settings after merging the config file and the command line
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub board_size: usize,
+    pub komi: f32,
+    pub level: AIDifficulty,
+    /// Numeric level from 1 to [`MAX_LEVEL`]; a named level sets its number
+    pub strength: u8,
+    /// Scoring, ko and suicide rules of new games
+    pub ruleset: GameRules,
+    pub variant: Variant,
+    pub pattern_dir: PathBuf,
+    pub theme: String,
+    /// Terminal UI language; `None` follows the locale
+    pub language: Option<Language>,
+    /// Plain-text terminal output for screen readers
+    pub accessible: bool,
+    /// Where finished games are archived; `None` keeps no archive
+    pub archive_dir: Option<PathBuf>,
+    pub time: TimeSettings,
+    pub limits: ResourceLimits,
+    /// Opening book consulted by `genmove`; `None` plays without one
+    pub book: Option<PathBuf>,
+    /// Fuseki database consulted by `genmove`; `None` plays without one
+    pub fuseki: Option<PathBuf>,
+    /// When `genmove` resigns
+    pub resign: ResignRule,
+    /// Seed of the engine's random choices; `None` draws a fresh one
+    pub seed: Option<u64>,
+    /// Whether komi was given explicitly, so the ruleset's does not replace it
+    komi_set: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            board_size: 19,
+            komi: 6.5,
+            level: AIDifficulty::Intermediate,
+            strength: AIDifficulty::Intermediate.level(),
+            ruleset: GameRules::default(),
+            variant: Variant::Standard,
+            pattern_dir: PathBuf::from("patterns"),
+            theme: "classic".to_string(),
+            language: None,
+            accessible: false,
+            archive_dir: None,
+            time: TimeSettings::default(),
+            limits: ResourceLimits::default(),
+            book: None,
+            fuseki: None,
+            resign: ResignRule::default(),
+            seed: None,
+            komi_set: false,
+        }
+    }
+}
+
+impl Config {
+    /// Loads the config file (or `--config PATH`) and applies flags from `args`.
+    /// A missing default config file is not an error.
+    #[cfg(feature = "native")]
+    pub fn load(args: &[String]) -> Result<Self, String> {
+        let mut config = match flag_value(args, "--config") {
+            Some(path) => Self::from_file(&PathBuf::from(path))?,
+            None => match Self::default_path() {
+                Some(path) if path.exists() => Self::from_file(&path)?,
+                _ => Config::default(),
+            },
+        };
+        config.apply_args(args)?;
+        Ok(config)
+    }
+
+    /// `$XDG_CONFIG_HOME/gnugo-rs/config.toml`, else `~/.config/gnugo-rs/config.toml`
+    #[cfg(feature = "native")]
+    pub fn default_path() -> Option<PathBuf> {
+        let base = match std::env::var_os("XDG_CONFIG_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+        };
+        Some(base.join("gnugo-rs").join("config.toml"))
+    }
+
+    /// Reads settings from a TOML file on top of the defaults
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let text = fileio::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Self::from_toml(&text).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Parses settings from TOML text on top of the defaults
+    pub fn from_toml(text: &str) -> Result<Self, String> {
+        let mut config = Config::default();
+        for (key, value) in toml::parse(text)? {
+            config.set(&key, &value)?;
+        }
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn set(&mut self, key: &str, value: &TomlValue) -> Result<(), String> {
+        let wrong_type = |expected: &str| format!("'{}' must be {}", key, expected);
+        let count = |value: &TomlValue| -> Result<usize, String> {
+            value
+                .as_integer()
+                .and_then(|i| usize::try_from(i).ok())
+                .filter(|&n| n > 0)
+                .ok_or_else(|| wrong_type("a positive integer"))
+        };
+        let non_negative = |value: &TomlValue| -> Result<u64, String> {
+            value
+                .as_integer()
+                .and_then(|i| u64::try_from(i).ok())
+                .ok_or_else(|| wrong_type("a non-negative integer"))
+        };
+
+        match key {
+            "game.board_size" => {
+                self.board_size = value
+                    .as_integer()
+                    .and_then(|i| usize::try_from(i).ok())
+                    .ok_or_else(|| wrong_type("an integer"))?
+            }
+            "game.komi" => self.set_komi(value.as_f64().ok_or_else(|| wrong_type("a number"))? as f32),
+            "game.ruleset" => self.set_ruleset(value.as_str().ok_or_else(|| wrong_type("a string"))?)?,
+            "game.variant" => {
+                let name = value.as_str().ok_or_else(|| wrong_type("a string"))?;
+                self.set_variant(name)?;
+            }
+            "game.capture_target" => {
+                self.variant = Variant::Capture { target: u32::try_from(count(value)?).map_err(|_| wrong_type("a small integer"))? }
+            }
+            "engine.level" => match (value.as_str(), value.as_integer()) {
+                (Some(name), _) => self.set_level(name)?,
+                (_, Some(number)) => self.set_level(&number.to_string())?,
+                _ => return Err(wrong_type("a level name or number")),
+            },
+            "engine.pattern_dir" => {
+                self.pattern_dir = PathBuf::from(value.as_str().ok_or_else(|| wrong_type("a string"))?)
+            }
+            "engine.max_memory_mb" => self.limits.cache_mb = Some(count(value)?),
+            "engine.tt_entries" => self.limits.tt_entries = Some(count(value)?),
+            "engine.max_nodes" => self.limits.max_nodes = Some(count(value)?),
+            "engine.threads" => self.limits.threads = Some(count(value)?),
+            "engine.resign_threshold" => {
+                self.resign.threshold = value.as_f64().ok_or_else(|| wrong_type("a number"))? as f32
+            }
+            "engine.seed" => self.seed = Some(non_negative(value)?),
+            "engine.resign_moves" => {
+                self.resign.moves = usize::try_from(non_negative(value)?).map_err(|_| wrong_type("a small integer"))?
+            }
+            "engine.book" => self.book = Some(PathBuf::from(value.as_str().ok_or_else(|| wrong_type("a string"))?)),
+            "engine.fuseki" => self.fuseki = Some(PathBuf::from(value.as_str().ok_or_else(|| wrong_type("a string"))?)),
+            "display.language" => {
+                let name = value.as_str().ok_or_else(|| wrong_type("a string"))?;
+                self.language = Some(Language::from_name(name).ok_or_else(|| format!("Unknown language '{}'", name))?);
+            }
+            "display.accessible" => self.accessible = value.as_bool().ok_or_else(|| wrong_type("a boolean"))?,
+            "archive.dir" => {
+                self.archive_dir = Some(PathBuf::from(value.as_str().ok_or_else(|| wrong_type("a string"))?))
+            }
+            "display.theme" => self.theme = value.as_str().ok_or_else(|| wrong_type("a string"))?.to_string(),
+            "time.main_time" => self.time.main_time = non_negative(value)?,
+            "time.byo_yomi" => self.time.byo_yomi_time = non_negative(value)?,
+            "time.byo_yomi_stones" => {
+                self.time.byo_yomi_stones = u32::try_from(non_negative(value)?).map_err(|_| wrong_type("a small integer"))?
+            }
+            _ => return Err(format!("Unknown setting '{}'", key)),
+        }
+        Ok(())
+    }
+
+    /// Applies command line overrides
+    pub fn apply_args(&mut self, args: &[String]) -> Result<(), String> {
+        fn parse<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+            value.parse().map_err(|_| format!("Invalid value '{}' for {}", value, flag))
+        }
+
+        if let Some(v) = flag_value(args, "--size") {
+            self.board_size = parse("--size", v)?;
+        }
+        if let Some(v) = flag_value(args, "--komi") {
+            self.set_komi(parse("--komi", v)?);
+        }
+        if let Some(v) = flag_value(args, "--level") {
+            self.set_level(v)?;
+        }
+        if let Some(v) = flag_value(args, "--ruleset") {
+            self.set_ruleset(v)?;
+        }
+        if let Some(v) = flag_value(args, "--variant") {
+            self.set_variant(v)?;
+        }
+        if let Some(v) = flag_value(args, "--capture-target") {
+            let target: u32 = parse("--capture-target", v)?;
+            if target == 0 {
+                return Err("--capture-target must be at least 1".to_string());
+            }
+            self.variant = Variant::Capture { target };
+        }
+        if let Some(v) = flag_value(args, "--patterns") {
+            self.pattern_dir = PathBuf::from(v);
+        }
+        if let Some(v) = flag_value(args, "--theme") {
+            self.theme = v.to_string();
+        }
+        if let Some(v) = flag_value(args, "--resign-threshold") {
+            self.resign.threshold = parse("--resign-threshold", v)?;
+        }
+        if let Some(v) = flag_value(args, "--resign-moves") {
+            self.resign.moves = parse("--resign-moves", v)?;
+        }
+        if let Some(v) = flag_value(args, "--seed") {
+            self.seed = Some(parse("--seed", v)?);
+        }
+        if let Some(v) = flag_value(args, "--book") {
+            self.book = Some(PathBuf::from(v));
+        }
+        if let Some(v) = flag_value(args, "--fuseki") {
+            self.fuseki = Some(PathBuf::from(v));
+        }
+        if let Some(v) = flag_value(args, "--archive") {
+            self.archive_dir = Some(PathBuf::from(v));
+        }
+        if args.iter().any(|a| a == "--accessible") {
+            self.accessible = true;
+        }
+        if let Some(v) = flag_value(args, "--lang") {
+            self.language = Some(Language::from_name(v).ok_or_else(|| format!("Unknown language '{}'", v))?);
+        }
+        if let Some(v) = flag_value(args, "--main-time") {
+            self.time.main_time = parse("--main-time", v)?;
+        }
+        if let Some(v) = flag_value(args, "--byo-yomi") {
+            self.time.byo_yomi_time = parse("--byo-yomi", v)?;
+        }
+        if let Some(v) = flag_value(args, "--byo-yomi-stones") {
+            self.time.byo_yomi_stones = parse("--byo-yomi-stones", v)?;
+        }
+        for (flag, limit) in [
+            ("--max-memory", &mut self.limits.cache_mb),
+            ("--tt-size", &mut self.limits.tt_entries),
+            ("--max-nodes", &mut self.limits.max_nodes),
+            ("--threads", &mut self.limits.threads),
+        ] {
+            if let Some(v) = flag_value(args, flag) {
+                *limit = Some(parse::<usize>(flag, v)?).filter(|&n| n > 0);
+            }
+        }
+        self.validate()
+    }
+
+    /// Sets the level from a difficulty name or a number from 1 to [`MAX_LEVEL`]
+    fn set_level(&mut self, level: &str) -> Result<(), String> {
+        let number = parse_level(level).ok_or_else(|| format!("Unknown level '{}', expected a name or 1 to {}", level, MAX_LEVEL))?;
+        self.level = AIDifficulty::from_level(number);
+        self.strength = number;
+        Ok(())
+    }
+
+    /// Switches to the named variant, keeping a capture target already set
+    fn set_variant(&mut self, name: &str) -> Result<(), String> {
+        let variant = Variant::from_name(name).ok_or_else(|| format!("Unknown variant '{}'", name))?;
+        if variant.capture_target().is_none() || self.variant.capture_target().is_none() {
+            self.variant = variant;
+        }
+        Ok(())
+    }
+
+    fn set_komi(&mut self, komi: f32) {
+        self.komi = komi;
+        self.komi_set = true;
+    }
+
+    /// Switches to the named ruleset, taking its komi unless komi was given
+    fn set_ruleset(&mut self, name: &str) -> Result<(), String> {
+        self.ruleset = GameRules::from_name(name).ok_or_else(|| format!("Unknown ruleset '{}'", name))?;
+        if !self.komi_set {
+            self.komi = self.ruleset.komi;
+        }
+        Ok(())
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if !(2..=25).contains(&self.board_size) {
+            return Err(format!("Unsupported board size {}", self.board_size));
+        }
+        if !self.komi.is_finite() {
+            return Err(format!("Invalid komi {}", self.komi));
+        }
+        if self.resign.threshold.is_nan() || self.resign.threshold < 0.0 {
+            return Err(format!("Invalid resign threshold {}", self.resign.threshold));
+        }
+        if self.theme != "classic" && RenderConfig::from_theme(&self.theme).is_none() {
+            return Err(format!("Unknown theme '{}'", self.theme));
+        }
+        Ok(())
+    }
+
+    /// Whether `arg` is a config override flag, either one that consumes
+    /// the next argument or a switch
+    pub fn is_flag(arg: &str) -> bool {
+        VALUE_FLAGS.contains(&arg) || SWITCH_FLAGS.contains(&arg)
+    }
+
+    /// `args` from the first argument that is neither a config flag nor a
+    /// flag's value, e.g. from `--gtp` in `--size 9 --gtp`
+    pub fn skip_flags(args: &[String]) -> &[String] {
+        let mut rest = args;
+        while let Some(arg) = rest.first().filter(|arg| Self::is_flag(arg)) {
+            let taken = if VALUE_FLAGS.contains(&arg.as_str()) { 2 } else { 1 };
+            rest = rest.get(taken..).unwrap_or_default();
+        }
+        rest
+    }
+
+    /// A fresh game with the configured size, rules and komi
+    pub fn new_game(&self) -> Game {
+        let mut game = Game::with_rules(self.board_size, self.ruleset);
+        game.komi = self.komi;
+        game.variant = self.variant;
+        game.clock = Clock::new(self.time.time_control());
+        game
+    }
+}
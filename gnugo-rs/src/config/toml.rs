@@ -0,0 +1,162 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! The subset of TOML used by config files: `[section]` headers and
+//! `key = value` lines with strings, integers, floats and booleans
+
+/// A scalar TOML value
+#[derive(Debug, Clone, PartialEq)]
+pub enum TomlValue {
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+}
+
+impl TomlValue {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            TomlValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Integers are accepted where floats are expected
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            TomlValue::Float(f) => Some(*f),
+            TomlValue::Integer(i) => Some(*i as f64),
+            _ => None,
+        }
+    }
+
+    pub fn as_integer(&self) -> Option<i64> {
+        match self {
+            TomlValue::Integer(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            TomlValue::Boolean(b) => Some(*b),
+            _ => None,
+        }
+    }
+}
+
+/// Parses a document into `("section.key", value)` pairs in file order
+pub fn parse(text: &str) -> Result<Vec<(String, TomlValue)>, String> {
+    let mut entries = Vec::new();
+    let mut section = String::new();
+
+    for (index, raw) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let line = strip_comment(raw).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix('[') {
+            let name = header
+                .strip_suffix(']')
+                .ok_or_else(|| format!("line {}: unterminated section header", line_number))?
+                .trim();
+            if name.is_empty() || !name.split('.').all(is_bare_key) {
+                return Err(format!("line {}: invalid section name '{}'", line_number, name));
+            }
+            section = name.to_string();
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| format!("line {}: expected key = value", line_number))?;
+        let key = key.trim();
+        if !is_bare_key(key) {
+            return Err(format!("line {}: invalid key '{}'", line_number, key));
+        }
+        let value = parse_value(value.trim()).map_err(|e| format!("line {}: {}", line_number, e))?;
+
+        let full_key = if section.is_empty() { key.to_string() } else { format!("{}.{}", section, key) };
+        if entries.iter().any(|(k, _)| *k == full_key) {
+            return Err(format!("line {}: duplicate key '{}'", line_number, full_key));
+        }
+        entries.push((full_key, value));
+    }
+
+    Ok(entries)
+}
+
+fn is_bare_key(key: &str) -> bool {
+    !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Removes a trailing `#` comment that is not inside a string
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match quote {
+            Some('"') if escaped => escaped = false,
+            Some('"') if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '#' => return &line[..i],
+            None => {}
+        }
+    }
+    line
+}
+
+fn parse_value(text: &str) -> Result<TomlValue, String> {
+    if let Some(rest) = text.strip_prefix('"') {
+        let body = rest.strip_suffix('"').ok_or("unterminated string")?;
+        return unescape(body).map(TomlValue::String);
+    }
+    if let Some(rest) = text.strip_prefix('\'') {
+        let body = rest.strip_suffix('\'').ok_or("unterminated string")?;
+        return Ok(TomlValue::String(body.to_string()));
+    }
+    match text {
+        "true" => return Ok(TomlValue::Boolean(true)),
+        "false" => return Ok(TomlValue::Boolean(false)),
+        "" => return Err("missing value".to_string()),
+        _ => {}
+    }
+
+    let digits = text.replace('_', "");
+    if let Ok(i) = digits.parse::<i64>() {
+        return Ok(TomlValue::Integer(i));
+    }
+    if let Ok(f) = digits.parse::<f64>() {
+        return Ok(TomlValue::Float(f));
+    }
+    Err(format!("unsupported value '{}'", text))
+}
+
+fn unescape(body: &str) -> Result<String, String> {
+    let mut out = String::new();
+    let mut chars = body.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('"') => out.push('"'),
+            Some('\\') => out.push('\\'),
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('r') => out.push('\r'),
+            Some('u') => {
+                let hex: String = chars.by_ref().take(4).collect();
+                let code = u32::from_str_radix(&hex, 16).map_err(|_| "invalid unicode escape")?;
+                out.push(char::from_u32(code).ok_or("invalid unicode escape")?);
+            }
+            _ => return Err("invalid escape sequence".to_string()),
+        }
+    }
+    Ok(out)
+}
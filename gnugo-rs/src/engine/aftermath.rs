@@ -0,0 +1,59 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Aftermath: playing on after the game to take dead stones off and fill
+//! dame, so that area scoring needs no agreement about dead stones
+
+use crate::engine::board::{Board, Stone};
+use crate::engine::final_status::{FinalStatus, StringStatus};
+use crate::engine::rules::GameRules;
+use crate::engine::scoring::{remove_stones, territory_map};
+
+/// Move `color` should play in the aftermath, or `None` to pass: first a
+/// liberty of a dead enemy string, then a dame point next to its own
+/// stones. Moves that put the mover's own string in atari are never chosen.
+pub fn aftermath_move(board: &Board, color: Stone, rules: &GameRules) -> Option<(usize, usize)> {
+    let enemy = match color {
+        Stone::Black => Stone::White,
+        Stone::White => Stone::Black,
+        Stone::Empty => return None,
+    };
+    let status = FinalStatus::analyze(board);
+    let dead = status.dead();
+
+    let mut captures: Vec<(usize, usize)> = dead
+        .iter()
+        .filter(|&&(x, y)| board.get_stone(x, y) == enemy)
+        .flat_map(|&(x, y)| board.find_liberties(x, y))
+        .collect();
+    captures.sort();
+    captures.dedup();
+
+    let cleaned = remove_stones(board, &dead);
+    let owners = territory_map(&cleaned);
+    let size = board.size();
+    let dame = (1..=size).flat_map(|y| (1..=size).map(move |x| (x, y))).filter(|&(x, y)| {
+        board.get_stone(x, y) == Stone::Empty
+            && owners[y - 1][x - 1] == Stone::Empty
+            && board
+                .neighbors(x, y)
+                .iter()
+                .any(|&(nx, ny)| board.get_stone(nx, ny) == color && status.status(nx, ny) != Some(StringStatus::Dead))
+    });
+
+    captures.into_iter().chain(dame).find(|&(x, y)| is_safe(board, x, y, color, rules))
+}
+
+/// Legal, and leaves the new stone's string with two liberties unless it
+/// captured something
+fn is_safe(board: &Board, x: usize, y: usize, color: Stone, rules: &GameRules) -> bool {
+    if board.is_legal(x, y, color, rules).is_err() {
+        return false;
+    }
+    let mut next = board.clone();
+    let before = next.get_captured();
+    if next.place_stone_under(x, y, color, rules).is_err() {
+        return false;
+    }
+    next.get_captured() != before || next.count_liberties(x, y) >= 2
+}
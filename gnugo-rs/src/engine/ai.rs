@@ -3,104 +3,882 @@
 
 //! AI module for Go game
 
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
+
 use crate::engine::board::{Board, Stone};
+use crate::engine::endgame::{self, EndgameMove};
 use crate::engine::evaluation::Evaluator;
-use rand::seq::SliceRandom;
-use rand::thread_rng;
+use crate::engine::game::{Action, Game};
+use crate::engine::ko;
+use crate::engine::mcts::{self, MctsSettings};
+use crate::engine::move_generation::MoveGenerator;
+use crate::engine::move_reasons::{MoveReasons, Reason};
+use crate::engine::rules::{GameRules, KoRule, Variant};
+use crate::engine::search::{self, SEARCH_WIDTH};
+use crate::engine::trace::SearchTrace;
+use crate::engine::tsumego::DEFAULT_MAX_NODES;
+use crate::gtp::format_move;
+use crate::patterns::fuseki;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Strongest numeric level
+pub const MAX_LEVEL: u8 = 10;
+
+/// A numeric level from a difficulty name or a number from 1 to [`MAX_LEVEL`]
+pub fn parse_level(text: &str) -> Option<u8> {
+    match AIDifficulty::from_name(text) {
+        Some(difficulty) => Some(difficulty.level()),
+        None => text.parse().ok().filter(|level| (1..=MAX_LEVEL).contains(level)),
+    }
+}
+
+/// Thinking time each level's limits are sized for
+pub const REFERENCE_MOVE_TIME: Duration = Duration::from_secs(5);
+
+/// Below this much thinking time the engine plays in a hurry, reading a
+/// single ply
+pub const PANIC_TIME: Duration = Duration::from_secs(1);
+
+/// Deepest alpha-beta iteration when the clock decides the depth
+pub const MAX_SEARCH_DEPTH: usize = 8;
+
+/// Share of a move's time budget the search may use, leaving the rest
+/// for the work around it
+const SEARCH_SHARE: f32 = 0.8;
+
+/// Plies [`AI::top_moves`] reads at least, so each move comes with a line
+/// of play
+pub const TOP_MOVES_DEPTH: usize = 3;
+
+/// Lead in evaluation units at which a player's winning chance is about
+/// three in four
+const WINRATE_SCALE: f32 = 10.0;
+
+/// Temperature of the Beginner level: moves a few points apart are all
+/// played now and then, and one worth ten points more about seven times
+/// as often
+const BEGINNER_TEMPERATURE: f32 = 5.0;
 
 /// AI difficulty levels
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AIDifficulty {
-    Beginner,   // Random moves
+    Beginner,   // Loosely weighted random moves
     Intermediate, // Basic evaluation
-    Advanced,    // Better evaluation (future)
+    Advanced,    // Alpha-beta search
+}
+
+impl AIDifficulty {
+    /// Parses a difficulty name such as "beginner" (case-insensitive)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "beginner" => Some(AIDifficulty::Beginner),
+            "intermediate" => Some(AIDifficulty::Intermediate),
+            "advanced" => Some(AIDifficulty::Advanced),
+            _ => None,
+        }
+    }
+
+    /// Lower-case name of the difficulty
+    pub fn name(&self) -> &'static str {
+        match self {
+            AIDifficulty::Beginner => "beginner",
+            AIDifficulty::Intermediate => "intermediate",
+            AIDifficulty::Advanced => "advanced",
+        }
+    }
+
+    /// The numeric level that plays like this difficulty
+    pub fn level(&self) -> u8 {
+        match self {
+            AIDifficulty::Beginner => 1,
+            AIDifficulty::Intermediate => 5,
+            AIDifficulty::Advanced => 8,
+        }
+    }
+
+    /// The difficulty whose way of choosing moves numeric `level` uses:
+    /// 1 plays at random, 2 to 5 by move reasons, higher levels search
+    pub fn from_level(level: u8) -> Self {
+        match level {
+            0..=1 => AIDifficulty::Beginner,
+            2..=5 => AIDifficulty::Intermediate,
+            _ => AIDifficulty::Advanced,
+        }
+    }
+
+    /// Search limits for this level
+    pub fn profile(&self) -> LevelProfile {
+        match self {
+            AIDifficulty::Beginner => LevelProfile {
+                ladder_depth: 20,
+                net_depth: 1,
+                owl_nodes: 20_000,
+                playouts: 50,
+                endgame_depth: 1,
+                search_depth: 1,
+                search_nodes: 1_000,
+                tree_playouts: 0,
+                dragons: true,
+                endgame: true,
+                temperature: BEGINNER_TEMPERATURE,
+            },
+            AIDifficulty::Intermediate => LevelProfile {
+                ladder_depth: 60,
+                net_depth: 2,
+                owl_nodes: DEFAULT_MAX_NODES,
+                playouts: 200,
+                endgame_depth: 2,
+                search_depth: 1,
+                search_nodes: 5_000,
+                tree_playouts: 0,
+                dragons: true,
+                endgame: true,
+                temperature: 0.0,
+            },
+            AIDifficulty::Advanced => LevelProfile {
+                ladder_depth: 150,
+                net_depth: 3,
+                owl_nodes: 1_000_000,
+                playouts: 1_000,
+                endgame_depth: 4,
+                search_depth: 3,
+                search_nodes: 20_000,
+                tree_playouts: 0,
+                dragons: true,
+                endgame: true,
+                temperature: 0.0,
+            },
+        }
+    }
+}
+
+/// Resource limits of every search module, so the level alone trades
+/// strength against speed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LevelProfile {
+    /// Attacker moves read before a ladder counts as escaped
+    pub ladder_depth: usize,
+    /// Net moves read in a row before a string counts as escaped
+    pub net_depth: usize,
+    /// Node budget of life-and-death reading
+    pub owl_nodes: usize,
+    /// Random playouts behind ownership estimates
+    pub playouts: usize,
+    /// Plies read when valuing endgame moves
+    pub endgame_depth: usize,
+    /// Plies of the alpha-beta search behind Advanced moves
+    pub search_depth: usize,
+    /// Positions the alpha-beta search may visit per move
+    pub search_nodes: usize,
+    /// Playouts of the Monte Carlo tree search that picks the move at the
+    /// strongest levels; 0 leaves the choice to alpha-beta or the reasons
+    pub tree_playouts: usize,
+    /// Whether moves are weighed by the life and death of dragons
+    pub dragons: bool,
+    /// Whether the endgame module picks boundary plays
+    pub endgame: bool,
+    /// Spread of the move choice: a move is picked with odds growing as
+    /// exp(value / temperature), so 0 always plays the best
+    pub temperature: f32,
+}
+
+impl LevelProfile {
+    /// Limits and analysis modules of numeric `level`, from 1 to
+    /// [`MAX_LEVEL`] like GNU Go's `--level`. Levels 1, 5 and 8 are the
+    /// Beginner, Intermediate and Advanced difficulties; levels between
+    /// them leave out modules or read less and play less precisely, those
+    /// above read deeper and choose by Monte Carlo tree search
+    pub fn for_level(level: u8) -> LevelProfile {
+        let intermediate = AIDifficulty::Intermediate.profile();
+        let advanced = AIDifficulty::Advanced.profile();
+        match level.clamp(1, MAX_LEVEL) {
+            1 => AIDifficulty::Beginner.profile(),
+            2 => LevelProfile { dragons: false, endgame: false, temperature: 3.0, ..intermediate },
+            3 => LevelProfile { dragons: false, temperature: 2.0, ..intermediate },
+            4 => LevelProfile { owl_nodes: intermediate.owl_nodes / 2, playouts: 100, temperature: 1.0, ..intermediate },
+            5 => intermediate,
+            6 => LevelProfile { playouts: 500, search_depth: 2, search_nodes: 10_000, ..advanced },
+            7 => LevelProfile { playouts: 750, search_depth: 2, search_nodes: 15_000, ..advanced },
+            8 => advanced,
+            9 => LevelProfile { playouts: 2_000, search_depth: 4, search_nodes: 50_000, tree_playouts: 2_000, ..advanced },
+            _ => LevelProfile { playouts: 4_000, search_depth: 5, search_nodes: 100_000, tree_playouts: 5_000, ..advanced },
+        }
+    }
+
+    /// The limits for a move that may take `budget`: reading grows and
+    /// shrinks with the time against [`REFERENCE_MOVE_TIME`], and a level
+    /// that searches deepens as far as the time allows. Under
+    /// [`PANIC_TIME`] only one ply is read
+    pub fn with_time(self, budget: Duration) -> LevelProfile {
+        let scale = budget.as_secs_f32() / REFERENCE_MOVE_TIME.as_secs_f32();
+        // A limit that is off stays off
+        let scaled = |n: usize| ((n as f32 * scale) as usize).max(n.min(1));
+        if budget < PANIC_TIME {
+            let tree_playouts = scaled(self.tree_playouts);
+            return LevelProfile { endgame_depth: 1, search_depth: 1, search_nodes: SEARCH_WIDTH, tree_playouts, ..self };
+        }
+        LevelProfile {
+            owl_nodes: scaled(self.owl_nodes),
+            playouts: scaled(self.playouts),
+            search_depth: if self.search_depth > 1 { MAX_SEARCH_DEPTH } else { 1 },
+            search_nodes: scaled(self.search_nodes),
+            tree_playouts: scaled(self.tree_playouts),
+            ..self
+        }
+    }
+}
+
+impl Default for LevelProfile {
+    fn default() -> Self {
+        AIDifficulty::Intermediate.profile()
+    }
+}
+
+/// Hard caps on memory and work that apply whatever the level, for hosts
+/// with strict memory limits. `None` leaves a resource unlimited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ResourceLimits {
+    /// Memory for caches and transposition tables, in megabytes
+    pub cache_mb: Option<usize>,
+    /// Entries in a search's transposition table
+    pub tt_entries: Option<usize>,
+    /// Positions examined per move or per search
+    pub max_nodes: Option<usize>,
+    /// Threads of parallel searches; `None` uses one per core
+    pub threads: Option<usize>,
+}
+
+impl ResourceLimits {
+    /// Entries a table may hold when each takes about `entry_bytes`
+    pub fn table_entries(&self, entry_bytes: usize) -> usize {
+        let by_memory = self.cache_mb.map_or(usize::MAX, |mb| mb.saturating_mul(1 << 20) / entry_bytes.max(1));
+        self.tt_entries.unwrap_or(usize::MAX).min(by_memory)
+    }
+
+    /// `budget` capped by `max_nodes`
+    pub fn nodes(&self, budget: usize) -> usize {
+        self.max_nodes.map_or(budget, |max| budget.min(max))
+    }
+
+    /// Threads a parallel search may run; one without thread support
+    pub fn threads(&self) -> usize {
+        if !cfg!(feature = "native") {
+            return 1;
+        }
+        self.threads.unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get()))
+    }
+}
+
+/// When the engine gives up a lost game
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResignRule {
+    /// Share of the board's points the player must trail by
+    pub threshold: f32,
+    /// Moves in a row the player must have been that far behind; 0 never
+    /// resigns
+    pub moves: usize,
+}
+
+impl Default for ResignRule {
+    fn default() -> Self {
+        ResignRule { threshold: 0.25, moves: 1 }
+    }
+}
+
+/// A candidate move as [`AI::top_moves`] ranks it
+#[derive(Debug, Clone, PartialEq)]
+pub struct TopMove {
+    pub point: (usize, usize),
+    /// What the move is worth, as the search weighs it
+    pub value: i32,
+    /// The mover's chance of winning after the move, by evaluation with
+    /// komi left out
+    pub winrate: f32,
+    /// The expected line of play, starting with the move
+    pub pv: Vec<(usize, usize)>,
+}
+
+/// How boldly the engine plays, after GNU Go's handicap adjustments
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Style {
+    /// Above zero the engine invades and seeks contact with the opponent;
+    /// below zero it connects and keeps its territory solid
+    pub aggression: i32,
+}
+
+impl Style {
+    /// Style for `player` when Black received `handicap` stones: White
+    /// must catch up and plays aggressively, Black protects its lead
+    pub fn for_handicap(handicap: usize, player: Stone) -> Self {
+        let stones = if handicap < 2 { 0 } else { handicap.min(9) as i32 };
+        let aggression = match player {
+            Stone::White => stones,
+            Stone::Black => -stones,
+            Stone::Empty => 0,
+        };
+        Style { aggression }
+    }
+
+    /// Adjustment to the value of `player` playing (x, y) on `board`:
+    /// opponent stones within two points count for an aggressive style,
+    /// own neighbors and liberties for a solid one
+    fn bonus(&self, board: &Board, x: usize, y: usize, player: Stone) -> i32 {
+        if self.aggression == 0 {
+            return 0;
+        }
+        let size = board.size();
+        let opponent = if player == Stone::Black { Stone::White } else { Stone::Black };
+        let mut nearby_opponents = 0;
+        for ny in y.saturating_sub(2).max(1)..=(y + 2).min(size) {
+            for nx in x.saturating_sub(2).max(1)..=(x + 2).min(size) {
+                if nx.abs_diff(x) + ny.abs_diff(y) <= 2 && board.get_stone(nx, ny) == opponent {
+                    nearby_opponents += 1;
+                }
+            }
+        }
+        let neighbors = board.neighbors(x, y);
+        let own = neighbors.iter().filter(|&&(nx, ny)| board.get_stone(nx, ny) == player).count() as i32;
+        let liberties = neighbors.iter().filter(|&&(nx, ny)| board.get_stone(nx, ny) == Stone::Empty).count() as i32;
+        if self.aggression > 0 {
+            self.aggression * (nearby_opponents - own)
+        } else {
+            -self.aggression * (own + liberties - nearby_opponents)
+        }
+    }
+}
+
+/// Where the AI moves: the board, the rules its moves must obey and the
+/// positions seen so far, which superko forbids repeating
+struct Position<'a> {
+    board: &'a Board,
+    rules: GameRules,
+    history: Vec<u64>,
+    /// Points added to White's score
+    komi: f32,
+    /// Points never to play, see [`AI::excluding`]
+    excluded: &'a [(usize, usize)],
+}
+
+impl<'a> Position<'a> {
+    /// `board` under the default rules, with no earlier positions
+    fn of_board(board: &'a Board, excluded: &'a [(usize, usize)]) -> Self {
+        let rules = GameRules::default();
+        Position { board, rules, history: Vec::new(), komi: rules.komi, excluded }
+    }
+
+    fn of_game(game: &'a Game, excluded: &'a [(usize, usize)]) -> Self {
+        Position { board: &game.board, rules: game.rules, history: game.position_hashes(), komi: game.komi, excluded }
+    }
+
+    /// Whether `player` may play `point` here, and it is sensible
+    fn allows(&self, (x, y): (usize, usize), player: Stone) -> bool {
+        !self.excluded.contains(&(x, y))
+            && MoveGenerator::is_legal(self.board, x, y, player, &self.rules, &self.history)
+            && MoveGenerator::is_sensible(self.board, x, y, player)
+    }
+
+    /// The legal moves of `player` that neither fill its own eyes nor
+    /// throw stones away, row by row
+    fn sensible_moves(&self, player: Stone) -> Vec<(usize, usize)> {
+        let mut moves: Vec<(usize, usize)> = MoveGenerator::generate_sensible_moves(self.board, player, &self.rules, &self.history)
+            .into_iter()
+            .map(|m| (m.row, m.col))
+            .filter(|point| !self.excluded.contains(point))
+            .collect();
+        moves.sort_by_key(|&(x, y)| (y, x));
+        moves
+    }
 }
 
 /// AI player
 pub struct AI {
     difficulty: AIDifficulty,
+    limits: ResourceLimits,
+    variant: Variant,
+    style: Style,
+    /// Thinking time for the move; `None` plays by the level's limits
+    time_budget: Option<Duration>,
+    resign: ResignRule,
+    /// Limits and modules; the difficulty's own unless set
+    profile: LevelProfile,
+    /// Source of every random choice, seeded by [`AI::with_seed`] for
+    /// reproducible play
+    rng: RefCell<StdRng>,
+    excluded: Vec<(usize, usize)>,
 }
 
 impl AI {
     /// Create a new AI with given difficulty
     pub fn new(difficulty: AIDifficulty) -> Self {
-        AI { difficulty }
+        AI {
+            difficulty,
+            limits: ResourceLimits::default(),
+            variant: Variant::Standard,
+            style: Style::default(),
+            time_budget: None,
+            resign: ResignRule::default(),
+            profile: difficulty.profile(),
+            rng: RefCell::new(StdRng::from_entropy()),
+            excluded: Vec::new(),
+        }
+    }
+
+    /// An AI playing at numeric `level`, see [`LevelProfile::for_level`]
+    pub fn for_level(level: u8) -> Self {
+        AI { profile: LevelProfile::for_level(level), ..AI::new(AIDifficulty::from_level(level)) }
+    }
+
+    /// Plays in `style`, e.g. [`Style::for_handicap`] in handicap games
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Plays for the objective of `variant`, e.g. captures in Capture Go
+    pub fn with_variant(mut self, variant: Variant) -> Self {
+        self.variant = variant;
+        self
+    }
+
+    /// Caps the positions evaluated per move
+    pub fn with_limits(mut self, limits: ResourceLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Sizes the reading to `budget`, e.g. [`Clock::move_budget`](crate::engine::clock::Clock::move_budget);
+    /// `None` keeps the level's own limits
+    pub fn with_time_budget(mut self, budget: Option<Duration>) -> Self {
+        self.time_budget = budget;
+        self
+    }
+
+    /// Makes the random choices from `seed`, so the same positions get
+    /// the same moves
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = RefCell::new(StdRng::seed_from_u64(seed));
+        self
+    }
+
+    /// Gives up by `rule` instead of the default
+    pub fn with_resign_rule(mut self, rule: ResignRule) -> Self {
+        self.resign = rule;
+        self
     }
 
-    /// Get the best move for the current player
+    /// Never plays `point`, e.g. a move the game refused; the next best
+    /// move is chosen instead
+    pub fn excluding(mut self, point: (usize, usize)) -> Self {
+        self.excluded.push(point);
+        self
+    }
+
+    /// The level's limits, sized to the time budget if there is one
+    fn profile(&self) -> LevelProfile {
+        self.time_budget.map_or(self.profile, |budget| self.profile.with_time(budget))
+    }
+
+    /// Get the best move for the current player, under the default rules
+    /// and with no earlier positions to avoid
     pub fn get_best_move(&self, board: &Board, player: Stone) -> Option<(usize, usize)> {
+        self.best_move(&Position::of_board(board, &self.excluded), player)
+    }
+
+    /// The best move for `player` in `game`: legal under the game's rules
+    /// and never repeating a position its superko rule forbids
+    pub fn get_game_move(&self, game: &Game, player: Stone) -> Option<(usize, usize)> {
+        self.best_move(&Position::of_game(game, &self.excluded), player)
+    }
+
+    fn best_move(&self, position: &Position, player: Stone) -> Option<(usize, usize)> {
+        let board = position.board;
+        let legal = |&point: &(usize, usize)| position.allows(point, player);
         match self.difficulty {
-            AIDifficulty::Beginner => self.random_move(board),
-            AIDifficulty::Intermediate => self.greedy_move(board, player),
-            AIDifficulty::Advanced => self.greedy_move(board, player), // TODO: implement minimax
+            AIDifficulty::Beginner => self.random_move(position, player),
+            _ if self.variant != Variant::Standard => self.capture_move(position, player, None),
+            _ => fuseki::opening_move(board).filter(legal).or_else(|| {
+                // The tree search reads kos and the endgame itself
+                if self.profile().tree_playouts > 0 {
+                    return self.tree_move(position, player, None);
+                }
+                ko::ko_move(board, player)
+                    .map(|choice| choice.point())
+                    .filter(legal)
+                    .or_else(|| self.endgame_move(board, player).map(|play| play.point).filter(legal))
+                    .or_else(|| match self.difficulty {
+                        AIDifficulty::Advanced => self.search_move(position, player, None),
+                        _ => self.greedy_move(position, player, None),
+                    })
+            }),
         }
     }
 
-    /// Random move (beginner level)
-    fn random_move(&self, board: &Board) -> Option<(usize, usize)> {
-        let size = board.size();
-        let mut valid_moves = Vec::new();
-        
-        // Find all empty positions
-        for y in 0..size {
-            for x in 0..size {
-                if board.get_stone(x, y) == Stone::Empty {
-                    valid_moves.push((x, y));
+    /// Like [`AI::get_game_move`], recording every move tried into `trace`
+    pub fn get_best_move_traced(&self, game: &Game, player: Stone, trace: &mut SearchTrace) -> Option<(usize, usize)> {
+        let position = Position::of_game(game, &self.excluded);
+        let board = position.board;
+        let legal = |&point: &(usize, usize)| position.allows(point, player);
+        trace.begin_search(format!("genmove {} ({})", player, self.difficulty.name()), board);
+        let best = match self.difficulty {
+            AIDifficulty::Beginner => {
+                let choice = self.random_move(&position, player);
+                trace.enter(player, choice);
+                trace.comment("random choice");
+                trace.leave();
+                choice
+            }
+            _ if self.variant != Variant::Standard => self.capture_move(&position, player, Some(&mut *trace)),
+            _ => match fuseki::opening_move(board).filter(legal) {
+                Some(point) => {
+                    trace.enter(player, Some(point));
+                    trace.comment("opening point");
+                    trace.leave();
+                    Some(point)
                 }
+                None if self.profile().tree_playouts > 0 => self.tree_move(&position, player, Some(&mut *trace)),
+                None => match ko::ko_move(board, player).filter(|choice| legal(&choice.point())) {
+                    Some(choice) => {
+                        trace.enter(player, Some(choice.point()));
+                        trace.comment(choice.name());
+                        trace.leave();
+                        Some(choice.point())
+                    }
+                    None => match self.endgame_move(board, player).filter(|play| legal(&play.point)) {
+                        Some(play) => {
+                            trace.enter(player, Some(play.point));
+                            let timing = if play.sente { "sente" } else { "gote" };
+                            trace.comment(format!("{} {} worth {:.1} points", timing, play.kind.name(), play.value));
+                            trace.leave();
+                            Some(play.point)
+                        }
+                        None if self.difficulty == AIDifficulty::Advanced => {
+                            self.search_move(&position, player, Some(&mut *trace))
+                        }
+                        None => self.greedy_move(&position, player, Some(&mut *trace)),
+                    },
+                },
+            },
+        };
+        trace.comment(format!("selected {}", best.map_or("pass".to_string(), |(x, y)| format_move(x, y, board.size()))));
+        best
+    }
+
+    /// The biggest boundary play, once the game has reached its endgame
+    fn endgame_move(&self, board: &Board, player: Stone) -> Option<EndgameMove> {
+        let profile = self.profile();
+        if !profile.endgame || !endgame::is_endgame(board) {
+            return None;
+        }
+        endgame::endgame_moves(board, player, profile.endgame_depth).into_iter().next().filter(|play| play.priority() > 0.0)
+    }
+
+    /// Whether `player` is too far behind to play on: the game has
+    /// reached its endgame and the estimated score, with `komi` for White,
+    /// trails by more than the [`ResignRule`] threshold. Beginners and
+    /// variants never resign
+    pub fn should_resign(&self, board: &Board, player: Stone, komi: f32) -> bool {
+        if self.difficulty == AIDifficulty::Beginner
+            || self.variant != Variant::Standard
+            || self.resign.moves == 0
+            || !endgame::is_endgame(board)
+        {
+            return false;
+        }
+        let score = Evaluator::estimate_score(board);
+        let lead = score.black as f32 - score.white as f32 - komi;
+        let behind = if player == Stone::Black { -lead } else { lead };
+        behind > self.resign.threshold * (board.size() * board.size()) as f32
+    }
+
+    /// Whether `player`, to move in `game`, should resign: the position
+    /// now and those before its last [`ResignRule::moves`] - 1 moves all
+    /// pass [`AI::should_resign`]
+    pub fn should_resign_game(&self, game: &Game, player: Stone) -> bool {
+        let needed = self.resign.moves;
+        if needed == 0 {
+            return false;
+        }
+        let earlier = game
+            .history
+            .iter()
+            .rev()
+            .filter(|state| state.current_player == (player == Stone::Black) && state.action != Action::Resign)
+            .take(needed - 1)
+            .map(|state| &state.board);
+        let positions: Vec<&Board> = std::iter::once(&game.board).chain(earlier).collect();
+        positions.len() == needed && positions.iter().all(|board| self.should_resign(board, player, game.komi))
+    }
+
+    /// Beginner move: a sensible move picked by its [`MoveReasons`] at
+    /// the level's temperature, so better moves come up more often
+    fn random_move(&self, position: &Position, player: Stone) -> Option<(usize, usize)> {
+        let (reasons, tried) = self.weigh_moves(position, player, None);
+        let moves: Vec<((usize, usize), i32)> = tried.into_iter().map(|(x, y)| ((x, y), reasons.value(x, y))).collect();
+        self.choose(&moves)
+    }
+
+    /// Greedy move based on evaluation (intermediate level): the move
+    /// with the highest combined value of its [`MoveReasons`], or one
+    /// near it at levels with a temperature
+    fn greedy_move(&self, position: &Position, player: Stone, trace: Option<&mut SearchTrace>) -> Option<(usize, usize)> {
+        let (reasons, tried) = self.weigh_moves(position, player, trace);
+        let moves: Vec<((usize, usize), i32)> = tried.into_iter().map(|(x, y)| ((x, y), reasons.value(x, y))).collect();
+        self.choose(&moves)
+    }
+
+    /// One of `moves` by its value at the level's temperature
+    fn choose(&self, moves: &[((usize, usize), i32)]) -> Option<(usize, usize)> {
+        choose_by_temperature(moves, self.profile().temperature, &mut *self.rng.borrow_mut())
+    }
+
+    /// Alpha-beta move (advanced level): the best moves by their reasons
+    /// are read [`LevelProfile::search_depth`] plies deep, and what the
+    /// search finds replaces their one-ply evaluation gain
+    fn search_move(&self, position: &Position, player: Stone, mut trace: Option<&mut SearchTrace>) -> Option<(usize, usize)> {
+        let board = position.board;
+        let (reasons, tried) = self.weigh_moves(position, player, None);
+        let mut candidates: Vec<(usize, usize)> = tried;
+        candidates.sort_by_key(|&(x, y)| std::cmp::Reverse(reasons.value(x, y)));
+        candidates.truncate(SEARCH_WIDTH);
+
+        let profile = self.profile();
+        let deadline = self.time_budget.map(|budget| Instant::now() + budget.mul_f32(SEARCH_SHARE));
+        let budget = self.limits.nodes(profile.search_nodes);
+        let result = search::alpha_beta_until(board, player, &candidates, profile.search_depth, budget, deadline);
+        let mut moves = Vec::new();
+        for &((x, y), searched) in &result.values {
+            let value = searched + unsearched_value(&reasons, (x, y));
+            if let Some(trace) = trace.as_deref_mut() {
+                trace.enter(player, Some((x, y)));
+                trace.comment(format!("value {} after {} plies", value, result.depth));
+                trace.leave();
             }
+            moves.push(((x, y), value));
         }
-        
-        if valid_moves.is_empty() {
-            return None;
+        self.choose(&moves).or_else(|| candidates.first().copied())
+    }
+
+    /// Monte Carlo move (strongest levels): the sensible moves are
+    /// searched with [`LevelProfile::tree_playouts`] playouts, RAVE telling
+    /// them apart early, and the most visited is played
+    fn tree_move(&self, position: &Position, player: Stone, trace: Option<&mut SearchTrace>) -> Option<(usize, usize)> {
+        let candidates = position.sensible_moves(player);
+        let settings = self.mcts_settings(position.komi);
+        let result = mcts::search_among(position.board, player, &candidates, &settings);
+        if let Some(trace) = trace {
+            trace.comment(format!("{} playouts, {} threads", settings.playouts, result.threads));
+            for &(point, stats) in &result.moves {
+                trace.enter(player, Some(point));
+                trace.comment(format!(
+                    "{} visits, win rate {:.2}, RAVE {:.2} over {}",
+                    stats.visits,
+                    stats.win_rate(),
+                    stats.rave_rate(),
+                    stats.rave_visits
+                ));
+                trace.leave();
+            }
         }
-        
-        // Randomly select a move
-        let mut rng = thread_rng();
-        Some(*valid_moves.choose(&mut rng).unwrap())
+        result.best()
+    }
+
+    /// Settings of this level's tree search with `komi`: its playouts
+    /// within the node limit, on as many threads as the [`ResourceLimits`]
+    /// allow
+    pub fn mcts_settings(&self, komi: f32) -> MctsSettings {
+        MctsSettings {
+            playouts: self.limits.nodes(self.profile().tree_playouts),
+            komi,
+            seed: Some(self.rng.borrow_mut().gen()),
+            threads: self.limits.threads(),
+            ..MctsSettings::default()
+        }
+    }
+
+    /// The best `n` moves for `player`, best first, each searched at least
+    /// [`TOP_MOVES_DEPTH`] plies for its value and line of play
+    pub fn top_moves(&self, board: &Board, player: Stone, n: usize) -> Vec<TopMove> {
+        let (reasons, mut candidates) = self.weigh_moves(&Position::of_board(board, &self.excluded), player, None);
+        candidates.sort_by_key(|&(x, y)| (std::cmp::Reverse(reasons.value(x, y)), y, x));
+        candidates.truncate(n.max(SEARCH_WIDTH));
+
+        let profile = self.profile();
+        let depth = profile.search_depth.max(TOP_MOVES_DEPTH);
+        let result = search::exact_values(board, player, &candidates, depth, self.limits.nodes(profile.search_nodes));
+        let lead = Evaluator::evaluate_position(board) * if player == Stone::Black { 1 } else { -1 };
+        let winrate = |gain: i32| 1.0 / (1.0 + (-((lead + gain) as f32) / WINRATE_SCALE).exp());
+        let mut top: Vec<TopMove> = if result.values.is_empty() {
+            // Not even one ply finished: rank by the reasons alone
+            candidates
+                .iter()
+                .map(|&(x, y)| {
+                    let gain = reasons.value(x, y);
+                    TopMove { point: (x, y), value: gain, winrate: winrate(gain), pv: vec![(x, y)] }
+                })
+                .collect()
+        } else {
+            result
+                .values
+                .iter()
+                .zip(result.variations)
+                .map(|(&(point, searched), pv)| TopMove {
+                    point,
+                    value: searched + unsearched_value(&reasons, point),
+                    winrate: winrate(searched),
+                    pv,
+                })
+                .collect()
+        };
+        top.sort_by_key(|m| (std::cmp::Reverse(m.value), m.point.1, m.point.0));
+        top.truncate(n);
+        top
     }
 
-    /// Greedy move based on evaluation (intermediate level)
-    fn greedy_move(&self, board: &Board, player: Stone) -> Option<(usize, usize)> {
+    /// Why each move is worth playing for `player`, as the standard
+    /// variant's greedy search weighs it
+    pub fn move_reasons(&self, board: &Board, player: Stone) -> MoveReasons {
+        self.weigh_moves(&Position::of_board(board, &self.excluded), player, None).0
+    }
+
+    /// Collects the reasons for every move, adding what each sensible move
+    /// of `position` (see [`MoveGenerator::generate_sensible_moves`]) tried
+    /// within the node budget gains by evaluation and by style; also
+    /// returns the moves tried, in order
+    fn weigh_moves(
+        &self,
+        position: &Position,
+        player: Stone,
+        mut trace: Option<&mut SearchTrace>,
+    ) -> (MoveReasons, Vec<(usize, usize)>) {
+        let board = position.board;
         let size = board.size();
-        let mut valid_moves = Vec::new();
+        let mut valid_moves = position.sensible_moves(player);
         
-        // Find all empty positions
-        for y in 0..size {
-            for x in 0..size {
-                if board.get_stone(x, y) == Stone::Empty {
-                    valid_moves.push((x, y));
-                }
-            }
+        let mut reasons = MoveReasons::collect_with(board, player, self.profile.dragons);
+        let mut tried = Vec::new();
+        let budget = self.limits.nodes(usize::MAX);
+        if budget < valid_moves.len() {
+            // Not every point can be read: start from the center
+            let center = size + 1;
+            valid_moves.sort_by_key(|&(x, y)| (2 * x).abs_diff(center) + (2 * y).abs_diff(center));
         }
         
-        if valid_moves.is_empty() {
-            return None;
+        // For black, higher is better; for white, lower is better
+        let sign = if player == Stone::Black { 1 } else { -1 };
+        let before = sign * Evaluator::evaluate_position(board);
+
+        // One scratch board; each candidate is tried and taken back
+        let mut test_board = board.clone();
+        if position.rules.ko_rule == KoRule::None {
+            test_board.clear_ko_point();
         }
-        
-        // Find the move with best evaluation
-        let mut best_move = valid_moves[0];
-        let mut best_score = i32::MIN;
-        
         for (x, y) in valid_moves {
-            // Create a temporary board to test the move
-            let mut test_board = board.clone();
-            
-            // Try to place the stone
-            if test_board.place_stone(x, y, player).is_ok() {
-                let score = Evaluator::evaluate_position(&test_board);
-                
-                // For black, higher is better; for white, lower is better
-                let adjusted_score = if player == Stone::Black { score } else { -score };
-                
-                if adjusted_score > best_score {
-                    best_score = adjusted_score;
-                    best_move = (x, y);
+            if tried.len() >= budget {
+                break;
+            }
+            if test_board.try_move(x, y, player) {
+                let score = sign * Evaluator::evaluate_position(&test_board);
+                test_board.pop_move();
+                tried.push((x, y));
+                reasons.add((x, y), Reason::Territory, score - before);
+                let style = self.style.bonus(board, x, y, player);
+                if style != 0 {
+                    reasons.add((x, y), Reason::Style, style);
+                }
+                if let Some(trace) = trace.as_deref_mut() {
+                    let points = Evaluator::move_value(board, (x, y), player).unwrap_or(0.0);
+                    trace.enter(player, Some((x, y)));
+                    trace.comment(format!("value {} ({:.1} points)", reasons.value(x, y), points));
+                    trace.leave();
                 }
             }
         }
         
-        Some(best_move)
+        (reasons, tried)
+    }
+}
+
+impl AI {
+    /// Capture Go move: take stones, never leave a group in atari, and
+    /// threaten the opponent's weakest groups
+    fn capture_move(&self, position: &Position, player: Stone, mut trace: Option<&mut SearchTrace>) -> Option<(usize, usize)> {
+        let board = position.board;
+        let opponent = if player == Stone::Black { Stone::White } else { Stone::Black };
+        let budget = self.limits.nodes(usize::MAX);
+        let mut best: Option<((usize, usize), i32)> = None;
+
+        for (x, y) in position.sensible_moves(player).into_iter().take(budget) {
+            let mut after = board.clone();
+            let Ok(placement) = after.place_stone_detailed(x, y, player) else {
+                continue;
+            };
+            let captured = placement.captured.len();
+            let (_, own_in_atari) = stones_in_atari(&after, player);
+            let (threats, _) = stones_in_atari(&after, opponent);
+            let liberties = after.count_liberties(x, y);
+            let score = 1000 * captured as i32 - 200 * own_in_atari as i32 + 30 * threats as i32 + 2 * liberties as i32;
+            if let Some(trace) = trace.as_deref_mut() {
+                trace.enter(player, Some((x, y)));
+                trace.comment(format!("value {}", score));
+                trace.leave();
+            }
+            if best.is_none_or(|(_, value)| score > value) {
+                best = Some(((x, y), score));
+            }
+        }
+        best.map(|(point, _)| point)
+    }
+}
+
+/// One of `moves` with its value: at `temperature` 0 the first of the
+/// best, otherwise any, with odds in proportion to
+/// exp(value / `temperature`)
+pub fn choose_by_temperature<R: Rng>(moves: &[((usize, usize), i32)], temperature: f32, rng: &mut R) -> Option<(usize, usize)> {
+    let best = moves.iter().fold(None, |best: Option<((usize, usize), i32)>, &(point, value)| {
+        if best.is_none_or(|(_, b)| value > b) { Some((point, value)) } else { best }
+    })?;
+    if temperature <= 0.0 {
+        return Some(best.0);
+    }
+    // Measured from the best, so no weight overflows
+    let weights = moves.iter().map(|&(_, value)| ((value - best.1) as f32 / temperature).exp());
+    match WeightedIndex::new(weights) {
+        Ok(index) => Some(moves[index.sample(rng)].0),
+        Err(_) => Some(best.0),
+    }
+}
+
+/// What the reasons for `point` add besides the territory the search
+/// measures itself
+fn unsearched_value(reasons: &MoveReasons, (x, y): (usize, usize)) -> i32 {
+    reasons.reasons(x, y).iter().filter(|r| r.reason != Reason::Territory).map(|r| r.value).sum()
+}
+
+/// Groups of `color` with a single liberty, and the stones in them
+fn stones_in_atari(board: &Board, color: Stone) -> (usize, usize) {
+    let size = board.size();
+    let mut seen = vec![false; size * size];
+    let (mut groups, mut stones) = (0, 0);
+    for y in 1..=size {
+        for x in 1..=size {
+            if seen[(y - 1) * size + x - 1] || board.get_stone(x, y) != color {
+                continue;
+            }
+            if let Some(group) = board.find_group(x, y) {
+                for &(gx, gy) in group.positions() {
+                    seen[(gy - 1) * size + gx - 1] = true;
+                }
+                if group.liberties() == 1 {
+                    groups += 1;
+                    stones += group.positions().len();
+                }
+            }
+        }
     }
+    (groups, stones)
 }
 
 /// Get a random valid move (standalone function for simple AI)
@@ -0,0 +1,308 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Position analysis helpers for external frontends
+//!
+//! [`analyze`] collects everything into an [`Analysis`], which converts to
+//! JSON with vertices in GTP notation so other tools need not parse GTP.
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::engine::ai::LevelProfile;
+use crate::engine::board::{Board, Stone};
+use crate::engine::breakin::breakable_points;
+use crate::engine::profile::{self, Subsystem};
+use crate::engine::evaluation::Evaluator;
+use crate::engine::fast_board::FastBoard;
+use crate::engine::game::Game;
+use crate::engine::influence::{InfluenceMap, STONE_STRENGTH};
+use crate::engine::rules::ScoringRule;
+use crate::engine::scoring::{dead_stones, final_score, format_result, remove_stones, territory_map};
+use crate::engine::tsumego::LifeStatus;
+use crate::gtp::format_move;
+use crate::json::JsonValue;
+
+/// Number of candidate moves included by [`Game::analysis_json`]
+pub const DEFAULT_CANDIDATES: usize = 5;
+
+/// A candidate move with its evaluation from the mover's point of view
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CandidateMove {
+    pub point: (usize, usize),
+    pub value: i32,
+}
+
+impl CandidateMove {
+    /// `{move, value}`
+    pub fn to_json(&self, size: usize) -> JsonValue {
+        JsonValue::object(vec![
+            ("move", format_move(self.point.0, self.point.1, size).into()),
+            ("value", self.value.into()),
+        ])
+    }
+}
+
+/// A connected string of stones and its estimated status
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dragon {
+    pub color: Stone,
+    pub stones: Vec<(usize, usize)>,
+    pub liberties: usize,
+    pub status: LifeStatus,
+}
+
+impl Dragon {
+    /// `{color, status, liberties, stones}`
+    pub fn to_json(&self, size: usize) -> JsonValue {
+        JsonValue::object(vec![
+            ("color", color_name(self.color).into()),
+            ("status", self.status.name().into()),
+            ("liberties", self.liberties.into()),
+            ("stones", vertices(&self.stones, size)),
+        ])
+    }
+}
+
+/// Area score with dead stones removed
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoreEstimate {
+    pub black: usize,
+    pub white: usize,
+    pub komi: f32,
+    /// Black minus White minus komi
+    pub margin: f32,
+    pub dead: Vec<(usize, usize)>,
+}
+
+impl ScoreEstimate {
+    pub fn new(game: &Game) -> Self {
+        let dead = dead_stones(&game.board);
+        let (black, white) = final_score(&game.board, ScoringRule::Area, &dead);
+        ScoreEstimate {
+            black,
+            white,
+            komi: game.komi,
+            margin: black as f32 - white as f32 - game.komi,
+            dead,
+        }
+    }
+
+    /// `{black, white, komi, result, dead}`
+    pub fn to_json(&self, size: usize) -> JsonValue {
+        JsonValue::object(vec![
+            ("black", self.black.into()),
+            ("white", self.white.into()),
+            ("komi", self.komi.into()),
+            ("result", format_result(self.margin).into()),
+            ("dead", vertices(&self.dead, size)),
+        ])
+    }
+}
+
+/// Full analysis of a position
+#[derive(Debug, Clone)]
+pub struct Analysis {
+    pub size: usize,
+    pub to_move: Stone,
+    pub candidates: Vec<CandidateMove>,
+    pub ownership: Vec<f32>,
+    pub dragons: Vec<Dragon>,
+    pub score: ScoreEstimate,
+}
+
+impl Analysis {
+    pub fn to_json(&self) -> JsonValue {
+        JsonValue::object(vec![
+            ("size", self.size.into()),
+            ("to_move", color_name(self.to_move).into()),
+            (
+                "moves",
+                JsonValue::Array(self.candidates.iter().map(|c| c.to_json(self.size)).collect()),
+            ),
+            ("ownership", self.ownership.clone().into()),
+            (
+                "dragons",
+                JsonValue::Array(self.dragons.iter().map(|d| d.to_json(self.size)).collect()),
+            ),
+            ("score", self.score.to_json(self.size)),
+        ])
+    }
+}
+
+/// Analyzes the position with the best `count` moves for the side to move
+pub fn analyze(game: &Game, count: usize) -> Analysis {
+    let to_move = game.current_player();
+    let score = ScoreEstimate::new(game);
+    Analysis {
+        size: game.board.size(),
+        to_move,
+        candidates: candidate_moves(&game.board, to_move, count),
+        ownership: ownership(&game.board, &OwnershipSettings { to_move, ..Default::default() }),
+        dragons: dragons(&game.board, &score.dead),
+        score,
+    }
+}
+
+/// Every string on the board, top-left first; strings in `dead` are dead
+pub fn dragons(board: &Board, dead: &[(usize, usize)]) -> Vec<Dragon> {
+    let size = board.size();
+    let mut seen = vec![false; size * size];
+    let mut dragons = Vec::new();
+
+    for y in 1..=size {
+        for x in 1..=size {
+            if seen[(y - 1) * size + (x - 1)] {
+                continue;
+            }
+            let Some(group) = board.find_group(x, y) else { continue };
+            let mut stones = group.positions().to_vec();
+            stones.sort_by_key(|&(x, y)| (y, x));
+            for &(sx, sy) in &stones {
+                seen[(sy - 1) * size + (sx - 1)] = true;
+            }
+            let status = if dead.contains(&(x, y)) { LifeStatus::Dead } else { LifeStatus::Alive };
+            dragons.push(Dragon { color: group.color(), stones, liberties: group.liberties(), status });
+        }
+    }
+
+    dragons
+}
+
+fn color_name(stone: Stone) -> &'static str {
+    match stone {
+        Stone::Black => "black",
+        Stone::White => "white",
+        Stone::Empty => "empty",
+    }
+}
+
+fn vertices(points: &[(usize, usize)], size: usize) -> JsonValue {
+    points.iter().map(|&(x, y)| format_move(x, y, size)).collect::<Vec<_>>().into()
+}
+
+/// Evaluates every legal move for `color` and returns the best `count`
+pub fn candidate_moves(board: &Board, color: Stone, count: usize) -> Vec<CandidateMove> {
+    let size = board.size();
+    let mut candidates = Vec::new();
+    let mut test_board = board.clone();
+
+    for y in 1..=size {
+        for x in 1..=size {
+            if board.get_stone(x, y) != Stone::Empty {
+                continue;
+            }
+            if test_board.try_move(x, y, color) {
+                let score = Evaluator::evaluate_position(&test_board);
+                test_board.pop_move();
+                let value = if color == Stone::Black { score } else { -score };
+                candidates.push(CandidateMove { point: (x, y), value });
+            }
+        }
+    }
+
+    candidates.sort_by_key(|c| std::cmp::Reverse(c.value));
+    candidates.truncate(count);
+    candidates
+}
+
+/// How [`ownership`] estimates the owner of each point
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OwnershipMethod {
+    /// Settled territory after dead stone removal; unsettled points are
+    /// graded by the influence of nearby stones. Fast and deterministic.
+    Influence,
+    /// Average final owner over random playouts
+    Playouts,
+}
+
+/// Options for [`ownership`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OwnershipSettings {
+    pub method: OwnershipMethod,
+    /// Number of playouts for [`OwnershipMethod::Playouts`]
+    pub playouts: usize,
+    /// Side to move first in playouts
+    pub to_move: Stone,
+    /// Seed for reproducible playouts; `None` uses a random seed
+    pub seed: Option<u64>,
+}
+
+impl Default for OwnershipSettings {
+    fn default() -> Self {
+        OwnershipSettings {
+            method: OwnershipMethod::Influence,
+            playouts: LevelProfile::default().playouts,
+            to_move: Stone::Black,
+            seed: None,
+        }
+    }
+}
+
+/// Expected owner of every intersection, row by row from the top-left,
+/// from 1.0 (black) through 0.0 (neutral) to -1.0 (white). Dead stones
+/// are credited to the capturing side.
+///
+/// The result always has `size * size` entries; frontends can draw it
+/// directly as a heatmap.
+pub fn ownership(board: &Board, settings: &OwnershipSettings) -> Vec<f32> {
+    match settings.method {
+        OwnershipMethod::Influence => profile::measure(Subsystem::Influence, || influence_ownership(board)),
+        OwnershipMethod::Playouts => profile::measure(Subsystem::Playouts, || playout_ownership(board, settings)),
+    }
+}
+
+fn influence_ownership(board: &Board) -> Vec<f32> {
+    let size = board.size();
+    let cleaned = remove_stones(board, &dead_stones(board));
+    let mut territory = territory_map(&cleaned);
+    // Territory the opponent can break into only counts by influence
+    for (x, y) in breakable_points(&cleaned) {
+        territory[y - 1][x - 1] = Stone::Empty;
+    }
+    let balance = InfluenceMap::compute(&cleaned).balance();
+    let mut owner = vec![0.0; size * size];
+
+    for y in 1..=size {
+        for x in 1..=size {
+            owner[(y - 1) * size + (x - 1)] = match (cleaned.get_stone(x, y), territory[y - 1][x - 1]) {
+                (Stone::Black, _) | (Stone::Empty, Stone::Black) => 1.0,
+                (Stone::White, _) | (Stone::Empty, Stone::White) => -1.0,
+                (Stone::Empty, Stone::Empty) => {
+                    // In units of one stone's own influence, squashed into (-1, 1)
+                    let influence = balance[(y - 1) * size + (x - 1)] / STONE_STRENGTH;
+                    influence / (1.0 + influence.abs())
+                }
+            };
+        }
+    }
+
+    owner
+}
+
+fn playout_ownership(board: &Board, settings: &OwnershipSettings) -> Vec<f32> {
+    let size = board.size();
+    let mut rng = match settings.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let playouts = settings.playouts.max(1);
+    let mut totals = vec![0.0; size * size];
+
+    let start = FastBoard::from_board(board);
+    for _ in 0..playouts {
+        let mut end = start.clone();
+        end.playout(settings.to_move, size * size * 3, &mut rng);
+        for y in 1..=size {
+            for x in 1..=size {
+                totals[(y - 1) * size + (x - 1)] += match end.owner(x, y) {
+                    Stone::Black => 1.0,
+                    Stone::White => -1.0,
+                    Stone::Empty => 0.0,
+                };
+            }
+        }
+    }
+
+    totals.iter().map(|total| total / playouts as f32).collect()
+}
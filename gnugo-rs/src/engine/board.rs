@@ -3,8 +3,10 @@
 
 use std::fmt;
 
+use crate::engine::rules::{GameRules, KoRule};
+
 /// Represents a stone on the board
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Stone {
     Empty,
     Black,
@@ -18,23 +20,119 @@ pub struct StoneGroup {
     liberties: usize,
 }
 
+impl StoneGroup {
+    /// Color of the group
+    pub fn color(&self) -> Stone {
+        self.color
+    }
+
+    /// Stones of the group, 1-based
+    pub fn positions(&self) -> &[(usize, usize)] {
+        &self.positions
+    }
+
+    /// Number of liberties of the group
+    pub fn liberties(&self) -> usize {
+        self.liberties
+    }
+}
+
 /// Represents the Go board
+///
+/// Points are stored row by row in one vector with a one-point border
+/// around the board, so each point is a single index and its neighbors sit
+/// at fixed offsets (see [`Board::point`] and [`Board::adjacent`]). Border
+/// cells stay empty.
 #[derive(Debug, Clone)]
 pub struct Board {
-    grid: Vec<Vec<Stone>>,
+    cells: Vec<Stone>,
     size: usize,
     captured: [usize; 2], // [black, white]
     ko_point: Option<(usize, usize)>, // Ko threat position (if any)
+    /// Zobrist hash of the stones, updated with every change
+    hash: u64,
+    /// Moves made by `try_move`, newest last
+    undo_stack: Vec<UndoRecord>,
+    /// Move number each stone was played at, by index; 0 for stones set up
+    /// with `set_stone`. Stale on empty points, so captures and `pop_move`
+    /// need not touch it
+    ages: Vec<u32>,
+    /// Moves played so far, passes counted with [`Board::count_pass`]
+    move_number: u32,
+}
+
+/// What a stone placed with [`Board::place_stone_detailed`] did
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Placement {
+    /// Points of the stones removed, 1-based
+    pub captured: Vec<(usize, usize)>,
+    /// Stones removed of each color, [black, white]
+    pub captured_by_color: [usize; 2],
+    /// Point the opponent may not retake at once, if the move made a ko
+    pub ko_point: Option<(usize, usize)>,
+}
+
+impl Placement {
+    /// Whether the move created a ko
+    pub fn created_ko(&self) -> bool {
+        self.ko_point.is_some()
+    }
+}
+
+/// Why [`Board::is_legal`] rejected a move
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IllegalMove {
+    OutOfBounds,
+    /// The color to play was `Stone::Empty`
+    NoColor,
+    Occupied,
+    /// Retaking the simple-ko point at once
+    Ko,
+    Suicide,
+}
+
+impl IllegalMove {
+    /// The message `Board::place_stone` gives for the same move
+    pub fn message(&self) -> &'static str {
+        match self {
+            IllegalMove::OutOfBounds => "Position out of bounds",
+            IllegalMove::NoColor => "Cannot place empty stone",
+            IllegalMove::Occupied => "Position already occupied",
+            IllegalMove::Ko => "Ko threat violation",
+            IllegalMove::Suicide => "Suicide move not allowed",
+        }
+    }
+}
+
+impl fmt::Display for IllegalMove {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+/// A move made with [`Board::try_move`], kept until [`Board::pop_move`]
+#[derive(Debug, Clone)]
+struct UndoRecord {
+    pos: usize,
+    /// Opponent stones the move removed
+    captured_stones: Vec<usize>,
+    captured: [usize; 2],
+    ko_point: Option<(usize, usize)>,
+    hash: u64,
 }
 
 impl Board {
     /// Creates a new empty board of given size
     pub fn new(size: usize) -> Self {
         Board {
-            grid: vec![vec![Stone::Empty; size]; size],
+            cells: vec![Stone::Empty; (size + 2) * (size + 2)],
             size,
             captured: [0, 0],
             ko_point: None,
+            hash: size as u64,
+            undo_stack: Vec::new(),
+            ages: vec![0; (size + 2) * (size + 2)],
+            move_number: 0,
         }
     }
 
@@ -43,13 +141,50 @@ impl Board {
         self.size
     }
 
+    /// Distance between the indices of vertically adjacent points
+    pub fn stride(&self) -> usize {
+        self.size + 2
+    }
+
+    /// Index of (x, y); x, y are 1-based coordinates (1..=size)
+    pub fn point(&self, x: usize, y: usize) -> usize {
+        y * self.stride() + x
+    }
+
+    /// Coordinates (x, y) of index `pos`
+    pub fn coords(&self, pos: usize) -> (usize, usize) {
+        (pos % self.stride(), pos / self.stride())
+    }
+
+    /// Whether `pos` is a point of the board rather than of the border
+    pub fn is_on_board(&self, pos: usize) -> bool {
+        let (x, y) = self.coords(pos);
+        (1..=self.size).contains(&x) && (1..=self.size).contains(&y)
+    }
+
+    /// Indices of all points, row by row from the top-left
+    pub fn points(&self) -> impl Iterator<Item = usize> + '_ {
+        (1..=self.size).flat_map(move |y| (1..=self.size).map(move |x| self.point(x, y)))
+    }
+
+    /// Indices of the on-board orthogonal neighbors of `pos`
+    pub fn adjacent(&self, pos: usize) -> impl Iterator<Item = usize> + '_ {
+        let stride = self.stride();
+        [pos - 1, pos + 1, pos - stride, pos + stride].into_iter().filter(move |&n| self.is_on_board(n))
+    }
+
+    /// Stone at index `pos`; border cells are empty
+    pub fn stone_at(&self, pos: usize) -> Stone {
+        self.cells[pos]
+    }
+
     /// Gets the stone at a specific position (x, y)
     /// x, y are 1-based coordinates (1..=size)
     pub fn get_stone(&self, x: usize, y: usize) -> Stone {
         if x == 0 || y == 0 || x > self.size || y > self.size {
             return Stone::Empty;
         }
-        self.grid[y - 1][x - 1]
+        self.cells[self.point(x, y)]
     }
 
     /// Gets the number of captured stones
@@ -78,173 +213,350 @@ impl Board {
         if x == 0 || y == 0 || x > self.size || y > self.size {
             return;
         }
-        self.grid[y - 1][x - 1] = stone;
+        let pos = self.point(x, y);
+        self.set_cell(pos, stone);
+        self.ages[pos] = 0;
+    }
+
+    /// Board from a diagram with one line per row, top row first: `X` for
+    /// Black, `O` for White, `.` or `+` for an empty point. Spaces and
+    /// blank lines are ignored; the rows must make a square.
+    pub fn from_ascii(diagram: &str) -> Result<Board, String> {
+        let rows: Vec<Vec<char>> = diagram
+            .lines()
+            .map(|line| line.chars().filter(|c| !c.is_whitespace()).collect::<Vec<_>>())
+            .filter(|row| !row.is_empty())
+            .collect();
+        let size = rows.len();
+        if size == 0 {
+            return Err("empty diagram".to_string());
+        }
+        let mut board = Board::new(size);
+        for (y, row) in rows.iter().enumerate() {
+            if row.len() != size {
+                return Err(format!("row {} has {} points, expected {}", y + 1, row.len(), size));
+            }
+            for (x, &c) in row.iter().enumerate() {
+                let stone = match c {
+                    'X' | 'x' => Stone::Black,
+                    'O' | 'o' => Stone::White,
+                    '.' | '+' => Stone::Empty,
+                    _ => return Err(format!("unexpected '{}' in row {}", c, y + 1)),
+                };
+                board.set_stone(x + 1, y + 1, stone);
+            }
+        }
+        Ok(board)
+    }
+
+    /// The stones as a diagram [`Board::from_ascii`] reads back, without
+    /// a trailing newline
+    pub fn to_ascii(&self) -> String {
+        (1..=self.size)
+            .map(|y| {
+                (1..=self.size)
+                    .map(|x| match self.get_stone(x, y) {
+                        Stone::Black => 'X',
+                        Stone::White => 'O',
+                        Stone::Empty => '.',
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Changes one cell, keeping the hash up to date
+    fn set_cell(&mut self, pos: usize, stone: Stone) {
+        self.hash ^= self.zobrist_key(pos, self.cells[pos]) ^ self.zobrist_key(pos, stone);
+        self.cells[pos] = stone;
+    }
+
+    /// Zobrist key of `stone` at `pos`; 0 for an empty point
+    fn zobrist_key(&self, pos: usize, stone: Stone) -> u64 {
+        let color = match stone {
+            Stone::Empty => return 0,
+            Stone::Black => 1,
+            Stone::White => 2,
+        };
+        let (x, y) = self.coords(pos);
+        let i = ((y - 1) * self.size + x - 1) as u64;
+        // splitmix64 of (point, color) stands in for a random key table
+        let mut key = (i * 2 + color).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        key = (key ^ (key >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        key = (key ^ (key >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        key ^ (key >> 31)
     }
 
     /// Finds a group of connected stones at position (x, y) (public for testing)
+    /// x, y are 1-based coordinates (1..=size)
     pub fn find_group(&self, x: usize, y: usize) -> Option<StoneGroup> {
-        if x >= self.size || y >= self.size || self.grid[y][x] == Stone::Empty {
+        let color = self.get_stone(x, y);
+        if color == Stone::Empty {
             return None;
         }
-        
-        let color = self.grid[y][x];
-        let mut visited = vec![vec![false; self.size]; self.size];
-        let mut positions = Vec::new();
-        let mut queue = vec![(x, y)];
-        visited[y][x] = true;
-        
-        while let Some((cx, cy)) = queue.pop() {
-            positions.push((cx, cy));
-            
-            // Check 4 neighbors
-            let neighbors = [(0, -1), (0, 1), (-1, 0), (1, 0)];
-            for &(dx, dy) in &neighbors {
-                let nx = cx as isize + dx;
-                let ny = cy as isize + dy;
-                
-                if nx >= 0 && nx < self.size as isize && ny >= 0 && ny < self.size as isize {
-                    let nx = nx as usize;
-                    let ny = ny as usize;
-                    
-                    if !visited[ny][nx] && self.grid[ny][nx] == color {
-                        visited[ny][nx] = true;
-                        queue.push((nx, ny));
-                    }
-                }
-            }
-        }
-        
-        // Count liberties
-        let liberties = self.count_liberties_for_positions(&positions);
-        
+        let (stones, liberties) = self.group_at(self.point(x, y));
         Some(StoneGroup {
             color,
-            positions,
-            liberties,
+            positions: stones.into_iter().map(|pos| self.coords(pos)).collect(),
+            liberties: liberties.len(),
         })
     }
 
+    /// Stones of the group at `pos` and its distinct liberties
+    fn group_at(&self, pos: usize) -> (Vec<usize>, Vec<usize>) {
+        let color = self.cells[pos];
+        let mut visited = vec![false; self.cells.len()];
+        let mut stones = Vec::new();
+        let mut liberties = Vec::new();
+        let mut queue = vec![pos];
+        visited[pos] = true;
+
+        while let Some(current) = queue.pop() {
+            stones.push(current);
+            for n in self.adjacent(current) {
+                if visited[n] {
+                    continue;
+                }
+                if self.cells[n] == color {
+                    visited[n] = true;
+                    queue.push(n);
+                } else if self.cells[n] == Stone::Empty {
+                    visited[n] = true;
+                    liberties.push(n);
+                }
+            }
+        }
+
+        (stones, liberties)
+    }
+
+    /// Returns the on-board orthogonal neighbors of (x, y), 1-based
+    pub fn neighbors(&self, x: usize, y: usize) -> Vec<(usize, usize)> {
+        self.adjacent(self.point(x, y)).map(|n| self.coords(n)).collect()
+    }
+
     /// Places a stone on the board and handles captures
     pub fn place_stone(&mut self, x: usize, y: usize, stone: Stone) -> Result<(), &'static str> {
-                // Convert 1-based to 0-based for internal storage
-        let ix = x - 1;
-        let iy = y - 1;
-        
+        self.play_at(x, y, stone, &GameRules::default()).map(|_| ())
+    }
+
+    /// Places a stone as `rules` allow, accepting exactly the moves
+    /// [`Board::is_legal`] does
+    pub fn place_stone_under(&mut self, x: usize, y: usize, stone: Stone, rules: &GameRules) -> Result<(), &'static str> {
+        self.play_at(x, y, stone, rules).map(|_| ())
+    }
+
+    /// Whether `color` may play (x, y) under `rules`, without touching the
+    /// board. Superko needs the game's history and is left to
+    /// [`GameRules::play`].
+    pub fn is_legal(&self, x: usize, y: usize, color: Stone, rules: &GameRules) -> Result<(), IllegalMove> {
         if x == 0 || y == 0 || x > self.size || y > self.size {
-            return Err("Position out of bounds");
+            return Err(IllegalMove::OutOfBounds);
         }
-        
-        if self.grid[iy][ix] != Stone::Empty {
-            return Err("Position already occupied");
+        if color == Stone::Empty {
+            return Err(IllegalMove::NoColor);
         }
-        
-        // Check ko rule
-        if let Some((ko_x, ko_y)) = self.ko_point {
-            if x == ko_x && y == ko_y {
-                return Err("Ko threat violation");
+        let pos = self.point(x, y);
+        if self.cells[pos] != Stone::Empty {
+            return Err(IllegalMove::Occupied);
+        }
+        if rules.ko_rule != KoRule::None && self.ko_point == Some((x, y)) {
+            return Err(IllegalMove::Ko);
+        }
+        // Legal if the stone keeps a liberty, joins a group that keeps
+        // another one, or takes the last liberty of an enemy group
+        let breathes = self.adjacent(pos).any(|n| match self.cells[n] {
+            Stone::Empty => true,
+            stone => {
+                let (_, liberties) = self.group_at(n);
+                (stone == color) == (liberties.len() > 1)
             }
+        });
+        // Suicide, where allowed, must take more than the new stone
+        let joins = self.adjacent(pos).any(|n| self.cells[n] == color);
+        if breathes || (rules.allow_suicide && joins) { Ok(()) } else { Err(IllegalMove::Suicide) }
+    }
+
+    /// Like [`Board::place_stone`], but a move that leaves its own group
+    /// of two or more stones without liberties removes that group instead
+    /// of being refused. A lone stone's suicide is still refused
+    pub fn place_stone_allowing_suicide(&mut self, x: usize, y: usize, stone: Stone) -> Result<(), &'static str> {
+        self.play_at(x, y, stone, &GameRules { allow_suicide: true, ..GameRules::default() }).map(|_| ())
+    }
+
+    /// Like [`Board::place_stone`], also reporting the captures and ko the
+    /// move made
+    pub fn place_stone_detailed(&mut self, x: usize, y: usize, stone: Stone) -> Result<Placement, &'static str> {
+        let captured = self.play_at(x, y, stone, &GameRules::default())?;
+        let mut captured_by_color = [0, 0];
+        match stone {
+            Stone::Black => captured_by_color[1] = captured.len(),
+            _ => captured_by_color[0] = captured.len(),
         }
-        
-        // Place the stone
-        self.grid[iy][ix] = stone;
-        
+        Ok(Placement {
+            captured: captured.into_iter().map(|pos| self.coords(pos)).collect(),
+            captured_by_color,
+            ko_point: self.ko_point,
+        })
+    }
+
+    /// Plays `color` at (x, y) so that [`Board::pop_move`] can take it back
+    /// cheaply; returns false, changing nothing, for an illegal move
+    pub fn try_move(&mut self, x: usize, y: usize, color: Stone) -> bool {
+        let (captured, ko_point, hash) = (self.captured, self.ko_point, self.hash);
+        match self.play_at(x, y, color, &GameRules::default()) {
+            Ok(captured_stones) => {
+                let pos = self.point(x, y);
+                self.undo_stack.push(UndoRecord { pos, captured_stones, captured, ko_point, hash });
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Takes back the latest [`Board::try_move`]; false if there is none
+    pub fn pop_move(&mut self) -> bool {
+        let Some(record) = self.undo_stack.pop() else {
+            return false;
+        };
+        let opponent = match self.cells[record.pos] {
+            Stone::Black => Stone::White,
+            _ => Stone::Black,
+        };
+        self.cells[record.pos] = Stone::Empty;
+        for &pos in &record.captured_stones {
+            self.cells[pos] = opponent;
+        }
+        self.move_number -= 1;
+        self.captured = record.captured;
+        self.ko_point = record.ko_point;
+        self.hash = record.hash;
+        true
+    }
+
+    /// Counts a pass, so that later stones keep the game's move numbers;
+    /// like any other move it lifts the ko ban
+    pub fn count_pass(&mut self) {
+        self.move_number += 1;
+        self.clear_ko_point();
+    }
+
+    /// Moves played so far, passes included
+    pub fn move_number(&self) -> u32 {
+        self.move_number
+    }
+
+    /// Move number the stone at (x, y) was played at; `None` for an empty
+    /// point or a stone set up with [`Board::set_stone`]
+    pub fn stone_age(&self, x: usize, y: usize) -> Option<u32> {
+        if x == 0 || y == 0 || x > self.size || y > self.size {
+            return None;
+        }
+        let pos = self.point(x, y);
+        Some(self.ages[pos]).filter(|&age| age > 0 && self.cells[pos] != Stone::Empty)
+    }
+
+    /// Stones played in the last `n` moves and still on the board, as
+    /// (x, y, move number), newest first
+    pub fn recent_stones(&self, n: u32) -> Vec<(usize, usize, u32)> {
+        let since = self.move_number.saturating_sub(n);
+        let mut stones: Vec<_> = self
+            .points()
+            .filter(|&pos| self.ages[pos] > since && self.cells[pos] != Stone::Empty)
+            .map(|pos| {
+                let (x, y) = self.coords(pos);
+                (x, y, self.ages[pos])
+            })
+            .collect();
+        stones.sort_by_key(|&(_, _, age)| std::cmp::Reverse(age));
+        stones
+    }
+
+    /// Moves made by [`Board::try_move`] and not yet taken back
+    pub fn stack_depth(&self) -> usize {
+        self.undo_stack.len()
+    }
+
+    /// Places a stone under `rules` and returns the stones it captured
+    fn play_at(&mut self, x: usize, y: usize, stone: Stone, rules: &GameRules) -> Result<Vec<usize>, &'static str> {
+        if x == 0 || y == 0 || x > self.size || y > self.size {
+            return Err("Position out of bounds");
+        }
+
         let opponent = match stone {
             Stone::Black => Stone::White,
             Stone::White => Stone::Black,
             Stone::Empty => return Err("Cannot place empty stone"),
         };
-        
+
+        if self.get_stone(x, y) != Stone::Empty {
+            return Err("Position already occupied");
+        }
+
+        // Check ko rule
+        if rules.ko_rule != KoRule::None && self.ko_point == Some((x, y)) {
+            return Err("Ko threat violation");
+        }
+
+        // Place the stone
+        let pos = self.point(x, y);
+        self.set_cell(pos, stone);
+
         // Check and capture opponent stones in all 4 directions
-        let mut captured_any = false;
-        let mut captured_single_stone = false;
-        let mut capture_position = (0, 0);
-        
-        // Check all 4 directions for captures
-        let directions = [(0, -1), (0, 1), (-1, 0), (1, 0)];
-        for &(dx, dy) in &directions {
-            let nx = x as isize + dx;
-            let ny = y as isize + dy;
-            
-            if nx >= 0 && nx < self.size as isize && ny >= 0 && ny < self.size as isize {
-                let nx = nx as usize;
-                let ny = ny as usize;
-                
-                if self.grid[ny][nx] == opponent {
-                    if let Some(group) = self.find_group(nx, ny) {
-                        if group.liberties == 0 {
-                            // Check if this is a single stone capture (potential ko)
-                            if group.positions.len() == 1 {
-                                captured_single_stone = true;
-                                capture_position = group.positions[0];
-                            }
-                            
-                            self.capture_group(&group);
-                            captured_any = true;
-                        }
-                    }
+        let mut captured_stones = Vec::new();
+
+        for n in self.adjacent(pos).collect::<Vec<_>>() {
+            if self.cells[n] == opponent {
+                let (group, liberties) = self.group_at(n);
+                if liberties.is_empty() {
+                    self.capture_group(&group, opponent);
+                    captured_stones.extend(group);
                 }
             }
         }
-        
-        // Set ko point if exactly one stone was captured
-        if captured_single_stone {
-            self.set_ko_point(capture_position.0, capture_position.1);
-        } else {
-            self.clear_ko_point();
-        }
-        
+
         // If no opponent was captured, check if our own stone has liberties
-        if !captured_any {
-            if let Some(own_group) = self.find_group(x, y) {
-                if own_group.liberties == 0 {
-                    // Suicide - remove our own stone
-                    self.grid[y][x] = Stone::Empty;
-                    return Err("Suicide move not allowed");
-                }
+        let (own_group, own_liberties) = self.group_at(pos);
+        if captured_stones.is_empty() && own_liberties.is_empty() {
+            if rules.allow_suicide && own_group.len() > 1 {
+                // The whole group dies and counts as captured
+                self.capture_group(&own_group, stone);
+                self.clear_ko_point();
+                self.move_number += 1;
+                return Ok(captured_stones);
             }
+            // Suicide - remove our own stone
+            self.set_cell(pos, Stone::Empty);
+            return Err("Suicide move not allowed");
         }
-        
-        Ok(())
-    }
-
-    /// Counts liberties for a group of positions
-    fn count_liberties_for_positions(&self, positions: &[(usize, usize)]) -> usize {
-        let mut liberties = 0;
-        let mut checked = vec![vec![false; self.size]; self.size];
-        
-        for &(x, y) in positions {
-            let neighbors = [(0, -1), (0, 1), (-1, 0), (1, 0)];
-            for &(dx, dy) in &neighbors {
-                let nx = x as isize + dx;
-                let ny = y as isize + dy;
-                
-                if nx >= 0 && nx < self.size as isize && ny >= 0 && ny < self.size as isize {
-                    let nx = nx as usize;
-                    let ny = ny as usize;
-                    
-                    if !checked[ny][nx] && self.grid[ny][nx] == Stone::Empty {
-                        liberties += 1;
-                        checked[ny][nx] = true;
-                    }
-                }
-            }
+
+        self.move_number += 1;
+        self.ages[pos] = self.move_number;
+
+        // A ko arises only when a lone stone captures exactly one stone
+        // and is left with that single liberty
+        let is_ko = captured_stones.len() == 1 && own_group.len() == 1 && own_liberties.len() == 1;
+        if is_ko {
+            let (ko_x, ko_y) = self.coords(captured_stones[0]);
+            self.set_ko_point(ko_x, ko_y);
+        } else {
+            self.clear_ko_point();
         }
-        
-        liberties
+
+        Ok(captured_stones)
     }
 
     /// Captures a group of stones
-    fn capture_group(&mut self, group: &StoneGroup) {
-        let count = group.positions.len();
-        
-        for &(x, y) in &group.positions {
-            self.grid[y][x] = Stone::Empty;
+    fn capture_group(&mut self, stones: &[usize], color: Stone) {
+        for &pos in stones {
+            self.set_cell(pos, Stone::Empty);
         }
-        
+
         // Update captured count
-        match group.color {
-            Stone::Black => self.captured[0] += count,
-            Stone::White => self.captured[1] += count,
+        match color {
+            Stone::Black => self.captured[0] += stones.len(),
+            Stone::White => self.captured[1] += stones.len(),
             Stone::Empty => {}
         }
     }
@@ -272,7 +584,7 @@ impl Board {
         // For larger boards, calculate hoshi positions
         // 3-3 points for sizes 7-11, 4-4 for larger
         let hoshi = if self.size <= 11 { 3 } else { 4 }; // 1-based: 3 or 4
-        let middle = (self.size + 1) / 2; // 1-based middle (e.g., 10 for 19x19)
+        let middle = self.size.div_ceil(2); // 1-based middle (e.g., 10 for 19x19)
 
         // Normalize coordinates by mirroring to lower numbers
         let m = if x > middle { self.size + 1 - x } else { x };
@@ -284,7 +596,7 @@ impl Board {
         }
 
         // Even sized boards only have corner hoshi
-        if self.size % 2 == 0 {
+        if self.size.is_multiple_of(2) {
             return false;
         }
 
@@ -305,60 +617,42 @@ impl Board {
 
     /// Counts the number of stones of a specific color on the board
     pub fn stones_on_board(&self, color: Stone) -> usize {
-        let mut count = 0;
-        for row in &self.grid {
-            for &stone in row {
-                if stone == color {
-                    count += 1;
-                }
-            }
+        self.points().filter(|&pos| self.cells[pos] == color).count()
+    }
+
+    /// Zobrist hash of the stones on the board, for superko checks; ko
+    /// point and captures are not part of the position
+    pub fn position_hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// The [`Board::position_hash`] of the board with every stone moved
+    /// by `map`, a symmetry of the board taking each 1-based (x, y) to
+    /// another point
+    pub fn mapped_hash(&self, map: impl Fn(usize, usize) -> (usize, usize)) -> u64 {
+        let mut hash = self.size as u64;
+        for pos in self.points().filter(|&pos| self.cells[pos] != Stone::Empty) {
+            let (x, y) = self.coords(pos);
+            let (mx, my) = map(x, y);
+            hash ^= self.zobrist_key(self.point(mx, my), self.cells[pos]);
         }
-        count
+        hash
     }
 
     /// Counts liberties for a single stone or group at (x,y)
+    /// x, y are 1-based coordinates (1..=size)
     pub fn count_liberties(&self, x: usize, y: usize) -> usize {
-        if x >= self.size || y >= self.size || self.grid[y][x] == Stone::Empty {
-            return 0;
-        }
-        
-        if let Some(group) = self.find_group(x, y) {
-            group.liberties
-        } else {
-            0
-        }
+        self.find_group(x, y).map_or(0, |group| group.liberties)
     }
 
     /// Finds all liberty positions for a stone or group at (x,y)
+    /// x, y are 1-based coordinates (1..=size)
     pub fn find_liberties(&self, x: usize, y: usize) -> Vec<(usize, usize)> {
-        if x >= self.size || y >= self.size || self.grid[y][x] == Stone::Empty {
+        if self.get_stone(x, y) == Stone::Empty {
             return Vec::new();
         }
-        
-        let mut liberties = Vec::new();
-        let mut checked = vec![vec![false; self.size]; self.size];
-        
-        if let Some(group) = self.find_group(x, y) {
-            for &(x, y) in &group.positions {
-                let neighbors = [(0, -1), (0, 1), (-1, 0), (1, 0)];
-                for &(dx, dy) in &neighbors {
-                    let nx = x as isize + dx;
-                    let ny = y as isize + dy;
-                    
-                    if nx >= 0 && nx < self.size as isize && ny >= 0 && ny < self.size as isize {
-                        let nx = nx as usize;
-                        let ny = ny as usize;
-                        
-                        if !checked[ny][nx] && self.grid[ny][nx] == Stone::Empty {
-                            liberties.push((nx, ny));
-                            checked[ny][nx] = true;
-                        }
-                    }
-                }
-            }
-        }
-        
-        liberties
+        let (_, liberties) = self.group_at(self.point(x, y));
+        liberties.into_iter().map(|pos| self.coords(pos)).collect()
     }
 }
 
@@ -0,0 +1,114 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Break-in reading: whether a territory that looks enclosed can still be
+//! reduced by capturing part of its wall, or invaded by a group that lives
+
+use crate::engine::ai::LevelProfile;
+use crate::engine::board::{Board, Stone};
+use crate::engine::eye::EyeAnalyzer;
+use crate::engine::owl::{self, OwlStatus};
+use crate::engine::scoring::territories;
+
+/// Owl node budget for each invasion read
+pub const BREAK_IN_NODES: usize = 2_000;
+
+/// Invasion points read per territory
+const MAX_INVASIONS: usize = 3;
+
+/// Territories smaller than this leave no room for an invasion to live
+const MIN_INVASION_AREA: usize = 6;
+
+/// How the intruder gets into a territory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakInKind {
+    /// Captures wall stones by atari or ladder
+    Capture,
+    /// Plays inside and lives
+    Invasion,
+}
+
+/// The first move of a successful break-in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BreakIn {
+    pub point: (usize, usize),
+    pub kind: BreakInKind,
+}
+
+fn opponent(color: Stone) -> Stone {
+    match color {
+        Stone::Black => Stone::White,
+        Stone::White => Stone::Black,
+        Stone::Empty => Stone::Empty,
+    }
+}
+
+/// Reads whether the opponent of `owner` can break into the empty points
+/// of `region`, the intruder moving first
+pub fn break_in(board: &Board, region: &[(usize, usize)], owner: Stone) -> Option<BreakIn> {
+    let intruder = opponent(owner);
+    let analyzer = EyeAnalyzer::new();
+    let ladder_depth = LevelProfile::default().ladder_depth;
+
+    // Wall strings short of liberties
+    let mut wall: Vec<(usize, usize)> = Vec::new();
+    for &(x, y) in region {
+        for (nx, ny) in board.neighbors(x, y) {
+            if board.get_stone(nx, ny) != owner || wall.iter().any(|&(wx, wy)| same_string(board, (wx, wy), (nx, ny))) {
+                continue;
+            }
+            wall.push((nx, ny));
+        }
+    }
+    for &(x, y) in &wall {
+        let point = match board.count_liberties(x, y) {
+            1 => board.find_liberties(x, y).first().copied(),
+            2 => analyzer.find_ladder_attack_point(board, x, y, ladder_depth),
+            _ => None,
+        };
+        if let Some(point) = point.filter(|&(px, py)| board.clone().place_stone(px, py, intruder).is_ok()) {
+            return Some(BreakIn { point, kind: BreakInKind::Capture });
+        }
+    }
+
+    if region.len() < MIN_INVASION_AREA {
+        return None;
+    }
+    for point in invasion_points(board, region) {
+        let mut after = board.clone();
+        if after.place_stone(point.0, point.1, intruder).is_ok()
+            && owl::analyze(&after, point, BREAK_IN_NODES).status == OwlStatus::Alive
+        {
+            return Some(BreakIn { point, kind: BreakInKind::Invasion });
+        }
+    }
+    None
+}
+
+/// Points of territories on `board` that the other side can break into
+pub fn breakable_points(board: &Board) -> Vec<(usize, usize)> {
+    territories(board)
+        .into_iter()
+        .filter(|(owner, region)| break_in(board, region, *owner).is_some())
+        .flat_map(|(_, region)| region)
+        .collect()
+}
+
+fn same_string(board: &Board, a: (usize, usize), b: (usize, usize)) -> bool {
+    board.find_group(a.0, a.1).is_some_and(|group| group.positions().contains(&b))
+}
+
+/// Interior points of `region` with the most room around them
+fn invasion_points(board: &Board, region: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let mut points: Vec<((usize, usize), usize)> = region
+        .iter()
+        .copied()
+        .filter(|&(x, y)| board.neighbors(x, y).iter().all(|&(nx, ny)| board.get_stone(nx, ny) == Stone::Empty))
+        .map(|(x, y)| {
+            let room = region.iter().filter(|&&(rx, ry)| rx.abs_diff(x) + ry.abs_diff(y) <= 2).count();
+            ((x, y), room)
+        })
+        .collect();
+    points.sort_by_key(|&(point, room)| (std::cmp::Reverse(room), point));
+    points.into_iter().take(MAX_INVASIONS).map(|(point, _)| point).collect()
+}
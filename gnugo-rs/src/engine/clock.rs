@@ -0,0 +1,214 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Game clock: main time followed by Japanese byo-yomi or Canadian overtime
+
+use std::time::Duration;
+
+use crate::engine::board::Stone;
+
+/// Time held back from every overtime move for the controller's lag, so
+/// a move budgeted to the end of a period still lands inside it
+const OVERTIME_MARGIN: Duration = Duration::from_secs(1);
+
+/// What follows main time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overtime {
+    /// Absolute time: the game is lost when main time runs out
+    None,
+    /// `periods` periods of `time` each; a move made within a period keeps it
+    Japanese { time: Duration, periods: u32 },
+    /// `stones` moves must be made in `time`, then the period starts over
+    Canadian { time: Duration, stones: u32 },
+}
+
+/// Time allowed to each side
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeControl {
+    pub main_time: Duration,
+    pub overtime: Overtime,
+}
+
+impl TimeControl {
+    /// No time limit
+    pub const UNLIMITED: TimeControl = TimeControl { main_time: Duration::ZERO, overtime: Overtime::None };
+
+    /// GTP `time_settings` values, in seconds: Canadian overtime, or absolute
+    /// time when there are no overtime stones. A positive overtime with no
+    /// stones means no limit at all
+    pub fn canadian(main_time: u64, byo_yomi_time: u64, byo_yomi_stones: u32) -> Self {
+        let overtime = match (byo_yomi_time, byo_yomi_stones) {
+            (0, _) => Overtime::None,
+            (_, 0) => return TimeControl::UNLIMITED,
+            (time, stones) => Overtime::Canadian { time: Duration::from_secs(time), stones },
+        };
+        TimeControl { main_time: Duration::from_secs(main_time), overtime }
+    }
+
+    /// Main time followed by `periods` byo-yomi periods of `period_time` seconds
+    pub fn japanese(main_time: u64, period_time: u64, periods: u32) -> Self {
+        let overtime = if period_time > 0 && periods > 0 {
+            Overtime::Japanese { time: Duration::from_secs(period_time), periods }
+        } else {
+            Overtime::None
+        };
+        TimeControl { main_time: Duration::from_secs(main_time), overtime }
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        self.main_time.is_zero() && self.overtime == Overtime::None
+    }
+}
+
+/// One side's remaining time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SideTime {
+    /// Main time, or in overtime what is left of the current period
+    left: Duration,
+    in_overtime: bool,
+    /// Byo-yomi periods, or stones still due in the Canadian period
+    count: u32,
+    flagged: bool,
+}
+
+/// Both sides' time under one [`TimeControl`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Clock {
+    control: TimeControl,
+    sides: [SideTime; 2],
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Clock::new(TimeControl::UNLIMITED)
+    }
+}
+
+fn side_index(color: Stone) -> Option<usize> {
+    match color {
+        Stone::Black => Some(0),
+        Stone::White => Some(1),
+        Stone::Empty => None,
+    }
+}
+
+impl Clock {
+    /// Both sides start with the full main time
+    pub fn new(control: TimeControl) -> Self {
+        let side = SideTime { left: control.main_time, in_overtime: false, count: 0, flagged: false };
+        Clock { control, sides: [side; 2] }
+    }
+
+    pub fn control(&self) -> TimeControl {
+        self.control
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        self.control.is_unlimited()
+    }
+
+    /// Whether `color` has run out of time
+    pub fn is_flagged(&self, color: Stone) -> bool {
+        side_index(color).is_some_and(|i| self.sides[i].flagged)
+    }
+
+    /// Starts overtime with a fresh period, or flags the side if there is none
+    fn enter_overtime(side: &mut SideTime, overtime: Overtime) {
+        side.in_overtime = true;
+        match overtime {
+            Overtime::None => side.flagged = true,
+            Overtime::Japanese { time, periods } => (side.left, side.count) = (time, periods),
+            Overtime::Canadian { time, stones } => (side.left, side.count) = (time, stones),
+        }
+    }
+
+    /// Charges `used` for one move by `color`; returns false once the side
+    /// has run out of time
+    pub fn charge(&mut self, color: Stone, mut used: Duration) -> bool {
+        let Some(i) = side_index(color) else { return true };
+        if self.is_unlimited() || self.sides[i].flagged {
+            return !self.sides[i].flagged;
+        }
+        let overtime = self.control.overtime;
+        let side = &mut self.sides[i];
+        if !side.in_overtime {
+            if used <= side.left {
+                side.left -= used;
+                return true;
+            }
+            used -= side.left;
+            Clock::enter_overtime(side, overtime);
+        }
+        match overtime {
+            Overtime::None => side.flagged = true,
+            Overtime::Japanese { time, .. } => {
+                // Every period overrun in full is lost
+                while used > time && !side.flagged {
+                    used -= time;
+                    side.count -= 1;
+                    side.flagged = side.count == 0;
+                }
+                side.left = time;
+            }
+            Overtime::Canadian { time, stones } => {
+                if used > side.left {
+                    side.flagged = true;
+                } else {
+                    side.left -= used;
+                    side.count -= 1;
+                    if side.count == 0 {
+                        (side.left, side.count) = (time, stones);
+                    }
+                }
+            }
+        }
+        !side.flagged
+    }
+
+    /// Time and stones left in GTP `time_left` terms: stones are 0 in main
+    /// time, and count byo-yomi periods under Japanese overtime. `None`
+    /// without a time limit
+    pub fn time_left(&self, color: Stone) -> Option<(Duration, u32)> {
+        let side = self.sides[side_index(color)?];
+        if self.is_unlimited() {
+            return None;
+        }
+        Some((side.left, if side.in_overtime { side.count } else { 0 }))
+    }
+
+    /// Sets the time left as GTP `time_left` reports it; 0 stones means main time
+    pub fn set_time_left(&mut self, color: Stone, left: Duration, stones: u32) -> Result<(), String> {
+        let i = side_index(color).ok_or("Invalid color")?;
+        if stones > 0 && self.control.overtime == Overtime::None {
+            return Err("No overtime in these time settings".to_string());
+        }
+        self.sides[i] = SideTime { left, in_overtime: stones > 0, count: stones, flagged: false };
+        Ok(())
+    }
+
+    /// How long `color` can afford to think about its next move: a share of
+    /// the main time plus one overtime move, or in overtime its share of
+    /// the period less [`OVERTIME_MARGIN`]. `None` without a time limit
+    pub fn move_budget(&self, color: Stone) -> Option<Duration> {
+        let side = self.sides[side_index(color)?];
+        if self.is_unlimited() {
+            return None;
+        }
+        if side.flagged {
+            return Some(Duration::ZERO);
+        }
+        if side.in_overtime {
+            let share = match self.control.overtime {
+                Overtime::Canadian { .. } => side.left / side.count.max(1),
+                _ => side.left,
+            };
+            return Some(share.saturating_sub(OVERTIME_MARGIN));
+        }
+        let overtime = match self.control.overtime {
+            Overtime::None => Duration::ZERO,
+            Overtime::Japanese { time, .. } => time,
+            Overtime::Canadian { time, stones } => time / stones,
+        };
+        Some((side.left / 20).saturating_add(overtime))
+    }
+}
@@ -0,0 +1,275 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Dragons: worms joined by connections the opponent cannot cut, with
+//! their eyes, room to escape and life-and-death status
+
+use crate::engine::board::{Board, Stone};
+use crate::engine::escape::{escape_route, EscapeRoute};
+use crate::engine::eye::{EyeAnalyzer, HalfEyeType};
+use crate::engine::influence::{InfluenceMap, STONE_STRENGTH};
+use crate::engine::owl::{self, OwlStatus};
+use crate::engine::scoring::territories;
+use crate::engine::worm::{Code, Worms};
+
+/// Owl node budget for each dragon that is neither clearly alive nor dead
+pub const DRAGON_OWL_NODES: usize = 500;
+
+/// Enclosed areas at least this big count as two eyes
+const TWO_EYE_AREA: usize = 7;
+
+/// Empty points reached within [`ESCAPE_RADIUS`] that let a dragon run
+const ESCAPE_ROOM: usize = 12;
+
+/// Steps through empty points counted as escape room
+const ESCAPE_RADIUS: usize = 3;
+
+/// A dragon with an escape route this cheap runs out instead of being read
+const EASY_ESCAPE_COST: f32 = 3.0;
+
+/// A group of worms that live or die together
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dragon {
+    /// Origin of the dragon's first worm
+    pub origin: (usize, usize),
+    pub color: Stone,
+    pub stones: Vec<(usize, usize)>,
+    /// Origins of the worms amalgamated into the dragon
+    pub worms: Vec<(usize, usize)>,
+    /// Liberties of all its worms
+    pub liberties: Vec<(usize, usize)>,
+    /// Eyes from areas enclosed by the dragon's color
+    pub eyes: usize,
+    /// Empty points the dragon can run to, not counting its eyes
+    pub escape: usize,
+    /// Cheapest way to the center or to friendly stones
+    pub escape_route: Option<EscapeRoute>,
+    /// Own minus enemy influence on its liberties, on average
+    pub support: f32,
+    pub status: OwlStatus,
+    /// Move that kills a critical dragon
+    pub attack_point: Option<(usize, usize)>,
+    /// Move that saves a critical dragon
+    pub defense_point: Option<(usize, usize)>,
+}
+
+/// All dragons of a position, queryable by coordinate
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dragons {
+    size: usize,
+    /// Index into `dragons` of the dragon at each point, [y - 1][x - 1]
+    index: Vec<Vec<Option<usize>>>,
+    dragons: Vec<Dragon>,
+}
+
+impl Dragons {
+    /// Amalgamates the worms of `board` and reads each dragon's status
+    pub fn compute(board: &Board) -> Self {
+        Self::from_worms(board, &Worms::compute(board))
+    }
+
+    /// Like [`Dragons::compute`], from worms already read
+    pub fn from_worms(board: &Board, worms: &Worms) -> Self {
+        // Union-find over worm indices
+        let count = worms.iter().count();
+        let mut parent: Vec<usize> = (0..count).collect();
+        fn root(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
+            }
+            i
+        }
+        for (i, a) in worms.iter().enumerate() {
+            for (j, b) in worms.iter().enumerate().skip(i + 1) {
+                if a.color == b.color && connected(board, &a.stones, &b.stones, &a.liberties, &b.liberties) {
+                    let (ra, rb) = (root(&mut parent, i), root(&mut parent, j));
+                    parent[rb] = ra;
+                }
+            }
+        }
+
+        let size = board.size();
+        let mut index = vec![vec![None; size]; size];
+        let mut dragons: Vec<Dragon> = Vec::new();
+        let mut dragon_of_root = vec![None; count];
+        for (i, worm) in worms.iter().enumerate() {
+            let r = root(&mut parent, i);
+            let d = *dragon_of_root[r].get_or_insert_with(|| {
+                dragons.push(Dragon {
+                    origin: worm.origin,
+                    color: worm.color,
+                    stones: Vec::new(),
+                    worms: Vec::new(),
+                    liberties: Vec::new(),
+                    eyes: 0,
+                    escape: 0,
+                    escape_route: None,
+                    support: 0.0,
+                    status: OwlStatus::Unknown,
+                    attack_point: None,
+                    defense_point: None,
+                });
+                dragons.len() - 1
+            });
+            dragons[d].worms.push(worm.origin);
+            dragons[d].stones.extend(&worm.stones);
+            for &point in &worm.liberties {
+                if !dragons[d].liberties.contains(&point) {
+                    dragons[d].liberties.push(point);
+                }
+            }
+            for &(x, y) in &worm.stones {
+                index[y - 1][x - 1] = Some(d);
+            }
+        }
+
+        // Enclosed areas give eyes to the dragons bordering them; a false
+        // eye gives none
+        let mut eye_points = vec![vec![false; size]; size];
+        let analyzer = EyeAnalyzer::new();
+        for (owner, region) in territories(board) {
+            let eyes = match region[..] {
+                _ if region.len() >= TWO_EYE_AREA => 2,
+                [(x, y)] if analyzer.half_eye(board, owner, x, y).is_some_and(|h| h.eye_type == HalfEyeType::False) => 0,
+                _ => 1,
+            };
+            let mut bordering: Vec<usize> = region
+                .iter()
+                .flat_map(|&(x, y)| board.neighbors(x, y))
+                .filter(|&(x, y)| board.get_stone(x, y) == owner)
+                .filter_map(|(x, y)| index[y - 1][x - 1])
+                .collect();
+            bordering.sort_unstable();
+            bordering.dedup();
+            for d in bordering {
+                dragons[d].eyes += eyes;
+            }
+            for (x, y) in region {
+                eye_points[y - 1][x - 1] = true;
+            }
+        }
+
+        let influence = InfluenceMap::compute(board);
+        for dragon in &mut dragons {
+            dragon.escape = escape_room(board, &dragon.stones, &eye_points);
+            dragon.escape_route = escape_route(board, &dragon.stones, &influence);
+            let enemy = if dragon.color == Stone::Black { Stone::White } else { Stone::Black };
+            if !dragon.liberties.is_empty() {
+                let total: f32 = dragon
+                    .liberties
+                    .iter()
+                    .map(|&(x, y)| influence.influence(x, y, dragon.color) - influence.influence(x, y, enemy))
+                    .sum();
+                dragon.support = total / dragon.liberties.len() as f32;
+            }
+            // A worm that cannot be defended takes the dragon with it
+            let lost = dragon
+                .worms
+                .iter()
+                .filter_map(|&(x, y)| worms.get(x, y))
+                .any(|w| w.attack_code == Code::Win && w.defense_code == Code::Fail);
+            let runs = dragon.escape >= ESCAPE_ROOM
+                || dragon.escape_route.as_ref().is_some_and(|route| route.cost <= EASY_ESCAPE_COST);
+            if dragon.eyes >= 2 || (runs && !lost) {
+                dragon.status = OwlStatus::Alive;
+                continue;
+            }
+            let result = owl::analyze(board, dragon.origin, DRAGON_OWL_NODES);
+            dragon.status = result.status;
+            if result.status == OwlStatus::Critical {
+                dragon.attack_point = result.attack_point;
+                dragon.defense_point = result.defense_point;
+            }
+        }
+        Dragons { size, index, dragons }
+    }
+
+    /// The dragon with a stone at (x, y)
+    pub fn get(&self, x: usize, y: usize) -> Option<&Dragon> {
+        if x == 0 || y == 0 || x > self.size || y > self.size {
+            return None;
+        }
+        self.index[y - 1][x - 1].map(|i| &self.dragons[i])
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Dragon> {
+        self.dragons.iter()
+    }
+
+    /// Stones of dragons with `status`, sorted
+    pub fn stones(&self, status: OwlStatus) -> Vec<(usize, usize)> {
+        let mut stones: Vec<(usize, usize)> =
+            self.dragons.iter().filter(|d| d.status == status).flat_map(|d| d.stones.iter().copied()).collect();
+        stones.sort();
+        stones
+    }
+}
+
+/// How safe `dragon` is, from 0.0 (dead) to 1.0 (settled): its eyes,
+/// liberties, room to run and the strength of friendly stones nearby
+pub fn group_strength(dragon: &Dragon) -> f32 {
+    match dragon.status {
+        OwlStatus::Dead => return 0.0,
+        _ if dragon.eyes >= 2 => return 1.0,
+        _ => {}
+    }
+    let eyes = dragon.eyes.min(2) as f32 / 2.0;
+    let liberties = dragon.liberties.len().min(6) as f32 / 6.0;
+    let escape = dragon.escape.min(ESCAPE_ROOM) as f32 / ESCAPE_ROOM as f32;
+    let support = (dragon.support / STONE_STRENGTH).clamp(-1.0, 1.0) / 2.0 + 0.5;
+    let strength = 0.35 * eyes + 0.25 * liberties + 0.25 * escape + 0.15 * support;
+    if dragon.status == OwlStatus::Critical { strength / 2.0 } else { strength }
+}
+
+/// Whether two worms of one color stay connected whoever moves first:
+/// they share two liberties, touch diagonally with both crossing points
+/// open, or share a liberty the opponent cannot safely occupy
+fn connected(
+    board: &Board,
+    a: &[(usize, usize)],
+    b: &[(usize, usize)],
+    a_liberties: &[(usize, usize)],
+    b_liberties: &[(usize, usize)],
+) -> bool {
+    let shared: Vec<(usize, usize)> = a_liberties.iter().copied().filter(|p| b_liberties.contains(p)).collect();
+    if shared.len() >= 2 {
+        return true;
+    }
+    let color = board.get_stone(a[0].0, a[0].1);
+    let opponent = if color == Stone::Black { Stone::White } else { Stone::Black };
+    let diagonal = a.iter().any(|&(ax, ay)| {
+        b.iter().any(|&(bx, by)| {
+            ax.abs_diff(bx) == 1
+                && ay.abs_diff(by) == 1
+                && board.get_stone(ax, by) == Stone::Empty
+                && board.get_stone(bx, ay) == Stone::Empty
+        })
+    });
+    if diagonal {
+        return true;
+    }
+    shared.into_iter().any(|(x, y)| {
+        let mut after = board.clone();
+        after.place_stone(x, y, opponent).is_err() || after.count_liberties(x, y) <= 1
+    })
+}
+
+/// Empty points within [`ESCAPE_RADIUS`] steps of `stones`, outside eyes
+fn escape_room(board: &Board, stones: &[(usize, usize)], eye_points: &[Vec<bool>]) -> usize {
+    let mut seen: Vec<(usize, usize)> = Vec::new();
+    let mut frontier: Vec<(usize, usize)> = stones.to_vec();
+    for _ in 0..ESCAPE_RADIUS {
+        let mut next = Vec::new();
+        for (x, y) in frontier {
+            for (nx, ny) in board.neighbors(x, y) {
+                if board.get_stone(nx, ny) == Stone::Empty && !eye_points[ny - 1][nx - 1] && !seen.contains(&(nx, ny)) {
+                    seen.push((nx, ny));
+                    next.push((nx, ny));
+                }
+            }
+        }
+        frontier = next;
+    }
+    seen.len()
+}
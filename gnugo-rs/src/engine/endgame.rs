@@ -0,0 +1,187 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Endgame: boundary plays between settled areas, valued in points and
+//! ordered the way a player would take them, sente before gote
+
+use crate::engine::board::{Board, Stone};
+use crate::engine::evaluation::Evaluator;
+use crate::engine::influence::{InfluenceMap, Region};
+
+/// The position counts as an endgame once no more than this many empty
+/// points per board line are still open, neither side's territory
+const OPEN_POINTS_PER_LINE: usize = 2;
+
+/// Distance within which a follow-up move counts as part of the same play
+const FOLLOW_UP_RADIUS: usize = 2;
+
+/// Shape of a boundary play
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EndgameKind {
+    /// Turns around the head of an enemy stone touching one's own
+    Hane,
+    /// Extends a stone straight along the enemy's wall
+    Push,
+    /// Slides along the first line from a second-line stone
+    MonkeyJump,
+    /// Fills a neutral point between the two colors
+    Dame,
+}
+
+impl EndgameKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EndgameKind::Hane => "hane",
+            EndgameKind::Push => "push",
+            EndgameKind::MonkeyJump => "monkey jump",
+            EndgameKind::Dame => "dame",
+        }
+    }
+}
+
+/// A boundary play and what it is worth
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EndgameMove {
+    pub point: (usize, usize),
+    pub kind: EndgameKind,
+    /// Points the play swings, by [`Evaluator::move_value`]
+    pub value: f32,
+    /// Whether it threatens a follow-up bigger than itself, so the opponent
+    /// has to answer
+    pub sente: bool,
+}
+
+impl EndgameMove {
+    /// Value used for ordering: a sente play keeps the move, so it is
+    /// worth twice a gote play of the same size
+    pub fn priority(&self) -> f32 {
+        if self.sente { 2.0 * self.value } else { self.value }
+    }
+}
+
+fn opponent(color: Stone) -> Stone {
+    match color {
+        Stone::Black => Stone::White,
+        Stone::White => Stone::Black,
+        Stone::Empty => Stone::Empty,
+    }
+}
+
+/// Whether few enough empty points are still open for the game to be
+/// in its endgame
+pub fn is_endgame(board: &Board) -> bool {
+    let size = board.size();
+    let influence = InfluenceMap::compute(board);
+    let open = (1..=size)
+        .flat_map(|y| (1..=size).map(move |x| (x, y)))
+        .filter(|&(x, y)| {
+            board.get_stone(x, y) == Stone::Empty && !matches!(influence.classify(board, x, y), Region::Territory(_))
+        })
+        .count();
+    board.stones_on_board(Stone::Black) + board.stones_on_board(Stone::White) > 0 && open <= OPEN_POINTS_PER_LINE * size
+}
+
+/// Boundary plays for `color` outside its own territory and moyo, biggest
+/// first. With `depth` of two plies or more, each play is also read for a
+/// follow-up that makes it sente
+pub fn endgame_moves(board: &Board, color: Stone, depth: usize) -> Vec<EndgameMove> {
+    let size = board.size();
+    let influence = InfluenceMap::compute(board);
+    let mut moves = Vec::new();
+    for y in 1..=size {
+        for x in 1..=size {
+            let region = influence.classify(board, x, y);
+            if region == Region::Territory(color) || region == Region::Moyo(color) {
+                continue;
+            }
+            let Some(kind) = classify(board, (x, y), color) else {
+                continue;
+            };
+            let Some(value) = Evaluator::move_value(board, (x, y), color) else {
+                continue;
+            };
+            if kind != EndgameKind::Dame && value <= 0.0 {
+                continue;
+            }
+            let sente = depth >= 2 && is_sente(board, (x, y), color, value);
+            moves.push(EndgameMove { point: (x, y), kind, value, sente });
+        }
+    }
+    moves.sort_by(|a, b| b.priority().total_cmp(&a.priority()).then(a.point.cmp(&b.point)));
+    moves
+}
+
+/// The kind of boundary play `color` makes at the empty `point`, if any
+fn classify(board: &Board, (x, y): (usize, usize), color: Stone) -> Option<EndgameKind> {
+    if board.get_stone(x, y) != Stone::Empty {
+        return None;
+    }
+    let size = board.size() as isize;
+    let stone_at = |dx: isize, dy: isize| {
+        let (px, py) = (x as isize + dx, y as isize + dy);
+        if px < 1 || py < 1 || px > size || py > size {
+            None
+        } else {
+            Some(board.get_stone(px as usize, py as usize))
+        }
+    };
+    let directions = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+    let own_next = directions.iter().any(|&(dx, dy)| stone_at(dx, dy) == Some(color));
+    let enemy_next = directions.iter().any(|&(dx, dy)| stone_at(dx, dy) == Some(opponent(color)));
+
+    for &(dx, dy) in &directions {
+        let (px, py) = (-dy, dx);
+        // Own stone behind, enemy stone alongside
+        if stone_at(-dx, -dy) == Some(color)
+            && (stone_at(px, py) == Some(opponent(color)) || stone_at(-px, -py) == Some(opponent(color)))
+        {
+            return Some(EndgameKind::Push);
+        }
+        // Enemy stone ahead, next to an own stone diagonal to the move
+        for side in [1, -1] {
+            if stone_at(dx, dy) == Some(opponent(color))
+                && stone_at(dx + side * px, dy + side * py) == Some(color)
+                && stone_at(side * px, side * py) == Some(Stone::Empty)
+            {
+                return Some(EndgameKind::Hane);
+            }
+        }
+    }
+
+    // Three points along the first line from an own second-line stone
+    let on_edge = x == 1 || y == 1 || x == board.size() || y == board.size();
+    if on_edge && !own_next && !enemy_next {
+        let inward = if x == 1 { (1, 0) } else if x == board.size() { (-1, 0) } else if y == 1 { (0, 1) } else { (0, -1) };
+        for &(dx, dy) in &directions {
+            if (dx, dy) == inward || (dx, dy) == (-inward.0, -inward.1) {
+                continue;
+            }
+            let on_line = (1..3).all(|step| stone_at(step * dx, step * dy) == Some(Stone::Empty));
+            if on_line && stone_at(3 * dx + inward.0, 3 * dy + inward.1) == Some(color) {
+                return Some(EndgameKind::MonkeyJump);
+            }
+        }
+    }
+
+    (own_next && enemy_next).then_some(EndgameKind::Dame)
+}
+
+/// Whether `color`, having played `point` worth `value`, threatens a
+/// follow-up nearby worth more than the play itself
+fn is_sente(board: &Board, point: (usize, usize), color: Stone, value: f32) -> bool {
+    let mut after = board.clone();
+    if after.place_stone(point.0, point.1, color).is_err() {
+        return false;
+    }
+    let size = board.size();
+    let near = (1..=size)
+        .flat_map(|y| (1..=size).map(move |x| (x, y)))
+        .filter(|&(x, y)| x.abs_diff(point.0) + y.abs_diff(point.1) <= FOLLOW_UP_RADIUS);
+    near.filter_map(|(x, y)| {
+        if after.get_stone(x, y) != Stone::Empty {
+            return None;
+        }
+        Evaluator::move_value(&after, (x, y), color)
+    })
+    .any(|follow_up| follow_up > value)
+}
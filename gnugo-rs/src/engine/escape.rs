@@ -0,0 +1,132 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Escape routes: the cheapest path from a dragon's liberties to the open
+//! center or to friendly stones, where every step costs more the stronger
+//! the enemy's influence on it
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::engine::board::{Board, Stone};
+use crate::engine::influence::{InfluenceMap, STONE_STRENGTH};
+
+/// Routes costing more than this do not count as a way out
+pub const MAX_ESCAPE_COST: f32 = 6.0;
+
+/// Extra cost of a step where the enemy has a stone's full influence
+const ENEMY_COST: f32 = 2.0;
+
+/// Points at least this far from every edge are open center
+const CENTER_LINE: usize = 4;
+
+/// What an escape route leads to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EscapeGoal {
+    /// An empty point on or above [`CENTER_LINE`]
+    Center,
+    /// A friendly stone outside the dragon
+    Friend((usize, usize)),
+}
+
+/// The cheapest way out for a dragon
+#[derive(Debug, Clone, PartialEq)]
+pub struct EscapeRoute {
+    pub goal: EscapeGoal,
+    /// Empty points from a liberty of the dragon to the goal
+    pub path: Vec<(usize, usize)>,
+    pub cost: f32,
+}
+
+impl EscapeRoute {
+    /// The move that starts running
+    pub fn first_step(&self) -> (usize, usize) {
+        self.path[0]
+    }
+}
+
+/// Search state ordered by lowest cost first
+struct Step {
+    cost: f32,
+    point: (usize, usize),
+}
+
+impl PartialEq for Step {
+    fn eq(&self, other: &Self) -> bool {
+        self.cost == other.cost
+    }
+}
+
+impl Eq for Step {}
+
+impl PartialOrd for Step {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Step {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.total_cmp(&self.cost).then_with(|| other.point.cmp(&self.point))
+    }
+}
+
+/// The cheapest route within [`MAX_ESCAPE_COST`] from the dragon made of
+/// `stones` to the center or to friendly stones, through empty points
+pub fn escape_route(board: &Board, stones: &[(usize, usize)], influence: &InfluenceMap) -> Option<EscapeRoute> {
+    let (&(sx, sy), size) = (stones.first()?, board.size());
+    let color = board.get_stone(sx, sy);
+    let enemy = if color == Stone::Black { Stone::White } else { Stone::Black };
+    let index = |(x, y): (usize, usize)| (y - 1) * size + x - 1;
+    let step_cost = |(x, y): (usize, usize)| 1.0 + ENEMY_COST * (influence.influence(x, y, enemy) / STONE_STRENGTH).min(1.0);
+
+    let mut cost = vec![f32::INFINITY; size * size];
+    let mut previous: Vec<Option<(usize, usize)>> = vec![None; size * size];
+    let mut queue = BinaryHeap::new();
+    for &(x, y) in stones {
+        for point in board.neighbors(x, y) {
+            let c = step_cost(point);
+            if board.get_stone(point.0, point.1) == Stone::Empty && c < cost[index(point)] {
+                cost[index(point)] = c;
+                queue.push(Step { cost: c, point });
+            }
+        }
+    }
+
+    while let Some(Step { cost: c, point }) = queue.pop() {
+        if c > cost[index(point)] || c > MAX_ESCAPE_COST {
+            continue;
+        }
+        let (x, y) = point;
+        let line = x.min(y).min(size + 1 - x).min(size + 1 - y);
+        let friend = board
+            .neighbors(x, y)
+            .into_iter()
+            .find(|&(nx, ny)| board.get_stone(nx, ny) == color && !stones.contains(&(nx, ny)));
+        let goal = match friend {
+            Some(stone) => Some(EscapeGoal::Friend(stone)),
+            None if line >= CENTER_LINE => Some(EscapeGoal::Center),
+            None => None,
+        };
+        if let Some(goal) = goal {
+            let mut path = vec![point];
+            while let Some(back) = previous[index(*path.last().unwrap())] {
+                path.push(back);
+            }
+            path.reverse();
+            return Some(EscapeRoute { goal, path, cost: c });
+        }
+        for next in board.neighbors(x, y) {
+            if board.get_stone(next.0, next.1) != Stone::Empty {
+                continue;
+            }
+            let total = c + step_cost(next);
+            if total < cost[index(next)] {
+                cost[index(next)] = total;
+                previous[index(next)] = Some(point);
+                queue.push(Step { cost: total, point: next });
+            }
+        }
+    }
+    None
+}
@@ -3,29 +3,116 @@
 
 //! Position evaluation and scoring
 
+use std::cell::RefCell;
+
 use crate::engine::board::Board;
 use crate::engine::board::Stone;
+use crate::engine::influence::InfluenceMap;
+use crate::engine::profile::{self, Subsystem};
+use crate::engine::scoring::territories;
 
 /// Evaluates the strength of a position
 pub struct Evaluator;
 
+/// Slots in the per-thread evaluation cache; a new position replaces
+/// whatever was in its slot
+pub const EVAL_CACHE_SIZE: usize = 1 << 16;
+
+/// Lookups in the evaluation cache
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl CacheStats {
+    /// Share of lookups answered from the cache, 0 before any lookup
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 { 0.0 } else { self.hits as f64 / total as f64 }
+    }
+}
+
+/// Scores keyed on position hash and board size
+#[derive(Default)]
+struct EvalCache {
+    /// Allocated on first insert
+    slots: Vec<Option<((u64, usize), i32)>>,
+    stats: CacheStats,
+}
+
+impl EvalCache {
+    fn get(&mut self, key: (u64, usize)) -> Option<i32> {
+        let slot = self.slots.get(key.0 as usize % EVAL_CACHE_SIZE).copied().flatten();
+        match slot {
+            Some((stored, score)) if stored == key => {
+                self.stats.hits += 1;
+                Some(score)
+            }
+            _ => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    fn insert(&mut self, key: (u64, usize), score: i32) {
+        if self.slots.is_empty() {
+            self.slots = vec![None; EVAL_CACHE_SIZE];
+        }
+        self.slots[key.0 as usize % EVAL_CACHE_SIZE] = Some((key, score));
+    }
+}
+
+thread_local! {
+    static CACHE: RefCell<EvalCache> = RefCell::new(EvalCache::default());
+}
+
+/// Area count of a position and who owns each point
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoreEstimate {
+    pub black: i32,
+    pub white: i32,
+    /// Owner of each point, indexed [y - 1][x - 1]; `Stone::Empty` for dame
+    pub ownership: Vec<Vec<Stone>>,
+}
+
 impl Evaluator {
     /// Evaluates the position for black player
-    /// Returns a score where positive values favor black, negative favor white
+    /// Returns a score where positive values favor black, negative favor white.
+    /// Positions already evaluated on this thread come from a cache
     pub fn evaluate_position(board: &Board) -> i32 {
-        let mut score = 0;
-        
+        let key = (board.position_hash(), board.size());
+        if let Some(score) = CACHE.with(|cache| cache.borrow_mut().get(key)) {
+            return score;
+        }
+        let score = Evaluator::breakdown(board).iter().map(|&(_, value)| value).sum();
+        CACHE.with(|cache| cache.borrow_mut().insert(key, score));
+        score
+    }
+
+    /// Hits and misses of this thread's evaluation cache
+    pub fn cache_stats() -> CacheStats {
+        CACHE.with(|cache| cache.borrow().stats)
+    }
+
+    /// Empties this thread's evaluation cache and its statistics
+    pub fn clear_cache() {
+        CACHE.with(|cache| *cache.borrow_mut() = EvalCache::default());
+    }
+
+    /// The named terms that [`Evaluator::evaluate_position`] adds up
+    pub fn breakdown(board: &Board) -> [(&'static str, i32); 3] {
         // Count stones
         let black_stones = board.stones_on_board(Stone::Black);
         let white_stones = board.stones_on_board(Stone::White);
-        
-        score += (black_stones as i32) - (white_stones as i32);
-        
+
         // Evaluate territory and influence (simplified)
-        score += Evaluator::evaluate_territory(board);
-        score += Evaluator::evaluate_influence(board);
-        
-        score
+        [
+            ("stones", (black_stones as i32) - (white_stones as i32)),
+            ("territory", Evaluator::evaluate_territory(board)),
+            ("influence", profile::measure(Subsystem::Influence, || Evaluator::evaluate_influence(board))),
+        ]
     }
     
     /// Evaluates territorial advantage
@@ -34,8 +121,8 @@ impl Evaluator {
         let size = board.size();
         
         // Simple territorial evaluation
-        for row in 0..size {
-            for col in 0..size {
+        for row in 1..=size {
+            for col in 1..=size {
                 let stone = board.get_stone(row, col);
                 match stone {
                     Stone::Black => {
@@ -64,32 +151,10 @@ impl Evaluator {
         territory_score
     }
     
-    /// Evaluates positional influence
+    /// Empty points Black controls by influence, less those White controls
     fn evaluate_influence(board: &Board) -> i32 {
-        let mut influence_score = 0;
-        let size = board.size();
-        
-        // Simplified influence evaluation based on stone positions
-        for row in 0..size {
-            for col in 0..size {
-                let stone = board.get_stone(row, col);
-                match stone {
-                    Stone::Black => {
-                        // Black stones have influence in surrounding area
-                        influence_score += Evaluator::calculate_influence(board, row, col, Stone::Black);
-                    }
-                    Stone::White => {
-                        // White stones have influence in surrounding area
-                        influence_score -= Evaluator::calculate_influence(board, row, col, Stone::White);
-                    }
-                    Stone::Empty => {
-                        // No influence from empty points
-                    }
-                }
-            }
-        }
-        
-        influence_score
+        let (black, white) = InfluenceMap::compute(board).territory_count(board);
+        (black as i32 - white as i32) / 4
     }
     
     /// Counts adjacent stones of a particular color
@@ -98,110 +163,80 @@ impl Evaluator {
         let size = board.size();
         
         // Check up
-        if row > 0 && board.get_stone(row - 1, col) == color {
+        if row > 1 && board.get_stone(row - 1, col) == color {
             count += 1;
         }
         
         // Check down
-        if row < size - 1 && board.get_stone(row + 1, col) == color {
+        if row < size && board.get_stone(row + 1, col) == color {
             count += 1;
         }
         
         // Check left
-        if col > 0 && board.get_stone(row, col - 1) == color {
+        if col > 1 && board.get_stone(row, col - 1) == color {
             count += 1;
         }
         
         // Check right
-        if col < size - 1 && board.get_stone(row, col + 1) == color {
+        if col < size && board.get_stone(row, col + 1) == color {
             count += 1;
         }
         
         count
     }
     
-    /// Calculates influence from a stone at position
-    fn calculate_influence(board: &Board, row: usize, col: usize, color: Stone) -> i32 {
-        let mut influence = 0;
-        let size = board.size();
-        
-        // Influence decreases with distance
-        for r in 0..size {
-            for c in 0..size {
-                let distance = (r as i32 - row as i32).abs() + (c as i32 - col as i32).abs();
-                if distance <= 3 && board.get_stone(r, c) == color {
-                    // Influence value decreases with distance
-                    influence += 4 - distance as i32;
-                }
+    /// Points a move by `color` at (x, y) is worth: Black's points after
+    /// the move minus Black's points had the opponent played there instead,
+    /// from `color`'s point of view. Counting the opponent's follow-up too
+    /// makes this the swing of a gote move. A stone played in the other
+    /// side's territory is taken to die and changes nothing. `None` if
+    /// `color` cannot play there
+    pub fn move_value(board: &Board, (x, y): (usize, usize), color: Stone) -> Option<f32> {
+        board.clone().place_stone(x, y, color).ok()?;
+        let owner = InfluenceMap::compute(board).territory(board, x, y);
+        let after = |player: Stone| {
+            let mut next = board.clone();
+            if owner != opponent(player) {
+                // Where the player cannot play, the point stays as it is
+                let _ = next.place_stone(x, y, player);
             }
-        }
-        
-        influence
+            Evaluator::points(&next)
+        };
+        let swing = after(color) - after(opponent(color));
+        Some(if color == Stone::Black { swing } else { -swing })
     }
-    
-    /// Estimates the score for a position using simple territory counting
-    pub fn estimate_score(board: &Board) -> (i32, i32) {
-        let mut black_score = 0i32;
-        let mut white_score = 0i32;
-        
+
+    /// Black's lead in area by influence: stones, territory in full and
+    /// moyo at half value
+    fn points(board: &Board) -> f32 {
+        let influence = InfluenceMap::compute(board);
+        let (black_territory, white_territory) = influence.territory_count(board);
+        let (black_moyo, white_moyo) = influence.moyo_count(board);
+        let stones = board.stones_on_board(Stone::Black) as f32 - board.stones_on_board(Stone::White) as f32;
+        stones + black_territory as f32 - white_territory as f32 + (black_moyo as f32 - white_moyo as f32) / 2.0
+    }
+
+    /// Estimates the area score of a position: every stone, plus each
+    /// empty region bordered by a single color. Regions touching both
+    /// colors are dame
+    pub fn estimate_score(board: &Board) -> ScoreEstimate {
         let size = board.size();
-        
-        // Count stones
-        black_score += board.stones_on_board(Stone::Black) as i32;
-        white_score += board.stones_on_board(Stone::White) as i32;
-        
-        // Simple territory estimation
-        for row in 0..size {
-            for col in 0..size {
-                match board.get_stone(row, col) {
-                    Stone::Black => {
-                        black_score += 1;
-                    }
-                    Stone::White => {
-                        white_score += 1;
-                    }
-                    Stone::Empty => {
-                        // Check if surrounded by black or white
-                        let mut black_count = 0;
-                        let mut white_count = 0;
-                        
-                        // Check adjacent positions
-                        if row > 0 && board.get_stone(row - 1, col) == Stone::Black {
-                            black_count += 1;
-                        }
-                        if row < size - 1 && board.get_stone(row + 1, col) == Stone::Black {
-                            black_count += 1;
-                        }
-                        if col > 0 && board.get_stone(row, col - 1) == Stone::Black {
-                            black_count += 1;
-                        }
-                        if col < size - 1 && board.get_stone(row, col + 1) == Stone::Black {
-                            black_count += 1;
-                        }
-                        
-                        if row > 0 && board.get_stone(row - 1, col) == Stone::White {
-                            white_count += 1;
-                        }
-                        if row < size - 1 && board.get_stone(row + 1, col) == Stone::White {
-                            white_count += 1;
-                        }
-                        if col > 0 && board.get_stone(row, col - 1) == Stone::White {
-                            white_count += 1;
-                        }
-                        if col < size - 1 && board.get_stone(row, col + 1) == Stone::White {
-                            white_count += 1;
-                        }
-                        
-                        if black_count > white_count {
-                            black_score += 1;
-                        } else if white_count > black_count {
-                            white_score += 1;
-                        }
-                    }
-                }
+        let mut ownership: Vec<Vec<Stone>> =
+            (1..=size).map(|y| (1..=size).map(|x| board.get_stone(x, y)).collect()).collect();
+        for (owner, region) in territories(board) {
+            for (x, y) in region {
+                ownership[y - 1][x - 1] = owner;
             }
         }
-        
-        (black_score, white_score)
+        let count = |color: Stone| ownership.iter().flatten().filter(|&&owner| owner == color).count() as i32;
+        ScoreEstimate { black: count(Stone::Black), white: count(Stone::White), ownership }
+    }
+}
+
+fn opponent(color: Stone) -> Stone {
+    match color {
+        Stone::Black => Stone::White,
+        Stone::White => Stone::Black,
+        Stone::Empty => Stone::Empty,
     }
-}
\ No newline at end of file
+}
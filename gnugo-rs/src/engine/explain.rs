@@ -0,0 +1,167 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Explanations of generated moves for `explain_last_move`
+
+use std::fmt::Write;
+
+use crate::engine::ai::AIDifficulty;
+use crate::engine::analysis::{candidate_moves, CandidateMove};
+use crate::engine::board::{Board, Placement, Stone};
+use crate::engine::evaluation::Evaluator;
+use crate::gtp::format_move;
+
+/// Number of alternative moves listed in an explanation
+const EXPLAIN_CANDIDATES: usize = 5;
+
+/// Why the engine chose a move
+#[derive(Debug, Clone)]
+pub struct MoveExplanation {
+    pub color: Stone,
+    /// `None` for a pass
+    pub point: Option<(usize, usize)>,
+    pub level: AIDifficulty,
+    /// Tactical effects of the move and how it was selected
+    pub reasons: Vec<String>,
+    /// Evaluation terms (Black's view) before and after the move
+    pub breakdown: Vec<(&'static str, i32, i32)>,
+    /// Best moves by evaluation, from the mover's point of view
+    pub candidates: Vec<CandidateMove>,
+    pub size: usize,
+}
+
+impl MoveExplanation {
+    /// Explains playing `point` for `color` on `board`, the position before the move
+    pub fn new(board: &Board, color: Stone, point: Option<(usize, usize)>, level: AIDifficulty) -> Self {
+        let candidates = candidate_moves(board, color, usize::MAX);
+        let mut reasons = Vec::new();
+        let mut after = board.clone();
+
+        match point {
+            None if candidates.is_empty() => reasons.push("no legal move, so pass".to_string()),
+            None => reasons.push("pass".to_string()),
+            Some((x, y)) => {
+                if level == AIDifficulty::Beginner {
+                    reasons.push(format!("random choice among {} legal moves", candidates.len()));
+                } else if let Some(rank) = candidates.iter().position(|c| c.point == (x, y)) {
+                    reasons.push(format!(
+                        "ranked {} of {} legal moves by evaluation (value {})",
+                        rank + 1,
+                        candidates.len(),
+                        candidates[rank].value
+                    ));
+                }
+                if let Some(points) = Evaluator::move_value(board, (x, y), color) {
+                    reasons.push(format!("worth about {:.1} points", points));
+                }
+                if let Ok(placement) = after.place_stone_detailed(x, y, color) {
+                    reasons.extend(tactical_reasons(board, &after, &placement, color, (x, y)));
+                }
+            }
+        }
+
+        let before_terms = Evaluator::breakdown(board);
+        let after_terms = Evaluator::breakdown(&after);
+        let breakdown = before_terms
+            .iter()
+            .zip(after_terms.iter())
+            .map(|(&(name, before), &(_, after))| (name, before, after))
+            .collect();
+
+        MoveExplanation {
+            color,
+            point,
+            level,
+            reasons,
+            breakdown,
+            candidates: candidates.into_iter().take(EXPLAIN_CANDIDATES).collect(),
+            size: board.size(),
+        }
+    }
+
+    /// Multi-line report, one `key: value` section per line
+    pub fn to_text(&self) -> String {
+        let color = if self.color == Stone::Black { "black" } else { "white" };
+        let vertex = self.point.map_or("pass".to_string(), |(x, y)| format_move(x, y, self.size));
+        let mut text = format!("move: {} {} ({} level)", color, vertex, self.level.name());
+
+        for reason in &self.reasons {
+            let _ = write!(text, "\nreason: {}", reason);
+        }
+        // The move generators score whole positions; no pattern database is consulted
+        text.push_str("\npatterns: none");
+
+        let sign = if self.color == Stone::Black { 1 } else { -1 };
+        let (mut before_total, mut after_total) = (0, 0);
+        for &(name, before, after) in &self.breakdown {
+            let _ = write!(text, "\nvalue {}: {} -> {} ({:+})", name, before, after, sign * (after - before));
+            before_total += before;
+            after_total += after;
+        }
+        let _ = write!(
+            text,
+            "\nvalue total: {} -> {} ({:+})",
+            before_total,
+            after_total,
+            sign * (after_total - before_total)
+        );
+
+        let alternatives: Vec<String> = self
+            .candidates
+            .iter()
+            .map(|c| format!("{} {}", format_move(c.point.0, c.point.1, self.size), c.value))
+            .collect();
+        if !alternatives.is_empty() {
+            let _ = write!(text, "\ncandidates: {}", alternatives.join(", "));
+        }
+        text
+    }
+}
+
+/// Captures, ataris, rescues and connections caused by the move at `point`
+fn tactical_reasons(
+    before: &Board,
+    after: &Board,
+    placement: &Placement,
+    color: Stone,
+    point: (usize, usize),
+) -> Vec<String> {
+    let opponent = if color == Stone::Black { Stone::White } else { Stone::Black };
+    let size = before.size();
+    let vertex = |(x, y): (usize, usize)| format_move(x, y, size);
+    let mut reasons = Vec::new();
+
+    let captured = placement.captured.len();
+    if captured > 0 {
+        reasons.push(format!("captures {} stone{}", captured, if captured == 1 { "" } else { "s" }));
+    }
+
+    let mut seen: Vec<(usize, usize)> = Vec::new();
+    let mut friendly = 0;
+    for (nx, ny) in before.neighbors(point.0, point.1) {
+        if seen.contains(&(nx, ny)) {
+            continue;
+        }
+        let Some(group) = before.find_group(nx, ny) else { continue };
+        seen.extend(group.positions());
+        // Name strings by their top-left stone
+        let anchor = group.positions().iter().copied().min_by_key(|&(x, y)| (y, x)).unwrap_or((nx, ny));
+
+        if group.color() == color {
+            friendly += 1;
+            if group.liberties() == 1 && after.count_liberties(point.0, point.1) > 1 {
+                reasons.push(format!("saves {} from atari", vertex(anchor)));
+            }
+        } else if after.get_stone(nx, ny) == opponent && after.count_liberties(nx, ny) == 1 && group.liberties() > 1 {
+            reasons.push(format!("puts {} in atari", vertex(anchor)));
+        }
+    }
+    if friendly >= 2 {
+        reasons.push(format!("connects {} strings", friendly));
+    }
+    if after.count_liberties(point.0, point.1) == 1 && captured == 0 {
+        reasons.push("self-atari".to_string());
+    }
+
+    reasons
+}
@@ -3,19 +3,25 @@
 
 //! Eye pattern detection and analysis
 
+use std::fmt;
+
 use super::board::{Board, Stone};
+use super::profile::{self, Subsystem};
+use crate::patterns::eyes::eye_shape;
 
-/// Eye pattern data structure
+/// Eye data of one point of an eye space, as GNU Go reports it
 #[derive(Debug, Clone)]
 pub struct EyeData {
+    pub point: (usize, usize),           // The point described
     pub origin: (usize, usize),          // Origin of the eye space
     pub color: Stone,                     // Color that controls the eye
     pub esize: usize,                     // Number of eye intersections
     pub msize: usize,                     // Number of marginal intersections
-    pub value: EyeValue,                  // Eye value
-    pub marginal: bool,                   // Is this a marginal eye?
-    pub neighbors: usize,                 // Number of neighboring stones
-    pub marginal_neighbors: usize,        // Number of marginal neighbors
+    pub value: EyeValue,                  // Eye value of the whole space
+    pub marginal: bool,                   // Is this point marginal?
+    pub neighbors: usize,                 // Neighbors in the same eye space
+    pub marginal_neighbors: usize,        // Marginal neighbors in the eye space
+    pub vital_point: Option<(usize, usize)>, // Point deciding between min and max eyes
 }
 
 /// Eye value representation
@@ -26,12 +32,12 @@ pub struct EyeValue {
     pub is_eye: bool,
 }
 
-impl EyeValue {
-    pub fn to_string(&self) -> String {
+impl fmt::Display for EyeValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         if self.is_eye {
-            format!("{}.{}", self.min_eyes, self.max_eyes)
+            write!(f, "{}.{}", self.min_eyes, self.max_eyes)
         } else {
-            "0.0".to_string()
+            write!(f, "0.0")
         }
     }
 }
@@ -41,142 +47,211 @@ impl EyeValue {
 pub struct HalfEyeData {
     pub value: f32,                       // Topological eye value
     pub eye_type: HalfEyeType,            // Type of half-eye
-    pub attack_point: Option<(usize, usize)>, // Attack point if any
-    pub defense_point: Option<(usize, usize)>, // Defense point if any
+    pub attack_point: Option<(usize, usize)>, // Enemy move that spoils the eye further
+    pub defense_point: Option<(usize, usize)>, // Own move that makes the eye proper
 }
 
-/// Half-eye types
+/// Half-eye types, by the topological value of the point's diagonals
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum HalfEyeType {
+    /// A proper eye the enemy cannot spoil
     Normal,
+    /// A proper eye for now, with a diagonal the enemy can still take
     Marginal,
+    /// Enough diagonals are lost that the point is no eye at all
     False,
+    /// An eye if its owner moves first, a false eye otherwise
     Half,
     Unknown,
 }
 
+/// Topological values from which a point is a half eye and a false eye
+const HALF_EYE_VALUE: f32 = 1.5;
+const FALSE_EYE_VALUE: f32 = 2.0;
+
+impl HalfEyeType {
+    fn from_value(value: f32, contested: bool) -> Self {
+        if value >= FALSE_EYE_VALUE {
+            HalfEyeType::False
+        } else if value >= HALF_EYE_VALUE {
+            HalfEyeType::Half
+        } else if contested {
+            HalfEyeType::Marginal
+        } else {
+            HalfEyeType::Normal
+        }
+    }
+
+    /// How badly the eye is spoiled, from 0 for a proper eye
+    fn severity(&self) -> u8 {
+        match self {
+            HalfEyeType::Normal | HalfEyeType::Marginal => 0,
+            HalfEyeType::Half | HalfEyeType::Unknown => 1,
+            HalfEyeType::False => 2,
+        }
+    }
+}
+
 /// Eye pattern analyzer
 pub struct EyeAnalyzer {
     // Configuration and state for eye detection
 }
 
+impl Default for EyeAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl EyeAnalyzer {
     pub fn new() -> Self {
         EyeAnalyzer {}
     }
 
-    /// Analyze eye patterns for the entire board
+    /// Eye data for every point of every eye space of `color`
     pub fn analyze_eyes(&self, board: &Board, color: Stone) -> Vec<EyeData> {
         let mut eyes = Vec::new();
-        let size = board.size();
-        
-        // Simple eye detection algorithm
-        for y in 0..size {
-            for x in 0..size {
-                if let Some(eye) = self.detect_eye(board, x, y, color) {
-                    eyes.push(eye);
+        for space in eye_spaces(board, color) {
+            let marginals: Vec<(usize, usize)> =
+                space.iter().copied().filter(|&(x, y)| is_marginal(board, x, y, color)).collect();
+            let (esize, msize) = (space.len(), marginals.len());
+            let (mut value, vital_point) = eye_value(&space, msize);
+            // A one-point space is worth only what its diagonals leave of it
+            if let [(x, y)] = space[..] {
+                match self.half_eye(board, color, x, y).map(|half| half.eye_type) {
+                    Some(HalfEyeType::False) => value = EyeValue { min_eyes: 0, max_eyes: 0, is_eye: false },
+                    Some(HalfEyeType::Half) => value.min_eyes = 0,
+                    _ => {}
                 }
             }
+            for &point in &space {
+                let neighbors: Vec<(usize, usize)> =
+                    board.neighbors(point.0, point.1).into_iter().filter(|p| space.contains(p)).collect();
+                eyes.push(EyeData {
+                    point,
+                    origin: space[0],
+                    color,
+                    esize,
+                    msize,
+                    value,
+                    marginal: marginals.contains(&point),
+                    neighbors: neighbors.len(),
+                    marginal_neighbors: neighbors.iter().filter(|p| marginals.contains(p)).count(),
+                    vital_point,
+                });
+            }
         }
-        
         eyes
     }
 
-    /// Detect if a position is part of an eye
-    fn detect_eye(&self, board: &Board, x: usize, y: usize, color: Stone) -> Option<EyeData> {
-        if board.get_stone(x, y) != Stone::Empty {
+    /// Eye data of the point (x, y) as part of an eye space of `color`
+    pub fn eye_data(&self, board: &Board, color: Stone, x: usize, y: usize) -> Option<EyeData> {
+        self.analyze_eyes(board, color).into_iter().find(|eye| eye.point == (x, y))
+    }
+
+    /// Half-eye analysis of the empty point (x, y) as an eye of `color`,
+    /// none if an enemy stone is next to it
+    pub fn half_eye(&self, board: &Board, color: Stone, x: usize, y: usize) -> Option<HalfEyeData> {
+        let enemy = if color == Stone::Black { Stone::White } else { Stone::Black };
+        if board.get_stone(x, y) != Stone::Empty
+            || board.neighbors(x, y).iter().any(|&(nx, ny)| board.get_stone(nx, ny) == enemy)
+        {
             return None;
         }
+        let (value, contested) = topological_value(board, x, y, color);
+        let eye_type = HalfEyeType::from_value(value, !contested.is_empty());
 
-        // Check if this empty point is surrounded by stones of the same color
-        let mut neighbors = 0;
-        let mut enemy_neighbors = 0;
-        let mut empty_neighbors = 0;
-        
-        let directions = [(0, -1), (0, 1), (-1, 0), (1, 0)];
-        for &(dx, dy) in &directions {
-            let nx = x as isize + dx;
-            let ny = y as isize + dy;
-            
-            if nx >= 0 && nx < board.size() as isize && ny >= 0 && ny < board.size() as isize {
-                let nx = nx as usize;
-                let ny = ny as usize;
-                match board.get_stone(nx, ny) {
-                    Stone::Black => {
-                        if color == Stone::Black {
-                            neighbors += 1;
-                        } else {
-                            enemy_neighbors += 1;
-                        }
-                    }
-                    Stone::White => {
-                        if color == Stone::White {
-                            neighbors += 1;
-                        } else {
-                            enemy_neighbors += 1;
-                        }
-                    }
-                    Stone::Empty => empty_neighbors += 1,
+        // Moves on the contested diagonals, or capturing the stones there
+        let mut attack_point = None;
+        let mut defense_point = None;
+        for &(dx, dy) in &contested {
+            if board.get_stone(dx, dy) != Stone::Empty {
+                // Capturing the stone takes back its half of the diagonal
+                if defense_point.is_none() && eye_type.severity() > 0 && value - 0.5 < HALF_EYE_VALUE {
+                    defense_point = ladder_attack(board, (dx, dy), NET_LADDER_DEPTH);
                 }
+                continue;
+            }
+            let point = (dx, dy);
+            let after = |player: Stone| {
+                let mut next = board.clone();
+                next.place_stone(point.0, point.1, player).ok()?;
+                (next.get_stone(x, y) == Stone::Empty).then(|| {
+                    let (value, contested) = topological_value(&next, x, y, color);
+                    HalfEyeType::from_value(value, !contested.is_empty())
+                })
+            };
+            if defense_point.is_none() && eye_type.severity() > 0 && after(color).is_some_and(|t| t.severity() == 0) {
+                defense_point = Some(point);
+            }
+            if attack_point.is_none() && after(enemy).is_some_and(|t| t.severity() > eye_type.severity())
+            {
+                attack_point = Some(point);
             }
         }
+        Some(HalfEyeData { value, eye_type, attack_point, defense_point })
+    }
 
-        // Simple eye detection logic
-        if neighbors >= 3 && enemy_neighbors == 0 {
-            Some(EyeData {
-                origin: (x, y),
-                color,
-                esize: 1,
-                msize: 0,
-                value: EyeValue {
-                    min_eyes: 1,
-                    max_eyes: 1,
-                    is_eye: true,
-                },
-                marginal: empty_neighbors > 0,
-                neighbors,
-                marginal_neighbors: empty_neighbors,
-            })
-        } else {
-            None
-        }
+    /// Topological value of the empty point (x, y) as an eye of `color`,
+    /// from its diagonals: below 1.5 a real eye, 1.5 a half eye and from
+    /// 2 a false eye. None if an enemy stone is next to it
+    pub fn topological_eye(&self, board: &Board, color: Stone, x: usize, y: usize) -> Option<f32> {
+        self.half_eye(board, color, x, y).map(|half| half.value)
     }
 
-    /// Check if a move is a ladder attack
-    pub fn is_ladder_attack(&self, board: &Board, x: usize, y: usize) -> bool {
-        // Simple ladder detection: check if a string has exactly 2 liberties
-        // and if attacking it would be effective
-        
-        let stone = board.get_stone(x, y);
-        if stone == Stone::Empty {
-            return false;
+    /// Points of `eye_space` whose occupation decides how many eyes it
+    /// makes, for the attacker and the defender alike: the vital point of
+    /// a standard shape, none for a settled one, and otherwise the empty
+    /// points touching the most others of the space
+    pub fn vital_points(&self, board: &Board, eye_space: &[(usize, usize)]) -> Vec<(usize, usize)> {
+        if let Some((shape, vital)) = eye_shape(eye_space) {
+            return vital.filter(|_| shape.min_eyes < shape.max_eyes).into_iter().collect();
         }
-
-        // Count liberties
-        let liberties = board.count_liberties(x, y);
-        if liberties != 2 {
-            return false;
+        if eye_space.len() < 3 {
+            return Vec::new();
         }
+        let scored: Vec<((usize, usize), usize)> = eye_space
+            .iter()
+            .copied()
+            .filter(|&(x, y)| board.get_stone(x, y) == Stone::Empty)
+            .map(|(x, y)| ((x, y), board.neighbors(x, y).iter().filter(|p| eye_space.contains(p)).count()))
+            .collect();
+        let best = scored.iter().map(|&(_, count)| count).max().unwrap_or(0);
+        if best < 2 {
+            return Vec::new();
+        }
+        scored.into_iter().filter(|&(_, count)| count == best).map(|(point, _)| point).collect()
+    }
 
-        // TODO: Implement proper ladder analysis
-        // For now, return true for any string with 2 liberties
-        true
+    /// Check whether the string at (x, y), which must have two liberties,
+    /// is captured in a ladder read up to `depth` attacker moves
+    pub fn is_ladder_attack(&self, board: &Board, x: usize, y: usize, depth: usize) -> bool {
+        self.find_ladder_attack_point(board, x, y, depth).is_some()
     }
 
-    /// Find attack point for a ladder
-    pub fn find_ladder_attack_point(&self, board: &Board, x: usize, y: usize) -> Option<(usize, usize)> {
-        if !self.is_ladder_attack(board, x, y) {
+    /// Find the first attacker move of a working ladder against the
+    /// two-liberty string at (x, y)
+    pub fn find_ladder_attack_point(&self, board: &Board, x: usize, y: usize, depth: usize) -> Option<(usize, usize)> {
+        if board.get_stone(x, y) == Stone::Empty || board.count_liberties(x, y) != 2 {
             return None;
         }
+        profile::measure(Subsystem::Reading, || ladder_attack(board, (x, y), depth))
+    }
 
-        // Find the liberty that would capture the string
-        let liberties = board.find_liberties(x, y);
-        if liberties.is_empty() {
+    /// Whether the string at (x, y), with two or three liberties, is
+    /// captured in a net read up to `depth` net moves
+    pub fn is_net_attack(&self, board: &Board, x: usize, y: usize, depth: usize) -> bool {
+        self.find_net_attack_point(board, x, y, depth).is_some()
+    }
+
+    /// Find a loose attacker move, not on a liberty, after which the string
+    /// at (x, y) cannot escape however it runs: each extension is met by
+    /// a ladder or by another net
+    pub fn find_net_attack_point(&self, board: &Board, x: usize, y: usize, depth: usize) -> Option<(usize, usize)> {
+        if board.get_stone(x, y) == Stone::Empty || !(2..=3).contains(&board.count_liberties(x, y)) {
             return None;
         }
-
-        // Return the first liberty as the attack point
-        Some(liberties[0])
+        profile::measure(Subsystem::Reading, || net_attack(board, (x, y), depth))
     }
 
     /// Load eye patterns from file (placeholder)
@@ -185,3 +260,257 @@ impl EyeAnalyzer {
         Ok(())
     }
 }
+
+/// Eye spaces larger than this are open areas, not eyes
+const MAX_EYE_SPACE: usize = 16;
+
+/// Areas of empty points bordering `color`, with the enemy strings
+/// that have no liberties outside them, small enough to make eyes; each
+/// sorted from the top-left, its origin first
+fn eye_spaces(board: &Board, color: Stone) -> Vec<Vec<(usize, usize)>> {
+    let size = board.size();
+    let mut seen = vec![vec![false; size]; size];
+    let mut spaces = Vec::new();
+    for y in 1..=size {
+        for x in 1..=size {
+            if seen[y - 1][x - 1] || board.get_stone(x, y) != Stone::Empty {
+                continue;
+            }
+            seen[y - 1][x - 1] = true;
+            let mut space = vec![(x, y)];
+            let mut i = 0;
+            while i < space.len() {
+                let (px, py) = space[i];
+                for (nx, ny) in board.neighbors(px, py) {
+                    if !seen[ny - 1][nx - 1] && board.get_stone(nx, ny) == Stone::Empty {
+                        seen[ny - 1][nx - 1] = true;
+                        space.push((nx, ny));
+                    }
+                }
+                i += 1;
+            }
+            let bordered = space
+                .iter()
+                .any(|&(px, py)| board.neighbors(px, py).iter().any(|&(nx, ny)| board.get_stone(nx, ny) == color));
+            if space.len() > MAX_EYE_SPACE || !bordered {
+                continue;
+            }
+            let mut inside = Vec::new();
+            for &(px, py) in &space {
+                for (nx, ny) in board.neighbors(px, py) {
+                    let stone = board.get_stone(nx, ny);
+                    if stone == Stone::Empty || stone == color || inside.contains(&(nx, ny)) {
+                        continue;
+                    }
+                    if board.find_liberties(nx, ny).iter().all(|liberty| space.contains(liberty)) {
+                        inside.extend(board.find_group(nx, ny).map(|group| group.positions().to_vec()).unwrap_or_default());
+                    }
+                }
+            }
+            space.extend(inside);
+            space.sort_by_key(|&(px, py)| (py, px));
+            spaces.push(space);
+        }
+    }
+    spaces
+}
+
+/// Whether the enemy has a say at the point (x, y) of an eye space of
+/// `color`: an enemy stone, a point next to one, or a point whose
+/// diagonals the enemy holds enough of to make the eye false
+fn is_marginal(board: &Board, x: usize, y: usize, color: Stone) -> bool {
+    let enemy = if color == Stone::Black { Stone::White } else { Stone::Black };
+    if board.get_stone(x, y) == enemy || board.neighbors(x, y).iter().any(|&(nx, ny)| board.get_stone(nx, ny) == enemy) {
+        return true;
+    }
+    let size = board.size() as isize;
+    let (mut on_board, mut held) = (0, 0);
+    for (dx, dy) in [(-1, -1), (1, -1), (-1, 1), (1, 1)] {
+        let (dx, dy) = (x as isize + dx, y as isize + dy);
+        if dx < 1 || dy < 1 || dx > size || dy > size {
+            continue;
+        }
+        on_board += 1;
+        if board.get_stone(dx as usize, dy as usize) == enemy {
+            held += 1;
+        }
+    }
+    // One enemy diagonal spoils an eye on the edge, two in the middle
+    held >= if on_board == 4 { 2 } else { 1 }
+}
+
+/// Points free of the enemy that make two eyes whoever moves first
+const SURE_TWO_EYES: usize = 7;
+
+/// Topological value of the point (x, y) as an eye of `color`: 1 for
+/// each diagonal held by a safe enemy stone, 0.5 for each empty diagonal
+/// the enemy can safely play or enemy diagonal stone that can be captured
+/// in a ladder, and 1 more on the edge, where fewer diagonals are needed.
+/// Also returns the diagonals still in dispute
+fn topological_value(board: &Board, x: usize, y: usize, color: Stone) -> (f32, Vec<(usize, usize)>) {
+    let size = board.size() as isize;
+    let enemy = if color == Stone::Black { Stone::White } else { Stone::Black };
+    let (mut value, mut contested, mut on_edge) = (0.0, Vec::new(), false);
+    for (dx, dy) in [(-1, -1), (1, -1), (-1, 1), (1, 1)] {
+        let (dx, dy) = (x as isize + dx, y as isize + dy);
+        if dx < 1 || dy < 1 || dx > size || dy > size {
+            on_edge = true;
+            continue;
+        }
+        let (dx, dy) = (dx as usize, dy as usize);
+        match board.get_stone(dx, dy) {
+            stone if stone == color => {}
+            Stone::Empty => {
+                let mut after = board.clone();
+                if after.place_stone(dx, dy, enemy).is_ok() && after.count_liberties(dx, dy) >= 2 {
+                    value += 0.5;
+                    contested.push((dx, dy));
+                }
+            }
+            _ if ladder_attack(board, (dx, dy), NET_LADDER_DEPTH).is_some() => {
+                value += 0.5;
+                contested.push((dx, dy));
+            }
+            _ => value += 1.0,
+        }
+    }
+    if on_edge {
+        value += 1.0;
+    }
+    (value, contested)
+}
+
+/// Eyes the eye space made of `space` yields, and the point that decides
+/// between fewer and more. A space free of the enemy is worth what its
+/// standard shape is; otherwise none if all of it is marginal, at most
+/// one from a space of two points or fewer, and two for certain once at
+/// least [`SURE_TWO_EYES`] points are free of the enemy
+fn eye_value(space: &[(usize, usize)], msize: usize) -> (EyeValue, Option<(usize, usize)>) {
+    if msize == 0 {
+        if let Some((shape, vital)) = eye_shape(space) {
+            let (min_eyes, max_eyes) = (shape.min_eyes, shape.max_eyes);
+            let value = EyeValue { min_eyes, max_eyes, is_eye: max_eyes > 0 };
+            return (value, vital.filter(|_| min_eyes < max_eyes));
+        }
+    }
+    let solid = space.len() - msize;
+    let (min_eyes, max_eyes) = match (solid, space.len()) {
+        (0, _) => (0, 0),
+        (_, 1..=2) => (1, 1),
+        (solid, _) if solid >= SURE_TWO_EYES => (2, 2),
+        _ => (1, 2),
+    };
+    (EyeValue { min_eyes, max_eyes, is_eye: max_eyes > 0 }, None)
+}
+
+/// Attacker move that captures the string at `target` by ladder, reading
+/// at most `depth` ataris
+fn ladder_attack(board: &Board, target: (usize, usize), depth: usize) -> Option<(usize, usize)> {
+    let liberties = board.find_liberties(target.0, target.1);
+    if liberties.len() == 1 {
+        return Some(liberties[0]);
+    }
+    if liberties.len() != 2 || depth == 0 {
+        return None;
+    }
+
+    let attacker = match board.get_stone(target.0, target.1) {
+        Stone::Black => Stone::White,
+        Stone::White => Stone::Black,
+        Stone::Empty => return None,
+    };
+    liberties.into_iter().find(|&(lx, ly)| {
+        let mut next = board.clone();
+        next.place_stone(lx, ly, attacker).is_ok()
+            && next.count_liberties(target.0, target.1) == 1
+            && !ladder_escapes(&next, target, depth - 1)
+    })
+}
+
+/// Ataris read in the ladders that answer each escape from a net
+const NET_LADDER_DEPTH: usize = 40;
+
+/// Attacker move two points away from the string at `target` that
+/// captures it however it escapes, reading at most `depth` net moves
+fn net_attack(board: &Board, target: (usize, usize), depth: usize) -> Option<(usize, usize)> {
+    if depth == 0 {
+        return None;
+    }
+    let attacker = match board.get_stone(target.0, target.1) {
+        Stone::Black => Stone::White,
+        Stone::White => Stone::Black,
+        Stone::Empty => return None,
+    };
+    let liberties = board.find_liberties(target.0, target.1);
+    let mut candidates: Vec<(usize, usize)> = liberties
+        .iter()
+        .flat_map(|&(lx, ly)| board.neighbors(lx, ly))
+        .filter(|&(cx, cy)| board.get_stone(cx, cy) == Stone::Empty && !liberties.contains(&(cx, cy)))
+        .collect();
+    candidates.sort();
+    candidates.dedup();
+
+    candidates.into_iter().find(|&(cx, cy)| {
+        let mut next = board.clone();
+        next.place_stone(cx, cy, attacker).is_ok()
+            && next.count_liberties(cx, cy) >= 2
+            && !net_escapes(&next, target, depth - 1)
+    })
+}
+
+/// Whether the string at `target`, after a net move, gets out: by an
+/// extension or a capture that leaves it neither in atari, nor in a
+/// ladder, nor in another net
+fn net_escapes(board: &Board, target: (usize, usize), depth: usize) -> bool {
+    escape_moves(board, target).into_iter().any(|(mx, my)| {
+        let mut next = board.clone();
+        if next.place_stone(mx, my, board.get_stone(target.0, target.1)).is_err() {
+            return false;
+        }
+        match next.count_liberties(target.0, target.1) {
+            0 | 1 => false,
+            2 => ladder_attack(&next, target, NET_LADDER_DEPTH).is_none() && net_attack(&next, target, depth).is_none(),
+            3 => net_attack(&next, target, depth).is_none(),
+            _ => true,
+        }
+    })
+}
+
+/// Liberties of the string at `target` and the last liberties of the
+/// attacker strings next to it
+fn escape_moves(board: &Board, target: (usize, usize)) -> Vec<(usize, usize)> {
+    let defender = board.get_stone(target.0, target.1);
+    let Some(group) = board.find_group(target.0, target.1) else {
+        return Vec::new();
+    };
+
+    let mut moves = board.find_liberties(target.0, target.1);
+    for &(sx, sy) in group.positions() {
+        for (nx, ny) in board.neighbors(sx, sy) {
+            let stone = board.get_stone(nx, ny);
+            if stone != Stone::Empty && stone != defender && board.count_liberties(nx, ny) == 1 {
+                moves.extend(board.find_liberties(nx, ny));
+            }
+        }
+    }
+    moves.sort();
+    moves.dedup();
+    moves
+}
+
+/// Whether the string at `target`, in atari, escapes by extending or by
+/// capturing an adjacent attacker string that is itself in atari
+fn ladder_escapes(board: &Board, target: (usize, usize), depth: usize) -> bool {
+    let defender = board.get_stone(target.0, target.1);
+    escape_moves(board, target).into_iter().any(|(mx, my)| {
+        let mut next = board.clone();
+        if next.place_stone(mx, my, defender).is_err() {
+            return false;
+        }
+        match next.count_liberties(target.0, target.1) {
+            0 | 1 => false,
+            2 => ladder_attack(&next, target, depth).is_none(),
+            _ => true,
+        }
+    })
+}
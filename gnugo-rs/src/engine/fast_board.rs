@@ -0,0 +1,378 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Lightweight board for Monte Carlo playouts
+//!
+//! Points are indices into a padded array with a border ring, and groups
+//! are circular stone lists carrying pseudo-liberty counts: a liberty
+//! shared by two stones counts twice. The sum and sum of squares of the
+//! liberty indices tell when all pseudo-liberties are the same point, so
+//! atari is exact. Nothing is allocated after construction and there is
+//! no history; only legal moves, captures and simple ko are tracked.
+
+use rand::Rng;
+
+use crate::engine::board::{Board, Stone};
+
+const EMPTY: u8 = 0;
+const BLACK: u8 = 1;
+const WHITE: u8 = 2;
+const BORDER: u8 = 3;
+
+fn cell_of(stone: Stone) -> u8 {
+    match stone {
+        Stone::Empty => EMPTY,
+        Stone::Black => BLACK,
+        Stone::White => WHITE,
+    }
+}
+
+/// Pseudo-liberties of a group
+#[derive(Debug, Clone, Copy, Default)]
+struct Liberties {
+    count: u32,
+    sum: u64,
+    sum_squares: u64,
+}
+
+impl Liberties {
+    fn add(&mut self, point: usize) {
+        let p = point as u64;
+        self.count += 1;
+        self.sum += p;
+        self.sum_squares += p * p;
+    }
+
+    fn remove(&mut self, point: usize) {
+        let p = point as u64;
+        self.count -= 1;
+        self.sum -= p;
+        self.sum_squares -= p * p;
+    }
+
+    fn merge(&mut self, other: Liberties) {
+        self.count += other.count;
+        self.sum += other.sum;
+        self.sum_squares += other.sum_squares;
+    }
+
+    /// Exactly one distinct liberty
+    fn in_atari(&self) -> bool {
+        self.count > 0 && u64::from(self.count) * self.sum_squares == self.sum * self.sum
+    }
+}
+
+/// Board for playouts; see the module documentation
+#[derive(Debug, Clone)]
+pub struct FastBoard {
+    size: usize,
+    stride: usize,
+    cells: Vec<u8>,
+    /// Representative stone of each stone's group
+    group: Vec<usize>,
+    /// Next stone of the same group, circular
+    next: Vec<usize>,
+    /// Stones per group, by representative
+    stones: Vec<u32>,
+    /// Liberties per group, by representative
+    liberties: Vec<Liberties>,
+    /// Empty points, and where each point sits in that list
+    empty: Vec<usize>,
+    empty_index: Vec<usize>,
+    ko: Option<usize>,
+}
+
+impl FastBoard {
+    /// Copies the stones and ko point of `board`
+    pub fn from_board(board: &Board) -> Self {
+        let size = board.size();
+        let stride = size + 2;
+        let points = stride * stride;
+        let mut fast = FastBoard {
+            size,
+            stride,
+            cells: vec![BORDER; points],
+            group: (0..points).collect(),
+            next: (0..points).collect(),
+            stones: vec![0; points],
+            liberties: vec![Liberties::default(); points],
+            empty: Vec::with_capacity(size * size),
+            empty_index: vec![0; points],
+            ko: None,
+        };
+        for y in 1..=size {
+            for x in 1..=size {
+                let p = fast.index(x, y);
+                fast.cells[p] = EMPTY;
+                fast.empty_index[p] = fast.empty.len();
+                fast.empty.push(p);
+            }
+        }
+        for y in 1..=size {
+            for x in 1..=size {
+                let stone = board.get_stone(x, y);
+                if stone != Stone::Empty {
+                    fast.place(fast.index(x, y), cell_of(stone));
+                }
+            }
+        }
+        fast.ko = board.get_ko_point().map(|(x, y)| fast.index(x, y));
+        fast
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    fn index(&self, x: usize, y: usize) -> usize {
+        y * self.stride + x
+    }
+
+    fn neighbors(&self, p: usize) -> [usize; 4] {
+        [p - 1, p + 1, p - self.stride, p + self.stride]
+    }
+
+    /// Stone at (x, y), 1-based
+    pub fn get_stone(&self, x: usize, y: usize) -> Stone {
+        if x == 0 || y == 0 || x > self.size || y > self.size {
+            return Stone::Empty;
+        }
+        match self.cells[self.index(x, y)] {
+            BLACK => Stone::Black,
+            WHITE => Stone::White,
+            _ => Stone::Empty,
+        }
+    }
+
+    /// Points still empty
+    pub fn empty_points(&self) -> usize {
+        self.empty.len()
+    }
+
+    /// Whether `color` may play (x, y): empty, not the ko point and not suicide
+    pub fn is_legal(&self, x: usize, y: usize, color: Stone) -> bool {
+        x >= 1 && y >= 1 && x <= self.size && y <= self.size && color != Stone::Empty
+            && self.legal_at(self.index(x, y), cell_of(color))
+    }
+
+    fn legal_at(&self, p: usize, color: u8) -> bool {
+        if self.cells[p] != EMPTY || self.ko == Some(p) {
+            return false;
+        }
+        self.neighbors(p).into_iter().any(|n| match self.cells[n] {
+            EMPTY => true,
+            BORDER => false,
+            // Joining a friendly group that keeps another liberty, or
+            // capturing an enemy group whose last liberty this is
+            c => (c == color) != self.liberties[self.group[n]].in_atari(),
+        })
+    }
+
+    /// Plays `color` at (x, y) if legal; returns whether it did
+    pub fn play(&mut self, x: usize, y: usize, color: Stone) -> bool {
+        if !self.is_legal(x, y, color) {
+            return false;
+        }
+        self.place(self.index(x, y), cell_of(color));
+        true
+    }
+
+    /// Puts a stone at `p` without legality checks, merging and capturing
+    fn place(&mut self, p: usize, color: u8) {
+        let opponent = BLACK + WHITE - color;
+        self.remove_empty(p);
+        self.cells[p] = color;
+        self.group[p] = p;
+        self.next[p] = p;
+        self.stones[p] = 1;
+        self.liberties[p] = Liberties::default();
+
+        for n in self.neighbors(p) {
+            match self.cells[n] {
+                EMPTY => self.liberties[p].add(n),
+                BORDER => {}
+                _ => self.liberties[self.group[n]].remove(p),
+            }
+        }
+        for n in self.neighbors(p) {
+            if self.cells[n] == color && self.group[n] != self.group[p] {
+                self.merge(self.group[p], self.group[n]);
+            }
+        }
+
+        let mut captured = 0;
+        let mut captured_at = 0;
+        for n in self.neighbors(p) {
+            if self.cells[n] == opponent && self.liberties[self.group[n]].count == 0 {
+                captured += self.stones[self.group[n]];
+                captured_at = n;
+                self.capture(self.group[n]);
+            }
+        }
+
+        let own = self.group[p];
+        self.ko = (captured == 1 && self.stones[own] == 1 && self.liberties[own].in_atari()).then_some(captured_at);
+    }
+
+    /// Joins the groups led by `a` and `b`, keeping the larger one's leader
+    fn merge(&mut self, a: usize, b: usize) {
+        let (keep, gone) = if self.stones[a] >= self.stones[b] { (a, b) } else { (b, a) };
+        let mut s = gone;
+        loop {
+            self.group[s] = keep;
+            s = self.next[s];
+            if s == gone {
+                break;
+            }
+        }
+        self.next.swap(keep, gone);
+        self.stones[keep] += self.stones[gone];
+        let liberties = self.liberties[gone];
+        self.liberties[keep].merge(liberties);
+    }
+
+    /// Removes the group led by `leader`, giving liberties back to its neighbors
+    fn capture(&mut self, leader: usize) {
+        let mut s = leader;
+        loop {
+            self.cells[s] = EMPTY;
+            self.empty_index[s] = self.empty.len();
+            self.empty.push(s);
+            s = self.next[s];
+            if s == leader {
+                break;
+            }
+        }
+        loop {
+            for n in self.neighbors(s) {
+                if self.cells[n] == BLACK || self.cells[n] == WHITE {
+                    self.liberties[self.group[n]].add(s);
+                }
+            }
+            s = self.next[s];
+            if s == leader {
+                break;
+            }
+        }
+    }
+
+    fn remove_empty(&mut self, p: usize) {
+        let i = self.empty_index[p];
+        let last = self.empty.pop().expect("placing on a full board");
+        if last != p {
+            self.empty[i] = last;
+            self.empty_index[last] = i;
+        }
+    }
+
+    /// Whether every on-board neighbor of `p` is a stone of `color`
+    fn is_eye(&self, p: usize, color: u8) -> bool {
+        self.neighbors(p).into_iter().all(|n| self.cells[n] == color || self.cells[n] == BORDER)
+    }
+
+    /// Whether `color` at `p` would put stones already on the board into
+    /// atari without capturing anything. A lone stone may still be thrown
+    /// in, as in most nakade and throw-ins
+    fn is_self_atari(&self, p: usize, color: u8) -> bool {
+        let mut liberty = None;
+        let mut joins = false;
+        for n in self.neighbors(p) {
+            match self.cells[n] {
+                EMPTY if liberty.is_some() => return false,
+                EMPTY => liberty = Some(n),
+                BORDER => {}
+                c if c == color => joins = true,
+                // Capturing gives liberties
+                _ if self.liberties[self.group[n]].in_atari() => return false,
+                _ => {}
+            }
+        }
+        if !joins {
+            return false;
+        }
+        for n in self.neighbors(p) {
+            if self.cells[n] != color {
+                continue;
+            }
+            let leader = self.group[n];
+            let mut s = leader;
+            loop {
+                for l in self.neighbors(s) {
+                    if self.cells[l] == EMPTY && l != p && liberty != Some(l) {
+                        if liberty.is_some() {
+                            return false;
+                        }
+                        liberty = Some(l);
+                    }
+                }
+                s = self.next[s];
+                if s == leader {
+                    break;
+                }
+            }
+        }
+        true
+    }
+
+    /// Whether a playout may choose `p` for `color`: legal, not one of its
+    /// own eyes and no self-atari
+    fn is_policy_move(&self, p: usize, color: u8) -> bool {
+        !self.is_eye(p, color) && self.legal_at(p, color) && !self.is_self_atari(p, color)
+    }
+
+    /// Plays a random legal move for `color` that neither fills one of its
+    /// own eyes nor puts its stones into atari; returns whether one was found
+    pub fn play_random(&mut self, color: Stone, rng: &mut impl Rng) -> bool {
+        self.random_move(color, rng).is_some()
+    }
+
+    /// Like [`FastBoard::play_random`], returning the point played
+    pub fn random_move(&mut self, color: Stone, rng: &mut impl Rng) -> Option<(usize, usize)> {
+        let color = cell_of(color);
+        if self.empty.is_empty() {
+            return None;
+        }
+        let start = rng.gen_range(0..self.empty.len());
+        for i in 0..self.empty.len() {
+            let p = self.empty[(start + i) % self.empty.len()];
+            if self.is_policy_move(p, color) {
+                self.place(p, color);
+                return Some((p % self.stride, p / self.stride));
+            }
+        }
+        None
+    }
+
+    /// Whether (x, y) is a legal move for `color` that neither fills one
+    /// of its own eyes nor is a self-atari, the moves playouts choose from
+    pub fn is_playout_move(&self, x: usize, y: usize, color: Stone) -> bool {
+        self.is_legal(x, y, color) && self.is_policy_move(self.index(x, y), cell_of(color))
+    }
+
+    /// Plays random moves from `to_move` on until both sides pass or
+    /// `max_moves` moves have been tried
+    pub fn playout(&mut self, to_move: Stone, max_moves: usize, rng: &mut impl Rng) {
+        let mut color = to_move;
+        let mut passes = 0;
+        for _ in 0..max_moves {
+            passes = if self.play_random(color, rng) { 0 } else { passes + 1 };
+            if passes >= 2 {
+                break;
+            }
+            color = if color == Stone::Black { Stone::White } else { Stone::Black };
+        }
+    }
+
+    /// Owner of (x, y) at the end of a playout: the stone there, or the
+    /// color of all neighbors of an empty point; `Empty` for dame
+    pub fn owner(&self, x: usize, y: usize) -> Stone {
+        let p = self.index(x, y);
+        match self.cells[p] {
+            EMPTY => [BLACK, WHITE]
+                .into_iter()
+                .find(|&c| self.is_eye(p, c))
+                .map_or(Stone::Empty, |c| if c == BLACK { Stone::Black } else { Stone::White }),
+            _ => self.get_stone(x, y),
+        }
+    }
+}
@@ -0,0 +1,148 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Alive, dead or seki status of every string at the end of the game
+
+use crate::engine::board::{Board, Stone};
+use crate::engine::dragon::Dragons;
+use crate::engine::owl::OwlStatus;
+use crate::engine::scoring::{dead_stones, seki_stones};
+use crate::engine::tsumego::{LifeStatus, TsumegoSolver};
+
+/// Strings judged alive with at most this many liberties are read out
+const READ_LIBERTIES: usize = 2;
+
+/// Node budget of each reading; unproven strings keep their static status
+const READ_NODES: usize = 1_000;
+
+/// Final status of a string
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StringStatus {
+    Alive,
+    Dead,
+    Seki,
+}
+
+impl StringStatus {
+    /// Name as GTP `final_status_list` takes it
+    pub fn name(&self) -> &'static str {
+        match self {
+            StringStatus::Alive => "alive",
+            StringStatus::Dead => "dead",
+            StringStatus::Seki => "seki",
+        }
+    }
+
+    /// Parses "alive", "dead" or "seki" (case-insensitive)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "alive" => Some(StringStatus::Alive),
+            "dead" => Some(StringStatus::Dead),
+            "seki" => Some(StringStatus::Seki),
+            _ => None,
+        }
+    }
+}
+
+/// Status of every stone on a finished board
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FinalStatus {
+    size: usize,
+    /// By point, row by row; `None` for empty points
+    statuses: Vec<Option<StringStatus>>,
+}
+
+impl FinalStatus {
+    /// Classifies every string: seki from [`seki_stones`], dead from
+    /// [`dead_stones`] or from dragons owl reads as dead, and the rest
+    /// alive, except that strings short of liberties are read out and
+    /// marked dead if they cannot be saved
+    pub fn analyze(board: &Board) -> Self {
+        let size = board.size();
+        let mut status = FinalStatus { size, statuses: vec![None; size * size] };
+        for y in 1..=size {
+            for x in 1..=size {
+                if board.get_stone(x, y) != Stone::Empty {
+                    status.set(x, y, StringStatus::Alive);
+                }
+            }
+        }
+        for (x, y) in seki_stones(board) {
+            status.set(x, y, StringStatus::Seki);
+        }
+        for (x, y) in dead_stones(board) {
+            status.set(x, y, StringStatus::Dead);
+        }
+        // Owl leaves true seki alive, so its dead dragons overrule the
+        // shared-liberty seki guess
+        for (x, y) in Dragons::compute(board).stones(OwlStatus::Dead) {
+            status.set(x, y, StringStatus::Dead);
+        }
+
+        for y in 1..=size {
+            for x in 1..=size {
+                if status.status(x, y) != Some(StringStatus::Alive) {
+                    continue;
+                }
+                let Some(group) = board.find_group(x, y) else { continue };
+                // Visit each string once, from its first stone
+                if group.positions().iter().min_by_key(|&&(px, py)| (py, px)) != Some(&(x, y)) {
+                    continue;
+                }
+                if group.liberties() <= READ_LIBERTIES && cannot_be_saved(board, (x, y)) {
+                    for &(px, py) in group.positions() {
+                        status.set(px, py, StringStatus::Dead);
+                    }
+                }
+            }
+        }
+        status
+    }
+
+    fn set(&mut self, x: usize, y: usize, value: StringStatus) {
+        self.statuses[(y - 1) * self.size + x - 1] = Some(value);
+    }
+
+    /// Status of the stone at (x, y); `None` for an empty or off-board point
+    pub fn status(&self, x: usize, y: usize) -> Option<StringStatus> {
+        if x == 0 || y == 0 || x > self.size || y > self.size {
+            return None;
+        }
+        self.statuses[(y - 1) * self.size + x - 1]
+    }
+
+    /// Stones with `status`, sorted
+    pub fn stones(&self, status: StringStatus) -> Vec<(usize, usize)> {
+        let mut stones: Vec<(usize, usize)> = (1..=self.size)
+            .flat_map(|y| (1..=self.size).map(move |x| (x, y)))
+            .filter(|&(x, y)| self.status(x, y) == Some(status))
+            .collect();
+        stones.sort();
+        stones
+    }
+
+    /// Dead stones, to take off before scoring
+    pub fn dead(&self) -> Vec<(usize, usize)> {
+        self.stones(StringStatus::Dead)
+    }
+}
+
+/// Whether the opponent, moving first, captures the string at `target`
+/// however it defends. Moves are read only on the string's liberties and
+/// the empty points next to them.
+fn cannot_be_saved(board: &Board, target: (usize, usize)) -> bool {
+    let mut region = Vec::new();
+    for (x, y) in board.find_liberties(target.0, target.1) {
+        region.push((x, y));
+        region.extend(board.neighbors(x, y).into_iter().filter(|&(nx, ny)| board.get_stone(nx, ny) == Stone::Empty));
+    }
+    region.sort();
+    region.dedup();
+
+    let attacker = match board.get_stone(target.0, target.1) {
+        Stone::Black => Stone::White,
+        _ => Stone::Black,
+    };
+    let mut solver = TsumegoSolver::new(board, target, READ_NODES).with_region(region);
+    solver.solve(board, attacker).status == LifeStatus::Dead
+}
@@ -3,8 +3,23 @@
 
 //! Game logic and state management
 
+use std::collections::HashSet;
+use std::time::Duration;
+
+use crate::engine::aftermath::aftermath_move;
+use crate::engine::analysis;
 use crate::engine::board::Board;
 use crate::engine::board::Stone;
+use crate::engine::clock::Clock;
+use crate::engine::final_status::FinalStatus;
+use crate::engine::rules::{fixed_handicap, GameRules, KoRule, ScoringRule, Variant};
+use crate::engine::scoring::{final_score, Score};
+use crate::engine::worm::Worms;
+use crate::json::JsonValue;
+
+/// Times a whole-board position may occur with the same side to move
+/// before the game ends without result; superko forbids the repetition
+const CYCLE_LIMIT: usize = 3;
 
 /// Represents the state of a Go game
 #[derive(Debug, Clone)]
@@ -13,7 +28,7 @@ pub struct Game {
     pub board: Board,
     /// Current player to move (true = black, false = white)
     pub current_player: bool,
-    /// Game history for undo functionality
+    /// The state before every move, pass and resignation, for undo
     pub history: Vec<GameState>,
     /// Captured stones count
     pub captured_stones: [u32; 2], // [black, white]
@@ -25,14 +40,111 @@ pub struct Game {
     winner: Option<Stone>,
     /// Komi (compensation points for white)
     pub komi: f32,
+    /// Rule variant; Capture Go ends at the first capture target reached
+    pub variant: Variant,
+    /// Ko and suicide rules that `make_move` enforces
+    pub rules: GameRules,
+    /// Handicap stones Black received; 0 for an even game
+    pub handicap: usize,
+    /// Rengo team members in playing order, [black, white]; empty for a single player
+    teams: [Vec<String>; 2],
+    /// Moves and passes made by each color, [black, white], to rotate team members
+    turns: [usize; 2],
+    /// Thinking times reported through [`Game::record_move_time`]
+    move_times: Vec<Duration>,
+    /// Prisoners handed over by passing, [black, white], under rules with
+    /// pass stones
+    pass_stones: [u32; 2],
+    /// Moves and passes in the order they were made
+    move_list: Vec<GameMove>,
+    /// Time each side has left; charged by [`Game::record_move_time`]
+    pub clock: Clock,
+}
+
+/// One move or pass of a game
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameMove {
+    /// 1 for the first move; passes are numbered too
+    pub number: usize,
+    pub color: Stone,
+    /// 1-based (x, y); `None` for a pass
+    pub point: Option<(usize, usize)>,
+    /// Opponent stones the move took off the board
+    pub captures: u32,
+}
+
+/// Summary of a game so far, from [`Game::statistics`]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct GameStatistics {
+    /// Stones played
+    pub moves: usize,
+    pub passes: usize,
+    /// Prisoners taken, [black, white]
+    pub captures: [u32; 2],
+    /// Most stones removed by a single move
+    pub largest_capture: u32,
+    /// Kos taken, each ko counted once however often it changed hands
+    pub ko_fights: usize,
+    /// Mean of the recorded move times; `None` if none were recorded
+    pub average_move_time: Option<Duration>,
+}
+
+impl GameStatistics {
+    /// One `key value` line per statistic, as GTP and the CLI print them
+    pub fn to_text(&self) -> String {
+        let mut lines = vec![
+            format!("moves {}", self.moves),
+            format!("passes {}", self.passes),
+            format!("captures black {} white {}", self.captures[0], self.captures[1]),
+            format!("largest_capture {}", self.largest_capture),
+            format!("ko_fights {}", self.ko_fights),
+        ];
+        if let Some(time) = self.average_move_time {
+            lines.push(format!("average_move_time {:.2}", time.as_secs_f64()));
+        }
+        lines.join("\n")
+    }
+
+    /// `{moves, passes, captures: {black, white}, largest_capture, ko_fights, average_move_time}`
+    pub fn to_json(&self) -> JsonValue {
+        JsonValue::object(vec![
+            ("moves", self.moves.into()),
+            ("passes", self.passes.into()),
+            (
+                "captures",
+                JsonValue::object(vec![
+                    ("black", (self.captures[0] as usize).into()),
+                    ("white", (self.captures[1] as usize).into()),
+                ]),
+            ),
+            ("largest_capture", (self.largest_capture as usize).into()),
+            ("ko_fights", self.ko_fights.into()),
+            ("average_move_time", self.average_move_time.map(|t| t.as_secs_f64()).into()),
+        ])
+    }
 }
 
 /// Game status
 #[derive(Debug, Clone, Copy, PartialEq)]
-enum GameStatus {
+pub enum GameStatus {
     InProgress,
     Ended,
     Resigned,
+    /// A Capture Go player took the target number of stones
+    CaptureTarget,
+    /// A player ran out of time
+    TimeOut,
+    /// The players kept repeating a position, as in triple ko
+    NoResult,
+}
+
+/// What was done from a [`GameState`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// A stone at (x, y), 1-based
+    Play(usize, usize),
+    Pass,
+    Resign,
 }
 
 /// Represents a snapshot of game state
@@ -41,6 +153,17 @@ pub struct GameState {
     pub board: Board,
     pub current_player: bool,
     pub captured_stones: [u32; 2],
+    pub turns: [usize; 2],
+    pub pass_count: u32,
+    /// The move, pass or resignation made from this state
+    pub action: Action,
+    status: GameStatus,
+    winner: Option<Stone>,
+    pass_stones: [u32; 2],
+    /// Both clocks before the action was charged
+    clock: Clock,
+    /// Number of thinking times recorded before the action
+    move_times: usize,
 }
 
 impl Game {
@@ -55,9 +178,23 @@ impl Game {
             status: GameStatus::InProgress,
             winner: None,
             komi: 6.5, // Standard komi
+            variant: Variant::Standard,
+            rules: GameRules::default(),
+            handicap: 0,
+            teams: [Vec::new(), Vec::new()],
+            turns: [0, 0],
+            move_times: Vec::new(),
+            pass_stones: [0, 0],
+            move_list: Vec::new(),
+            clock: Clock::default(),
         }
     }
     
+    /// Creates a new game played under `rules`, with their komi
+    pub fn with_rules(size: usize, rules: GameRules) -> Self {
+        Game { rules, komi: rules.komi, ..Game::new(size) }
+    }
+
     /// Makes a move on the board
     pub fn make_move(&mut self, row: usize, col: usize) -> Result<(), String> {
         if self.status != GameStatus::InProgress {
@@ -65,11 +202,7 @@ impl Game {
         }
 
         // Save current state for potential undo
-        self.history.push(GameState {
-            board: self.board.clone(),
-            current_player: self.current_player,
-            captured_stones: self.captured_stones,
-        });
+        self.history.push(self.snapshot(Action::Play(row, col)));
         
         // Try to place the stone
         let stone = if self.current_player { 
@@ -78,14 +211,28 @@ impl Game {
             Stone::White 
         };
         
-        match self.board.place_stone(row, col, stone) {
-            Ok(()) => {
+        let history = if self.rules.ko_rule == KoRule::Superko { self.position_hashes() } else { Vec::new() };
+        match self.rules.play(&self.board, row, col, stone, &history) {
+            Ok(board) => {
+                self.board = board;
                 // Update captured stones count and reset pass count when a move is made
+                let prisoners = self.prisoners(stone);
                 self.update_captured_stones();
+                self.record_move(stone, Some((row, col)), self.prisoners(stone) - prisoners);
                 self.reset_pass_count();
+                self.turns[self.color_index()] += 1;
+                if let Some(target) = self.variant.capture_target() {
+                    if self.prisoners(stone) >= target {
+                        self.status = GameStatus::CaptureTarget;
+                        self.winner = Some(stone);
+                    }
+                }
                 
                 // Switch players
                 self.current_player = !self.current_player;
+                if self.status == GameStatus::InProgress && self.repetitions() >= CYCLE_LIMIT {
+                    self.status = GameStatus::NoResult;
+                }
                 
                 Ok(())
             },
@@ -97,17 +244,99 @@ impl Game {
         }
     }
     
+    /// Plays `color` at `point`, or passes for `None`, whoever was to move,
+    /// as GTP controllers and SGF records may give one color several
+    /// moves in a row
+    pub fn play_move(&mut self, color: Stone, point: Option<(usize, usize)>) -> Result<(), String> {
+        if color == Stone::Empty {
+            return Err("Invalid color".to_string());
+        }
+        if self.status != GameStatus::InProgress {
+            return Err("Game is already over".to_string());
+        }
+        let to_move = self.current_player;
+        self.current_player = color == Stone::Black;
+        let result = match point {
+            Some((x, y)) => self.make_move(x, y),
+            None => self.pass(),
+        };
+        if result.is_err() {
+            self.current_player = to_move;
+        }
+        result
+    }
+
+    /// Puts `stone` at (x, y), or clears the point for `Stone::Empty`,
+    /// without using a turn or capturing, as SGF setup properties do
+    pub fn setup_stone(&mut self, x: usize, y: usize, stone: Stone) -> Result<(), String> {
+        let size = self.board.size();
+        if x == 0 || y == 0 || x > size || y > size {
+            return Err("Position out of bounds".to_string());
+        }
+        self.board.set_stone(x, y, stone);
+        self.board.clear_ko_point();
+        Ok(())
+    }
+
+    /// Gives the next move to `color`
+    pub fn set_to_move(&mut self, color: Stone) {
+        self.current_player = color != Stone::White;
+    }
+
+    /// Places `count` handicap stones on the standard points of an empty
+    /// board and gives White the move
+    pub fn place_handicap(&mut self, count: usize) -> Result<Vec<(usize, usize)>, String> {
+        let size = self.board.size();
+        if !self.history.is_empty() || self.board.stones_on_board(Stone::Black) + self.board.stones_on_board(Stone::White) > 0 {
+            return Err("Board is not empty".to_string());
+        }
+        let points = fixed_handicap(size, count).ok_or_else(|| format!("Invalid handicap {} on {}x{}", count, size, size))?;
+        for &(x, y) in &points {
+            self.board.set_stone(x, y, Stone::Black);
+        }
+        self.handicap = count;
+        self.current_player = false;
+        Ok(points)
+    }
+
     /// Undoes the last move
     pub fn undo_move(&mut self) -> Option<()> {
         if let Some(last_state) = self.history.pop() {
             self.board = last_state.board;
             self.current_player = last_state.current_player;
             self.captured_stones = last_state.captured_stones;
+            self.turns = last_state.turns;
+            self.pass_count = last_state.pass_count;
+            self.status = last_state.status;
+            self.winner = last_state.winner;
+            self.pass_stones = last_state.pass_stones;
+            self.clock = last_state.clock;
+            self.move_times.truncate(last_state.move_times);
+            if last_state.action != Action::Resign {
+                self.move_list.pop();
+            }
             Some(())
         } else {
             None
         }
     }
+
+    /// The current state, to be followed by `action`
+    fn snapshot(&self, action: Action) -> GameState {
+        GameState {
+            board: self.board.clone(),
+            current_player: self.current_player,
+            captured_stones: self.captured_stones,
+            turns: self.turns,
+            pass_count: self.pass_count,
+            action,
+            status: self.status,
+            winner: self.winner,
+            pass_stones: self.pass_stones,
+            clock: self.clock.clone(),
+            move_times: self.move_times.len(),
+        }
+    }
     
     /// Returns the current player
     pub fn current_player(&self) -> Stone {
@@ -133,13 +362,21 @@ impl Game {
             return Err("Game is already over".to_string());
         }
 
+        self.history.push(self.snapshot(Action::Pass));
+        self.record_move(self.current_player(), None, 0);
+        self.board.count_pass();
         self.pass_count += 1;
-        
-        // If both players pass consecutively, end the game
-        if self.pass_count >= 2 {
+        if self.rules.pass_stones {
+            self.pass_stones[self.color_index()] += 1;
+        }
+
+        // If both players pass consecutively, end the game; with pass
+        // stones only once White has passed last
+        if self.pass_count >= 2 && (!self.rules.pass_stones || self.current_player() == Stone::White) {
             self.status = GameStatus::Ended;
             self.determine_winner();
         }
+        self.turns[self.color_index()] += 1;
         
         // Switch players
         self.current_player = !self.current_player;
@@ -153,6 +390,7 @@ impl Game {
             return Err("Game is already over".to_string());
         }
 
+        self.history.push(self.snapshot(Action::Resign));
         self.status = GameStatus::Resigned;
         self.winner = Some(match self.current_player() {
             Stone::Black => Stone::White,
@@ -163,17 +401,54 @@ impl Game {
         Ok(())
     }
 
+    /// Reopens a game ended by two passes, so that play can go on
+    pub fn resume(&mut self) -> Result<(), String> {
+        if self.status != GameStatus::Ended {
+            return Err("Only a game ended by passing can be resumed".to_string());
+        }
+        self.status = GameStatus::InProgress;
+        self.winner = None;
+        self.pass_count = 0;
+        Ok(())
+    }
+
+    /// After two passes, plays on for both sides with [`aftermath_move`]
+    /// until both pass again, taking dead stones off and filling dame so
+    /// that area scoring is unambiguous. Returns the number of stones played.
+    pub fn play_aftermath(&mut self) -> Result<usize, String> {
+        self.resume()?;
+        let size = self.board.size();
+        let mut played = 0;
+        while !self.is_game_over() {
+            let point = aftermath_move(&self.board, self.current_player(), &self.rules)
+                .filter(|_| played < 2 * size * size);
+            match point {
+                Some((x, y)) if self.make_move(x, y).is_ok() => played += 1,
+                _ => self.pass()?,
+            }
+        }
+        Ok(played)
+    }
+
     /// Check if game is over
     pub fn is_game_over(&self) -> bool {
         self.status != GameStatus::InProgress
     }
 
+    /// Whether and how the game ended
+    pub fn game_status(&self) -> GameStatus {
+        self.status
+    }
+
     /// Get game status
     pub fn status(&self) -> &'static str {
         match self.status {
             GameStatus::InProgress => "In Progress",
             GameStatus::Ended => "Ended by agreement",
             GameStatus::Resigned => "Resigned",
+            GameStatus::CaptureTarget => "Capture target reached",
+            GameStatus::TimeOut => "Lost on time",
+            GameStatus::NoResult => "No result",
         }
     }
 
@@ -182,21 +457,232 @@ impl Game {
         self.winner
     }
 
-    /// Score territory and determine winner (simple implementation)
+    /// Opponent stones `color` has taken off the board
+    pub fn prisoners(&self, color: Stone) -> u32 {
+        match color {
+            Stone::Black => self.captured_stones[1],
+            Stone::White => self.captured_stones[0],
+            Stone::Empty => 0,
+        }
+    }
+
+    /// SGF and GTP style result of a finished game: the margin such as
+    /// "W+0.5" when counted, "B+Resign" on resignation, "B+Time" when a
+    /// player ran out of time, "0" for a draw and "Void" for a game without
+    /// result.
+    /// Capture Go reports only the winner, as "B+". `None` while the game
+    /// is in progress.
+    pub fn result(&self) -> Option<String> {
+        let winner = match self.winner {
+            Some(Stone::Black) => "B",
+            Some(Stone::White) => "W",
+            _ => "0",
+        };
+        match self.status {
+            GameStatus::InProgress => None,
+            GameStatus::Ended if self.variant == Variant::Standard => Some(self.score().result()),
+            GameStatus::NoResult => Some("Void".to_string()),
+            _ if winner == "0" => Some(winner.to_string()),
+            GameStatus::Resigned => Some(format!("{}+Resign", winner)),
+            GameStatus::TimeOut => Some(format!("{}+Time", winner)),
+            _ => Some(format!("{}+", winner)),
+        }
+    }
+
+    /// Prisoners `color` has handed over by passing
+    pub fn pass_stones(&self, color: Stone) -> u32 {
+        match color {
+            Stone::Black => self.pass_stones[0],
+            Stone::White => self.pass_stones[1],
+            Stone::Empty => 0,
+        }
+    }
+
+    /// Score of the current position under the game's scoring rule, with
+    /// the stones [`FinalStatus`] judges dead taken off
+    pub fn score(&self) -> Score {
+        let dead = FinalStatus::analyze(&self.board).dead();
+        match self.rules.scoring {
+            ScoringRule::Area => Score::area(&self.board, &dead, self.komi),
+            ScoringRule::Territory => {
+                let (black, white) = final_score(&self.board, ScoringRule::Territory, &dead);
+                Score {
+                    black: black + self.pass_stones[1] as usize,
+                    white: white + self.pass_stones[0] as usize,
+                    komi: self.komi,
+                }
+            }
+        }
+    }
+
+    /// Worm data of the current position, read afresh so it always
+    /// follows the latest move
+    pub fn worms(&self) -> Worms {
+        Worms::compute(&self.board)
+    }
+
+    /// Scores the game and determines the winner
     fn determine_winner(&mut self) {
-        // Simple scoring: count stones + territory + komi
-        let black_score = self.board.stones_on_board(Stone::Black) as i32 + self.captured_stones[0] as i32;
-        let mut white_score = self.board.stones_on_board(Stone::White) as i32 + self.captured_stones[1] as i32;
-        
-        // Add komi to white score
-        white_score += self.komi as i32;
-        
-        if black_score > white_score {
-            self.winner = Some(Stone::Black);
-        } else if white_score > black_score {
-            self.winner = Some(Stone::White);
-        } else {
-            self.winner = None; // Tie
+        if self.variant != Variant::Standard {
+            // Capture Go ended by passes: the player with more captures wins
+            let (black, white) = (self.prisoners(Stone::Black), self.prisoners(Stone::White));
+            self.winner = match black.cmp(&white) {
+                std::cmp::Ordering::Greater => Some(Stone::Black),
+                std::cmp::Ordering::Less => Some(Stone::White),
+                std::cmp::Ordering::Equal => None,
+            };
+            return;
+        }
+        self.winner = self.score().winner();
+    }
+
+    /// Moves and passes (`None`) so far in order
+    pub fn moves(&self) -> Vec<(Stone, Option<(usize, usize)>)> {
+        self.move_list.iter().map(|m| (m.color, m.point)).collect()
+    }
+
+    /// Moves and passes so far, with their numbers and captures
+    pub fn move_list(&self) -> &[GameMove] {
+        &self.move_list
+    }
+
+    /// The latest move or pass
+    pub fn last_move(&self) -> Option<&GameMove> {
+        self.move_list.last()
+    }
+
+    fn record_move(&mut self, color: Stone, point: Option<(usize, usize)>, captures: u32) {
+        let number = self.move_list.len() + 1;
+        self.move_list.push(GameMove { number, color, point, captures });
+    }
+
+    /// Each history entry as its state before and the board after
+    fn steps(&self) -> impl Iterator<Item = (&GameState, &Board)> {
+        let boards = self.history.iter().map(|state| &state.board).skip(1).chain([&self.board]);
+        self.history.iter().zip(boards)
+    }
+
+    /// Times the current position has occurred with the same side to move,
+    /// counting the current one
+    pub fn repetitions(&self) -> usize {
+        let hash = self.board.position_hash();
+        let earlier = self
+            .history
+            .iter()
+            .filter(|state| state.current_player == self.current_player && state.board.position_hash() == hash)
+            .count();
+        earlier + 1
+    }
+
+    /// Hashes of every position so far, the current one last, for superko
+    pub fn position_hashes(&self) -> Vec<u64> {
+        self.history.iter().map(|state| &state.board).chain([&self.board]).map(Board::position_hash).collect()
+    }
+
+    /// Records how long the last move took to choose and charges it to the
+    /// mover's clock; the game is lost if that runs out of time
+    pub fn record_move_time(&mut self, time: Duration) {
+        self.move_times.push(time);
+        let Some(color) = self.move_list.last().map(|m| m.color) else { return };
+        if !self.clock.charge(color, time) && self.status == GameStatus::InProgress {
+            self.status = GameStatus::TimeOut;
+            self.winner = Some(if color == Stone::Black { Stone::White } else { Stone::Black });
+        }
+    }
+
+    /// Ends the game on time if the side to move, thinking for `elapsed`
+    /// so far, has run out; returns whether it did
+    pub fn check_flag(&mut self, elapsed: Duration) -> bool {
+        let color = self.current_player();
+        if self.status != GameStatus::InProgress || self.clock.clone().charge(color, elapsed) {
+            return false;
+        }
+        self.status = GameStatus::TimeOut;
+        self.winner = Some(if color == Stone::Black { Stone::White } else { Stone::Black });
+        true
+    }
+
+    /// Move count, captures, kos, passes and thinking time so far
+    pub fn statistics(&self) -> GameStatistics {
+        let captured_after = self.history.iter().map(|state| state.captured_stones).skip(1).chain([self.captured_stones]);
+        let largest_capture = self
+            .history
+            .iter()
+            .zip(captured_after)
+            .map(|(state, after)| after.iter().sum::<u32>().saturating_sub(state.captured_stones.iter().sum()))
+            .max()
+            .unwrap_or(0);
+
+        // A ko is the pair of points its two captures are played on
+        let mut kos = HashSet::new();
+        for (state, after) in self.steps() {
+            if let (Some(ko), Action::Play(x, y)) = (after.get_ko_point(), state.action) {
+                let point = (x, y);
+                kos.insert(if ko < point { (ko, point) } else { (point, ko) });
+            }
+        }
+
+        let total: Duration = self.move_times.iter().sum();
+        GameStatistics {
+            moves: self.history.iter().filter(|state| matches!(state.action, Action::Play(..))).count(),
+            passes: self.history.iter().filter(|state| state.action == Action::Pass).count(),
+            captures: [self.prisoners(Stone::Black), self.prisoners(Stone::White)],
+            largest_capture,
+            ko_fights: kos.len(),
+            average_move_time: (!self.move_times.is_empty()).then(|| total / self.move_times.len() as u32),
+        }
+    }
+
+    /// Makes `color` a rengo team whose `members` play its moves in turn;
+    /// an empty list goes back to a single unnamed player
+    pub fn set_team(&mut self, color: Stone, members: Vec<String>) -> Result<(), String> {
+        let index = match color {
+            Stone::Black => 0,
+            Stone::White => 1,
+            Stone::Empty => return Err("Invalid color".to_string()),
+        };
+        if members.iter().any(|name| name.trim().is_empty()) {
+            return Err("Team member names cannot be empty".to_string());
+        }
+        self.teams[index] = members;
+        Ok(())
+    }
+
+    /// Team members of `color` in playing order
+    pub fn team(&self, color: Stone) -> &[String] {
+        match color {
+            Stone::Black => &self.teams[0],
+            Stone::White => &self.teams[1],
+            Stone::Empty => &[],
+        }
+    }
+
+    /// Team member whose turn it is, if the side to move is a team
+    pub fn player_to_move(&self) -> Option<&str> {
+        let team = &self.teams[self.color_index()];
+        if team.is_empty() {
+            return None;
+        }
+        Some(&team[self.turns[self.color_index()] % team.len()])
+    }
+
+    /// Team members of `color` joined as in the SGF `PB`/`PW` of a rengo game
+    pub fn team_label(&self, color: Stone) -> Option<String> {
+        let team = self.team(color);
+        (!team.is_empty()).then(|| team.join(" & "))
+    }
+
+    /// Plays `point` (`None` passes) for the team member `name`, refusing
+    /// moves made out of rotation
+    pub fn play_as(&mut self, name: &str, point: Option<(usize, usize)>) -> Result<(), String> {
+        if let Some(expected) = self.player_to_move() {
+            if !expected.eq_ignore_ascii_case(name) {
+                return Err(format!("It is {}'s turn", expected));
+            }
+        }
+        match point {
+            Some((x, y)) => self.make_move(x, y),
+            None => self.pass(),
         }
     }
 
@@ -205,6 +691,15 @@ impl Game {
         self.pass_count
     }
 
+    /// Candidate moves, ownership, dragon statuses and score estimate as JSON
+    pub fn analysis_json(&self) -> JsonValue {
+        analysis::analyze(self, analysis::DEFAULT_CANDIDATES).to_json()
+    }
+
+    fn color_index(&self) -> usize {
+        if self.current_player { 0 } else { 1 }
+    }
+
     /// Reset pass count (当移动时重置)
     fn reset_pass_count(&mut self) {
         self.pass_count = 0;
@@ -216,4 +711,4 @@ impl Game {
         self.captured_stones[0] = black_captured as u32;
         self.captured_stones[1] = white_captured as u32;
     }
-}
\ No newline at end of file
+}
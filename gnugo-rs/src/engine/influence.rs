@@ -0,0 +1,198 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Influence: every stone radiates strength that halves with each step
+//! and does not pass through enemy stones. The per-point totals for each
+//! color feed territory estimation and GTP visualization, and classify
+//! empty points into territory, moyo and area.
+
+use crate::engine::board::{Board, Stone};
+
+/// Influence a stone exerts on its own point
+pub const STONE_STRENGTH: f32 = 64.0;
+
+/// Share of influence kept with each step away from the stone
+const DECAY: f32 = 0.5;
+
+/// Steps beyond which a stone exerts no influence
+const RADIUS: usize = 4;
+
+/// An empty point is one side's territory when that side's influence is
+/// at least this many times the other's
+const TERRITORY_RATIO: f32 = 3.0;
+
+/// Minimum influence for an empty point to count as territory
+const TERRITORY_MINIMUM: f32 = 8.0;
+
+/// An empty point is one side's moyo when that side's influence is at
+/// least this many times the other's
+const MOYO_RATIO: f32 = 2.0;
+
+/// Minimum influence for an empty point to count as moyo
+const MOYO_MINIMUM: f32 = 4.0;
+
+/// What an empty point is to the side that dominates it, as in GNU Go's
+/// moyo output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Region {
+    /// Secure enough to count
+    Territory(Stone),
+    /// Framework that needs another move to secure
+    Moyo(Stone),
+    /// Leaning to one side, but open to both
+    Area(Stone),
+    Neutral,
+}
+
+impl Region {
+    /// `B`/`W` territory, `b`/`w` moyo, `+`/`-` area of Black/White, `.` neutral
+    pub fn symbol(&self) -> char {
+        match self {
+            Region::Territory(Stone::Black) => 'B',
+            Region::Territory(_) => 'W',
+            Region::Moyo(Stone::Black) => 'b',
+            Region::Moyo(_) => 'w',
+            Region::Area(Stone::Black) => '+',
+            Region::Area(_) => '-',
+            Region::Neutral => '.',
+        }
+    }
+}
+
+/// Black and white influence on every point of a board
+#[derive(Debug, Clone, PartialEq)]
+pub struct InfluenceMap {
+    size: usize,
+    /// Row by row from the top-left
+    black: Vec<f32>,
+    white: Vec<f32>,
+}
+
+impl InfluenceMap {
+    /// Spreads the influence of every stone on `board`
+    pub fn compute(board: &Board) -> Self {
+        let size = board.size();
+        let mut map = InfluenceMap { size, black: vec![0.0; size * size], white: vec![0.0; size * size] };
+        for y in 1..=size {
+            for x in 1..=size {
+                let color = board.get_stone(x, y);
+                if color != Stone::Empty {
+                    map.radiate(board, (x, y), color);
+                }
+            }
+        }
+        map
+    }
+
+    /// Breadth-first spread from one stone, stopped by enemy stones
+    fn radiate(&mut self, board: &Board, from: (usize, usize), color: Stone) {
+        let size = self.size;
+        let mut reached = vec![false; size * size];
+        reached[(from.1 - 1) * size + from.0 - 1] = true;
+        let mut frontier = vec![from];
+        let mut strength = STONE_STRENGTH;
+        for step in 0..=RADIUS {
+            let mut next = Vec::new();
+            for &(x, y) in &frontier {
+                let values = if color == Stone::Black { &mut self.black } else { &mut self.white };
+                values[(y - 1) * size + x - 1] += strength;
+                if step == RADIUS {
+                    continue;
+                }
+                for (nx, ny) in board.neighbors(x, y) {
+                    let stone = board.get_stone(nx, ny);
+                    let i = (ny - 1) * size + nx - 1;
+                    if !reached[i] && (stone == Stone::Empty || stone == color) {
+                        reached[i] = true;
+                        next.push((nx, ny));
+                    }
+                }
+            }
+            frontier = next;
+            strength *= DECAY;
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Influence of `color` at (x, y); 0 off the board
+    pub fn influence(&self, x: usize, y: usize, color: Stone) -> f32 {
+        if x == 0 || y == 0 || x > self.size || y > self.size {
+            return 0.0;
+        }
+        let i = (y - 1) * self.size + x - 1;
+        match color {
+            Stone::Black => self.black[i],
+            Stone::White => self.white[i],
+            Stone::Empty => 0.0,
+        }
+    }
+
+    /// Black minus white influence at every point, row by row from the top-left
+    pub fn balance(&self) -> Vec<f32> {
+        self.black.iter().zip(&self.white).map(|(b, w)| b - w).collect()
+    }
+
+    /// Side whose territory the empty point (x, y) is, by influence;
+    /// `Stone::Empty` for contested and occupied points
+    pub fn territory(&self, board: &Board, x: usize, y: usize) -> Stone {
+        match self.classify(board, x, y) {
+            Region::Territory(color) => color,
+            _ => Stone::Empty,
+        }
+    }
+
+    /// Territory, moyo or area of the side with more influence at the
+    /// empty point (x, y); occupied points are neutral
+    pub fn classify(&self, board: &Board, x: usize, y: usize) -> Region {
+        if board.get_stone(x, y) != Stone::Empty {
+            return Region::Neutral;
+        }
+        let (black, white) = (self.influence(x, y, Stone::Black), self.influence(x, y, Stone::White));
+        let (color, own, other) = if black > white {
+            (Stone::Black, black, white)
+        } else if white > black {
+            (Stone::White, white, black)
+        } else {
+            return Region::Neutral;
+        };
+        if own >= TERRITORY_MINIMUM && own >= TERRITORY_RATIO * other {
+            Region::Territory(color)
+        } else if own >= MOYO_MINIMUM && own >= MOYO_RATIO * other {
+            Region::Moyo(color)
+        } else {
+            Region::Area(color)
+        }
+    }
+
+    /// [`InfluenceMap::classify`] for every point, indexed [y - 1][x - 1]
+    pub fn regions(&self, board: &Board) -> Vec<Vec<Region>> {
+        (1..=self.size)
+            .map(|y| (1..=self.size).map(|x| self.classify(board, x, y)).collect())
+            .collect()
+    }
+
+    /// Empty points each side controls by influence, (black, white)
+    pub fn territory_count(&self, board: &Board) -> (usize, usize) {
+        self.count(board, |region| matches!(region, Region::Territory(_)))
+    }
+
+    /// Empty points in each side's moyo, (black, white)
+    pub fn moyo_count(&self, board: &Board) -> (usize, usize) {
+        self.count(board, |region| matches!(region, Region::Moyo(_)))
+    }
+
+    fn count(&self, board: &Board, wanted: impl Fn(Region) -> bool) -> (usize, usize) {
+        let (mut black, mut white) = (0, 0);
+        for region in self.regions(board).into_iter().flatten().filter(|&region| wanted(region)) {
+            match region {
+                Region::Territory(Stone::Black) | Region::Moyo(Stone::Black) | Region::Area(Stone::Black) => black += 1,
+                Region::Neutral => {}
+                _ => white += 1,
+            }
+        }
+        (black, white)
+    }
+}
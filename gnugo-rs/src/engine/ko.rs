@@ -0,0 +1,149 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Ko fights: finding ko threats, weighing them against the ko, and
+//! choosing between threatening, answering a threat and resolving the ko
+
+use crate::engine::board::{Board, Stone};
+
+/// A move that threatens to capture stones unless answered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KoThreat {
+    pub point: (usize, usize),
+    /// Stones the threat puts in atari
+    pub size: usize,
+}
+
+/// What a ko fight asks of the side to move
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KoChoice {
+    /// The ko may not be retaken at once: play a threat elsewhere
+    Threat(KoThreat),
+    /// The opponent's threat is bigger than the ko: save the stones
+    Answer((usize, usize)),
+    /// The ko is worth more than the threat: connect and end the ko
+    Resolve((usize, usize)),
+}
+
+impl KoChoice {
+    pub fn point(&self) -> (usize, usize) {
+        match *self {
+            KoChoice::Threat(threat) => threat.point,
+            KoChoice::Answer(point) | KoChoice::Resolve(point) => point,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            KoChoice::Threat(_) => "ko threat",
+            KoChoice::Answer(_) => "answers ko threat",
+            KoChoice::Resolve(_) => "resolves ko",
+        }
+    }
+}
+
+fn opponent(color: Stone) -> Stone {
+    match color {
+        Stone::Black => Stone::White,
+        Stone::White => Stone::Black,
+        Stone::Empty => Stone::Empty,
+    }
+}
+
+/// Moves by `player` that put opponent stones in atari without leaving the
+/// threatening stone in atari itself, biggest first
+pub fn ko_threats(board: &Board, player: Stone) -> Vec<KoThreat> {
+    let size = board.size();
+    let mut threats = Vec::new();
+    for y in 1..=size {
+        for x in 1..=size {
+            if board.get_stone(x, y) != Stone::Empty || board.get_ko_point() == Some((x, y)) {
+                continue;
+            }
+            let mut after = board.clone();
+            if after.place_stone(x, y, player).is_err() || after.count_liberties(x, y) < 2 {
+                continue;
+            }
+            let mut groups: Vec<Vec<(usize, usize)>> = Vec::new();
+            for (nx, ny) in after.neighbors(x, y) {
+                if after.get_stone(nx, ny) != opponent(player) || groups.iter().any(|g| g.contains(&(nx, ny))) {
+                    continue;
+                }
+                if let Some(group) = after.find_group(nx, ny) {
+                    // Only stones this move put in atari make a threat
+                    if group.liberties() == 1 && board.count_liberties(nx, ny) > 1 {
+                        groups.push(group.positions().to_vec());
+                    }
+                }
+            }
+            let size = groups.iter().map(Vec::len).sum();
+            if size > 0 {
+                threats.push(KoThreat { point: (x, y), size });
+            }
+        }
+    }
+    threats.sort_by_key(|threat| std::cmp::Reverse(threat.size));
+    threats
+}
+
+/// Points where the opponent of `player` could take a ko, each with the
+/// stones at stake: the stone taken plus `player`'s stones that die with it
+pub fn open_kos(board: &Board, player: Stone) -> Vec<((usize, usize), usize)> {
+    let size = board.size();
+    let mut kos = Vec::new();
+    for y in 1..=size {
+        for x in 1..=size {
+            if board.get_stone(x, y) != Stone::Empty {
+                continue;
+            }
+            let mut after = board.clone();
+            let Ok(placement) = after.place_stone_detailed(x, y, opponent(player)) else {
+                continue;
+            };
+            if !placement.created_ko() || board.clone().place_stone(x, y, player).is_err() {
+                continue;
+            }
+            // Neighbors left short of liberties once the ko is lost
+            let weak: usize = after
+                .neighbors(x, y)
+                .into_iter()
+                .filter(|&(nx, ny)| after.get_stone(nx, ny) == player && after.count_liberties(nx, ny) == 1)
+                .filter_map(|(nx, ny)| after.find_group(nx, ny))
+                .map(|group| group.positions().len())
+                .sum();
+            kos.push(((x, y), 1 + weak));
+        }
+    }
+    kos
+}
+
+/// Stones of `player` the opponent's latest stone put in atari, with the
+/// move that saves them, if a single move does
+fn threat_to_answer(board: &Board, player: Stone) -> Option<((usize, usize), usize)> {
+    let &(lx, ly, _) = board.recent_stones(1).first()?;
+    if board.get_stone(lx, ly) != opponent(player) {
+        return None;
+    }
+    board
+        .neighbors(lx, ly)
+        .into_iter()
+        .filter(|&(nx, ny)| board.get_stone(nx, ny) == player && board.count_liberties(nx, ny) == 1)
+        .filter_map(|(nx, ny)| {
+            let group = board.find_group(nx, ny)?;
+            let (ex, ey) = board.find_liberties(nx, ny)[0];
+            let mut after = board.clone();
+            after.place_stone(ex, ey, player).ok()?;
+            (after.count_liberties(ex, ey) > 1).then_some(((ex, ey), group.positions().len()))
+        })
+        .max_by_key(|&(_, size)| size)
+}
+
+/// The ko move for `player`, if a ko fight is on
+pub fn ko_move(board: &Board, player: Stone) -> Option<KoChoice> {
+    if board.get_ko_point().is_some() {
+        return ko_threats(board, player).first().copied().map(KoChoice::Threat);
+    }
+    let (answer, threat) = threat_to_answer(board, player)?;
+    let (ko, stakes) = open_kos(board, player).into_iter().max_by_key(|&(_, stakes)| stakes)?;
+    Some(if threat > stakes { KoChoice::Answer(answer) } else { KoChoice::Resolve(ko) })
+}
@@ -0,0 +1,346 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Monte Carlo tree search over [`FastBoard`] playouts, with RAVE
+//!
+//! Besides its own wins and visits, every node keeps all-moves-as-first
+//! (AMAF) statistics: the results of all playouts through its parent in
+//! which its move was played first by its color, anywhere later in the
+//! playout. Selection blends the two by the standard RAVE schedule, so
+//! moves are told apart after a few playouts instead of a few hundred.
+
+use std::sync::Mutex;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::engine::ai::ResourceLimits;
+use crate::engine::board::{Board, Stone};
+use crate::engine::fast_board::FastBoard;
+use crate::engine::move_generation::Move;
+use crate::engine::profile::{self, Subsystem};
+use crate::engine::search::order_moves;
+
+/// Visits at which a move's own statistics count as much as its RAVE
+/// statistics, the k of `beta = sqrt(k / (3n + k))`
+pub const RAVE_EQUIVALENCE: f32 = 1000.0;
+
+/// Weight of the exploration term in child selection
+const EXPLORATION: f32 = 0.2;
+
+/// Visits a leaf needs before its children are added
+const EXPANSION_VISITS: u32 = 2;
+
+/// Visits counted as lost on every node a playout passes until its result
+/// is known, so parallel playouts spread over the tree
+const VIRTUAL_LOSS: u32 = 1;
+
+/// Playout moves allowed per point of the board
+const PLAYOUT_MOVES_PER_POINT: usize = 3;
+
+fn opponent(color: Stone) -> Stone {
+    match color {
+        Stone::Black => Stone::White,
+        Stone::White => Stone::Black,
+        Stone::Empty => Stone::Empty,
+    }
+}
+
+/// Playout results of one node, from the point of view of the player who
+/// made its move
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct NodeStats {
+    pub visits: u32,
+    pub wins: f32,
+    /// Playouts through the parent in which the move was played first by
+    /// the same color
+    pub rave_visits: u32,
+    pub rave_wins: f32,
+}
+
+impl NodeStats {
+    pub fn win_rate(&self) -> f32 {
+        if self.visits == 0 { 0.5 } else { self.wins / self.visits as f32 }
+    }
+
+    pub fn rave_rate(&self) -> f32 {
+        if self.rave_visits == 0 { 0.5 } else { self.rave_wins / self.rave_visits as f32 }
+    }
+
+    /// Share of the RAVE rate in [`NodeStats::value`]: 1 before the first
+    /// visit, falling toward 0 as visits pass `equivalence`
+    pub fn beta(&self, equivalence: f32) -> f32 {
+        if equivalence <= 0.0 {
+            return 0.0;
+        }
+        (equivalence / (3.0 * self.visits as f32 + equivalence)).sqrt()
+    }
+
+    /// `(1 - beta) * win rate + beta * RAVE rate`
+    pub fn value(&self, equivalence: f32) -> f32 {
+        let beta = self.beta(equivalence);
+        (1.0 - beta) * self.win_rate() + beta * self.rave_rate()
+    }
+}
+
+/// Options for [`search`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MctsSettings {
+    pub playouts: usize,
+    /// Points added to White's area when scoring a playout
+    pub komi: f32,
+    /// RAVE equivalence k; 0 selects by playout results alone (plain UCT)
+    pub rave_equivalence: f32,
+    /// Seed for reproducible searches; `None` uses a random seed. Only a
+    /// single-threaded search is reproducible
+    pub seed: Option<u64>,
+    /// Threads running playouts on the shared tree
+    pub threads: usize,
+}
+
+impl Default for MctsSettings {
+    fn default() -> Self {
+        MctsSettings {
+            playouts: 1_000,
+            komi: 6.5,
+            rave_equivalence: RAVE_EQUIVALENCE,
+            seed: None,
+            threads: ResourceLimits::default().threads(),
+        }
+    }
+}
+
+/// Outcome of a search
+#[derive(Debug, Clone, PartialEq)]
+pub struct MctsResult {
+    /// Moves of the root, most visited first
+    pub moves: Vec<((usize, usize), NodeStats)>,
+    /// Threads that ran the playouts
+    pub threads: usize,
+}
+
+impl MctsResult {
+    /// The most visited move; `None` to pass
+    pub fn best(&self) -> Option<(usize, usize)> {
+        self.moves.first().map(|&(point, _)| point)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    point: (usize, usize),
+    /// Player of the move into this node
+    color: Stone,
+    stats: NodeStats,
+    children: Vec<usize>,
+    expanded: bool,
+}
+
+/// Runs `settings.playouts` playouts for `color` to move on `board`
+pub fn search(board: &Board, color: Stone, settings: &MctsSettings) -> MctsResult {
+    let start = FastBoard::from_board(board);
+    let size = board.size();
+    let candidates: Vec<(usize, usize)> = (1..=size)
+        .flat_map(|y| (1..=size).map(move |x| (x, y)))
+        .filter(|&(x, y)| start.is_playout_move(x, y, color))
+        .collect();
+    search_among(board, color, &candidates, settings)
+}
+
+/// Like [`search`], with the root's moves limited to `candidates`, e.g.
+/// the moves the game's rules allow
+pub fn search_among(board: &Board, color: Stone, candidates: &[(usize, usize)], settings: &MctsSettings) -> MctsResult {
+    profile::measure(Subsystem::Playouts, || run(board, color, candidates, settings))
+}
+
+/// The search tree, shared by all threads behind one lock
+struct Tree {
+    nodes: Vec<Node>,
+    /// Playouts started so far
+    started: usize,
+}
+
+/// A playout on its way down the tree: the nodes passed and the moves
+/// played, the tree's moves first
+struct Descent {
+    board: FastBoard,
+    path: Vec<usize>,
+    played: Vec<((usize, usize), Stone)>,
+    to_move: Stone,
+}
+
+impl Tree {
+    /// Walks down from the root by the blended RAVE value, expanding a
+    /// leaf once it has been visited enough. Every node passed takes a
+    /// virtual loss until [`Tree::backup`], steering other threads elsewhere
+    fn descend(&mut self, start: &FastBoard, color: Stone, k: f32) -> Descent {
+        let size = start.size();
+        let nodes = &mut self.nodes;
+        let mut descent = Descent { board: start.clone(), path: vec![0], played: Vec::new(), to_move: color };
+        loop {
+            let current = *descent.path.last().unwrap();
+            let to_move = descent.to_move;
+            if !nodes[current].expanded {
+                if current != 0 && nodes[current].stats.visits < EXPANSION_VISITS {
+                    break;
+                }
+                let children: Vec<(usize, usize)> = (1..=size)
+                    .flat_map(|y| (1..=size).map(move |x| (x, y)))
+                    .filter(|&(x, y)| descent.board.is_playout_move(x, y, to_move))
+                    .collect();
+                expand(nodes, current, &children, to_move);
+            }
+            let parent_visits = nodes[current].stats.visits.max(1) as f32;
+            // Of equal scores the first child wins, so the root tries its
+            // best ordered moves first
+            let Some(&next) = nodes[current].children.iter().rev().max_by(|&&a, &&b| {
+                let score = |n: usize| {
+                    let stats = &nodes[n].stats;
+                    stats.value(k) + EXPLORATION * (parent_visits.ln() / (stats.visits as f32 + 1.0)).sqrt()
+                };
+                score(a).total_cmp(&score(b))
+            }) else {
+                break;
+            };
+            let (x, y) = nodes[next].point;
+            if !descent.board.play(x, y, to_move) {
+                break;
+            }
+            descent.played.push(((x, y), to_move));
+            descent.path.push(next);
+            descent.to_move = opponent(to_move);
+        }
+        for &n in &descent.path {
+            nodes[n].stats.visits += VIRTUAL_LOSS;
+        }
+        descent
+    }
+
+    /// Takes back the virtual loss of `descent` and records who won its
+    /// playout, on the path and, for the AMAF statistics, on the children
+    /// of every node along it
+    fn backup(&mut self, descent: &Descent, winner: Stone) {
+        let size = descent.board.size();
+        let (path, played) = (&descent.path, &descent.played);
+        let nodes = &mut self.nodes;
+        // From the leaf up, `first` holds who played each point first from
+        // the current node on
+        let index = |(x, y): (usize, usize)| y * (size + 1) + x;
+        let mut first = vec![Stone::Empty; (size + 1) * (size + 1)];
+        let leaf = path.len() - 1;
+        for &(point, mover) in played[leaf..].iter().rev() {
+            first[index(point)] = mover;
+        }
+        for (depth, &n) in path.iter().enumerate().rev() {
+            if depth < leaf {
+                let (point, mover) = played[depth];
+                first[index(point)] = mover;
+            }
+            nodes[n].stats.visits += 1 - VIRTUAL_LOSS;
+            nodes[n].stats.wins += if nodes[n].color == winner { 1.0 } else { 0.0 };
+            for i in 0..nodes[n].children.len() {
+                let child = nodes[n].children[i];
+                let mover = nodes[child].color;
+                if first[index(nodes[child].point)] == mover {
+                    nodes[child].stats.rave_visits += 1;
+                    nodes[child].stats.rave_wins += if mover == winner { 1.0 } else { 0.0 };
+                }
+            }
+        }
+    }
+}
+
+/// Adds a child of `parent` for each of `points`, in that order
+fn expand(nodes: &mut Vec<Node>, parent: usize, points: &[(usize, usize)], to_move: Stone) {
+    for &point in points {
+        nodes.push(Node { point, color: to_move, stats: NodeStats::default(), children: Vec::new(), expanded: false });
+        let child = nodes.len() - 1;
+        nodes[parent].children.push(child);
+    }
+    nodes[parent].expanded = true;
+}
+
+/// Plays random moves to the end of the game, adding them to `descent`;
+/// returns the winner
+fn simulate(descent: &mut Descent, komi: f32, rng: &mut StdRng) -> Stone {
+    let size = descent.board.size();
+    let mut passes = 0;
+    for _ in 0..size * size * PLAYOUT_MOVES_PER_POINT {
+        match descent.board.random_move(descent.to_move, rng) {
+            Some(point) => {
+                descent.played.push((point, descent.to_move));
+                passes = 0;
+            }
+            None => passes += 1,
+        }
+        if passes >= 2 {
+            break;
+        }
+        descent.to_move = opponent(descent.to_move);
+    }
+    playout_winner(&descent.board, komi)
+}
+
+/// One thread's share of the search: playouts until `settings.playouts`
+/// have been started, holding the lock only to walk and update the tree
+fn work(tree: &Mutex<Tree>, start: &FastBoard, color: Stone, settings: &MctsSettings, mut rng: StdRng) {
+    loop {
+        let mut descent = {
+            let mut tree = tree.lock().unwrap();
+            if tree.started >= settings.playouts {
+                return;
+            }
+            tree.started += 1;
+            tree.descend(start, color, settings.rave_equivalence)
+        };
+        let winner = simulate(&mut descent, settings.komi, &mut rng);
+        tree.lock().unwrap().backup(&descent, winner);
+    }
+}
+
+fn run(board: &Board, color: Stone, candidates: &[(usize, usize)], settings: &MctsSettings) -> MctsResult {
+    let seed = settings.seed.unwrap_or_else(rand::random);
+    let start = FastBoard::from_board(board);
+    let root = Node { point: (0, 0), color: opponent(color), stats: NodeStats::default(), children: Vec::new(), expanded: false };
+    let mut nodes = vec![root];
+    // The root's moves in search order, the deeper nodes' in board order
+    let candidates: Vec<Move> = candidates.iter().map(|&(x, y)| Move::new(x, y)).collect();
+    let ordered: Vec<(usize, usize)> = order_moves(board, color, &candidates).into_iter().map(|m| (m.row, m.col)).collect();
+    expand(&mut nodes, 0, &ordered, color);
+    let tree = Mutex::new(Tree { nodes, started: 0 });
+
+    let threads = if cfg!(feature = "native") { settings.threads.max(1) } else { 1 };
+    if threads > 1 {
+        std::thread::scope(|scope| {
+            for thread in 0..threads as u64 {
+                let (tree, start) = (&tree, &start);
+                scope.spawn(move || work(tree, start, color, settings, StdRng::seed_from_u64(seed.wrapping_add(thread))));
+            }
+        });
+    } else {
+        work(&tree, &start, color, settings, StdRng::seed_from_u64(seed));
+    }
+
+    let nodes = tree.into_inner().unwrap().nodes;
+    let mut moves: Vec<((usize, usize), NodeStats)> =
+        nodes[0].children.iter().map(|&c| (nodes[c].point, nodes[c].stats)).collect();
+    moves.sort_by(|a, b| b.1.visits.cmp(&a.1.visits).then(b.1.win_rate().total_cmp(&a.1.win_rate())));
+    MctsResult { moves, threads }
+}
+
+/// Winner of a finished playout by area, with `komi` for White
+fn playout_winner(board: &FastBoard, komi: f32) -> Stone {
+    let size = board.size();
+    let mut black = 0.0;
+    let mut white = komi;
+    for y in 1..=size {
+        for x in 1..=size {
+            match board.owner(x, y) {
+                Stone::Black => black += 1.0,
+                Stone::White => white += 1.0,
+                Stone::Empty => {}
+            }
+        }
+    }
+    if black > white { Stone::Black } else { Stone::White }
+}
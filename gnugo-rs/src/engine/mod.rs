@@ -6,9 +6,31 @@
 // Placeholder for engine functionality
 // This will contain the core game logic implementation
 pub mod board;
+pub mod fast_board;
 pub mod game;
 pub mod move_generation;
 pub mod evaluation;
+pub mod endgame;
+pub mod influence;
 pub mod ai;
+pub mod move_reasons;
+pub mod search;
+pub mod mcts;
 pub mod rules;
-pub mod eye;
\ No newline at end of file
+pub mod clock;
+pub mod eye;
+pub mod worm;
+pub mod dragon;
+pub mod escape;
+pub mod ko;
+pub mod analysis;
+pub mod explain;
+pub mod scoring;
+pub mod breakin;
+pub mod final_status;
+pub mod aftermath;
+pub mod tsumego;
+pub mod solver;
+pub mod owl;
+pub mod trace;
+pub mod profile;
\ No newline at end of file
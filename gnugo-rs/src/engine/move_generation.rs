@@ -5,6 +5,26 @@
 
 use crate::engine::board::Board;
 use crate::engine::board::Stone;
+use crate::engine::eye::{EyeAnalyzer, HalfEyeType};
+use crate::engine::rules::{GameRules, KoRule};
+use crate::patterns::eyes::eye_shape;
+
+/// Smallest eye space worth reducing with a nakade sacrifice
+const NAKADE_MIN_SPACE: usize = 3;
+
+/// Largest eye space a nakade sacrifice is read for
+const NAKADE_MAX_SPACE: usize = 6;
+
+/// Stones a snapback must take back
+const SNAPBACK_STONES: usize = 2;
+
+fn opponent(color: Stone) -> Stone {
+    match color {
+        Stone::Black => Stone::White,
+        Stone::White => Stone::Black,
+        Stone::Empty => Stone::Empty,
+    }
+}
 
 /// Represents a move in Go
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -26,14 +46,16 @@ impl Move {
 pub struct MoveGenerator;
 
 impl MoveGenerator {
-    /// Generates all valid moves for the current player
-    pub fn generate_valid_moves(board: &Board, player: Stone) -> Vec<Move> {
+    /// Generates every move `player` may make under `rules`, exactly the
+    /// set `Game::make_move` accepts; `history` holds the position hashes
+    /// seen so far (see `Game::position_hashes`)
+    pub fn generate_valid_moves(board: &Board, player: Stone, rules: &GameRules, history: &[u64]) -> Vec<Move> {
         let mut moves = Vec::new();
         let size = board.size();
         
-        for row in 0..size {
-            for col in 0..size {
-                if Self::is_valid_move(board, row, col, player) {
+        for row in 1..=size {
+            for col in 1..=size {
+                if Self::is_legal(board, row, col, player, rules, history) {
                     moves.push(Move::new(row, col));
                 }
             }
@@ -41,85 +63,94 @@ impl MoveGenerator {
         
         moves
     }
+
+    /// Whether `Game::make_move` would accept `player` at (x, y) under
+    /// `rules`, given the position hashes in `history`
+    pub fn is_legal(board: &Board, x: usize, y: usize, player: Stone, rules: &GameRules, history: &[u64]) -> bool {
+        board.is_legal(x, y, player, rules).is_ok()
+            && (rules.ko_rule != KoRule::Superko || rules.play(board, x, y, player, history).is_ok())
+    }
     
-    /// Checks if a move is valid
-    pub fn is_valid_move(board: &Board, row: usize, col: usize, player: Stone) -> bool {
-        // Check if position is on board
-        if row >= board.size() || col >= board.size() {
-            return false;
+    /// The valid moves worth considering: those that neither fill one of
+    /// `player`'s own eyes nor put its stones into atari for nothing
+    pub fn generate_sensible_moves(board: &Board, player: Stone, rules: &GameRules, history: &[u64]) -> Vec<Move> {
+        Self::generate_valid_moves(board, player, rules, history)
+            .into_iter()
+            .filter(|m| Self::is_sensible(board, m.row, m.col, player))
+            .collect()
+    }
+
+    /// Whether the empty point (x, y) is neither one of `player`'s own
+    /// eyes nor a self-atari, short of a sacrifice that reading justifies
+    pub fn is_sensible(board: &Board, x: usize, y: usize, player: Stone) -> bool {
+        !Self::fills_own_eye(board, x, y, player) && (!Self::is_self_atari(board, x, y, player) || Self::is_sacrifice(board, x, y, player))
+    }
+
+    /// Whether (x, y) is an eye of `player`: empty, surrounded by its
+    /// stones, none of them in atari, and not made false by the
+    /// opponent's diagonals. A point the opponent could play to capture,
+    /// as in a ko, is no eye
+    pub fn fills_own_eye(board: &Board, x: usize, y: usize, player: Stone) -> bool {
+        board.get_stone(x, y) == Stone::Empty
+            && board.neighbors(x, y).iter().all(|&(nx, ny)| board.get_stone(nx, ny) == player && board.count_liberties(nx, ny) > 1)
+            && EyeAnalyzer::new().half_eye(board, player, x, y).is_some_and(|half| half.eye_type != HalfEyeType::False)
+    }
+
+    /// Whether `player` at (x, y) leaves its string with one liberty
+    /// without capturing anything, or with none
+    pub fn is_self_atari(board: &Board, x: usize, y: usize, player: Stone) -> bool {
+        let mut after = board.clone();
+        match after.place_stone_detailed(x, y, player) {
+            Ok(placement) => placement.captured.is_empty() && after.count_liberties(x, y) == 1,
+            Err(_) => true,
         }
-        
-        // Check if position is empty
-        if board.get_stone(row, col) != Stone::Empty {
+    }
+
+    /// Whether the self-atari of `player` at (x, y) gives stones away on
+    /// purpose: a snapback, where the capture can be taken back with more
+    /// stones, or a nakade, filling an eye space of the opponent whose
+    /// shape then makes only one eye
+    pub fn is_sacrifice(board: &Board, x: usize, y: usize, player: Stone) -> bool {
+        let mut after = board.clone();
+        if after.place_stone_detailed(x, y, player).is_err() {
             return false;
         }
-        
-        // Check for suicide (basic implementation)
-        // This is a simplified check - a full implementation would be more complex
-        let mut has_liberty = false;
-        
-        // Check adjacent positions for liberties or friendly stones
-        if row > 0 {
-            let adjacent_stone = board.get_stone(row - 1, col);
-            if adjacent_stone == Stone::Empty || adjacent_stone == player {
-                has_liberty = true;
-            }
-        }
-        
-        if row < board.size() - 1 {
-            let adjacent_stone = board.get_stone(row + 1, col);
-            if adjacent_stone == Stone::Empty || adjacent_stone == player {
-                has_liberty = true;
-            }
-        }
-        
-        if col > 0 {
-            let adjacent_stone = board.get_stone(row, col - 1);
-            if adjacent_stone == Stone::Empty || adjacent_stone == player {
-                has_liberty = true;
-            }
-        }
-        
-        if col < board.size() - 1 {
-            let adjacent_stone = board.get_stone(row, col + 1);
-            if adjacent_stone == Stone::Empty || adjacent_stone == player {
-                has_liberty = true;
+        let enemy = opponent(player);
+        let Some(&(lx, ly)) = after.find_liberties(x, y).first() else { return false };
+
+        // Snapback: the capturing stones are left in atari and retaking
+        // them wins back more than one stone
+        let mut captured = after.clone();
+        if captured.place_stone_detailed(lx, ly, enemy).is_ok() && captured.count_liberties(lx, ly) == 1 {
+            if let Some(&(rx, ry)) = captured.find_liberties(lx, ly).first() {
+                if captured.place_stone_detailed(rx, ry, player).is_ok_and(|retake| retake.captured.len() >= SNAPBACK_STONES) {
+                    return true;
+                }
             }
         }
-        
-        // If there's no liberty, it might be suicide - but we need to check if it captures
-        if !has_liberty {
-            // Check if this move captures opponent stones
-            let opponent = match player {
-                Stone::Black => Stone::White,
-                Stone::White => Stone::Black,
-                _ => Stone::Empty,
-            };
-            
-            // Check adjacent opponent stones for capture
-            if row > 0 && board.get_stone(row - 1, col) == opponent {
-                // Would need to check if the group gets captured
-                // Simplified: allow if captures opponent
-                return true;
-            }
-            
-            if row < board.size() - 1 && board.get_stone(row + 1, col) == opponent {
-                return true;
-            }
-            
-            if col > 0 && board.get_stone(row, col - 1) == opponent {
-                return true;
-            }
-            
-            if col < board.size() - 1 && board.get_stone(row, col + 1) == opponent {
-                return true;
+
+        // Nakade: the points not held by the opponent around the move
+        // form an enclosed eye space that only makes one eye
+        let mut space = vec![(x, y)];
+        let mut i = 0;
+        while i < space.len() {
+            let (px, py) = space[i];
+            for (nx, ny) in after.neighbors(px, py) {
+                if after.get_stone(nx, ny) != enemy && !space.contains(&(nx, ny)) {
+                    space.push((nx, ny));
+                    if space.len() > NAKADE_MAX_SPACE {
+                        return false;
+                    }
+                }
             }
-            
-            // If no liberties and no captures, it's invalid
-            return false;
+            i += 1;
         }
-        
-        true
+        space.len() >= NAKADE_MIN_SPACE && eye_shape(&space).is_some_and(|(shape, _)| shape.min_eyes <= 1)
+    }
+
+    /// Checks if a move is valid under the default rules
+    pub fn is_valid_move(board: &Board, row: usize, col: usize, player: Stone) -> bool {
+        board.is_legal(row, col, player, &GameRules::default()).is_ok()
     }
     
     /// Generates all possible moves (including invalid ones)
@@ -127,8 +158,8 @@ impl MoveGenerator {
         let mut moves = Vec::new();
         let size = board.size();
         
-        for row in 0..size {
-            for col in 0..size {
+        for row in 1..=size {
+            for col in 1..=size {
                 moves.push(Move::new(row, col));
             }
         }
@@ -0,0 +1,233 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Move reasons, after GNU Go's genmove: each pass over the position
+//! records why a vertex is worth playing, the reasons of a vertex add up
+//! to a single value, and the vertex with the highest value is played
+
+use std::fmt::Write;
+
+use crate::engine::board::{Board, Stone};
+use crate::engine::dragon::{group_strength, Dragons};
+use crate::engine::owl::OwlStatus;
+use crate::engine::worm::{Code, Worms};
+use crate::gtp::format_move;
+use crate::patterns::fuseki;
+use crate::patterns::joseki::{self, Corner};
+
+/// Value per stone of capturing or saving a worm whose fate is unsettled
+const CRITICAL_WORM_VALUE: i32 = 5;
+
+/// Value per stone of killing or saving a critical dragon
+const CRITICAL_DRAGON_VALUE: i32 = 20;
+
+/// Value per stone of a group with no strength at all, shared among its
+/// liberties
+const WEAK_GROUP_VALUE: f32 = 10.0;
+
+/// Value of a standard joseki continuation
+const JOSEKI_VALUE: i32 = 10;
+
+/// Smallest board whose corners are far enough apart for joseki
+const JOSEKI_MIN_SIZE: usize = 13;
+
+/// Value of the opening point on a nearly empty board
+const OPENING_VALUE: i32 = 50;
+
+/// Why a move is worth playing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    /// Captures the unsettled worm with this origin
+    AttackWorm((usize, usize)),
+    /// Saves the unsettled worm with this origin
+    DefendWorm((usize, usize)),
+    /// Kills the critical dragon with this origin
+    AttackDragon((usize, usize)),
+    /// Saves the critical dragon with this origin
+    DefendDragon((usize, usize)),
+    /// Runs out with the unsettled dragon with this origin
+    Escape((usize, usize)),
+    /// Takes or adds a liberty of the weak dragon with this origin
+    WeakDragon((usize, usize)),
+    /// Follows the named opening or joseki
+    Pattern(&'static str),
+    /// Gains by whole-board evaluation
+    Territory,
+    /// Suits the playing style
+    Style,
+}
+
+impl Reason {
+    /// Short description, naming strings by their origin
+    pub fn describe(&self, size: usize) -> String {
+        let vertex = |(x, y): (usize, usize)| format_move(x, y, size);
+        match *self {
+            Reason::AttackWorm(origin) => format!("attack {}", vertex(origin)),
+            Reason::DefendWorm(origin) => format!("defend {}", vertex(origin)),
+            Reason::AttackDragon(origin) => format!("kill dragon {}", vertex(origin)),
+            Reason::DefendDragon(origin) => format!("save dragon {}", vertex(origin)),
+            Reason::Escape(origin) => format!("run out with {}", vertex(origin)),
+            Reason::WeakDragon(origin) => format!("weak dragon {}", vertex(origin)),
+            Reason::Pattern(name) => format!("pattern {}", name),
+            Reason::Territory => "territory".to_string(),
+            Reason::Style => "style".to_string(),
+        }
+    }
+}
+
+/// A reason and what it adds to the value of its move
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MoveReason {
+    pub reason: Reason,
+    pub value: i32,
+}
+
+/// The reasons recorded for every vertex of a board
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoveReasons {
+    size: usize,
+    /// Row by row from the top-left
+    reasons: Vec<Vec<MoveReason>>,
+}
+
+impl MoveReasons {
+    /// No reasons yet on a board of `size`
+    pub fn new(size: usize) -> Self {
+        MoveReasons { size, reasons: vec![Vec::new(); size * size] }
+    }
+
+    /// Tactical, dragon and pattern reasons for `color` to play on `board`
+    pub fn collect(board: &Board, color: Stone) -> Self {
+        MoveReasons::collect_with(board, color, true)
+    }
+
+    /// Like [`MoveReasons::collect`], leaving out the dragon reasons and
+    /// the owl reading behind them unless `dragons` is set
+    pub fn collect_with(board: &Board, color: Stone, dragons: bool) -> Self {
+        let mut reasons = MoveReasons::new(board.size());
+        let worms = Worms::compute(board);
+        reasons.add_worm_reasons(&worms, color);
+        if dragons {
+            reasons.add_dragon_reasons(&Dragons::from_worms(board, &worms), color);
+        }
+        reasons.add_pattern_reasons(board, color);
+        reasons
+    }
+
+    /// Records `reason` worth `value` for the move at `point`
+    pub fn add(&mut self, (x, y): (usize, usize), reason: Reason, value: i32) {
+        self.reasons[(y - 1) * self.size + x - 1].push(MoveReason { reason, value });
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Reasons recorded for the move at (x, y)
+    pub fn reasons(&self, x: usize, y: usize) -> &[MoveReason] {
+        &self.reasons[(y - 1) * self.size + x - 1]
+    }
+
+    /// Combined value of the move at (x, y)
+    pub fn value(&self, x: usize, y: usize) -> i32 {
+        self.reasons(x, y).iter().map(|r| r.value).sum()
+    }
+
+    /// Vertices with at least one reason and their combined values, best first
+    pub fn ranked(&self) -> Vec<((usize, usize), i32)> {
+        let mut ranked: Vec<((usize, usize), i32)> = (1..=self.size)
+            .flat_map(|y| (1..=self.size).map(move |x| (x, y)))
+            .filter(|&(x, y)| !self.reasons(x, y).is_empty())
+            .map(|(x, y)| ((x, y), self.value(x, y)))
+            .collect();
+        ranked.sort_by_key(|&(point, value)| (std::cmp::Reverse(value), point.1, point.0));
+        ranked
+    }
+
+    /// One line per vertex, best first: the vertex, its value and each
+    /// reason with what it adds
+    pub fn to_text(&self, count: usize) -> String {
+        let mut text = String::new();
+        for ((x, y), value) in self.ranked().into_iter().take(count) {
+            if !text.is_empty() {
+                text.push('\n');
+            }
+            let _ = write!(text, "{} {}:", format_move(x, y, self.size), value);
+            let parts: Vec<String> =
+                self.reasons(x, y).iter().map(|r| format!("{} {:+}", r.reason.describe(self.size), r.value)).collect();
+            let _ = write!(text, " {}", parts.join(", "));
+        }
+        text
+    }
+
+    /// Capturing or saving worms that reading shows can go either way
+    fn add_worm_reasons(&mut self, worms: &Worms, color: Stone) {
+        for worm in worms.iter().filter(|w| w.attack_code != Code::Fail && w.defense_code != Code::Fail) {
+            let value = CRITICAL_WORM_VALUE * worm.size() as i32;
+            let (point, reason) = if worm.color == color {
+                (worm.defense_point, Reason::DefendWorm(worm.origin))
+            } else {
+                (worm.attack_point, Reason::AttackWorm(worm.origin))
+            };
+            if let Some(point) = point {
+                self.add(point, reason, value);
+            }
+        }
+    }
+
+    /// Moves that save `color`'s critical dragons or kill the opponent's,
+    /// and liberties of weak dragons of either color, each valued by the
+    /// stones at stake and, for weak dragons, by how weak they are and how
+    /// few liberties they have. An unsettled dragon of `color` with an
+    /// escape route also values the first step out at its full weakness
+    fn add_dragon_reasons(&mut self, dragons: &Dragons, color: Stone) {
+        for dragon in dragons.iter().filter(|dragon| dragon.status != OwlStatus::Dead) {
+            let stones = dragon.stones.len();
+            if dragon.status == OwlStatus::Critical {
+                let (point, reason) = if dragon.color == color {
+                    (dragon.defense_point, Reason::DefendDragon(dragon.origin))
+                } else {
+                    (dragon.attack_point, Reason::AttackDragon(dragon.origin))
+                };
+                if let Some(point) = point {
+                    self.add(point, reason, CRITICAL_DRAGON_VALUE * stones as i32);
+                }
+            }
+            let weakness = (1.0 - group_strength(dragon)) * WEAK_GROUP_VALUE * stones as f32;
+            if dragon.color == color && dragon.status != OwlStatus::Alive {
+                if let Some(route) = &dragon.escape_route {
+                    self.add(route.first_step(), Reason::Escape(dragon.origin), weakness as i32);
+                }
+            }
+            let value = (weakness / dragon.liberties.len().max(1) as f32) as i32;
+            if value > 0 {
+                for &point in &dragon.liberties {
+                    self.add(point, Reason::WeakDragon(dragon.origin), value);
+                }
+            }
+        }
+    }
+
+    /// The opening point, and joseki continuations in occupied corners
+    /// of big boards
+    fn add_pattern_reasons(&mut self, board: &Board, color: Stone) {
+        if let Some(point) = fuseki::opening_move(board) {
+            self.add(point, Reason::Pattern("opening"), OPENING_VALUE);
+        }
+        if board.size() < JOSEKI_MIN_SIZE {
+            return;
+        }
+        for corner in [Corner::UpperLeft, Corner::UpperRight, Corner::LowerLeft, Corner::LowerRight] {
+            let Some(found) = joseki::identify(board, corner, color) else { continue };
+            if found.moves == 0 {
+                continue;
+            }
+            for continuation in found.continuations.iter().filter(|c| c.color == color) {
+                let (x, y) = continuation.point;
+                if board.get_stone(x, y) == Stone::Empty {
+                    self.add(continuation.point, Reason::Pattern(continuation.name), JOSEKI_VALUE);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,315 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Owl: life-and-death reading for a surrounded dragon
+//!
+//! Unlike the tsumego solver, which tries every point of a fixed region,
+//! owl follows the dragon's eye space as it changes and only reads the
+//! moves the owl patterns suggest. A dragon whose space grows past
+//! [`ESCAPE_SPACE`] has broken out and counts as alive.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::engine::board::{Board, Stone};
+use crate::engine::eye::{EyeAnalyzer, HalfEyeType};
+use crate::engine::profile::{self, Subsystem};
+use crate::patterns::owl::{attack_pattern, defense_pattern};
+
+/// Empty and friendly points reachable from the dragon beyond which it has escaped
+pub const ESCAPE_SPACE: usize = 24;
+
+/// Moves read at each node, best first, besides passing
+const MAX_CANDIDATES: usize = 6;
+
+/// Bonus for a candidate on the vital point of an eye shape
+const VITAL_POINT_VALUE: i32 = 70;
+
+/// Life-and-death status of a dragon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OwlStatus {
+    /// Lives whoever moves first
+    Alive,
+    /// Dies whoever moves first
+    Dead,
+    /// Lives or dies depending on who moves first
+    Critical,
+    /// The node budget ran out before a result was proven
+    Unknown,
+}
+
+impl OwlStatus {
+    pub fn name(&self) -> &'static str {
+        match self {
+            OwlStatus::Alive => "alive",
+            OwlStatus::Dead => "dead",
+            OwlStatus::Critical => "critical",
+            OwlStatus::Unknown => "unknown",
+        }
+    }
+}
+
+/// Result of reading one dragon
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwlResult {
+    pub status: OwlStatus,
+    /// Move that kills the dragon, when the attacker moves first
+    pub attack_point: Option<(usize, usize)>,
+    /// Move that saves the dragon, when the defender moves first
+    pub defense_point: Option<(usize, usize)>,
+    /// Number of positions visited
+    pub nodes: usize,
+}
+
+/// Position hash, side to move, pass count and ko point
+type PositionKey = (u64, Stone, u32, Option<(usize, usize)>);
+
+/// Bounded owl reader for the dragon at `target`
+struct OwlReader {
+    target: (usize, usize),
+    defender: Stone,
+    nodes: usize,
+    max_nodes: usize,
+    table: HashMap<PositionKey, bool>,
+    /// Positions on the current search path, to cut off repetition cycles
+    path: Vec<u64>,
+}
+
+/// Reads whether the string at `target` can be killed and whether it can be
+/// saved, visiting at most `max_nodes` positions in total
+pub fn analyze(board: &Board, target: (usize, usize), max_nodes: usize) -> OwlResult {
+    let defender = board.get_stone(target.0, target.1);
+    if defender == Stone::Empty {
+        return OwlResult { status: OwlStatus::Dead, attack_point: None, defense_point: None, nodes: 0 };
+    }
+    let mut reader = OwlReader {
+        target,
+        defender,
+        nodes: 0,
+        max_nodes,
+        table: HashMap::new(),
+        path: Vec::new(),
+    };
+    profile::measure(Subsystem::Owl, || {
+        let (mut attack_point, mut defense_point) = (None, None);
+        let killed = reader.attacker_wins(board, opponent(defender), 0, &mut attack_point);
+        let saved = reader.attacker_wins(board, defender, 0, &mut defense_point).map(|dead| !dead);
+        let status = match (killed, saved) {
+            (Some(false), _) => OwlStatus::Alive,
+            (Some(true), Some(false)) => OwlStatus::Dead,
+            (Some(true), Some(true)) => OwlStatus::Critical,
+            _ => OwlStatus::Unknown,
+        };
+        OwlResult {
+            status,
+            attack_point: if killed == Some(true) { attack_point } else { None },
+            defense_point: if saved == Some(true) { defense_point } else { None },
+            nodes: reader.nodes,
+        }
+    })
+}
+
+impl OwlReader {
+    /// Whether the attacker kills the dragon with best play, or `None` if the
+    /// node budget ran out. `best` receives the winning move of the side to
+    /// move, if it has one other than passing
+    fn attacker_wins(
+        &mut self,
+        board: &Board,
+        to_move: Stone,
+        passes: u32,
+        best: &mut Option<(usize, usize)>,
+    ) -> Option<bool> {
+        if board.get_stone(self.target.0, self.target.1) != self.defender {
+            return Some(true);
+        }
+        let space = self.eye_space(board);
+        if passes >= 2 || space.len() > ESCAPE_SPACE || self.has_two_eyes(board, &space) {
+            return Some(false);
+        }
+
+        let key = (board.position_hash(), to_move, passes, board.get_ko_point());
+        if let Some(&result) = self.table.get(&key) {
+            return Some(result);
+        }
+        self.nodes += 1;
+        if self.nodes > self.max_nodes {
+            return None;
+        }
+
+        let attacker_to_move = to_move != self.defender;
+        let mut candidates: Vec<Option<(usize, usize)>> =
+            self.candidates(board, &space, to_move).into_iter().map(Some).collect();
+        candidates.push(None);
+
+        let mut exhausted = false;
+        self.path.push(key.0);
+        for candidate in candidates {
+            let mut child = board.clone();
+            let child_passes = match candidate {
+                Some((x, y)) => {
+                    if child.place_stone(x, y, to_move).is_err() || self.path.contains(&child.position_hash()) {
+                        continue;
+                    }
+                    0
+                }
+                None => {
+                    child.clear_ko_point();
+                    passes + 1
+                }
+            };
+            match self.attacker_wins(&child, opponent(to_move), child_passes, &mut None) {
+                Some(result) if result == attacker_to_move => {
+                    self.path.pop();
+                    self.table.insert(key, result);
+                    *best = candidate;
+                    return Some(result);
+                }
+                Some(_) => {}
+                None => exhausted = true,
+            }
+        }
+        self.path.pop();
+        if exhausted {
+            return None;
+        }
+        let result = !attacker_to_move;
+        self.table.insert(key, result);
+        Some(result)
+    }
+
+    /// Empty and dragon-colored points reachable from the target without
+    /// crossing an attacker stone
+    fn eye_space(&self, board: &Board) -> HashSet<(usize, usize)> {
+        let mut space = HashSet::from([self.target]);
+        let mut stack = vec![self.target];
+        while let Some((x, y)) = stack.pop() {
+            for (nx, ny) in board.neighbors(x, y) {
+                let stone = board.get_stone(nx, ny);
+                if (stone == Stone::Empty || stone == self.defender) && space.insert((nx, ny)) {
+                    if space.len() > ESCAPE_SPACE {
+                        return space;
+                    }
+                    stack.push((nx, ny));
+                }
+            }
+        }
+        space
+    }
+
+    /// Whether the dragon's space holds two eyes enclosed by its own stones:
+    /// a single point only counts when the attacker cannot play there and
+    /// its diagonals do not make it a false eye
+    fn has_two_eyes(&self, board: &Board, space: &HashSet<(usize, usize)>) -> bool {
+        let attacker = opponent(self.defender);
+        let mut seen = HashSet::new();
+        let mut eyes = 0;
+        for &point in space {
+            if board.get_stone(point.0, point.1) != Stone::Empty || !seen.insert(point) {
+                continue;
+            }
+            let mut area = vec![point];
+            let mut enclosed = true;
+            let mut i = 0;
+            while i < area.len() {
+                let (x, y) = area[i];
+                for (nx, ny) in board.neighbors(x, y) {
+                    match board.get_stone(nx, ny) {
+                        Stone::Empty if seen.insert((nx, ny)) => area.push((nx, ny)),
+                        Stone::Empty => {}
+                        stone => enclosed &= stone == self.defender,
+                    }
+                }
+                i += 1;
+            }
+            let (x, y) = area[0];
+            let false_eye = || {
+                EyeAnalyzer::new().half_eye(board, self.defender, x, y).is_some_and(|half| half.eye_type == HalfEyeType::False)
+            };
+            if enclosed && (area.len() > 1 || (board.clone().place_stone(x, y, attacker).is_err() && !false_eye())) {
+                eyes += 1;
+            }
+        }
+        eyes >= 2
+    }
+
+    /// Empty points of the space, and liberties of the dragon, ranked by the
+    /// owl patterns for the side to move, vital points of the eye shapes
+    /// first. The defender also considers capturing surrounding stones in
+    /// atari, wherever their last liberty is
+    fn candidates(&self, board: &Board, space: &HashSet<(usize, usize)>, to_move: Stone) -> Vec<(usize, usize)> {
+        let liberties = board.find_liberties(self.target.0, self.target.1);
+        let mut points: Vec<(usize, usize)> = space
+            .iter()
+            .copied()
+            .filter(|&(x, y)| board.get_stone(x, y) == Stone::Empty)
+            .collect();
+        points.sort_unstable();
+        let analyzer = EyeAnalyzer::new();
+        let vital: Vec<(usize, usize)> =
+            empty_areas(board, &points).iter().flat_map(|area| analyzer.vital_points(board, area)).collect();
+        let mut scored: Vec<(i32, (usize, usize))> = points
+            .into_iter()
+            .map(|point| {
+                let pattern = if to_move == self.defender {
+                    defense_pattern(board, point, self.defender)
+                } else {
+                    attack_pattern(board, point, self.defender)
+                };
+                let liberty = if liberties.contains(&point) { 10 } else { 0 };
+                let vital = if vital.contains(&point) { VITAL_POINT_VALUE } else { 0 };
+                (pattern.map_or(0, |p| p.value) + liberty + vital, point)
+            })
+            .collect();
+        scored.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+        let mut moves: Vec<(usize, usize)> = scored.into_iter().take(MAX_CANDIDATES).map(|(_, point)| point).collect();
+        if to_move == self.defender {
+            let attacker = opponent(self.defender);
+            let mut captures: Vec<(usize, usize)> = space
+                .iter()
+                .filter(|&&(x, y)| board.get_stone(x, y) == self.defender)
+                .flat_map(|&(x, y)| board.neighbors(x, y))
+                .filter(|&(x, y)| board.get_stone(x, y) == attacker && board.count_liberties(x, y) == 1)
+                .flat_map(|(x, y)| board.find_liberties(x, y))
+                .filter(|point| !moves.contains(point))
+                .collect();
+            captures.sort_unstable();
+            captures.dedup();
+            captures.extend(moves);
+            moves = captures;
+        }
+        moves
+    }
+}
+
+/// Connected areas of the empty `points`, each sorted
+fn empty_areas(board: &Board, points: &[(usize, usize)]) -> Vec<Vec<(usize, usize)>> {
+    let mut seen = HashSet::new();
+    let mut areas = Vec::new();
+    for &point in points {
+        if !seen.insert(point) {
+            continue;
+        }
+        let mut area = vec![point];
+        let mut i = 0;
+        while i < area.len() {
+            let (x, y) = area[i];
+            for next in board.neighbors(x, y) {
+                if points.contains(&next) && seen.insert(next) {
+                    area.push(next);
+                }
+            }
+            i += 1;
+        }
+        area.sort_unstable();
+        areas.push(area);
+    }
+    areas
+}
+
+fn opponent(color: Stone) -> Stone {
+    match color {
+        Stone::Black => Stone::White,
+        Stone::White => Stone::Black,
+        Stone::Empty => Stone::Empty,
+    }
+}
@@ -0,0 +1,140 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Wall time spent in each engine subsystem, to guide optimization work
+//!
+//! With the `profile` feature, [`measure`] times its closure and charges it
+//! to a subsystem in a per-thread table; without it [`measure`] just calls
+//! the closure. Time of nested measurements counts for both subsystems.
+//! GTP reports the table with `stats`.
+
+use std::cell::RefCell;
+use std::fmt::Write;
+use std::time::Duration;
+
+/// Whether this build records timings
+pub const ENABLED: bool = cfg!(feature = "profile");
+
+/// Parts of the engine timed separately
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Subsystem {
+    Patterns,
+    Reading,
+    Owl,
+    Influence,
+    Playouts,
+}
+
+impl Subsystem {
+    pub const ALL: [Subsystem; 5] = [
+        Subsystem::Patterns,
+        Subsystem::Reading,
+        Subsystem::Owl,
+        Subsystem::Influence,
+        Subsystem::Playouts,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Subsystem::Patterns => "patterns",
+            Subsystem::Reading => "reading",
+            Subsystem::Owl => "owl",
+            Subsystem::Influence => "influence",
+            Subsystem::Playouts => "playouts",
+        }
+    }
+}
+
+/// Time and number of calls per subsystem
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Timings {
+    time: [Duration; 5],
+    calls: [u64; 5],
+}
+
+impl Timings {
+    pub fn time(&self, subsystem: Subsystem) -> Duration {
+        self.time[subsystem as usize]
+    }
+
+    pub fn calls(&self, subsystem: Subsystem) -> u64 {
+        self.calls[subsystem as usize]
+    }
+
+    #[cfg(feature = "profile")]
+    fn add(&mut self, subsystem: Subsystem, elapsed: Duration) {
+        self.time[subsystem as usize] += elapsed;
+        self.calls[subsystem as usize] += 1;
+    }
+}
+
+/// Timings of the current move and of all moves since the last reset
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Stats {
+    /// Moves started with [`begin_move`]
+    pub moves: u64,
+    pub last_move: Timings,
+    pub total: Timings,
+}
+
+impl Stats {
+    /// One line per subsystem, times in milliseconds
+    pub fn to_text(&self) -> String {
+        let mut text = format!("moves: {}", self.moves);
+        for subsystem in Subsystem::ALL {
+            let ms = |timings: &Timings| timings.time(subsystem).as_secs_f64() * 1000.0;
+            let _ = write!(
+                text,
+                "\n{}: {:.3} ms last move, {:.3} ms total, {} calls",
+                subsystem.name(),
+                ms(&self.last_move),
+                ms(&self.total),
+                self.total.calls(subsystem)
+            );
+        }
+        text
+    }
+}
+
+thread_local! {
+    static STATS: RefCell<Stats> = RefCell::new(Stats::default());
+}
+
+/// Runs `f`, charging its wall time to `subsystem` in profiling builds
+#[cfg(feature = "profile")]
+pub fn measure<R>(subsystem: Subsystem, f: impl FnOnce() -> R) -> R {
+    let start = std::time::Instant::now();
+    let result = f();
+    let elapsed = start.elapsed();
+    STATS.with(|stats| {
+        let mut stats = stats.borrow_mut();
+        stats.last_move.add(subsystem, elapsed);
+        stats.total.add(subsystem, elapsed);
+    });
+    result
+}
+
+/// Runs `f`, charging its wall time to `subsystem` in profiling builds
+#[cfg(not(feature = "profile"))]
+pub fn measure<R>(_subsystem: Subsystem, f: impl FnOnce() -> R) -> R {
+    f()
+}
+
+/// Starts the timings of a new move on this thread
+pub fn begin_move() {
+    STATS.with(|stats| {
+        let mut stats = stats.borrow_mut();
+        stats.moves += 1;
+        stats.last_move = Timings::default();
+    });
+}
+
+/// This thread's timings so far
+pub fn stats() -> Stats {
+    STATS.with(|stats| stats.borrow().clone())
+}
+
+/// Clears this thread's timings
+pub fn reset() {
+    STATS.with(|stats| *stats.borrow_mut() = Stats::default());
+}
@@ -6,10 +6,77 @@ use crate::engine::board::{Board, Stone};
 /// Represents the Go game rules configuration
 #[derive(Debug, Clone, Copy)]
 pub struct GameRules {
-    /// Allow suicide moves
+    /// Allow multi-stone suicide: a move that leaves its own string
+    /// without liberties removes it. A lone stone may never commit suicide
     pub allow_suicide: bool,
     /// Ko rule type
     pub ko_rule: KoRule,
+    /// How the final position is counted
+    pub scoring: ScoringRule,
+    /// A passing player hands the opponent a prisoner, and White must
+    /// make the last pass so both hand over as many (AGA)
+    pub pass_stones: bool,
+    /// Komi new games under these rules start with
+    pub komi: f32,
+}
+
+/// Scoring methods
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScoringRule {
+    /// Stones plus surrounded territory (Chinese rules)
+    Area,
+    /// Surrounded territory plus prisoners (Japanese rules)
+    Territory,
+}
+
+impl ScoringRule {
+    /// "area" or "territory"
+    pub fn name(&self) -> &'static str {
+        match self {
+            ScoringRule::Area => "area",
+            ScoringRule::Territory => "territory",
+        }
+    }
+
+    /// Parses a scoring method or ruleset name such as "chinese" or "territory"
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "area" | "chinese" => Some(ScoringRule::Area),
+            "territory" | "japanese" => Some(ScoringRule::Territory),
+            _ => None,
+        }
+    }
+}
+
+/// Game variants played on the same board
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Variant {
+    /// Ordinary Go, decided by counting
+    #[default]
+    Standard,
+    /// Capture Go (Atari Go): the first player to capture `target` stones
+    /// wins. Suicide is never allowed.
+    Capture { target: u32 },
+}
+
+impl Variant {
+    /// Parses a variant name: "standard", or "capture"/"atari" with a
+    /// target of one stone
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "standard" | "go" => Some(Variant::Standard),
+            "capture" | "atari" | "atari-go" => Some(Variant::Capture { target: 1 }),
+            _ => None,
+        }
+    }
+
+    /// Stones to capture for a win, if the variant has a capture target
+    pub fn capture_target(&self) -> Option<u32> {
+        match self {
+            Variant::Standard => None,
+            Variant::Capture { target } => Some(*target),
+        }
+    }
 }
 
 /// Different types of ko rules
@@ -19,7 +86,7 @@ pub enum KoRule {
     None,
     /// Simple ko rule (most common)
     Simple,
-    /// Superko rules (various types)
+    /// Positional superko: no move may repeat an earlier whole-board position
     Superko,
 }
 
@@ -28,40 +95,91 @@ impl Default for GameRules {
         GameRules {
             allow_suicide: false,
             ko_rule: KoRule::Simple,
+            scoring: ScoringRule::Area,
+            pass_stones: false,
+            komi: 6.5,
         }
     }
 }
 
 impl GameRules {
+    /// Parses a ruleset name. "chinese" uses superko and "japanese" simple
+    /// ko with territory scoring; "new-zealand" (or "nz") and "ing" also
+    /// allow multi-stone suicide, though neither lets a lone stone
+    /// commit suicide. "aga" counts area with pass stones, so
+    /// territory counting gives the same result, and 7.5 komi. A bare
+    /// scoring method such as "area" keeps the default ko and suicide rules.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_lowercase();
+        let (scoring, ko_rule, allow_suicide) = match name.as_str() {
+            "chinese" => (ScoringRule::Area, KoRule::Superko, false),
+            "japanese" => (ScoringRule::Territory, KoRule::Simple, false),
+            "new-zealand" | "nz" | "ing" => (ScoringRule::Area, KoRule::Superko, true),
+            "aga" => {
+                return Some(GameRules {
+                    ko_rule: KoRule::Superko,
+                    pass_stones: true,
+                    komi: 7.5,
+                    ..GameRules::default()
+                })
+            }
+            other => return ScoringRule::from_name(other).map(|scoring| GameRules { scoring, ..GameRules::default() }),
+        };
+        Some(GameRules { allow_suicide, ko_rule, scoring, ..GameRules::default() })
+    }
+
     /// Checks if a move is legal according to game rules
     pub fn is_legal_move(&self, board: &Board, x: usize, y: usize, stone: Stone) -> Result<(), &'static str> {
-        if x >= board.size() || y >= board.size() {
-            return Err("Position out of bounds");
-        }
-        
-        // Check if position is empty
-        if board.get_stone(x, y) != Stone::Empty {
-            return Err("Position already occupied");
-        }
-        
-        // Check ko rule
-        if self.ko_rule != KoRule::None {
-            if board.get_ko_point() == Some((x, y)) {
-                return Err("Ko threat violation");
-            }
-        }
-        
-        // Create a temporary board to test the move
-        let mut test_board = board.clone();
-        
-        // Try to place the stone
-        if let Err(e) = test_board.place_stone(x, y, stone) {
-            if !self.allow_suicide && e == "Suicide move not allowed" {
-                return Err("Suicide move not allowed");
-            }
-            return Err(e);
+        board.is_legal(x, y, stone, self).map_err(|e| e.message())
+    }
+}
+
+impl GameRules {
+    /// Board after `stone` plays (x, y) under these rules, checked against
+    /// `history`, the position hashes seen so far. The simple-ko point is
+    /// playable only without a ko rule; a suicide removes the group when
+    /// the rules allow it.
+    pub fn play(&self, board: &Board, x: usize, y: usize, stone: Stone, history: &[u64]) -> Result<Board, &'static str> {
+        let mut next = board.clone();
+        next.place_stone_under(x, y, stone, self)?;
+        if self.ko_rule == KoRule::Superko && history.contains(&next.position_hash()) {
+            return Err("Superko violation");
         }
-        
-        Ok(())
+        Ok(next)
     }
-}
\ No newline at end of file
+}
+
+/// Fixed handicap placement from the GTP specification: star points in
+/// the order D4, Q16, D16, Q4, then the center and sides. Returns `None`
+/// for a count the board does not allow (2..=9 on odd boards from 9x9,
+/// 2..=4 on 7x7 and even boards).
+pub fn fixed_handicap(size: usize, count: usize) -> Option<Vec<(usize, usize)>> {
+    let max = if size < 7 { 0 } else if size == 7 || size.is_multiple_of(2) { 4 } else { 9 };
+    if !(2..=max).contains(&count) {
+        return None;
+    }
+
+    let edge = if size >= 13 { 4 } else { 3 };
+    let (low, high, mid) = (edge, size + 1 - edge, size.div_ceil(2));
+    // (column, row from the bottom), as in GTP vertices
+    let mut points = vec![(low, low), (high, high)];
+    if count >= 3 {
+        points.push((low, high));
+    }
+    if count >= 4 {
+        points.push((high, low));
+    }
+    if count >= 6 {
+        points.push((low, mid));
+        points.push((high, mid));
+    }
+    if count >= 8 {
+        points.push((mid, low));
+        points.push((mid, high));
+    }
+    if count % 2 == 1 && count >= 5 {
+        points.push((mid, mid));
+    }
+
+    Some(points.into_iter().map(|(x, row)| (x, size + 1 - row)).collect())
+}
@@ -0,0 +1,366 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Final position scoring
+
+use crate::engine::board::{Board, Stone};
+use crate::engine::move_generation::MoveGenerator;
+use crate::engine::rules::ScoringRule;
+
+/// A connected region of empty points and the colors bordering it
+struct EmptyRegion {
+    points: Vec<(usize, usize)>,
+    touches_black: bool,
+    touches_white: bool,
+}
+
+impl EmptyRegion {
+    /// The color owning this region, if it borders only one color
+    fn owner(&self) -> Stone {
+        match (self.touches_black, self.touches_white) {
+            (true, false) => Stone::Black,
+            (false, true) => Stone::White,
+            _ => Stone::Empty,
+        }
+    }
+}
+
+/// Flood-fills all empty regions of the board
+fn empty_regions(board: &Board) -> Vec<EmptyRegion> {
+    let size = board.size();
+    let mut visited = vec![vec![false; size]; size];
+    let mut regions = Vec::new();
+
+    for y in 1..=size {
+        for x in 1..=size {
+            if visited[y - 1][x - 1] || board.get_stone(x, y) != Stone::Empty {
+                continue;
+            }
+
+            let mut region = EmptyRegion {
+                points: Vec::new(),
+                touches_black: false,
+                touches_white: false,
+            };
+            let mut stack = vec![(x, y)];
+            visited[y - 1][x - 1] = true;
+
+            while let Some((cx, cy)) = stack.pop() {
+                region.points.push((cx, cy));
+                for (nx, ny) in board.neighbors(cx, cy) {
+                    match board.get_stone(nx, ny) {
+                        Stone::Black => region.touches_black = true,
+                        Stone::White => region.touches_white = true,
+                        Stone::Empty => {
+                            if !visited[ny - 1][nx - 1] {
+                                visited[ny - 1][nx - 1] = true;
+                                stack.push((nx, ny));
+                            }
+                        }
+                    }
+                }
+            }
+            regions.push(region);
+        }
+    }
+
+    regions
+}
+
+/// Owner of every empty point, indexed [y - 1][x - 1]. Occupied points
+/// and dame are `Stone::Empty`.
+pub fn territory_map(board: &Board) -> Vec<Vec<Stone>> {
+    let size = board.size();
+    let mut map = vec![vec![Stone::Empty; size]; size];
+    for region in empty_regions(board) {
+        let owner = region.owner();
+        for (x, y) in region.points {
+            map[y - 1][x - 1] = owner;
+        }
+    }
+    map
+}
+
+/// Empty regions bordered by a single color, with that color
+pub fn territories(board: &Board) -> Vec<(Stone, Vec<(usize, usize)>)> {
+    empty_regions(board)
+        .into_iter()
+        .filter(|region| region.owner() != Stone::Empty)
+        .map(|region| (region.owner(), region.points))
+        .collect()
+}
+
+/// Counts area (stones plus surrounded empty points) for both colors.
+/// Returns (black, white). Empty regions touching both colors are dame.
+pub fn area_score(board: &Board) -> (usize, usize) {
+    let mut black = board.stones_on_board(Stone::Black);
+    let mut white = board.stones_on_board(Stone::White);
+
+    for region in empty_regions(board) {
+        match region.owner() {
+            Stone::Black => black += region.points.len(),
+            Stone::White => white += region.points.len(),
+            Stone::Empty => {}
+        }
+    }
+
+    (black, white)
+}
+
+/// Stones of groups in seki: a black and a white group sharing a liberty
+/// and each down to two liberties, where the opponent cannot take any of
+/// them without putting itself in atari. A capturing race, where one side
+/// can still shorten the other's outside liberty, is not seki.
+pub fn seki_stones(board: &Board) -> Vec<(usize, usize)> {
+    let size = board.size();
+    let mut seki = Vec::new();
+    for y in 1..=size {
+        for x in 1..=size {
+            if board.get_stone(x, y) != Stone::Empty {
+                continue;
+            }
+            let groups: Vec<_> = board
+                .neighbors(x, y)
+                .into_iter()
+                .filter_map(|(nx, ny)| board.find_group(nx, ny))
+                .filter(|group| group.liberties() == 2 && !can_approach(board, group.positions()[0], group.color()))
+                .collect();
+            let shared = [Stone::Black, Stone::White].iter().all(|&color| groups.iter().any(|g| g.color() == color));
+            if shared {
+                for group in &groups {
+                    seki.extend_from_slice(group.positions());
+                }
+            }
+        }
+    }
+    seki.sort();
+    seki.dedup();
+    seki
+}
+
+/// Whether the opponent of the group at `stone` can take one of its
+/// liberties without being left in atari
+fn can_approach(board: &Board, (x, y): (usize, usize), color: Stone) -> bool {
+    let opponent = if color == Stone::Black { Stone::White } else { Stone::Black };
+    board
+        .find_liberties(x, y)
+        .into_iter()
+        .any(|(lx, ly)| !MoveGenerator::is_self_atari(board, lx, ly, opponent))
+}
+
+/// Counts territory (surrounded empty points) plus prisoners for both
+/// colors, treating `dead` stones as captured. Returns (black, white).
+/// Dame and the eyes of groups in seki (see [`seki_stones`]) are no one's
+/// territory.
+pub fn territory_score(board: &Board, dead: &[(usize, usize)]) -> (usize, usize) {
+    let [black_lost, white_lost] = board.get_captured();
+    let mut black = white_lost;
+    let mut white = black_lost;
+    for &(x, y) in dead {
+        match board.get_stone(x, y) {
+            Stone::Black => white += 1,
+            Stone::White => black += 1,
+            Stone::Empty => {}
+        }
+    }
+
+    let alive = remove_stones(board, dead);
+    let seki = seki_stones(&alive);
+    for region in empty_regions(&alive) {
+        let in_seki = region
+            .points
+            .iter()
+            .any(|&(x, y)| alive.neighbors(x, y).iter().any(|p| seki.contains(p)));
+        match region.owner() {
+            _ if in_seki => {}
+            Stone::Black => black += region.points.len(),
+            Stone::White => white += region.points.len(),
+            Stone::Empty => {}
+        }
+    }
+
+    (black, white)
+}
+
+/// Scores the position under `rule` after removing `dead` stones.
+/// Komi is not included. Returns (black, white).
+pub fn final_score(board: &Board, rule: ScoringRule, dead: &[(usize, usize)]) -> (usize, usize) {
+    match rule {
+        ScoringRule::Area => area_score(&remove_stones(board, dead)),
+        ScoringRule::Territory => territory_score(board, dead),
+    }
+}
+
+/// Points of each color in a scored position
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Score {
+    /// Black's points
+    pub black: usize,
+    /// White's points before komi
+    pub white: usize,
+    pub komi: f32,
+}
+
+impl Score {
+    /// Chinese area score of `board` after removing `dead`: stones plus
+    /// surrounded empty points, with komi for White
+    pub fn area(board: &Board, dead: &[(usize, usize)], komi: f32) -> Self {
+        let (black, white) = area_score(&remove_stones(board, dead));
+        Score { black, white, komi }
+    }
+
+    /// White's points with komi
+    pub fn white_total(&self) -> f32 {
+        self.white as f32 + self.komi
+    }
+
+    /// Black's lead over White, komi included; negative when White leads
+    pub fn margin(&self) -> f32 {
+        self.black as f32 - self.white_total()
+    }
+
+    /// The leading color; `None` for a draw
+    pub fn winner(&self) -> Option<Stone> {
+        let margin = self.margin();
+        if margin > 0.0 {
+            Some(Stone::Black)
+        } else if margin < 0.0 {
+            Some(Stone::White)
+        } else {
+            None
+        }
+    }
+
+    /// The margin as an SGF style result such as "W+6.5"
+    pub fn result(&self) -> String {
+        format_result(self.margin())
+    }
+}
+
+/// Returns a copy of the board with the given stones taken off
+pub fn remove_stones(board: &Board, stones: &[(usize, usize)]) -> Board {
+    let mut result = board.clone();
+    for &(x, y) in stones {
+        result.set_stone(x, y, Stone::Empty);
+    }
+    result
+}
+
+/// Formats a margin as an SGF style result such as "W+6.5"
+pub fn format_result(margin: f32) -> String {
+    if margin > 0.0 {
+        format!("B+{}", margin)
+    } else if margin < 0.0 {
+        format!("W+{}", -margin)
+    } else {
+        "0".to_string()
+    }
+}
+
+/// Guesses which stones are dead at the end of the game.
+///
+/// Stones and empty points not separated by enemy stones form an area.
+/// An area is strong if it owns two separate territories or at least six
+/// points of territory. Weak areas are examined smallest first: one is
+/// dead when it borders a strong enemy area, or when taking it off would
+/// leave a larger enemy area strong. Dead stones are removed before the
+/// next area is examined. Weak areas that never qualify, and areas with
+/// stones in seki (see [`seki_stones`]), are left alive.
+pub fn dead_stones(board: &Board) -> Vec<(usize, usize)> {
+    let mut working = board.clone();
+    let mut dead = Vec::new();
+
+    loop {
+        let seki = seki_stones(&working);
+        let mut candidates = Vec::new();
+        for color in [Stone::Black, Stone::White] {
+            let enemy = if color == Stone::Black { Stone::White } else { Stone::Black };
+            let (_, areas) = color_areas(&working, color);
+            let (enemy_labels, enemy_areas) = color_areas(&working, enemy);
+
+            for area in areas.into_iter().filter(|a| !a.strong && !a.stones.iter().any(|p| seki.contains(p))) {
+                let faces_strong_enemy = area.empty.iter().any(|&(x, y)| {
+                    enemy_labels[y - 1][x - 1].is_some_and(|id| enemy_areas[id].strong)
+                });
+                if faces_strong_enemy || captured_area_strengthens_enemy(&working, &area, enemy) {
+                    candidates.push(area);
+                }
+            }
+        }
+
+        let Some(smallest) = candidates.into_iter().min_by_key(|a| a.stones.len()) else {
+            break;
+        };
+        working = remove_stones(&working, &smallest.stones);
+        dead.extend(smallest.stones);
+    }
+
+    dead.sort();
+    dead
+}
+
+/// Checks whether removing `area` leaves the enemy area that absorbs it
+/// strong and larger than the removed stones
+fn captured_area_strengthens_enemy(board: &Board, area: &Area, enemy: Stone) -> bool {
+    let reduced = remove_stones(board, &area.stones);
+    let (labels, areas) = color_areas(&reduced, enemy);
+    let (x, y) = area.stones[0];
+    labels[y - 1][x - 1].is_some_and(|id| areas[id].strong && areas[id].stones.len() > area.stones.len())
+}
+
+/// Stones of one color together with the empty points they reach
+struct Area {
+    stones: Vec<(usize, usize)>,
+    empty: Vec<(usize, usize)>,
+    strong: bool,
+}
+
+/// Partitions the stones of `color` into areas. Returns the area index of
+/// every point reached by one, and the areas themselves.
+fn color_areas(board: &Board, color: Stone) -> (Vec<Vec<Option<usize>>>, Vec<Area>) {
+    let size = board.size();
+    let regions = empty_regions(board);
+    let mut labels = vec![vec![None; size]; size];
+    let mut areas = Vec::new();
+
+    for y in 1..=size {
+        for x in 1..=size {
+            if board.get_stone(x, y) != color || labels[y - 1][x - 1].is_some() {
+                continue;
+            }
+            let id = areas.len();
+            let mut area = Area {
+                stones: Vec::new(),
+                empty: Vec::new(),
+                strong: false,
+            };
+            let mut stack = vec![(x, y)];
+            labels[y - 1][x - 1] = Some(id);
+
+            while let Some((cx, cy)) = stack.pop() {
+                if board.get_stone(cx, cy) == color {
+                    area.stones.push((cx, cy));
+                } else {
+                    area.empty.push((cx, cy));
+                }
+                for (nx, ny) in board.neighbors(cx, cy) {
+                    let stone = board.get_stone(nx, ny);
+                    if (stone == color || stone == Stone::Empty) && labels[ny - 1][nx - 1].is_none() {
+                        labels[ny - 1][nx - 1] = Some(id);
+                        stack.push((nx, ny));
+                    }
+                }
+            }
+
+            let owned: Vec<&EmptyRegion> = regions
+                .iter()
+                .filter(|r| r.owner() == color && labels[r.points[0].1 - 1][r.points[0].0 - 1] == Some(id))
+                .collect();
+            let points: usize = owned.iter().map(|r| r.points.len()).sum();
+            area.strong = owned.len() >= 2 || points >= 6;
+            areas.push(area);
+        }
+    }
+
+    (labels, areas)
+}
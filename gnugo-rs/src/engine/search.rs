@@ -0,0 +1,268 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Alpha-beta search for the Advanced level: depth-limited negamax with
+//! alpha-beta pruning and iterative deepening, played out on one board
+//! with [`Board::try_move`] and [`Board::pop_move`]
+
+use std::cmp::Reverse;
+use std::time::Instant;
+
+use crate::engine::board::{Board, Stone};
+use crate::engine::evaluation::Evaluator;
+use crate::engine::move_generation::Move;
+use crate::patterns::owl;
+
+/// Moves searched at each node, best ordered first
+pub const SEARCH_WIDTH: usize = 8;
+
+/// Ordering bonus per stone captured by a move
+const CAPTURE_ORDER: i32 = 100;
+
+/// Ordering bonus per own stone a move takes out of atari
+const RESCUE_ORDER: i32 = 50;
+
+/// Share of a matching shape pattern's value added to the ordering score
+const PATTERN_ORDER_SHARE: i32 = 2;
+
+/// Points from the last move within which a move counts as near it
+const PROXIMITY_RANGE: usize = 3;
+
+/// Ordering bonus per point a move is nearer the last move than
+/// [`PROXIMITY_RANGE`] and one more
+const PROXIMITY_ORDER: i32 = 5;
+
+/// Ordering bonus per stone of either color next to a move
+const CONTACT_ORDER: i32 = 10;
+
+/// Positions visited between looks at the clock
+const CLOCK_INTERVAL: usize = 64;
+
+/// Outcome of a search
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchResult {
+    /// Root moves with what they gain for the mover, best first; below
+    /// the best, pruning leaves only upper bounds unless every root move
+    /// was searched in full, as by [`exact_values`]
+    pub values: Vec<((usize, usize), i32)>,
+    /// The expected line of play after each move of `values`, in the same
+    /// order, starting with the move itself
+    pub variations: Vec<Vec<(usize, usize)>>,
+    /// Plies of the deepest iteration that finished
+    pub depth: usize,
+    /// Positions visited over all iterations
+    pub nodes: usize,
+}
+
+impl SearchResult {
+    pub fn best(&self) -> Option<((usize, usize), i32)> {
+        self.values.first().copied()
+    }
+}
+
+fn opponent(color: Stone) -> Stone {
+    match color {
+        Stone::Black => Stone::White,
+        Stone::White => Stone::Black,
+        Stone::Empty => Stone::Empty,
+    }
+}
+
+/// Evaluation from the point of view of `color`
+fn evaluate(board: &Board, color: Stone) -> i32 {
+    let score = Evaluator::evaluate_position(board);
+    if color == Stone::Black { score } else { -score }
+}
+
+struct Search {
+    board: Board,
+    nodes: usize,
+    budget: usize,
+    deadline: Option<Instant>,
+    /// Set once the deadline has passed
+    timed_out: bool,
+}
+
+impl Search {
+    /// Whether the node budget or the time is used up
+    fn exhausted(&mut self) -> bool {
+        if !self.timed_out && self.nodes.is_multiple_of(CLOCK_INTERVAL) {
+            self.timed_out = self.deadline.is_some_and(|deadline| Instant::now() >= deadline);
+        }
+        self.timed_out || self.nodes >= self.budget
+    }
+
+    /// Negamax value of the position for `color` to move, `depth` plies
+    /// deep, with the best line found left in `line`; `None` once the
+    /// node budget runs out
+    fn negamax(&mut self, color: Stone, depth: usize, mut alpha: i32, beta: i32, line: &mut Vec<(usize, usize)>) -> Option<i32> {
+        line.clear();
+        if depth == 0 {
+            return Some(evaluate(&self.board, color));
+        }
+        let mut best = None;
+        let mut reply = Vec::new();
+        for (x, y) in ordered_moves(&self.board, color, SEARCH_WIDTH) {
+            if self.exhausted() {
+                return None;
+            }
+            if !self.board.try_move(x, y, color) {
+                continue;
+            }
+            self.nodes += 1;
+            let value = self.negamax(opponent(color), depth - 1, -beta, -alpha, &mut reply).map(|v| -v);
+            self.board.pop_move();
+            let value = value?;
+            if best.is_none_or(|b| value > b) {
+                best = Some(value);
+                line.clear();
+                line.push((x, y));
+                line.extend_from_slice(&reply);
+            }
+            alpha = alpha.max(value);
+            if alpha >= beta {
+                break;
+            }
+        }
+        // No move to make: the position stands as it is
+        Some(best.unwrap_or_else(|| evaluate(&self.board, color)))
+    }
+}
+
+/// Searches `moves` for `color` with iterative deepening up to `depth`
+/// plies, within `budget` positions. Each iteration tries the previous
+/// one's best move first; an iteration cut short by the budget is
+/// dropped. Values are relative to the position before the move
+pub fn alpha_beta(board: &Board, color: Stone, moves: &[(usize, usize)], depth: usize, budget: usize) -> SearchResult {
+    alpha_beta_until(board, color, moves, depth, budget, None)
+}
+
+/// Like [`alpha_beta`], also stopping at `deadline`: the result is that
+/// of the deepest iteration finished in time
+pub fn alpha_beta_until(
+    board: &Board,
+    color: Stone,
+    moves: &[(usize, usize)],
+    depth: usize,
+    budget: usize,
+    deadline: Option<Instant>,
+) -> SearchResult {
+    deepen(board, color, moves, depth, budget, deadline, false)
+}
+
+/// Like [`alpha_beta`], but searching every root move in full, so each
+/// value is exact rather than a bound; for ranking several moves
+pub fn exact_values(board: &Board, color: Stone, moves: &[(usize, usize)], depth: usize, budget: usize) -> SearchResult {
+    deepen(board, color, moves, depth, budget, None, true)
+}
+
+/// Iterative deepening over the root `moves`; with `exact` every root
+/// move gets a full window
+fn deepen(
+    board: &Board,
+    color: Stone,
+    moves: &[(usize, usize)],
+    depth: usize,
+    budget: usize,
+    deadline: Option<Instant>,
+    exact: bool,
+) -> SearchResult {
+    let before = evaluate(board, color);
+    let mut search = Search { board: board.clone(), nodes: 0, budget, deadline, timed_out: false };
+    let mut result = SearchResult { values: Vec::new(), variations: Vec::new(), depth: 0, nodes: 0 };
+    let mut order = moves.to_vec();
+    let mut reply = Vec::new();
+
+    'deepening: for plies in 1..=depth.max(1) {
+        let mut lines = Vec::new();
+        let mut alpha = i32::MIN + 1;
+        for &(x, y) in &order {
+            if search.exhausted() {
+                break 'deepening;
+            }
+            if !search.board.try_move(x, y, color) {
+                continue;
+            }
+            search.nodes += 1;
+            // Later moves only need to show they are better than the best so far
+            let bound = if exact { i32::MAX } else { -alpha };
+            let value = search.negamax(opponent(color), plies - 1, i32::MIN + 1, bound, &mut reply).map(|v| -v);
+            search.board.pop_move();
+            let Some(value) = value else {
+                break 'deepening;
+            };
+            alpha = alpha.max(value);
+            let mut line = vec![(x, y)];
+            line.extend_from_slice(&reply);
+            lines.push(((x, y), value - before, line));
+        }
+        lines.sort_by_key(|&((x, y), value, _)| (Reverse(value), y, x));
+        order = lines.iter().map(|&(point, _, _)| point).collect();
+        result.values = lines.iter().map(|&(point, value, _)| (point, value)).collect();
+        result.variations = lines.into_iter().map(|(_, _, line)| line).collect();
+        result.depth = plies;
+    }
+    result.nodes = search.nodes;
+    result
+}
+
+/// Up to `width` moves for `color`, most promising first by
+/// [`order_moves`]. Own single-point eyes are never filled
+pub fn ordered_moves(board: &Board, color: Stone, width: usize) -> Vec<(usize, usize)> {
+    let size = board.size();
+    let mut moves = Vec::new();
+    for y in 1..=size {
+        for x in 1..=size {
+            if board.get_stone(x, y) != Stone::Empty || board.get_ko_point() == Some((x, y)) {
+                continue;
+            }
+            if board.neighbors(x, y).iter().all(|&(nx, ny)| board.get_stone(nx, ny) == color) {
+                continue;
+            }
+            moves.push(Move::new(x, y));
+        }
+    }
+    order_moves(board, color, &moves).into_iter().take(width).map(|m| (m.row, m.col)).collect()
+}
+
+/// `moves` for `color`, most promising first by a score weighing, from
+/// heaviest to lightest: captures, escapes from atari, shape patterns,
+/// nearness to the last move and contact with other stones. Points
+/// nearer the center break ties
+pub fn order_moves(board: &Board, color: Stone, moves: &[Move]) -> Vec<Move> {
+    let center = board.size() + 1;
+    let last = board.recent_stones(1).first().map(|&(x, y, _)| (x, y));
+    let mut scored = Vec::with_capacity(moves.len());
+    for &m in moves {
+        let (x, y) = (m.row, m.col);
+        let mut score = 0;
+        let mut seen: Vec<(usize, usize)> = Vec::new();
+        for (nx, ny) in board.neighbors(x, y) {
+            let Some(group) = board.find_group(nx, ny) else { continue };
+            score += CONTACT_ORDER;
+            // A string touched on two sides counts once
+            let origin = group.positions().iter().copied().min().unwrap_or((nx, ny));
+            if seen.contains(&origin) {
+                continue;
+            }
+            seen.push(origin);
+            if group.liberties() == 1 {
+                score += if group.color() == color { RESCUE_ORDER } else { CAPTURE_ORDER } * group.positions().len() as i32;
+            }
+        }
+        let pattern = owl::attack_pattern(board, (x, y), opponent(color))
+            .into_iter()
+            .chain(owl::defense_pattern(board, (x, y), color))
+            .map(|p| p.value)
+            .max()
+            .unwrap_or(0);
+        score += pattern / PATTERN_ORDER_SHARE;
+        if let Some((lx, ly)) = last {
+            score += PROXIMITY_ORDER * (PROXIMITY_RANGE + 1).saturating_sub(x.abs_diff(lx) + y.abs_diff(ly)) as i32;
+        }
+        let distance = (2 * x).abs_diff(center) + (2 * y).abs_diff(center);
+        scored.push(((Reverse(score), distance, y, x), m));
+    }
+    scored.sort_by_key(|&(key, _)| key);
+    scored.into_iter().map(|(_, m)| m).collect()
+}
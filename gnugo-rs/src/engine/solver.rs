@@ -0,0 +1,324 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Perfect play on tiny boards: alpha-beta over the whole game with a
+//! transposition table, under area scoring and positional superko, the
+//! game ending at two passes in a row
+//!
+//! Pass-alive strings found by Benson's algorithm bound the score, which
+//! settles most positions long before the end of the game.
+//!
+//! A result found while superko ruled out a move depends on the moves
+//! that led to it, so each stored result keeps the earlier positions its
+//! superko cuts repeated and is only used again while they are all on
+//! the path.
+
+use std::collections::HashMap;
+
+use crate::engine::board::{Board, Stone};
+use crate::engine::scoring::area_score;
+
+/// Largest board the solver takes
+pub const MAX_SOLVE_SIZE: usize = 5;
+
+/// Default node budget for a solve
+pub const DEFAULT_SOLVE_NODES: usize = 50_000_000;
+
+/// Stack for the solving thread: a game can run through very many
+/// positions before superko ends it, a ply of recursion each
+const SOLVER_STACK: usize = 1 << 30;
+
+/// Outcome of [`solve_game`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GameSolution {
+    /// Black's area minus White's with best play by both, before komi
+    pub score: i32,
+    /// Black's margin with komi; below zero White wins
+    pub margin: f32,
+    /// The first move of best play; `None` to pass
+    pub best_move: Option<(usize, usize)>,
+    /// Positions visited
+    pub nodes: usize,
+}
+
+impl GameSolution {
+    /// `None` for a draw
+    pub fn winner(&self) -> Option<Stone> {
+        if self.margin > 0.0 {
+            Some(Stone::Black)
+        } else if self.margin < 0.0 {
+            Some(Stone::White)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Bound {
+    Exact,
+    /// The value is at least this
+    Lower,
+    /// The value is at most this
+    Upper,
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    value: i32,
+    bound: Bound,
+    best: Option<(usize, usize)>,
+    /// Hashes of the positions before this one that superko cuts below repeated
+    repeated: Vec<u64>,
+}
+
+/// Best value found at a node, the move behind it and the hashes of the
+/// positions before it that superko cuts below repeated
+type Outcome = (i32, Option<(usize, usize)>, Vec<u64>);
+
+/// Connected points, as a string or a region
+type Points = Vec<(usize, usize)>;
+
+struct Solver {
+    board: Board,
+    /// Position hashes since the start, for superko
+    path: Vec<u64>,
+    /// By position hash, player to move and whether the last move passed
+    table: HashMap<(u64, Stone, bool), Entry>,
+    nodes: usize,
+    max_nodes: usize,
+    /// Every point, nearest the center first
+    order: Vec<(usize, usize)>,
+}
+
+fn opponent(color: Stone) -> Stone {
+    match color {
+        Stone::Black => Stone::White,
+        Stone::White => Stone::Black,
+        Stone::Empty => Stone::Empty,
+    }
+}
+
+/// Strings of `color`, as point lists, and the regions they enclose: the
+/// connected sets of points not of `color`
+fn strings_and_regions(board: &Board, color: Stone) -> (Vec<Points>, Vec<Points>) {
+    let size = board.size();
+    let mut seen = vec![false; (size + 1) * (size + 1)];
+    let (mut strings, mut regions) = (Vec::new(), Vec::new());
+    for y in 1..=size {
+        for x in 1..=size {
+            if seen[y * (size + 1) + x] {
+                continue;
+            }
+            let own = board.get_stone(x, y) == color;
+            seen[y * (size + 1) + x] = true;
+            let mut points = vec![(x, y)];
+            let mut i = 0;
+            while i < points.len() {
+                let (px, py) = points[i];
+                for (nx, ny) in board.neighbors(px, py) {
+                    if !seen[ny * (size + 1) + nx] && (board.get_stone(nx, ny) == color) == own {
+                        seen[ny * (size + 1) + nx] = true;
+                        points.push((nx, ny));
+                    }
+                }
+                i += 1;
+            }
+            if own { strings.push(points) } else { regions.push(points) }
+        }
+    }
+    (strings, regions)
+}
+
+/// Points certain to end as `color`'s area, by Benson's algorithm: its
+/// pass-alive strings, and the regions they enclose whose empty points
+/// all touch them, where the opponent cannot make an eye
+fn safe_area(board: &Board, color: Stone) -> usize {
+    let (strings, regions) = strings_and_regions(board, color);
+    let touches = |region: &[(usize, usize)], string: &[(usize, usize)]| {
+        region.iter().any(|&(x, y)| board.neighbors(x, y).iter().any(|p| string.contains(p)))
+    };
+    // A region is vital to a string when each of its empty points is a
+    // liberty of the string
+    let vital = |region: &[(usize, usize)], string: &[(usize, usize)]| {
+        region
+            .iter()
+            .filter(|&&(x, y)| board.get_stone(x, y) == Stone::Empty)
+            .all(|&(x, y)| board.neighbors(x, y).iter().any(|p| string.contains(p)))
+    };
+    let mut alive = vec![true; strings.len()];
+    let mut enclosed = vec![true; regions.len()];
+    loop {
+        let mut changed = false;
+        for (s, string) in strings.iter().enumerate() {
+            let vitals = (0..regions.len()).filter(|&r| enclosed[r] && touches(&regions[r], string) && vital(&regions[r], string)).count();
+            if alive[s] && vitals < 2 {
+                alive[s] = false;
+                changed = true;
+            }
+        }
+        for (r, region) in regions.iter().enumerate() {
+            if enclosed[r] && (0..strings.len()).any(|s| !alive[s] && touches(region, &strings[s])) {
+                enclosed[r] = false;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    let stones: usize = (0..strings.len()).filter(|&s| alive[s]).map(|s| strings[s].len()).sum();
+    let territory: usize = (0..regions.len())
+        .filter(|&r| enclosed[r] && (0..strings.len()).any(|s| alive[s] && vital(&regions[r], &strings[s])))
+        .map(|r| regions[r].len())
+        .sum();
+    stones + territory
+}
+
+impl Solver {
+    /// Area of `color` minus the opponent's once the game is over
+    fn final_score(&self, color: Stone) -> i32 {
+        let (black, white) = area_score(&self.board);
+        let score = black as i32 - white as i32;
+        if color == Stone::Black { score } else { -score }
+    }
+
+    /// Negamax value for `color` to move; `None` once the node budget
+    /// runs out
+    fn negamax(&mut self, color: Stone, passed: bool, mut alpha: i32, beta: i32) -> Option<Outcome> {
+        self.nodes += 1;
+        if self.nodes > self.max_nodes {
+            return None;
+        }
+        // Away from the root, safe areas may settle the result or show it
+        // cannot matter
+        if self.path.len() > 1 || passed {
+            let points = (self.board.size() * self.board.size()) as i32;
+            let own = safe_area(&self.board, color) as i32;
+            let other = safe_area(&self.board, opponent(color)) as i32;
+            let (lowest, highest) = (2 * own - points, points - 2 * other);
+            if lowest == highest || highest <= alpha {
+                return Some((highest, None, Vec::new()));
+            }
+            if lowest >= beta {
+                return Some((lowest, None, Vec::new()));
+            }
+        }
+        let key = (self.board.position_hash(), color, passed);
+        let mut first = None;
+        if let Some(entry) = self.table.get(&key) {
+            let usable = entry.repeated.iter().all(|h| self.path.contains(h))
+                && match entry.bound {
+                    Bound::Exact => true,
+                    Bound::Lower => entry.value >= beta,
+                    Bound::Upper => entry.value <= alpha,
+                };
+            if usable {
+                return Some((entry.value, entry.best, entry.repeated.clone()));
+            }
+            first = Some(entry.best);
+        }
+
+        // The stored best move first, then the board from the center out,
+        // then a pass
+        let mut moves: Vec<Option<(usize, usize)>> = first.into_iter().collect();
+        moves.extend(self.order.iter().filter(|&&(x, y)| first != Some(Some((x, y))) && self.board.get_stone(x, y) == Stone::Empty).map(|&p| Some(p)));
+        if first != Some(None) {
+            moves.push(None);
+        }
+
+        let alpha_before = alpha;
+        let mut best: Option<(i32, Option<(usize, usize)>)> = None;
+        let mut repeated: Vec<u64> = Vec::new();
+        for candidate in moves {
+            let (value, below) = match candidate {
+                Some((x, y)) => {
+                    // Superko covers simple ko along the path
+                    self.board.clear_ko_point();
+                    if !self.board.try_move(x, y, color) {
+                        continue;
+                    }
+                    let hash = self.board.position_hash();
+                    if self.path.contains(&hash) {
+                        self.board.pop_move();
+                        repeated.push(hash);
+                        continue;
+                    }
+                    self.path.push(hash);
+                    let child = self.negamax(opponent(color), false, -beta, -alpha);
+                    self.path.pop();
+                    self.board.pop_move();
+                    let (value, _, below) = child?;
+                    (-value, below)
+                }
+                None if passed => (self.final_score(color), Vec::new()),
+                None => {
+                    let (value, _, below) = self.negamax(opponent(color), true, -beta, -alpha)?;
+                    (-value, below)
+                }
+            };
+            repeated.extend(below);
+            if best.is_none_or(|(b, _)| value > b) {
+                best = Some((value, candidate));
+            }
+            alpha = alpha.max(value);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        // Passing is always legal, so there is a best move
+        let (value, point) = best.unwrap_or((self.final_score(color), None));
+        // The position itself is on the path wherever it is searched
+        let here = self.board.position_hash();
+        repeated.sort_unstable();
+        repeated.dedup();
+        repeated.retain(|&h| h != here);
+        let bound = if value <= alpha_before {
+            Bound::Upper
+        } else if value >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.table.insert(key, Entry { value, bound, best: point, repeated: repeated.clone() });
+        Some((value, point, repeated))
+    }
+}
+
+/// Solves the game on `board`, a board of at most [`MAX_SOLVE_SIZE`]
+/// lines, with `to_move` playing first and no positions before it, within
+/// `max_nodes` positions
+pub fn solve_game(board: &Board, to_move: Stone, komi: f32, max_nodes: usize) -> Result<GameSolution, String> {
+    let size = board.size();
+    if size > MAX_SOLVE_SIZE {
+        return Err(format!("Only boards up to {}x{} can be solved", MAX_SOLVE_SIZE, MAX_SOLVE_SIZE));
+    }
+    if to_move == Stone::Empty {
+        return Err("No side to move".to_string());
+    }
+    let center = size + 1;
+    let mut order: Vec<(usize, usize)> = (1..=size).flat_map(|y| (1..=size).map(move |x| (x, y))).collect();
+    order.sort_by_key(|&(x, y)| ((2 * x).abs_diff(center) + (2 * y).abs_diff(center), y, x));
+    let mut solver =
+        Solver { board: board.clone(), path: vec![board.position_hash()], table: HashMap::new(), nodes: 0, max_nodes, order };
+    // No result lies outside the whole board's area
+    let bound = (size * size) as i32;
+    let mut run = || solver.negamax(to_move, false, -bound, bound);
+    let outcome = if cfg!(feature = "native") {
+        std::thread::scope(|scope| {
+            std::thread::Builder::new()
+                .stack_size(SOLVER_STACK)
+                .spawn_scoped(scope, run)
+                .map_err(|e| format!("Cannot start the solver: {}", e))?
+                .join()
+                .map_err(|_| "The solver failed".to_string())
+        })?
+    } else {
+        run()
+    };
+    let (value, best_move, _) = outcome.ok_or_else(|| format!("No result within {} nodes", max_nodes))?;
+    let nodes = solver.nodes;
+    let score = if to_move == Stone::Black { value } else { -value };
+    Ok(GameSolution { score, margin: score as f32 - komi, best_move, nodes })
+}
@@ -0,0 +1,155 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Records the variations a search reads as an SGF tree
+//!
+//! Searches call [`SearchTrace::enter`] before trying a move and
+//! [`SearchTrace::leave`] afterwards, with [`SearchTrace::comment`] to note
+//! what they concluded. Every search traced into the same recorder becomes
+//! one top-level variation, as with GNU Go's `start_sgftrace`; its first
+//! node sets up the position the search started from.
+
+use std::collections::HashMap;
+
+use crate::engine::board::{Board, Stone};
+use crate::sgf::{SGFNode, SGFProperty, SGFTree};
+
+/// A move of the trace, `None` being a pass
+type TraceMove = (Stone, Option<(usize, usize)>);
+
+#[derive(Debug, Clone, Default)]
+struct TraceNode {
+    mv: Option<TraceMove>,
+    /// Points changed relative to the start position (search nodes only)
+    setup: Vec<(Stone, (usize, usize))>,
+    comment: Vec<String>,
+    children: Vec<usize>,
+}
+
+/// Variation tree rooted at the position where tracing started
+#[derive(Debug, Clone)]
+pub struct SearchTrace {
+    board: Board,
+    to_move: Stone,
+    nodes: Vec<TraceNode>,
+    /// Path from the root to the current node
+    path: Vec<usize>,
+    /// Length of `path` at the current search's node
+    base: usize,
+}
+
+impl SearchTrace {
+    /// Starts an empty trace of searches from `board` with `to_move` to play
+    pub fn new(board: &Board, to_move: Stone) -> Self {
+        SearchTrace {
+            board: board.clone(),
+            to_move,
+            nodes: vec![TraceNode::default()],
+            path: vec![0],
+            base: 1,
+        }
+    }
+
+    /// Number of moves recorded
+    pub fn len(&self) -> usize {
+        self.nodes.iter().filter(|node| node.mv.is_some()).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Descends into `point` for `color`, reusing an existing variation
+    pub fn enter(&mut self, color: Stone, point: Option<(usize, usize)>) {
+        let current = *self.path.last().unwrap_or(&0);
+        let existing = self.nodes[current]
+            .children
+            .iter()
+            .copied()
+            .find(|&child| self.nodes[child].mv == Some((color, point)));
+        let child = existing.unwrap_or_else(|| {
+            self.nodes.push(TraceNode { mv: Some((color, point)), ..Default::default() });
+            let child = self.nodes.len() - 1;
+            self.nodes[current].children.push(child);
+            child
+        });
+        self.path.push(child);
+    }
+
+    /// Returns to the parent of the current node
+    pub fn leave(&mut self) {
+        if self.path.len() > self.base {
+            self.path.pop();
+        }
+    }
+
+    /// Attaches a note to the current node
+    pub fn comment(&mut self, text: impl Into<String>) {
+        let current = *self.path.last().unwrap_or(&0);
+        self.nodes[current].comment.push(text.into());
+    }
+
+    /// Starts a new top-level variation labelled `title` for a search of
+    /// `board`; moves entered until the next call hang below it
+    pub fn begin_search(&mut self, title: impl Into<String>, board: &Board) {
+        let size = self.board.size().min(board.size());
+        let setup = (1..=size)
+            .flat_map(|y| (1..=size).map(move |x| (x, y)))
+            .filter(|&(x, y)| board.get_stone(x, y) != self.board.get_stone(x, y))
+            .map(|(x, y)| (board.get_stone(x, y), (x, y)))
+            .collect();
+        self.nodes.push(TraceNode { setup, comment: vec![title.into()], ..Default::default() });
+        let node = self.nodes.len() - 1;
+        self.nodes[0].children.push(node);
+        self.path = vec![0, node];
+        self.base = self.path.len();
+    }
+
+    /// The trace as an SGF game tree, the start position as setup stones
+    pub fn to_sgf_tree(&self) -> SGFTree {
+        let size = self.board.size();
+        let mut root = self.sgf_node(0);
+        let mut set = |key: &str, value: SGFProperty| {
+            root.properties.entry(key.to_string()).or_default().push(value);
+        };
+        set("FF", SGFProperty::Number(4));
+        set("GM", SGFProperty::Number(1));
+        set("SZ", SGFProperty::Number(size as i32));
+        set("PL", SGFProperty::Color(self.to_move));
+        for y in 1..=size {
+            for x in 1..=size {
+                match self.board.get_stone(x, y) {
+                    Stone::Black => set("AB", SGFProperty::Point((x - 1, y - 1))),
+                    Stone::White => set("AW", SGFProperty::Point((x - 1, y - 1))),
+                    Stone::Empty => {}
+                }
+            }
+        }
+        SGFTree { root, current: 0 }
+    }
+
+    fn sgf_node(&self, index: usize) -> SGFNode {
+        let node = &self.nodes[index];
+        let mut properties = HashMap::new();
+        if let Some((color, point)) = node.mv {
+            let key = if color == Stone::Black { "B" } else { "W" };
+            let value = point.map_or(SGFProperty::Text(String::new()), |(x, y)| SGFProperty::Point((x - 1, y - 1)));
+            properties.insert(key.to_string(), vec![value]);
+        }
+        for &(stone, (x, y)) in &node.setup {
+            let key = match stone {
+                Stone::Black => "AB",
+                Stone::White => "AW",
+                Stone::Empty => "AE",
+            };
+            properties.entry(key.to_string()).or_default().push(SGFProperty::Point((x - 1, y - 1)));
+        }
+        if !node.comment.is_empty() {
+            properties.insert("C".to_string(), vec![SGFProperty::Text(node.comment.join("\n"))]);
+        }
+        SGFNode {
+            properties,
+            children: node.children.iter().map(|&child| self.sgf_node(child)).collect(),
+        }
+    }
+}
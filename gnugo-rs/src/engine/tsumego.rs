@@ -0,0 +1,403 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Life-and-death (tsumego) reading for a single target group
+
+use std::collections::HashMap;
+
+use crate::engine::ai::ResourceLimits;
+use crate::engine::board::{Board, Stone};
+use crate::engine::profile::{self, Subsystem};
+use crate::engine::trace::SearchTrace;
+use crate::gtp::format_move;
+
+/// Outcome of life-and-death reading
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LifeStatus {
+    Alive,
+    Dead,
+    /// Whoever wins the ko decides the group's fate
+    Ko,
+    /// The node budget ran out before a result was proven
+    Unknown,
+}
+
+impl LifeStatus {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LifeStatus::Alive => "alive",
+            LifeStatus::Dead => "dead",
+            LifeStatus::Ko => "ko",
+            LifeStatus::Unknown => "unknown",
+        }
+    }
+}
+
+/// Result of solving a problem
+#[derive(Debug, Clone)]
+pub struct SolveResult {
+    pub status: LifeStatus,
+    /// Principal variation, `None` being a pass
+    pub main_line: Vec<(Stone, Option<(usize, usize)>)>,
+    /// Number of positions visited
+    pub nodes: usize,
+}
+
+/// Region contents, side to move, pass count and ko point
+type PositionKey = (Vec<Stone>, Stone, u32, Option<(usize, usize)>);
+
+/// Default node budget for a solve
+pub const DEFAULT_MAX_NODES: usize = 200_000;
+
+/// Bounded life-and-death reader for the group at `target`
+pub struct TsumegoSolver {
+    target: (usize, usize),
+    defender: Stone,
+    region: Vec<(usize, usize)>,
+    nodes: usize,
+    max_nodes: usize,
+    table: HashMap<PositionKey, bool>,
+    /// Results stop being stored once the table holds this many
+    max_table: usize,
+    /// Positions on the current search path, to cut off repetition cycles
+    path: Vec<Vec<Stone>>,
+    /// Records the variations read, when set
+    trace: Option<SearchTrace>,
+    /// Side that may retake a ko at once, as if it had ko threats to spare
+    ko_master: Option<Stone>,
+}
+
+/// Solution of a life-and-death problem from [`solve_life_death`]
+#[derive(Debug, Clone)]
+pub struct LifeDeathSolution {
+    /// A stone of the group read
+    pub target: (usize, usize),
+    pub status: LifeStatus,
+    /// The first move of the side to move in the main line; `None` when
+    /// it passes or has no move that helps
+    pub solving_move: Option<(usize, usize)>,
+    /// Principal variation, `None` being a pass
+    pub main_line: Vec<(Stone, Option<(usize, usize)>)>,
+    /// Positions visited over both readings
+    pub nodes: usize,
+}
+
+impl TsumegoSolver {
+    /// Creates a solver for the group at `target`. The search region is the
+    /// bounding box of all stones on the board, widened by one line.
+    pub fn new(board: &Board, target: (usize, usize), max_nodes: usize) -> Self {
+        TsumegoSolver {
+            target,
+            defender: board.get_stone(target.0, target.1),
+            region: default_region(board),
+            nodes: 0,
+            max_nodes,
+            table: HashMap::new(),
+            max_table: usize::MAX,
+            path: Vec::new(),
+            trace: None,
+            ko_master: None,
+        }
+    }
+
+    /// Lets `color` retake kos without a ko threat
+    pub fn with_ko_master(mut self, color: Stone) -> Self {
+        self.ko_master = Some(color);
+        self
+    }
+
+    /// Restricts the moves read to `region`
+    pub fn with_region(mut self, region: Vec<(usize, usize)>) -> Self {
+        self.region = region;
+        self
+    }
+
+    /// Caps the node budget and the transposition table size
+    pub fn apply_limits(&mut self, limits: &ResourceLimits) {
+        self.max_nodes = limits.nodes(self.max_nodes);
+        // Key vector, side to move, passes, ko point and hash map overhead
+        let entry_bytes = self.region.len() * std::mem::size_of::<Stone>() + 64;
+        self.max_table = limits.table_entries(entry_bytes);
+    }
+
+    fn store(&mut self, key: PositionKey, result: bool) {
+        if self.table.len() < self.max_table {
+            self.table.insert(key, result);
+        }
+    }
+
+    /// Records the variations of following solves into `trace`
+    pub fn set_trace(&mut self, trace: SearchTrace) {
+        self.trace = Some(trace);
+    }
+
+    /// Removes and returns the trace set with [`TsumegoSolver::set_trace`]
+    pub fn take_trace(&mut self) -> Option<SearchTrace> {
+        self.trace.take()
+    }
+
+    fn note(&mut self, text: &str) {
+        if let Some(trace) = &mut self.trace {
+            trace.comment(text);
+        }
+    }
+
+    /// Reads the problem with `to_move` playing first
+    pub fn solve(&mut self, board: &Board, to_move: Stone) -> SolveResult {
+        let vertex = format_move(self.target.0, self.target.1, board.size());
+        if let Some(trace) = &mut self.trace {
+            trace.begin_search(format!("tsumego {}, {} to move", vertex, to_move), board);
+        }
+        let mut line = Vec::new();
+        let status = if self.defender == Stone::Empty {
+            LifeStatus::Dead
+        } else {
+            match profile::measure(Subsystem::Owl, || self.attacker_wins(board, to_move, 0, &mut line)) {
+                Some(true) => LifeStatus::Dead,
+                Some(false) => LifeStatus::Alive,
+                None => LifeStatus::Unknown,
+            }
+        };
+
+        self.note(&format!("{}: {} after {} nodes", vertex, status.name(), self.nodes));
+        SolveResult {
+            status,
+            main_line: line,
+            nodes: self.nodes,
+        }
+    }
+
+    /// Returns whether the attacker captures the target with best play,
+    /// or `None` if the node budget was exhausted
+    fn attacker_wins(
+        &mut self,
+        board: &Board,
+        to_move: Stone,
+        passes: u32,
+        line: &mut Vec<(Stone, Option<(usize, usize)>)>,
+    ) -> Option<bool> {
+        // A target just taken in a ko is not captured until the ko is over
+        if board.get_stone(self.target.0, self.target.1) != self.defender && board.get_ko_point() != Some(self.target) {
+            self.note("captured");
+            return Some(true);
+        }
+        if passes >= 2 || self.has_two_eyes(board) {
+            self.note(if passes >= 2 { "both passed: alive" } else { "two eyes: alive" });
+            return Some(false);
+        }
+
+        let key = (self.position_key(board), to_move, passes, board.get_ko_point());
+        if let Some(&result) = self.table.get(&key) {
+            self.note(if result { "transposition: dead" } else { "transposition: alive" });
+            return Some(result);
+        }
+
+        self.nodes += 1;
+        if self.nodes > self.max_nodes {
+            self.note("node budget exhausted");
+            return None;
+        }
+
+        let attacker_to_move = to_move != self.defender;
+        let next = opponent(to_move);
+        let mut candidates: Vec<Option<(usize, usize)>> =
+            self.ordered_moves(board).into_iter().map(Some).collect();
+        candidates.push(None);
+
+        let mut first_line = None;
+        let mut exhausted = false;
+        self.path.push(key.0.clone());
+        for candidate in candidates {
+            let mut child = board.clone();
+            let child_passes = match candidate {
+                Some((x, y)) => {
+                    // The ko master's retakes stand for ko threats answered
+                    // elsewhere, so they may repeat a position
+                    let master = self.ko_master == Some(to_move);
+                    if master {
+                        child.clear_ko_point();
+                    }
+                    if child.place_stone(x, y, to_move).is_err()
+                        || (!master && self.path.contains(&self.position_key(&child)))
+                    {
+                        continue;
+                    }
+                    0
+                }
+                None => {
+                    child.clear_ko_point();
+                    passes + 1
+                }
+            };
+
+            let mut child_line = Vec::new();
+            if let Some(trace) = &mut self.trace {
+                trace.enter(to_move, candidate);
+            }
+            let outcome = self.attacker_wins(&child, next, child_passes, &mut child_line);
+            if let Some(trace) = &mut self.trace {
+                trace.leave();
+            }
+            match outcome {
+                Some(result) if result == attacker_to_move => {
+                    // This move wins for the side to move
+                    line.push((to_move, candidate));
+                    line.extend(child_line);
+                    self.path.pop();
+                    self.store(key, result);
+                    return Some(result);
+                }
+                Some(_) => {
+                    if first_line.is_none() {
+                        let mut refuted = vec![(to_move, candidate)];
+                        refuted.extend(child_line);
+                        first_line = Some(refuted);
+                    }
+                }
+                None => exhausted = true,
+            }
+        }
+
+        self.path.pop();
+        if exhausted {
+            return None;
+        }
+
+        // Every move loses for the side to move
+        line.extend(first_line.unwrap_or_default());
+        let result = !attacker_to_move;
+        self.store(key, result);
+        Some(result)
+    }
+
+    /// Region points ordered with the target's liberties first
+    fn ordered_moves(&self, board: &Board) -> Vec<(usize, usize)> {
+        let liberties = board.find_liberties(self.target.0, self.target.1);
+        let mut moves: Vec<(usize, usize)> = liberties
+            .iter()
+            .copied()
+            .filter(|p| self.region.contains(p))
+            .collect();
+        moves.extend(
+            self.region
+                .iter()
+                .copied()
+                .filter(|&(x, y)| board.get_stone(x, y) == Stone::Empty && !liberties.contains(&(x, y))),
+        );
+        moves
+    }
+
+    /// The target string is unconditionally alive once it owns two
+    /// single-point eyes the attacker cannot fill
+    fn has_two_eyes(&self, board: &Board) -> bool {
+        let Some(group) = board.find_group(self.target.0, self.target.1) else {
+            return false;
+        };
+        let stones = group.positions();
+        let attacker = opponent(self.defender);
+
+        let eyes = board
+            .find_liberties(self.target.0, self.target.1)
+            .into_iter()
+            .filter(|&(x, y)| {
+                board.neighbors(x, y).iter().all(|p| stones.contains(p))
+                    && board.clone().place_stone(x, y, attacker).is_err()
+            })
+            .count();
+        eyes >= 2
+    }
+
+    fn position_key(&self, board: &Board) -> Vec<Stone> {
+        self.region
+            .iter()
+            .map(|&(x, y)| board.get_stone(x, y))
+            .collect()
+    }
+}
+
+/// Reads the life and death of the largest string lying wholly inside
+/// `region`, with `to_move` playing first and moves kept to the region.
+/// The group is read twice, once with each side winning every ko: it is
+/// alive or dead if the result holds either way, and a ko if it turns
+/// on who wins the ko
+pub fn solve_life_death(board: &Board, region: &[(usize, usize)], to_move: Stone) -> Result<LifeDeathSolution, String> {
+    if to_move == Stone::Empty {
+        return Err("No side to move".to_string());
+    }
+    let mut target: Option<((usize, usize), usize)> = None;
+    for &(x, y) in region {
+        let Some(group) = board.find_group(x, y) else { continue };
+        let stones = group.positions();
+        if stones.iter().all(|p| region.contains(p)) && target.is_none_or(|(_, size)| stones.len() > size) {
+            target = Some(((x, y), stones.len()));
+        }
+    }
+    let Some((target, _)) = target else {
+        return Err("No group lies wholly inside the region".to_string());
+    };
+    let defender = board.get_stone(target.0, target.1);
+
+    let read = |ko_master: Stone| {
+        TsumegoSolver::new(board, target, DEFAULT_MAX_NODES)
+            .with_region(region.to_vec())
+            .with_ko_master(ko_master)
+            .solve(board, to_move)
+    };
+    let attacker_kos = read(opponent(defender));
+    let defender_kos = read(defender);
+    let nodes = attacker_kos.nodes + defender_kos.nodes;
+    let (status, line) = match (attacker_kos.status, defender_kos.status) {
+        (LifeStatus::Alive, _) => (LifeStatus::Alive, attacker_kos.main_line),
+        (_, LifeStatus::Dead) => (LifeStatus::Dead, defender_kos.main_line),
+        (LifeStatus::Dead, LifeStatus::Alive) => {
+            // The line in which the side to move wins the ko
+            (LifeStatus::Ko, if to_move == defender { defender_kos.main_line } else { attacker_kos.main_line })
+        }
+        _ => (LifeStatus::Unknown, Vec::new()),
+    };
+    let wins = match status {
+        LifeStatus::Alive => to_move == defender,
+        LifeStatus::Dead => to_move != defender,
+        LifeStatus::Ko => true,
+        LifeStatus::Unknown => false,
+    };
+    let solving_move = line.first().filter(|_| wins).and_then(|&(_, point)| point);
+    Ok(LifeDeathSolution { target, status, solving_move, main_line: line, nodes })
+}
+
+/// Empty and occupied points inside the stones' bounding box plus a margin of one
+pub fn default_region(board: &Board) -> Vec<(usize, usize)> {
+    let size = board.size();
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (size, size, 1, 1);
+    let mut any = false;
+    for y in 1..=size {
+        for x in 1..=size {
+            if board.get_stone(x, y) != Stone::Empty {
+                any = true;
+                min_x = min_x.min(x);
+                min_y = min_y.min(y);
+                max_x = max_x.max(x);
+                max_y = max_y.max(y);
+            }
+        }
+    }
+    if !any {
+        return Vec::new();
+    }
+
+    let mut region = Vec::new();
+    for y in min_y.saturating_sub(1).max(1)..=(max_y + 1).min(size) {
+        for x in min_x.saturating_sub(1).max(1)..=(max_x + 1).min(size) {
+            region.push((x, y));
+        }
+    }
+    region
+}
+
+fn opponent(color: Stone) -> Stone {
+    match color {
+        Stone::Black => Stone::White,
+        Stone::White => Stone::Black,
+        Stone::Empty => Stone::Empty,
+    }
+}
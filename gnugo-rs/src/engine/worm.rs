@@ -0,0 +1,236 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Worms: per-string data in the manner of GNU Go's worm module
+//!
+//! [`Worms::compute`] reads every string of a position once: its
+//! liberties, cutting role, tactical status and effective size, so later
+//! stages can look them up by coordinate instead of reading again.
+
+use crate::engine::ai::LevelProfile;
+use crate::engine::board::{Board, Stone};
+use crate::engine::eye::EyeAnalyzer;
+
+/// Empty points further than this from every string belong to none
+const EFFECTIVE_RADIUS: usize = 3;
+
+/// Result of tactical reading, as in GNU Go's result codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Code {
+    Fail,
+    /// Works only by winning a ko
+    Ko,
+    Win,
+}
+
+impl Code {
+    /// GNU Go's numeric code: 0 fail, 2 ko, 5 win
+    pub fn value(&self) -> u8 {
+        match self {
+            Code::Fail => 0,
+            Code::Ko => 2,
+            Code::Win => 5,
+        }
+    }
+}
+
+/// One string of stones and what reading found out about it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Worm {
+    /// Topmost, then leftmost stone
+    pub origin: (usize, usize),
+    pub color: Stone,
+    pub stones: Vec<(usize, usize)>,
+    pub liberties: Vec<(usize, usize)>,
+    /// Stones plus a share of the empty points nearer to this string than to others
+    pub effective_size: f32,
+    /// Whether the string separates two opponent strings it touches
+    pub cutstone: bool,
+    /// Liberties shared with another string of the same color, where the
+    /// opponent would cut the two apart
+    pub cut_points: Vec<(usize, usize)>,
+    pub attack_code: Code,
+    pub attack_point: Option<(usize, usize)>,
+    pub defense_code: Code,
+    pub defense_point: Option<(usize, usize)>,
+}
+
+impl Worm {
+    pub fn size(&self) -> usize {
+        self.stones.len()
+    }
+}
+
+/// All worms of a position, queryable by coordinate
+#[derive(Debug, Clone, PartialEq)]
+pub struct Worms {
+    size: usize,
+    /// Index into `worms` of the string at each point, [y - 1][x - 1]
+    index: Vec<Vec<Option<usize>>>,
+    worms: Vec<Worm>,
+}
+
+impl Worms {
+    /// Reads every string on `board`
+    pub fn compute(board: &Board) -> Self {
+        let size = board.size();
+        let mut index = vec![vec![None; size]; size];
+        let mut worms = Vec::new();
+        for y in 1..=size {
+            for x in 1..=size {
+                if index[y - 1][x - 1].is_some() || board.get_stone(x, y) == Stone::Empty {
+                    continue;
+                }
+                let Some(group) = board.find_group(x, y) else { continue };
+                let mut stones = group.positions().to_vec();
+                stones.sort_by_key(|&(sx, sy)| (sy, sx));
+                for &(sx, sy) in &stones {
+                    index[sy - 1][sx - 1] = Some(worms.len());
+                }
+                worms.push(Worm {
+                    origin: stones[0],
+                    color: group.color(),
+                    liberties: board.find_liberties(x, y),
+                    stones,
+                    effective_size: 0.0,
+                    cutstone: false,
+                    cut_points: Vec::new(),
+                    attack_code: Code::Fail,
+                    attack_point: None,
+                    defense_code: Code::Fail,
+                    defense_point: None,
+                });
+            }
+        }
+        let mut result = Worms { size, index, worms };
+        result.find_cuts(board);
+        result.find_effective_sizes(board);
+        for worm in &mut result.worms {
+            if let Some((code, point)) = attack(board, worm.origin) {
+                (worm.attack_code, worm.attack_point) = (code, Some(point));
+                if let Some(point) = defend(board, worm.origin) {
+                    (worm.defense_code, worm.defense_point) = (Code::Win, Some(point));
+                }
+            }
+        }
+        result
+    }
+
+    /// The worm with a stone at (x, y)
+    pub fn get(&self, x: usize, y: usize) -> Option<&Worm> {
+        if x == 0 || y == 0 || x > self.size || y > self.size {
+            return None;
+        }
+        self.index[y - 1][x - 1].map(|i| &self.worms[i])
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Worm> {
+        self.worms.iter()
+    }
+
+    fn worm_at(&self, (x, y): (usize, usize)) -> Option<usize> {
+        self.index[y - 1][x - 1]
+    }
+
+    /// Neighboring worms of each worm
+    fn neighbors(&self, board: &Board, i: usize) -> Vec<usize> {
+        let mut found = Vec::new();
+        for &(x, y) in &self.worms[i].stones {
+            for point in board.neighbors(x, y) {
+                if let Some(j) = self.worm_at(point).filter(|&j| j != i && !found.contains(&j)) {
+                    found.push(j);
+                }
+            }
+        }
+        found
+    }
+
+    fn find_cuts(&mut self, board: &Board) {
+        for i in 0..self.worms.len() {
+            let color = self.worms[i].color;
+            let enemies = self.neighbors(board, i).into_iter().filter(|&j| self.worms[j].color != color).count();
+            let cut_points = self.worms[i]
+                .liberties
+                .iter()
+                .copied()
+                .filter(|&(x, y)| {
+                    board
+                        .neighbors(x, y)
+                        .into_iter()
+                        .filter_map(|point| self.worm_at(point))
+                        .any(|j| j != i && self.worms[j].color == color)
+                })
+                .collect();
+            self.worms[i].cutstone = enemies >= 2;
+            self.worms[i].cut_points = cut_points;
+        }
+    }
+
+    /// Shares each empty point equally among the nearest worms within
+    /// [`EFFECTIVE_RADIUS`]
+    fn find_effective_sizes(&mut self, board: &Board) {
+        for worm in &mut self.worms {
+            worm.effective_size = worm.stones.len() as f32;
+        }
+        for y in 1..=self.size {
+            for x in 1..=self.size {
+                if board.get_stone(x, y) != Stone::Empty {
+                    continue;
+                }
+                let distances: Vec<usize> = self
+                    .worms
+                    .iter()
+                    .map(|worm| worm.stones.iter().map(|&(sx, sy)| sx.abs_diff(x) + sy.abs_diff(y)).min().unwrap_or(usize::MAX))
+                    .collect();
+                let Some(&nearest) = distances.iter().min().filter(|&&d| d <= EFFECTIVE_RADIUS) else {
+                    continue;
+                };
+                let share = 1.0 / distances.iter().filter(|&&d| d == nearest).count() as f32;
+                for (worm, &d) in self.worms.iter_mut().zip(&distances) {
+                    if d == nearest {
+                        worm.effective_size += share;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The move capturing the string at `target` with the attacker to move:
+/// at its last liberty, by ladder, or by net
+fn attack(board: &Board, target: (usize, usize)) -> Option<(Code, (usize, usize))> {
+    let color = board.get_stone(target.0, target.1);
+    let attacker = if color == Stone::Black { Stone::White } else { Stone::Black };
+    let liberties = board.find_liberties(target.0, target.1);
+    if let [(x, y)] = liberties[..] {
+        let placement = board.clone().place_stone_detailed(x, y, attacker).ok()?;
+        let code = if placement.created_ko() { Code::Ko } else { Code::Win };
+        return Some((code, (x, y)));
+    }
+    let profile = LevelProfile::default();
+    let analyzer = EyeAnalyzer::new();
+    analyzer
+        .find_ladder_attack_point(board, target.0, target.1, profile.ladder_depth)
+        .or_else(|| analyzer.find_net_attack_point(board, target.0, target.1, profile.net_depth))
+        .map(|point| (Code::Win, point))
+}
+
+/// A move after which the string at `target` can no longer be attacked:
+/// extending at a liberty or capturing a neighbor in atari
+fn defend(board: &Board, target: (usize, usize)) -> Option<(usize, usize)> {
+    let color = board.get_stone(target.0, target.1);
+    let group = board.find_group(target.0, target.1)?;
+    let mut candidates = board.find_liberties(target.0, target.1);
+    for &(x, y) in group.positions() {
+        for (nx, ny) in board.neighbors(x, y) {
+            let stone = board.get_stone(nx, ny);
+            if stone != Stone::Empty && stone != color && board.count_liberties(nx, ny) == 1 {
+                candidates.extend(board.find_liberties(nx, ny));
+            }
+        }
+    }
+    candidates.into_iter().find(|&(x, y)| {
+        let mut after = board.clone();
+        after.place_stone(x, y, color).is_ok() && attack(&after, target).is_none()
+    })
+}
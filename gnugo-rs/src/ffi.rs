@@ -0,0 +1,341 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! C API for embedding the engine in C/C++ programs (feature `cdylib`)
+//!
+//! Build the shared library with
+//! `cargo rustc --release --lib --features cdylib --crate-type cdylib`
+//! and include `include/gnugo_rs.h`.
+//!
+//! Conventions follow GNU Go's board.h: colors are `GG_EMPTY` (0),
+//! `GG_WHITE` (1) and `GG_BLACK` (2); vertices are 0-based `(i, j)` with
+//! `i` the row counted from the top and `j` the column; `(-1, -1)` is a pass.
+//! Functions returning `int` use 1 for success/true and 0 for failure/false.
+
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_float, c_int};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use crate::engine::ai::{AI, MAX_LEVEL};
+use crate::engine::board::Stone;
+use crate::engine::final_status::FinalStatus;
+use crate::engine::game::Game;
+use crate::engine::rules::ScoringRule;
+use crate::engine::scoring::final_score;
+use crate::sgf::SGFHandler;
+
+pub const GG_EMPTY: c_int = 0;
+pub const GG_WHITE: c_int = 1;
+pub const GG_BLACK: c_int = 2;
+pub const GG_PASS: c_int = -1;
+
+/// Opaque handle owning a game and the engine settings
+pub struct GgBoard {
+    game: Game,
+    /// Numeric level, 1 to [`MAX_LEVEL`]
+    level: u8,
+}
+
+fn to_stone(color: c_int) -> Option<Stone> {
+    match color {
+        GG_WHITE => Some(Stone::White),
+        GG_BLACK => Some(Stone::Black),
+        _ => None,
+    }
+}
+
+fn from_stone(stone: Stone) -> c_int {
+    match stone {
+        Stone::Empty => GG_EMPTY,
+        Stone::White => GG_WHITE,
+        Stone::Black => GG_BLACK,
+    }
+}
+
+/// Runs `f`, answering `fallback` if it panics: unwinding into C is
+/// undefined behavior
+fn catch<T>(fallback: T, f: impl FnOnce() -> T) -> T {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(fallback)
+}
+
+/// Converts a 0-based (i, j) vertex into board coordinates
+fn to_point(board: &GgBoard, i: c_int, j: c_int) -> Option<(usize, usize)> {
+    let size = board.game.board.size() as c_int;
+    if (0..size).contains(&i) && (0..size).contains(&j) {
+        Some((j as usize + 1, i as usize + 1))
+    } else {
+        None
+    }
+}
+
+/// Creates a board of `size` (2..25); returns NULL for other sizes
+#[no_mangle]
+pub extern "C" fn gg_board_new(size: c_int) -> *mut GgBoard {
+    catch(ptr::null_mut(), || {
+        if !(2..=25).contains(&size) {
+            return ptr::null_mut();
+        }
+        Box::into_raw(Box::new(GgBoard {
+            game: Game::new(size as usize),
+            level: 5,
+        }))
+    })
+}
+
+/// Frees a board created by `gg_board_new`
+///
+/// # Safety
+/// `board` must be NULL or a pointer from `gg_board_new` not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn gg_board_free(board: *mut GgBoard) {
+    catch((), || {
+        if !board.is_null() {
+            drop(Box::from_raw(board));
+        }
+    })
+}
+
+/// Board size, or 0 for NULL
+///
+/// # Safety
+/// `board` must be NULL or a live pointer from `gg_board_new`.
+#[no_mangle]
+pub unsafe extern "C" fn gg_board_size(board: *const GgBoard) -> c_int {
+    catch(0, || board.as_ref().map_or(0, |b| b.game.board.size() as c_int))
+}
+
+/// Empties the board, keeping size and komi
+///
+/// # Safety
+/// `board` must be NULL or a live pointer from `gg_board_new`.
+#[no_mangle]
+pub unsafe extern "C" fn gg_clear_board(board: *mut GgBoard) {
+    catch((), || {
+        if let Some(b) = board.as_mut() {
+            let komi = b.game.komi;
+            b.game = Game::new(b.game.board.size());
+            b.game.komi = komi;
+        }
+    })
+}
+
+/// # Safety
+/// `board` must be NULL or a live pointer from `gg_board_new`.
+#[no_mangle]
+pub unsafe extern "C" fn gg_set_komi(board: *mut GgBoard, komi: c_float) {
+    catch((), || {
+        if let Some(b) = board.as_mut() {
+            b.game.komi = komi;
+        }
+    })
+}
+
+/// # Safety
+/// `board` must be NULL or a live pointer from `gg_board_new`.
+#[no_mangle]
+pub unsafe extern "C" fn gg_get_komi(board: *const GgBoard) -> c_float {
+    catch(0.0, || board.as_ref().map_or(0.0, |b| b.game.komi))
+}
+
+/// Sets the engine level, 1 to 10 like GNU Go's `--level`
+///
+/// # Safety
+/// `board` must be NULL or a live pointer from `gg_board_new`.
+#[no_mangle]
+pub unsafe extern "C" fn gg_set_level(board: *mut GgBoard, level: c_int) -> c_int {
+    catch(0, || {
+        let Ok(level) = u8::try_from(level) else { return 0 };
+        match board.as_mut() {
+            Some(b) if (1..=MAX_LEVEL).contains(&level) => {
+                b.level = level;
+                1
+            }
+            _ => 0,
+        }
+    })
+}
+
+/// Color at (i, j), or `GG_EMPTY` off the board
+///
+/// # Safety
+/// `board` must be NULL or a live pointer from `gg_board_new`.
+#[no_mangle]
+pub unsafe extern "C" fn gg_get_color(board: *const GgBoard, i: c_int, j: c_int) -> c_int {
+    catch(GG_EMPTY, || {
+        let Some(b) = board.as_ref() else { return GG_EMPTY };
+        match to_point(b, i, j) {
+            Some((x, y)) => from_stone(b.game.board.get_stone(x, y)),
+            None => GG_EMPTY,
+        }
+    })
+}
+
+/// Color to move next
+///
+/// # Safety
+/// `board` must be NULL or a live pointer from `gg_board_new`.
+#[no_mangle]
+pub unsafe extern "C" fn gg_to_move(board: *const GgBoard) -> c_int {
+    catch(GG_EMPTY, || board.as_ref().map_or(GG_EMPTY, |b| from_stone(b.game.current_player())))
+}
+
+/// Whether `color` may play at (i, j) (passes are always legal)
+///
+/// # Safety
+/// `board` must be NULL or a live pointer from `gg_board_new`.
+#[no_mangle]
+pub unsafe extern "C" fn gg_is_legal(board: *const GgBoard, i: c_int, j: c_int, color: c_int) -> c_int {
+    catch(0, || {
+        let (Some(b), Some(stone)) = (board.as_ref(), to_stone(color)) else { return 0 };
+        if i == GG_PASS && j == GG_PASS {
+            return 1;
+        }
+        match to_point(b, i, j) {
+            Some((x, y)) => b.game.board.clone().place_stone(x, y, stone).is_ok() as c_int,
+            None => 0,
+        }
+    })
+}
+
+/// Plays `color` at (i, j) or passes at (-1, -1); returns 0 if illegal
+///
+/// # Safety
+/// `board` must be NULL or a live pointer from `gg_board_new`.
+#[no_mangle]
+pub unsafe extern "C" fn gg_play(board: *mut GgBoard, i: c_int, j: c_int, color: c_int) -> c_int {
+    catch(0, || {
+        let (Some(b), Some(stone)) = (board.as_mut(), to_stone(color)) else { return 0 };
+        b.game.current_player = stone == Stone::Black;
+        if i == GG_PASS && j == GG_PASS {
+            return b.game.pass().is_ok() as c_int;
+        }
+        match to_point(b, i, j) {
+            Some((x, y)) => b.game.make_move(x, y).is_ok() as c_int,
+            None => 0,
+        }
+    })
+}
+
+/// Generates and plays a move for `color`, storing it in `*i`, `*j`
+/// ((-1, -1) for a pass). Returns 0 on error.
+///
+/// # Safety
+/// `board` must be NULL or a live pointer from `gg_board_new`; `i` and `j`
+/// must be NULL or valid for writing.
+#[no_mangle]
+pub unsafe extern "C" fn gg_genmove(board: *mut GgBoard, color: c_int, i: *mut c_int, j: *mut c_int) -> c_int {
+    catch(0, || {
+        let (Some(b), Some(stone)) = (board.as_mut(), to_stone(color)) else { return 0 };
+        b.game.current_player = stone == Stone::Black;
+
+        let (mi, mj) = match AI::for_level(b.level).get_game_move(&b.game, stone) {
+            Some((x, y)) if b.game.make_move(x, y).is_ok() => (y as c_int - 1, x as c_int - 1),
+            _ => {
+                if b.game.pass().is_err() {
+                    return 0;
+                }
+                (GG_PASS, GG_PASS)
+            }
+        };
+        if !i.is_null() {
+            *i = mi;
+        }
+        if !j.is_null() {
+            *j = mj;
+        }
+        1
+    })
+}
+
+/// Takes back the last move; returns 0 if there is none
+///
+/// # Safety
+/// `board` must be NULL or a live pointer from `gg_board_new`.
+#[no_mangle]
+pub unsafe extern "C" fn gg_undo(board: *mut GgBoard) -> c_int {
+    catch(0, || board.as_mut().and_then(|b| b.game.undo_move()).is_some() as c_int)
+}
+
+/// Stones captured by `color`
+///
+/// # Safety
+/// `board` must be NULL or a live pointer from `gg_board_new`.
+#[no_mangle]
+pub unsafe extern "C" fn gg_captures(board: *const GgBoard, color: c_int) -> c_int {
+    catch(0, || {
+        match (board.as_ref(), to_stone(color)) {
+            (Some(b), Some(stone)) => b.game.captured(stone) as c_int,
+            _ => 0,
+        }
+    })
+}
+
+/// Liberties of the string at (i, j), or 0 for an empty or invalid vertex
+///
+/// # Safety
+/// `board` must be NULL or a live pointer from `gg_board_new`.
+#[no_mangle]
+pub unsafe extern "C" fn gg_countlib(board: *const GgBoard, i: c_int, j: c_int) -> c_int {
+    catch(0, || {
+        let Some(b) = board.as_ref() else { return 0 };
+        match to_point(b, i, j) {
+            Some((x, y)) if b.game.board.get_stone(x, y) != Stone::Empty => b.game.board.count_liberties(x, y) as c_int,
+            _ => 0,
+        }
+    })
+}
+
+/// Area score with dead stones removed. Stores each side's points if the
+/// pointers are non-NULL and returns Black minus White minus komi.
+///
+/// # Safety
+/// `board` must be NULL or a live pointer from `gg_board_new`; `black` and
+/// `white` must be NULL or valid for writing.
+#[no_mangle]
+pub unsafe extern "C" fn gg_score(board: *const GgBoard, black: *mut c_float, white: *mut c_float) -> c_float {
+    catch(0.0, || {
+        let Some(b) = board.as_ref() else { return 0.0 };
+        let dead = FinalStatus::analyze(&b.game.board).dead();
+        let (black_points, white_points) = final_score(&b.game.board, ScoringRule::Area, &dead);
+        if !black.is_null() {
+            *black = black_points as c_float;
+        }
+        if !white.is_null() {
+            *white = white_points as c_float;
+        }
+        black_points as c_float - white_points as c_float - b.game.komi
+    })
+}
+
+/// Replaces the game with the main line of an SGF file; returns 0 on error
+///
+/// # Safety
+/// `board` must be NULL or a live pointer from `gg_board_new`; `path` must be
+/// NULL or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn gg_load_sgf(board: *mut GgBoard, path: *const c_char) -> c_int {
+    catch(0, || {
+        let Some(b) = board.as_mut() else { return 0 };
+        if path.is_null() {
+            return 0;
+        }
+        let Ok(path) = CStr::from_ptr(path).to_str() else { return 0 };
+
+        let handler = SGFHandler::new();
+        let mut game = Game::new(19);
+        match handler.load_file(path).and_then(|tree| handler.apply_to_game(&tree, &mut game).map_err(String::from)) {
+            Ok(()) => {
+                b.game = game;
+                1
+            }
+            Err(_) => 0,
+        }
+    })
+}
+
+/// Library version as a static NUL-terminated string
+#[no_mangle]
+pub extern "C" fn gg_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr() as *const c_char
+}
@@ -0,0 +1,45 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! File access, available only with the `native` feature
+//!
+//! Browser (wasm32) builds have no file system; there every call fails with
+//! `io::ErrorKind::Unsupported` and callers report it like any other I/O error.
+
+use std::io;
+use std::path::Path;
+
+#[cfg(feature = "native")]
+pub fn read(path: &Path) -> io::Result<Vec<u8>> {
+    std::fs::read(path)
+}
+
+#[cfg(feature = "native")]
+pub fn read_to_string(path: &Path) -> io::Result<String> {
+    std::fs::read_to_string(path)
+}
+
+#[cfg(feature = "native")]
+pub fn write(path: &Path, contents: &[u8]) -> io::Result<()> {
+    std::fs::write(path, contents)
+}
+
+#[cfg(not(feature = "native"))]
+fn unsupported() -> io::Error {
+    io::Error::new(io::ErrorKind::Unsupported, "file access is not available in this build")
+}
+
+#[cfg(not(feature = "native"))]
+pub fn read(_path: &Path) -> io::Result<Vec<u8>> {
+    Err(unsupported())
+}
+
+#[cfg(not(feature = "native"))]
+pub fn read_to_string(_path: &Path) -> io::Result<String> {
+    Err(unsupported())
+}
+
+#[cfg(not(feature = "native"))]
+pub fn write(_path: &Path, _contents: &[u8]) -> io::Result<()> {
+    Err(unsupported())
+}
@@ -0,0 +1,322 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! GTP controller: spawns an external engine and drives it over its stdin/stdout
+//!
+//! Used by the tournament manager and for cross-checking against the
+//! original GNU Go.
+
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, ExitStatus, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::engine::board::Stone;
+
+/// Errors from talking to an external engine
+#[derive(Debug)]
+pub enum GtpError {
+    /// The engine could not be started or written to
+    Io(io::Error),
+    /// The engine answered with a `?` failure response
+    Failure(String),
+    /// No response arrived within the timeout; the engine has been killed
+    Timeout,
+    /// The engine exited or closed its output
+    Crashed(Option<ExitStatus>),
+    /// The engine printed something that is not a GTP response
+    Protocol(String),
+}
+
+impl fmt::Display for GtpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GtpError::Io(e) => write!(f, "I/O error: {}", e),
+            GtpError::Failure(message) => write!(f, "engine failure: {}", message),
+            GtpError::Timeout => write!(f, "engine timed out"),
+            GtpError::Crashed(Some(status)) => write!(f, "engine exited ({})", status),
+            GtpError::Crashed(None) => write!(f, "engine closed its output"),
+            GtpError::Protocol(message) => write!(f, "protocol error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for GtpError {}
+
+impl From<io::Error> for GtpError {
+    fn from(e: io::Error) -> Self {
+        GtpError::Io(e)
+    }
+}
+
+/// A running external GTP engine
+pub struct GtpClient {
+    child: Child,
+    stdin: Option<ChildStdin>,
+    /// Lines from the engine's stdout, read on a separate thread so reads can time out
+    lines: Receiver<String>,
+    next_id: u32,
+    timeout: Option<Duration>,
+    /// Set after a timeout or crash; no further commands are sent
+    dead: bool,
+}
+
+impl GtpClient {
+    /// Starts `program` with `args`. The engine's stderr passes through to ours.
+    pub fn spawn(program: &str, args: &[String]) -> Result<Self, GtpError> {
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()?;
+
+        let stdin = child.stdin.take();
+        let stdout = child.stdout.take().ok_or_else(|| GtpError::Protocol("no stdout".to_string()))?;
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(GtpClient { child, stdin, lines, next_id: 1, timeout: None, dead: false })
+    }
+
+    /// Starts an engine from a shell-like command line such as `gnugo --mode gtp --level 5`.
+    /// Arguments are split on whitespace; double quotes group words.
+    pub fn from_command_line(command_line: &str) -> Result<Self, GtpError> {
+        let words = split_command_line(command_line);
+        let (program, args) = words
+            .split_first()
+            .ok_or_else(|| GtpError::Protocol("empty engine command".to_string()))?;
+        Self::spawn(program, args)
+    }
+
+    /// Default time allowed for each response; `None` waits forever
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// Sends a command and returns the response text (without the `=` prefix)
+    pub fn send(&mut self, command: &str) -> Result<String, GtpError> {
+        self.send_with_timeout(command, self.timeout)
+    }
+
+    /// Sends a command, waiting at most `timeout` for the answer
+    pub fn send_with_timeout(&mut self, command: &str, timeout: Option<Duration>) -> Result<String, GtpError> {
+        if self.dead {
+            return Err(GtpError::Crashed(self.child.try_wait().ok().flatten()));
+        }
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let written = match self.stdin.as_mut() {
+            Some(stdin) => writeln!(stdin, "{} {}", id, command.trim()).and_then(|_| stdin.flush()),
+            None => Err(io::Error::new(io::ErrorKind::BrokenPipe, "stdin closed")),
+        };
+        // An engine that already exited may still have an answer buffered
+        let timeout = if written.is_err() { Some(Duration::from_secs(1)) } else { timeout };
+
+        let result = self.read_response(id, timeout);
+        if let Err(GtpError::Timeout) = result {
+            if written.is_err() {
+                return Err(self.crashed());
+            }
+            self.dead = true;
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+        result
+    }
+
+    fn read_response(&mut self, id: u32, timeout: Option<Duration>) -> Result<String, GtpError> {
+        let deadline = timeout.map(|t| Instant::now() + t);
+        let mut response: Option<(bool, Vec<String>)> = None;
+
+        loop {
+            let line = match deadline {
+                Some(deadline) => {
+                    let left = deadline.saturating_duration_since(Instant::now());
+                    match self.lines.recv_timeout(left) {
+                        Ok(line) => line,
+                        Err(RecvTimeoutError::Timeout) => return Err(GtpError::Timeout),
+                        Err(RecvTimeoutError::Disconnected) => return Err(self.crashed()),
+                    }
+                }
+                None => match self.lines.recv() {
+                    Ok(line) => line,
+                    Err(_) => return Err(self.crashed()),
+                },
+            };
+            let line = line.trim_end_matches('\r');
+
+            match response.as_mut() {
+                None => {
+                    // Engines sometimes print banners before answering; skip anything
+                    // that does not start a response
+                    let success = match line.chars().next() {
+                        Some('=') => true,
+                        Some('?') => false,
+                        _ => continue,
+                    };
+                    let rest = &line[1..];
+                    let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+                    if digits > 0 && rest[..digits].parse::<u32>().ok() != Some(id) {
+                        return Err(GtpError::Protocol(format!("response id {} does not match {}", &rest[..digits], id)));
+                    }
+                    response = Some((success, vec![rest[digits..].trim().to_string()]));
+                }
+                Some((success, text)) => {
+                    if line.is_empty() {
+                        let text = text.join("\n").trim_end().to_string();
+                        return if *success { Ok(text) } else { Err(GtpError::Failure(text)) };
+                    }
+                    text.push(line.to_string());
+                }
+            }
+        }
+    }
+
+    fn crashed(&mut self) -> GtpError {
+        self.dead = true;
+        // Give the process a moment to finish exiting so the status can be reported
+        for _ in 0..10 {
+            if let Ok(Some(status)) = self.child.try_wait() {
+                return GtpError::Crashed(Some(status));
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        GtpError::Crashed(None)
+    }
+
+    /// Whether the engine process is still running and usable
+    pub fn is_alive(&mut self) -> bool {
+        !self.dead && matches!(self.child.try_wait(), Ok(None))
+    }
+
+    pub fn name(&mut self) -> Result<String, GtpError> {
+        self.send("name")
+    }
+
+    pub fn version(&mut self) -> Result<String, GtpError> {
+        self.send("version")
+    }
+
+    pub fn protocol_version(&mut self) -> Result<String, GtpError> {
+        self.send("protocol_version")
+    }
+
+    pub fn known_command(&mut self, command: &str) -> Result<bool, GtpError> {
+        Ok(self.send(&format!("known_command {}", command))? == "true")
+    }
+
+    pub fn list_commands(&mut self) -> Result<Vec<String>, GtpError> {
+        Ok(self.send("list_commands")?.lines().map(|l| l.trim().to_string()).collect())
+    }
+
+    pub fn boardsize(&mut self, size: usize) -> Result<(), GtpError> {
+        self.send(&format!("boardsize {}", size)).map(|_| ())
+    }
+
+    pub fn clear_board(&mut self) -> Result<(), GtpError> {
+        self.send("clear_board").map(|_| ())
+    }
+
+    pub fn komi(&mut self, komi: f32) -> Result<(), GtpError> {
+        self.send(&format!("komi {}", komi)).map(|_| ())
+    }
+
+    /// Plays a move given as a GTP vertex ("D4", "pass")
+    pub fn play(&mut self, color: Stone, vertex: &str) -> Result<(), GtpError> {
+        self.send(&format!("play {} {}", color_arg(color), vertex)).map(|_| ())
+    }
+
+    /// Asks the engine for a move; returns the vertex, "pass" or "resign" in lower case
+    pub fn genmove(&mut self, color: Stone) -> Result<String, GtpError> {
+        Ok(self.send(&format!("genmove {}", color_arg(color)))?.to_lowercase())
+    }
+
+    pub fn final_score(&mut self) -> Result<String, GtpError> {
+        self.send("final_score")
+    }
+
+    pub fn time_settings(&mut self, main_time: u64, byo_yomi_time: u64, byo_yomi_stones: u32) -> Result<(), GtpError> {
+        self.send(&format!("time_settings {} {} {}", main_time, byo_yomi_time, byo_yomi_stones)).map(|_| ())
+    }
+
+    pub fn time_left(&mut self, color: Stone, seconds: u64, stones: u32) -> Result<(), GtpError> {
+        self.send(&format!("time_left {} {} {}", color_arg(color), seconds, stones)).map(|_| ())
+    }
+
+    /// Sends `quit` and waits briefly for the engine to exit, killing it otherwise
+    pub fn quit(mut self) -> Result<(), GtpError> {
+        self.shutdown()
+    }
+
+    fn shutdown(&mut self) -> Result<(), GtpError> {
+        if !self.dead {
+            let _ = self.send_with_timeout("quit", Some(Duration::from_secs(2)));
+            self.dead = true;
+        }
+        // Closing stdin lets engines that ignore quit see end of input
+        self.stdin = None;
+        for _ in 0..50 {
+            if let Ok(Some(_)) = self.child.try_wait() {
+                return Ok(());
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        self.child.kill()?;
+        self.child.wait()?;
+        Ok(())
+    }
+}
+
+impl Drop for GtpClient {
+    fn drop(&mut self) {
+        let _ = self.shutdown();
+    }
+}
+
+fn color_arg(color: Stone) -> &'static str {
+    match color {
+        Stone::White => "white",
+        _ => "black",
+    }
+}
+
+/// Splits a command line on whitespace, keeping double-quoted words together
+fn split_command_line(command_line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut has_word = false;
+
+    for c in command_line.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                has_word = true;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if has_word {
+                    words.push(std::mem::take(&mut current));
+                    has_word = false;
+                }
+            }
+            c => {
+                current.push(c);
+                has_word = true;
+            }
+        }
+    }
+    if has_word {
+        words.push(current);
+    }
+    words
+}
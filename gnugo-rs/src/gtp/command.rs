@@ -0,0 +1,77 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Splitting GTP command lines, total over arbitrary input
+//!
+//! Follows the preprocessing rules of the GTP specification: control
+//! characters other than tab and newline are dropped, tabs count as
+//! spaces and everything after `#` is a comment. [`parse_bytes`] accepts
+//! raw input, which makes it a convenient cargo-fuzz target.
+
+use std::fmt;
+
+/// One command: optional id, name and arguments
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GtpCommand {
+    pub id: Option<u32>,
+    pub name: String,
+    pub args: Vec<String>,
+}
+
+impl GtpCommand {
+    /// Argument `index`, or "" when missing
+    pub fn arg(&self, index: usize) -> &str {
+        self.args.get(index).map_or("", String::as_str)
+    }
+}
+
+/// Why a line holds no command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandError {
+    /// Blank or comment-only line, which GTP ignores
+    Empty,
+    /// An id with nothing after it
+    MissingName(u32),
+    /// The line is not UTF-8; `valid_up_to` bytes are
+    InvalidUtf8 { valid_up_to: usize },
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CommandError::Empty => write!(f, "empty command"),
+            CommandError::MissingName(id) => write!(f, "command {} has no name", id),
+            CommandError::InvalidUtf8 { valid_up_to } => write!(f, "invalid UTF-8 after byte {}", valid_up_to),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+/// Parses one command line
+pub fn parse_command(line: &str) -> Result<GtpCommand, CommandError> {
+    let line = line.split('#').next().unwrap_or("");
+    let cleaned: String = line
+        .chars()
+        .filter(|c| !c.is_control() || *c == '\t')
+        .map(|c| if c == '\t' { ' ' } else { c })
+        .collect();
+
+    let mut words = cleaned.split_whitespace();
+    let first = words.next().ok_or(CommandError::Empty)?;
+    let (id, name) = match first.parse::<u32>() {
+        Ok(id) => (Some(id), words.next().ok_or(CommandError::MissingName(id))?),
+        Err(_) => (None, first),
+    };
+    Ok(GtpCommand {
+        id,
+        name: name.to_string(),
+        args: words.map(str::to_string).collect(),
+    })
+}
+
+/// Parses one command line from raw bytes
+pub fn parse_bytes(bytes: &[u8]) -> Result<GtpCommand, CommandError> {
+    let line = std::str::from_utf8(bytes).map_err(|e| CommandError::InvalidUtf8 { valid_up_to: e.valid_up_to() })?;
+    parse_command(line)
+}
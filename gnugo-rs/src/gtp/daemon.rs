@@ -0,0 +1,55 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Multi-session GTP server: one independent game and engine per connection
+
+use std::io::{self, BufReader};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+
+use super::GTPHandler;
+use crate::config::Config;
+
+/// Normalizes a listen address; a bare port number binds to localhost
+pub fn listen_address(spec: &str) -> String {
+    if spec.parse::<u16>().is_ok() {
+        format!("127.0.0.1:{}", spec)
+    } else {
+        spec.to_string()
+    }
+}
+
+/// Accepts connections forever, serving each GTP session on its own thread
+pub fn serve(address: &str, config: &Config) -> io::Result<()> {
+    let listener = TcpListener::bind(address)?;
+    eprintln!("GTP daemon listening on {}", listener.local_addr()?);
+
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                let config = config.clone();
+                thread::spawn(move || {
+                    let peer = stream
+                        .peer_addr()
+                        .map(|a| a.to_string())
+                        .unwrap_or_else(|_| "unknown".to_string());
+                    eprintln!("GTP session opened: {}", peer);
+                    if let Err(e) = serve_session(stream, &config) {
+                        eprintln!("GTP session {} error: {}", peer, e);
+                    }
+                    eprintln!("GTP session closed: {}", peer);
+                });
+            }
+            Err(e) => eprintln!("GTP daemon accept error: {}", e),
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs one GTP session over a connected stream
+pub fn serve_session(stream: TcpStream, config: &Config) -> io::Result<()> {
+    let reader = BufReader::new(stream.try_clone()?);
+    let mut handler = GTPHandler::with_config(config);
+    handler.run_with(reader, stream)
+}
@@ -4,89 +4,232 @@
 //! Go Text Protocol (GTP) implementation for GNU Go Rust
 
 use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use crate::engine::aftermath::aftermath_move;
+use crate::engine::ai::{AIDifficulty, LevelProfile, ResignRule, ResourceLimits, Style, AI, MAX_LEVEL};
 use crate::engine::game::Game;
-use crate::engine::board::Stone;
+use crate::engine::board::{Board, Stone};
+use crate::engine::clock::{Clock, TimeControl};
+use crate::engine::explain::MoveExplanation;
+use crate::engine::evaluation::Evaluator;
 use crate::engine::eye::EyeAnalyzer;
+use crate::engine::influence::InfluenceMap;
+use crate::engine::owl::{self, OwlStatus};
+use crate::engine::profile;
+use crate::engine::rules::KoRule;
+use crate::engine::trace::SearchTrace;
+use crate::patterns::fuseki::{FusekiDatabase, FUSEKI_MOVES, FUSEKI_SIZES};
+use crate::patterns::joseki::{self, Corner};
+use crate::patterns::PatternMatcher;
+use crate::sgf::book::{OpeningBook, BOOK_MOVES};
 use crate::sgf::SGFHandler;
+use crate::config::Config;
+use command::{CommandError, GtpCommand};
+
+pub mod command;
+#[cfg(feature = "native")]
+pub mod client;
+#[cfg(feature = "native")]
+pub mod daemon;
+
+/// Moves listed by `move_reasons` without a vertex
+const MOVE_REASONS_SHOWN: usize = 10;
+
+/// Moves listed by `top_moves` without a count
+const TOP_MOVES_SHOWN: usize = 5;
 
 /// GTP protocol handler
 pub struct GTPHandler {
     game: Game,
     eye_analyzer: EyeAnalyzer,
+    /// Numeric level used by `genmove`, 1 to [`MAX_LEVEL`]
+    level: u8,
+    /// Caps on the work `genmove` may do
+    limits: ResourceLimits,
+    /// Why the most recent `genmove` chose its move
+    last_explanation: Option<MoveExplanation>,
+    /// Searches recorded between `start_sgftrace` and `finish_sgftrace`
+    sgf_trace: Option<SearchTrace>,
+    /// Set by `quit`; the command loop stops after answering it
+    quit_requested: bool,
+    /// Pattern databases, reloaded by `reload_patterns`
+    patterns: PatternMatcher,
+    /// Directory `reload_patterns` reads without an argument
+    pattern_dir: PathBuf,
+    /// Opening book `genmove` plays from in the first [`BOOK_MOVES`] moves
+    book: Option<OpeningBook>,
+    /// Fuseki database `genmove` plays from in the first [`FUSEKI_MOVES`] moves
+    fuseki: Option<FusekiDatabase>,
+    /// When `genmove` resigns
+    resign: ResignRule,
+    /// Seed last given to `rng`, reported by `get_random_seed`
+    seed: u64,
+    /// Draws the seed of every `genmove` and its book choices
+    rng: StdRng,
 }
 
 impl GTPHandler {
     /// Create a new GTP handler
     pub fn new(size: usize) -> Self {
+        let seed = rand::random();
         GTPHandler {
             game: Game::new(size),
             eye_analyzer: EyeAnalyzer::new(),
+            level: AIDifficulty::Intermediate.level(),
+            limits: ResourceLimits::default(),
+            last_explanation: None,
+            sgf_trace: None,
+            quit_requested: false,
+            patterns: PatternMatcher::new(),
+            pattern_dir: PathBuf::from("patterns"),
+            book: None,
+            fuseki: None,
+            resign: ResignRule::default(),
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Plays openings from `book`
+    pub fn with_book(mut self, book: OpeningBook) -> Self {
+        self.book = Some(book);
+        self
+    }
+
+    /// Plays whole-board openings from `fuseki`
+    pub fn with_fuseki(mut self, fuseki: FusekiDatabase) -> Self {
+        self.fuseki = Some(fuseki);
+        self
+    }
+
+    /// Create a handler whose game starts from the configured size and komi
+    pub fn with_config(config: &Config) -> Self {
+        let mut handler = Self::new(config.board_size);
+        handler.game = config.new_game();
+        handler.level = config.strength;
+        handler.limits = config.limits;
+        handler.pattern_dir = config.pattern_dir.clone();
+        handler.resign = config.resign;
+        if let Some(seed) = config.seed {
+            handler.reseed(seed);
+        }
+        handler.patterns.reload_databases(&config.pattern_dir);
+        // A book that fails to load leaves the engine to find its own openings
+        if let Some(path) = &config.book {
+            match OpeningBook::load(path) {
+                Ok(book) => handler.book = Some(book),
+                Err(e) => eprintln!("{}", e),
+            }
         }
+        if let Some(path) = &config.fuseki {
+            match FusekiDatabase::load(path) {
+                Ok(fuseki) => handler.fuseki = Some(fuseki),
+                Err(e) => eprintln!("{}", e),
+            }
+        }
+        handler
     }
 
     /// Run GTP protocol loop
     pub fn run(&mut self) -> io::Result<()> {
         let stdin = io::stdin();
-        let mut stdout = io::stdout();        
-        for line in stdin.lock().lines() {
-            let line = line?;
-            let response = self.process_command(&line);            
-            writeln!(stdout, "{}", response)?;
-            stdout.flush()?;
-        }        
-        Ok(())
+        self.run_with(stdin.lock(), io::stdout())
     }
 
-    /// Process a single GTP command
-    fn process_command(&mut self, command: &str) -> String {
-        let parts: Vec<&str> = command.split_whitespace().collect();
-        if parts.is_empty() {
-            return "".to_string();
+    /// Run GTP protocol loop on an arbitrary command stream
+    pub fn run_with<R: BufRead, W: Write>(&mut self, mut input: R, mut output: W) -> io::Result<()> {
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            if input.read_until(b'\n', &mut line)? == 0 {
+                break;
+            }
+            let response = self.process_line(&line);
+            write!(output, "{}", response)?;
+            output.flush()?;
+            if self.quit_requested {
+                break;
+            }
         }
+        Ok(())
+    }
 
-        // Handle command ID (optional)
-        let (id, cmd_parts) = if let Ok(id) = parts[0].parse::<u32>() {
-            (Some(id), &parts[1..])
-        } else {
-            (None, &parts[..])
-        };
-
-        if cmd_parts.is_empty() {
-            return self.format_response(id, "unknown command");
+    /// Process one raw input line; malformed input gets a failure response
+    fn process_line(&mut self, line: &[u8]) -> String {
+        match command::parse_bytes(line) {
+            Ok(command) => self.process_command(&command),
+            Err(CommandError::Empty) => "".to_string(),
+            Err(CommandError::MissingName(id)) => self.format_response(Some(id), "? unknown command"),
+            Err(e) => self.format_response(None, &format!("? {}", e)),
         }
+    }
 
-        let response = match cmd_parts[0] {
+    /// Process a single GTP command
+    fn process_command(&mut self, command: &GtpCommand) -> String {
+        let id = command.id;
+        let args: Vec<&str> = command.args.iter().map(String::as_str).collect();
+
+        let response = match command.name.as_str() {
             "protocol_version" => self.protocol_version(),
             "name" => self.name(),
             "version" => self.version(),
-            "boardsize" => self.boardsize(if cmd_parts.len() > 1 { cmd_parts[1] } else { "" }),
+            "boardsize" => self.boardsize(command.arg(0)),
             "clear_board" => self.clear_board(),
-            "komi" => self.komi(if cmd_parts.len() > 1 { cmd_parts[1] } else { "" }),
+            "fixed_handicap" => self.fixed_handicap(command.arg(0)),
+            "komi" => self.komi(command.arg(0)),
             "get_komi" => self.get_komi(),
-            "play" => self.play(if cmd_parts.len() > 2 { (cmd_parts[1], cmd_parts[2]) } else { ("", "") }),
-            "genmove" => self.genmove(if cmd_parts.len() > 1 { cmd_parts[1] } else { "" }),
+            "play" => self.play((command.arg(0), command.arg(1))),
+            "genmove" => self.genmove(command.arg(0)),
             "genmove_black" => self.genmove_black(),
             "genmove_white" => self.genmove_white(),
+            "kgs-genmove_cleanup" => self.genmove_cleanup(command.arg(0)),
+            "explain_last_move" => self.explain_last_move(),
+            "move_reasons" => self.move_reasons(command.arg(0)),
+            "top_moves" => self.top_moves(command.arg(0)),
+            "last_move" => self.last_move(),
+            "start_sgftrace" => self.start_sgftrace(),
+            "stats" => self.stats(command.arg(0)),
+            "finish_sgftrace" => self.finish_sgftrace(command.arg(0)),
             "undo" => self.undo(),
-            "captures" => self.captures(if cmd_parts.len() > 1 { cmd_parts[1] } else { "" }),
+            "rengo_team" => self.rengo_team(&args),
+            "rengo_to_play" => self.rengo_to_play(),
+            "joseki" => self.joseki(command.arg(0)),
+            "game_statistics" => self.game.statistics().to_text(),
+            "reload_patterns" => self.reload_patterns(command.arg(0)),
+            "set_random_seed" => self.set_random_seed(command.arg(0)),
+            "get_random_seed" => self.seed.to_string(),
+            "level" => self.set_level(command.arg(0)),
+            "captures" => self.captures(command.arg(0)),
             "final_score" => self.final_score(),
-            "time_settings" => self.time_settings(),
-            "is_legal" => self.is_legal(if cmd_parts.len() > 2 { (cmd_parts[1], cmd_parts[2]) } else { ("", "") }),
-            "list_stones" => self.list_stones(if cmd_parts.len() > 1 { cmd_parts[1] } else { "" }),
-            "countlib" => self.countlib(if cmd_parts.len() > 1 { cmd_parts[1] } else { "" }),
-            "findlib" => self.findlib(if cmd_parts.len() > 1 { cmd_parts[1] } else { "" }),
-            "ladder_attack" => self.ladder_attack(if cmd_parts.len() > 1 { cmd_parts[1] } else { "" }),
-            "eye_data" => self.eye_data(if cmd_parts.len() > 2 { (cmd_parts[1], cmd_parts[2]) } else { ("", "") }),
-            "loadsgf" => self.loadsgf(if cmd_parts.len() > 1 { cmd_parts[1] } else { "" }),
-            "printsgf" => self.printsgf(if cmd_parts.len() > 1 { cmd_parts[1] } else { "" }),
+            "time_settings" => self.time_settings(&args),
+            "kgs-time_settings" => self.kgs_time_settings(&args),
+            "time_left" => self.time_left(&args),
+            "is_legal" => self.is_legal((command.arg(0), command.arg(1))),
+            "list_stones" => self.list_stones(command.arg(0)),
+            "countlib" => self.countlib(command.arg(0)),
+            "findlib" => self.findlib(command.arg(0)),
+            "ladder_attack" => self.ladder_attack(command.arg(0)),
+            "net_attack" => self.net_attack(command.arg(0)),
+            "owl_attack" => self.owl(command.arg(0), true),
+            "owl_defend" => self.owl(command.arg(0), false),
+            "influence" => self.influence(command.arg(0)),
+            "moyo" => self.moyo(),
+            "worm_data" => self.worm_data(command.arg(0)),
+            "eye_data" => self.eye_data((command.arg(0), command.arg(1))),
+            "loadsgf" => self.loadsgf(command.arg(0)),
+            "printsgf" => self.printsgf(command.arg(0)),
             "quit" | "exit" => {
-                std::process::exit(0);
+                self.quit_requested = true;
+                "".to_string()
             },
             "list" | "help" => self.list_commands(),
             "list_commands" => self.list_commands(),
             "showboard" => self.showboard(),
-            "known_command" => self.known_command(if cmd_parts.len() > 1 { cmd_parts[1] } else { "" }),
-            _ => format!("unknown command: {}", cmd_parts[0]),
+            "known_command" => self.known_command(command.arg(0)),
+            _ => format!("? unknown command: {}", command.name),
         };
 
         self.format_response(id, &response)
@@ -94,7 +237,10 @@ impl GTPHandler {
 
     /// Format GTP response
     fn format_response(&self, id: Option<u32>, content: &str) -> String {
-        let prefix = if content.starts_with('?') { "?" } else { "=" };
+        let (prefix, content) = match content.strip_prefix('?') {
+            Some(message) => ("?", message.trim_start()),
+            None => ("=", content),
+        };
         let id_str = id.map(|i| i.to_string()).unwrap_or_default();
         if content.is_empty() {
             format!("{}{}\n\n", prefix, id_str)
@@ -111,7 +257,11 @@ impl GTPHandler {
     fn boardsize(&mut self, size_str: &str) -> String {
         match size_str.parse::<usize>() {
             Ok(size) if (1..=25).contains(&size) => {
-                self.game = Game::new(size);
+                let (komi, variant, control) = (self.game.komi, self.game.variant, self.game.clock.control());
+                self.game = Game::with_rules(size, self.game.rules);
+                self.game.komi = komi;
+                self.game.variant = variant;
+                self.game.clock = Clock::new(control);
                 "".to_string()
             }
             _ => "? unacceptable size".to_string(),
@@ -119,11 +269,28 @@ impl GTPHandler {
     }
 
     fn clear_board(&mut self) -> String {
-        let size = self.game.board.size();
-        self.game = Game::new(size);
+        let (size, komi, variant) = (self.game.board.size(), self.game.komi, self.game.variant);
+        let control = self.game.clock.control();
+        self.game = Game::with_rules(size, self.game.rules);
+        self.game.komi = komi;
+        self.game.variant = variant;
+        self.game.clock = Clock::new(control);
         "".to_string()
     }
 
+    /// `fixed_handicap N`: places N stones on the standard points; the
+    /// engine then plays White aggressively and Black solidly
+    fn fixed_handicap(&mut self, count: &str) -> String {
+        let Ok(count) = count.parse::<usize>() else {
+            return "? invalid handicap".to_string();
+        };
+        let size = self.game.board.size();
+        match self.game.place_handicap(count) {
+            Ok(points) => points.iter().map(|&(x, y)| format_move(x, y, size)).collect::<Vec<_>>().join(" "),
+            Err(e) => format!("? {}", e),
+        }
+    }
+
     fn komi(&mut self, komi_str: &str) -> String {
         match komi_str.parse::<f32>() {
             Ok(komi) if (-360.0..360.0).contains(&komi) => {
@@ -139,67 +306,322 @@ impl GTPHandler {
     fn known_command(&self, command: &str) -> String {
         let commands = vec![
             "protocol_version", "name", "version", "boardsize", 
-            "clear_board", "komi", "get_komi", "play", "genmove", 
-            "genmove_black", "genmove_white", "undo", "captures",
-            "final_score", "time_settings", "quit", "exit",
+            "clear_board", "komi", "get_komi", "play", "genmove", "fixed_handicap",
+            "genmove_black", "genmove_white", "kgs-genmove_cleanup", "undo", "captures",
+            "final_score", "time_settings", "kgs-time_settings", "time_left", "quit", "exit",
             "list_commands", "showboard", "known_command", "list", "help",
             "is_legal", "list_stones", "countlib", "findlib",
-            "echo", "echo_err", "ladder_attack", "eye_data",
-            "loadsgf", "printsgf",
+            "echo", "echo_err", "ladder_attack", "net_attack", "owl_attack", "owl_defend", "worm_data", "influence", "moyo", "eye_data",
+            "loadsgf", "printsgf", "explain_last_move", "move_reasons",
+            "start_sgftrace", "finish_sgftrace", "stats",
+            "rengo_team", "rengo_to_play", "joseki", "game_statistics",
+            "reload_patterns", "last_move", "level", "set_random_seed", "get_random_seed", "top_moves",
         ];
         if commands.contains(&command) { "true".to_string() } else { "false".to_string() }
     }
 
     fn play(&mut self, (color, move_str): (&str, &str)) -> String {
-        let _stone = match color.to_lowercase().as_str() {
-            "black" => Stone::Black,
-            "white" => Stone::White,
+        let stone = match color.to_lowercase().as_str() {
+            "black" | "b" => Stone::Black,
+            "white" | "w" => Stone::White,
             _ => return "? invalid color".to_string(),
         };
 
-        if move_str.to_lowercase() == "pass" {
-            return match self.game.pass() {
-                Ok(()) => "".to_string(),
-                Err(e) => format!("? {}", e),
-            };
-        }
-
-        if let Some((x, y)) = parse_gtp_move(move_str, self.game.board.size()) {
-            match self.game.make_move(y, x) {
-                Ok(()) => "".to_string(),
-                Err(e) => format!("? {}", e),
-            }
+        let point = if move_str.to_lowercase() == "pass" {
+            None
         } else {
-            "? invalid move".to_string()
+            match parse_gtp_move(move_str, self.game.board.size()) {
+                Some(point) => Some(point),
+                None => return "? invalid move".to_string(),
+            }
+        };
+        match self.game.play_move(stone, point) {
+            Ok(()) => "".to_string(),
+            Err(e) => format!("? {}", e),
         }
     }
 
     fn genmove(&mut self, color: &str) -> String {
-        let _stone = match color.to_lowercase().as_str() {
+        let stone = match color.to_lowercase().as_str() {
             "black" => Stone::Black,
             "white" => Stone::White,
             _ => return "? invalid color".to_string(),
         };
+        if self.game.is_game_over() {
+            return "? game is over".to_string();
+        }
 
-        // Simple AI: find first valid move
-        let size = self.game.board.size();
-        for y in 0..size {
-            for x in 0..size {
-                if self.game.board.get_stone(x, y) == Stone::Empty {
-                    if self.game.make_move(y, x).is_ok() {
-                        return format_move(x, y);
+        profile::begin_move();
+        let started = Instant::now();
+        let before = self.game.board.clone();
+        self.game.current_player = stone == Stone::Black;
+        let mut ai = AI::for_level(self.level)
+            .with_limits(self.limits)
+            .with_time_budget(self.game.clock.move_budget(stone))
+            .with_resign_rule(self.resign)
+            .with_seed(self.rng.gen())
+            .with_variant(self.game.variant)
+            .with_style(Style::for_handicap(self.game.handicap, stone));
+        if ai.should_resign_game(&self.game, stone) {
+            return match self.game.resign() {
+                Ok(()) => "resign".to_string(),
+                Err(e) => format!("? {}", e),
+            };
+        }
+        let mut point = self.book_move(&before, stone).or_else(|| self.fuseki_move(&before, stone)).filter(|&(x, y)| self.game.make_move(x, y).is_ok());
+        while point.is_none() {
+            let choice = match &mut self.sgf_trace {
+                Some(trace) => ai.get_best_move_traced(&self.game, stone, trace),
+                None => ai.get_game_move(&self.game, stone),
+            };
+            match choice {
+                // The game refused the move: ask for the next best
+                Some((x, y)) if self.game.make_move(x, y).is_err() => ai = ai.excluding((x, y)),
+                Some(choice) => point = Some(choice),
+                None => {
+                    if let Err(e) = self.game.pass() {
+                        return format!("? {}", e);
                     }
+                    break;
                 }
             }
         }
+        self.game.record_move_time(started.elapsed());
+        self.last_explanation = Some(MoveExplanation::new(&before, stone, point, AIDifficulty::from_level(self.level)));
+
+        match point {
+            Some((x, y)) => format_move(x, y, before.size()),
+            None => "pass".to_string(),
+        }
+    }
+
+    /// A move from the opening book while the game is young enough
+    fn book_move(&mut self, board: &Board, color: Stone) -> Option<(usize, usize)> {
+        if self.game.move_list().len() >= BOOK_MOVES {
+            return None;
+        }
+        self.book.as_ref()?.choose(board, color, &mut self.rng)
+    }
+
+    /// A move from the fuseki database while the game is young enough
+    fn fuseki_move(&mut self, board: &Board, color: Stone) -> Option<(usize, usize)> {
+        if self.game.move_list().len() >= FUSEKI_MOVES || !FUSEKI_SIZES.contains(&board.size()) {
+            return None;
+        }
+        self.fuseki.as_ref()?.choose(board, color, &mut self.rng)
+    }
+
+    /// Starts the random choices over from `seed`
+    fn reseed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    /// `set_random_seed n`: makes the following moves reproducible
+    fn set_random_seed(&mut self, seed: &str) -> String {
+        match seed.parse() {
+            Ok(seed) => {
+                self.reseed(seed);
+                "".to_string()
+            }
+            _ => "? invalid seed".to_string(),
+        }
+    }
+
+    /// Time spent per subsystem and evaluation cache lookups; `stats
+    /// reset` clears the totals
+    fn stats(&mut self, arg: &str) -> String {
+        if !profile::ENABLED {
+            return "? timings need a build with --features profile".to_string();
+        }
+        match arg {
+            "" => {
+                let cache = Evaluator::cache_stats();
+                format!("{}\neval cache: {} hits, {} misses", profile::stats().to_text(), cache.hits, cache.misses)
+            }
+            "reset" => {
+                profile::reset();
+                Evaluator::clear_cache();
+                "".to_string()
+            }
+            _ => "? usage: stats [reset]".to_string(),
+        }
+    }
+
+    /// Starts recording the variations read by following searches
+    fn start_sgftrace(&mut self) -> String {
+        self.sgf_trace = Some(SearchTrace::new(&self.game.board, self.game.current_player()));
+        "".to_string()
+    }
+
+    /// Writes the variations recorded since `start_sgftrace` to `filename`
+    fn finish_sgftrace(&mut self, filename: &str) -> String {
+        if filename.is_empty() {
+            return "? missing filename".to_string();
+        }
+        let Some(trace) = self.sgf_trace.take() else {
+            return "? start_sgftrace was not called".to_string();
+        };
+        let sgf = SGFHandler::new().tree_to_sgf(&trace.to_sgf_tree());
+        match crate::fileio::write(Path::new(filename), sgf.as_bytes()) {
+            Ok(()) => "".to_string(),
+            Err(e) => format!("? cannot write {}: {}", filename, e),
+        }
+    }
+
+    /// `last_move`: color and vertex of the latest move, as "black D4"
+    fn last_move(&self) -> String {
+        let Some(last) = self.game.last_move() else {
+            return "? no previous move known".to_string();
+        };
+        let vertex = last.point.map_or("pass".to_string(), |(x, y)| format_move(x, y, self.game.board.size()));
+        format!("{} {}", last.color.to_string().to_lowercase(), vertex)
+    }
+
+    /// `explain_last_move`: move reasons and value breakdown behind the
+    /// most recent `genmove`
+    fn explain_last_move(&self) -> String {
+        match &self.last_explanation {
+            Some(explanation) => explanation.to_text(),
+            None => "? no move generated yet".to_string(),
+        }
+    }
 
-        // If no valid moves, pass
-        match self.game.pass() {
-            Ok(()) => "pass".to_string(),
+    /// `move_reasons [vertex]`: why `genmove` would play each move for the
+    /// side to move, best first, or the reasons for one vertex
+    fn move_reasons(&self, vertex: &str) -> String {
+        let (board, stone) = (&self.game.board, self.game.current_player());
+        let reasons = AI::for_level(self.level)
+            .with_limits(self.limits)
+            .with_variant(self.game.variant)
+            .with_style(Style::for_handicap(self.game.handicap, stone))
+            .move_reasons(board, stone);
+        if vertex.is_empty() {
+            return reasons.to_text(MOVE_REASONS_SHOWN);
+        }
+        match parse_gtp_move(vertex, board.size()) {
+            Some((x, y)) => reasons
+                .reasons(x, y)
+                .iter()
+                .map(|r| format!("{} {:+}", r.reason.describe(board.size()), r.value))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            None => "? invalid vertex".to_string(),
+        }
+    }
+
+    /// `top_moves [n]`: the best moves for the player to move, one per
+    /// line with its value, winning chance and expected line of play
+    fn top_moves(&self, count: &str) -> String {
+        let n = if count.is_empty() {
+            TOP_MOVES_SHOWN
+        } else {
+            match count.parse::<usize>() {
+                Ok(n) if n > 0 => n,
+                _ => return "? invalid count".to_string(),
+            }
+        };
+        let (board, stone) = (&self.game.board, self.game.current_player());
+        let size = board.size();
+        AI::for_level(self.level)
+            .with_limits(self.limits)
+            .with_variant(self.game.variant)
+            .with_style(Style::for_handicap(self.game.handicap, stone))
+            .top_moves(board, stone, n)
+            .iter()
+            .map(|m| {
+                let pv: Vec<String> = m.pv.iter().map(|&(x, y)| format_move(x, y, size)).collect();
+                format!("{} value {} winrate {:.2} pv {}", format_move(m.point.0, m.point.1, size), m.value, m.winrate, pv.join(" "))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// `rengo_team <color> [name...]`: sets the members of a team in playing
+    /// order, or clears it without names
+    fn rengo_team(&mut self, args: &[&str]) -> String {
+        let stone = match args.first().map(|c| c.to_lowercase()).as_deref() {
+            Some("black") | Some("b") => Stone::Black,
+            Some("white") | Some("w") => Stone::White,
+            _ => return "? invalid color".to_string(),
+        };
+        match self.game.set_team(stone, args[1..].iter().map(|name| name.to_string()).collect()) {
+            Ok(()) => "".to_string(),
             Err(e) => format!("? {}", e),
         }
     }
 
+    /// Name of the team member to play next, empty without teams
+    fn rengo_to_play(&self) -> String {
+        self.game.player_to_move().unwrap_or_default().to_string()
+    }
+
+    /// `level n`: plays at numeric level n, 1 to [`MAX_LEVEL`]
+    fn set_level(&mut self, level: &str) -> String {
+        match level.parse::<u8>() {
+            Ok(number) if (1..=MAX_LEVEL).contains(&number) => {
+                self.level = number;
+                "".to_string()
+            }
+            _ => format!("? level must be 1 to {}", MAX_LEVEL),
+        }
+    }
+
+    /// `reload_patterns [dir]`: rereads the pattern databases, from `dir`
+    /// if given (it then becomes the default), and reports one line per
+    /// database with its pattern count or load error
+    fn reload_patterns(&mut self, dir: &str) -> String {
+        if !dir.is_empty() {
+            self.pattern_dir = PathBuf::from(dir);
+        }
+        self.patterns.reload_databases(&self.pattern_dir).to_text()
+    }
+
+    /// `joseki <corner|vertex>`: name of the sequence in a corner (ul, ur,
+    /// ll, lr, or the corner nearest a vertex), then one line per standard
+    /// continuation
+    fn joseki(&self, arg: &str) -> String {
+        let size = self.game.board.size();
+        let corner = match Corner::from_name(arg) {
+            Some(corner) => corner,
+            None => match parse_gtp_move(arg, size) {
+                Some((x, y)) => Corner::nearest(x, y, size),
+                None => return "? invalid corner".to_string(),
+            },
+        };
+        let Some(found) = joseki::identify(&self.game.board, corner, self.game.current_player()) else {
+            return "? no known joseki in this corner".to_string();
+        };
+        let mut lines = vec![found.name.unwrap_or("empty corner").to_string()];
+        for next in found.continuations {
+            let color = if next.color == Stone::Black { "B" } else { "W" };
+            let vertex = format_move(next.point.0, next.point.1, size);
+            lines.push(format!("{} {} {}: {}", color, vertex, next.name, next.comment));
+        }
+        lines.join("\n")
+    }
+
+    /// `kgs-genmove_cleanup color`: like `genmove`, but takes dead stones off
+    /// and fills dame before passing, reopening a game ended by passes
+    fn genmove_cleanup(&mut self, color: &str) -> String {
+        let stone = match color.to_lowercase().as_str() {
+            "black" | "b" => Stone::Black,
+            "white" | "w" => Stone::White,
+            _ => return "? invalid color".to_string(),
+        };
+        if self.game.is_game_over() && self.game.resume().is_err() {
+            return "? game is over".to_string();
+        }
+        let name = if stone == Stone::Black { "black" } else { "white" };
+        let Some((x, y)) = aftermath_move(&self.game.board, stone, &self.game.rules) else {
+            return self.genmove(name);
+        };
+        match self.game.play_move(stone, Some((x, y))) {
+            Ok(()) => format_move(x, y, self.game.board.size()),
+            Err(_) => self.genmove(name),
+        }
+    }
+
     fn genmove_black(&mut self) -> String { self.genmove("black") }
     fn genmove_white(&mut self) -> String { self.genmove("white") }
 
@@ -220,17 +642,68 @@ impl GTPHandler {
     }
 
     fn final_score(&self) -> String {
-        if !self.game.is_game_over() {
-            return "? game not finished".to_string();
-        }
-        match self.game.winner() {
-            Some(Stone::Black) => "B+".to_string(),
-            Some(Stone::White) => "W+".to_string(),
-            _ => "0".to_string(),
-        }
+        self.game.result().unwrap_or_else(|| "? game not finished".to_string())
     }
 
-    fn time_settings(&self) -> String { "".to_string() }
+    /// `time_settings main_time byo_yomi_time byo_yomi_stones`, in seconds:
+    /// Canadian overtime for both sides
+    fn time_settings(&mut self, args: &[&str]) -> String {
+        let Ok(values) = args.iter().map(|a| a.parse()).collect::<Result<Vec<u64>, _>>() else {
+            return "? syntax error".to_string();
+        };
+        let &[main_time, byo_yomi_time, stones] = values.as_slice() else {
+            return "? syntax error".to_string();
+        };
+        let Ok(stones) = u32::try_from(stones) else {
+            return "? syntax error".to_string();
+        };
+        self.game.clock = Clock::new(TimeControl::canadian(main_time, byo_yomi_time, stones));
+        "".to_string()
+    }
+
+    /// `kgs-time_settings none|absolute M|byoyomi M T N|canadian M T S`, in seconds
+    fn kgs_time_settings(&mut self, args: &[&str]) -> String {
+        let Some((&system, values)) = args.split_first() else {
+            return "? syntax error".to_string();
+        };
+        let Ok(values) = values.iter().map(|a| a.parse()).collect::<Result<Vec<u64>, _>>() else {
+            return "? syntax error".to_string();
+        };
+        let control = match (system.to_lowercase().as_str(), values.as_slice()) {
+            ("none", []) => TimeControl::UNLIMITED,
+            ("absolute", &[main_time]) => TimeControl::canadian(main_time, 0, 0),
+            ("byoyomi", &[main_time, time, periods]) => match u32::try_from(periods) {
+                Ok(periods) => TimeControl::japanese(main_time, time, periods),
+                Err(_) => return "? syntax error".to_string(),
+            },
+            ("canadian", &[main_time, time, stones]) => match u32::try_from(stones) {
+                Ok(stones) => TimeControl::canadian(main_time, time, stones),
+                Err(_) => return "? syntax error".to_string(),
+            },
+            _ => return "? syntax error".to_string(),
+        };
+        self.game.clock = Clock::new(control);
+        "".to_string()
+    }
+
+    /// `time_left color time stones`: the controller's view of a side's clock
+    fn time_left(&mut self, args: &[&str]) -> String {
+        let stone = match args.first().map(|c| c.to_lowercase()).as_deref() {
+            Some("black") | Some("b") => Stone::Black,
+            Some("white") | Some("w") => Stone::White,
+            _ => return "? invalid color".to_string(),
+        };
+        let (Some(Ok(secs)), Some(Ok(stones))) = (args.get(1).map(|a| a.parse::<f64>()), args.get(2).map(|a| a.parse::<u32>())) else {
+            return "? syntax error".to_string();
+        };
+        let Ok(time) = Duration::try_from_secs_f64(secs) else {
+            return "? syntax error".to_string();
+        };
+        match self.game.clock.set_time_left(stone, time, stones) {
+            Ok(()) => "".to_string(),
+            Err(e) => format!("? {}", e),
+        }
+    }
 
     fn is_legal(&self, (color, move_str): (&str, &str)) -> String {
         let stone = match color.to_lowercase().as_str() {
@@ -241,16 +714,14 @@ impl GTPHandler {
         
         if move_str.to_lowercase() == "pass" { return "1".to_string(); }
         
-        if let Some((x, y)) = parse_gtp_move(move_str, self.game.board.size()) {
-            if self.game.board.get_stone(x, y) == Stone::Empty {
-                let mut test_board = self.game.board.clone();
-                return match test_board.place_stone(y, x, stone) {
-                    Ok(()) => "1".to_string(),
-                    Err(_) => "0".to_string(),
-                };
-            }
-        }
-        "0".to_string()
+        let Some((x, y)) = parse_gtp_move(move_str, self.game.board.size()) else {
+            return "0".to_string();
+        };
+        let rules = self.game.rules;
+        let legal = self.game.board.is_legal(x, y, stone, &rules).is_ok()
+            && (rules.ko_rule != KoRule::Superko
+                || rules.play(&self.game.board, x, y, stone, &self.game.position_hashes()).is_ok());
+        if legal { "1".to_string() } else { "0".to_string() }
     }
 
     fn list_stones(&self, color: &str) -> String {
@@ -262,10 +733,10 @@ impl GTPHandler {
         
         let size = self.game.board.size();
         let mut stones = Vec::new();
-        for y in 0..size {
-            for x in 0..size {
+        for y in 1..=size {
+            for x in 1..=size {
                 if self.game.board.get_stone(x, y) == stone {
-                    stones.push(format_move(x, y));
+                    stones.push(format_move(x, y, size));
                 }
             }
         }
@@ -284,7 +755,8 @@ impl GTPHandler {
     fn findlib(&self, move_str: &str) -> String {
         if let Some((x, y)) = parse_gtp_move(move_str, self.game.board.size()) {
             let liberties = self.game.board.find_liberties(x, y);
-            liberties.iter().map(|&(x, y)| format_move(x, y)).collect::<Vec<_>>().join("\n")
+            let size = self.game.board.size();
+            liberties.iter().map(|&(x, y)| format_move(x, y, size)).collect::<Vec<_>>().join("\n")
         } else {
             "? invalid move".to_string()
         }
@@ -301,8 +773,8 @@ impl GTPHandler {
                 return "? string must have exactly 2 liberties".to_string();
             }
             
-            if let Some(attack_point) = self.eye_analyzer.find_ladder_attack_point(&self.game.board, x, y) {
-                format!("1 {}", format_move(attack_point.0, attack_point.1))
+            if let Some(attack_point) = self.eye_analyzer.find_ladder_attack_point(&self.game.board, x, y, LevelProfile::for_level(self.level).ladder_depth) {
+                format!("1 {}", format_move(attack_point.0, attack_point.1, self.game.board.size()))
             } else {
                 "0".to_string()
             }
@@ -311,6 +783,116 @@ impl GTPHandler {
         }
     }
 
+    /// `net_attack VERTEX`: "1 MOVE" if a net captures the string, else "0"
+    fn net_attack(&self, move_str: &str) -> String {
+        let Some((x, y)) = parse_gtp_move(move_str, self.game.board.size()) else {
+            return "? invalid move".to_string();
+        };
+        if self.game.board.get_stone(x, y) == Stone::Empty {
+            return "? vertex must not be empty".to_string();
+        }
+        if !(2..=3).contains(&self.game.board.count_liberties(x, y)) {
+            return "? string must have 2 or 3 liberties".to_string();
+        }
+        match self.eye_analyzer.find_net_attack_point(&self.game.board, x, y, LevelProfile::for_level(self.level).net_depth) {
+            Some((ax, ay)) => format!("1 {}", format_move(ax, ay, self.game.board.size())),
+            None => "0".to_string(),
+        }
+    }
+
+    /// `owl_attack VERTEX` / `owl_defend VERTEX`: "1 MOVE" if the dragon can
+    /// be killed (saved) by moving first, "1 PASS" if no move is needed, else "0"
+    fn owl(&self, move_str: &str, attack: bool) -> String {
+        let Some((x, y)) = parse_gtp_move(move_str, self.game.board.size()) else {
+            return "? invalid move".to_string();
+        };
+        if self.game.board.get_stone(x, y) == Stone::Empty {
+            return "? vertex must not be empty".to_string();
+        }
+        let max_nodes = self.limits.nodes(LevelProfile::for_level(self.level).owl_nodes);
+        let result = owl::analyze(&self.game.board, (x, y), max_nodes);
+        let (succeeds, point) = if attack {
+            (matches!(result.status, OwlStatus::Dead | OwlStatus::Critical), result.attack_point)
+        } else {
+            (matches!(result.status, OwlStatus::Alive | OwlStatus::Critical), result.defense_point)
+        };
+        match (succeeds, point) {
+            (false, _) => "0".to_string(),
+            (true, Some((mx, my))) => format!("1 {}", format_move(mx, my, self.game.board.size())),
+            (true, None) => "1 PASS".to_string(),
+        }
+    }
+
+    /// `influence [black|white]`: one row of rounded values per board row,
+    /// top first; Black minus White unless a color is given
+    fn influence(&self, color: &str) -> String {
+        let map = InfluenceMap::compute(&self.game.board);
+        let size = map.size();
+        let value = |x: usize, y: usize| match color.to_lowercase().as_str() {
+            "" => Some(map.influence(x, y, Stone::Black) - map.influence(x, y, Stone::White)),
+            "black" | "b" => Some(map.influence(x, y, Stone::Black)),
+            "white" | "w" => Some(map.influence(x, y, Stone::White)),
+            _ => None,
+        };
+        if value(1, 1).is_none() {
+            return "? invalid color".to_string();
+        }
+        (1..=size)
+            .map(|y| (1..=size).map(|x| format!("{:4}", value(x, y).unwrap_or(0.0).round() as i32)).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// `moyo`: the board with stones as `X`/`O` and empty points marked
+    /// by `Region::symbol`, one row per line, top first
+    fn moyo(&self) -> String {
+        let board = &self.game.board;
+        let regions = InfluenceMap::compute(board).regions(board);
+        (1..=board.size())
+            .map(|y| {
+                (1..=board.size())
+                    .map(|x| match board.get_stone(x, y) {
+                        Stone::Black => 'X',
+                        Stone::White => 'O',
+                        Stone::Empty => regions[y - 1][x - 1].symbol(),
+                    })
+                    .map(String::from)
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// `worm_data VERTEX`: what reading knows about the string at VERTEX
+    fn worm_data(&self, move_str: &str) -> String {
+        let size = self.game.board.size();
+        let Some((x, y)) = parse_gtp_move(move_str, size) else {
+            return "? invalid move".to_string();
+        };
+        let worms = self.game.worms();
+        let Some(worm) = worms.get(x, y) else {
+            return "? vertex must not be empty".to_string();
+        };
+        let vertex = |point: Option<(usize, usize)>| point.map_or("PASS".to_string(), |(px, py)| format_move(px, py, size));
+        let points = |points: &[(usize, usize)]| points.iter().map(|&(px, py)| format!(" {}", format_move(px, py, size))).collect::<String>();
+        format!(
+            "color {}\norigin {}\nsize {}\neffective_size {:.2}\nliberties {}\ncutstone {}\ncut_points{}\n\
+             attack_code {}\nattack_point {}\ndefense_code {}\ndefense_point {}",
+            if worm.color == Stone::Black { "black" } else { "white" },
+            vertex(Some(worm.origin)),
+            worm.size(),
+            worm.effective_size,
+            worm.liberties.len(),
+            worm.cutstone as u8,
+            points(&worm.cut_points),
+            worm.attack_code.value(),
+            vertex(worm.attack_point),
+            worm.defense_code.value(),
+            vertex(worm.defense_point),
+        )
+    }
+
     fn eye_data(&self, (color, move_str): (&str, &str)) -> String {
         let stone_color = match color.to_lowercase().as_str() {
             "black" => Stone::Black,
@@ -319,25 +901,20 @@ impl GTPHandler {
         };
         
         if let Some((x, y)) = parse_gtp_move(move_str, self.game.board.size()) {
-            let eyes = self.eye_analyzer.analyze_eyes(&self.game.board, stone_color);
-            
-            for eye in eyes {
-                if eye.origin == (x, y) {
-                    return format!(
-                        "origin {} {}\ncolor {}\nesize {}\nmsize {}\nvalue {}\nmarginal {}\nneighbors {}\nmarginal_neighbors {}",
-                        eye.origin.0, eye.origin.1, 
-                        match eye.color {
-                            Stone::Black => "black",
-                            Stone::White => "white",
-                            Stone::Empty => "empty",
-                        },
-                        eye.esize, eye.msize, eye.value.to_string(), 
-                        eye.marginal as u8, eye.neighbors, eye.marginal_neighbors
-                    );
-                }
+            match self.eye_analyzer.eye_data(&self.game.board, stone_color, x, y) {
+                Some(eye) => format!(
+                    "origin {} {}\ncolor {}\nesize {}\nmsize {}\nvalue {}\nmarginal {}\nneighbors {}\nmarginal_neighbors {}",
+                    eye.origin.0, eye.origin.1, 
+                    match eye.color {
+                        Stone::Black => "black",
+                        Stone::White => "white",
+                        Stone::Empty => "empty",
+                    },
+                    eye.esize, eye.msize, eye.value, 
+                    eye.marginal as u8, eye.neighbors, eye.marginal_neighbors
+                ),
+                None => "? no eye data for this position".to_string(),
             }
-            
-            "? no eye data for this position".to_string()
         } else {
             "? invalid move".to_string()
         }
@@ -346,28 +923,39 @@ impl GTPHandler {
     fn list_commands(&self) -> String {
         vec![
             "protocol_version", "name", "version", "boardsize", "clear_board",
-            "komi", "get_komi", "play", "genmove", "genmove_black", "genmove_white",
-            "undo", "captures", "final_score", "time_settings",
+            "komi", "get_komi", "play", "genmove", "genmove_black", "genmove_white", "kgs-genmove_cleanup", "fixed_handicap",
+            "undo", "captures", "final_score", "time_settings", "kgs-time_settings", "time_left",
             "is_legal", "list_stones", "quit", "exit", "list", "help",
             "list_commands", "showboard", "known_command",
             "countlib", "findlib", "echo", "echo_err",
-            "ladder_attack", "eye_data", "loadsgf", "printsgf",
+            "ladder_attack", "net_attack", "owl_attack", "owl_defend", "worm_data", "influence", "moyo", "eye_data", "loadsgf", "printsgf",
+            "explain_last_move", "move_reasons", "start_sgftrace", "finish_sgftrace", "stats",
+            "rengo_team", "rengo_to_play", "joseki", "game_statistics",
+            "reload_patterns", "last_move", "level", "set_random_seed", "get_random_seed", "top_moves",
         ].join("\n")
     }
 
+    /// The board with the last move in parentheses, as GNU Go shows it
     fn showboard(&self) -> String {
         let board = &self.game.board;
         let size = board.size();
+        let last = self.game.last_move().and_then(|m| m.point);
         let mut result = String::new();
-        for y in 0..size {
-            for x in 0..size {
-                let c = match board.get_stone(x, y) {
+        for y in 1..=size {
+            for x in 1..=size + 1 {
+                result.push(match last {
+                    Some(point) if point == (x, y) => '(',
+                    Some(point) if point == (x - 1, y) => ')',
+                    _ => ' ',
+                });
+                if x > size {
+                    break;
+                }
+                result.push(match board.get_stone(x, y) {
                     Stone::Black => 'X',
                     Stone::White => 'O',
                     Stone::Empty => '.',
-                };
-                result.push(c);
-                result.push(' ');
+                });
             }
             result.push('\n');
         }
@@ -406,28 +994,29 @@ impl GTPHandler {
     }
 }
 
-/// Parse GTP move format (e.g., "D4")
-fn parse_gtp_move(move_str: &str, board_size: usize) -> Option<(usize, usize)> {
+/// Parse GTP move format (e.g., "D4") into 1-based board coordinates.
+/// GTP counts rows from the bottom while the board's y axis starts at the top.
+pub fn parse_gtp_move(move_str: &str, board_size: usize) -> Option<(usize, usize)> {
     if move_str.len() < 2 { return None; }
     
     let mut chars = move_str.chars();
     let col_char = chars.next()?.to_ascii_uppercase();
-    if col_char == 'I' { return None; }
+    if !col_char.is_ascii_uppercase() || col_char == 'I' { return None; }
     
-    let mut x = (col_char as u8 - b'A') as usize;
+    let mut x = (col_char as u8 - b'A') as usize + 1;
     if col_char > 'I' { x -= 1; }
     
     let row_str: String = chars.collect();
     let y: usize = match row_str.parse::<usize>() {
-        Ok(n) if n > 0 && n <= board_size => n - 1,
+        Ok(n) if n > 0 && n <= board_size => board_size + 1 - n,
         _ => return None,
     };
     
-    if x < board_size && y < board_size { Some((x, y)) } else { None }
+    if x <= board_size { Some((x, y)) } else { None }
 }
 
-/// Format move to GTP format (e.g., "D4")
-fn format_move(x: usize, y: usize) -> String {
-    let col_char = if x < 8 { (b'A' + x as u8) as char } else { (b'A' + x as u8 + 1) as char };
-    format!("{}{}", col_char, y + 1)
-}
\ No newline at end of file
+/// Format 1-based board coordinates to GTP format (e.g., "D4")
+pub fn format_move(x: usize, y: usize, board_size: usize) -> String {
+    let col_char = if x <= 8 { (b'A' + x as u8 - 1) as char } else { (b'A' + x as u8) as char };
+    format!("{}{}", col_char, board_size + 1 - y)
+}
@@ -4,11 +4,21 @@
 //! GNU Go Rust Rewrite (gnugo-rs) - Core Library
 
 
+pub mod cli;
+pub mod config;
 pub mod engine;
+#[cfg(feature = "cdylib")]
+pub mod ffi;
+pub mod fileio;
 pub mod gtp;
+pub mod json;
 pub mod patterns;
+#[cfg(feature = "native")]
+pub mod server;
 pub mod sgf;
 pub mod ui;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 #[cfg(test)]
 mod tests {
@@ -3,8 +3,12 @@
 
 //! GNU Go Rust Rewrite (gnugo-rs) - Main Entry Point
 
+use gnugo_rs::sgf::archive::Archive;
+use gnugo_rs::ui::i18n::{Language, Messages};
 use gnugo_rs::ui::terminal::TerminalUI;
 use gnugo_rs::gtp::GTPHandler;
+use gnugo_rs::cli;
+use gnugo_rs::config::Config;
 
 #[cfg(feature = "ko_test")]
 use gnugo_rs::engine::ko_test::test_ko_rule;
@@ -15,8 +19,19 @@ fn main() {
     // Parse command line arguments
     let args: Vec<String> = std::env::args().collect();
     
-    if args.len() > 1 {
-        match args[1].as_str() {
+    // Config file defaults, overridden by flags anywhere on the command line
+    let config = match Config::load(&args[1..]) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("config error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    
+    // The mode and its arguments, after any leading config flags
+    let command = Config::skip_flags(&args[1..]);
+    if let Some(mode) = command.first() {
+        match mode.as_str() {
             "--test-ko" => {
                 #[cfg(feature = "ko_test")]
                 {
@@ -26,14 +41,166 @@ fn main() {
                 #[cfg(not(feature = "ko_test"))]
                 {
                     eprintln!("Ko test feature not enabled. Build with '--features ko_test'");
-                    return;
+                    std::process::exit(1);
                 }
             },
             "--gtp" => {
                 println!("Starting in GTP mode...");
-                let mut gtp_handler = GTPHandler::new(19);
+                let mut gtp_handler = GTPHandler::with_config(&config);
                 if let Err(e) = gtp_handler.run() {
                     eprintln!("GTP error: {}", e);
+                    std::process::exit(1);
+                }
+                return;
+            },
+            "--gtp-listen" => {
+                let address = gnugo_rs::gtp::daemon::listen_address(command.get(1).map_or("3333", |s| s.as_str()));
+                if let Err(e) = gnugo_rs::gtp::daemon::serve(&address, &config) {
+                    eprintln!("GTP daemon error: {}", e);
+                    std::process::exit(1);
+                }
+                return;
+            },
+            "--serve-json" => {
+                let address = gnugo_rs::gtp::daemon::listen_address(command.get(1).map_or("8080", |s| s.as_str()));
+                let server = std::sync::Arc::new(gnugo_rs::server::json_rpc::JsonRpcServer::with_config(config));
+                if let Err(e) = server.serve(&address) {
+                    eprintln!("JSON-RPC server error: {}", e);
+                    std::process::exit(1);
+                }
+                return;
+            },
+            "--serve-ws" => {
+                let address = gnugo_rs::gtp::daemon::listen_address(command.get(1).map_or("8081", |s| s.as_str()));
+                if let Err(e) = gnugo_rs::server::websocket::serve(&address, &config) {
+                    eprintln!("WebSocket server error: {}", e);
+                    std::process::exit(1);
+                }
+                return;
+            },
+            "selfplay" => {
+                if let Err(e) = cli::selfplay::run(&command[1..], &config) {
+                    eprintln!("selfplay error: {}", e);
+                    std::process::exit(1);
+                }
+                return;
+            },
+            "solve" => {
+                if let Err(e) = cli::solve::run(&command[1..], &config) {
+                    eprintln!("solve error: {}", e);
+                    std::process::exit(1);
+                }
+                return;
+            },
+            "games" => {
+                if let Err(e) = cli::games::run(&command[1..], &config) {
+                    eprintln!("games error: {}", e);
+                    std::process::exit(1);
+                }
+                return;
+            },
+            "score" => {
+                if let Err(e) = cli::score::run(&command[1..], &config) {
+                    eprintln!("score error: {}", e);
+                    std::process::exit(1);
+                }
+                return;
+            },
+            "svg" => {
+                if let Err(e) = cli::svg::run(&command[1..]) {
+                    eprintln!("svg error: {}", e);
+                    std::process::exit(1);
+                }
+                return;
+            },
+            "convert" => {
+                if let Err(e) = cli::convert::run(&command[1..]) {
+                    eprintln!("convert error: {}", e);
+                    std::process::exit(1);
+                }
+                return;
+            },
+            "analyze" => {
+                if let Err(e) = cli::analyze::run(&command[1..]) {
+                    eprintln!("analyze error: {}", e);
+                    std::process::exit(1);
+                }
+                return;
+            },
+            "annotate" => {
+                if let Err(e) = cli::annotate::run(&command[1..]) {
+                    eprintln!("annotate error: {}", e);
+                    std::process::exit(1);
+                }
+                return;
+            },
+            "patterns" => {
+                if let Err(e) = cli::patterns::run(&config) {
+                    eprintln!("patterns error: {}", e);
+                    std::process::exit(1);
+                }
+                return;
+            },
+            "book" => {
+                if let Err(e) = cli::book::run(&command[1..]) {
+                    eprintln!("book error: {}", e);
+                    std::process::exit(1);
+                }
+                return;
+            },
+            "fuseki" => {
+                if let Err(e) = cli::fuseki::run(&command[1..]) {
+                    eprintln!("fuseki error: {}", e);
+                    std::process::exit(1);
+                }
+                return;
+            },
+            "tune" => {
+                if let Err(e) = cli::tune::run(&command[1..]) {
+                    eprintln!("tune error: {}", e);
+                    std::process::exit(1);
+                }
+                return;
+            },
+            "statistics" => {
+                if let Err(e) = cli::statistics::run(&command[1..]) {
+                    eprintln!("statistics error: {}", e);
+                    std::process::exit(1);
+                }
+                return;
+            },
+            "strength" => {
+                if let Err(e) = cli::strength::run(&command[1..]) {
+                    eprintln!("strength error: {}", e);
+                    std::process::exit(1);
+                }
+                return;
+            },
+            "cgos" => {
+                if let Err(e) = cli::cgos::run(&command[1..], &config) {
+                    eprintln!("cgos error: {}", e);
+                    std::process::exit(1);
+                }
+                return;
+            },
+            "igs" => {
+                if let Err(e) = cli::igs::run(&command[1..], &config) {
+                    eprintln!("igs error: {}", e);
+                    std::process::exit(1);
+                }
+                return;
+            },
+            "ogs" => {
+                if let Err(e) = cli::ogs::run(&command[1..], &config) {
+                    eprintln!("ogs error: {}", e);
+                    std::process::exit(1);
+                }
+                return;
+            },
+            "match" => {
+                if let Err(e) = cli::tournament::run(&command[1..], &config) {
+                    eprintln!("match error: {}", e);
+                    std::process::exit(1);
                 }
                 return;
             },
@@ -42,20 +209,27 @@ fn main() {
                 return;
             },
             _ => {
-                eprintln!("Unknown option: {}", args[1]);
+                eprintln!("Unknown option: {}", mode);
                 print_help();
-                return;
+                std::process::exit(1);
             }
         }
     }
     
     // Normal interactive game mode
-    let mut ui = TerminalUI::new(19);
+    let language = config.language.unwrap_or_else(Language::from_env);
+    let messages = Messages::new(language);
+    let mut ui = TerminalUI::with_theme(config.new_game(), &config.theme).with_language(language)
+        .with_accessible(config.accessible)
+        .with_archive(config.archive_dir.as_ref().map(Archive::new));
     
     // Run the game
     match ui.run() {
-        Ok(_) => println!("Game exited normally"),
-        Err(e) => eprintln!("Game error: {}", e),
+        Ok(_) => println!("{}", messages.get("Game exited normally")),
+        Err(e) => {
+            eprintln!("{}", messages.format("Game error: {}", &[&e]));
+            std::process::exit(1);
+        }
     }
 }
 
@@ -64,6 +238,77 @@ fn print_help() {
     println!("Usage:");
     println!("  gnugo_rs              - Start interactive terminal game");
     println!("  gnugo_rs --gtp        - Start in GTP protocol mode");
+    println!("  gnugo_rs --gtp-listen [ADDR|PORT]");
+    println!("                        - Serve concurrent GTP sessions over TCP (default port 3333)");
+    println!("  gnugo_rs --serve-json [ADDR|PORT]");
+    println!("                        - Serve a JSON-RPC analysis API over HTTP (default port 8080)");
+    println!("  gnugo_rs --serve-ws [ADDR|PORT]");
+    println!("                        - Serve a browser board and WebSocket API (default port 8081)");
     println!("  gnugo_rs --test-ko    - Run ko rule tests (requires ko_test feature)");
+    println!("  gnugo_rs selfplay [--games N] [--size N] [--komi K] [--sgf-dir DIR]");
+    println!("                    [--level-a LEVEL] [--level-b LEVEL] [--max-moves N]");
+    println!("                        - Play the engine against itself and report statistics");
+    println!("  gnugo_rs solve problem.sgf [--target VERTEX] [--color C] [--max-nodes N] [--trace FILE]");
+    println!("                        - Read the life and death of the marked group");
+    println!("  gnugo_rs score game.sgf [--ruleset chinese|japanese]");
+    println!("                        - Replay a finished game and print the final result");
+    println!("  gnugo_rs games list [--player NAME] [--mode MODE] [--size N] [--since YYYY-MM-DD]");
+    println!("                        - List archived games (needs --archive or [archive] dir)");
+    println!("  gnugo_rs svg game.sgf [--move N] [--numbers] [--no-coordinates] [--cell PX] [--output FILE]");
+    println!("                        - Draw a position from a game record as SVG");
+    println!("  gnugo_rs convert game.gib|game.ngf|game.sgf [--output FILE]");
+    println!("                        - Convert a Tygem or wBaduk record to SGF");
+    println!("  gnugo_rs analyze game.sgf [--candidates N] [--output FILE] [--annotate FILE]");
+    println!("                        - Write candidate moves, ownership, dragon statuses and");
+    println!("                          score estimate for the final position as JSON, with the");
+    println!("                          fuseki and joseki played; --annotate saves them as comments");
+    println!("  gnugo_rs annotate game.sgf [--threshold N] [--output FILE]");
+    println!("                        - Review a game: comment on moves that lose more than N");
+    println!("                          against the engine's choice and name its preferred move");
+    println!("  gnugo_rs patterns [--patterns DIR]");
+    println!("                        - Load the pattern databases and report counts and errors");
+    println!("                          (GTP reload_patterns does the same in a running engine)");
+    println!("  gnugo_rs statistics game.sgf [--json]");
+    println!("                        - Print move count, captures, largest capture, kos and passes");
+    println!("  gnugo_rs book DIR --output FILE [--moves N]");
+    println!("                        - Build an opening book from the first N moves (default 20)");
+    println!("                          of the SGF games in DIR, for --book");
+    println!("  gnugo_rs fuseki DIR --output FILE [--moves N]");
+    println!("                        - Count the moves of the first N moves (default 20) of the");
+    println!("                          9x9, 13x13 and 19x19 SGF games in DIR, for --fuseki");
+    println!("  gnugo_rs tune DIR --database FILE --output FILE [--min-rank RANK]");
+    println!("                        - Scale the pattern values of FILE by how often their moves");
+    println!("                          were played in the SGF games in DIR, by players of RANK");
+    println!("                          (e.g. 1d) or stronger, and write the database to --output");
+    println!("  gnugo_rs strength game.sgf... [--player NAME | --color black|white]");
+    println!("                        - Estimate a rank from engine agreement, blunders and");
+    println!("                          average loss; --player matches PB/PW across the records");
+    println!("  gnugo_rs match [--engine-a SPEC] [--engine-b SPEC] [--games N] [--sgf-dir DIR]");
+    println!("                 [--max-moves N]");
+    println!("                        - Play two engines against each other, alternating colors;");
+    println!("                          SPEC is internal[:LEVEL] or an external GTP command line");
+    println!("  gnugo_rs cgos --server HOST:PORT --user NAME --password PW [--engine SPEC] [--games N]");
+    println!("                        - Play rated games on a Computer Go Server");
+    println!("  gnugo_rs igs --user NAME --password PW [--server HOST:PORT] [--engine SPEC]");
+    println!("                [--games N] [--observe GAME]");
+    println!("                        - Play matches on an IGS-compatible server, or observe a game");
+    println!("  gnugo_rs ogs --user NAME --apikey KEY [--engine SPEC] [--concurrent N] [--games N]");
+    println!("                        - Accept challenges and play as a bot on online-go.com");
+    println!("                          (requires ogs feature)");
     println!("  gnugo_rs --help       - Show this help message");
+    println!();
+    println!("Settings (override ~/.config/gnugo-rs/config.toml):");
+    println!("  --config PATH         - Read settings from PATH instead");
+    println!("  --size N  --komi K  --level beginner|intermediate|advanced|1-10");
+    println!("  --ruleset chinese|japanese|aga|nz|ing  --patterns DIR  --theme classic|unicode|ascii  --lang en|zh");
+    println!("  --variant standard|capture  --capture-target N  (Capture Go: first to N captures wins)");
+    println!("  --accessible  plain-text output with spoken-style move announcements");
+    println!("  --archive DIR  save every finished game there (see `games list`)");
+    println!("  --main-time SECS  --byo-yomi SECS  --byo-yomi-stones N");
+    println!("  --max-memory MB  --tt-size ENTRIES  --max-nodes N  --threads N");
+    println!("  --book FILE  play the first moves of GTP games from an opening book");
+    println!("  --fuseki FILE  play whole-board openings on 9x9, 13x13 and 19x19 from a fuseki database");
+    println!("  --resign-threshold SHARE  --resign-moves N  resign once trailing by SHARE of the");
+    println!("                        board for N moves in a row (0 never resigns)");
+    println!("  --seed N  make the engine's random choices reproducible");
 }
\ No newline at end of file
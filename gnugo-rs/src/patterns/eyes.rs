@@ -0,0 +1,93 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Eye shapes: the standard shapes of small eye spaces, with the eyes each
+//! one gives and the vital point that decides between them
+//!
+//! Each shape is drawn row by row, top first: `.` is a point of the eye
+//! space, `*` its vital point and a blank no point. A shape matches an eye
+//! space made of the same points in any of the eight orientations.
+
+/// A shape of eye space and what it is worth
+pub struct EyeShape {
+    pub name: &'static str,
+    diagram: &'static [&'static str],
+    /// Eyes when the opponent moves first
+    pub min_eyes: u8,
+    /// Eyes when the owner moves first
+    pub max_eyes: u8,
+}
+
+const SHAPES: &[EyeShape] = &[
+    EyeShape { name: "one-point eye", diagram: &["."], min_eyes: 1, max_eyes: 1 },
+    EyeShape { name: "two-point eye", diagram: &[".."], min_eyes: 1, max_eyes: 1 },
+    EyeShape { name: "straight three", diagram: &[".*."], min_eyes: 1, max_eyes: 2 },
+    EyeShape { name: "bent three", diagram: &["*.", ". "], min_eyes: 1, max_eyes: 2 },
+    EyeShape { name: "straight four", diagram: &["...."], min_eyes: 2, max_eyes: 2 },
+    EyeShape { name: "bent four", diagram: &["...", "  ."], min_eyes: 2, max_eyes: 2 },
+    EyeShape { name: "zigzag four", diagram: &[".. ", " .."], min_eyes: 2, max_eyes: 2 },
+    EyeShape { name: "square four", diagram: &["..", ".."], min_eyes: 1, max_eyes: 1 },
+    EyeShape { name: "pyramid four", diagram: &[".*.", " . "], min_eyes: 1, max_eyes: 2 },
+    EyeShape { name: "bulky five", diagram: &[".. ", ".*."], min_eyes: 1, max_eyes: 2 },
+    EyeShape { name: "crossed five", diagram: &[" . ", ".*.", " . "], min_eyes: 1, max_eyes: 2 },
+    EyeShape { name: "rabbity six", diagram: &[" . ", ".*.", " .."], min_eyes: 1, max_eyes: 2 },
+    EyeShape { name: "rectangular six", diagram: &["...", "..."], min_eyes: 2, max_eyes: 2 },
+];
+
+/// Offset (dx, dy) in orientation `t` of the eight
+fn orient((dx, dy): (i32, i32), t: usize) -> (i32, i32) {
+    let (a, b) = match t % 4 {
+        0 => (dx, dy),
+        1 => (-dy, dx),
+        2 => (-dx, -dy),
+        _ => (dy, -dx),
+    };
+    if t >= 4 { (-a, b) } else { (a, b) }
+}
+
+/// Points shifted so the smallest x and y are 0, sorted
+fn normalize(points: &mut [(i32, i32)]) -> (i32, i32) {
+    let min_x = points.iter().map(|p| p.0).min().unwrap_or(0);
+    let min_y = points.iter().map(|p| p.1).min().unwrap_or(0);
+    for point in points.iter_mut() {
+        *point = (point.0 - min_x, point.1 - min_y);
+    }
+    points.sort();
+    (min_x, min_y)
+}
+
+/// The standard shape of the eye space made of `points`, with its vital
+/// point on the board if the shape has one
+pub fn eye_shape(points: &[(usize, usize)]) -> Option<(&'static EyeShape, Option<(usize, usize)>)> {
+    let mut space: Vec<(i32, i32)> = points.iter().map(|&(x, y)| (x as i32, y as i32)).collect();
+    let origin = normalize(&mut space);
+    for shape in SHAPES {
+        let mut cells = Vec::new();
+        let mut vital = None;
+        for (row, line) in shape.diagram.iter().enumerate() {
+            for (col, symbol) in line.chars().enumerate() {
+                if symbol == '*' {
+                    vital = Some((col as i32, row as i32));
+                }
+                if symbol != ' ' {
+                    cells.push((col as i32, row as i32));
+                }
+            }
+        }
+        if cells.len() != space.len() {
+            continue;
+        }
+        for t in 0..8 {
+            let mut oriented: Vec<(i32, i32)> = cells.iter().map(|&cell| orient(cell, t)).collect();
+            let shift = normalize(&mut oriented);
+            if oriented == space {
+                let vital = vital.map(|cell| {
+                    let (vx, vy) = orient(cell, t);
+                    ((vx - shift.0 + origin.0) as usize, (vy - shift.1 + origin.1) as usize)
+                });
+                return Some((shape, vital));
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,325 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Whole-board openings (fuseki): recognition on 19x19, and the first
+//! moves of a game on any size
+//!
+//! Each opening is the set of points one player occupies along a side,
+//! written as (along, line): the distance from one end of the side and
+//! the line counted from its edge. An opening matches on any side, read
+//! from either end.
+//!
+//! Until a board holds a few stones, [`opening_move`] takes an empty
+//! corner, then an open side, then the center.
+//!
+//! A [`FusekiDatabase`], like GNU Go's fuseki9/13/19 databases, holds the
+//! moves recorded games played from whole-board positions, keyed by board
+//! size and a hash that is the same for all eight symmetric copies of a
+//! position. Its file is plain text, one position per line: board size,
+//! canonical hash in hex, the player to move and its moves with counts,
+//! in the orientation of the canonical position, as in
+//! `9 5f3a09c2e1d4b786 B E5:12 C3:3`.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::Path;
+
+use rand::Rng;
+
+use crate::engine::board::{Board, Stone};
+use crate::engine::game::{Game, GameMove};
+use crate::fileio;
+use crate::gtp::{format_move, parse_gtp_move};
+use crate::patterns::pattern_transform::Transformation;
+use crate::sgf::{is_move_node, SGFHandler, SGFTree};
+
+/// Board size the openings are defined for
+pub const FUSEKI_SIZE: usize = 19;
+
+/// Openings, most specific first
+const OPENINGS: &[(&str, &[(usize, usize)])] = &[
+    ("Sanrensei", &[(4, 4), (10, 4), (16, 4)]),
+    ("High Chinese opening", &[(4, 4), (10, 4), (16, 3)]),
+    ("Low Chinese opening", &[(4, 4), (10, 3), (16, 3)]),
+    ("Nirensei", &[(4, 4), (16, 4)]),
+];
+
+/// Board point of (along, line) on the side numbered `side`, read forwards
+/// or backwards
+fn side_point(side: usize, reversed: bool, (along, line): (usize, usize)) -> (usize, usize) {
+    let n = FUSEKI_SIZE + 1;
+    let along = if reversed { n - along } else { along };
+    match side {
+        0 => (along, line),
+        1 => (along, n - line),
+        2 => (line, along),
+        _ => (n - line, along),
+    }
+}
+
+/// Name of the opening formed by the stones of `color`, if any
+pub fn identify(board: &Board, color: Stone) -> Option<&'static str> {
+    if board.size() != FUSEKI_SIZE {
+        return None;
+    }
+    OPENINGS
+        .iter()
+        .find(|(_, points)| {
+            (0..4).any(|side| {
+                [false, true].into_iter().any(|reversed| {
+                    points.iter().all(|&p| {
+                        let (x, y) = side_point(side, reversed, p);
+                        board.get_stone(x, y) == color
+                    })
+                })
+            })
+        })
+        .map(|&(name, _)| name)
+}
+
+/// Smallest board with room for corner openings
+const MIN_OPENING_SIZE: usize = 7;
+
+/// Line of the corner and side points: the fourth on big boards, the third
+/// on small ones
+fn opening_line(size: usize) -> usize {
+    if size >= 13 { 4 } else { 3 }
+}
+
+/// Whether no stone lies within `radius` points of (x, y), in both directions
+fn open_around(board: &Board, (x, y): (usize, usize), radius: usize) -> bool {
+    let size = board.size();
+    (y.saturating_sub(radius).max(1)..=(y + radius).min(size)).all(|ny| {
+        (x.saturating_sub(radius).max(1)..=(x + radius).min(size)).all(|nx| board.get_stone(nx, ny) == Stone::Empty)
+    })
+}
+
+/// The opening point to play on a nearly empty board: the star point of
+/// an empty corner, then the middle of an open side, then the center.
+/// `None` once the opening is over or no such point is left
+pub fn opening_move(board: &Board) -> Option<(usize, usize)> {
+    let size = board.size();
+    let stones = board.stones_on_board(Stone::Black) + board.stones_on_board(Stone::White);
+    // The opening lasts while there are fewer stones than a third of a line
+    if size < MIN_OPENING_SIZE || 3 * stones >= size {
+        return None;
+    }
+    let line = opening_line(size);
+    let (far, middle) = (size + 1 - line, size.div_ceil(2));
+    // Upper right first, then the diagonally opposite corner
+    let corners = [(far, line), (line, far), (far, far), (line, line)];
+    let sides = [(middle, line), (middle, far), (line, middle), (far, middle)];
+    let radius = line - 1;
+    corners
+        .into_iter()
+        .chain(sides)
+        .find(|&point| open_around(board, point, radius))
+        .or_else(|| Some((middle, middle)).filter(|&point| open_around(board, point, 1)))
+}
+
+/// Moves of a game entered into a fuseki database, and the moves of a
+/// game in which `genmove` consults it
+pub const FUSEKI_MOVES: usize = 20;
+
+/// Board sizes fuseki databases are built for
+pub const FUSEKI_SIZES: [usize; 3] = [9, 13, 19];
+
+/// `point` moved by `transformation` on a board of `size`, 1-based
+fn transform_point(transformation: Transformation, (x, y): (usize, usize), size: usize) -> (usize, usize) {
+    let (tx, ty) = transformation.apply(x - 1, y - 1, size);
+    (tx + 1, ty + 1)
+}
+
+/// The smallest hash of the eight symmetric copies of `board`, and each
+/// transformation that gives it
+fn canonical_transformations(board: &Board) -> (u64, Vec<Transformation>) {
+    let size = board.size();
+    let hashes: Vec<(Transformation, u64)> = Transformation::all()
+        .into_iter()
+        .map(|t| (t, board.mapped_hash(|x, y| transform_point(t, (x, y), size))))
+        .collect();
+    let lowest = hashes.iter().map(|&(_, hash)| hash).min().unwrap_or_default();
+    (lowest, hashes.into_iter().filter(|&(_, hash)| hash == lowest).map(|(t, _)| t).collect())
+}
+
+/// Hash shared by `board` and its rotations and reflections, and a
+/// transformation taking `board` to the copy it is the hash of
+pub fn canonical_hash(board: &Board) -> (u64, Transformation) {
+    let (hash, transformations) = canonical_transformations(board);
+    (hash, transformations[0])
+}
+
+/// A move of a fuseki database and how many times it was played
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FusekiMove {
+    pub point: (usize, usize),
+    pub count: u32,
+}
+
+/// Whole-board positions by board size, canonical hash and player to
+/// move, with the moves played from them in canonical orientation
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FusekiDatabase {
+    positions: HashMap<(usize, u64, Stone), Vec<FusekiMove>>,
+}
+
+/// Most played first, ties in board order, so equal databases compare equal
+fn sort_moves(moves: &mut [FusekiMove]) {
+    moves.sort_by_key(|m| (std::cmp::Reverse(m.count), m.point.1, m.point.0));
+}
+
+impl FusekiDatabase {
+    pub fn new() -> Self {
+        FusekiDatabase::default()
+    }
+
+    /// Positions in the database
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Counts `color` playing `point` on `board` once more. In a
+    /// symmetric position the symmetric moves count as one
+    pub fn add(&mut self, board: &Board, color: Stone, point: (usize, usize)) {
+        let size = board.size();
+        let (hash, transformations) = canonical_transformations(board);
+        let point = transformations
+            .into_iter()
+            .map(|t| transform_point(t, point, size))
+            .min_by_key(|&(x, y)| (y, x))
+            .unwrap_or(point);
+        let moves = self.positions.entry((size, hash, color)).or_default();
+        match moves.iter_mut().find(|m| m.point == point) {
+            Some(known) => known.count += 1,
+            None => moves.push(FusekiMove { point, count: 1 }),
+        }
+        sort_moves(moves);
+    }
+
+    /// Moves for `color` on `board` in the orientation of `board`, most
+    /// played first
+    pub fn moves(&self, board: &Board, color: Stone) -> Vec<FusekiMove> {
+        let size = board.size();
+        let (hash, transformation) = canonical_hash(board);
+        let back = transformation.inverse();
+        self.positions
+            .get(&(size, hash, color))
+            .map(|moves| moves.iter().map(|m| FusekiMove { point: transform_point(back, m.point, size), count: m.count }).collect())
+            .unwrap_or_default()
+    }
+
+    /// A move for `color` on `board` on an empty point other than the ko
+    /// point, picked at random in proportion to how often it was played
+    pub fn choose<R: Rng>(&self, board: &Board, color: Stone, rng: &mut R) -> Option<(usize, usize)> {
+        let moves: Vec<FusekiMove> = self
+            .moves(board, color)
+            .into_iter()
+            .filter(|m| board.get_stone(m.point.0, m.point.1) == Stone::Empty && board.get_ko_point() != Some(m.point))
+            .collect();
+        let total: u32 = moves.iter().map(|m| m.count).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut pick = rng.gen_range(0..total);
+        for m in &moves {
+            if pick < m.count {
+                return Some(m.point);
+            }
+            pick -= m.count;
+        }
+        None
+    }
+
+    /// Enters the first `moves` moves of the main line of `tree`, if it is
+    /// played on one of the [`FUSEKI_SIZES`]; returns whether it was
+    pub fn add_game(&mut self, tree: &SGFTree, moves: usize) -> Result<bool, String> {
+        let handler = SGFHandler::new();
+        let mut root = tree.root.clone();
+        root.children.clear();
+        let mut game = Game::new(19);
+        handler.apply_to_game(&SGFTree { root, current: 0 }, &mut game)?;
+        if !FUSEKI_SIZES.contains(&game.board.size()) {
+            return Ok(false);
+        }
+
+        let mut played = usize::from(is_move_node(&tree.root));
+        let mut node = tree.root.children.first();
+        while let Some(current) = node {
+            if played >= moves {
+                break;
+            }
+            let before = game.board.clone();
+            handler.apply_node(current, &mut game)?;
+            if is_move_node(current) {
+                played += 1;
+                if let Some(&GameMove { color, point: Some(point), .. }) = game.last_move() {
+                    self.add(&before, color, point);
+                }
+            }
+            node = current.children.first();
+        }
+        Ok(true)
+    }
+
+    /// Parses the database file format
+    pub fn from_text(text: &str) -> Result<Self, String> {
+        let mut db = FusekiDatabase::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let bad = |what: &str| format!("Line {}: {}", number + 1, what);
+            let mut fields = line.split_whitespace();
+            let size: usize = fields
+                .next()
+                .and_then(|f| f.parse().ok())
+                .filter(|s| (2..=25).contains(s))
+                .ok_or_else(|| bad("bad board size"))?;
+            let hash = fields.next().and_then(|f| u64::from_str_radix(f, 16).ok()).ok_or_else(|| bad("bad position hash"))?;
+            let color = match fields.next() {
+                Some("B") => Stone::Black,
+                Some("W") => Stone::White,
+                _ => return Err(bad("expected B or W")),
+            };
+            let moves = db.positions.entry((size, hash, color)).or_default();
+            for field in fields {
+                let (vertex, count) = field.split_once(':').ok_or_else(|| bad("expected VERTEX:COUNT"))?;
+                let point = parse_gtp_move(vertex, size).ok_or_else(|| bad("bad vertex"))?;
+                let count = count.parse().map_err(|_| bad("bad count"))?;
+                moves.push(FusekiMove { point, count });
+            }
+            sort_moves(moves);
+        }
+        Ok(db)
+    }
+
+    /// The database file format, positions in a fixed order
+    pub fn to_text(&self) -> String {
+        let mut keys: Vec<&(usize, u64, Stone)> = self.positions.keys().collect();
+        keys.sort_by_key(|&&(size, hash, color)| (size, hash, color == Stone::White));
+        let mut text = String::new();
+        for &(size, hash, color) in keys {
+            let _ = write!(text, "{} {:016x} {}", size, hash, if color == Stone::Black { "B" } else { "W" });
+            for m in &self.positions[&(size, hash, color)] {
+                let _ = write!(text, " {}:{}", format_move(m.point.0, m.point.1, size), m.count);
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fileio::read_to_string(path).map_err(|e| format!("Cannot read fuseki database '{}': {}", path.display(), e))?;
+        Self::from_text(&text)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        fileio::write(path, self.to_text().as_bytes())
+            .map_err(|e| format!("Cannot write fuseki database '{}': {}", path.display(), e))
+    }
+}
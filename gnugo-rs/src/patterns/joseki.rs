@@ -0,0 +1,212 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Joseki lookup: names the sequence played in a corner and lists the
+//! standard continuations
+//!
+//! Sequences are written in corner coordinates: "36" is the point three
+//! lines from one edge and six from the other, so "44" is the star point.
+//! Colors alternate from whoever played first in the corner, and a
+//! sequence matches its mirror image along the corner's diagonal. The
+//! database holds each sequence with a short evaluation of its last move;
+//! the name table gives the name of the longest named prefix.
+
+use crate::engine::board::{Board, Stone};
+
+/// Joseki database: sequence and evaluation of its last move
+const LINES: &[(&str, &str)] = &[
+    ("44", "Flexible; aims at speed and influence"),
+    ("33", "Secures the corner at once, but low and slow"),
+    ("34", "Balanced between territory and development"),
+    ("35", "Aims at the side and leaves the 3-3 point open"),
+    ("45", "Aims at influence and leaves the 3-4 point open"),
+    ("55", "Takes influence; the corner stays open"),
+    ("44 36", "The most common approach"),
+    ("44 46", "Faster and higher, toward the center"),
+    ("44 33", "White lives in the corner, Black gets thickness outside"),
+    ("44 36 63", "Solid: keeps the corner and looks at the other side"),
+    ("44 36 38", "Severe: fights for the side"),
+    ("44 36 39", "Looser than the one-space pincer, still prevents the extension"),
+    ("44 36 63 39", "White settles on the side; even"),
+    ("44 33 34", "Blocks from the side Black values more"),
+    ("44 33 34 43", "White crawls toward the other side"),
+    ("44 33 34 43 53", "Hane: keeps White low; even"),
+    ("33 44", "Presses the 3-3 stone low"),
+    ("34 53", "The most common approach"),
+    ("34 54", "Aims at influence rather than the corner"),
+];
+
+/// Name table, looked up by the longest prefix of a sequence
+const NAMES: &[(&str, &str)] = &[
+    ("44", "Star point (hoshi)"),
+    ("44 36", "Star point, low approach"),
+    ("44 46", "Star point, high approach"),
+    ("44 33", "Star point, 3-3 invasion"),
+    ("44 36 63", "Star point, low approach, knight's move response"),
+    ("44 36 38", "Star point, low approach, one-space low pincer"),
+    ("44 36 39", "Star point, low approach, two-space low pincer"),
+    ("44 36 63 39", "Star point, low approach, basic joseki"),
+    ("44 33 34", "Star point, 3-3 invasion, block"),
+    ("33", "3-3 point (san-san)"),
+    ("33 44", "3-3 point, shoulder hit"),
+    ("34", "3-4 point (komoku)"),
+    ("34 53", "3-4 point, small knight approach"),
+    ("34 54", "3-4 point, one-space high approach"),
+    ("35", "3-5 point (mokuhazushi)"),
+    ("45", "4-5 point (takamoku)"),
+    ("55", "5-5 point (gonogo)"),
+];
+
+/// A corner of the board
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Corner {
+    UpperLeft,
+    UpperRight,
+    LowerLeft,
+    LowerRight,
+}
+
+impl Corner {
+    /// Parses "ul", "upper_right", "lr" and the like
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().replace(['_', '-'], "").as_str() {
+            "ul" | "upperleft" | "tl" | "topleft" => Some(Corner::UpperLeft),
+            "ur" | "upperright" | "tr" | "topright" => Some(Corner::UpperRight),
+            "ll" | "lowerleft" | "bl" | "bottomleft" => Some(Corner::LowerLeft),
+            "lr" | "lowerright" | "br" | "bottomright" => Some(Corner::LowerRight),
+            _ => None,
+        }
+    }
+
+    /// Corner closest to (x, y)
+    pub fn nearest(x: usize, y: usize, size: usize) -> Self {
+        match (2 * x > size + 1, 2 * y > size + 1) {
+            (false, false) => Corner::UpperLeft,
+            (true, false) => Corner::UpperRight,
+            (false, true) => Corner::LowerLeft,
+            (true, true) => Corner::LowerRight,
+        }
+    }
+
+    /// Board point of corner coordinates (a, b)
+    fn to_board(self, (a, b): (usize, usize), size: usize) -> (usize, usize) {
+        match self {
+            Corner::UpperLeft => (a, b),
+            Corner::UpperRight => (size + 1 - a, b),
+            Corner::LowerLeft => (a, size + 1 - b),
+            Corner::LowerRight => (size + 1 - a, size + 1 - b),
+        }
+    }
+}
+
+/// A standard next move
+#[derive(Debug, Clone, PartialEq)]
+pub struct Continuation {
+    pub point: (usize, usize),
+    pub color: Stone,
+    pub name: &'static str,
+    pub comment: &'static str,
+}
+
+/// What the database knows about one corner
+#[derive(Debug, Clone, PartialEq)]
+pub struct JosekiMatch {
+    /// Name of the sequence on the board; `None` for an empty corner
+    pub name: Option<&'static str>,
+    /// Stones of the sequence
+    pub moves: usize,
+    pub continuations: Vec<Continuation>,
+}
+
+fn parse_line(line: &str) -> Vec<(usize, usize)> {
+    line.split_whitespace()
+        .filter_map(|p| {
+            let mut digits = p.chars().filter_map(|c| c.to_digit(10));
+            Some((digits.next()? as usize, digits.next()? as usize))
+        })
+        .collect()
+}
+
+/// Name of the longest named prefix of `line`
+fn name_of(line: &str) -> Option<&'static str> {
+    NAMES
+        .iter()
+        .filter(|(prefix, _)| *prefix == line || line.starts_with(&format!("{} ", prefix)))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|&(_, name)| name)
+}
+
+/// Identifies the sequence in `corner`, or `None` if the stones there are
+/// not a known joseki position. An empty corner lists the opening moves
+/// for `to_play`.
+pub fn identify(board: &Board, corner: Corner, to_play: Stone) -> Option<JosekiMatch> {
+    let size = board.size();
+    let region = size / 2;
+    let mut stones = Vec::new();
+    for b in 1..=region {
+        for a in 1..=region {
+            let (x, y) = corner.to_board((a, b), size);
+            match board.get_stone(x, y) {
+                Stone::Empty => {}
+                stone => stones.push(((a, b), stone)),
+            }
+        }
+    }
+
+    // (line, mirrored, first color) of the position on the board
+    let (line, mirrored, first) = if stones.is_empty() {
+        ("", false, to_play)
+    } else {
+        let mut found = None;
+        'lines: for &(line, _) in LINES {
+            let moves = parse_line(line);
+            if moves.len() != stones.len() {
+                continue;
+            }
+            for mirrored in [false, true] {
+                for first in [Stone::Black, Stone::White] {
+                    let matches = moves.iter().enumerate().all(|(i, &(a, b))| {
+                        let point = if mirrored { (b, a) } else { (a, b) };
+                        let color = if i.is_multiple_of(2) { first } else { opponent(first) };
+                        stones.contains(&(point, color))
+                    });
+                    if matches {
+                        found = Some((line, mirrored, first));
+                        break 'lines;
+                    }
+                }
+            }
+        }
+        found?
+    };
+
+    let played = parse_line(line).len();
+    let next_color = if played.is_multiple_of(2) { first } else { opponent(first) };
+    let continuations = LINES
+        .iter()
+        .filter(|(next, _)| {
+            let moves = parse_line(next);
+            moves.len() == played + 1 && (line.is_empty() || next.starts_with(&format!("{} ", line)))
+        })
+        .filter_map(|&(next, comment)| {
+            let (a, b) = *parse_line(next).last()?;
+            let (a, b) = if mirrored { (b, a) } else { (a, b) };
+            (a <= region && b <= region).then(|| Continuation {
+                point: corner.to_board((a, b), size),
+                color: next_color,
+                name: name_of(next).unwrap_or(""),
+                comment,
+            })
+        })
+        .collect();
+
+    Some(JosekiMatch {
+        name: name_of(line),
+        moves: played,
+        continuations,
+    })
+}
+
+fn opponent(color: Stone) -> Stone {
+    if color == Stone::Black { Stone::White } else { Stone::Black }
+}
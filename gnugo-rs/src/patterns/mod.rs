@@ -9,13 +9,23 @@ pub mod pattern_transform;
 pub mod pattern_helpers;
 pub mod pattern_matcher_impl;
 pub mod pattern_loader;
+pub mod pattern_parser;
+pub mod pattern_tuning;
+pub mod joseki;
+pub mod fuseki;
+pub mod owl;
+pub mod eyes;
 
-pub use pattern_database::PatternDatabase;
+use crate::engine::board::Stone;
+
+pub use pattern_database::{LoadReport, PatternDatabase, PatternGrid};
 pub use pattern_matching::PatternMatcher;
 pub use pattern_transform::Transformation;
 pub use pattern_helpers::{PatternConstraint, move_allowed, on_board_after_transform};
 pub use pattern_matcher_impl::find_patterns_at;
 pub use pattern_loader::{load_database, load_all_databases, database_exists};
+pub use pattern_parser::{parse_patterns, PatternElement, TextPattern};
+pub use pattern_tuning::{write_values, PatternCount, PatternTuner};
 
 /// Represents a pattern value
 #[derive(Debug, Clone, Copy)]
@@ -35,7 +45,10 @@ impl PatVal {
 pub struct PatternMatchResult {
     pub pattern_id: u32,
     pub value: i32,
+    /// Point the pattern's anchor is on
     pub position: (usize, usize),
+    /// The player the pattern is for, its O stones
+    pub color: Stone,
     pub transform: Transformation,
 }
 
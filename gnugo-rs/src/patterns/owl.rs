@@ -0,0 +1,94 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Owl patterns: shapes that point the life-and-death reader at the vital
+//! moves of an eye space
+//!
+//! Each pattern is a 3x3 diagram around the move `*`, rows top first,
+//! drawn for the side whose dragon is read: `O` is the dragon's color, `X`
+//! the attacker, `.` empty, `o` O or empty, `x` X or empty, `#` off the
+//! board and `?` anything. A pattern matches in all eight orientations.
+
+use crate::engine::board::{Board, Stone};
+
+/// A shape and how urgent its move is
+pub struct OwlPattern {
+    pub name: &'static str,
+    diagram: [&'static str; 3],
+    pub value: i32,
+}
+
+/// Moves for the attacker, which wants to kill the `O` dragon
+const ATTACK: &[OwlPattern] = &[
+    OwlPattern { name: "placement between two stones", diagram: ["?O?", ".*.", "?O?"], value: 60 },
+    OwlPattern { name: "first-line placement", diagram: ["###", "O*O", "?.?"], value: 55 },
+    OwlPattern { name: "first-line hane", diagram: ["###", "X*.", "?O?"], value: 50 },
+    OwlPattern { name: "throw-in", diagram: ["?O?", "O*O", "?.?"], value: 45 },
+    OwlPattern { name: "eye-stealing diagonal", diagram: ["O.?", ".*O", "?O?"], value: 40 },
+    OwlPattern { name: "block", diagram: ["?X?", "o*o", "?O?"], value: 30 },
+];
+
+/// Moves for the defender, which wants two eyes for the `O` dragon
+const DEFENSE: &[OwlPattern] = &[
+    OwlPattern { name: "vital point", diagram: ["?O?", ".*.", "?O?"], value: 60 },
+    OwlPattern { name: "first-line block", diagram: ["###", "X*.", "?O?"], value: 50 },
+    OwlPattern { name: "eye-making turn", diagram: ["?O?", "O*.", "?.?"], value: 40 },
+    OwlPattern { name: "first-line descent", diagram: ["###", "o*o", "?O?"], value: 35 },
+    OwlPattern { name: "widen", diagram: ["?x?", "O*.", "?.?"], value: 20 },
+];
+
+/// Offset (dx, dy) in orientation `t` of the eight
+fn orient((dx, dy): (i32, i32), t: usize) -> (i32, i32) {
+    let (a, b) = match t % 4 {
+        0 => (dx, dy),
+        1 => (-dy, dx),
+        2 => (-dx, -dy),
+        _ => (dy, -dx),
+    };
+    if t >= 4 { (-a, b) } else { (a, b) }
+}
+
+fn matches(pattern: &OwlPattern, board: &Board, (x, y): (usize, usize), dragon: Stone, t: usize) -> bool {
+    let size = board.size() as i32;
+    pattern.diagram.iter().enumerate().all(|(row, line)| {
+        line.chars().enumerate().all(|(col, symbol)| {
+            let (dx, dy) = orient((col as i32 - 1, row as i32 - 1), t);
+            let (px, py) = (x as i32 + dx, y as i32 + dy);
+            let on_board = (1..=size).contains(&px) && (1..=size).contains(&py);
+            let stone = if on_board { board.get_stone(px as usize, py as usize) } else { Stone::Empty };
+            let own = stone == dragon;
+            let enemy = stone != Stone::Empty && !own;
+            match symbol {
+                '?' => true,
+                '#' => !on_board,
+                '*' | '.' => on_board && stone == Stone::Empty,
+                'O' => on_board && own,
+                'X' => on_board && enemy,
+                'o' => on_board && !enemy,
+                'x' => on_board && !own,
+                _ => false,
+            }
+        })
+    })
+}
+
+/// The most urgent pattern in `patterns` with its move at `point`
+fn best_match(patterns: &'static [OwlPattern], board: &Board, point: (usize, usize), dragon: Stone) -> Option<&'static OwlPattern> {
+    if board.get_stone(point.0, point.1) != Stone::Empty {
+        return None;
+    }
+    patterns
+        .iter()
+        .filter(|pattern| (0..8).any(|t| matches(pattern, board, point, dragon, t)))
+        .max_by_key(|pattern| pattern.value)
+}
+
+/// Attack pattern at `point` against the `dragon` color, if any
+pub fn attack_pattern(board: &Board, point: (usize, usize), dragon: Stone) -> Option<&'static OwlPattern> {
+    best_match(ATTACK, board, point, dragon)
+}
+
+/// Defense pattern at `point` for the `dragon` color, if any
+pub fn defense_pattern(board: &Board, point: (usize, usize), dragon: Stone) -> Option<&'static OwlPattern> {
+    best_match(DEFENSE, board, point, dragon)
+}
@@ -6,11 +6,129 @@
 use super::PatVal;
 use std::collections::HashMap;
 use std::io;
+use std::path::Path;
+use crate::engine::board::{Board, Stone};
 use crate::patterns::pattern_loader::load_database;
+use crate::patterns::pattern_parser::{Edges, PatternElement, TextPattern};
+use crate::patterns::pattern_transform::Transformation;
+
+/// The diagram of a pattern, as matched against the board
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatternGrid {
+    pub width: usize,
+    pub height: usize,
+    /// Row by row from the top
+    pub elements: Vec<PatternElement>,
+    /// Row and column put on the point matched
+    pub anchor: (usize, usize),
+    pub edges: Edges,
+    /// The transformations that place the pattern differently, one of
+    /// each set its symmetry makes equal
+    transformations: Vec<Transformation>,
+}
+
+impl PatternGrid {
+    /// A grid of `width` by `height` elements, row by row from the top
+    pub fn new(width: usize, height: usize, elements: Vec<PatternElement>, anchor: (usize, usize), edges: Edges) -> Self {
+        let mut grid = PatternGrid { width, height, elements, anchor, edges, transformations: Vec::new() };
+        let mut placements = Vec::new();
+        for transformation in Transformation::all() {
+            let mut placement = grid.placement(transformation);
+            placement.sort_unstable_by_key(|&(offset, _)| offset);
+            if !placements.contains(&placement) {
+                placements.push(placement);
+                grid.transformations.push(transformation);
+            }
+        }
+        grid
+    }
+
+    /// The grid of a parsed text pattern. Without a move or marked point
+    /// the first O stone is the anchor, as in GNU Go
+    pub fn from_text(pattern: &TextPattern) -> Self {
+        let (height, width) = pattern.dimensions();
+        let elements: Vec<PatternElement> = pattern.grid.iter().flatten().copied().collect();
+        let first_own = elements.iter().position(|&e| e == PatternElement::Own).map(|i| (i / width, i % width));
+        let anchor = pattern.anchor.or(first_own).unwrap_or((0, 0));
+        PatternGrid::new(width, height, elements, anchor, pattern.edges)
+    }
+
+    /// Offsets from the anchor of each element under `transformation`,
+    /// with `None` for the points off the edges the pattern is drawn
+    /// against
+    fn placement(&self, transformation: Transformation) -> Vec<((isize, isize), Option<PatternElement>)> {
+        let (ar, ac) = (self.anchor.0 as isize, self.anchor.1 as isize);
+        let (height, width) = (self.height as isize, self.width as isize);
+        let mut points = Vec::new();
+        for row in 0..height {
+            for col in 0..width {
+                let element = self.elements[(row * width + col) as usize];
+                points.push(((col - ac, row - ar), Some(element)));
+            }
+            if self.edges.left {
+                points.push(((-1 - ac, row - ar), None));
+            }
+            if self.edges.right {
+                points.push(((width - ac, row - ar), None));
+            }
+        }
+        for col in 0..width {
+            if self.edges.top {
+                points.push(((col - ac, -1 - ar), None));
+            }
+            if self.edges.bottom {
+                points.push(((col - ac, height - ar), None));
+            }
+        }
+        points.into_iter().map(|((dx, dy), element)| (transformation.apply_offset(dx, dy), element)).collect()
+    }
+
+    /// Whether the pattern matches with its anchor on (x, y) under
+    /// `transformation`, O standing for `color`: every element on the
+    /// board and fitting the stone there, and the edges where drawn
+    pub fn matches(&self, board: &Board, (x, y): (usize, usize), color: Stone, transformation: Transformation) -> bool {
+        let size = board.size() as isize;
+        let opponent = if color == Stone::Black { Stone::White } else { Stone::Black };
+        self.placement(transformation).into_iter().all(|((dx, dy), element)| {
+            let (px, py) = (x as isize + dx, y as isize + dy);
+            let on_board = (1..=size).contains(&px) && (1..=size).contains(&py);
+            let Some(element) = element else { return !on_board };
+            if !on_board {
+                return false;
+            }
+            let stone = board.get_stone(px as usize, py as usize);
+            match element {
+                PatternElement::Empty | PatternElement::Move => stone == Stone::Empty,
+                PatternElement::Own => stone == color,
+                PatternElement::Opponent => stone == opponent,
+                PatternElement::OwnOrEmpty => stone != opponent,
+                PatternElement::OpponentOrEmpty => stone != color,
+                PatternElement::Any => true,
+            }
+        })
+    }
+
+    /// For each color the pattern is for when it matches with its anchor
+    /// on (x, y), the first transformation under which it does
+    pub fn match_at(&self, board: &Board, point: (usize, usize)) -> Vec<(Stone, Transformation)> {
+        [Stone::Black, Stone::White]
+            .into_iter()
+            .filter_map(|color| self.match_for(board, point, color).map(|t| (color, t)))
+            .collect()
+    }
+
+    /// The first transformation under which the grid matches for `color`
+    /// with its anchor on `point`
+    pub fn match_for(&self, board: &Board, point: (usize, usize), color: Stone) -> Option<Transformation> {
+        self.transformations.iter().copied().find(|&t| self.matches(board, point, color, t))
+    }
+}
 
 /// Pattern database structure
 pub struct PatternDatabase {
     patterns: HashMap<u32, Vec<PatVal>>,
+    /// Diagrams by pattern id; a pattern without one never matches
+    grids: HashMap<u32, PatternGrid>,
     pattern_type: super::PatternType,
     name: String,
 }
@@ -20,6 +138,7 @@ impl PatternDatabase {
     pub fn new(name: &str, pattern_type: super::PatternType) -> Self {
         PatternDatabase {
             patterns: HashMap::new(),
+            grids: HashMap::new(),
             pattern_type,
             name: name.to_string(),
         }
@@ -35,6 +154,22 @@ impl PatternDatabase {
         self.patterns.insert(pattern_id, values);
     }
     
+    /// Sets the diagram the pattern with `pattern_id` matches
+    pub fn add_grid(&mut self, pattern_id: u32, grid: PatternGrid) {
+        self.grids.insert(pattern_id, grid);
+    }
+
+    /// Adds a parsed text pattern with its value and diagram
+    pub fn add_text_pattern(&mut self, pattern_id: u32, pattern: &TextPattern) {
+        self.add_pattern(pattern_id, vec![PatVal::new(pattern_id, pattern.value.round() as i32)]);
+        self.add_grid(pattern_id, PatternGrid::from_text(pattern));
+    }
+
+    /// Gets the diagram of a pattern
+    pub fn get_grid(&self, pattern_id: u32) -> Option<&PatternGrid> {
+        self.grids.get(&pattern_id)
+    }
+
     /// Saves patterns to a database file
     pub fn save_to_file(&self, _path: &str) -> Result<(), String> {
         // In a real implementation, this would write to a .db file
@@ -68,6 +203,41 @@ impl PatternDatabase {
     }
 }
 
+/// Outcome of loading one database in [`PatternDatabases::reload_from`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DatabaseLoad {
+    pub name: &'static str,
+    /// Patterns loaded, or why the file could not be read
+    pub result: Result<usize, String>,
+    /// Patterns in use afterwards; the previous database stays on error
+    pub in_use: usize,
+}
+
+/// What a reload did, one entry per database
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LoadReport {
+    pub databases: Vec<DatabaseLoad>,
+}
+
+impl LoadReport {
+    /// Databases that failed to load
+    pub fn errors(&self) -> usize {
+        self.databases.iter().filter(|d| d.result.is_err()).count()
+    }
+
+    /// One line per database: `name count`, or `name error: reason (kept N)`
+    pub fn to_text(&self) -> String {
+        self.databases
+            .iter()
+            .map(|d| match &d.result {
+                Ok(count) => format!("{} {}", d.name, count),
+                Err(e) => format!("{} error: {} (kept {})", d.name, e, d.in_use),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
 /// Predefined pattern databases
 pub struct PatternDatabases {
     attack_db: PatternDatabase,
@@ -77,6 +247,12 @@ pub struct PatternDatabases {
     endgame_db: PatternDatabase,
 }
 
+impl Default for PatternDatabases {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl PatternDatabases {
     /// Creates a new set of pattern databases
     pub fn new() -> Self {
@@ -91,14 +267,45 @@ impl PatternDatabases {
     
     /// Loads all pattern databases
     pub fn load_all(&mut self) -> io::Result<()> {
-        self.attack_db = PatternDatabase::load_from_file("patterns/attack.db", super::PatternType::Attack)?;
-        self.defense_db = PatternDatabase::load_from_file("patterns/defense.db", super::PatternType::Defense)?;
-        self.fuseki_db = PatternDatabase::load_from_file("patterns/fuseki.db", super::PatternType::Fuseki)?;
-        self.joseki_db = PatternDatabase::load_from_file("patterns/joseki.db", super::PatternType::Joseki)?;
-        self.endgame_db = PatternDatabase::load_from_file("patterns/endgame.db", super::PatternType::Endgame)?;
+        self.load_all_from(Path::new("patterns"))
+    }
+    
+    /// Loads all pattern databases from `dir` (the configured pattern directory)
+    pub fn load_all_from(&mut self, dir: &Path) -> io::Result<()> {
+        let path = |file: &str| dir.join(file).to_string_lossy().into_owned();
+        self.attack_db = PatternDatabase::load_from_file(&path("attack.db"), super::PatternType::Attack)?;
+        self.defense_db = PatternDatabase::load_from_file(&path("defense.db"), super::PatternType::Defense)?;
+        self.fuseki_db = PatternDatabase::load_from_file(&path("fuseki.db"), super::PatternType::Fuseki)?;
+        self.joseki_db = PatternDatabase::load_from_file(&path("joseki.db"), super::PatternType::Joseki)?;
+        self.endgame_db = PatternDatabase::load_from_file(&path("endgame.db"), super::PatternType::Endgame)?;
         Ok(())
     }
     
+    /// Loads each database from `dir` on its own, keeping the current one
+    /// when its file cannot be read, and reports counts and errors
+    pub fn reload_from(&mut self, dir: &Path) -> LoadReport {
+        let mut report = LoadReport::default();
+        let databases = [
+            ("attack", super::PatternType::Attack, &mut self.attack_db),
+            ("defense", super::PatternType::Defense, &mut self.defense_db),
+            ("fuseki", super::PatternType::Fuseki, &mut self.fuseki_db),
+            ("joseki", super::PatternType::Joseki, &mut self.joseki_db),
+            ("endgame", super::PatternType::Endgame, &mut self.endgame_db),
+        ];
+        for (name, pattern_type, db) in databases {
+            let path = dir.join(format!("{}.db", name)).to_string_lossy().into_owned();
+            let result = match PatternDatabase::load_from_file(&path, pattern_type) {
+                Ok(loaded) => {
+                    *db = loaded;
+                    Ok(db.get_pattern_count())
+                }
+                Err(e) => Err(format!("{}: {}", path, e)),
+            };
+            report.databases.push(DatabaseLoad { name, result, in_use: db.get_pattern_count() });
+        }
+        report
+    }
+
     /// Gets the attack pattern database
     pub fn get_attack_db(&self) -> &PatternDatabase {
         &self.attack_db
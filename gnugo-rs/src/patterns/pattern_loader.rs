@@ -3,25 +3,34 @@
 
 //! Pattern database loader for GNU Go
 
-use std::fs::File;
-use std::io::{self, BufReader};
+use std::io::{self, Cursor};
 use std::path::Path;
 use byteorder::{LittleEndian, ReadBytesExt};
-use crate::patterns::{PatternDatabase, PatternType, PatVal};
+use crate::patterns::{parse_patterns, PatternDatabase, PatternType, PatVal};
+use crate::fileio;
 
-/// Loads a pattern database from a .db file
+/// Magic number of the binary format, "GUNG" in file order
+const MAGIC: u32 = 0x474E5547;
+
+/// Loads a pattern database from a .db file: the binary format, or GNU
+/// Go's text format when the file does not start with its magic number.
+/// Text patterns get ids in file order
 pub fn load_database(path: &str, pattern_type: PatternType) -> io::Result<PatternDatabase> {
     let mut db = PatternDatabase::new(path, pattern_type);
     
-    let file = File::open(path)?;
-    let mut reader = BufReader::new(file);
-    
-    // Read header
-    let magic = reader.read_u32::<LittleEndian>()?;
-    if magic != 0x474E5547 { // "GNU Go" magic number
-        return Err(io::Error::new(io::ErrorKind::InvalidData, "Invalid pattern database"));
+    let bytes = fileio::read(Path::new(path))?;
+    if !bytes.starts_with(&MAGIC.to_le_bytes()) {
+        let invalid = |e: String| io::Error::new(io::ErrorKind::InvalidData, e);
+        let text = String::from_utf8(bytes).map_err(|_| invalid("Invalid pattern database".to_string()))?;
+        for (id, pattern) in parse_patterns(&text).map_err(invalid)?.iter().enumerate() {
+            db.add_text_pattern(id as u32, pattern);
+        }
+        return Ok(db);
     }
+    let mut reader = Cursor::new(bytes);
     
+    // Read header
+    let _magic = reader.read_u32::<LittleEndian>()?;
     let _version = reader.read_u32::<LittleEndian>()?;
     let pattern_count = reader.read_u32::<LittleEndian>()?;
     
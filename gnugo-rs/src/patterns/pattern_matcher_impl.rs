@@ -4,6 +4,7 @@
 //! Implementation of core pattern matching algorithms
 
 use crate::engine::board::{Board, Stone};
+use crate::engine::profile::{self, Subsystem};
 use crate::patterns::{
     PatternDatabase, PatternMatchResult,
     pattern_transform::Transformation,
@@ -37,9 +38,10 @@ impl<'a> PatternMatcher<'a> {
     pub fn match_all_positions(&mut self) {
         let size = self.board.size();
         
-        for y in 0..size {
-            for x in 0..size {
-                if !self.constraints.check(self.board, x, y) {
+        for y in 1..=size {
+            for x in 1..=size {
+                // Constraints count edge distances from 0
+                if !self.constraints.check(self.board, x - 1, y - 1) {
                     continue;
                 }
                 
@@ -49,23 +51,14 @@ impl<'a> PatternMatcher<'a> {
     }
 
     fn match_at_position(&mut self, x: usize, y: usize) {
-        let stone = self.board.get_stone(x, y);
-        if stone == Stone::Empty {
-            return;
-        }
-
         for (pattern_id, pattern_values) in self.db.get_patterns().iter() {
-            if let Some(trans) = self.pattern_fits(x, y, *pattern_id) {
+            for (color, trans) in self.pattern_fits(x, y, *pattern_id) {
                 for val in pattern_values {
                     (self.callback)(PatternMatchResult {
                         pattern_id: *pattern_id,
                         value: val.value,
-                        position: if trans == Transformation::Identity {
-                            (x, y)
-                        } else {
-                            let (tx, ty) = trans.apply(x, y, self.board.size());
-                            (tx, ty)
-                        },
+                        position: (x, y),
+                        color,
                         transform: trans,
                     });
                 }
@@ -73,14 +66,12 @@ impl<'a> PatternMatcher<'a> {
         }
     }
 
-    fn pattern_fits(&self, _x: usize, _y: usize, _pattern_id: u32) -> Option<Transformation> {
-        // Get pattern info from database
-        // For now just return Identity for demonstration
-        Some(Transformation::Identity)
+    fn pattern_fits(&self, x: usize, y: usize, pattern_id: u32) -> Vec<(Stone, Transformation)> {
+        self.db.get_grid(pattern_id).map_or_else(Vec::new, |grid| grid.match_at(self.board, (x, y)))
     }
 }
 
-/// Finds all patterns matching at a specific position
+/// Finds all patterns with their anchor on (x, y)
 pub fn find_patterns_at(
     board: &Board,
     x: usize,
@@ -92,7 +83,7 @@ pub fn find_patterns_at(
     let constraints = PatternConstraint::new(0, board.size(), 1);
     
     let mut matcher = PatternMatcher::new(board, db, &mut callback, constraints);
-    matcher.match_at_position(x, y);
+    profile::measure(Subsystem::Patterns, || matcher.match_at_position(x, y));
     
     results
 }
\ No newline at end of file
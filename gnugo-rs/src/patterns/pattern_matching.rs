@@ -4,17 +4,29 @@
 //! Pattern matching algorithms
 
 use super::{PatternDatabase, PatternMatchResult, PatternType, PatVal};
-use crate::patterns::pattern_database::PatternDatabases;
+use crate::patterns::pattern_database::{LoadReport, PatternDatabases};
 use crate::engine::board::Board;
 use crate::engine::board::Stone;
+use crate::engine::profile::{self, Subsystem};
 use crate::patterns::pattern_transform::Transformation;
 use std::collections::HashMap;
 use std::io;
+use std::path::Path;
 
 /// Pattern matcher structure
 pub struct PatternMatcher {
     databases: PatternDatabases,
-    pattern_cache: HashMap<(u32, usize, usize), Vec<PatternMatchResult>>,
+    /// Matches by pattern type and the board's position hash, so a board
+    /// that changes is matched afresh
+    pattern_cache: HashMap<(u32, u64), Vec<PatternMatchResult>>,
+    /// Most positions kept in `pattern_cache`; it is emptied when full
+    cache_limit: usize,
+}
+
+impl Default for PatternMatcher {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl PatternMatcher {
@@ -23,16 +35,48 @@ impl PatternMatcher {
         PatternMatcher {
             databases: PatternDatabases::new(),
             pattern_cache: HashMap::new(),
+            cache_limit: usize::MAX,
+        }
+    }
+
+    /// Bounds the match cache to `entries` board positions
+    pub fn set_cache_limit(&mut self, entries: usize) {
+        self.cache_limit = entries;
+        if self.pattern_cache.len() > entries {
+            self.pattern_cache.clear();
         }
     }
     
-    /// Loads all pattern databases
+    /// Loads all pattern databases and drops cached matches
     pub fn load_databases(&mut self) -> io::Result<()> {
+        self.pattern_cache.clear();
         self.databases.load_all()
     }
     
+    /// Loads all pattern databases from a directory and drops cached
+    /// matches
+    pub fn load_databases_from(&mut self, dir: &Path) -> io::Result<()> {
+        self.pattern_cache.clear();
+        self.databases.load_all_from(dir)
+    }
+    
+    /// Reloads the databases from `dir` while running and drops cached
+    /// matches; a database that fails to load keeps its old patterns
+    pub fn reload_databases(&mut self, dir: &Path) -> LoadReport {
+        self.pattern_cache.clear();
+        self.databases.reload_from(dir)
+    }
+
     /// Finds all matching patterns on the board
     pub fn find_matching_patterns(&mut self, board: &Board, pattern_type: PatternType) -> Vec<PatternMatchResult> {
+        profile::measure(Subsystem::Patterns, || self.match_board(board, pattern_type))
+    }
+
+    fn match_board(&mut self, board: &Board, pattern_type: PatternType) -> Vec<PatternMatchResult> {
+        let key = (pattern_type as u32, board.position_hash());
+        if let Some(cached) = self.pattern_cache.get(&key) {
+            return cached.clone();
+        }
         let mut results = Vec::new();
         
         // Get the appropriate database
@@ -46,51 +90,37 @@ impl PatternMatcher {
         
         // For each position on the board
         let size = board.size();
-        for row in 0..size {
-            for col in 0..size {
-                // Check if this position has been cached
-                if let Some(cached) = self.pattern_cache.get(&(pattern_type as u32, row, col)) {
-                    results.extend(cached.iter().cloned());
-                    continue;
-                }
-                
+        for y in 1..=size {
+            for x in 1..=size {
                 // Search for patterns at this position
-                let matches = self.search_patterns_at_position(board, row, col, db);
-                
-                // Cache the results
-                self.pattern_cache.insert((pattern_type as u32, row, col), matches.clone());
-                
-                // Add to results
-                results.extend(matches);
+                results.extend(self.search_patterns_at_position(board, x, y, db));
             }
         }
         
+        // Cache the results
+        if self.pattern_cache.len() >= self.cache_limit {
+            self.pattern_cache.clear();
+        }
+        self.pattern_cache.insert(key, results.clone());
         results
     }
     
-    /// Searches for patterns at a specific position
-    fn search_patterns_at_position(&self, board: &Board, row: usize, col: usize, db: &PatternDatabase) -> Vec<PatternMatchResult> {
+    /// Searches for patterns with their anchor on (x, y)
+    fn search_patterns_at_position(&self, board: &Board, x: usize, y: usize, db: &PatternDatabase) -> Vec<PatternMatchResult> {
         let mut results = Vec::new();
         
-        // Get the stone at this position
-        let stone = board.get_stone(row, col);
-        
-        // If the position is empty, skip
-        if stone == Stone::Empty {
-            return results;
-        }
-        
         // For each pattern in the database
         for (pattern_id, pattern_values) in db.get_patterns().iter() {
             // Check if the pattern matches at this position
-            if self.pattern_matches(board, row, col, *pattern_id) {
+            for (color, transform) in self.pattern_matches(board, x, y, db, *pattern_id) {
                 // Add all pattern values to results
                 for val in pattern_values {
                     results.push(PatternMatchResult {
                         pattern_id: *pattern_id,
                         value: val.value,
-                        position: (row, col),
-                        transform: Transformation::Identity,
+                        position: (x, y),
+                        color,
+                        transform,
                     });
                 }
             }
@@ -99,14 +129,11 @@ impl PatternMatcher {
         results
     }
     
-    /// Checks if a pattern matches at a specific position
-    fn pattern_matches(&self, _board: &Board, _row: usize, _col: usize, _pattern_id: u32) -> bool {
-        // This is a simplified implementation
-        // In a real implementation, this would check the pattern against the board
-        
-        // For now, we'll just return true for demonstration purposes
-        // In a real implementation, this would be replaced with actual pattern matching logic
-        true
+    /// The colors a pattern matches for with its anchor on (x, y), and
+    /// how, comparing its grid with the board under each transformation;
+    /// a pattern without a grid never matches
+    fn pattern_matches(&self, board: &Board, x: usize, y: usize, db: &PatternDatabase, pattern_id: u32) -> Vec<(Stone, Transformation)> {
+        db.get_grid(pattern_id).map_or_else(Vec::new, |grid| grid.match_at(board, (x, y)))
     }
     
     /// Evaluates the board using pattern matching
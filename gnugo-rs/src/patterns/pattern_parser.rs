@@ -0,0 +1,311 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Parser for GNU Go's text pattern databases, the `.db` files mkpat
+//! compiles
+//!
+//! A pattern starts with `Pattern NAME`, then a diagram drawn with
+//! `. X O x o , ! * ? Q Y` and board edges of `+ - |`, then the `:` line
+//! with the symmetry, the class letters and attributes such as
+//! `value(50)`. An optional constraint diagram labels points with
+//! letters, `;` lines hold the constraint and `>` lines the action.
+//! Text after a diagram row, past a space, is a comment.
+
+/// Characters that may appear in a pattern diagram
+const DIAGRAM_CHARS: &str = ".XOxo,!*?QY";
+
+/// Board edge characters of a diagram
+const EDGE_CHARS: &str = "+-|";
+
+/// Letters that label points in a constraint diagram
+const LABEL_CHARS: &str = "abcdefghijklmnpqrstuvwyzABCDEFGHIJKLMNPRSTUVWZ";
+
+/// Symmetries of the `:` line
+const SYMMETRY_CHARS: &str = "8|\\/X-+O";
+
+/// Lines that set how mkpat compiles the patterns after them
+const DIRECTIVES: [&str; 3] = ["attribute_map", "goal_elements", "callback_data"];
+
+/// One point of a pattern diagram, O being the player the pattern is for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatternElement {
+    /// `.`, also `,` and `!`, which only GNU Go's connection and
+    /// influence code tells apart
+    Empty,
+    /// `O`, also `Q`
+    Own,
+    /// `X`, also `Y`
+    Opponent,
+    /// `o`: own stone or empty
+    OwnOrEmpty,
+    /// `x`: opponent stone or empty
+    OpponentOrEmpty,
+    /// `?`: anything
+    Any,
+    /// `*`: empty, the suggested move
+    Move,
+}
+
+impl PatternElement {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '.' | ',' | '!' => Some(PatternElement::Empty),
+            'O' | 'Q' => Some(PatternElement::Own),
+            'X' | 'Y' => Some(PatternElement::Opponent),
+            'o' => Some(PatternElement::OwnOrEmpty),
+            'x' => Some(PatternElement::OpponentOrEmpty),
+            '?' => Some(PatternElement::Any),
+            '*' => Some(PatternElement::Move),
+            _ => None,
+        }
+    }
+}
+
+/// Board edges a diagram is drawn against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Edges {
+    pub top: bool,
+    pub bottom: bool,
+    pub left: bool,
+    pub right: bool,
+}
+
+/// A pattern of a text database
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextPattern {
+    pub name: String,
+    /// Rows from the top, without the edge marks
+    pub grid: Vec<Vec<PatternElement>>,
+    /// Row and column of the move, `*`, or else of the point marked `Q`
+    /// or `Y`
+    pub anchor: Option<(usize, usize)>,
+    pub edges: Edges,
+    /// Symmetry character, e.g. `8` for all eight or `|` for a mirror
+    pub symmetry: char,
+    /// Class letters, e.g. `OX`; `-` for none
+    pub class: String,
+    /// Argument of `value(...)`; 0 without one
+    pub value: f32,
+    /// The other attributes in order, with their argument if any, e.g.
+    /// `("shape", Some("1"))` or a helper name on its own
+    pub attributes: Vec<(String, Option<String>)>,
+    /// Points labelled in the constraint diagram, by row and column
+    pub labels: Vec<(char, (usize, usize))>,
+    /// The `;` lines, joined by spaces
+    pub constraint: Option<String>,
+    /// The `>` lines, joined by spaces
+    pub action: Option<String>,
+}
+
+impl TextPattern {
+    /// Rows and columns of the diagram
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.grid.len(), self.grid.first().map_or(0, |row| row.len()))
+    }
+}
+
+/// A pattern being read, with its diagram rows as written
+struct Reading {
+    pattern: TextPattern,
+    /// Line number of the `Pattern` line
+    line: usize,
+    /// Whether the `:` line has been read
+    entry: bool,
+    constraint_rows: Vec<String>,
+}
+
+/// A diagram row: `None` for an edge line of `+` and `-`, else the
+/// points between the `|` marks and whether there were marks on the
+/// left and right
+fn diagram_row(token: &str) -> Option<(&str, bool, bool)> {
+    if token.starts_with(['+', '-']) {
+        return None;
+    }
+    let left = token.starts_with('|');
+    let token = token.strip_prefix('|').unwrap_or(token);
+    let right = token.ends_with('|');
+    Some((token.strip_suffix('|').unwrap_or(token), left, right))
+}
+
+/// Splits the `:` line into its symmetry, class and attributes
+fn parse_entry(line: &str, pattern: &mut TextPattern) -> Result<(), String> {
+    let mut fields = line.split(',').map(str::trim);
+    let symmetry = fields.next().unwrap_or("");
+    let mut chars = symmetry.chars();
+    pattern.symmetry = match (chars.next(), chars.next()) {
+        (Some(c), None) if SYMMETRY_CHARS.contains(c) => c,
+        _ => return Err(format!("bad symmetry '{}'", symmetry)),
+    };
+    pattern.class = fields.next().ok_or("missing class")?.to_string();
+    for field in fields.filter(|f| !f.is_empty()) {
+        let (name, argument) = match field.split_once('(') {
+            Some((name, rest)) => {
+                let argument = rest.strip_suffix(')').ok_or_else(|| format!("missing ')' in '{}'", field))?;
+                (name.trim(), Some(argument.trim().to_string()))
+            }
+            None => (field, None),
+        };
+        if name == "value" {
+            let argument = argument.unwrap_or_default();
+            pattern.value = argument.parse().map_err(|_| format!("bad value '{}'", argument))?;
+        } else {
+            pattern.attributes.push((name.to_string(), argument));
+        }
+    }
+    Ok(())
+}
+
+/// Checks a finished pattern and reads the labels of its constraint
+/// diagram
+fn finish(reading: Reading) -> Result<TextPattern, String> {
+    let Reading { mut pattern, line, entry, constraint_rows } = reading;
+    let bad = |what: &str| format!("Line {}: pattern {} {}", line, pattern.name, what);
+    if pattern.grid.is_empty() {
+        return Err(bad("has no diagram"));
+    }
+    if !entry {
+        return Err(bad("has no ':' line"));
+    }
+    let rows: Vec<&str> = constraint_rows.iter().filter_map(|row| diagram_row(row)).map(|(points, _, _)| points).collect();
+    if !rows.is_empty() {
+        let (height, width) = pattern.dimensions();
+        if rows.len() != height || rows.iter().any(|row| row.chars().count() != width) {
+            return Err(bad("has a constraint diagram of another size"));
+        }
+        for (r, row) in rows.iter().enumerate() {
+            for (c, label) in row.chars().enumerate() {
+                if LABEL_CHARS.contains(label) {
+                    pattern.labels.push((label, (r, c)));
+                } else if !DIAGRAM_CHARS.contains(label) {
+                    return Err(bad(&format!("has '{}' in its constraint diagram", label)));
+                }
+            }
+        }
+    }
+    Ok(pattern)
+}
+
+/// Parses a text pattern database, in file order
+pub fn parse_patterns(text: &str) -> Result<Vec<TextPattern>, String> {
+    let mut patterns = Vec::new();
+    let mut reading: Option<Reading> = None;
+    // Whether a blank or comment line has ended the diagram being read
+    let mut diagram_done = false;
+    for (number, line) in text.lines().enumerate() {
+        let bad = |what: &str| format!("Line {}: {}", number + 1, what);
+        let line = line.trim_end();
+        let first = line.split_whitespace().next().unwrap_or("");
+        if first == "Pattern" {
+            if let Some(done) = reading.take() {
+                patterns.push(finish(done)?);
+            }
+            let name = line.split_whitespace().nth(1).ok_or_else(|| bad("pattern without a name"))?;
+            let pattern = TextPattern {
+                name: name.to_string(),
+                grid: Vec::new(),
+                anchor: None,
+                edges: Edges::default(),
+                symmetry: '8',
+                class: String::new(),
+                value: 0.0,
+                attributes: Vec::new(),
+                labels: Vec::new(),
+                constraint: None,
+                action: None,
+            };
+            reading = Some(Reading { pattern, line: number + 1, entry: false, constraint_rows: Vec::new() });
+            diagram_done = false;
+            continue;
+        }
+        if DIRECTIVES.contains(&first) {
+            if let Some(done) = reading.take() {
+                patterns.push(finish(done)?);
+            }
+            continue;
+        }
+        if line.is_empty() || line.starts_with('#') {
+            diagram_done = reading.as_ref().is_some_and(|r| !r.pattern.grid.is_empty() || !r.constraint_rows.is_empty());
+            continue;
+        }
+        let Some(current) = reading.as_mut() else {
+            return Err(bad("expected Pattern"));
+        };
+        let append = |text: &mut Option<String>, more: &str| {
+            let more = more.trim();
+            match text {
+                Some(text) => {
+                    text.push(' ');
+                    text.push_str(more);
+                }
+                None => *text = Some(more.to_string()),
+            }
+        };
+        match line.chars().next() {
+            Some(':') => {
+                if current.entry || current.pattern.grid.is_empty() {
+                    return Err(bad("unexpected ':' line"));
+                }
+                parse_entry(&line[1..], &mut current.pattern).map_err(|e| bad(&e))?;
+                current.entry = true;
+                diagram_done = false;
+            }
+            Some(';') if current.entry => append(&mut current.pattern.constraint, &line[1..]),
+            Some('>') if current.entry => append(&mut current.pattern.action, &line[1..]),
+            Some(c) if DIAGRAM_CHARS.contains(c) || EDGE_CHARS.contains(c) || LABEL_CHARS.contains(c) => {
+                if current.entry {
+                    if diagram_done && !current.constraint_rows.is_empty() || current.pattern.constraint.is_some() {
+                        return Err(bad("unexpected diagram"));
+                    }
+                    current.constraint_rows.push(first.to_string());
+                    diagram_done = false;
+                    continue;
+                }
+                if diagram_done {
+                    return Err(bad("unexpected diagram"));
+                }
+                let pattern = &mut current.pattern;
+                let Some((points, left, right)) = diagram_row(first) else {
+                    if pattern.edges.bottom {
+                        return Err(bad("second bottom edge"));
+                    }
+                    if pattern.grid.is_empty() {
+                        pattern.edges.top = true;
+                    } else {
+                        pattern.edges.bottom = true;
+                    }
+                    continue;
+                };
+                if pattern.edges.bottom {
+                    return Err(bad("diagram row below the bottom edge"));
+                }
+                if !pattern.grid.is_empty() && (left != pattern.edges.left || right != pattern.edges.right) {
+                    return Err(bad("edge marks differ from the rows above"));
+                }
+                pattern.edges.left = left;
+                pattern.edges.right = right;
+                let row = pattern.grid.len();
+                let mut elements = Vec::new();
+                for (col, c) in points.chars().enumerate() {
+                    let element = PatternElement::from_char(c).ok_or_else(|| bad(&format!("bad diagram character '{}'", c)))?;
+                    let marked = c == 'Q' || c == 'Y';
+                    if element == PatternElement::Move || marked && pattern.anchor.is_none() {
+                        if element == PatternElement::Move && pattern.grid.iter().flatten().chain(&elements).any(|&e| e == PatternElement::Move) {
+                            return Err(bad("more than one '*'"));
+                        }
+                        pattern.anchor = Some((row, col));
+                    }
+                    elements.push(element);
+                }
+                if pattern.grid.first().is_some_and(|top| top.len() != elements.len()) {
+                    return Err(bad("pattern is not rectangular"));
+                }
+                pattern.grid.push(elements);
+            }
+            _ => return Err(bad("malformed line")),
+        }
+    }
+    if let Some(done) = reading {
+        patterns.push(finish(done)?);
+    }
+    Ok(patterns)
+}
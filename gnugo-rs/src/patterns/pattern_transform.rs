@@ -34,6 +34,30 @@ impl Transformation {
         }
     }
     
+    /// Applies the transformation to an offset (dx, dy) from a point, so
+    /// that offsets move the way [`Transformation::apply`] moves points
+    pub fn apply_offset(&self, dx: isize, dy: isize) -> (isize, isize) {
+        match self {
+            Transformation::Identity => (dx, dy),
+            Transformation::Rot90 => (dy, -dx),
+            Transformation::Rot180 => (-dx, -dy),
+            Transformation::Rot270 => (-dy, dx),
+            Transformation::Mirror => (-dx, dy),
+            Transformation::MirrorRot90 => (dy, dx),
+            Transformation::MirrorRot180 => (dx, -dy),
+            Transformation::MirrorRot270 => (-dy, -dx),
+        }
+    }
+
+    /// The transformation that undoes this one
+    pub fn inverse(&self) -> Self {
+        match self {
+            Transformation::Rot90 => Transformation::Rot270,
+            Transformation::Rot270 => Transformation::Rot90,
+            other => *other,
+        }
+    }
+
     /// Returns all possible transformations
     pub fn all() -> [Self; 8] {
         [
@@ -0,0 +1,184 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Pattern value tuning from game records
+//!
+//! Replaying games, every pattern with a move, `*`, is matched for the
+//! player to move on each empty point, and counted as played when the
+//! move made was its move. A pattern played more often than patterns are
+//! on average gains value in proportion, one played less often loses it.
+//! Constraints are not read, and patterns without a `value(...)` keep
+//! theirs, which GNU Go works out elsewhere.
+
+use crate::engine::board::{Board, Stone};
+use crate::engine::game::{Game, GameMove};
+use crate::patterns::pattern_database::PatternGrid;
+use crate::patterns::pattern_parser::{PatternElement, TextPattern};
+use crate::sgf::review::{player_rank, Rank};
+use crate::sgf::{is_move_node, SGFHandler, SGFTree};
+
+/// Fewest matches before a pattern's value is changed
+pub const MIN_MATCHES: u32 = 10;
+
+/// Matches at the average rate a pattern's own rate is blended with, so
+/// rarely matched patterns move little
+const PRIOR_MATCHES: f32 = 10.0;
+
+/// Most a value is multiplied or divided by in one tuning
+const MAX_SCALE: f32 = 4.0;
+
+/// How often a pattern matched and how often its move was played
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PatternCount {
+    pub matched: u32,
+    pub played: u32,
+}
+
+/// Counts for the patterns of one database, in file order
+pub struct PatternTuner {
+    patterns: Vec<TextPattern>,
+    /// `None` for a pattern without a move
+    grids: Vec<Option<PatternGrid>>,
+    counts: Vec<PatternCount>,
+    /// Only moves of players of this rank or stronger count
+    min_rank: Option<Rank>,
+}
+
+impl PatternTuner {
+    pub fn new(patterns: Vec<TextPattern>) -> Self {
+        let grids = patterns
+            .iter()
+            .map(|p| p.grid.iter().flatten().any(|&e| e == PatternElement::Move).then(|| PatternGrid::from_text(p)))
+            .collect();
+        let counts = vec![PatternCount::default(); patterns.len()];
+        PatternTuner { patterns, grids, counts, min_rank: None }
+    }
+
+    /// Counts only the moves of players the records rank at least `rank`
+    pub fn with_min_rank(mut self, rank: Rank) -> Self {
+        self.min_rank = Some(rank);
+        self
+    }
+
+    pub fn patterns(&self) -> &[TextPattern] {
+        &self.patterns
+    }
+
+    /// Counts by pattern, in the order of [`PatternTuner::patterns`]
+    pub fn counts(&self) -> &[PatternCount] {
+        &self.counts
+    }
+
+    /// Counts the patterns matching for `color` on `board`, where `color`
+    /// played `point`
+    pub fn add_move(&mut self, board: &Board, color: Stone, point: (usize, usize)) {
+        let size = board.size();
+        for (grid, count) in self.grids.iter().zip(&mut self.counts) {
+            let Some(grid) = grid else { continue };
+            for (x, y) in (1..=size).flat_map(|y| (1..=size).map(move |x| (x, y))) {
+                if board.get_stone(x, y) == Stone::Empty && grid.match_for(board, (x, y), color).is_some() {
+                    count.matched += 1;
+                    if (x, y) == point {
+                        count.played += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Counts the moves of the main line of `tree` by players strong
+    /// enough; returns how many were counted
+    pub fn add_game(&mut self, tree: &SGFTree) -> Result<usize, String> {
+        let counted: Vec<Stone> = [Stone::Black, Stone::White]
+            .into_iter()
+            .filter(|&color| match self.min_rank {
+                Some(min) => player_rank(tree, color).is_some_and(|rank| rank.at_least(min)),
+                None => true,
+            })
+            .collect();
+        if counted.is_empty() {
+            return Ok(0);
+        }
+
+        let handler = SGFHandler::new();
+        let mut root = tree.root.clone();
+        root.children.clear();
+        let mut game = Game::new(19);
+        handler.apply_to_game(&SGFTree { root, current: 0 }, &mut game)?;
+
+        let mut moves = 0;
+        let mut node = tree.root.children.first();
+        while let Some(current) = node {
+            let before = game.board.clone();
+            handler.apply_node(current, &mut game)?;
+            if is_move_node(current) {
+                if let Some(&GameMove { color, point: Some(point), .. }) = game.last_move() {
+                    if counted.contains(&color) {
+                        self.add_move(&before, color, point);
+                        moves += 1;
+                    }
+                }
+            }
+            node = current.children.first();
+        }
+        Ok(moves)
+    }
+
+    /// The value of each pattern after tuning, in file order: its value
+    /// scaled by how much more or less often than average its move was
+    /// played. Values of patterns matched fewer than [`MIN_MATCHES`]
+    /// times, or without a value, stay as they are
+    pub fn tuned_values(&self) -> Vec<f32> {
+        let matched: u32 = self.counts.iter().map(|c| c.matched).sum();
+        let played: u32 = self.counts.iter().map(|c| c.played).sum();
+        let average = if matched == 0 { 0.0 } else { played as f32 / matched as f32 };
+        self.patterns
+            .iter()
+            .zip(&self.counts)
+            .map(|(pattern, count)| {
+                if average == 0.0 || pattern.value == 0.0 || count.matched < MIN_MATCHES {
+                    return pattern.value;
+                }
+                let rate = (count.played as f32 + PRIOR_MATCHES * average) / (count.matched as f32 + PRIOR_MATCHES);
+                let scale = (rate / average).clamp(1.0 / MAX_SCALE, MAX_SCALE);
+                (pattern.value * scale * 100.0).round() / 100.0
+            })
+            .collect()
+    }
+}
+
+/// `text`, a pattern database, with the `value(...)` of the n-th pattern
+/// replaced by `values[n]`; everything else is kept as written
+pub fn write_values(text: &str, values: &[f32]) -> Result<String, String> {
+    let mut output = String::with_capacity(text.len());
+    // Index of the pattern being read, and whether its `:` line is done
+    let mut current: Option<usize> = None;
+    let mut entry_done = false;
+    let mut patterns = 0;
+    for line in text.split_inclusive('\n') {
+        let first = line.split_whitespace().next().unwrap_or("");
+        if first == "Pattern" {
+            current = Some(patterns);
+            patterns += 1;
+            entry_done = false;
+        } else if line.starts_with(':') && !entry_done {
+            entry_done = true;
+            if let Some(index) = current {
+                let value = values.get(index).ok_or_else(|| format!("No value for pattern {}", index + 1))?;
+                if let Some(start) = line.find("value(") {
+                    let start = start + "value(".len();
+                    let end = line[start..].find(')').map(|e| start + e).ok_or("Unclosed value(")?;
+                    output.push_str(&line[..start]);
+                    output.push_str(&value.to_string());
+                    output.push_str(&line[end..]);
+                    continue;
+                }
+            }
+        }
+        output.push_str(line);
+    }
+    if patterns != values.len() {
+        return Err(format!("{} values for {} patterns", values.len(), patterns));
+    }
+    Ok(output)
+}
@@ -0,0 +1,86 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Just enough HTTP/1.1 to serve the engine's network APIs
+
+use std::io::{self, BufRead, Write};
+
+/// Largest request body accepted
+const MAX_BODY: usize = 1 << 20;
+
+/// A parsed HTTP request
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HttpRequest {
+    /// Case-insensitive header lookup
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// Reads one request from the stream
+pub fn read_request<R: BufRead>(reader: &mut R) -> io::Result<HttpRequest> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "connection closed"));
+    }
+    let mut parts = line.split_whitespace();
+    let method = parts.next().ok_or_else(|| invalid("missing method"))?.to_string();
+    let path = parts.next().ok_or_else(|| invalid("missing path"))?.to_string();
+
+    let mut headers = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(invalid("truncated headers"));
+        }
+        let trimmed = line.trim_end();
+        if trimmed.is_empty() {
+            break;
+        }
+        if let Some((key, value)) = trimmed.split_once(':') {
+            headers.push((key.trim().to_string(), value.trim().to_string()));
+        }
+        if headers.len() > 100 {
+            return Err(invalid("too many headers"));
+        }
+    }
+
+    let mut request = HttpRequest { method, path, headers, body: Vec::new() };
+    let length = match request.header("Content-Length") {
+        Some(value) => value.parse::<usize>().map_err(|_| invalid("bad Content-Length"))?,
+        None => 0,
+    };
+    if length > MAX_BODY {
+        return Err(invalid("request body too large"));
+    }
+    request.body = vec![0; length];
+    reader.read_exact(&mut request.body)?;
+    Ok(request)
+}
+
+/// Writes a complete response and closes the exchange
+pub fn write_response<W: Write>(writer: &mut W, status: &str, content_type: &str, body: &str) -> io::Result<()> {
+    write!(
+        writer,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nAccess-Control-Allow-Origin: *\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    writer.flush()
+}
@@ -0,0 +1,345 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! JSON-RPC 2.0 over HTTP: lets scripts use the engine without speaking GTP
+//!
+//! Every request is POSTed as a JSON-RPC object. `new_game` returns a game
+//! id that the other methods (play, genmove, analyze, ownership, score, svg)
+//! take as their `game` parameter. `strength` works on game records
+//! instead and needs no game.
+
+use std::collections::HashMap;
+use std::io::{self, BufReader};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread;
+
+use super::http::{read_request, write_response};
+use super::{candidates_json, ownership_json, parse_color, score_json};
+use crate::config::Config;
+use crate::engine::ai::{parse_level, AIDifficulty, Style, AI, MAX_LEVEL};
+use crate::engine::analysis::{OwnershipMethod, OwnershipSettings};
+use crate::engine::game::Game;
+use crate::gtp::{format_move, parse_gtp_move};
+use crate::json::JsonValue;
+use crate::sgf::review::{collect_stats, player_colors};
+use crate::sgf::SGFHandler;
+use crate::ui::svg::{render_svg, Diagram, SvgOptions};
+
+/// JSON-RPC error codes
+const PARSE_ERROR: i32 = -32700;
+const INVALID_REQUEST: i32 = -32600;
+const METHOD_NOT_FOUND: i32 = -32601;
+const INVALID_PARAMS: i32 = -32602;
+const ENGINE_ERROR: i32 = -32000;
+
+/// An error returned to the caller
+#[derive(Debug, Clone)]
+pub struct RpcError {
+    pub code: i32,
+    pub message: String,
+}
+
+impl RpcError {
+    fn new(code: i32, message: impl Into<String>) -> Self {
+        RpcError { code, message: message.into() }
+    }
+}
+
+/// A game, locked on its own so requests on other games need not wait
+type SharedGame = Arc<Mutex<Game>>;
+
+/// Games shared by all connections
+pub struct JsonRpcServer {
+    /// Defaults for new games and engine moves
+    config: Config,
+    games: Mutex<HashMap<u64, SharedGame>>,
+    next_id: AtomicU64,
+}
+
+impl Default for JsonRpcServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JsonRpcServer {
+    pub fn new() -> Self {
+        Self::with_config(Config::default())
+    }
+
+    pub fn with_config(config: Config) -> Self {
+        JsonRpcServer {
+            config,
+            games: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Handles a raw request body and returns the response document
+    pub fn handle_text(&self, body: &str) -> JsonValue {
+        match JsonValue::parse(body) {
+            Ok(request) => self.handle(&request),
+            Err(e) => error_response(JsonValue::Null, RpcError::new(PARSE_ERROR, e)),
+        }
+    }
+
+    /// Handles a parsed JSON-RPC request
+    pub fn handle(&self, request: &JsonValue) -> JsonValue {
+        let id = request.get("id").cloned().unwrap_or(JsonValue::Null);
+        let Some(method) = request.get("method").and_then(|m| m.as_str()) else {
+            return error_response(id, RpcError::new(INVALID_REQUEST, "missing method"));
+        };
+        let empty = JsonValue::Object(Vec::new());
+        let params = request.get("params").unwrap_or(&empty);
+
+        match self.dispatch(method, params) {
+            Ok(result) => JsonValue::object(vec![
+                ("jsonrpc", "2.0".into()),
+                ("result", result),
+                ("id", id),
+            ]),
+            Err(e) => error_response(id, e),
+        }
+    }
+
+    fn dispatch(&self, method: &str, params: &JsonValue) -> Result<JsonValue, RpcError> {
+        match method {
+            "new_game" => self.new_game(params),
+            "play" => self.with_game(params, |game| play(game, params)),
+            "genmove" => self.with_game(params, |game| genmove(game, params, &self.config)),
+            "analyze" => self.with_game(params, |game| analyze(game, params)),
+            "ownership" => self.with_game(params, |game| ownership(game, params, self.config.level)),
+            "score" => self.with_game(params, |game| Ok(score_json(game))),
+            "svg" => self.with_game(params, |game| Ok(svg(game, params))),
+            "strength" => strength(params),
+            "delete_game" => {
+                let id = game_id(params)?;
+                match self.games().remove(&id) {
+                    Some(_) => Ok(JsonValue::Null),
+                    None => Err(RpcError::new(INVALID_PARAMS, format!("unknown game {}", id))),
+                }
+            }
+            _ => Err(RpcError::new(METHOD_NOT_FOUND, format!("unknown method '{}'", method))),
+        }
+    }
+
+    fn new_game(&self, params: &JsonValue) -> Result<JsonValue, RpcError> {
+        let size = match params.get("size") {
+            Some(v) => v.as_usize().filter(|s| (2..=25).contains(s))
+                .ok_or_else(|| RpcError::new(INVALID_PARAMS, "size must be 2..25"))?,
+            None => self.config.board_size,
+        };
+        let mut game = Game::with_rules(size, self.config.ruleset);
+        game.variant = self.config.variant;
+        game.komi = match params.get("komi") {
+            Some(komi) => komi.as_f64().ok_or_else(|| RpcError::new(INVALID_PARAMS, "komi must be a number"))? as f32,
+            None => self.config.komi,
+        };
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.games().insert(id, Arc::new(Mutex::new(game)));
+        Ok(JsonValue::object(vec![("game", (id as f64).into())]))
+    }
+
+    fn with_game<F>(&self, params: &JsonValue, action: F) -> Result<JsonValue, RpcError>
+    where
+        F: FnOnce(&mut Game) -> Result<JsonValue, RpcError>,
+    {
+        let id = game_id(params)?;
+        let game = self
+            .games()
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("unknown game {}", id)))?;
+        let mut game = game
+            .lock()
+            .map_err(|_| RpcError::new(ENGINE_ERROR, format!("game {} was lost to an engine failure", id)))?;
+        action(&mut game)
+    }
+
+    /// The game table; it is only ever inserted into or removed from, so
+    /// a panic elsewhere cannot leave it half updated
+    fn games(&self) -> MutexGuard<'_, HashMap<u64, SharedGame>> {
+        self.games.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Accepts HTTP connections forever, one thread per connection
+    pub fn serve(self: Arc<Self>, address: &str) -> io::Result<()> {
+        let listener = TcpListener::bind(address)?;
+        eprintln!("JSON-RPC server listening on http://{}", listener.local_addr()?);
+
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    let server = Arc::clone(&self);
+                    thread::spawn(move || {
+                        if let Err(e) = server.serve_connection(stream) {
+                            eprintln!("JSON-RPC connection error: {}", e);
+                        }
+                    });
+                }
+                Err(e) => eprintln!("JSON-RPC accept error: {}", e),
+            }
+        }
+        Ok(())
+    }
+
+    fn serve_connection(&self, stream: TcpStream) -> io::Result<()> {
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut writer = stream;
+        let request = read_request(&mut reader)?;
+
+        match request.method.as_str() {
+            "POST" => {
+                let body = String::from_utf8_lossy(&request.body);
+                let response = self.handle_text(&body);
+                write_response(&mut writer, "200 OK", "application/json", &response.to_string())
+            }
+            "OPTIONS" => write_response(&mut writer, "204 No Content", "text/plain", ""),
+            _ => write_response(&mut writer, "405 Method Not Allowed", "text/plain", "POST JSON-RPC requests\n"),
+        }
+    }
+}
+
+fn error_response(id: JsonValue, error: RpcError) -> JsonValue {
+    JsonValue::object(vec![
+        ("jsonrpc", "2.0".into()),
+        (
+            "error",
+            JsonValue::object(vec![("code", error.code.into()), ("message", error.message.into())]),
+        ),
+        ("id", id),
+    ])
+}
+
+fn game_id(params: &JsonValue) -> Result<u64, RpcError> {
+    params
+        .get("game")
+        .and_then(|g| g.as_usize())
+        .map(|g| g as u64)
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, "missing game id"))
+}
+
+fn color_param(game: &Game, params: &JsonValue) -> Result<crate::engine::board::Stone, RpcError> {
+    match params.get("color").and_then(|c| c.as_str()) {
+        Some(name) => parse_color(name).ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("invalid color '{}'", name))),
+        None => Ok(game.current_player()),
+    }
+}
+
+fn play(game: &mut Game, params: &JsonValue) -> Result<JsonValue, RpcError> {
+    let color = color_param(game, params)?;
+    let vertex = params
+        .get("vertex")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, "missing vertex"))?;
+
+    game.current_player = color == crate::engine::board::Stone::Black;
+    let result = if vertex.eq_ignore_ascii_case("pass") {
+        game.pass()
+    } else {
+        let (x, y) = parse_gtp_move(vertex, game.board.size())
+            .ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("invalid vertex '{}'", vertex)))?;
+        game.make_move(x, y)
+    };
+    result.map_err(|e| RpcError::new(ENGINE_ERROR, e))?;
+    Ok(JsonValue::Null)
+}
+
+fn genmove(game: &mut Game, params: &JsonValue, config: &Config) -> Result<JsonValue, RpcError> {
+    let color = color_param(game, params)?;
+    let level = match params.get("level") {
+        Some(JsonValue::String(name)) => parse_level(name),
+        Some(number) => number.as_usize().filter(|n| (1..=MAX_LEVEL as usize).contains(n)).map(|n| n as u8),
+        None => Some(config.strength),
+    }
+    .ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("level must be a level name or 1 to {}", MAX_LEVEL)))?;
+
+    game.current_player = color == crate::engine::board::Stone::Black;
+    let size = game.board.size();
+    let ai = AI::for_level(level)
+        .with_limits(config.limits)
+        .with_variant(game.variant)
+        .with_style(Style::for_handicap(game.handicap, color));
+    let vertex = match ai.get_game_move(game, color) {
+        Some((x, y)) if game.make_move(x, y).is_ok() => format_move(x, y, size),
+        _ => {
+            game.pass().map_err(|e| RpcError::new(ENGINE_ERROR, e))?;
+            "pass".to_string()
+        }
+    };
+    Ok(JsonValue::object(vec![("move", vertex.into())]))
+}
+
+/// `method` is "influence" (default) or "playouts"; `playouts` and `seed`
+/// tune the latter
+fn ownership(game: &mut Game, params: &JsonValue, level: AIDifficulty) -> Result<JsonValue, RpcError> {
+    let mut settings = OwnershipSettings {
+        to_move: game.current_player(),
+        playouts: level.profile().playouts,
+        ..Default::default()
+    };
+    settings.method = match params.get("method").and_then(|m| m.as_str()) {
+        None | Some("influence") => OwnershipMethod::Influence,
+        Some("playouts") => OwnershipMethod::Playouts,
+        Some(other) => return Err(RpcError::new(INVALID_PARAMS, format!("unknown ownership method '{}'", other))),
+    };
+    if let Some(playouts) = params.get("playouts").and_then(|p| p.as_usize()) {
+        settings.playouts = playouts.clamp(1, 10_000);
+    }
+    settings.seed = params.get("seed").and_then(|s| s.as_usize()).map(|s| s as u64);
+    Ok(ownership_json(game, &settings))
+}
+
+/// `{svg}`: the current position, with optional `numbers` and `coordinates` flags
+fn svg(game: &mut Game, params: &JsonValue) -> JsonValue {
+    let defaults = SvgOptions::default();
+    let flag = |name: &str, default: bool| params.get(name).and_then(|v| v.as_bool()).unwrap_or(default);
+    let options = SvgOptions {
+        coordinates: flag("coordinates", defaults.coordinates),
+        move_numbers: flag("numbers", defaults.move_numbers),
+        cell_size: params.get("cell").and_then(|c| c.as_usize()).map_or(defaults.cell_size, |c| c.clamp(8, 100)),
+    };
+    JsonValue::object(vec![("svg", render_svg(&Diagram::from_game(game), &options).into())])
+}
+
+fn analyze(game: &mut Game, params: &JsonValue) -> Result<JsonValue, RpcError> {
+    let color = color_param(game, params)?;
+    let count = params.get("count").and_then(|c| c.as_usize()).unwrap_or(10);
+    Ok(JsonValue::object(vec![
+        ("moves", candidates_json(game, color, count)),
+        ("score", score_json(game)),
+    ]))
+}
+
+/// Rank estimate from `sgf`, one record or an array of them, for the games
+/// of `player` (matched against PB/PW) or else the moves of `color`
+fn strength(params: &JsonValue) -> Result<JsonValue, RpcError> {
+    let texts: Vec<&str> = match params.get("sgf") {
+        Some(JsonValue::Array(items)) => items.iter().filter_map(|i| i.as_str()).collect(),
+        Some(item) => item.as_str().into_iter().collect(),
+        None => Vec::new(),
+    };
+    if texts.is_empty() {
+        return Err(RpcError::new(INVALID_PARAMS, "missing sgf"));
+    }
+    let handler = SGFHandler::new();
+    let trees = texts
+        .iter()
+        .map(|text| handler.parse(text))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))?;
+
+    let stats = match (params.get("player").and_then(|p| p.as_str()), params.get("color").and_then(|c| c.as_str())) {
+        (Some(player), _) => collect_stats(&trees, |tree| player_colors(tree, player)),
+        (None, Some(name)) => {
+            let color = parse_color(name)
+                .ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("invalid color '{}'", name)))?;
+            collect_stats(&trees, |_| vec![color])
+        }
+        (None, None) => return Err(RpcError::new(INVALID_PARAMS, "missing player or color")),
+    };
+    Ok(stats.map_err(|e| RpcError::new(ENGINE_ERROR, e))?.to_json())
+}
@@ -0,0 +1,46 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Network server modes exposing the engine to other programs
+
+pub mod http;
+pub mod json_rpc;
+pub mod websocket;
+
+use crate::engine::analysis::{candidate_moves, ownership, OwnershipSettings, ScoreEstimate};
+use crate::engine::board::Stone;
+use crate::engine::game::Game;
+use crate::json::JsonValue;
+
+/// Parses a color parameter such as "black", "W" or "white"
+pub fn parse_color(name: &str) -> Option<Stone> {
+    match name.to_lowercase().as_str() {
+        "black" | "b" => Some(Stone::Black),
+        "white" | "w" => Some(Stone::White),
+        _ => None,
+    }
+}
+
+/// Best candidate moves for `color` as `[{move, value}]`
+pub fn candidates_json(game: &Game, color: Stone, count: usize) -> JsonValue {
+    let size = game.board.size();
+    JsonValue::Array(
+        candidate_moves(&game.board, color, count)
+            .iter()
+            .map(|c| c.to_json(size))
+            .collect(),
+    )
+}
+
+/// Ownership map as `{size, ownership}`
+pub fn ownership_json(game: &Game, settings: &OwnershipSettings) -> JsonValue {
+    JsonValue::object(vec![
+        ("size", game.board.size().into()),
+        ("ownership", ownership(&game.board, settings).into()),
+    ])
+}
+
+/// Area score estimate with dead stones removed
+pub fn score_json(game: &Game) -> JsonValue {
+    ScoreEstimate::new(game).to_json(game.board.size())
+}
@@ -0,0 +1,541 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! WebSocket mode for browser frontends
+//!
+//! Each connection owns one game. The client sends JSON messages such as
+//! `{"type":"play","vertex":"D4"}`. The server answers with `state`,
+//! `analysis`, `clock` and `error` messages. A plain GET on `/` returns a
+//! minimal browser board that speaks this protocol.
+
+use std::io::{self, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::http::{read_request, write_response, HttpRequest};
+use super::{candidates_json, ownership_json, parse_color, score_json};
+use crate::config::Config;
+use crate::engine::ai::{AIDifficulty, ResourceLimits, Style, AI};
+use crate::engine::analysis::OwnershipSettings;
+use crate::engine::board::Stone;
+use crate::engine::clock::{Clock, TimeControl};
+use crate::engine::game::Game;
+use crate::gtp::{format_move, parse_gtp_move};
+use crate::json::JsonValue;
+use crate::sgf::archive::Archive;
+
+/// GUID appended to the client key during the opening handshake (RFC 6455)
+const HANDSHAKE_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// Largest message accepted from a client
+const MAX_MESSAGE: usize = 1 << 20;
+
+/// Browser board served on plain HTTP requests
+const WEB_CLIENT: &str = include_str!("web_client.html");
+
+/// Frame opcodes
+const OP_CONTINUATION: u8 = 0x0;
+const OP_TEXT: u8 = 0x1;
+const OP_BINARY: u8 = 0x2;
+const OP_CLOSE: u8 = 0x8;
+const OP_PING: u8 = 0x9;
+const OP_PONG: u8 = 0xA;
+
+/// Accepts connections forever, one game per WebSocket connection
+pub fn serve(address: &str, config: &Config) -> io::Result<()> {
+    let listener = TcpListener::bind(address)?;
+    eprintln!("WebSocket server listening on http://{}", listener.local_addr()?);
+
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                let config = config.clone();
+                thread::spawn(move || {
+                    if let Err(e) = serve_connection(stream, &config) {
+                        eprintln!("WebSocket connection error: {}", e);
+                    }
+                });
+            }
+            Err(e) => eprintln!("WebSocket accept error: {}", e),
+        }
+    }
+    Ok(())
+}
+
+fn serve_connection(stream: TcpStream, config: &Config) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+    let request = read_request(&mut reader)?;
+
+    let Some(accept) = handshake_accept(&request) else {
+        return match request.method.as_str() {
+            "GET" => write_response(&mut writer, "200 OK", "text/html; charset=utf-8", WEB_CLIENT),
+            _ => write_response(&mut writer, "405 Method Not Allowed", "text/plain", "GET only\n"),
+        };
+    };
+    write!(
+        writer,
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+        accept
+    )?;
+    writer.flush()?;
+
+    let writer = Arc::new(Mutex::new(writer));
+    let session = Arc::new(Mutex::new(Session::new(config)));
+    let closed = Arc::new(AtomicBool::new(false));
+
+    // Clock ticks are pushed from their own thread so a silent client still sees time run
+    let ticker = {
+        let (writer, session, closed) = (Arc::clone(&writer), Arc::clone(&session), Arc::clone(&closed));
+        thread::spawn(move || {
+            while !closed.load(Ordering::Relaxed) {
+                thread::sleep(Duration::from_secs(1));
+                let message = {
+                    let mut session = session.lock().unwrap();
+                    if session.game.is_game_over() {
+                        continue;
+                    }
+                    session.check_flag();
+                    session.clock_json()
+                };
+                if send_text(&writer, &message).is_err() {
+                    break;
+                }
+            }
+        })
+    };
+
+    let result = message_loop(&mut reader, &writer, &session);
+    closed.store(true, Ordering::Relaxed);
+    let _ = ticker.join();
+    result
+}
+
+fn message_loop<R: Read>(reader: &mut R, writer: &Arc<Mutex<TcpStream>>, session: &Arc<Mutex<Session>>) -> io::Result<()> {
+    for message in session.lock().unwrap().snapshot() {
+        send_text(writer, &message)?;
+    }
+
+    let mut message = Vec::new();
+    loop {
+        let (fin, opcode, payload) = read_frame(reader)?;
+        match opcode {
+            OP_TEXT | OP_BINARY | OP_CONTINUATION => {
+                if message.len() + payload.len() > MAX_MESSAGE {
+                    return Err(io::Error::new(io::ErrorKind::InvalidData, "message too large"));
+                }
+                message.extend_from_slice(&payload);
+                if !fin {
+                    continue;
+                }
+                let text = String::from_utf8_lossy(&message).into_owned();
+                message.clear();
+
+                let replies = session.lock().unwrap().handle_text(&text);
+                for reply in replies {
+                    send_text(writer, &reply)?;
+                }
+            }
+            OP_PING => write_frame(&mut *writer.lock().unwrap(), OP_PONG, &payload)?,
+            OP_PONG => {}
+            OP_CLOSE => {
+                let _ = write_frame(&mut *writer.lock().unwrap(), OP_CLOSE, &payload);
+                return Ok(());
+            }
+            _ => return Err(io::Error::new(io::ErrorKind::InvalidData, "unknown opcode")),
+        }
+    }
+}
+
+/// Computes Sec-WebSocket-Accept if the request is a WebSocket upgrade
+fn handshake_accept(request: &HttpRequest) -> Option<String> {
+    let upgrade = request.header("Upgrade")?;
+    if !upgrade.eq_ignore_ascii_case("websocket") {
+        return None;
+    }
+    request.header("Sec-WebSocket-Key").map(accept_key)
+}
+
+/// Sec-WebSocket-Accept value for a client's Sec-WebSocket-Key
+pub fn accept_key(key: &str) -> String {
+    base64(&sha1(format!("{}{}", key, HANDSHAKE_GUID).as_bytes()))
+}
+
+fn send_text(writer: &Mutex<TcpStream>, message: &JsonValue) -> io::Result<()> {
+    write_frame(&mut *writer.lock().unwrap(), OP_TEXT, message.to_string().as_bytes())
+}
+
+/// Reads one frame, returning (fin, opcode, unmasked payload)
+fn read_frame<R: Read>(reader: &mut R) -> io::Result<(bool, u8, Vec<u8>)> {
+    let mut header = [0u8; 2];
+    reader.read_exact(&mut header)?;
+    let fin = header[0] & 0x80 != 0;
+    let opcode = header[0] & 0x0F;
+    let masked = header[1] & 0x80 != 0;
+
+    let length = match header[1] & 0x7F {
+        126 => {
+            let mut bytes = [0u8; 2];
+            reader.read_exact(&mut bytes)?;
+            u16::from_be_bytes(bytes) as usize
+        }
+        127 => {
+            let mut bytes = [0u8; 8];
+            reader.read_exact(&mut bytes)?;
+            u64::from_be_bytes(bytes) as usize
+        }
+        n => n as usize,
+    };
+    if length > MAX_MESSAGE {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "frame too large"));
+    }
+
+    let mut mask = [0u8; 4];
+    if masked {
+        reader.read_exact(&mut mask)?;
+    }
+    let mut payload = vec![0u8; length];
+    reader.read_exact(&mut payload)?;
+    if masked {
+        for (i, byte) in payload.iter_mut().enumerate() {
+            *byte ^= mask[i % 4];
+        }
+    }
+    Ok((fin, opcode, payload))
+}
+
+/// Writes one unmasked, unfragmented frame
+fn write_frame<W: Write>(writer: &mut W, opcode: u8, payload: &[u8]) -> io::Result<()> {
+    let mut frame = vec![0x80 | opcode];
+    match payload.len() {
+        n if n < 126 => frame.push(n as u8),
+        n if n <= u16::MAX as usize => {
+            frame.push(126);
+            frame.extend_from_slice(&(n as u16).to_be_bytes());
+        }
+        n => {
+            frame.push(127);
+            frame.extend_from_slice(&(n as u64).to_be_bytes());
+        }
+    }
+    frame.extend_from_slice(payload);
+    writer.write_all(&frame)?;
+    writer.flush()
+}
+
+/// Per-connection game and engine settings; the game keeps the clock
+pub struct Session {
+    pub game: Game,
+    /// When the side to move started thinking
+    turn_started: Instant,
+    level: AIDifficulty,
+    limits: ResourceLimits,
+    engine_color: Option<Stone>,
+    analysis: bool,
+    last_move: Option<String>,
+    /// Where finished games are saved
+    archive: Option<Archive>,
+    /// Whether the current game has been archived
+    archived: bool,
+}
+
+impl Session {
+    pub fn new(config: &Config) -> Self {
+        Session {
+            game: config.new_game(),
+            turn_started: Instant::now(),
+            level: config.level,
+            limits: config.limits,
+            engine_color: None,
+            analysis: true,
+            last_move: None,
+            archive: config.archive_dir.as_ref().map(Archive::new),
+            archived: false,
+        }
+    }
+
+    /// Handles one client message and returns the messages to send back
+    pub fn handle_text(&mut self, text: &str) -> Vec<JsonValue> {
+        let result = JsonValue::parse(text).and_then(|message| self.handle(&message));
+        if let (Some(archive), true, false) = (&self.archive, self.game.is_game_over(), self.archived) {
+            self.archived = true;
+            let engine = |color| if self.engine_color == Some(color) { "gnugo_rs" } else { "Human" };
+            if let Err(e) = archive.save_game("web", &self.game, engine(Stone::Black), engine(Stone::White)) {
+                eprintln!("archive error: {}", e);
+            }
+        }
+        match result {
+            Ok(()) => self.snapshot(),
+            Err(e) => vec![JsonValue::object(vec![("type", "error".into()), ("message", e.into())])],
+        }
+    }
+
+    fn handle(&mut self, message: &JsonValue) -> Result<(), String> {
+        let kind = message.get("type").and_then(|t| t.as_str()).ok_or("missing message type")?;
+        match kind {
+            "new_game" => self.new_game(message)?,
+            "play" => {
+                let vertex = message.get("vertex").and_then(|v| v.as_str()).ok_or("missing vertex")?;
+                self.play(vertex)?;
+                self.engine_reply();
+            }
+            "pass" => {
+                self.play("pass")?;
+                self.engine_reply();
+            }
+            "genmove" => self.genmove()?,
+            "undo" => {
+                self.game.undo_move().ok_or("nothing to undo")?;
+                self.last_move = None;
+                self.turn_started = Instant::now();
+            }
+            "resign" => self.game.resign()?,
+            "state" => {}
+            _ => return Err(format!("unknown message type '{}'", kind)),
+        }
+        Ok(())
+    }
+
+    fn new_game(&mut self, message: &JsonValue) -> Result<(), String> {
+        let size = match message.get("size") {
+            Some(v) => v.as_usize().filter(|s| (2..=25).contains(s)).ok_or("size must be 2..25")?,
+            None => self.game.board.size(),
+        };
+        let mut game = Game::with_rules(size, self.game.rules);
+        game.variant = self.game.variant;
+        game.komi = match message.get("komi") {
+            Some(komi) => komi.as_f64().ok_or("komi must be a number")? as f32,
+            None => self.game.komi,
+        };
+        if let Some(level) = message.get("level").and_then(|l| l.as_str()) {
+            self.level = AIDifficulty::from_name(level).ok_or_else(|| format!("unknown level '{}'", level))?;
+        }
+        self.engine_color = match message.get("engine").and_then(|c| c.as_str()) {
+            Some(name) => Some(parse_color(name).ok_or_else(|| format!("invalid color '{}'", name))?),
+            None => None,
+        };
+        if let Some(analysis) = message.get("analysis").and_then(|a| a.as_bool()) {
+            self.analysis = analysis;
+        }
+        let main_time = match message.get("main_time") {
+            Some(time) => {
+                let seconds = time.as_f64().ok_or("main_time must be a number")?;
+                Some(Duration::try_from_secs_f64(seconds).map_err(|_| "main_time must be a non-negative number of seconds")?)
+            }
+            None => None,
+        };
+        // Overtime stays as configured
+        let mut control = self.game.clock.control();
+        if let Some(main_time) = main_time {
+            control = TimeControl { main_time, ..control };
+        }
+        game.clock = Clock::new(control);
+
+        self.game = game;
+        self.archived = false;
+        self.turn_started = Instant::now();
+        self.last_move = None;
+        self.engine_reply();
+        Ok(())
+    }
+
+    fn play(&mut self, vertex: &str) -> Result<(), String> {
+        if vertex.eq_ignore_ascii_case("pass") {
+            self.game.pass()?;
+            self.last_move = Some("pass".to_string());
+        } else {
+            let size = self.game.board.size();
+            let (x, y) = parse_gtp_move(vertex, size).ok_or_else(|| format!("invalid vertex '{}'", vertex))?;
+            self.game.make_move(x, y)?;
+            self.last_move = Some(format_move(x, y, size));
+        }
+        let elapsed = self.turn_started.elapsed();
+        self.turn_started = Instant::now();
+        self.game.record_move_time(elapsed);
+        Ok(())
+    }
+
+    fn genmove(&mut self) -> Result<(), String> {
+        let color = self.game.current_player();
+        let size = self.game.board.size();
+        let ai = AI::new(self.level)
+            .with_limits(self.limits)
+            .with_variant(self.game.variant)
+            .with_style(Style::for_handicap(self.game.handicap, color));
+        let vertex = match ai.get_game_move(&self.game, color) {
+            Some((x, y)) => format_move(x, y, size),
+            None => "pass".to_string(),
+        };
+        self.play(&vertex)
+    }
+
+    /// Lets the engine move if it plays the side to move
+    fn engine_reply(&mut self) {
+        if self.engine_color == Some(self.game.current_player()) && !self.game.is_game_over() {
+            let _ = self.genmove();
+        }
+    }
+
+    /// Ends the game on time if the side to move has run out
+    pub fn check_flag(&mut self) {
+        self.game.check_flag(self.turn_started.elapsed());
+    }
+
+    /// Full state plus analysis, sent after every change
+    pub fn snapshot(&mut self) -> Vec<JsonValue> {
+        self.check_flag();
+        let mut messages = vec![self.state_json()];
+        if self.analysis && !self.game.is_game_over() {
+            let color = self.game.current_player();
+            messages.push(JsonValue::object(vec![
+                ("type", "analysis".into()),
+                ("moves", candidates_json(&self.game, color, 5)),
+                ("ownership", ownership_json(&self.game, &OwnershipSettings::default())),
+                ("score", score_json(&self.game)),
+            ]));
+        }
+        messages
+    }
+
+    fn state_json(&self) -> JsonValue {
+        let size = self.game.board.size();
+        let rows: Vec<String> = (1..=size)
+            .map(|y| {
+                (1..=size)
+                    .map(|x| match self.game.board.get_stone(x, y) {
+                        Stone::Black => 'X',
+                        Stone::White => 'O',
+                        Stone::Empty => '.',
+                    })
+                    .collect()
+            })
+            .collect();
+        let winner = self.game.winner().map(|w| color_name(w).to_string());
+
+        JsonValue::object(vec![
+            ("type", "state".into()),
+            ("size", size.into()),
+            ("board", rows.into()),
+            ("to_move", color_name(self.game.current_player()).into()),
+            ("komi", self.game.komi.into()),
+            (
+                "captures",
+                JsonValue::object(vec![
+                    ("black", (self.game.captured(Stone::Black) as usize).into()),
+                    ("white", (self.game.captured(Stone::White) as usize).into()),
+                ]),
+            ),
+            ("last_move", self.last_move.clone().into()),
+            ("game_over", self.game.is_game_over().into()),
+            ("winner", winner.into()),
+            ("clock", self.clock_fields()),
+        ])
+    }
+
+    /// Periodic clock update
+    pub fn clock_json(&self) -> JsonValue {
+        JsonValue::object(vec![("type", "clock".into()), ("clock", self.clock_fields())])
+    }
+
+    /// Time left per side in GTP `time_left` terms, counting the running
+    /// turn; `remaining` is null without a time limit
+    fn clock_fields(&self) -> JsonValue {
+        let running = if self.game.is_game_over() { Stone::Empty } else { self.game.current_player() };
+        let side = |color: Stone| {
+            let elapsed = if color == running { self.turn_started.elapsed() } else { Duration::ZERO };
+            let left = match self.game.clock.time_left(color) {
+                Some((time, stones)) if elapsed <= time => Some((time - elapsed, stones)),
+                Some(_) => {
+                    let mut clock = self.game.clock.clone();
+                    if clock.charge(color, elapsed) { clock.time_left(color) } else { Some((Duration::ZERO, 0)) }
+                }
+                None => None,
+            };
+            JsonValue::object(vec![
+                ("remaining", left.map(|(time, _)| time.as_secs_f64()).into()),
+                ("stones", left.map(|(_, stones)| stones as usize).unwrap_or(0).into()),
+            ])
+        };
+        JsonValue::object(vec![
+            ("running", color_name(running).into()),
+            ("black", side(Stone::Black)),
+            ("white", side(Stone::White)),
+        ])
+    }
+}
+
+fn color_name(color: Stone) -> &'static str {
+    match color {
+        Stone::Black => "black",
+        Stone::White => "white",
+        Stone::Empty => "none",
+    }
+}
+
+/// SHA-1 digest, needed only for the handshake
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut h: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+
+    for chunk in message.chunks(64) {
+        let mut w = [0u32; 80];
+        for (i, word) in chunk.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = h;
+        for (i, &word) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A827999),
+                20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+            let temp = a.rotate_left(5).wrapping_add(f).wrapping_add(e).wrapping_add(k).wrapping_add(word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+        for (state, value) in h.iter_mut().zip([a, b, c, d, e]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+
+    let mut digest = [0u8; 20];
+    for (i, word) in h.iter().enumerate() {
+        digest[i * 4..i * 4 + 4].copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+/// Standard base64 with padding
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::new();
+    for chunk in data.chunks(3) {
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
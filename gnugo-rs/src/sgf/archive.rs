@@ -0,0 +1,212 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Archive of finished games
+//!
+//! Each game is written to `<root>/<YYYY-MM-DD>/<HHMMSS>-<mode>.sgf` (UTC)
+//! and gets a line in `<root>/index.tsv` with its date, mode, board size,
+//! players and result, so listing the archive never reparses the SGFs.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::{record_to_sgf, SGFHandler, SGFProperty};
+use crate::engine::board::Stone;
+use crate::engine::game::Game;
+
+const INDEX_FILE: &str = "index.tsv";
+const INDEX_HEADER: &str = "# date\ttime\tmode\tsize\tblack\twhite\tresult\tfile";
+
+/// One line of the index
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchiveEntry {
+    /// `YYYY-MM-DD`, UTC
+    pub date: String,
+    /// `HH:MM:SS`, UTC
+    pub time: String,
+    /// What produced the game, e.g. "terminal" or "selfplay"
+    pub mode: String,
+    pub size: usize,
+    pub black: String,
+    pub white: String,
+    /// SGF `RE` value, empty if unknown
+    pub result: String,
+    /// Path of the SGF relative to the archive root
+    pub file: String,
+}
+
+impl ArchiveEntry {
+    fn to_line(&self) -> String {
+        let fields = [
+            &self.date,
+            &self.time,
+            &self.mode,
+            &self.size.to_string(),
+            &self.black,
+            &self.white,
+            &self.result,
+            &self.file,
+        ];
+        let fields: Vec<String> = fields.iter().map(|f| f.replace(['\t', '\n', '\r'], " ")).collect();
+        fields.join("\t")
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let fields: Vec<&str> = line.split('\t').collect();
+        let [date, time, mode, size, black, white, result, file] = fields[..] else {
+            return None;
+        };
+        Some(ArchiveEntry {
+            date: date.to_string(),
+            time: time.to_string(),
+            mode: mode.to_string(),
+            size: size.parse().ok()?,
+            black: black.to_string(),
+            white: white.to_string(),
+            result: result.to_string(),
+            file: file.to_string(),
+        })
+    }
+}
+
+/// A directory of archived games
+#[derive(Debug, Clone)]
+pub struct Archive {
+    root: PathBuf,
+}
+
+impl Archive {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Archive { root: root.into() }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Stores `sgf` as a game played in `mode` and indexes it under the
+    /// players, result and size found in its root node
+    pub fn save(&self, mode: &str, sgf: &str) -> Result<ArchiveEntry, String> {
+        let tree = SGFHandler::new().parse(sgf)?;
+        let text = |key: &str| match tree.root.properties.get(key).and_then(|v| v.first()) {
+            Some(SGFProperty::Text(t)) => t.clone(),
+            Some(SGFProperty::Number(n)) => n.to_string(),
+            Some(SGFProperty::Real(r)) => r.to_string(),
+            Some(SGFProperty::Color(c)) => c.to_string(),
+            _ => String::new(),
+        };
+        let size = match tree.root.properties.get("SZ").and_then(|v| v.first()) {
+            Some(SGFProperty::Number(n)) => usize::try_from(*n).unwrap_or(19),
+            _ => 19,
+        };
+
+        let (date, time) = utc_now();
+        let day = self.root.join(&date);
+        fs::create_dir_all(&day).map_err(|e| format!("Cannot create '{}': {}", day.display(), e))?;
+        let mode_name: String = mode.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '-' }).collect();
+        let stem = format!("{}-{}", time.replace(':', ""), mode_name);
+        let name = (1..)
+            .map(|n| if n == 1 { format!("{}.sgf", stem) } else { format!("{}-{}.sgf", stem, n) })
+            .find(|name| !day.join(name).exists())
+            .unwrap_or_default();
+        let path = day.join(&name);
+        fs::write(&path, sgf).map_err(|e| format!("Cannot write '{}': {}", path.display(), e))?;
+
+        let entry = ArchiveEntry {
+            date: date.clone(),
+            time,
+            mode: mode.to_string(),
+            size,
+            black: text("PB"),
+            white: text("PW"),
+            result: text("RE"),
+            file: format!("{}/{}", date, name),
+        };
+        let index = self.root.join(INDEX_FILE);
+        let new_index = !index.exists();
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&index)
+            .map_err(|e| format!("Cannot open '{}': {}", index.display(), e))?;
+        let header = if new_index { format!("{}\n", INDEX_HEADER) } else { String::new() };
+        writeln!(file, "{}{}", header, entry.to_line()).map_err(|e| format!("Cannot write '{}': {}", index.display(), e))?;
+        Ok(entry)
+    }
+
+    /// Stores the moves of `game` with the given players; rengo teams set
+    /// on the game take their place
+    pub fn save_game(&self, mode: &str, game: &Game, black: &str, white: &str) -> Result<ArchiveEntry, String> {
+        let result = game_result(game);
+        let black = game.team_label(Stone::Black).unwrap_or_else(|| black.to_string());
+        let white = game.team_label(Stone::White).unwrap_or_else(|| white.to_string());
+        let sgf = record_to_sgf(
+            game.board.size(),
+            game.komi,
+            &game.moves(),
+            &[("PB", &black), ("PW", &white), ("RE", &result)],
+        );
+        self.save(mode, &sgf)
+    }
+
+    /// All indexed games, oldest first; an archive never written to is empty
+    pub fn entries(&self) -> Result<Vec<ArchiveEntry>, String> {
+        let index = self.root.join(INDEX_FILE);
+        if !index.exists() {
+            return Ok(Vec::new());
+        }
+        let text = fs::read_to_string(&index).map_err(|e| format!("Cannot read '{}': {}", index.display(), e))?;
+        Ok(text
+            .lines()
+            .filter(|line| !line.starts_with('#') && !line.trim().is_empty())
+            .filter_map(ArchiveEntry::from_line)
+            .collect())
+    }
+}
+
+/// SGF `RE` value for a finished game
+fn game_result(game: &Game) -> String {
+    if !game.is_game_over() {
+        return String::new();
+    }
+    if game.status() == "No result" {
+        return "Void".to_string();
+    }
+    let suffix = match game.status() {
+        "Resigned" => "R",
+        "Lost on time" => "T",
+        _ => "",
+    };
+    match game.winner() {
+        Some(Stone::Black) => format!("B+{}", suffix),
+        Some(_) => format!("W+{}", suffix),
+        None => "0".to_string(),
+    }
+}
+
+/// Current UTC date and time as `YYYY-MM-DD` and `HH:MM:SS`
+fn utc_now() -> (String, String) {
+    let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let (days, rest) = (seconds / 86_400, seconds % 86_400);
+    let (year, month, day) = civil_from_days(days as i64);
+    (
+        format!("{:04}-{:02}-{:02}", year, month, day),
+        format!("{:02}:{:02}:{:02}", rest / 3600, rest % 3600 / 60, rest % 60),
+    )
+}
+
+/// Gregorian date of a day count since 1970-01-01 (Howard Hinnant's algorithm)
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
@@ -0,0 +1,180 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Opening book: for positions met in the openings of recorded games, the
+//! moves played there, weighted by how often
+//!
+//! The book file is plain text, one position per line: board size, the
+//! position hash in hex, the player to move and its moves with weights,
+//! as in `19 5f3a09c2e1d4b786 W D4:12 Q4:3`. Lines starting with `#` are
+//! comments.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::Path;
+
+use rand::Rng;
+
+use super::{is_move_node, SGFHandler, SGFTree};
+use crate::engine::board::{Board, Stone};
+use crate::engine::game::{Game, GameMove};
+use crate::fileio;
+use crate::gtp::{format_move, parse_gtp_move};
+
+/// Moves of each game entered into a book, and the moves of a game in
+/// which `genmove` consults it
+pub const BOOK_MOVES: usize = 20;
+
+/// A move of the book and how many times it was played
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BookMove {
+    pub point: (usize, usize),
+    pub weight: u32,
+}
+
+/// Positions by board size, position hash and player to move
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OpeningBook {
+    positions: HashMap<(usize, u64, Stone), Vec<BookMove>>,
+}
+
+/// Heaviest first, ties in board order, so equal books compare equal
+fn sort_moves(moves: &mut [BookMove]) {
+    moves.sort_by_key(|m| (std::cmp::Reverse(m.weight), m.point.1, m.point.0));
+}
+
+impl OpeningBook {
+    pub fn new() -> Self {
+        OpeningBook::default()
+    }
+
+    /// Positions in the book
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Counts `color` playing `point` on `board` once more
+    pub fn add(&mut self, board: &Board, color: Stone, point: (usize, usize)) {
+        let moves = self.positions.entry((board.size(), board.position_hash(), color)).or_default();
+        match moves.iter_mut().find(|m| m.point == point) {
+            Some(known) => known.weight += 1,
+            None => moves.push(BookMove { point, weight: 1 }),
+        }
+        sort_moves(moves);
+    }
+
+    /// Book moves for `color` on `board`, heaviest first
+    pub fn moves(&self, board: &Board, color: Stone) -> Vec<BookMove> {
+        self.positions.get(&(board.size(), board.position_hash(), color)).cloned().unwrap_or_default()
+    }
+
+    /// A book move for `color` on `board` on an empty point other than the
+    /// ko point, picked at random in proportion to its weight
+    pub fn choose<R: Rng>(&self, board: &Board, color: Stone, rng: &mut R) -> Option<(usize, usize)> {
+        let moves: Vec<BookMove> = self
+            .moves(board, color)
+            .into_iter()
+            .filter(|m| board.get_stone(m.point.0, m.point.1) == Stone::Empty && board.get_ko_point() != Some(m.point))
+            .collect();
+        let total: u32 = moves.iter().map(|m| m.weight).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut pick = rng.gen_range(0..total);
+        for m in &moves {
+            if pick < m.weight {
+                return Some(m.point);
+            }
+            pick -= m.weight;
+        }
+        None
+    }
+
+    /// Enters the first `moves` moves of the main line of `tree`
+    pub fn add_game(&mut self, tree: &SGFTree, moves: usize) -> Result<(), String> {
+        let handler = SGFHandler::new();
+        let mut root = tree.root.clone();
+        root.children.clear();
+        let mut game = Game::new(19);
+        handler.apply_to_game(&SGFTree { root, current: 0 }, &mut game)?;
+
+        let mut played = usize::from(is_move_node(&tree.root));
+        let mut node = tree.root.children.first();
+        while let Some(current) = node {
+            if played >= moves {
+                break;
+            }
+            let before = game.board.clone();
+            handler.apply_node(current, &mut game)?;
+            if is_move_node(current) {
+                played += 1;
+                if let Some(&GameMove { color, point: Some(point), .. }) = game.last_move() {
+                    self.add(&before, color, point);
+                }
+            }
+            node = current.children.first();
+        }
+        Ok(())
+    }
+
+    /// Parses the book file format
+    pub fn from_text(text: &str) -> Result<Self, String> {
+        let mut book = OpeningBook::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let bad = |what: &str| format!("Line {}: {}", number + 1, what);
+            let mut fields = line.split_whitespace();
+            let size: usize = fields
+                .next()
+                .and_then(|f| f.parse().ok())
+                .filter(|s| (2..=25).contains(s))
+                .ok_or_else(|| bad("bad board size"))?;
+            let hash = fields.next().and_then(|f| u64::from_str_radix(f, 16).ok()).ok_or_else(|| bad("bad position hash"))?;
+            let color = match fields.next() {
+                Some("B") => Stone::Black,
+                Some("W") => Stone::White,
+                _ => return Err(bad("expected B or W")),
+            };
+            let moves = book.positions.entry((size, hash, color)).or_default();
+            for field in fields {
+                let (vertex, weight) = field.split_once(':').ok_or_else(|| bad("expected VERTEX:WEIGHT"))?;
+                let point = parse_gtp_move(vertex, size).ok_or_else(|| bad("bad vertex"))?;
+                let weight = weight.parse().map_err(|_| bad("bad weight"))?;
+                moves.push(BookMove { point, weight });
+            }
+            sort_moves(moves);
+        }
+        Ok(book)
+    }
+
+    /// The book file format, positions in a fixed order
+    pub fn to_text(&self) -> String {
+        let mut keys: Vec<&(usize, u64, Stone)> = self.positions.keys().collect();
+        keys.sort_by_key(|&&(size, hash, color)| (size, hash, color == Stone::White));
+        let mut text = String::new();
+        for &(size, hash, color) in keys {
+            let _ = write!(text, "{} {:016x} {}", size, hash, if color == Stone::Black { "B" } else { "W" });
+            for m in &self.positions[&(size, hash, color)] {
+                let _ = write!(text, " {}:{}", format_move(m.point.0, m.point.1, size), m.weight);
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fileio::read_to_string(path).map_err(|e| format!("Cannot read book '{}': {}", path.display(), e))?;
+        Self::from_text(&text)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        fileio::write(path, self.to_text().as_bytes()).map_err(|e| format!("Cannot write book '{}': {}", path.display(), e))
+    }
+}
@@ -0,0 +1,272 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Readers for Tygem GIB and wBaduk NGF game records
+//!
+//! Both are converted into an [`SGFTree`] holding the root properties and
+//! the main line, so the rest of the program treats them like SGF.
+
+use std::collections::HashMap;
+
+use super::{SGFNode, SGFProperty, SGFTree, MAX_DEPTH};
+use crate::engine::board::Stone;
+use crate::engine::rules::fixed_handicap;
+
+/// Game information and moves gathered by a reader. Points are 0-based
+/// (column, row from the top); `None` is a pass.
+struct Record {
+    size: usize,
+    komi: f32,
+    handicap: usize,
+    info: Vec<(&'static str, String)>,
+    moves: Vec<(Stone, Option<(usize, usize)>)>,
+}
+
+impl Record {
+    fn into_tree(self) -> Result<SGFTree, String> {
+        let mut root = node(vec![
+            ("FF", SGFProperty::Number(4)),
+            ("GM", SGFProperty::Number(1)),
+            ("SZ", SGFProperty::Number(self.size as i32)),
+            ("KM", SGFProperty::Real(self.komi)),
+        ]);
+        if self.handicap >= 2 {
+            let stones = fixed_handicap(self.size, self.handicap)
+                .ok_or_else(|| format!("Unsupported handicap {} on {}x{}", self.handicap, self.size, self.size))?;
+            root.properties.insert("HA".to_string(), vec![SGFProperty::Number(self.handicap as i32)]);
+            root.properties.insert(
+                "AB".to_string(),
+                stones.into_iter().map(|(x, y)| SGFProperty::Point((x - 1, y - 1))).collect(),
+            );
+        }
+        for (key, value) in self.info {
+            if !value.is_empty() {
+                root.properties.insert(key.to_string(), vec![SGFProperty::Text(value)]);
+            }
+        }
+
+        if self.moves.len() >= MAX_DEPTH {
+            return Err(format!("More than {} moves", MAX_DEPTH - 1));
+        }
+
+        // Build the main line from the last move backwards
+        let mut next: Option<SGFNode> = None;
+        for (color, point) in self.moves.into_iter().rev() {
+            if let Some((x, y)) = point {
+                if x >= self.size || y >= self.size {
+                    return Err(format!("Move ({}, {}) is off the {}x{} board", x, y, self.size, self.size));
+                }
+            }
+            let key = if color == Stone::Black { "B" } else { "W" };
+            let value = point.map_or(SGFProperty::Text(String::new()), SGFProperty::Point);
+            let mut move_node = node(vec![(key, value)]);
+            move_node.children.extend(next.take());
+            next = Some(move_node);
+        }
+        root.children.extend(next);
+
+        Ok(SGFTree { root, current: 0 })
+    }
+}
+
+fn node(properties: Vec<(&str, SGFProperty)>) -> SGFNode {
+    SGFNode {
+        properties: properties.into_iter().map(|(k, v)| (k.to_string(), vec![v])).collect(),
+        children: Vec::new(),
+    }
+}
+
+/// "name (rank)" into its parts
+fn split_rank(player: &str) -> (String, String) {
+    match player.trim().rsplit_once('(') {
+        Some((name, rank)) => (name.trim().to_string(), rank.trim_end_matches(')').trim().to_string()),
+        None => (player.trim().to_string(), String::new()),
+    }
+}
+
+/// Parses a Tygem GIB record.
+///
+/// The header holds `\[KEY=VALUE\]` lines between `\HS` and `\HE`;
+/// `GAMEINFOMAIN` packs `GONGJE` (komi x10), `GRLT` (result code) and
+/// `ZIPSU` (margin x10). Moves between `\GS` and `\GE` are
+/// `STO 0 <n> <color> <x> <y>` (color 1 black, 2 white, 0-based points),
+/// `SKI 0 <n>` for a pass, and `INI 0 1 <handicap> ...` for the setup.
+pub fn parse_gib(text: &str) -> Result<SGFTree, String> {
+    let mut header: HashMap<&str, &str> = HashMap::new();
+    let mut handicap = 0;
+    let mut moves = Vec::new();
+
+    for line in text.lines().map(str::trim) {
+        if let Some(entry) = line.strip_prefix("\\[").and_then(|l| l.strip_suffix("\\]")) {
+            if let Some((key, value)) = entry.split_once('=') {
+                header.insert(key.trim(), value.trim());
+            }
+            continue;
+        }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let number = |i: usize| -> Result<usize, String> {
+            fields
+                .get(i)
+                .and_then(|f| f.parse().ok())
+                .ok_or_else(|| format!("Malformed GIB line '{}'", line))
+        };
+        match fields.first() {
+            Some(&"INI") => handicap = number(3)?,
+            Some(&"STO") => {
+                let color = if number(3)? == 2 { Stone::White } else { Stone::Black };
+                moves.push((color, Some((number(4)?, number(5)?))));
+            }
+            Some(&"SKI") => {
+                let color = match moves.last() {
+                    Some((Stone::Black, _)) => Stone::White,
+                    Some(_) => Stone::Black,
+                    None if handicap >= 2 => Stone::White,
+                    None => Stone::Black,
+                };
+                moves.push((color, None));
+            }
+            _ => {}
+        }
+    }
+
+    // "GBKIND:3,GTYPE:0,GRLT:0,ZIPSU:35,GONGJE:65,..."
+    let main: HashMap<&str, &str> = header
+        .get("GAMEINFOMAIN")
+        .map(|info| info.split(',').filter_map(|field| field.split_once(':')).collect())
+        .unwrap_or_default();
+    let tenths = |value: Option<&&str>| value.and_then(|v| v.trim().parse::<f32>().ok()).map(|v| v / 10.0);
+    let komi = tenths(main.get("GONGJE"))
+        .or_else(|| tenths(header.get("GAMEGONGJE")))
+        .unwrap_or(if handicap >= 2 { 0.5 } else { 6.5 });
+    let margin = tenths(main.get("ZIPSU")).unwrap_or(0.0);
+    let result = match main.get("GRLT").map(|r| r.trim()) {
+        Some("0") => format!("B+{}", margin),
+        Some("1") => format!("W+{}", margin),
+        Some("3") => "B+R".to_string(),
+        Some("4") => "W+R".to_string(),
+        Some("7") => "B+T".to_string(),
+        Some("8") => "W+T".to_string(),
+        _ => String::new(),
+    };
+
+    let (black, black_rank) = split_rank(header.get("GAMEBLACKNAME").copied().unwrap_or(""));
+    let (white, white_rank) = split_rank(header.get("GAMEWHITENAME").copied().unwrap_or(""));
+    let date: Vec<&str> = header
+        .get("GAMEDATE")
+        .map(|d| d.split('-').map(str::trim).take(3).collect())
+        .unwrap_or_default();
+    let date = match date[..] {
+        [year, month, day] => format!("{}-{:0>2}-{:0>2}", year, month, day),
+        _ => String::new(),
+    };
+
+    Record {
+        size: 19,
+        komi,
+        handicap,
+        info: vec![
+            ("PB", black),
+            ("BR", black_rank),
+            ("PW", white),
+            ("WR", white_rank),
+            ("DT", date),
+            ("PC", header.get("GAMEPLACE").map_or(String::new(), |p| p.to_string())),
+            ("RE", result),
+        ],
+        moves,
+    }
+    .into_tree()
+}
+
+/// Parses a wBaduk NGF record.
+///
+/// Twelve header lines (title, size, white, black, place, handicap, -,
+/// komi, date, -, result, move count) precede `PM<nn><color><xy><xy>`
+/// move lines, where coordinates are letters from `B` (first line); any
+/// other letter is a pass.
+pub fn parse_ngf(text: &str) -> Result<SGFTree, String> {
+    let lines: Vec<&str> = text.lines().map(str::trim).collect();
+    if lines.len() < 12 {
+        return Err("NGF header is incomplete".to_string());
+    }
+    let size: usize = lines[1].parse().map_err(|_| format!("Bad NGF board size '{}'", lines[1]))?;
+    if !(2..=25).contains(&size) {
+        return Err(format!("Unsupported board size {}", size));
+    }
+    let handicap: usize = lines[5].parse().unwrap_or(0);
+    let mut komi: f32 = lines[7].parse().unwrap_or(0.0);
+    // Even games store the integer part of a half-point komi
+    if handicap < 2 && komi.fract() == 0.0 {
+        komi += 0.5;
+    }
+
+    // "name  3D*": the rank is the last word
+    let player = |line: &str| -> (String, String) {
+        match line.rsplit_once(char::is_whitespace) {
+            Some((name, rank)) => (name.trim().to_string(), rank.trim_end_matches('*').to_string()),
+            None => (line.to_string(), String::new()),
+        }
+    };
+    let (white, white_rank) = player(lines[2]);
+    let (black, black_rank) = player(lines[3]);
+
+    let date = lines[8].get(..8).filter(|d| d.bytes().all(|b| b.is_ascii_digit()));
+    let date = date.map_or(String::new(), |d| format!("{}-{}-{}", &d[..4], &d[4..6], &d[6..]));
+
+    // "White wins by resignation", "Black wins by 3.5 points", "White wins on time"
+    let outcome = lines[10].to_lowercase();
+    let winner = if outcome.contains("white win") {
+        "W"
+    } else if outcome.contains("black win") {
+        "B"
+    } else {
+        ""
+    };
+    let result = if winner.is_empty() {
+        String::new()
+    } else if outcome.contains("resign") {
+        format!("{}+R", winner)
+    } else if outcome.contains("time") {
+        format!("{}+T", winner)
+    } else {
+        let margin = outcome.split_whitespace().find_map(|w| w.parse::<f32>().ok());
+        margin.map_or(format!("{}+", winner), |m| format!("{}+{}", winner, m))
+    };
+
+    let coord = |c: u8| -> Option<usize> {
+        let value = c.checked_sub(b'B')? as usize;
+        (value < size).then_some(value)
+    };
+    let mut moves = Vec::new();
+    for line in &lines[12..] {
+        let bytes = line.as_bytes();
+        if !line.starts_with("PM") || bytes.len() < 7 {
+            continue;
+        }
+        let color = match bytes[4] {
+            b'B' => Stone::Black,
+            b'W' => Stone::White,
+            _ => return Err(format!("Bad NGF move line '{}'", line)),
+        };
+        let point = coord(bytes[5]).zip(coord(bytes[6]));
+        moves.push((color, point));
+    }
+
+    Record {
+        size,
+        komi,
+        handicap,
+        info: vec![
+            ("GN", lines[0].to_string()),
+            ("PB", black),
+            ("BR", black_rank),
+            ("PW", white),
+            ("WR", white_rank),
+            ("PC", lines[4].to_string()),
+            ("DT", date),
+            ("RE", result),
+        ],
+        moves,
+    }
+    .into_tree()
+}
@@ -3,13 +3,121 @@
 
 //! SGF (Smart Game Format) file support for GNU Go Rust
 
+pub mod book;
+pub mod import;
+pub mod opening;
+pub mod review;
+#[cfg(feature = "native")]
+pub mod archive;
+
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::{self, BufRead, BufReader, Write};
+use std::fmt;
 use std::path::Path;
 
-use crate::engine::board::{Board, Stone};
+use crate::engine::board::Stone;
 use crate::engine::game::Game;
+use crate::engine::rules::GameRules;
+use crate::fileio;
+
+/// Most nodes on any path from the root. Deeper files are rejected, as
+/// cloning and dropping a tree recurse along its paths.
+pub const MAX_DEPTH: usize = 2000;
+
+/// Most variations nested inside each other
+pub const MAX_NESTING: usize = 256;
+
+type Chars<'a> = std::iter::Peekable<std::str::Chars<'a>>;
+
+/// What was wrong with SGF input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SgfErrorKind {
+    /// A particular character was required, `None` meaning end of input
+    Expected { wanted: char, found: Option<char> },
+    UnexpectedChar(char),
+    MissingIdentifier,
+    /// The tree is deeper than [`MAX_DEPTH`] or nests more than
+    /// [`MAX_NESTING`] variations
+    TooDeep,
+    InvalidUtf8,
+}
+
+/// A parse failure and where it happened: a character offset, or a byte
+/// offset for invalid UTF-8
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SgfError {
+    pub kind: SgfErrorKind,
+    pub position: usize,
+}
+
+impl fmt::Display for SgfError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let position = self.position;
+        match &self.kind {
+            SgfErrorKind::Expected { wanted, found: Some(c) } => {
+                write!(f, "Expected '{}' but found '{}' at position {}", wanted, c, position)
+            }
+            SgfErrorKind::Expected { wanted, found: None } => {
+                write!(f, "Expected '{}' but input ended at position {}", wanted, position)
+            }
+            SgfErrorKind::UnexpectedChar(c) => write!(f, "Unexpected character '{}' at position {}", c, position),
+            SgfErrorKind::MissingIdentifier => write!(f, "Expected identifier at position {}", position),
+            SgfErrorKind::TooDeep => write!(f, "Game tree too deep at position {}", position),
+            SgfErrorKind::InvalidUtf8 => write!(f, "Invalid UTF-8 at byte {}", position),
+        }
+    }
+}
+
+impl std::error::Error for SgfError {}
+
+impl From<SgfError> for String {
+    fn from(e: SgfError) -> Self {
+        e.to_string()
+    }
+}
+
+/// Why a parsed record cannot be set up as a game
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameSetupError {
+    /// SZ is not a size from 2 to 25
+    BoardSize(SGFProperty),
+    /// KM is not a finite number
+    Komi(SGFProperty),
+    /// The game refused a setup stone or move
+    Move(String),
+}
+
+impl fmt::Display for GameSetupError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameSetupError::BoardSize(value) => write!(f, "Unsupported board size {}", value),
+            GameSetupError::Komi(value) => write!(f, "Invalid komi {}", value),
+            GameSetupError::Move(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for GameSetupError {}
+
+impl From<GameSetupError> for String {
+    fn from(e: GameSetupError) -> Self {
+        e.to_string()
+    }
+}
+
+/// Consumes `wanted` or fails
+fn expect(chars: &mut Chars, position: &mut usize, wanted: char) -> Result<(), SgfError> {
+    match chars.next() {
+        Some(c) if c == wanted => {
+            *position += 1;
+            Ok(())
+        }
+        found => Err(SgfError { kind: SgfErrorKind::Expected { wanted, found }, position: *position }),
+    }
+}
+
+fn empty_node() -> SGFNode {
+    SGFNode { properties: HashMap::new(), children: Vec::new() }
+}
 
 /// SGF property types
 #[derive(Debug, Clone, PartialEq)]
@@ -24,6 +132,20 @@ pub enum SGFProperty {
     None,
 }
 
+/// The value as written, without escapes
+impl fmt::Display for SGFProperty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SGFProperty::Number(n) | SGFProperty::Double(n) => write!(f, "{}", n),
+            SGFProperty::Real(r) => write!(f, "{}", r),
+            SGFProperty::Color(c) => write!(f, "{}", stone_to_sgf_color(*c)),
+            SGFProperty::Text(t) => write!(f, "{}", t),
+            SGFProperty::Point((x, y)) | SGFProperty::Move((x, y)) => write!(f, "{}", format_sgf_point(*x, *y)),
+            SGFProperty::None => Ok(()),
+        }
+    }
+}
+
 /// SGF node in the game tree
 #[derive(Debug, Clone)]
 pub struct SGFNode {
@@ -41,51 +163,62 @@ pub struct SGFTree {
 /// SGF file parser and generator
 pub struct SGFHandler;
 
+impl Default for SGFHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl SGFHandler {
     pub fn new() -> Self {
         SGFHandler
     }
 
-    /// Load SGF file and return game tree
+    /// Load SGF file and return game tree. Files ending in `.gib` or `.ngf`
+    /// are read as Tygem or wBaduk records.
     pub fn load_file<P: AsRef<Path>>(&self, path: P) -> Result<SGFTree, String> {
         let path_str = path.as_ref().to_string_lossy().to_string();
-        let file = File::open(path).map_err(|e| format!("Cannot open file '{}': {}", path_str, e))?;
-        let reader = BufReader::new(file);
-        
-        let mut content = String::new();
-        let mut line_number = 0;
-        for line in reader.lines() {
-            line_number += 1;
-            let line_content = line.map_err(|e| format!("Read error at line {} in '{}': {}", line_number, path_str, e))?;
-            content.push_str(&line_content);
+        let bytes = fileio::read(path.as_ref()).map_err(|e| format!("Cannot open file '{}': {}", path_str, e))?;
+        let extension = path.as_ref().extension().map(|e| e.to_string_lossy().to_lowercase());
+        // Player names in these formats are often not UTF-8
+        let imported = match extension.as_deref() {
+            Some("gib") => Some(import::parse_gib(&String::from_utf8_lossy(&bytes))),
+            Some("ngf") => Some(import::parse_ngf(&String::from_utf8_lossy(&bytes))),
+            _ => None,
+        };
+        if let Some(tree) = imported {
+            return tree.map_err(|e| format!("Parse error in '{}': {}", path_str, e));
         }
+        let text = String::from_utf8(bytes).map_err(|e| format!("Read error in '{}': {}", path_str, e))?;
+        let content: String = text.lines().collect();
 
         self.parse(&content).map_err(|e| format!("Parse error in '{}': {}", path_str, e))
     }
 
     /// Parse SGF content string
-    pub fn parse(&self, content: &str) -> Result<SGFTree, String> {
+    pub fn parse(&self, content: &str) -> Result<SGFTree, SgfError> {
         let mut chars = content.chars().peekable();
         let mut position = 0;
         self.parse_tree(&mut chars, &mut position)
     }
 
+    /// Parse SGF from raw bytes, which must be UTF-8. Never panics, so it
+    /// can serve as a cargo-fuzz target.
+    pub fn parse_bytes(&self, bytes: &[u8]) -> Result<SGFTree, SgfError> {
+        let content = std::str::from_utf8(bytes).map_err(|e| SgfError {
+            kind: SgfErrorKind::InvalidUtf8,
+            position: e.valid_up_to(),
+        })?;
+        self.parse(content)
+    }
+
     /// Parse SGF game tree with position tracking
-    fn parse_tree(&self, chars: &mut std::iter::Peekable<std::str::Chars>, position: &mut usize) -> Result<SGFTree, String> {
+    fn parse_tree(&self, chars: &mut Chars, position: &mut usize) -> Result<SGFTree, SgfError> {
         self.skip_whitespace(chars, position);
-        
-        if chars.next() != Some('(') {
-            return Err(format!("Expected '(' at position {}", position));
-        }
-        *position += 1;
-
-        let root = self.parse_node(chars, position)?;
-        
+        expect(chars, position, '(')?;
+        let root = self.parse_sequence(chars, position, 0, 0)?;
         self.skip_whitespace(chars, position);
-        if chars.next() != Some(')') {
-            return Err(format!("Expected ')' at position {}", position));
-        }
-        *position += 1;
+        expect(chars, position, ')')?;
 
         Ok(SGFTree {
             root,
@@ -93,165 +226,148 @@ impl SGFHandler {
         })
     }
 
-    /// Parse SGF node with position tracking
-    fn parse_node(&self, chars: &mut std::iter::Peekable<std::str::Chars>, position: &mut usize) -> Result<SGFNode, String> {
-        self.skip_whitespace(chars, position);
-        
-        if chars.peek() != Some(&';') {
-            return Err(format!("Expected ';' at position {}", position));
+    /// Parse a sequence of nodes and the variations after it, `depth`
+    /// nodes and `nesting` variations below the root. Sequences are read
+    /// iteratively so that only variations recurse.
+    fn parse_sequence(
+        &self,
+        chars: &mut Chars,
+        position: &mut usize,
+        depth: usize,
+        nesting: usize,
+    ) -> Result<SGFNode, SgfError> {
+        let mut sequence = vec![self.parse_node(chars, position)?];
+        let mut variations = Vec::new();
+
+        loop {
+            self.skip_whitespace(chars, position);
+            match chars.peek() {
+                Some(';') if variations.is_empty() => {
+                    if depth + sequence.len() >= MAX_DEPTH {
+                        return Err(SgfError { kind: SgfErrorKind::TooDeep, position: *position });
+                    }
+                    sequence.push(self.parse_node(chars, position)?);
+                }
+                Some('(') => {
+                    if nesting >= MAX_NESTING {
+                        return Err(SgfError { kind: SgfErrorKind::TooDeep, position: *position });
+                    }
+                    chars.next();
+                    *position += 1;
+                    variations.push(self.parse_sequence(chars, position, depth + sequence.len(), nesting + 1)?);
+                    self.skip_whitespace(chars, position);
+                    expect(chars, position, ')')?;
+                }
+                _ => break,
+            }
         }
-        chars.next(); // consume ';'
-        *position += 1;
 
-        let mut properties = HashMap::new();
-        let mut children = Vec::new();
+        let mut last = sequence.pop().unwrap_or_else(empty_node);
+        last.children = variations;
+        while let Some(mut node) = sequence.pop() {
+            node.children.push(last);
+            last = node;
+        }
+        Ok(last)
+    }
+
+    /// Parse one node's properties
+    fn parse_node(&self, chars: &mut Chars, position: &mut usize) -> Result<SGFNode, SgfError> {
+        self.skip_whitespace(chars, position);
+        expect(chars, position, ';')?;
 
-        // Parse properties
+        let mut node = empty_node();
         while let Some(&c) = chars.peek() {
-            if c == '(' || c == ')' {
+            if c == '(' || c == ')' || c == ';' {
                 break;
             }
-            
             if c.is_ascii_uppercase() {
                 let (key, values) = self.parse_property(chars, position)?;
-                properties.insert(key, values);
-            } else if c == ';' {
-                // ';' indicates start of a new node, not end of properties
-                break;
+                node.properties.insert(key, values);
+            } else if c.is_whitespace() {
+                self.skip_whitespace(chars, position);
             } else {
-                return Err(format!("Unexpected character '{}' at position {}", c, position));
-            }
-        }
-
-        // Parse children
-        while let Some(&c) = chars.peek() {
-            match c {
-                ';' => {
-                    children.push(self.parse_node(chars, position)?);
-                }
-                '(' => {
-                    children.push(self.parse_branch(chars, position)?);
-                }
-                _ => break,
+                return Err(SgfError { kind: SgfErrorKind::UnexpectedChar(c), position: *position });
             }
         }
-
-        Ok(SGFNode {
-            properties,
-            children,
-        })
+        Ok(node)
     }
 
     /// Parse property with values and position tracking
-    fn parse_property(&self, chars: &mut std::iter::Peekable<std::str::Chars>, position: &mut usize) -> Result<(String, Vec<SGFProperty>), String> {
+    fn parse_property(&self, chars: &mut Chars, position: &mut usize) -> Result<(String, Vec<SGFProperty>), SgfError> {
         let key = self.read_identifier(chars, position)?;
         let mut values = Vec::new();
 
+        self.skip_whitespace(chars, position);
         while chars.peek() == Some(&'[') {
             chars.next(); // consume '['
             *position += 1;
-            let value = self.parse_value(chars, position)?;
-            values.push(value);
-            
-            if chars.next() != Some(']') {
-                return Err(format!("Expected ']' after property value at position {}", position));
-            }
-            *position += 1;
+            values.push(self.parse_value(chars, position));
+            expect(chars, position, ']')?;
+            self.skip_whitespace(chars, position);
         }
 
         Ok((key, values))
     }
 
     /// Parse property value with position tracking
-    fn parse_value(&self, chars: &mut std::iter::Peekable<std::str::Chars>, position: &mut usize) -> Result<SGFProperty, String> {
+    fn parse_value(&self, chars: &mut Chars, position: &mut usize) -> SGFProperty {
         let mut value_str = String::new();
         
         while let Some(&c) = chars.peek() {
-            if c == ']' || c == '\\' {
+            if c == ']' {
                 break;
             }
+            chars.next();
+            *position += 1;
             if c == '\\' {
-                chars.next(); // consume escape
-                *position += 1;
                 if let Some(c) = chars.next() {
                     value_str.push(c);
                     *position += 1;
                 }
             } else {
-                value_str.push(chars.next().unwrap());
-                *position += 1;
+                value_str.push(c);
             }
         }
 
         // Try to parse as different types
         if let Ok(num) = value_str.parse::<i32>() {
-            return Ok(SGFProperty::Number(num));
+            return SGFProperty::Number(num);
         }
         if let Ok(real) = value_str.parse::<f32>() {
-            return Ok(SGFProperty::Real(real));
-        }
-        if value_str == "1" || value_str == "2" {
-            return Ok(SGFProperty::Double(value_str.parse().unwrap()));
+            return SGFProperty::Real(real);
         }
         if value_str == "B" {
-            return Ok(SGFProperty::Color(Stone::Black));
+            return SGFProperty::Color(Stone::Black);
         }
         if value_str == "W" {
-            return Ok(SGFProperty::Color(Stone::White));
+            return SGFProperty::Color(Stone::White);
         }
-        if value_str.len() == 2 {
-            if let Some((x, y)) = self.parse_point(&value_str) {
-                return Ok(SGFProperty::Point((x, y)));
-            }
+        if let Some((x, y)) = self.parse_point(&value_str) {
+            return SGFProperty::Point((x, y));
         }
 
-        Ok(SGFProperty::Text(value_str))
+        SGFProperty::Text(value_str)
     }
 
     /// Parse SGF point (e.g., "dd")
     fn parse_point(&self, s: &str) -> Option<(usize, usize)> {
-        if s.len() != 2 {
-            return None;
-        }
-        
-        let mut chars = s.chars();
-        let col_char = chars.next()?;
-        let row_char = chars.next()?;
-        
-        if !col_char.is_ascii_lowercase() || !row_char.is_ascii_lowercase() {
-            return None;
-        }
-        
-        let x = (col_char as u8 - b'a') as usize;
-        let y = (row_char as u8 - b'a') as usize;
-        
-        Some((x, y))
-    }
-
-    /// Parse branch (subtree) with position tracking
-    fn parse_branch(&self, chars: &mut std::iter::Peekable<std::str::Chars>, position: &mut usize) -> Result<SGFNode, String> {
-        if chars.next() != Some('(') {
-            return Err(format!("Expected '(' at position {}", position));
-        }
-        *position += 1;
-        
-        let node = self.parse_node(chars, position)?;
-        
-        if chars.next() != Some(')') {
-            return Err(format!("Expected ')' at position {}", position));
+        match s.as_bytes() {
+            &[col, row] if col.is_ascii_lowercase() && row.is_ascii_lowercase() => {
+                Some(((col - b'a') as usize, (row - b'a') as usize))
+            }
+            _ => None,
         }
-        *position += 1;
-        
-        Ok(node)
     }
 
     /// Read identifier (property key) with position tracking
-    fn read_identifier(&self, chars: &mut std::iter::Peekable<std::str::Chars>, position: &mut usize) -> Result<String, String> {
+    fn read_identifier(&self, chars: &mut Chars, position: &mut usize) -> Result<String, SgfError> {
         let mut ident = String::new();
         
         while let Some(&c) = chars.peek() {
             if c.is_ascii_uppercase() {
-                ident.push(chars.next().unwrap());
+                ident.push(c);
+                chars.next();
                 *position += 1;
             } else {
                 break;
@@ -259,14 +375,14 @@ impl SGFHandler {
         }
         
         if ident.is_empty() {
-            return Err(format!("Expected identifier at position {}", position));
+            return Err(SgfError { kind: SgfErrorKind::MissingIdentifier, position: *position });
         }
         
         Ok(ident)
     }
 
     /// Skip whitespace characters with position tracking
-    fn skip_whitespace(&self, chars: &mut std::iter::Peekable<std::str::Chars>, position: &mut usize) {
+    fn skip_whitespace(&self, chars: &mut Chars, position: &mut usize) {
         while let Some(&c) = chars.peek() {
             if c.is_whitespace() {
                 chars.next();
@@ -277,6 +393,14 @@ impl SGFHandler {
         }
     }
 
+    /// Serializes a game tree; root properties come first, others sorted
+    pub fn tree_to_sgf(&self, tree: &SGFTree) -> String {
+        let mut sgf = String::from("(");
+        write_node(&tree.root, &mut sgf);
+        sgf.push_str(")\n");
+        sgf
+    }
+
     /// Convert game to SGF format with move history (aligned with GNU Go behavior)
     pub fn game_to_sgf(&self, game: &Game, filename: Option<&str>) -> Result<String, String> {
         let mut sgf = String::new();
@@ -288,18 +412,24 @@ impl SGFHandler {
         sgf.push_str(&game.komi.to_string());
         
         // Add GNU Go version information (like original)
-        sgf.push_str("]GN[GNU Go Rust load and print]\n");
+        sgf.push_str("]GN[GNU Go Rust load and print]");
+        for (key, color) in [("PB", Stone::Black), ("PW", Stone::White)] {
+            if let Some(label) = game.team_label(color) {
+                sgf.push_str(&format!("{}[{}]", key, label.replace('\\', "\\\\").replace(']', "\\]")));
+            }
+        }
+        sgf.push('\n');
 
         // Export current board state as setup properties (aligned with GNU Go)
         if game.board.size() > 0 {
             let mut black_stones = Vec::new();
             let mut white_stones = Vec::new();
             
-            for y in 0..game.board.size() {
-                for x in 0..game.board.size() {
+            for y in 1..=game.board.size() {
+                for x in 1..=game.board.size() {
                     let stone = game.board.get_stone(x, y);
                     if stone != Stone::Empty {
-                        let point = format_sgf_point(x, y);
+                        let point = format_sgf_point(x - 1, y - 1);
                         match stone {
                             Stone::Black => black_stones.push(point),
                             Stone::White => white_stones.push(point),
@@ -330,10 +460,8 @@ impl SGFHandler {
         // Write to file if filename provided (matches GNU Go behavior)
         if let Some(filename) = filename {
             if filename != "-" {  // GNU Go uses "-" for stdout
-                let mut file = File::create(filename)
-                    .map_err(|e| format!("Cannot create file '{}': {}", filename, e))?;
-                file.write_all(sgf.as_bytes())
-                    .map_err(|e| format!("Write error to '{}': {}", filename, e))?;
+                fileio::write(Path::new(filename), sgf.as_bytes())
+                    .map_err(|e| format!("Cannot write file '{}': {}", filename, e))?;
                 return Ok("".to_string());  // Empty string when writing to file
             }
         }
@@ -342,64 +470,178 @@ impl SGFHandler {
     }
 
     /// Apply SGF tree to game
-    pub fn apply_to_game(&self, tree: &SGFTree, game: &mut Game) -> Result<(), String> {
+    pub fn apply_to_game(&self, tree: &SGFTree, game: &mut Game) -> Result<(), GameSetupError> {
         // Start with empty board of correct size
-        if let Some(SGFProperty::Number(size)) = tree.root.properties.get("SZ").and_then(|v| v.first()) {
-            *game = Game::new(*size as usize);
+        match tree.root.properties.get("SZ").and_then(|v| v.first()) {
+            Some(&SGFProperty::Number(size)) if (2..=25).contains(&size) => *game = Game::new(size as usize),
+            Some(size) => return Err(GameSetupError::BoardSize(size.clone())),
+            None => {}
         }
 
-        // Apply komi
-        if let Some(SGFProperty::Real(komi)) = tree.root.properties.get("KM").and_then(|v| v.first()) {
-            game.komi = *komi;
+        // Apply komi; an empty KM keeps the current komi
+        match tree.root.properties.get("KM").and_then(|v| v.first()) {
+            Some(SGFProperty::Real(komi)) if komi.is_finite() => game.komi = *komi,
+            Some(SGFProperty::Number(komi)) => game.komi = *komi as f32,
+            Some(SGFProperty::Text(text)) if text.trim().is_empty() => {}
+            Some(SGFProperty::None) | None => {}
+            Some(komi) => return Err(GameSetupError::Komi(komi.clone())),
+        }
+        if let Some(SGFProperty::Number(handicap)) = tree.root.properties.get("HA").and_then(|v| v.first()) {
+            game.handicap = (*handicap).max(0) as usize;
+        }
+        // Rulesets this engine does not know keep the current rules
+        if let Some(SGFProperty::Text(ruleset)) = tree.root.properties.get("RU").and_then(|v| v.first()) {
+            if let Some(rules) = GameRules::from_name(ruleset) {
+                game.rules = rules;
+            }
         }
 
         // Apply moves from SGF tree
-        self.apply_moves(&tree.root, game)
+        self.apply_moves(&tree.root, game).map_err(GameSetupError::Move)
     }
 
-    /// Recursively apply moves from SGF node
-    fn apply_moves(&self, node: &SGFNode, game: &mut Game) -> Result<(), String> {
-        // Save game state for branch support
-        let game_snapshot = game.clone();
-        
+    /// Apply moves from SGF node, following the main line
+    fn apply_moves(&self, root: &SGFNode, game: &mut Game) -> Result<(), String> {
+        let mut node = Some(root);
+        while let Some(current) = node {
+            self.apply_node(current, game)?;
+            node = current.children.first();
+        }
+        Ok(())
+    }
+
+    /// Applies the setup, player to move and moves of one node
+    pub(crate) fn apply_node(&self, node: &SGFNode, game: &mut Game) -> Result<(), String> {
+        // Apply setup stones (AB/AW/AE) from this node
+        for (key, stone) in [("AB", Stone::Black), ("AW", Stone::White), ("AE", Stone::Empty)] {
+            if let Some(points) = node.properties.get(key) {
+                for point in points {
+                    if let SGFProperty::Point((x, y)) = point {
+                        game.setup_stone(*x + 1, *y + 1, stone)?;
+                    }
+                }
+            }
+        }
+        if let Some(SGFProperty::Color(color)) = node.properties.get("PL").and_then(|v| v.first()) {
+            game.set_to_move(*color);
+        }
+
         // Apply moves from this node
         // SGF uses (a, b) where a=column (x), b=row from top (y in our system)
-        if let Some(moves) = node.properties.get("B") {
-            for mv in moves {
-                if let SGFProperty::Point((x, y)) = mv {
-                    game.make_move(*x, *y)
-                        .map_err(|e| format!("Failed to apply black move: {}", e))?;
+        for (key, color) in [("B", Stone::Black), ("W", Stone::White)] {
+            if let Some(moves) = node.properties.get(key) {
+                for mv in moves {
+                    let point = match mv {
+                        SGFProperty::Point((x, y)) if !is_sgf_pass(*x, *y, game.board.size()) => Some((*x + 1, *y + 1)),
+                        SGFProperty::Point(_) => None,
+                        SGFProperty::Text(t) if t.is_empty() => None,
+                        _ => continue,
+                    };
+                    game.play_move(color, point)
+                        .map_err(|e| format!("Failed to apply {} move: {}", color.to_string().to_lowercase(), e))?;
                 }
             }
         }
-        
-        if let Some(moves) = node.properties.get("W") {
-            for mv in moves {
-                if let SGFProperty::Point((x, y)) = mv {
-                    game.make_move(*x, *y)
-                        .map_err(|e| format!("Failed to apply white move: {}", e))?;
-                }
+
+        Ok(())
+    }
+}
+
+/// Writes `node` and its main line; further children become variations
+fn write_node(node: &SGFNode, sgf: &mut String) {
+    let mut node = node;
+    loop {
+        sgf.push(';');
+        let mut keys: Vec<&String> = node.properties.keys().collect();
+        keys.sort_by_key(|k| (!["FF", "GM", "SZ"].contains(&k.as_str()), k.as_str()));
+        for key in keys {
+            sgf.push_str(key);
+            for value in &node.properties[key] {
+                let text = match value {
+                    SGFProperty::Number(n) | SGFProperty::Double(n) => n.to_string(),
+                    SGFProperty::Real(r) => r.to_string(),
+                    SGFProperty::Color(c) => stone_to_sgf_color(*c).to_string(),
+                    SGFProperty::Text(t) => t.replace('\\', "\\\\").replace(']', "\\]"),
+                    SGFProperty::Point((x, y)) | SGFProperty::Move((x, y)) => format_sgf_point(*x, *y),
+                    SGFProperty::None => String::new(),
+                };
+                sgf.push('[');
+                sgf.push_str(&text);
+                sgf.push(']');
             }
         }
 
-        // Recursively apply moves from children (branches)
-        for (i, child) in node.children.iter().enumerate() {
-            if i > 0 {
-                // For branches beyond the first, restore snapshot
-                *game = game_snapshot.clone();
+        // The main line continues in a loop, so only variations recurse
+        match node.children.as_slice() {
+            [] => return,
+            [only] => {
+                if node.properties.contains_key("B") || node.properties.contains_key("W") {
+                    if sgf.rsplit('\n').next().map_or(0, str::len) > 60 {
+                        sgf.push('\n');
+                    }
+                } else {
+                    sgf.push('\n');
+                }
+                node = only;
+            }
+            children => {
+                for child in children {
+                    sgf.push_str("\n(");
+                    write_node(child, sgf);
+                    sgf.push(')');
+                }
+                return;
             }
-            self.apply_moves(child, game)?;
         }
+    }
+}
 
-        Ok(())
+/// Whether `node` plays a move
+pub fn is_move_node(node: &SGFNode) -> bool {
+    node.properties.contains_key("B") || node.properties.contains_key("W")
+}
+
+/// Calls `f` with every node of the main line and the number of moves
+/// played up to and including it
+pub fn for_each_main_line_node(tree: &mut SGFTree, mut f: impl FnMut(usize, &mut SGFNode)) {
+    let mut node = &mut tree.root;
+    let mut move_number = 0;
+    loop {
+        if is_move_node(node) {
+            move_number += 1;
+        }
+        f(move_number, node);
+        match node.children.first_mut() {
+            Some(child) => node = child,
+            None => return,
+        }
     }
 }
 
+/// Adds a paragraph to the comment of `node`
+pub fn append_comment(node: &mut SGFNode, text: &str) {
+    let comment = node.properties.entry("C".to_string()).or_default();
+    let text = match comment.first() {
+        Some(SGFProperty::Text(t)) if !t.is_empty() => format!("{}\n{}", t, text),
+        _ => text.to_string(),
+    };
+    *comment = vec![SGFProperty::Text(text)];
+}
+
+/// "tt" denotes a pass on boards up to 19x19 (FF[3] convention)
+fn is_sgf_pass(x: usize, y: usize, size: usize) -> bool {
+    size <= 19 && x == 19 && y == 19
+}
+
 /// Format point to SGF format (e.g., "dd")
 pub fn format_sgf_point(x: usize, y: usize) -> String {
-    let col_char = (b'a' + x as u8) as char;
-    let row_char = (b'a' + y as u8) as char;
-    format!("{}{}", col_char, row_char)
+    // FF[4] continues with upper case letters past 26 lines
+    let letter = |v: usize| match v {
+        0..=25 => (b'a' + v as u8) as char,
+        26..=51 => (b'A' + (v - 26) as u8) as char,
+        _ => '?',
+    };
+    format!("{}{}", letter(x), letter(y))
 }
 
 /// Convert Stone to SGF color
@@ -409,4 +651,31 @@ pub fn stone_to_sgf_color(stone: Stone) -> &'static str {
         Stone::White => "W",
         Stone::Empty => "",
     }
-}
\ No newline at end of file
+}
+/// Build an SGF game record from a move sequence. Moves use 1-based board
+/// coordinates, `None` being a pass; `extra` holds additional root
+/// properties such as PB, PW or RE.
+pub fn record_to_sgf(
+    size: usize,
+    komi: f32,
+    moves: &[(Stone, Option<(usize, usize)>)],
+    extra: &[(&str, &str)],
+) -> String {
+    let mut sgf = format!("(;FF[4]GM[1]SZ[{}]KM[{}]", size, komi);
+    for (key, value) in extra {
+        let escaped = value.replace('\\', "\\\\").replace(']', "\\]");
+        sgf.push_str(&format!("{}[{}]", key, escaped));
+    }
+    sgf.push('\n');
+
+    for (i, (color, point)) in moves.iter().enumerate() {
+        let coord = point.map(|(x, y)| format_sgf_point(x - 1, y - 1)).unwrap_or_default();
+        sgf.push_str(&format!(";{}[{}]", stone_to_sgf_color(*color), coord));
+        if (i + 1) % 10 == 0 {
+            sgf.push('\n');
+        }
+    }
+
+    sgf.push_str(")\n");
+    sgf
+}
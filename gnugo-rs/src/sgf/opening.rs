@@ -0,0 +1,83 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Opening recognition for game records
+//!
+//! Replays the main line and notes the move at which each player's fuseki
+//! becomes recognizable and each corner reaches a named joseki position.
+
+use super::{append_comment, for_each_main_line_node, is_move_node, SGFHandler, SGFTree};
+use crate::engine::board::Stone;
+use crate::engine::game::Game;
+use crate::patterns::{fuseki, joseki};
+use crate::patterns::joseki::Corner;
+
+/// Moves replayed before recognition stops
+pub const OPENING_MOVES: usize = 60;
+
+/// Something recognized at a move
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpeningEvent {
+    /// Move nodes played so far, passes included
+    pub move_number: usize,
+    pub description: String,
+}
+
+/// Fuseki and joseki found in the first [`OPENING_MOVES`] moves of `tree`
+pub fn recognize(tree: &SGFTree) -> Result<Vec<OpeningEvent>, String> {
+    let handler = SGFHandler::new();
+    let mut root = tree.root.clone();
+    root.children.clear();
+    let mut game = Game::new(19);
+    handler.apply_to_game(&SGFTree { root, current: 0 }, &mut game)?;
+
+    let corners = [Corner::UpperLeft, Corner::UpperRight, Corner::LowerLeft, Corner::LowerRight];
+    let corner_names = ["Upper left", "Upper right", "Lower left", "Lower right"];
+    let mut fuseki_seen: [Option<&str>; 2] = [None, None];
+    let mut joseki_seen: [Option<&str>; 4] = [None; 4];
+    let mut events = Vec::new();
+    let mut move_number = usize::from(is_move_node(&tree.root));
+    let mut node = tree.root.children.first();
+
+    while let Some(current) = node {
+        if move_number >= OPENING_MOVES {
+            break;
+        }
+        handler.apply_node(current, &mut game)?;
+        if is_move_node(current) {
+            move_number += 1;
+            for (i, color) in [Stone::Black, Stone::White].into_iter().enumerate() {
+                let name = fuseki::identify(&game.board, color);
+                if name.is_some() && name != fuseki_seen[i] {
+                    fuseki_seen[i] = name;
+                    let player = if color == Stone::Black { "Black" } else { "White" };
+                    events.push(OpeningEvent { move_number, description: format!("{}: {}", player, name.unwrap_or_default()) });
+                }
+            }
+            for (i, &corner) in corners.iter().enumerate() {
+                let name = joseki::identify(&game.board, corner, game.current_player()).and_then(|found| found.name);
+                if name.is_some() && name != joseki_seen[i] {
+                    joseki_seen[i] = name;
+                    events.push(OpeningEvent {
+                        move_number,
+                        description: format!("{} corner: {}", corner_names[i], name.unwrap_or_default()),
+                    });
+                }
+            }
+        }
+        node = current.children.first();
+    }
+    Ok(events)
+}
+
+/// Appends each event to the comment of its move node on the main line
+pub fn annotate(tree: &mut SGFTree, events: &[OpeningEvent]) {
+    for_each_main_line_node(tree, |move_number, node| {
+        if !is_move_node(node) {
+            return;
+        }
+        for event in events.iter().filter(|e| e.move_number == move_number) {
+            append_comment(node, &event.description);
+        }
+    });
+}
@@ -0,0 +1,251 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Move-by-move review of a game record against the engine's choices, and
+//! a rough rank estimate built on it
+//!
+//! The rank comes from how often a player picks the engine's move, how
+//! much evaluation their moves give up on average and how often they
+//! blunder. It is calibrated against this engine's own evaluation, so it
+//! suits comparing players and pairing them more than certifying a rank.
+
+use std::fmt;
+
+use super::{is_move_node, SGFHandler, SGFNode, SGFProperty, SGFTree};
+use crate::engine::analysis::candidate_moves;
+use crate::engine::board::{Board, Stone};
+use crate::engine::evaluation::Evaluator;
+use crate::engine::game::Game;
+use crate::json::JsonValue;
+
+/// Evaluation loss from which a move counts as a blunder
+pub const BLUNDER_LOSS: i32 = 10;
+
+/// Fewest reviewed moves for a rank estimate
+pub const MIN_RANKED_MOVES: usize = 10;
+
+/// The engine's view of one move
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoveReview {
+    /// Move nodes played so far, passes included
+    pub move_number: usize,
+    pub color: Stone,
+    /// `None` for a pass
+    pub played: Option<(usize, usize)>,
+    /// The engine's choice; `None` when no move was legal
+    pub best: Option<(usize, usize)>,
+    /// Evaluation after the engine's move minus after the played one,
+    /// from the mover's point of view
+    pub loss: i32,
+    /// Place of the played move in the engine's ranking, 0 for its choice;
+    /// `None` for a pass
+    pub engine_rank: Option<usize>,
+}
+
+/// Evaluation of the position for `color`
+fn value_for(board: &Board, color: Stone) -> i32 {
+    let score = Evaluator::evaluate_position(board);
+    if color == Stone::Black { score } else { -score }
+}
+
+/// Color and point of the move in `node`, 1-based, `None` passing
+fn node_move(node: &SGFNode, size: usize) -> Option<(Stone, Option<(usize, usize)>)> {
+    let (color, values) = [("B", Stone::Black), ("W", Stone::White)]
+        .into_iter()
+        .find_map(|(key, color)| node.properties.get(key).map(|v| (color, v)))?;
+    let point = match values.first() {
+        Some(SGFProperty::Point((x, y))) if *x < size && *y < size => Some((x + 1, y + 1)),
+        _ => None,
+    };
+    Some((color, point))
+}
+
+/// Replays the main line of `tree` and reviews every move after the root
+pub fn review_moves(tree: &SGFTree) -> Result<Vec<MoveReview>, String> {
+    let handler = SGFHandler::new();
+    let mut root = tree.root.clone();
+    root.children.clear();
+    let mut game = Game::new(19);
+    handler.apply_to_game(&SGFTree { root, current: 0 }, &mut game)?;
+
+    let size = game.board.size();
+    let mut reviews = Vec::new();
+    let mut move_number = usize::from(is_move_node(&tree.root));
+    let mut node = tree.root.children.first();
+    while let Some(current) = node {
+        let before = game.board.clone();
+        handler.apply_node(current, &mut game)?;
+        if let Some((color, played)) = node_move(current, size) {
+            move_number += 1;
+            let candidates = candidate_moves(&before, color, usize::MAX);
+            let best = candidates.first();
+            reviews.push(MoveReview {
+                move_number,
+                color,
+                played,
+                best: best.map(|c| c.point),
+                loss: best.map_or(0, |c| c.value - value_for(&game.board, color)),
+                engine_rank: played.and_then(|p| candidates.iter().position(|c| c.point == p)),
+            });
+        }
+        node = current.children.first();
+    }
+    Ok(reviews)
+}
+
+/// Rank the record gives the player of `color`, from `BR` or `WR`
+pub fn player_rank(tree: &SGFTree, color: Stone) -> Option<Rank> {
+    let key = if color == Stone::Black { "BR" } else { "WR" };
+    match tree.root.properties.get(key).and_then(|v| v.first()) {
+        Some(SGFProperty::Text(rank)) => Rank::parse(rank),
+        _ => None,
+    }
+}
+
+/// Colors that `name` played in `tree`, matched against `PB` and `PW`
+/// without regard to case
+pub fn player_colors(tree: &SGFTree, name: &str) -> Vec<Stone> {
+    let name = name.to_lowercase();
+    [("PB", Stone::Black), ("PW", Stone::White)]
+        .into_iter()
+        .filter(|(key, _)| match tree.root.properties.get(*key).and_then(|v| v.first()) {
+            Some(SGFProperty::Text(player)) => player.to_lowercase().contains(&name),
+            _ => false,
+        })
+        .map(|(_, color)| color)
+        .collect()
+}
+
+/// An approximate Go rank
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rank {
+    Kyu(u32),
+    Dan(u32),
+}
+
+impl Rank {
+    /// Reads an SGF rank such as `5k`, `3d` or `2p`, professionals
+    /// counting as 9 dan; a trailing `?` or `*` is ignored
+    pub fn parse(text: &str) -> Option<Rank> {
+        let text = text.trim().trim_end_matches(['?', '*']).to_lowercase();
+        let kind = text.chars().last()?;
+        let number: u32 = text[..text.len() - kind.len_utf8()].trim().parse().ok().filter(|&n| n > 0)?;
+        match kind {
+            'k' => Some(Rank::Kyu(number)),
+            'd' => Some(Rank::Dan(number)),
+            'p' => Some(Rank::Dan(9)),
+            _ => None,
+        }
+    }
+
+    /// Whether this rank is `other` or stronger
+    pub fn at_least(&self, other: Rank) -> bool {
+        let level = |rank: &Rank| match *rank {
+            Rank::Kyu(n) => 1 - n as i32,
+            Rank::Dan(n) => n as i32,
+        };
+        level(self) >= level(&other)
+    }
+}
+
+impl fmt::Display for Rank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rank::Kyu(n) => write!(f, "{} kyu", n),
+            Rank::Dan(n) => write!(f, "{} dan", n),
+        }
+    }
+}
+
+/// Totals over the reviewed moves of one player
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PlayerStats {
+    /// Stones played; passes are not counted
+    pub moves: usize,
+    /// Moves that were the engine's choice
+    pub matches: usize,
+    /// Moves among the engine's three best
+    pub top3: usize,
+    /// Moves losing at least [`BLUNDER_LOSS`]
+    pub blunders: usize,
+    pub total_loss: i64,
+}
+
+impl PlayerStats {
+    /// Adds the moves of `color` in `reviews`
+    pub fn add(&mut self, reviews: &[MoveReview], color: Stone) {
+        for review in reviews.iter().filter(|r| r.color == color && r.played.is_some()) {
+            self.moves += 1;
+            self.matches += usize::from(review.engine_rank == Some(0));
+            self.top3 += usize::from(review.engine_rank.is_some_and(|r| r < 3));
+            self.blunders += usize::from(review.loss >= BLUNDER_LOSS);
+            self.total_loss += i64::from(review.loss.max(0));
+        }
+    }
+
+    fn rate(&self, count: usize) -> f32 {
+        if self.moves == 0 { 0.0 } else { count as f32 / self.moves as f32 }
+    }
+
+    /// Share of moves that were the engine's choice
+    pub fn agreement(&self) -> f32 {
+        self.rate(self.matches)
+    }
+
+    /// Share of moves among the engine's three best
+    pub fn top3_agreement(&self) -> f32 {
+        self.rate(self.top3)
+    }
+
+    /// Share of moves that were blunders
+    pub fn blunder_rate(&self) -> f32 {
+        self.rate(self.blunders)
+    }
+
+    /// Mean evaluation given up per move
+    pub fn average_loss(&self) -> f32 {
+        if self.moves == 0 { 0.0 } else { self.total_loss as f32 / self.moves as f32 }
+    }
+
+    /// Estimated rank, from 30 kyu to 9 dan; `None` below
+    /// [`MIN_RANKED_MOVES`] moves
+    pub fn rank(&self) -> Option<Rank> {
+        if self.moves < MIN_RANKED_MOVES {
+            return None;
+        }
+        // Each point of average loss costs about two ranks, each percent
+        // of blunders one, and agreeing with the engine gains up to twenty
+        let score = 2.0 * self.average_loss() + 100.0 * self.blunder_rate() - 20.0 * self.agreement();
+        let kyu = (score.round() as i32).clamp(-8, 30);
+        Some(if kyu >= 1 { Rank::Kyu(kyu as u32) } else { Rank::Dan((1 - kyu) as u32) })
+    }
+
+    /// `{moves, agreement, top3_agreement, blunders, blunder_rate, average_loss, rank}`
+    pub fn to_json(&self) -> JsonValue {
+        JsonValue::object(vec![
+            ("moves", self.moves.into()),
+            ("agreement", self.agreement().into()),
+            ("top3_agreement", self.top3_agreement().into()),
+            ("blunders", self.blunders.into()),
+            ("blunder_rate", self.blunder_rate().into()),
+            ("average_loss", self.average_loss().into()),
+            ("rank", self.rank().map(|r| r.to_string()).into()),
+        ])
+    }
+}
+
+/// Totals over `trees` for the colors `colors` picks in each record
+pub fn collect_stats(trees: &[SGFTree], colors: impl Fn(&SGFTree) -> Vec<Stone>) -> Result<PlayerStats, String> {
+    let mut stats = PlayerStats::default();
+    for tree in trees {
+        let colors = colors(tree);
+        if colors.is_empty() {
+            continue;
+        }
+        let reviews = review_moves(tree)?;
+        for color in colors {
+            stats.add(&reviews, color);
+        }
+    }
+    Ok(stats)
+}
@@ -0,0 +1,71 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Plain-text output for screen readers
+//!
+//! Moves are announced as sentences and the board is read out row by row,
+//! without box-drawing characters or ANSI escapes. Vertices follow the
+//! terminal client: a column letter (skipping I) and the row counted from
+//! the top, as typed at its prompt.
+
+use crate::engine::board::{Board, Stone};
+use crate::ui::i18n::Messages;
+
+/// Terminal client name of (x, y), e.g. "D4"
+pub fn vertex_name(x: usize, y: usize) -> String {
+    let column = if x <= 8 { (b'A' + x as u8 - 1) as char } else { (b'A' + x as u8) as char };
+    format!("{}{}", column, y)
+}
+
+fn color_name(messages: &Messages, color: Stone) -> &'static str {
+    messages.get(if color == Stone::Black { "Black" } else { "White" })
+}
+
+/// Sentence for `color` playing `point` (`None` passes), given the
+/// position before and after the move
+pub fn announce_move(messages: &Messages, before: &Board, after: &Board, color: Stone, point: Option<(usize, usize)>) -> String {
+    let name = color_name(messages, color);
+    let Some((x, y)) = point else {
+        return messages.format("{} passes.", &[&name]);
+    };
+    let opponent = if color == Stone::Black { Stone::White } else { Stone::Black };
+    let captured = before.stones_on_board(opponent).saturating_sub(after.stones_on_board(opponent));
+    let vertex = vertex_name(x, y);
+    match captured {
+        0 => messages.format("{} plays {}.", &[&name, &vertex]),
+        1 => messages.format("{} plays {}, captures 1 stone.", &[&name, &vertex]),
+        n => messages.format("{} plays {}, captures {} stones.", &[&name, &vertex, &n]),
+    }
+}
+
+/// Contents of row `y`, e.g. "Row 4: Black D4, White Q4."
+pub fn describe_row(messages: &Messages, board: &Board, y: usize) -> String {
+    let stones: Vec<String> = (1..=board.size())
+        .filter_map(|x| match board.get_stone(x, y) {
+            Stone::Empty => None,
+            stone => Some(format!("{} {}", color_name(messages, stone), vertex_name(x, y))),
+        })
+        .collect();
+    if stones.is_empty() {
+        messages.format("Row {}: empty.", &[&y])
+    } else {
+        messages.format("Row {}: {}.", &[&y, &stones.join(messages.get(", "))])
+    }
+}
+
+/// Every row holding stones, top to bottom, one per line
+pub fn describe_board(messages: &Messages, board: &Board) -> String {
+    let rows: Vec<String> = (1..=board.size())
+        .filter(|&y| (1..=board.size()).any(|x| board.get_stone(x, y) != Stone::Empty))
+        .map(|y| describe_row(messages, board, y))
+        .collect();
+    if rows.is_empty() {
+        return messages.get("The board is empty.").to_string();
+    }
+    let mut text = rows.join("\n");
+    if rows.len() < board.size() {
+        text.push('\n');
+        text.push_str(messages.get("Other rows are empty."));
+    }
+    text
+}
@@ -27,6 +27,23 @@ impl Default for RenderConfig {
     }
 }
 
+impl RenderConfig {
+    /// Built-in theme by name: "unicode" (the default symbols) or "ascii"
+    pub fn from_theme(name: &str) -> Option<Self> {
+        match name {
+            "unicode" => Some(Self::default()),
+            "ascii" => Some(Self {
+                empty_symbol: '.',
+                black_symbol: 'X',
+                white_symbol: 'O',
+                use_colors: false,
+                show_coordinates: true,
+            }),
+            _ => None,
+        }
+    }
+}
+
 /// Configurable board renderer
 pub struct BoardRenderer {
     config: RenderConfig,
@@ -45,6 +62,11 @@ impl BoardRenderer {
     
     /// Renders the board to a string
     pub fn render(&self, board: &Board) -> String {
+        self.render_with_last_move(board, None)
+    }
+
+    /// Renders the board with the stone at `last_move` in parentheses
+    pub fn render_with_last_move(&self, board: &Board, last_move: Option<(usize, usize)>) -> String {
         let mut output = String::new();
         
         if self.config.show_coordinates {
@@ -70,7 +92,9 @@ impl BoardRenderer {
                 let stone = board.get_stone(x, y);
                 let symbol = self.stone_to_symbol(stone);
                 
-                if self.config.use_colors {
+                if last_move == Some((x, y)) {
+                    output.push_str(&self.mark(symbol, stone));
+                } else if self.config.use_colors {
                     output.push_str(&self.colorize(symbol, stone));
                 } else {
                     output.push_str(&format!(" {} ", symbol));
@@ -104,6 +128,15 @@ impl BoardRenderer {
         }
     }
     
+    /// The last move's symbol in parentheses, colored like its stone
+    fn mark(&self, symbol: char, stone: Stone) -> String {
+        match stone {
+            Stone::Black if self.config.use_colors => format!("\x1b[34m({})\x1b[0m", symbol),
+            Stone::White if self.config.use_colors => format!("\x1b[37m({})\x1b[0m", symbol),
+            _ => format!("({})", symbol),
+        }
+    }
+
     /// Updates render configuration
     pub fn update_config(&mut self, config: RenderConfig) {
         self.config = config;
@@ -10,7 +10,9 @@ use crate::engine::board::{Board, Stone};
 /// - X increases from left to right: 1, 2, 3, ..., 19 (columns A, B, C...)
 /// - Y increases from top to bottom: 1, 2, 3, ..., 19 (rows)
 /// - Display shows row 1 at top, row 19 at bottom (like a spreadsheet)
-pub fn draw_board(board: &Board) {
+///
+/// The stone at `last_move` is drawn in parentheses.
+pub fn draw_board(board: &Board, last_move: Option<(usize, usize)>) {
     let size = board.size();
     
     // Column headers (A-H, J-T) - skip I as per Go convention
@@ -30,6 +32,8 @@ pub fn draw_board(board: &Board) {
         print!("{:2}", y);
         for x in 1..=size {
             match board.get_stone(x, y) {
+                Stone::Black if last_move == Some((x, y)) => print!("(O)"),
+                Stone::White if last_move == Some((x, y)) => print!("(X)"),
                 Stone::Black => print!(" O "),
                 Stone::White => print!(" X "),
                 Stone::Empty => {
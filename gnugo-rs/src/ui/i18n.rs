@@ -0,0 +1,155 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! Message catalog for the terminal UI
+//!
+//! Messages are looked up by their English text, gettext style, so one
+//! without a translation simply shows in English. `{}` marks are filled
+//! in order by [`Messages::format`].
+
+use std::env;
+use std::fmt::Display;
+
+/// Languages with a catalog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    English,
+    Chinese,
+}
+
+impl Language {
+    /// Parses a name ("en", "chinese") or a locale such as "zh_CN.UTF-8"
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_lowercase();
+        let code = name.split(['_', '-', '.', '@']).next().unwrap_or("");
+        match code {
+            "en" | "english" | "c" | "posix" => Some(Language::English),
+            "zh" | "chinese" => Some(Language::Chinese),
+            _ => None,
+        }
+    }
+
+    /// Language of the user's locale (`LC_ALL`, `LC_MESSAGES`, then `LANG`),
+    /// English when unset or without a catalog
+    pub fn from_env() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|locale| Language::from_name(&locale))
+            .unwrap_or(Language::English)
+    }
+
+    /// Two-letter language code
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Chinese => "zh",
+        }
+    }
+}
+
+/// Translated messages for one language
+#[derive(Debug, Clone, Copy)]
+pub struct Messages {
+    language: Language,
+}
+
+impl Messages {
+    pub fn new(language: Language) -> Self {
+        Messages { language }
+    }
+
+    pub fn language(&self) -> Language {
+        self.language
+    }
+
+    /// Translation of `english`, or `english` itself
+    pub fn get<'a>(&self, english: &'a str) -> &'a str {
+        let catalog = match self.language {
+            Language::English => return english,
+            Language::Chinese => CHINESE,
+        };
+        catalog
+            .iter()
+            .find(|(source, _)| *source == english)
+            .map_or(english, |(_, translated)| translated)
+    }
+
+    /// Translation of `english` with each `{}` replaced by the next argument
+    pub fn format(&self, english: &str, args: &[&dyn Display]) -> String {
+        let mut pieces = self.get(english).split("{}");
+        let mut text = pieces.next().unwrap_or("").to_string();
+        let mut args = args.iter();
+        for piece in pieces {
+            if let Some(arg) = args.next() {
+                text.push_str(&arg.to_string());
+            }
+            text.push_str(piece);
+        }
+        text
+    }
+}
+
+const CHINESE: &[(&str, &str)] = &[
+    ("Black", "黑"),
+    ("White", "白"),
+    ("In Progress", "进行中"),
+    ("Ended by agreement", "双方同意终局"),
+    ("Resigned", "中盘认输"),
+    ("Capture target reached", "吃子目标达成"),
+    ("Lost on time", "超时负"),
+    ("No result", "无胜负"),
+    ("Game Status: {}", "对局状态：{}"),
+    ("Winner: {}", "胜方：{}"),
+    ("Result: Tie", "结果：和棋"),
+    ("Captured - Black: {}, White: {}", "提子 - 黑：{}，白：{}"),
+    ("Score by {}: Black {}, White {}, {}", "{}计分：黑 {}，白 {}，{}"),
+    ("area", "数子"),
+    ("territory", "数目"),
+    ("Press Enter to exit...", "按回车键退出……"),
+    ("Current player: {}", "当前行棋方：{}"),
+    ("Pass count: {}", "连续停一手次数：{}"),
+    ("Time left - Black: {}, White: {}", "剩余时间 - 黑：{}，白：{}"),
+    ("{} ({} periods)", "{}（{} 次读秒）"),
+    ("{} ({} stones)", "{}（{} 手）"),
+    ("out of time", "超时"),
+    (
+        "Commands: move (e.g. A1), pass, resign, team COLOR NAMES, joseki CORNER, quit",
+        "命令：落子（如 A1）、pass 停一手、resign 认输、team 颜色 姓名 设置联棋队伍、joseki 角 查询定式、quit 退出",
+    ),
+    ("Enter command: ", "请输入命令："),
+    ("Invalid command! Use: A1, pass, resign, quit", "无效命令！可用：A1、pass、resign、quit"),
+    ("Game exited normally", "对局正常结束"),
+    ("Game is already over", "对局已经结束"),
+    ("Position out of bounds", "位置超出棋盘"),
+    ("Position already occupied", "该位置已有棋子"),
+    ("Ko threat violation", "违反劫争规则"),
+    ("Suicide move not allowed", "不允许自杀着"),
+    ("Game error: {}", "对局出错：{}"),
+    ("{} passes.", "{}停一手。"),
+    ("{} plays {}.", "{}下在 {}。"),
+    ("{} plays {}, captures 1 stone.", "{}下在 {}，提一子。"),
+    ("{} plays {}, captures {} stones.", "{}下在 {}，提 {} 子。"),
+    ("{} resigns.", "{}认输。"),
+    ("{} to play.", "轮到{}走。"),
+    ("Row {}: empty.", "第 {} 行：空。"),
+    ("Row {}: {}.", "第 {} 行：{}。"),
+    (", ", "，"),
+    ("The board is empty.", "棋盘上没有棋子。"),
+    ("Other rows are empty.", "其余各行都是空的。"),
+    ("No such row", "没有这一行"),
+    ("Game saved as {}", "棋谱已保存为 {}"),
+    ("{} ({})", "{}（{}）"),
+    ("Team {}: {}", "{}方队伍：{}"),
+    ("Team {} cleared", "{}方队伍已清除"),
+    ("Usage: team black|white NAME...", "用法：team black|white 姓名……"),
+    ("Usage: joseki ul|ur|ll|lr", "用法：joseki ul|ur|ll|lr"),
+    ("No known joseki in this corner", "这个角没有已知的定式"),
+    ("Empty corner", "空角"),
+    ("Continuations:", "后续着法："),
+    (
+        "Commands: move (e.g. A1), pass, resign, board, row N, team COLOR NAMES, joseki CORNER, help, quit",
+        "命令：落子（如 A1）、pass 停一手、resign 认输、board 读出棋盘、row N 读出第 N 行、team 颜色 姓名 设置联棋队伍、joseki 角 查询定式、help 帮助、quit 退出",
+    ),
+];
@@ -3,5 +3,10 @@
 
 //! Character-based UI for GNU Go Rust
 
+#[cfg(feature = "native")]
 pub mod terminal;
-pub mod board_view;
\ No newline at end of file
+pub mod board_view;
+pub mod board_renderer;
+pub mod i18n;
+pub mod accessible;
+pub mod svg;
@@ -0,0 +1,170 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! SVG board diagrams for web pages and documents
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::engine::board::{Board, Stone};
+use crate::engine::game::Game;
+use crate::engine::rules::fixed_handicap;
+use crate::gtp::format_move;
+use crate::sgf::{SGFHandler, SGFNode, SGFTree};
+
+/// A position to draw and its annotations
+#[derive(Debug, Clone)]
+pub struct Diagram {
+    pub board: Board,
+    /// Point of the last stone played, if any
+    pub last_move: Option<(usize, usize)>,
+    /// Move number of each stone still on the board
+    pub numbers: HashMap<(usize, usize), usize>,
+}
+
+impl Diagram {
+    /// A bare position without annotations
+    pub fn new(board: Board) -> Self {
+        Diagram { board, last_move: None, numbers: HashMap::new() }
+    }
+
+    /// The current position of `game`, numbering stones in the order they
+    /// were placed (setup stones and passes are not counted)
+    pub fn from_game(game: &Game) -> Self {
+        let mut diagram = Diagram::new(game.board.clone());
+        let points = game.moves().into_iter().filter_map(|(_, point)| point);
+        for (number, point) in points.enumerate() {
+            diagram.numbers.insert(point, number + 1);
+            diagram.last_move = Some(point);
+        }
+        // Captured stones lose their numbers
+        diagram.numbers.retain(|&(x, y), _| game.board.get_stone(x, y) != Stone::Empty);
+        diagram
+    }
+
+    /// The main line of `tree` after `moves` move nodes, or at its end
+    pub fn from_sgf(tree: &SGFTree, moves: Option<usize>) -> Result<Self, String> {
+        let mut tree = tree.clone();
+        if let Some(moves) = moves {
+            truncate_main_line(&mut tree.root, moves);
+        }
+        let mut game = Game::new(19);
+        SGFHandler::new().apply_to_game(&tree, &mut game)?;
+        Ok(Diagram::from_game(&game))
+    }
+}
+
+/// Keeps the first `moves` move nodes of the main line
+fn truncate_main_line(node: &mut SGFNode, moves: usize) {
+    let is_move = |node: &SGFNode| node.properties.contains_key("B") || node.properties.contains_key("W");
+    let remaining = if is_move(node) { moves.saturating_sub(1) } else { moves };
+    match node.children.first() {
+        Some(child) if remaining > 0 || !is_move(child) => {
+            node.children.truncate(1);
+            truncate_main_line(&mut node.children[0], remaining);
+        }
+        _ => node.children.clear(),
+    }
+}
+
+/// Drawing options for [`render_svg`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SvgOptions {
+    /// Column letters and row numbers around the board
+    pub coordinates: bool,
+    /// Label stones with their move numbers
+    pub move_numbers: bool,
+    /// Distance between lines in pixels
+    pub cell_size: usize,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        SvgOptions { coordinates: true, move_numbers: false, cell_size: 24 }
+    }
+}
+
+/// Renders a diagram as a standalone SVG document
+pub fn render_svg(diagram: &Diagram, options: &SvgOptions) -> String {
+    let board = &diagram.board;
+    let size = board.size();
+    let cell = options.cell_size.max(8) as f32;
+    let margin = if options.coordinates { cell * 1.5 } else { cell * 0.75 };
+    let extent = margin * 2.0 + cell * (size - 1) as f32;
+    let pos = |i: usize| margin + cell * (i - 1) as f32;
+    let last = pos(size);
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{0}" viewBox="0 0 {0} {0}" font-family="sans-serif">"#,
+        extent
+    );
+    let _ = writeln!(svg, r##"<rect width="{0}" height="{0}" fill="#dcb35c"/>"##, extent);
+
+    // Grid and star points
+    let mut grid = String::new();
+    for i in 1..=size {
+        let _ = write!(grid, "M{} {}H{}M{} {}V{}", pos(1), pos(i), last, pos(i), pos(1), last);
+    }
+    let _ = writeln!(svg, r##"<path d="{}" stroke="#000" stroke-width="1" fill="none"/>"##, grid);
+    let stars = if size.is_multiple_of(2) { 4 } else if size >= 15 { 9 } else { 5 };
+    for (x, y) in fixed_handicap(size, stars).unwrap_or_default() {
+        let _ = writeln!(svg, r##"<circle cx="{}" cy="{}" r="{}" fill="#000"/>"##, pos(x), pos(y), cell * 0.1);
+    }
+
+    if options.coordinates {
+        let font = cell * 0.45;
+        let _ = writeln!(svg, r##"<g font-size="{}" fill="#000" text-anchor="middle" dominant-baseline="central">"##, font);
+        for i in 1..=size {
+            let vertex = format_move(i, size, size);
+            let column = &vertex[..1];
+            let row = size + 1 - i;
+            for y in [margin - cell, last + cell] {
+                let _ = writeln!(svg, r#"<text x="{}" y="{}">{}</text>"#, pos(i), y, column);
+            }
+            for x in [margin - cell, last + cell] {
+                let _ = writeln!(svg, r#"<text x="{}" y="{}">{}</text>"#, x, pos(i), row);
+            }
+        }
+        svg.push_str("</g>\n");
+    }
+
+    // Stones with their labels
+    for y in 1..=size {
+        for x in 1..=size {
+            let (fill, ink) = match board.get_stone(x, y) {
+                Stone::Black => ("#000", "#fff"),
+                Stone::White => ("#fff", "#000"),
+                Stone::Empty => continue,
+            };
+            let (cx, cy) = (pos(x), pos(y));
+            let _ = writeln!(
+                svg,
+                r##"<circle cx="{}" cy="{}" r="{}" fill="{}" stroke="#000" stroke-width="1"/>"##,
+                cx, cy, cell * 0.48, fill
+            );
+            match diagram.numbers.get(&(x, y)).filter(|_| options.move_numbers) {
+                Some(number) => {
+                    let digits = number.to_string().len().max(2) as f32;
+                    let _ = writeln!(
+                        svg,
+                        r#"<text x="{}" y="{}" font-size="{}" fill="{}" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+                        cx, cy, cell * 1.1 / digits, ink, number
+                    );
+                }
+                None if diagram.last_move == Some((x, y)) => {
+                    let _ = writeln!(
+                        svg,
+                        r#"<circle cx="{}" cy="{}" r="{}" fill="none" stroke="{}" stroke-width="{}"/>"#,
+                        cx, cy, cell * 0.22, ink, cell * 0.08
+                    );
+                }
+                None => {}
+            }
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
@@ -2,85 +2,306 @@
 //! License: GPL-3.0-or-later
 
 use std::io::{self, Write};
+use std::time::Instant;
 use crate::engine::game::Game;
 use crate::engine::board::Stone;
+use crate::engine::clock::Overtime;
+use crate::engine::rules::Variant;
 use super::board_view::draw_board;
+use super::board_renderer::{BoardRenderer, RenderConfig};
+use super::i18n::{Language, Messages};
+use crate::sgf::archive::Archive;
+use super::accessible::{announce_move, describe_board, describe_row, vertex_name};
+use crate::patterns::joseki::{self, Corner};
 
 /// Terminal-based interface
 pub struct TerminalUI {
     game: Game,
+    /// `None` draws the classic board view
+    renderer: Option<BoardRenderer>,
+    messages: Messages,
+    /// Screen reader mode: spoken-style announcements instead of a redrawn board
+    accessible: bool,
+    /// Announcement of the last move, printed before the next prompt
+    announcement: Option<String>,
+    /// Where the finished game is saved
+    archive: Option<Archive>,
+    /// Command output shown under the next redrawn board
+    notice: Option<String>,
+    /// When the side to move started thinking, for the game clock
+    turn_started: Instant,
 }
 
 impl TerminalUI {
     pub fn new(size: usize) -> Self {
         TerminalUI {
             game: Game::new(size),
+            renderer: None,
+            messages: Messages::new(Language::English),
+            accessible: false,
+            announcement: None,
+            archive: None,
+            notice: None,
+            turn_started: Instant::now(),
+        }
+    }
+
+    /// Starts from `game`, drawing with the named theme ("classic" or a `RenderConfig` theme)
+    pub fn with_theme(game: Game, theme: &str) -> Self {
+        TerminalUI {
+            game,
+            renderer: RenderConfig::from_theme(theme).map(BoardRenderer::new),
+            messages: Messages::new(Language::English),
+            accessible: false,
+            announcement: None,
+            archive: None,
+            notice: None,
+            turn_started: Instant::now(),
+        }
+    }
+
+    /// Shows prompts and status lines in `language`
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.messages = Messages::new(language);
+        self
+    }
+
+    /// Announces moves in words and never clears or redraws the screen
+    pub fn with_accessible(mut self, accessible: bool) -> Self {
+        self.accessible = accessible;
+        self
+    }
+
+    /// Saves the game to `archive` once it is over
+    pub fn with_archive(mut self, archive: Option<Archive>) -> Self {
+        self.archive = archive;
+        self
+    }
+
+    fn stone_name(&self, stone: Stone) -> &'static str {
+        self.messages.get(if stone == Stone::Black { "Black" } else { "White" })
+    }
+
+    /// Side to move, with the rengo team member whose turn it is
+    fn to_play_label(&self) -> String {
+        let color = self.stone_name(self.game.current_player());
+        match self.game.player_to_move() {
+            Some(name) => self.messages.format("{} ({})", &[&color, &name]),
+            None => color.to_string(),
+        }
+    }
+
+    /// Handles `team black|white NAME...`; no names clears the team. The
+    /// prompt names the member to play, so only screen readers get a reply
+    fn set_team(&mut self, args: &str) {
+        let mut words = args.split_whitespace();
+        let color = match words.next().map(str::to_lowercase).as_deref() {
+            Some("black") | Some("b") => Stone::Black,
+            Some("white") | Some("w") => Stone::White,
+            _ => return self.show_error("Usage: team black|white NAME..."),
+        };
+        let members: Vec<String> = words.map(str::to_string).collect();
+        if let Err(e) = self.game.set_team(color, members) {
+            return self.show_error(&e);
+        }
+        if self.accessible {
+            let m = self.messages;
+            self.announcement = Some(match self.game.team_label(color) {
+                Some(label) => m.format("Team {}: {}", &[&self.stone_name(color), &label]),
+                None => m.format("Team {} cleared", &[&self.stone_name(color)]),
+            });
+        }
+    }
+
+    /// Shows command output now in accessible mode, else below the next board
+    fn tell(&mut self, text: String) {
+        if self.accessible {
+            println!("{}", text);
+        } else {
+            self.notice = Some(text);
+        }
+    }
+
+    /// Handles `joseki CORNER`, where the corner is ul, ur, ll, lr or a point near it
+    fn joseki(&mut self, arg: &str) {
+        let size = self.game.board.size();
+        let corner = match Corner::from_name(arg).or_else(|| parse_move(arg).map(|(x, y)| Corner::nearest(x, y, size))) {
+            Some(corner) => corner,
+            None => return self.show_error("Usage: joseki ul|ur|ll|lr"),
+        };
+        let m = self.messages;
+        let Some(found) = joseki::identify(&self.game.board, corner, self.game.current_player()) else {
+            return self.tell(m.get("No known joseki in this corner").to_string());
+        };
+        let mut lines = vec![found.name.unwrap_or(m.get("Empty corner")).to_string()];
+        if !found.continuations.is_empty() {
+            lines.push(m.get("Continuations:").to_string());
+        }
+        for next in found.continuations {
+            let vertex = vertex_name(next.point.0, next.point.1);
+            lines.push(format!("  {} {} - {}: {}", self.stone_name(next.color), vertex, next.name, next.comment));
+        }
+        self.tell(lines.join("\n"));
+    }
+
+    /// Prints an engine error in the UI language and gives time to read it
+    fn show_error(&self, error: &str) {
+        println!("{}", self.messages.get(error));
+        if !self.accessible {
+            std::thread::sleep(std::time::Duration::from_secs(1));
+        }
+    }
+
+    /// Time `color` has left on the game clock, in the UI language
+    fn clock_label(&self, color: Stone) -> String {
+        let m = self.messages;
+        let Some((left, count)) = self.game.clock.time_left(color) else {
+            return String::new();
+        };
+        if self.game.clock.is_flagged(color) {
+            return m.get("out of time").to_string();
+        }
+        let time = format!("{}:{:02}", left.as_secs() / 60, left.as_secs() % 60);
+        match self.game.clock.control().overtime {
+            _ if count == 0 => time,
+            Overtime::Japanese { .. } => m.format("{} ({} periods)", &[&time, &count]),
+            _ => m.format("{} ({} stones)", &[&time, &count]),
+        }
+    }
+
+    /// Plays `point` (`None` passes) for the current player, charges the
+    /// thinking time to its clock and records the announcement
+    fn play(&mut self, point: Option<(usize, usize)>) {
+        let before = self.game.board.clone();
+        let color = self.game.current_player();
+        let result = match point {
+            Some((x, y)) => self.game.make_move(x, y),
+            None => self.game.pass(),
+        };
+        match result {
+            Ok(()) => {
+                self.game.record_move_time(self.turn_started.elapsed());
+                self.turn_started = Instant::now();
+                self.announcement = Some(announce_move(&self.messages, &before, &self.game.board, color, point));
+            }
+            Err(e) => self.show_error(&e),
         }
     }
 
     /// Main game loop
     pub fn run(&mut self) -> io::Result<()> {
+        let m = self.messages;
+        if self.accessible {
+            println!("{}", m.get("Commands: move (e.g. A1), pass, resign, board, row N, team COLOR NAMES, joseki CORNER, help, quit"));
+        }
+        self.turn_started = Instant::now();
         loop {
-            self.clear_screen()?;
-            draw_board(&self.game.board);
-            println!();
+            if self.accessible {
+                if let Some(announcement) = self.announcement.take() {
+                    println!("{}", announcement);
+                }
+            } else {
+                self.clear_screen()?;
+                let last_move = self.game.last_move().and_then(|m| m.point);
+                match &self.renderer {
+                    Some(renderer) => print!("{}", renderer.render_with_last_move(&self.game.board, last_move)),
+                    None => draw_board(&self.game.board, last_move),
+                }
+                println!();
+                if let Some(notice) = self.notice.take() {
+                    println!("{}\n", notice);
+                }
+            }
             
             // Display game status
             if self.game.is_game_over() {
-                println!("Game Status: {}", self.game.status());
+                println!("{}", m.format("Game Status: {}", &[&m.get(self.game.status())]));
                 if let Some(winner) = self.game.winner() {
-                    println!("Winner: {:?}", winner);
-                } else {
-                    println!("Result: Tie");
+                    println!("{}", m.format("Winner: {}", &[&self.stone_name(winner)]));
+                } else if self.game.status() != "No result" {
+                    println!("{}", m.get("Result: Tie"));
+                }
+                if self.game.status() == "Ended by agreement" && self.game.variant == Variant::Standard {
+                    let score = self.game.score();
+                    println!("{}", m.format("Score by {}: Black {}, White {}, {}", &[
+                        &m.get(self.game.rules.scoring.name()),
+                        &score.black,
+                        &score.white_total(),
+                        &score.result(),
+                    ]));
+                }
+                println!("{}", m.format("Captured - Black: {}, White: {}", &[
+                    &self.game.captured(Stone::Black),
+                    &self.game.captured(Stone::White),
+                ]));
+                if let Some(archive) = &self.archive {
+                    match archive.save_game("terminal", &self.game, "Human", "Human") {
+                        Ok(entry) => println!("{}", m.format("Game saved as {}", &[&entry.file])),
+                        Err(e) => println!("{}", e),
+                    }
                 }
-                println!("Captured - Black: {}, White: {}", 
-                    self.game.captured(Stone::Black),
-                    self.game.captured(Stone::White));
                 println!();
-                print!("Press Enter to exit...");
+                print!("{}", m.get("Press Enter to exit..."));
                 io::stdout().flush()?;
                 io::stdin().read_line(&mut String::new())?;
                 break;
             }
             
-            println!("Current player: {:?}", self.game.current_player());
-            println!("Pass count: {}", self.game.pass_count());
-            println!("Commands: move (e.g. A1), pass, resign, quit");
+            if self.accessible {
+                println!("{}", m.format("{} to play.", &[&self.to_play_label()]));
+            } else {
+                println!("{}", m.format("Current player: {}", &[&self.to_play_label()]));
+                println!("{}", m.format("Pass count: {}", &[&self.game.pass_count()]));
+            }
+            if !self.game.clock.is_unlimited() {
+                println!("{}", m.format("Time left - Black: {}, White: {}", &[
+                    &self.clock_label(Stone::Black),
+                    &self.clock_label(Stone::White),
+                ]));
+            }
+            if !self.accessible {
+                println!("{}", m.get("Commands: move (e.g. A1), pass, resign, team COLOR NAMES, joseki CORNER, quit"));
+            }
             
-            print!("Enter command: ");
+            print!("{}", m.get("Enter command: "));
             io::stdout().flush()?;
             
             let mut input = String::new();
             io::stdin().read_line(&mut input)?;
             
-            let input = input.trim().to_lowercase();
+            let raw = input.trim();
+            let input = raw.to_lowercase();
             
             match input.as_str() {
                 "quit" => break,
-                "pass" => {
-                    if let Err(e) = self.game.pass() {
-                        println!("{}", e);
-                        std::thread::sleep(std::time::Duration::from_secs(1));
-                    }
-                },
+                "pass" => self.play(None),
                 "resign" => {
-                    if let Err(e) = self.game.resign() {
-                        println!("{}", e);
-                        std::thread::sleep(std::time::Duration::from_secs(1));
+                    let color = self.stone_name(self.game.current_player());
+                    match self.game.resign() {
+                        Ok(()) => self.announcement = Some(m.format("{} resigns.", &[&color])),
+                        Err(e) => self.show_error(&e),
                     }
                 },
+                "board" if self.accessible => println!("{}", describe_board(&m, &self.game.board)),
+                "help" if self.accessible => {
+                    println!("{}", m.get("Commands: move (e.g. A1), pass, resign, board, row N, team COLOR NAMES, joseki CORNER, help, quit"));
+                }
+                "team" => self.set_team(""),
+                _ if input.starts_with("joseki") => self.joseki(input[6..].trim()),
+                _ if input.starts_with("team ") => self.set_team(raw.get(5..).unwrap_or("")),
+                _ if self.accessible && input.starts_with("row ") => {
+                    match input[4..].trim().parse::<usize>() {
+                        Ok(y) if (1..=self.game.board.size()).contains(&y) => {
+                            println!("{}", describe_row(&m, &self.game.board, y));
+                        }
+                        _ => self.show_error("No such row"),
+                    }
+                }
                 _ => {
                     if let Some((x, y)) = parse_move(&input) {
-                        match self.game.make_move(x, y) {
-                            Ok(()) => {},
-                            Err(e) => {
-                                println!("{}", e);
-                                std::thread::sleep(std::time::Duration::from_secs(1));
-                            }
-                        }
+                        self.play(Some((x, y)));
                     } else {
-                        println!("Invalid command! Use: A1, pass, resign, quit");
-                        std::thread::sleep(std::time::Duration::from_secs(1));
+                        self.show_error("Invalid command! Use: A1, pass, resign, quit");
                     }
                 }
             }
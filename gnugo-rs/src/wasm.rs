@@ -0,0 +1,153 @@
+//! Copyright (C) 2026 wood&zulu_ai
+//! License: GPL-3.0-or-later
+
+//! JavaScript bindings for browser builds (feature `wasm`)
+//!
+//! Build with
+//! `cargo build --release --lib --target wasm32-unknown-unknown --no-default-features --features wasm`
+//! and generate the JS glue with `wasm-bindgen --target web`.
+//!
+//! Vertices use GTP notation ("D4", "pass") like the GTP front end.
+
+use wasm_bindgen::prelude::*;
+
+use crate::engine::ai::{AIDifficulty, AI};
+use crate::engine::board::Stone;
+use crate::engine::final_status::FinalStatus;
+use crate::engine::game::Game;
+use crate::engine::rules::ScoringRule;
+use crate::engine::scoring::final_score;
+use crate::gtp::{format_move, parse_gtp_move};
+use crate::sgf::SGFHandler;
+
+/// A game plus engine settings, exported to JavaScript as `Game`
+#[wasm_bindgen(js_name = Game)]
+pub struct WasmGame {
+    game: Game,
+    level: AIDifficulty,
+}
+
+#[wasm_bindgen(js_class = Game)]
+impl WasmGame {
+    #[wasm_bindgen(constructor)]
+    pub fn new(size: usize, komi: f32) -> Result<WasmGame, JsValue> {
+        if !(2..=25).contains(&size) {
+            return Err(JsValue::from_str(&format!("Unsupported board size {}", size)));
+        }
+        let mut game = Game::new(size);
+        game.komi = komi;
+        Ok(WasmGame { game, level: AIDifficulty::Intermediate })
+    }
+
+    /// Creates a game from the main line of an SGF record
+    #[wasm_bindgen(js_name = fromSgf)]
+    pub fn from_sgf(text: &str) -> Result<WasmGame, JsValue> {
+        Ok(WasmGame { game: parse_sgf(text)?, level: AIDifficulty::Intermediate })
+    }
+
+    pub fn size(&self) -> usize {
+        self.game.board.size()
+    }
+
+    pub fn komi(&self) -> f32 {
+        self.game.komi
+    }
+
+    /// "black" or "white"
+    #[wasm_bindgen(js_name = toMove)]
+    pub fn to_move(&self) -> String {
+        color_name(self.game.current_player()).to_string()
+    }
+
+    /// Sets the engine level by name ("beginner", "intermediate", "advanced")
+    #[wasm_bindgen(js_name = setLevel)]
+    pub fn set_level(&mut self, level: &str) -> Result<(), JsValue> {
+        self.level = AIDifficulty::from_name(level)
+            .ok_or_else(|| JsValue::from_str(&format!("Unknown level '{}'", level)))?;
+        Ok(())
+    }
+
+    /// Plays a GTP vertex or "pass" for the side to move
+    pub fn play(&mut self, vertex: &str) -> Result<(), JsValue> {
+        let result = if vertex.eq_ignore_ascii_case("pass") {
+            self.game.pass()
+        } else {
+            match parse_gtp_move(vertex, self.size()) {
+                Some((x, y)) => self.game.make_move(x, y),
+                None => Err(format!("Invalid vertex '{}'", vertex)),
+            }
+        };
+        result.map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Lets the engine play for the side to move and returns its vertex
+    pub fn genmove(&mut self) -> Result<String, JsValue> {
+        let color = self.game.current_player();
+        match AI::new(self.level).get_game_move(&self.game, color) {
+            Some((x, y)) if self.game.make_move(x, y).is_ok() => Ok(format_move(x, y, self.size())),
+            _ => {
+                self.game.pass().map_err(|e| JsValue::from_str(&e))?;
+                Ok("pass".to_string())
+            }
+        }
+    }
+
+    /// Takes back the last move; returns false if there is none
+    pub fn undo(&mut self) -> bool {
+        self.game.undo_move().is_some()
+    }
+
+    #[wasm_bindgen(js_name = isOver)]
+    pub fn is_over(&self) -> bool {
+        self.game.is_game_over()
+    }
+
+    /// Board rows from the top, `X` black, `O` white, `.` empty
+    pub fn board(&self) -> Vec<String> {
+        let size = self.size();
+        (1..=size)
+            .map(|y| {
+                (1..=size)
+                    .map(|x| match self.game.board.get_stone(x, y) {
+                        Stone::Black => 'X',
+                        Stone::White => 'O',
+                        Stone::Empty => '.',
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Area score with dead stones removed: Black minus White minus komi
+    pub fn score(&self) -> f32 {
+        let dead = FinalStatus::analyze(&self.game.board).dead();
+        let (black, white) = final_score(&self.game.board, ScoringRule::Area, &dead);
+        black as f32 - white as f32 - self.game.komi
+    }
+
+    #[wasm_bindgen(js_name = toSgf)]
+    pub fn to_sgf(&self) -> Result<String, JsValue> {
+        SGFHandler::new()
+            .game_to_sgf(&self.game, None)
+            .map_err(|e| JsValue::from_str(&e))
+    }
+}
+
+fn color_name(stone: Stone) -> &'static str {
+    match stone {
+        Stone::White => "white",
+        _ => "black",
+    }
+}
+
+/// Parses SGF text and replays its main line
+fn parse_sgf(text: &str) -> Result<Game, JsValue> {
+    let handler = SGFHandler::new();
+    let mut game = Game::new(19);
+    handler
+        .parse(text)
+        .map_err(String::from)
+        .and_then(|tree| handler.apply_to_game(&tree, &mut game).map_err(String::from))
+        .map_err(|e| JsValue::from_str(&e))?;
+    Ok(game)
+}
@@ -31,7 +31,7 @@ mod tests {
     fn test_game_creation() {
         let game = Game::new(13);
         // Test that game was created with correct board size
-        assert_eq!(game.current_player, true); // Black starts
+        assert!(game.current_player); // Black starts
     }
     
     #[test]
@@ -40,7 +40,7 @@ mod tests {
         // Using 1-based coordinates: (5, 5) is the center of a 9x9 board
         let result = game.make_move(5, 5);
         assert!(result.is_ok());
-        assert_eq!(game.current_player, false); // White's turn now
+        assert!(!game.current_player); // White's turn now
     }
     
     #[test]
@@ -51,4 +51,3753 @@ mod tests {
         let result = game.undo_move();
         assert!(result.is_some());
     }
+    
+    #[test]
+    fn test_capture_and_ko() {
+        let mut board = Board::new(9);
+        // Black surrounds the point (2, 2) except from the right; white shape to the right
+        board.set_stone(2, 1, Stone::Black);
+        board.set_stone(1, 2, Stone::Black);
+        board.set_stone(2, 3, Stone::Black);
+        board.set_stone(3, 1, Stone::White);
+        board.set_stone(4, 2, Stone::White);
+        board.set_stone(3, 3, Stone::White);
+        board.set_stone(2, 2, Stone::White);
+        
+        // Black captures the white stone at (2, 2) by playing (3, 2)
+        assert!(board.place_stone(3, 2, Stone::Black).is_ok());
+        assert_eq!(board.get_stone(2, 2), Stone::Empty);
+        assert_eq!(board.get_captured(), [0, 1]);
+        assert_eq!(board.get_ko_point(), Some((2, 2)));
+        
+        // Immediate recapture is forbidden by ko
+        assert!(board.place_stone(2, 2, Stone::White).is_err());
+    }
+    
+    #[test]
+    fn test_tsumego_kill_in_corner() {
+        use gnugo_rs::engine::tsumego::{LifeStatus, TsumegoSolver};
+        
+        let mut board = Board::new(9);
+        for &(x, y) in &[(1, 3), (2, 3), (3, 3), (4, 3), (4, 2), (5, 1)] {
+            board.set_stone(x, y, Stone::Black);
+        }
+        for &(x, y) in &[(1, 2), (2, 2), (3, 2), (4, 1)] {
+            board.set_stone(x, y, Stone::White);
+        }
+        
+        let mut solver = TsumegoSolver::new(&board, (2, 2), 10_000);
+        let result = solver.solve(&board, Stone::Black);
+        assert_eq!(result.status, LifeStatus::Dead);
+        assert!(!result.main_line.is_empty());
+    }
+    
+    #[test]
+    fn test_dead_stones_and_scoring() {
+        use gnugo_rs::engine::rules::ScoringRule;
+        use gnugo_rs::engine::scoring::{dead_stones, final_score};
+        
+        // Black wall on column 4, white wall on column 5, one invader each
+        let mut board = Board::new(9);
+        for y in 1..=9 {
+            board.set_stone(4, y, Stone::Black);
+            board.set_stone(5, y, Stone::White);
+        }
+        board.set_stone(2, 2, Stone::White);
+        board.set_stone(7, 7, Stone::Black);
+        
+        let dead = dead_stones(&board);
+        assert_eq!(dead, vec![(2, 2), (7, 7)]);
+        
+        assert_eq!(final_score(&board, ScoringRule::Area, &dead), (36, 45));
+        assert_eq!(final_score(&board, ScoringRule::Territory, &dead), (28, 37));
+    }
+    
+    #[test]
+    fn test_json_rpc_session() {
+        use gnugo_rs::server::json_rpc::JsonRpcServer;
+        
+        let server = JsonRpcServer::new();
+        let created = server.handle_text(r#"{"jsonrpc":"2.0","method":"new_game","params":{"size":9},"id":1}"#);
+        let game = created.get("result").and_then(|r| r.get("game")).and_then(|g| g.as_usize()).unwrap();
+        
+        let request = format!(r#"{{"jsonrpc":"2.0","method":"play","params":{{"game":{},"color":"b","vertex":"E5"}},"id":2}}"#, game);
+        assert!(server.handle_text(&request).get("error").is_none());
+        
+        let request = format!(r#"{{"jsonrpc":"2.0","method":"play","params":{{"game":{},"color":"w","vertex":"E5"}},"id":3}}"#, game);
+        assert!(server.handle_text(&request).get("error").is_some());
+        
+        let unknown = server.handle_text(r#"{"jsonrpc":"2.0","method":"nope","id":4}"#);
+        let code = unknown.get("error").and_then(|e| e.get("code")).and_then(|c| c.as_f64());
+        assert_eq!(code, Some(-32601.0));
+
+        for (level, ok) in [("3", true), ("\"beginner\"", true), ("\"2\"", true), ("11", false), ("0", false)] {
+            let request = format!(r#"{{"jsonrpc":"2.0","method":"genmove","params":{{"game":{},"level":{}}},"id":5}}"#, game, level);
+            assert_eq!(server.handle_text(&request).get("error").is_none(), ok, "{}", level);
+        }
+
+        // New games follow the configured ko rule, so the ko can be retaken
+        let mut config = gnugo_rs::config::Config::default();
+        config.ruleset.ko_rule = gnugo_rs::engine::rules::KoRule::None;
+        let server = JsonRpcServer::with_config(config);
+        server.handle_text(r#"{"jsonrpc":"2.0","method":"new_game","params":{"size":5},"id":1}"#);
+        let moves = ["b B5", "w C5", "b A4", "w B4", "w D4", "b B3", "w C3", "b C4", "w B4"];
+        for (color, vertex) in moves.iter().filter_map(|m| m.split_once(' ')) {
+            let request = format!(r#"{{"jsonrpc":"2.0","method":"play","params":{{"game":1,"color":"{}","vertex":"{}"}},"id":2}}"#, color, vertex);
+            assert!(server.handle_text(&request).get("error").is_none(), "{} {}", color, vertex);
+        }
+    }
+    
+    #[test]
+    fn test_websocket_session() {
+        use gnugo_rs::server::websocket::{accept_key, Session};
+        
+        // Handshake example from RFC 6455
+        assert_eq!(accept_key("dGhlIHNhbXBsZSBub25jZQ=="), "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+        
+        let mut session = Session::new(&gnugo_rs::config::Config::default());
+        let replies = session.handle_text(r#"{"type":"new_game","size":9,"engine":"white","analysis":false}"#);
+        assert_eq!(replies.len(), 1);
+        
+        let replies = session.handle_text(r#"{"type":"play","vertex":"E5"}"#);
+        let state = &replies[0];
+        assert_eq!(state.get("type").and_then(|t| t.as_str()), Some("state"));
+        // The engine answered as White, so Black is to move again
+        assert_eq!(state.get("to_move").and_then(|t| t.as_str()), Some("black"));
+        assert_eq!(session.game.board.get_stone(5, 5), Stone::Black);
+
+        for time in ["-1", "1e400", "\"300\""] {
+            let replies = session.handle_text(&format!(r#"{{"type":"new_game","main_time":{}}}"#, time));
+            assert_eq!(replies[0].get("type").and_then(|t| t.as_str()), Some("error"), "{}", time);
+        }
+        assert_eq!(session.game.board.get_stone(5, 5), Stone::Black);
+
+        // The game's clock runs between messages
+        session.handle_text(r#"{"type":"new_game","size":9,"main_time":0.01}"#);
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        let replies = session.handle_text(r#"{"type":"state"}"#);
+        assert_eq!(replies[0].get("winner").and_then(|w| w.as_str()), Some("white"));
+        assert_eq!(session.game.result().as_deref(), Some("W+Time"));
+    }
+    
+    #[test]
+    fn test_config_file_and_overrides() {
+        use gnugo_rs::config::Config;
+        use gnugo_rs::engine::rules::ScoringRule;
+        
+        let text = r#"
+            # bot settings
+            [game]
+            board_size = 9
+            komi = 7   # integers are fine
+            ruleset = "japanese"
+            
+            [display]
+            theme = 'ascii'
+            
+            [time]
+            main_time = 300
+        "#;
+        let mut config = Config::from_toml(text).unwrap();
+        assert_eq!(config.board_size, 9);
+        assert_eq!(config.komi, 7.0);
+        assert_eq!(config.ruleset.scoring, ScoringRule::Territory);
+        assert_eq!(config.time.main_time, 300);
+        
+        let args: Vec<String> = ["--komi", "5.5", "--size", "13"].iter().map(|s| s.to_string()).collect();
+        config.apply_args(&args).unwrap();
+        assert_eq!((config.board_size, config.komi), (13, 5.5));
+        
+        assert!(Config::from_toml("[game]\nboard_sise = 9").is_err());
+        assert!(Config::from_toml("[game]\nboard_size = 40").is_err());
+        for komi in ["nan", "inf", "-inf"] {
+            assert!(config.apply_args(&["--komi".to_string(), komi.to_string()]).is_err(), "{}", komi);
+        }
+
+        // Config flags may come before the mode
+        let args: Vec<String> = ["--size", "9", "--accessible", "--gtp", "--komi", "5"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(Config::skip_flags(&args), &args[3..]);
+        assert!(Config::skip_flags(&args[..2]).is_empty() && Config::skip_flags(&args[..1]).is_empty());
+    }
+    
+    #[test]
+    fn test_selfplay() {
+        use gnugo_rs::cli::selfplay::{play_game, SelfPlayConfig, SelfPlayGame};
+        use gnugo_rs::config::Config;
+        use gnugo_rs::engine::ai::{AIDifficulty, AI};
+        use gnugo_rs::sgf::SGFHandler;
+        use std::process::Command;
+
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let config = SelfPlayConfig::from_args(&args(&["--size", "7", "--level-a", "beginner"]), &Config::default()).unwrap();
+        assert_eq!((config.games, config.size, config.max_moves), (10, 7, 147));
+        assert_eq!(config.engine_a, AIDifficulty::Beginner);
+        assert!(SelfPlayConfig::from_args(&args(&["--size", "40"]), &Config::default()).is_err());
+        assert!(SelfPlayConfig::from_args(&args(&["--level-b", "nope"]), &Config::default()).is_err());
+
+        let black = AI::new(AIDifficulty::Beginner).with_seed(1);
+        let white = AI::new(AIDifficulty::Beginner).with_seed(2);
+        let game = play_game(5, 0.5, 30, &black, &white);
+        assert!(!game.moves.is_empty() && game.moves.len() <= 30);
+        assert!(game.moves.iter().enumerate().all(|(i, &(color, _))| color == if i % 2 == 0 { Stone::Black } else { Stone::White }));
+        let won = SelfPlayGame { moves: Vec::new(), margin: 2.5, resigned: None, void: false };
+        assert_eq!((won.winner(), won.result_string().as_str()), (Some(Stone::Black), "B+2.5"));
+        let resigned = SelfPlayGame { resigned: Some(Stone::Black), ..won };
+        assert_eq!(resigned.result_string(), "W+R");
+
+        // The subcommand writes one record per game, and fails with a status
+        let dir = std::env::temp_dir().join(format!("gnugo_rs_selfplay_{}", std::process::id()));
+        let program = env!("CARGO_BIN_EXE_gnugo_rs");
+        let status = Command::new(program)
+            .args(["selfplay", "--games", "2", "--size", "5", "--max-moves", "30", "--sgf-dir"])
+            .arg(&dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success());
+        for name in ["selfplay-0001.sgf", "selfplay-0002.sgf"] {
+            let tree = SGFHandler::new().load_file(dir.join(name)).unwrap();
+            assert!(!tree.root.children.is_empty(), "{}", name);
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+        let failed = Command::new(program).args(["selfplay", "--size", "40"]).output().unwrap();
+        assert_eq!(failed.status.code(), Some(1));
+    }
+
+    #[test]
+    fn test_gtp_client_drives_engine() {
+        use gnugo_rs::gtp::client::{GtpClient, GtpError};
+        use std::time::Duration;
+        
+        let program = env!("CARGO_BIN_EXE_gnugo_rs");
+        let mut engine = GtpClient::spawn(program, &["--gtp".to_string()]).unwrap();
+        engine.set_timeout(Some(Duration::from_secs(10)));
+        
+        assert_eq!(engine.name().unwrap(), "gnugo_rs");
+        engine.boardsize(9).unwrap();
+        engine.clear_board().unwrap();
+        engine.play(Stone::Black, "E5").unwrap();
+        assert!(matches!(engine.play(Stone::White, "E5"), Err(GtpError::Failure(_))));
+        let reply = engine.genmove(Stone::White).unwrap();
+        assert!(reply == "pass" || reply.len() >= 2);
+        engine.quit().unwrap();
+    }
+    
+    #[test]
+    fn test_gtp_daemon() {
+        use gnugo_rs::config::Config;
+        use gnugo_rs::gtp::daemon::{listen_address, serve};
+        use std::io::{BufRead, BufReader, Read, Write};
+        use std::net::{TcpListener, TcpStream};
+        use std::time::{Duration, Instant};
+        
+        assert_eq!(listen_address("3333"), "127.0.0.1:3333");
+        assert_eq!(listen_address("0.0.0.0:4000"), "0.0.0.0:4000");
+        
+        // Borrow a free port from the OS, then let the daemon bind it
+        let address = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().to_string();
+        let served = address.clone();
+        std::thread::spawn(move || serve(&served, &Config::default()));
+        let started = Instant::now();
+        let stream = loop {
+            match TcpStream::connect(&address) {
+                Ok(stream) => break stream,
+                Err(e) if started.elapsed() > Duration::from_secs(10) => panic!("daemon never listened: {}", e),
+                Err(_) => std::thread::sleep(Duration::from_millis(20)),
+            }
+        };
+        stream.set_read_timeout(Some(Duration::from_secs(10))).unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut writer = stream;
+        let mut ask = |command: &str| {
+            writeln!(writer, "{}", command).unwrap();
+            let (mut reply, mut blank) = (String::new(), String::new());
+            reader.read_line(&mut reply).unwrap();
+            reader.read_line(&mut blank).unwrap();
+            assert_eq!(blank, "\n");
+            reply.trim_end().to_string()
+        };
+        assert_eq!(ask("1 name"), "=1 gnugo_rs");
+        assert_eq!(ask("quit"), "=");
+        // The session ends and the daemon closes the connection
+        let mut rest = String::new();
+        assert_eq!(reader.read_to_string(&mut rest).unwrap(), 0);
+    }
+    
+    #[test]
+    fn test_match_time_keeper() {
+        use gnugo_rs::cli::tournament::{deadline, engine_from_spec, play_game, Ending, MatchConfig};
+        use gnugo_rs::config::TimeSettings;
+        use gnugo_rs::engine::clock::Clock;
+        use gnugo_rs::engine::rules::{GameRules, KoRule};
+        use std::time::Duration;
+        
+        // 10s main time, then 2 stones every 5s
+        let settings = TimeSettings { main_time: 10, byo_yomi_time: 5, byo_yomi_stones: 2 };
+        let mut clock = Clock::new(settings.time_control());
+        assert!(clock.charge(Stone::Black, Duration::from_secs(8)));
+        assert_eq!(clock.time_left(Stone::Black), Some((Duration::from_secs(2), 0)));
+        assert_eq!(deadline(&clock, Stone::Black), Some(Duration::from_secs(9)));
+        
+        // Overflowing main time enters byo-yomi with the excess charged
+        assert!(clock.charge(Stone::Black, Duration::from_secs(4)));
+        assert_eq!(clock.time_left(Stone::Black), Some((Duration::from_secs(3), 1)));
+        assert_eq!(deadline(&clock, Stone::Black), Some(Duration::from_secs(5)));
+        
+        // Completing the period resets it
+        assert!(clock.charge(Stone::Black, Duration::from_secs(3)));
+        assert_eq!(clock.time_left(Stone::Black), Some((Duration::from_secs(5), 2)));
+        assert!(!clock.charge(Stone::Black, Duration::from_secs(6)));
+        
+        assert_eq!(deadline(&Clock::new(TimeSettings::default().time_control()), Stone::Black), None);
+
+        // Internal levels are names or numbers
+        assert_eq!(engine_from_spec("internal:3", 5, Some(1)).unwrap().name(), "gnugo_rs (level 3)");
+        assert_eq!(engine_from_spec("internal", 2, Some(1)).unwrap().name(), "gnugo_rs (level 2)");
+        assert!(engine_from_spec("internal:beginner", 5, None).is_ok());
+        assert!(engine_from_spec("internal:11", 5, None).is_err());
+
+        // Games are refereed under the configured rules
+        let config = MatchConfig {
+            games: 1,
+            size: 5,
+            komi: 0.5,
+            max_moves: 40,
+            rules: GameRules { ko_rule: KoRule::None, ..GameRules::default() },
+            time: TimeSettings::default(),
+            sgf_dir: None,
+        };
+        let mut black = engine_from_spec("internal:1", 1, Some(1)).unwrap();
+        let mut white = engine_from_spec("internal:1", 1, Some(2)).unwrap();
+        let game = play_game(&config, black.as_mut(), white.as_mut());
+        assert!(matches!(game.ending, Ending::Score | Ending::NoResult), "{:?}", game.note);
+    }
+    
+    #[test]
+    fn test_cgos_session() {
+        use gnugo_rs::cli::cgos::CgosSession;
+        use gnugo_rs::cli::tournament::{EngineMove, InternalEngine, MatchEngine};
+        use gnugo_rs::config::TimeSettings;
+        use gnugo_rs::engine::ai::AIDifficulty;
+        use gnugo_rs::engine::rules::GameRules;
+        use gnugo_rs::gtp::client::GtpError;
+        use std::time::Duration;
+        
+        let mut engine = InternalEngine::new(AIDifficulty::Beginner).with_seed(7);
+        let mut session = CgosSession::new(&mut engine, "bot", "secret", 1);
+        
+        assert_eq!(session.handle_line("protocol").unwrap().as_deref(), Some("e1 gnugo_rs"));
+        assert_eq!(session.handle_line("username").unwrap().as_deref(), Some("bot"));
+        assert_eq!(session.handle_line("password").unwrap().as_deref(), Some("secret"));
+        
+        // Reconnect into a game in progress: two moves already played
+        assert_eq!(session.handle_line("setup 7 9 7.0 300000 alice(1800) bot(1700) E5 299000 D4 298000").unwrap(), None);
+        let reply = session.handle_line("genmove b 290000").unwrap().unwrap();
+        assert_ne!(reply, "E5");
+        assert_ne!(reply, "D4");
+        let answer = if reply == "C3" { "G7" } else { "C3" };
+        assert_eq!(session.handle_line(&format!("play w {} 280000", answer)).unwrap(), None);
+        
+        assert_eq!(session.handle_line("gameover 2026-01-01 W+Resign").unwrap(), None);
+        assert!(session.finished());
+        
+        // An engine that cannot move still answers the server
+        struct Broken;
+        impl MatchEngine for Broken {
+            fn name(&self) -> &str {
+                "broken"
+            }
+            fn new_game(&mut self, _: usize, _: f32, _: GameRules, _: &TimeSettings) -> Result<(), GtpError> {
+                Ok(())
+            }
+            fn play(&mut self, _: Stone, _: Option<(usize, usize)>) -> Result<(), GtpError> {
+                Ok(())
+            }
+            fn genmove(&mut self, _: Stone, _: Option<(Duration, u32)>, _: Option<Duration>) -> Result<EngineMove, GtpError> {
+                Err(GtpError::Failure("out of memory".to_string()))
+            }
+        }
+        let mut engine = Broken;
+        let mut session = CgosSession::new(&mut engine, "bot", "secret", 1);
+        session.handle_line("setup 8 9 7.0 300000 alice(1800) bot(1700)").unwrap();
+        assert_eq!(session.handle_line("genmove b 290000").unwrap().as_deref(), Some("resign"));
+    }
+    
+    #[test]
+    fn test_sgf_file_roundtrip() {
+        use gnugo_rs::sgf::SGFHandler;
+        
+        let mut game = Game::new(9);
+        game.make_move(3, 3).unwrap();
+        game.make_move(7, 7).unwrap();
+        
+        let path = std::env::temp_dir().join(format!("gnugo_rs_roundtrip_{}.sgf", std::process::id()));
+        let handler = SGFHandler::new();
+        handler.game_to_sgf(&game, Some(path.to_str().unwrap())).unwrap();
+        
+        let mut loaded = Game::new(19);
+        let tree = handler.load_file(&path).unwrap();
+        handler.apply_to_game(&tree, &mut loaded).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        
+        assert_eq!(loaded.board.size(), 9);
+        assert_eq!(loaded.board.get_stone(3, 3), Stone::Black);
+        assert_eq!(loaded.board.get_stone(7, 7), Stone::White);
+        assert!(handler.load_file(&path).is_err());
+    }
+    
+    #[test]
+    fn test_analysis_json() {
+        use gnugo_rs::json::JsonValue;
+        
+        let mut game = Game::new(9);
+        game.make_move(3, 3).unwrap();
+        game.make_move(7, 7).unwrap();
+        game.make_move(4, 3).unwrap();
+        
+        let json = JsonValue::parse(&game.analysis_json().to_string()).unwrap();
+        assert_eq!(json.get("size").and_then(|v| v.as_usize()), Some(9));
+        assert_eq!(json.get("to_move").and_then(|v| v.as_str()), Some("white"));
+        assert_eq!(json.get("moves").and_then(|v| v.as_array()).map(|m| m.len()), Some(5));
+        assert_eq!(json.get("ownership").and_then(|v| v.as_array()).map(|o| o.len()), Some(81));
+        
+        let dragons = json.get("dragons").and_then(|v| v.as_array()).unwrap();
+        assert_eq!(dragons.len(), 2);
+        let black = &dragons[0];
+        assert_eq!(black.get("color").and_then(|v| v.as_str()), Some("black"));
+        assert_eq!(black.get("status").and_then(|v| v.as_str()), Some("alive"));
+        assert_eq!(black.get("liberties").and_then(|v| v.as_usize()), Some(6));
+        let stones: Vec<&str> = black.get("stones").and_then(|v| v.as_array()).unwrap()
+            .iter().filter_map(|s| s.as_str()).collect();
+        assert_eq!(stones, vec!["C7", "D7"]);
+        
+        let score = json.get("score").unwrap();
+        assert_eq!(score.get("komi").and_then(|v| v.as_f64()), Some(6.5));
+        assert!(score.get("result").and_then(|v| v.as_str()).is_some());
+    }
+    
+    #[test]
+    fn test_ownership_methods() {
+        use gnugo_rs::engine::analysis::{ownership, OwnershipMethod, OwnershipSettings};
+        
+        let mut board = Board::new(9);
+        board.place_stone(3, 3, Stone::Black).unwrap();
+        board.place_stone(7, 7, Stone::White).unwrap();
+        
+        let influence = ownership(&board, &OwnershipSettings::default());
+        assert_eq!(influence.len(), 81);
+        assert_eq!(influence[2 * 9 + 2], 1.0);
+        assert_eq!(influence[6 * 9 + 6], -1.0);
+        assert!(influence[2 * 9 + 3] > 0.0 && influence[2 * 9 + 3] < 1.0);
+        assert!(influence[6 * 9 + 5] < 0.0);
+        
+        let settings = OwnershipSettings {
+            method: OwnershipMethod::Playouts,
+            playouts: 20,
+            seed: Some(7),
+            ..Default::default()
+        };
+        let playouts = ownership(&board, &settings);
+        assert_eq!(playouts.len(), 81);
+        assert!(playouts.iter().all(|v| (-1.0..=1.0).contains(v)));
+        assert_eq!(playouts, ownership(&board, &settings));
+    }
+    
+    #[test]
+    fn test_ogs_session() {
+        use gnugo_rs::cli::ogs::{OgsAction, OgsSession};
+        use gnugo_rs::cli::tournament::{InternalEngine, MatchEngine};
+        use gnugo_rs::engine::ai::AIDifficulty;
+        use gnugo_rs::json::JsonValue;
+        
+        let mut session = OgsSession::new("bot", "key", 1, Box::new(|| {
+            Ok(Box::new(InternalEngine::new(AIDifficulty::Beginner)) as Box<dyn MatchEngine>)
+        }));
+        let command = |action: &OgsAction| match action {
+            OgsAction::Send(JsonValue::Array(items)) => items[0].as_str().unwrap().to_string(),
+            OgsAction::Send(_) => panic!("frame is not an array"),
+            OgsAction::Rest(method, path, _) => format!("{} {}", method, path),
+        };
+        
+        assert_eq!(command(&session.login()), "authenticate");
+        assert!(session.handle_message(r#"[1, {"id": 42, "username": "bot"}]"#).unwrap().is_empty());
+        
+        let reply = session.handle_message(r#"["notification", {"type": "challenge", "challenge_id": 7, "width": 9, "height": 9, "handicap": 0}]"#).unwrap();
+        assert_eq!(command(&reply[0]), "POST me/challenges/7/accept");
+        let reply = session.handle_message(r#"["notification", {"type": "challenge", "challenge_id": 8, "width": 9, "height": 13}]"#).unwrap();
+        assert_eq!(command(&reply[0]), "DELETE me/challenges/8");
+        
+        let reply = session.handle_message(r#"["active_game", {"id": 100, "phase": "play"}]"#).unwrap();
+        assert_eq!(command(&reply[0]), "game/connect");
+        
+        // We are white and black has played: the bot answers at once
+        let reply = session.handle_message(r#"["game/100/gamedata", {"width": 9, "komi": 7.5, "phase": "play",
+            "players": {"black": {"id": 1}, "white": {"id": 42}}, "moves": [[4, 4, 1000]]}]"#).unwrap();
+        assert_eq!(command(&reply[0]), "game/move");
+        assert_eq!(session.active_games(), 1);
+        
+        // Our echoed move is ignored; the opponent's reply triggers another move
+        assert!(session.handle_message(r#"["game/100/move", {"move_number": 2, "move": [0, 0, 500]}]"#).unwrap().is_empty());
+        let reply = session.handle_message(r#"["game/100/move", {"move_number": 3, "move": [-1, -1, 500]}]"#).unwrap();
+        assert_eq!(command(&reply[0]), "game/move");
+        
+        // Clocks a Duration cannot hold are dropped rather than panicking
+        assert!(session.handle_message(r#"["game/100/clock", {"white_time": {"thinking_time": 1e300}}]"#).unwrap().is_empty());
+        assert!(session.handle_message(r#"["game/100/clock", {"white_time": 1e300}]"#).unwrap().is_empty());
+        
+        // Oversized boards are refused before an engine is set up
+        let err = session.handle_message(r#"["game/101/gamedata", {"width": 40, "phase": "play",
+            "players": {"black": {"id": 1}, "white": {"id": 42}}, "moves": []}]"#).unwrap_err();
+        assert!(err.contains("unsupported board size 40"));
+        assert_eq!(session.active_games(), 1);
+        
+        let reply = session.handle_message(r#"["game/100/phase", "stone removal"]"#).unwrap();
+        assert_eq!(command(&reply[0]), "game/removed_stones/accept");
+        let reply = session.handle_message(r#"["game/100/phase", "finished"]"#).unwrap();
+        assert_eq!(command(&reply[0]), "game/disconnect");
+        assert_eq!(session.games_finished(), 1);
+        assert_eq!(session.active_games(), 0);
+    }
+    
+    #[test]
+    fn test_igs_session() {
+        use gnugo_rs::cli::igs::IgsSession;
+        use gnugo_rs::cli::tournament::InternalEngine;
+        use gnugo_rs::engine::ai::AIDifficulty;
+        
+        let mut engine = InternalEngine::new(AIDifficulty::Beginner);
+        let mut session = IgsSession::new(&mut engine, "bot", "secret", 1);
+        
+        assert_eq!(session.handle_line("Login: ").unwrap(), vec!["bot"]);
+        assert_eq!(session.handle_line("Password: ").unwrap(), vec!["secret"]);
+        assert_eq!(session.handle_line("#> ").unwrap()[0], "toggle client true");
+        
+        session.handle_line("9 Match[9x9] in 10 minutes requested with alice as White.").unwrap();
+        let reply = session.handle_line("9 Use <match alice B 9 10 10> or <decline alice> to respond.").unwrap();
+        assert_eq!(reply, vec!["match alice B 9 10 10"]);
+        session.handle_line("9 Creating match [12] with alice.").unwrap();
+        
+        // We are black and move first once the server prompts
+        session.handle_line("15 Game 12 I: alice (0 600 -1) vs bot (0 600 -1)").unwrap();
+        let reply = session.handle_line("1 6").unwrap();
+        assert_eq!(reply.len(), 1);
+        let first = reply[0].clone();
+        
+        // Echo of our move, then alice's reply, then our turn again
+        session.handle_line("15 Game 12 I: alice (0 600 -1) vs bot (0 590 -1)").unwrap();
+        session.handle_line(&format!("15   0(B): {}", first)).unwrap();
+        assert!(session.handle_line("1 6").unwrap().is_empty());
+        session.handle_line("15 Game 12 I: alice (0 580 -1) vs bot (0 590 -1)").unwrap();
+        session.handle_line(&format!("15   0(B): {}", first)).unwrap();
+        session.handle_line("15   1(W): pass").unwrap();
+        assert_eq!(session.moves(), Some(2));
+        assert_eq!(session.handle_line("1 6").unwrap().len(), 1);
+        
+        assert_eq!(session.handle_line("1 7").unwrap(), vec!["done"]);
+        session.handle_line("9 {Game 12: alice vs bot : W+3.5}").unwrap();
+        assert!(session.finished());
+        
+        // Observing mirrors the moves without answering
+        let mut engine = InternalEngine::new(AIDifficulty::Beginner);
+        let mut observer = IgsSession::new(&mut engine, "bot", "secret", 1).observe(40);
+        observer.handle_line("Login:").unwrap();
+        observer.handle_line("Password:").unwrap();
+        assert_eq!(observer.handle_line("1 5").unwrap().last().unwrap(), "games 40");
+        let reply = observer.handle_line("7 [40]  carol [ 2d*] vs.   dave [ 1d*] ( 2 19  0  6.5 10  I) ( 3)").unwrap();
+        assert_eq!(reply, vec!["observe 40"]);
+        observer.handle_line("15 Game 40 I: carol (0 500 -1) vs dave (0 510 -1)").unwrap();
+        observer.handle_line("15   0(B): Q16").unwrap();
+        observer.handle_line("15   1(W): D4").unwrap();
+        assert!(observer.handle_line("1 8").unwrap().is_empty());
+        assert_eq!(observer.moves(), Some(2));
+    }
+
+    #[test]
+    fn test_gib_ngf_import() {
+        use gnugo_rs::engine::rules::fixed_handicap;
+        use gnugo_rs::sgf::import::{parse_gib, parse_ngf};
+        use gnugo_rs::sgf::{SGFHandler, SGFProperty};
+
+        let handler = SGFHandler::new();
+        let text = |tree: &gnugo_rs::sgf::SGFTree, key: &str| match tree.root.properties.get(key).map(|v| &v[0]) {
+            Some(SGFProperty::Text(t)) => t.clone(),
+            other => format!("{:?}", other),
+        };
+
+        let gib = "\\HS\n\\[GAMEBLACKNAME=kim (3D)\\]\n\\[GAMEWHITENAME=lee] (4D)\\]\n\
+                   \\[GAMEDATE=2024- 3- 7-12-00\\]\n\\[GAMEINFOMAIN=GBKIND:3,GRLT:1,ZIPSU:35,GONGJE:65,\\]\n\\HE\n\
+                   \\GS\n2 1 0\n127 0 &4\nINI 0 1 0 &4\nSTO 0 2 1 3 15\nSTO 0 3 2 15 3\nSKI 0 4\n\\GE\n";
+        let tree = parse_gib(gib).unwrap();
+        assert_eq!(text(&tree, "PB"), "kim");
+        assert_eq!(text(&tree, "WR"), "4D");
+        assert_eq!(text(&tree, "PW"), "lee]");
+        assert_eq!(text(&tree, "DT"), "2024-03-07");
+        assert_eq!(text(&tree, "RE"), "W+3.5");
+        assert_eq!(tree.root.properties["KM"], vec![SGFProperty::Real(6.5)]);
+
+        // Written SGF reads back to the same game
+        let reparsed = handler.parse(&handler.tree_to_sgf(&tree)).unwrap();
+        assert_eq!(text(&reparsed, "PW"), "lee]");
+        let mut game = Game::new(9);
+        handler.apply_to_game(&reparsed, &mut game).unwrap();
+        assert_eq!(game.board.size(), 19);
+        assert_eq!(game.board.get_stone(4, 16), Stone::Black);
+        assert_eq!(game.board.get_stone(16, 4), Stone::White);
+        assert!(!game.current_player);
+
+        let ngf = "Friendly\n9\nwhite 2D*\nblack 1K\nwbaduk\n2\n0\n0\n20240307 [12:00]\n5\nBlack wins by resignation\n2\n\
+                   PMAABFDDA\nPMABWFFFF\nPMACBKKAA\n";
+        let tree = parse_ngf(ngf).unwrap();
+        assert_eq!(text(&tree, "PW"), "white");
+        assert_eq!(text(&tree, "BR"), "1K");
+        assert_eq!(text(&tree, "RE"), "B+R");
+        assert_eq!(tree.root.properties["HA"], vec![SGFProperty::Number(2)]);
+        let mut game = Game::new(19);
+        handler.apply_to_game(&tree, &mut game).unwrap();
+        for (x, y) in fixed_handicap(9, 2).unwrap() {
+            assert_eq!(game.board.get_stone(x, y), Stone::Black);
+        }
+        assert_eq!(game.board.get_stone(5, 3), Stone::Black);
+        assert_eq!(game.board.get_stone(5, 5), Stone::White);
+        assert!(!game.current_player);
+
+        assert_eq!(fixed_handicap(19, 3), Some(vec![(4, 16), (16, 4), (4, 4)]));
+        assert_eq!(fixed_handicap(9, 9).map(|p| p.len()), Some(9));
+        assert_eq!(fixed_handicap(10, 5), None);
+    }
+
+    #[test]
+    fn test_svg_diagram() {
+        use gnugo_rs::server::json_rpc::JsonRpcServer;
+        use gnugo_rs::sgf::SGFHandler;
+        use gnugo_rs::ui::svg::{render_svg, Diagram, SvgOptions};
+
+        // White's A1 is captured by Black's A2
+        let tree = SGFHandler::new().parse("(;FF[4]SZ[9];B[bi];W[ai];B[ah];W[ee];B[cc];W[gg])").unwrap();
+        let diagram = Diagram::from_sgf(&tree, Some(5)).unwrap();
+        assert_eq!(diagram.last_move, Some((3, 3)));
+        assert_eq!(diagram.numbers.get(&(3, 3)), Some(&5));
+        assert_eq!(diagram.numbers.get(&(1, 9)), None);
+        assert_eq!(diagram.board.get_stone(7, 7), Stone::Empty);
+        assert_eq!(Diagram::from_sgf(&tree, None).unwrap().last_move, Some((7, 7)));
+
+        let plain = render_svg(&diagram, &SvgOptions::default());
+        assert!(plain.starts_with("<svg") && plain.trim_end().ends_with("</svg>"));
+        assert_eq!(plain.matches("stroke-width=\"1\"/>").count(), 4);
+        assert!(plain.contains(">J</text>") && !plain.contains(">I</text>"));
+        // Row 5 is labelled on both sides, but no stone carries a number
+        assert_eq!(plain.matches(">5</text>").count(), 2);
+
+        let numbered = render_svg(&diagram, &SvgOptions { move_numbers: true, coordinates: false, ..Default::default() });
+        assert_eq!(numbered.matches(">5</text>").count(), 1);
+        assert!(!numbered.contains(">J</text>"));
+
+        let server = JsonRpcServer::new();
+        let created = server.handle_text(r#"{"jsonrpc":"2.0","method":"new_game","params":{"size":9},"id":1}"#);
+        let game = created.get("result").and_then(|r| r.get("game")).and_then(|g| g.as_usize()).unwrap();
+        let request = format!(r#"{{"jsonrpc":"2.0","method":"svg","params":{{"game":{},"numbers":true}},"id":2}}"#, game);
+        let reply = server.handle_text(&request);
+        let svg = reply.get("result").and_then(|r| r.get("svg")).and_then(|s| s.as_str()).unwrap();
+        assert!(svg.starts_with("<svg"));
+    }
+
+    #[test]
+    fn test_explain_last_move() {
+        use gnugo_rs::engine::ai::AIDifficulty;
+        use gnugo_rs::engine::explain::MoveExplanation;
+        use gnugo_rs::gtp::GTPHandler;
+
+        let mut handler = GTPHandler::new(9);
+        let mut output = Vec::new();
+        let commands = "explain_last_move\nplay black E5\ngenmove white\nexplain_last_move\n";
+        handler.run_with(commands.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let replies: Vec<&str> = output.split("\n\n").collect();
+        assert!(replies[0].starts_with('?') && replies[0].contains("no move generated"));
+        assert!(replies[3].starts_with("= move: white "));
+        assert!(replies[3].contains("\nreason: ranked 1 of 80 legal moves"));
+        assert!(replies[3].contains("\nvalue influence: "));
+        assert!(replies[3].contains("\ncandidates: "));
+
+        // White A1 is in atari; Black B1 captures it
+        let mut board = Board::new(9);
+        board.set_stone(1, 9, Stone::White);
+        board.set_stone(1, 8, Stone::Black);
+        let capture = MoveExplanation::new(&board, Stone::Black, Some((2, 9)), AIDifficulty::Intermediate);
+        assert!(capture.reasons.iter().any(|r| r == "captures 1 stone"));
+        board.set_stone(2, 8, Stone::White);
+        board.set_stone(3, 8, Stone::White);
+        let atari = MoveExplanation::new(&board, Stone::Black, Some((2, 7)), AIDifficulty::Beginner);
+        assert!(atari.reasons[0].starts_with("random choice"));
+        let pass = MoveExplanation::new(&board, Stone::White, None, AIDifficulty::Advanced);
+        assert_eq!(pass.reasons, vec!["pass".to_string()]);
+        assert_eq!(pass.breakdown.len(), 3);
+    }
+
+    #[test]
+    fn test_sgf_trace() {
+        use gnugo_rs::engine::trace::SearchTrace;
+        use gnugo_rs::engine::tsumego::TsumegoSolver;
+        use gnugo_rs::gtp::GTPHandler;
+        use gnugo_rs::sgf::{SGFHandler, SGFProperty};
+
+        // Same corner as test_tsumego_kill_in_corner
+        let mut board = Board::new(9);
+        for &(x, y) in &[(1, 3), (2, 3), (3, 3), (4, 3), (4, 2), (5, 1)] {
+            board.set_stone(x, y, Stone::Black);
+        }
+        for &(x, y) in &[(1, 2), (2, 2), (3, 2), (4, 1)] {
+            board.set_stone(x, y, Stone::White);
+        }
+        let mut solver = TsumegoSolver::new(&board, (2, 2), 10_000);
+        solver.set_trace(SearchTrace::new(&board, Stone::Black));
+        let result = solver.solve(&board, Stone::Black);
+        let trace = solver.take_trace().unwrap();
+        assert!(trace.len() >= result.main_line.len());
+
+        // The main line is a path through the recorded variations
+        let tree = trace.to_sgf_tree();
+        assert_eq!(tree.root.properties["AB"].len(), 6);
+        let search = &tree.root.children[0];
+        let mut node = search;
+        for (color, point) in &result.main_line {
+            let key = if *color == Stone::Black { "B" } else { "W" };
+            let value = point.map_or(SGFProperty::Text(String::new()), |(x, y)| SGFProperty::Point((x - 1, y - 1)));
+            node = node
+                .children
+                .iter()
+                .find(|child| child.properties.get(key) == Some(&vec![value.clone()]))
+                .expect("main line move missing from trace");
+        }
+        let handler = SGFHandler::new();
+        let written = handler.tree_to_sgf(&tree);
+        assert!(written.contains("tsumego B8, Black to move"));
+        assert!(handler.parse(&written).is_ok());
+
+        let path = std::env::temp_dir().join(format!("gnugo_rs_trace_{}.sgf", std::process::id()));
+        let commands = format!(
+            "finish_sgftrace {0}\nplay black E5\nstart_sgftrace\ngenmove white\ngenmove black\nfinish_sgftrace {0}\n",
+            path.display()
+        );
+        let mut output = Vec::new();
+        GTPHandler::new(9).run_with(commands.as_bytes(), &mut output).unwrap();
+        let replies: Vec<String> = String::from_utf8(output).unwrap().split("\n\n").map(String::from).collect();
+        assert!(replies[0].starts_with('?'));
+        assert_eq!(replies[5], "=");
+        let traced = handler.parse(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).ok();
+        // One variation per genmove; the second starts from White's reply
+        assert_eq!(traced.root.children.len(), 2);
+        assert_eq!(traced.root.children[0].children.len(), 80);
+        assert_eq!(traced.root.children[1].properties["AW"].len(), 1);
+    }
+
+    #[test]
+    fn test_level_profiles_and_ladders() {
+        use gnugo_rs::engine::ai::{AIDifficulty, LevelProfile};
+        use gnugo_rs::engine::eye::EyeAnalyzer;
+        use gnugo_rs::engine::tsumego::DEFAULT_MAX_NODES;
+
+        let levels = [AIDifficulty::Beginner, AIDifficulty::Intermediate, AIDifficulty::Advanced];
+        let profiles: Vec<LevelProfile> = levels.iter().map(|level| level.profile()).collect();
+        for pair in profiles.windows(2) {
+            assert!(pair[0].ladder_depth < pair[1].ladder_depth);
+            assert!(pair[0].owl_nodes < pair[1].owl_nodes);
+            assert!(pair[0].playouts < pair[1].playouts);
+            assert!(pair[0].endgame_depth < pair[1].endgame_depth);
+        }
+        assert_eq!(LevelProfile::default().owl_nodes, DEFAULT_MAX_NODES);
+
+        // White K10 against Black J10, K11 and L9 can be laddered toward
+        // either the lower-left or the upper-right corner
+        let mut board = Board::new(19);
+        board.set_stone(10, 10, Stone::White);
+        board.set_stone(9, 10, Stone::Black);
+        board.set_stone(10, 9, Stone::Black);
+        board.set_stone(11, 11, Stone::Black);
+        let eyes = EyeAnalyzer::new();
+        let depth = AIDifficulty::Intermediate.profile().ladder_depth;
+        assert!(eyes.is_ladder_attack(&board, 10, 10, depth));
+        // Too shallow to see the edge
+        assert!(!eyes.is_ladder_attack(&board, 10, 10, AIDifficulty::Beginner.profile().ladder_depth / 4));
+
+        // A breaker on one path leaves the other; breakers on both save White
+        board.set_stone(5, 15, Stone::White);
+        assert_eq!(eyes.find_ladder_attack_point(&board, 10, 10, depth), Some((10, 11)));
+        board.set_stone(15, 5, Stone::White);
+        assert_eq!(eyes.find_ladder_attack_point(&board, 10, 10, depth), None);
+    }
+
+    #[test]
+    fn test_resource_limits() {
+        use gnugo_rs::config::Config;
+        use gnugo_rs::engine::ai::{AIDifficulty, ResourceLimits, AI};
+        use gnugo_rs::engine::tsumego::{LifeStatus, TsumegoSolver};
+
+        let unlimited = ResourceLimits::default();
+        assert_eq!(unlimited.nodes(1234), 1234);
+        assert_eq!(unlimited.table_entries(64), usize::MAX);
+        let limits = ResourceLimits { cache_mb: Some(1), tt_entries: Some(100_000), max_nodes: Some(500), threads: None };
+        assert_eq!(limits.table_entries(64), 16_384);
+        assert_eq!(limits.table_entries(1), 100_000);
+        assert_eq!(limits.nodes(10_000), 500);
+
+        let mut config = Config::from_toml("[engine]\nmax_memory_mb = 64\nmax_nodes = 2000").unwrap();
+        assert_eq!((config.limits.cache_mb, config.limits.max_nodes), (Some(64), Some(2000)));
+        assert!(Config::from_toml("[engine]\nmax_nodes = 0").is_err());
+        let args: Vec<String> = ["--tt-size", "4096", "--max-nodes", "10"].iter().map(|s| s.to_string()).collect();
+        config.apply_args(&args).unwrap();
+        assert_eq!((config.limits.tt_entries, config.limits.max_nodes), (Some(4096), Some(10)));
+
+        // The corner kill needs more than a handful of nodes
+        let mut board = Board::new(9);
+        for &(x, y) in &[(1, 3), (2, 3), (3, 3), (4, 3), (4, 2), (5, 1)] {
+            board.set_stone(x, y, Stone::Black);
+        }
+        for &(x, y) in &[(1, 2), (2, 2), (3, 2), (4, 1)] {
+            board.set_stone(x, y, Stone::White);
+        }
+        let mut solver = TsumegoSolver::new(&board, (2, 2), 10_000);
+        solver.apply_limits(&config.limits);
+        let result = solver.solve(&board, Stone::Black);
+        assert_eq!(result.status, LifeStatus::Unknown);
+
+        // A one-node budget still yields a legal move
+        let ai = AI::new(AIDifficulty::Intermediate).with_limits(ResourceLimits { max_nodes: Some(1), ..unlimited });
+        let (x, y) = ai.get_best_move(&board, Stone::Black).unwrap();
+        assert_eq!(board.get_stone(x, y), Stone::Empty);
+    }
+
+    #[test]
+    fn test_subsystem_stats() {
+        use gnugo_rs::engine::eye::EyeAnalyzer;
+        use gnugo_rs::engine::profile::{self, Subsystem};
+        use gnugo_rs::gtp::GTPHandler;
+
+        profile::reset();
+        profile::begin_move();
+        assert_eq!(profile::measure(Subsystem::Reading, || 6 * 7), 42);
+        let mut board = Board::new(9);
+        board.set_stone(5, 5, Stone::White);
+        board.set_stone(4, 5, Stone::Black);
+        board.set_stone(5, 4, Stone::Black);
+        EyeAnalyzer::new().is_ladder_attack(&board, 5, 5, 10);
+        let stats = profile::stats();
+        assert_eq!(stats.moves, 1);
+        let calls = if profile::ENABLED { 2 } else { 0 };
+        assert_eq!(stats.total.calls(Subsystem::Reading), calls);
+        assert_eq!(stats.last_move.calls(Subsystem::Owl), 0);
+
+        let mut handler = GTPHandler::new(9);
+        let mut output = Vec::new();
+        handler.run_with("genmove black\nstats\nstats reset\n".as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let replies: Vec<&str> = output.split("\n\n").collect();
+        if profile::ENABLED {
+            assert!(replies[1].starts_with("= moves: 2\npatterns: "));
+            assert!(replies[1].contains("\ninfluence: "));
+            assert_eq!(replies[2], "=");
+            assert_eq!(profile::stats().moves, 0);
+        } else {
+            assert!(replies[1].starts_with('?') && replies[1].contains("--features profile"));
+        }
+    }
+
+    #[test]
+    fn test_malformed_input_is_rejected() {
+        use gnugo_rs::gtp::command::{parse_bytes, CommandError};
+        use gnugo_rs::gtp::GTPHandler;
+        use gnugo_rs::sgf::{GameSetupError, SGFHandler, SgfErrorKind, MAX_DEPTH, MAX_NESTING};
+
+        let handler = SGFHandler::new();
+        let inputs: [&[u8]; 10] = [
+            b"", b"(", b"(;", b"(;B[", b"(;B[aa]", b"(;B[aa](;W[bb]", b";B[aa])", b"(;b[aa])", b"(;B[aa]\\", b"(;\xff)",
+        ];
+        for input in inputs {
+            assert!(handler.parse_bytes(input).is_err(), "{:?}", String::from_utf8_lossy(input));
+        }
+        let error = handler.parse_bytes(b"(;SZ[9];B[aa]").unwrap_err();
+        assert_eq!(error.kind, SgfErrorKind::Expected { wanted: ')', found: None });
+        assert_eq!(error.position, 13);
+        assert_eq!(handler.parse_bytes(b"(;\xff)").unwrap_err().kind, SgfErrorKind::InvalidUtf8);
+
+        // Board sizes the engine cannot hold are refused, not allocated
+        for size in ["-1", "0", "1000000", "99999999999999999999", "19:19"] {
+            let tree = handler.parse(&format!("(;SZ[{}])", size)).unwrap();
+            let error = handler.apply_to_game(&tree, &mut Game::new(9)).unwrap_err();
+            assert!(matches!(error, GameSetupError::BoardSize(_)), "{}", size);
+            assert!(error.to_string().starts_with("Unsupported board size"));
+        }
+        for komi in ["nan", "inf", "six"] {
+            let tree = handler.parse(&format!("(;SZ[9]KM[{}])", komi)).unwrap();
+            let error = handler.apply_to_game(&tree, &mut Game::new(9)).unwrap_err();
+            assert!(matches!(error, GameSetupError::Komi(_)), "{}", komi);
+        }
+        let tree = handler.parse("(;SZ[9]KM[])").unwrap();
+        assert!(handler.apply_to_game(&tree, &mut Game::new(9)).is_ok());
+
+        // Long games are read without deep recursion; deeper ones are refused
+        let moves: String = (0..MAX_DEPTH - 1).map(|i| if i % 2 == 0 { ";B[tt]" } else { ";W[tt]" }).collect();
+        let tree = handler.parse(&format!("(;SZ[19]{})", &moves[6..])).unwrap();
+        assert!(handler.tree_to_sgf(&tree.clone()).len() > moves.len());
+        let too_deep = format!("(;SZ[19]{};B[tt])", moves);
+        assert_eq!(handler.parse(&too_deep).unwrap_err().kind, SgfErrorKind::TooDeep);
+        let nested = format!("(;{}{})", "(;".repeat(MAX_NESTING + 1), ")".repeat(MAX_NESTING + 1));
+        assert_eq!(handler.parse(&nested).unwrap_err().kind, SgfErrorKind::TooDeep);
+
+        let command = parse_bytes(b"12 play\tblack D4 # comment\r\n").unwrap();
+        assert_eq!((command.id, command.name.as_str(), command.arg(1)), (Some(12), "play", "D4"));
+        assert_eq!(parse_bytes(b"  # only a comment"), Err(CommandError::Empty));
+        assert_eq!(parse_bytes(b"7"), Err(CommandError::MissingName(7)));
+        assert!(matches!(parse_bytes(b"name\xc3"), Err(CommandError::InvalidUtf8 { valid_up_to: 4 })));
+
+        let mut gtp = GTPHandler::new(9);
+        let mut output = Vec::new();
+        gtp.run_with(&b"na\xffme\nplay black\nplay black Z99\nname\n7\n8 nonsense\n"[..], &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let replies: Vec<&str> = output.split("\n\n").collect();
+        assert!(replies[0].starts_with('?') && replies[0].contains("UTF-8"));
+        assert_eq!((replies[1], replies[2]), ("? invalid move", "? invalid move"));
+        assert_eq!(replies[3], "= gnugo_rs");
+        assert_eq!((replies[4], replies[5]), ("?7 unknown command", "?8 unknown command: nonsense"));
+    }
+
+    #[test]
+    fn test_message_catalog() {
+        use gnugo_rs::config::Config;
+        use gnugo_rs::ui::i18n::{Language, Messages};
+
+        assert_eq!(Language::from_name("zh_CN.UTF-8"), Some(Language::Chinese));
+        assert_eq!(Language::from_name("en-GB"), Some(Language::English));
+        assert_eq!(Language::from_name("C"), Some(Language::English));
+        assert_eq!(Language::from_name("klingon"), None);
+
+        let english = Messages::new(Language::English);
+        let chinese = Messages::new(Language::Chinese);
+        assert_eq!(english.format("Current player: {}", &[&"Black"]), "Current player: Black");
+        assert_eq!(chinese.format("Current player: {}", &[&chinese.get("Black")]), "当前行棋方：黑");
+        assert_eq!(chinese.format("Captured - Black: {}, White: {}", &[&3, &1]), "提子 - 黑：3，白：1");
+        assert_eq!(chinese.get("Suicide move not allowed"), "不允许自杀着");
+        // Untranslated text falls back to English
+        assert_eq!(chinese.get("Something new"), "Something new");
+
+        let mut config = Config::from_toml("[display]\nlanguage = \"chinese\"").unwrap();
+        assert_eq!(config.language, Some(Language::Chinese));
+        config.apply_args(&["--lang".to_string(), "en".to_string()]).unwrap();
+        assert_eq!(config.language, Some(Language::English));
+        assert!(config.apply_args(&["--lang".to_string(), "xx".to_string()]).is_err());
+        assert_eq!(Config::default().language, None);
+    }
+
+    #[test]
+    fn test_accessible_output() {
+        use gnugo_rs::config::Config;
+        use gnugo_rs::ui::accessible::{announce_move, describe_board, describe_row, vertex_name};
+        use gnugo_rs::ui::i18n::{Language, Messages};
+
+        let english = Messages::new(Language::English);
+        assert_eq!(vertex_name(4, 4), "D4");
+        assert_eq!(vertex_name(9, 1), "J1");
+
+        // Black captures two white stones on the top edge by playing E1
+        let mut before = Board::new(9);
+        for (x, y) in [(2, 1), (3, 2), (4, 2)] {
+            before.set_stone(x, y, Stone::Black);
+        }
+        before.set_stone(3, 1, Stone::White);
+        before.set_stone(4, 1, Stone::White);
+        let mut after = before.clone();
+        after.place_stone(5, 1, Stone::Black).unwrap();
+        assert_eq!(
+            announce_move(&english, &before, &after, Stone::Black, Some((5, 1))),
+            "Black plays E1, captures 2 stones."
+        );
+        assert_eq!(announce_move(&english, &after, &after, Stone::White, None), "White passes.");
+        let chinese = Messages::new(Language::Chinese);
+        assert_eq!(announce_move(&chinese, &before, &after, Stone::Black, Some((5, 1))), "黑下在 E1，提 2 子。");
+
+        assert_eq!(describe_row(&english, &after, 1), "Row 1: Black B1, Black E1.");
+        assert_eq!(describe_row(&english, &after, 5), "Row 5: empty.");
+        let text = describe_board(&english, &after);
+        assert_eq!(text, "Row 1: Black B1, Black E1.\nRow 2: Black C2, Black D2.\nOther rows are empty.");
+        assert!(text.chars().all(|c| c.is_ascii() && (c == '\n' || !c.is_control())));
+        assert_eq!(describe_board(&english, &Board::new(9)), "The board is empty.");
+
+        let config = Config::from_toml("[display]\naccessible = true").unwrap();
+        assert!(config.accessible);
+        let mut config = Config::default();
+        config.apply_args(&["--accessible".to_string()]).unwrap();
+        assert!(config.accessible && Config::is_flag("--accessible"));
+    }
+
+    #[test]
+    fn test_game_archive() {
+        use gnugo_rs::cli::games::filter;
+        use gnugo_rs::config::Config;
+        use gnugo_rs::sgf::archive::Archive;
+        use gnugo_rs::sgf::record_to_sgf;
+
+        let dir = std::env::temp_dir().join(format!("gnugo_rs_archive_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let archive = Archive::new(&dir);
+        assert!(archive.entries().unwrap().is_empty());
+
+        let mut game = Game::new(9);
+        game.make_move(3, 3).unwrap();
+        game.make_move(7, 7).unwrap();
+        game.resign().unwrap();
+        let first = archive.save_game("terminal", &game, "Alice", "Bob").unwrap();
+        assert_eq!((first.size, first.result.as_str()), (9, "W+R"));
+        assert_eq!((first.black.as_str(), first.white.as_str()), ("Alice", "Bob"));
+        assert_eq!(first.date.len(), 10);
+        assert!(first.file.starts_with(&first.date) && first.file.ends_with("-terminal.sgf"));
+        assert!(dir.join(&first.file).exists());
+
+        let sgf = record_to_sgf(13, 6.5, &[(Stone::Black, Some((4, 4)))], &[("PB", "gnugo_rs"), ("PW", "gnugo_rs"), ("RE", "B+3.5")]);
+        let second = archive.save("selfplay", &sgf).unwrap();
+        assert_ne!(first.file, second.file);
+        assert_eq!(archive.entries().unwrap(), vec![first.clone(), second.clone()]);
+
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let all = archive.entries().unwrap();
+        assert_eq!(filter(all.clone(), &args(&["--mode", "selfplay"])).unwrap(), vec![second.clone()]);
+        assert_eq!(filter(all.clone(), &args(&["--player", "alice"])).unwrap(), vec![first.clone()]);
+        assert_eq!(filter(all.clone(), &args(&["--size", "13"])).unwrap(), vec![second]);
+        assert!(filter(all.clone(), &args(&["--since", "9999-01-01"])).unwrap().is_empty());
+        assert!(filter(all, &args(&["--size", "big"])).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let config = Config::from_toml("[archive]\ndir = \"/tmp/games\"").unwrap();
+        assert_eq!(config.archive_dir, Some(std::path::PathBuf::from("/tmp/games")));
+        let mut config = Config::default();
+        config.apply_args(&args(&["--archive", "games"])).unwrap();
+        assert_eq!(config.archive_dir, Some(std::path::PathBuf::from("games")));
+    }
+
+    #[test]
+    fn test_rengo_teams() {
+        use gnugo_rs::gtp::GTPHandler;
+        use gnugo_rs::sgf::SGFHandler;
+
+        let mut game = Game::new(9);
+        assert_eq!(game.player_to_move(), None);
+        game.set_team(Stone::Black, vec!["Alice".to_string(), "Carol".to_string()]).unwrap();
+        game.set_team(Stone::White, vec!["Bob".to_string(), "Dave".to_string()]).unwrap();
+        assert!(game.set_team(Stone::Empty, Vec::new()).is_err());
+        assert!(game.set_team(Stone::Black, vec![" ".to_string()]).is_err());
+
+        assert_eq!(game.player_to_move(), Some("Alice"));
+        game.play_as("Alice", Some((3, 3))).unwrap();
+        assert_eq!(game.player_to_move(), Some("Bob"));
+        assert!(game.play_as("Dave", Some((7, 7))).is_err());
+        game.play_as("bob", Some((7, 7))).unwrap();
+        assert_eq!(game.player_to_move(), Some("Carol"));
+        game.play_as("Carol", None).unwrap();
+        assert_eq!(game.player_to_move(), Some("Dave"));
+        game.play_as("Dave", Some((3, 7))).unwrap();
+        assert_eq!(game.player_to_move(), Some("Alice"));
+        game.undo_move();
+        assert_eq!(game.player_to_move(), Some("Dave"));
+        assert_eq!(game.team_label(Stone::Black).as_deref(), Some("Alice & Carol"));
+
+        let sgf = SGFHandler::new().game_to_sgf(&game, None).unwrap();
+        assert!(sgf.contains("PB[Alice & Carol]PW[Bob & Dave]"));
+
+        let mut output = Vec::new();
+        let commands = "rengo_team black Ann Cy\nrengo_team white Ben\nrengo_to_play\nplay black D4\nrengo_to_play\nrengo_team green X\nrengo_team black\nrengo_to_play\n";
+        GTPHandler::new(9).run_with(commands.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let replies: Vec<&str> = output.split("\n\n").collect();
+        assert_eq!(&replies[..4], &["=", "=", "= Ann", "="]);
+        assert_eq!(replies[4], "= Ben");
+        assert!(replies[5].starts_with('?'));
+        assert_eq!(&replies[6..8], &["=", "= Ben"]);
+    }
+
+    #[test]
+    fn test_capture_go() {
+        use gnugo_rs::config::Config;
+        use gnugo_rs::engine::ai::{AIDifficulty, AI};
+        use gnugo_rs::engine::rules::Variant;
+
+        assert_eq!(Variant::from_name("atari"), Some(Variant::Capture { target: 1 }));
+        assert_eq!(Variant::from_name("standard"), Some(Variant::Standard));
+        assert_eq!(Variant::from_name("chess"), None);
+
+        // Black D1 captures the white stone on C1 and wins at once
+        let mut game = Game::new(9);
+        game.variant = Variant::Capture { target: 1 };
+        for (x, y) in [(2, 1), (3, 1), (3, 2), (5, 5)] {
+            game.make_move(x, y).unwrap();
+        }
+        assert!(!game.is_game_over());
+        let ai = AI::new(AIDifficulty::Intermediate).with_variant(game.variant);
+        assert_eq!(ai.get_best_move(&game.board, Stone::Black), Some((4, 1)));
+        game.make_move(4, 1).unwrap();
+        assert!(game.is_game_over());
+        assert_eq!(game.status(), "Capture target reached");
+        assert_eq!((game.winner(), game.prisoners(Stone::Black)), (Some(Stone::Black), 1));
+        assert!(game.make_move(7, 7).is_err());
+
+        // With a higher target the game goes on, and the AI saves its stone in atari
+        let mut game = Game::new(9);
+        game.variant = Variant::Capture { target: 2 };
+        for (x, y) in [(2, 1), (1, 1), (5, 5), (3, 3), (1, 2)] {
+            game.make_move(x, y).unwrap();
+        }
+        assert_eq!(game.prisoners(Stone::Black), 1);
+        assert!(!game.is_game_over());
+        let mut board = Board::new(9);
+        board.set_stone(5, 5, Stone::White);
+        board.set_stone(4, 5, Stone::Black);
+        board.set_stone(6, 5, Stone::Black);
+        board.set_stone(5, 4, Stone::Black);
+        let ai = AI::new(AIDifficulty::Intermediate).with_variant(Variant::Capture { target: 1 });
+        assert_eq!(ai.get_best_move(&board, Stone::White), Some((5, 6)));
+        assert_eq!(ai.get_best_move(&board, Stone::Black), Some((5, 6)));
+
+        // Passing twice compares captures
+        let mut game = Game::new(9);
+        game.variant = Variant::Capture { target: 5 };
+        game.pass().unwrap();
+        game.pass().unwrap();
+        assert_eq!(game.winner(), None);
+
+        let config = Config::from_toml("[game]\nvariant = \"capture\"\ncapture_target = 3").unwrap();
+        assert_eq!(config.new_game().variant, Variant::Capture { target: 3 });
+        let mut config = Config::default();
+        config.apply_args(&["--capture-target".to_string(), "2".to_string()]).unwrap();
+        assert_eq!(config.variant, Variant::Capture { target: 2 });
+        config.apply_args(&["--variant".to_string(), "capture".to_string()]).unwrap();
+        assert_eq!(config.variant, Variant::Capture { target: 2 });
+        assert!(config.apply_args(&["--capture-target".to_string(), "0".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_joseki_lookup() {
+        use gnugo_rs::gtp::GTPHandler;
+        use gnugo_rs::patterns::joseki::{identify, Corner};
+
+        assert_eq!(Corner::from_name("upper_right"), Some(Corner::UpperRight));
+        assert_eq!(Corner::nearest(3, 17, 19), Corner::LowerLeft);
+
+        let empty = identify(&Board::new(19), Corner::UpperLeft, Stone::Black).unwrap();
+        assert_eq!((empty.name, empty.moves), (None, 0));
+        assert!(empty.continuations.iter().any(|c| c.point == (4, 4) && c.color == Stone::Black));
+
+        let mut output = Vec::new();
+        let commands = "boardsize 19\nplay black Q16\nplay white R14\njoseki ur\njoseki Q3\njoseki xx\nplay black C4\nplay white E3\njoseki ll\n";
+        GTPHandler::new(19).run_with(commands.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let replies: Vec<&str> = output.split("\n\n").collect();
+        let lines: Vec<&str> = replies[3].lines().collect();
+        assert_eq!(lines[0], "= Star point, low approach");
+        assert!(lines.contains(&"B O17 Star point, low approach, knight's move response: Solid: keeps the corner and looks at the other side"));
+        assert!(lines.iter().any(|l| l.starts_with("B R11 Star point, low approach, two-space low pincer")));
+        assert!(replies[4].starts_with("= empty corner\nB Q4"));
+        assert!(replies[5].starts_with('?'));
+        // A 3-4 point in the lower left with a small knight approach
+        assert!(replies[8].starts_with("= 3-4 point, small knight approach"));
+
+        // A position outside the database is not named
+        let mut board = Board::new(19);
+        board.set_stone(4, 4, Stone::Black);
+        board.set_stone(5, 5, Stone::Black);
+        assert!(identify(&board, Corner::UpperLeft, Stone::White).is_none());
+    }
+
+    #[test]
+    fn test_opening_recognition() {
+        use gnugo_rs::patterns::fuseki;
+        use gnugo_rs::sgf::opening::{annotate, recognize};
+        use gnugo_rs::sgf::{SGFHandler, SGFProperty};
+
+        let mut board = Board::new(19);
+        for (x, y) in [(16, 4), (16, 16)] {
+            board.set_stone(x, y, Stone::Black);
+        }
+        assert_eq!(fuseki::identify(&board, Stone::Black), Some("Nirensei"));
+        board.set_stone(16, 10, Stone::Black);
+        assert_eq!(fuseki::identify(&board, Stone::Black), Some("Sanrensei"));
+        assert_eq!(fuseki::identify(&board, Stone::White), None);
+        assert_eq!(fuseki::identify(&Board::new(9), Stone::Black), None);
+
+        // Black: Q16, R4, then R10; White takes two corners and approaches Q16 at R14
+        let sgf = "(;FF[4]GM[1]SZ[19];B[pd]C[Opening];W[dp];B[qp];W[dd];B[qj];W[qf])";
+        let handler = SGFHandler::new();
+        let mut tree = handler.parse(sgf).unwrap();
+        let events = recognize(&tree).unwrap();
+        let described: Vec<(usize, &str)> = events.iter().map(|e| (e.move_number, e.description.as_str())).collect();
+        assert!(described.contains(&(1, "Upper right corner: Star point (hoshi)")));
+        assert!(!described.iter().any(|&(_, d)| d == "Black: Nirensei"));
+        assert!(described.contains(&(5, "Black: Low Chinese opening")));
+        assert!(described.contains(&(6, "Upper right corner: Star point, low approach")));
+
+        annotate(&mut tree, &events);
+        let first = &tree.root.children[0];
+        match first.properties.get("C").and_then(|c| c.first()) {
+            Some(SGFProperty::Text(text)) => assert_eq!(text, "Opening\nUpper right corner: Star point (hoshi)"),
+            other => panic!("unexpected comment {:?}", other),
+        }
+        assert!(handler.tree_to_sgf(&tree).contains("C[Black: Low Chinese opening"));
+    }
+
+    #[test]
+    fn test_blunder_annotation() {
+        use gnugo_rs::cli::annotate::{annotate, review, DEFAULT_THRESHOLD};
+        use gnugo_rs::sgf::{SGFHandler, SGFProperty};
+
+        // Black ignores three white stones in atari at E6 and plays in the corner
+        let sgf = "(;FF[4]GM[1]SZ[9]AB[bc][fc][cb][db][eb][cd][dd]AW[cc][dc][ec];B[ia];W[ed])";
+        let handler = SGFHandler::new();
+        let mut tree = handler.parse(sgf).unwrap();
+        let mistakes = review(&tree, DEFAULT_THRESHOLD).unwrap();
+        assert_eq!(mistakes.len(), 1);
+        let blunder = &mistakes[0];
+        assert_eq!((blunder.move_number, blunder.color, blunder.played), (1, Stone::Black, Some((9, 1))));
+        assert_eq!(blunder.best, (5, 4));
+        assert!(blunder.loss > DEFAULT_THRESHOLD);
+        assert!(review(&tree, 1000).unwrap().is_empty());
+
+        annotate(&mut tree, &mistakes, 9);
+        let node = &tree.root.children[0];
+        assert!(node.properties.contains_key("BM"));
+        match node.properties.get("C").and_then(|c| c.first()) {
+            Some(SGFProperty::Text(text)) => {
+                assert!(text.starts_with("Black J9 loses ") && text.ends_with("it prefers E6"))
+            }
+            other => panic!("unexpected comment {:?}", other),
+        }
+        assert!(!node.children[0].properties.contains_key("BM"));
+        assert!(handler.tree_to_sgf(&tree).contains("BM[1]"));
+    }
+
+    #[test]
+    fn test_strength_estimate() {
+        use gnugo_rs::json::JsonValue;
+        use gnugo_rs::server::json_rpc::JsonRpcServer;
+        use gnugo_rs::sgf::review::{player_colors, review_moves, PlayerStats, Rank};
+        use gnugo_rs::sgf::SGFHandler;
+
+        let sgf = "(;FF[4]GM[1]SZ[9]PB[Alice]PW[Bob]AB[bc][fc][cb][db][eb][cd][dd]AW[cc][dc][ec];B[ia];W[ed])";
+        let tree = SGFHandler::new().parse(sgf).unwrap();
+        assert_eq!(player_colors(&tree, "alice"), vec![Stone::Black]);
+        assert!(player_colors(&tree, "Carol").is_empty());
+
+        let reviews = review_moves(&tree).unwrap();
+        assert_eq!(reviews.len(), 2);
+        let mut black = PlayerStats::default();
+        black.add(&reviews, Stone::Black);
+        assert_eq!((black.moves, black.matches, black.blunders), (1, 0, 1));
+        assert_eq!(black.rank(), None);
+
+        let strong = PlayerStats { moves: 100, matches: 80, top3: 95, blunders: 0, total_loss: 50 };
+        let weak = PlayerStats { moves: 100, matches: 5, top3: 15, blunders: 30, total_loss: 1500 };
+        assert!(matches!(strong.rank(), Some(Rank::Dan(_))));
+        assert_eq!(weak.rank(), Some(Rank::Kyu(30)));
+
+        let server = JsonRpcServer::new();
+        let request = JsonValue::object(vec![
+            ("jsonrpc", "2.0".into()),
+            ("method", "strength".into()),
+            ("params", JsonValue::object(vec![("sgf", vec![sgf].into()), ("player", "Alice".into())])),
+            ("id", 1usize.into()),
+        ]);
+        let result = server.handle(&request);
+        let stats = result.get("result").expect("strength result");
+        assert_eq!(stats.get("moves").and_then(|m| m.as_usize()), Some(1));
+        assert_eq!(stats.get("blunders").and_then(|m| m.as_usize()), Some(1));
+        assert_eq!(stats.get("rank"), Some(&JsonValue::Null));
+
+        let missing = server.handle_text(r#"{"jsonrpc":"2.0","method":"strength","params":{"sgf":"(;SZ[9])"},"id":2}"#);
+        assert!(missing.get("error").is_some());
+    }
+
+    #[test]
+    fn test_game_statistics() {
+        use gnugo_rs::gtp::GTPHandler;
+        use std::time::Duration;
+
+        // Black takes a ko at C2, White retakes it at B2 after a threat exchange
+        let mut game = Game::new(9);
+        let moves = [(2, 1), (3, 1), (1, 2), (4, 2), (2, 3), (3, 3), (9, 9), (2, 2), (3, 2), (7, 7), (6, 6), (2, 2)];
+        for (x, y) in moves {
+            game.make_move(x, y).unwrap();
+        }
+        game.pass().unwrap();
+        let statistics = game.statistics();
+        assert_eq!((statistics.moves, statistics.passes), (12, 1));
+        assert_eq!(statistics.captures, [1, 1]);
+        assert_eq!((statistics.largest_capture, statistics.ko_fights), (1, 1));
+        assert_eq!(statistics.average_move_time, None);
+
+        game.record_move_time(Duration::from_secs(2));
+        game.record_move_time(Duration::from_secs(4));
+        assert_eq!(game.statistics().average_move_time, Some(Duration::from_secs(3)));
+        let text = game.statistics().to_text();
+        assert!(text.contains("captures black 1 white 1\n") && text.ends_with("average_move_time 3.00"));
+        assert_eq!(game.statistics().to_json().get("ko_fights").and_then(|k| k.as_usize()), Some(1));
+
+        let mut output = Vec::new();
+        GTPHandler::new(9).run_with("play black E5\ngenmove white\ngame_statistics\n".as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let reply = output.split("\n\n").nth(2).unwrap();
+        assert!(reply.starts_with("= moves 2\npasses 0\n"));
+        assert!(reply.contains("average_move_time "));
+    }
+
+    #[test]
+    fn test_fast_board_matches_board() {
+        use gnugo_rs::engine::fast_board::FastBoard;
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
+
+        // Ko: White may not retake at B2 at once
+        let mut board = Board::new(9);
+        for (x, y, stone) in [(2, 1, Stone::Black), (1, 2, Stone::Black), (2, 3, Stone::Black), (3, 1, Stone::White), (4, 2, Stone::White), (3, 3, Stone::White), (2, 2, Stone::White)] {
+            board.place_stone(x, y, stone).unwrap();
+        }
+        let mut fast = FastBoard::from_board(&board);
+        assert!(fast.play(3, 2, Stone::Black));
+        assert_eq!(fast.get_stone(2, 2), Stone::Empty);
+        assert!(!fast.is_legal(2, 2, Stone::White));
+        assert!(fast.play(7, 7, Stone::White));
+        assert!(fast.is_legal(2, 2, Stone::White));
+
+        // Random sequences agree with Board on legality, captures and ko
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..5 {
+            let mut board = Board::new(9);
+            let mut fast = FastBoard::from_board(&board);
+            let mut color = Stone::Black;
+            for _ in 0..400 {
+                let (x, y) = (rng.gen_range(1..=9), rng.gen_range(1..=9));
+                let legal = board.clone().place_stone(x, y, color).is_ok();
+                assert_eq!(fast.is_legal(x, y, color), legal, "{:?} at ({}, {})", color, x, y);
+                if legal {
+                    board.place_stone(x, y, color).unwrap();
+                    assert!(fast.play(x, y, color));
+                    color = if color == Stone::Black { Stone::White } else { Stone::Black };
+                }
+                for y in 1..=9 {
+                    for x in 1..=9 {
+                        assert_eq!(fast.get_stone(x, y), board.get_stone(x, y));
+                    }
+                }
+            }
+        }
+
+        // A playout leaves only eyes and dame
+        let mut end = FastBoard::from_board(&Board::new(9));
+        end.playout(Stone::Black, 243, &mut rng);
+        assert!(end.empty_points() < 30);
+        assert!((1..=9).all(|y| (1..=9).all(|x| end.get_stone(x, y) != Stone::Empty || !end.is_legal(x, y, Stone::Black) || end.owner(x, y) != Stone::Empty)));
+    }
+
+    #[test]
+    fn test_superko_move_generation() {
+        use gnugo_rs::engine::ai::AI;
+        use gnugo_rs::engine::move_generation::{Move, MoveGenerator};
+        use gnugo_rs::engine::rules::{GameRules, KoRule};
+
+        // Black takes the ko at C2; White's retake at B2 would repeat a position
+        let mut game = Game::new(9);
+        for (x, y) in [(2, 1), (3, 1), (1, 2), (4, 2), (2, 3), (3, 3), (9, 9), (2, 2), (3, 2)] {
+            game.make_move(x, y).unwrap();
+        }
+        let retake = Move::new(2, 2);
+        for ko_rule in [KoRule::None, KoRule::Simple, KoRule::Superko] {
+            game.rules = GameRules { ko_rule, ..GameRules::default() };
+            let moves = MoveGenerator::generate_valid_moves(&game.board, Stone::White, &game.rules, &game.position_hashes());
+            assert_eq!(moves.contains(&retake), ko_rule == KoRule::None);
+            for y in 1..=9 {
+                for x in 1..=9 {
+                    assert_eq!(moves.contains(&Move::new(x, y)), game.clone().make_move(x, y).is_ok(), "({}, {})", x, y);
+                }
+            }
+        }
+
+        // Superko still forbids the retake once the ko point is forgotten
+        let mut board = game.board.clone();
+        board.clear_ko_point();
+        let history = game.position_hashes();
+        let simple = GameRules::default();
+        let superko = GameRules { ko_rule: KoRule::Superko, ..simple };
+        assert!(MoveGenerator::generate_valid_moves(&board, Stone::White, &simple, &history).contains(&retake));
+        assert!(!MoveGenerator::generate_valid_moves(&board, Stone::White, &superko, &history).contains(&retake));
+        assert_eq!(superko.play(&board, 2, 2, Stone::White, &history).unwrap_err(), "Superko violation");
+
+        // The AI's own candidates obey the same rules
+        game.rules = superko;
+        game.board = board;
+        for level in [5, 8] {
+            let ai = AI::for_level(level);
+            assert_eq!(ai.get_best_move(&game.board, Stone::White), Some((2, 2)));
+            let choice = ai.get_game_move(&game, Stone::White).unwrap();
+            assert_ne!(choice, (2, 2));
+            assert!(game.clone().make_move(choice.0, choice.1).is_ok());
+        }
+        for seed in 0..20 {
+            assert_ne!(AI::for_level(1).with_seed(seed).get_game_move(&game, Stone::White), Some((2, 2)));
+        }
+    }
+
+    #[test]
+    fn test_reload_patterns() {
+        use gnugo_rs::gtp::GTPHandler;
+
+        let dir = std::env::temp_dir().join(format!("gnugo_rs_patterns_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        // Header (magic, version, count), then two patterns with one value each
+        let words: [u32; 9] = [0x474E5547, 1, 2, 10, 1, 10, 5, 11, 0];
+        let bytes: Vec<u8> = words.iter().flat_map(|w| w.to_le_bytes()).collect();
+        std::fs::write(dir.join("attack.db"), &bytes).unwrap();
+
+        let run = |commands: String| {
+            let mut output = Vec::new();
+            let mut handler = GTPHandler::new(9);
+            handler.run_with(commands.as_bytes(), &mut output).unwrap();
+            String::from_utf8(output).unwrap()
+        };
+        let output = run(format!("reload_patterns {}\nknown_command reload_patterns\n", dir.display()));
+        let replies: Vec<&str> = output.split("\n\n").collect();
+        let lines: Vec<&str> = replies[0].lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[0], "= attack 2");
+        assert!(lines[1].starts_with("defense error: ") && lines[1].ends_with("(kept 0)"));
+        assert_eq!(replies[1], "= true");
+
+        // A broken file keeps the patterns loaded before it
+        let broken = dir.join("broken");
+        std::fs::create_dir_all(&broken).unwrap();
+        std::fs::write(broken.join("attack.db"), b"not a database").unwrap();
+        let output = run(format!("reload_patterns {}\nreload_patterns {}\n", dir.display(), broken.display()));
+        let second = output.split("\n\n").nth(1).unwrap();
+        assert!(second.starts_with("= attack error: ") && second.lines().next().unwrap().ends_with("(kept 2)"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_handicap_style() {
+        use gnugo_rs::engine::ai::{AIDifficulty, Style, AI};
+        use gnugo_rs::gtp::GTPHandler;
+
+        assert_eq!(Style::for_handicap(4, Stone::White).aggression, 4);
+        assert_eq!(Style::for_handicap(4, Stone::Black).aggression, -4);
+        assert_eq!(Style::for_handicap(1, Stone::White), Style::default());
+
+        let mut game = Game::new(9);
+        let stones = game.place_handicap(4).unwrap();
+        assert_eq!(stones.len(), 4);
+        assert_eq!((game.handicap, game.current_player()), (4, Stone::White));
+        assert!(game.place_handicap(2).is_err());
+
+        // Stones of `color` within two points of a move
+        let nearby = |board: &Board, (x, y): (usize, usize), color: Stone| {
+            (1..=9usize)
+                .flat_map(|ny| (1..=9usize).map(move |nx| (nx, ny)))
+                .filter(|&(nx, ny)| nx.abs_diff(x) + ny.abs_diff(y) <= 2 && board.get_stone(nx, ny) == color)
+                .count()
+        };
+        let ai = AI::new(AIDifficulty::Intermediate);
+        let plain = ai.get_best_move(&game.board, Stone::White).unwrap();
+        let bold = AI::new(AIDifficulty::Intermediate)
+            .with_style(Style::for_handicap(9, Stone::White))
+            .get_best_move(&game.board, Stone::White)
+            .unwrap();
+        assert!(nearby(&game.board, bold, Stone::Black) > nearby(&game.board, plain, Stone::Black));
+
+        game.make_move(bold.0, bold.1).unwrap();
+        let plain = ai.get_best_move(&game.board, Stone::Black).unwrap();
+        let solid = AI::new(AIDifficulty::Intermediate)
+            .with_style(Style::for_handicap(9, Stone::Black))
+            .get_best_move(&game.board, Stone::Black)
+            .unwrap();
+        assert!(nearby(&game.board, solid, Stone::White) < nearby(&game.board, plain, Stone::White));
+
+        let mut output = Vec::new();
+        GTPHandler::new(9).run_with("fixed_handicap 4\ngenmove white\nfixed_handicap 2\n".as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let replies: Vec<&str> = output.split("\n\n").collect();
+        assert_eq!(replies[0], "= C3 G7 C7 G3");
+        assert!(replies[1].starts_with("= "));
+        assert!(replies[2].starts_with('?'));
+    }
+
+    #[test]
+    fn test_flat_board_indices() {
+        let mut board = Board::new(9);
+        assert_eq!(board.stride(), 11);
+        let corner = board.point(1, 1);
+        assert_eq!(board.coords(corner), (1, 1));
+        assert_eq!(board.adjacent(corner).map(|n| board.coords(n)).collect::<Vec<_>>(), vec![(2, 1), (1, 2)]);
+        let center = board.point(5, 5);
+        assert_eq!(board.adjacent(center).collect::<Vec<_>>(), vec![center - 1, center + 1, center - 11, center + 11]);
+        assert!(!board.is_on_board(board.point(0, 3)) && !board.is_on_board(board.point(10, 9)));
+        assert_eq!(board.points().count(), 81);
+
+        board.place_stone(5, 5, Stone::Black).unwrap();
+        assert_eq!(board.stone_at(center), Stone::Black);
+        assert_eq!(board.stone_at(board.point(0, 5)), Stone::Empty);
+        assert_eq!(board.stones_on_board(Stone::Empty), 80);
+        for (x, y) in [(4, 5), (6, 5), (5, 4)] {
+            board.place_stone(x, y, Stone::White).unwrap();
+        }
+        assert_eq!(board.find_liberties(5, 5), vec![(5, 6)]);
+        board.place_stone(5, 6, Stone::White).unwrap();
+        assert_eq!((board.get_stone(5, 5), board.get_captured()), (Stone::Empty, [1, 0]));
+    }
+
+    #[test]
+    fn test_try_move_and_pop() {
+        let mut board = Board::new(9);
+        for (x, y, stone) in [(2, 1, Stone::Black), (1, 2, Stone::Black), (2, 3, Stone::Black), (3, 1, Stone::White), (4, 2, Stone::White), (3, 3, Stone::White), (2, 2, Stone::White)] {
+            board.place_stone(x, y, stone).unwrap();
+        }
+        let snapshot = |b: &Board| {
+            let stones: Vec<Stone> = (1..=9).flat_map(|y| (1..=9).map(move |x| (x, y))).map(|(x, y)| b.get_stone(x, y)).collect();
+            (stones, b.get_captured(), b.get_ko_point(), b.position_hash())
+        };
+        let before = snapshot(&board);
+
+        // Black takes the ko, White cannot retake, and both are undone in order
+        assert!(board.try_move(3, 2, Stone::Black));
+        let after_capture = snapshot(&board);
+        assert_eq!((board.get_stone(2, 2), board.get_ko_point()), (Stone::Empty, Some((2, 2))));
+        assert!(!board.try_move(2, 2, Stone::White));
+        assert_eq!(board.stack_depth(), 1);
+        assert!(board.try_move(7, 7, Stone::White));
+        assert_ne!(board.position_hash(), after_capture.3);
+        assert!(board.pop_move());
+        assert_eq!(snapshot(&board), after_capture);
+        assert!(board.pop_move());
+        assert_eq!(snapshot(&board), before);
+        assert!(!board.pop_move());
+
+        // The incremental hash agrees with a board built from scratch
+        board.try_move(3, 2, Stone::Black);
+        let mut rebuilt = Board::new(9);
+        for (x, y, stone) in [(2, 1, Stone::Black), (1, 2, Stone::Black), (2, 3, Stone::Black), (3, 1, Stone::White), (4, 2, Stone::White), (3, 3, Stone::White), (3, 2, Stone::Black)] {
+            rebuilt.set_stone(x, y, stone);
+        }
+        assert_eq!(board.position_hash(), rebuilt.position_hash());
+    }
+
+    #[test]
+    fn test_place_stone_details() {
+        let mut board = Board::new(9);
+        for (x, y, stone) in [(2, 1, Stone::Black), (1, 2, Stone::Black), (2, 3, Stone::Black), (2, 2, Stone::White), (3, 1, Stone::White), (4, 2, Stone::White), (3, 3, Stone::White)] {
+            board.set_stone(x, y, stone);
+        }
+
+        let quiet = board.place_stone_detailed(7, 7, Stone::Black).unwrap();
+        assert!(quiet.captured.is_empty() && !quiet.created_ko());
+
+        // Taking the ko removes one white stone and bans the retake
+        let ko = board.place_stone_detailed(3, 2, Stone::Black).unwrap();
+        assert_eq!(ko.captured, vec![(2, 2)]);
+        assert_eq!(ko.captured_by_color, [0, 1]);
+        assert_eq!(ko.ko_point, Some((2, 2)));
+        assert!(ko.created_ko());
+        assert!(board.place_stone_detailed(2, 2, Stone::White).is_err());
+
+        // A two-stone capture by White, counted against Black
+        let mut board = Board::new(9);
+        for (x, y, stone) in [(1, 1, Stone::Black), (2, 1, Stone::Black), (1, 2, Stone::White), (2, 2, Stone::White)] {
+            board.set_stone(x, y, stone);
+        }
+        let placement = board.place_stone_detailed(3, 1, Stone::White).unwrap();
+        assert_eq!(placement.captured.len(), 2);
+        assert!(placement.captured.contains(&(1, 1)) && placement.captured.contains(&(2, 1)));
+        assert_eq!(placement.captured_by_color, [2, 0]);
+        assert!(!placement.created_ko());
+    }
+
+    #[test]
+    fn test_board_is_legal() {
+        use gnugo_rs::engine::board::IllegalMove;
+        use gnugo_rs::engine::rules::{GameRules, KoRule};
+
+        let rules = GameRules::default();
+        let mut board = Board::new(9);
+        // White corner stone in atari at (1, 1); Black eye at (9, 9)
+        for (x, y, stone) in [(1, 1, Stone::White), (2, 1, Stone::Black), (8, 9, Stone::Black), (9, 8, Stone::Black)] {
+            board.set_stone(x, y, stone);
+        }
+        assert_eq!(board.is_legal(0, 1, Stone::Black, &rules), Err(IllegalMove::OutOfBounds));
+        assert_eq!(board.is_legal(5, 5, Stone::Empty, &rules), Err(IllegalMove::NoColor));
+        assert_eq!(board.is_legal(2, 1, Stone::White, &rules), Err(IllegalMove::Occupied));
+        assert_eq!(board.is_legal(9, 9, Stone::White, &rules), Err(IllegalMove::Suicide));
+        assert_eq!(board.is_legal(9, 9, Stone::Black, &rules), Ok(()));
+        // Filling its own last liberty is suicide for White, a capture for Black
+        assert_eq!(board.is_legal(1, 2, Stone::White, &rules), Ok(()));
+        assert_eq!(board.is_legal(1, 2, Stone::Black, &rules), Ok(()));
+
+        // The ko point is illegal unless the rules ignore ko
+        let mut board = Board::new(9);
+        for (x, y, stone) in [(2, 1, Stone::Black), (1, 2, Stone::Black), (2, 3, Stone::Black), (2, 2, Stone::White), (3, 1, Stone::White), (4, 2, Stone::White), (3, 3, Stone::White)] {
+            board.set_stone(x, y, stone);
+        }
+        board.place_stone(3, 2, Stone::Black).unwrap();
+        assert_eq!(board.is_legal(2, 2, Stone::White, &rules), Err(IllegalMove::Ko));
+        let no_ko = GameRules { ko_rule: KoRule::None, ..rules };
+        assert_eq!(board.is_legal(2, 2, Stone::White, &no_ko), Ok(()));
+
+        // Agrees with actually playing on every point
+        for y in 1..=9 {
+            for x in 1..=9 {
+                for color in [Stone::Black, Stone::White] {
+                    let played = board.clone().place_stone(x, y, color).is_ok();
+                    assert_eq!(board.is_legal(x, y, color, &rules).is_ok(), played, "{:?} at ({}, {})", color, x, y);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_board_ascii_diagrams() {
+        let board = Board::from_ascii(
+            "
+            . X .
+            X O X
+            . X .
+            ",
+        )
+        .unwrap();
+        assert_eq!(board.size(), 3);
+        assert_eq!(board.get_stone(2, 2), Stone::White);
+        assert_eq!(board.get_stone(2, 1), Stone::Black);
+        assert_eq!(board.get_stone(1, 1), Stone::Empty);
+        assert_eq!(board.count_liberties(2, 2), 0);
+        assert_eq!(board.to_ascii(), ".X.\nXOX\n.X.");
+
+        let round_trip = Board::from_ascii(&board.to_ascii()).unwrap();
+        assert_eq!(round_trip.position_hash(), board.position_hash());
+        assert!(Board::from_ascii("+x\no+").is_ok());
+
+        assert!(Board::from_ascii("").is_err());
+        assert!(Board::from_ascii("..\n...").is_err());
+        assert!(Board::from_ascii("..\n.#").is_err());
+    }
+
+    #[test]
+    fn test_game_with_rules() {
+        use gnugo_rs::engine::rules::{GameRules, KoRule};
+
+        // Black at (1, 1) and (2, 1) has one liberty left at (3, 1)
+        let setup = |game: &mut Game| {
+            for (x, y, stone) in [(1, 1, Stone::Black), (2, 1, Stone::Black), (1, 2, Stone::White), (2, 2, Stone::White), (4, 1, Stone::White), (3, 2, Stone::White)] {
+                game.board.set_stone(x, y, stone);
+            }
+        };
+        let mut game = Game::new(9);
+        setup(&mut game);
+        assert!(game.make_move(3, 1).is_err());
+
+        let suicide = GameRules { allow_suicide: true, ..GameRules::default() };
+        let mut game = Game::with_rules(9, suicide);
+        setup(&mut game);
+        let before = game.board.move_number();
+        game.make_move(3, 1).unwrap();
+        assert_eq!(game.board.move_number(), before + 1);
+        assert_eq!((1..=3).map(|x| game.board.get_stone(x, 1)).collect::<Vec<_>>(), vec![Stone::Empty; 3]);
+        assert_eq!(game.captured_stones, [3, 0]);
+        assert!(!game.current_player);
+
+        // Without a ko rule the ko may be retaken at once
+        let no_ko = GameRules { ko_rule: KoRule::None, ..GameRules::default() };
+        for (rules, retake) in [(GameRules::default(), false), (no_ko, true)] {
+            let mut game = Game::with_rules(9, rules);
+            for (x, y, stone) in [(2, 1, Stone::Black), (1, 2, Stone::Black), (2, 3, Stone::Black), (2, 2, Stone::White), (3, 1, Stone::White), (4, 2, Stone::White), (3, 3, Stone::White)] {
+                game.board.set_stone(x, y, stone);
+            }
+            game.make_move(3, 2).unwrap();
+            // The board agrees with itself on the ko point
+            let board = game.board.clone();
+            assert_eq!(board.is_legal(2, 2, Stone::White, &rules).is_ok(), retake);
+            assert_eq!(board.clone().place_stone_under(2, 2, Stone::White, &rules).is_ok(), retake);
+            assert_eq!(game.make_move(2, 2).is_ok(), retake);
+        }
+    }
+
+    #[test]
+    fn test_suicide_rulesets() {
+        use gnugo_rs::config::Config;
+        use gnugo_rs::engine::rules::{GameRules, KoRule, ScoringRule};
+        use gnugo_rs::sgf::SGFHandler;
+
+        let nz = GameRules::from_name("New-Zealand").unwrap();
+        assert!(nz.allow_suicide && nz.ko_rule == KoRule::Superko);
+        assert!(GameRules::from_name("ing").unwrap().allow_suicide);
+        let japanese = GameRules::from_name("japanese").unwrap();
+        assert!(!japanese.allow_suicide && japanese.scoring == ScoringRule::Territory);
+        assert_eq!(GameRules::from_name("area").unwrap().ko_rule, KoRule::Simple);
+        assert!(GameRules::from_name("klingon").is_none());
+
+        let args: Vec<String> = ["--ruleset", "nz"].iter().map(|s| s.to_string()).collect();
+        let mut config = Config::default();
+        config.apply_args(&args).unwrap();
+        assert!(config.new_game().rules.allow_suicide);
+
+        // White's suicide at C9 removes its three stones when replayed under NZ rules
+        let sgf = "(;GM[1]SZ[9]RU[NZ];B[da];W[aa];B[ab];W[ba];B[bb];W[ee];B[cb];W[ca])";
+        let handler = SGFHandler::new();
+        let mut game = Game::new(19);
+        handler.apply_to_game(&handler.parse(sgf).unwrap(), &mut game).unwrap();
+        assert!(game.rules.allow_suicide);
+        assert_eq!((1..=3).map(|x| game.board.get_stone(x, 1)).collect::<Vec<_>>(), vec![Stone::Empty; 3]);
+        assert_eq!(game.captured_stones, [0, 3]);
+        let mut strict = Game::new(19);
+        assert!(handler.apply_to_game(&handler.parse(&sgf.replace("RU[NZ]", "")).unwrap(), &mut strict).is_err());
+
+        // A lone stone may not commit suicide under either ruleset
+        for name in ["nz", "ing"] {
+            let rules = GameRules::from_name(name).unwrap();
+            let mut game = Game::with_rules(9, rules);
+            game.board.set_stone(2, 1, Stone::White);
+            game.board.set_stone(1, 2, Stone::White);
+            assert!(game.board.is_legal(1, 1, Stone::Black, &rules).is_err(), "{}", name);
+            assert!(game.make_move(1, 1).is_err(), "{}", name);
+            assert_eq!(game.board.get_stone(1, 1), Stone::Empty);
+            let mut board = game.board.clone();
+            assert!(board.place_stone_allowing_suicide(1, 1, Stone::Black).is_err());
+        }
+    }
+
+    #[test]
+    fn test_area_scoring() {
+        use gnugo_rs::gtp::GTPHandler;
+
+        // Black walls off three columns, White two, on a 7x7 board
+        let mut game = Game::new(7);
+        game.board = Board::from_ascii(&"...XO..\n".repeat(7)).unwrap();
+        let score = game.score();
+        assert_eq!((score.black, score.white), (28, 21));
+        assert_eq!(score.white_total(), 27.5);
+        assert_eq!(score.margin(), 0.5);
+        assert_eq!(score.result(), "B+0.5");
+        game.pass().unwrap();
+        game.pass().unwrap();
+        assert_eq!(game.winner(), Some(Stone::Black));
+
+        game.komi = 7.0;
+        assert_eq!(game.score().winner(), None);
+
+        let mut handler = GTPHandler::new(7);
+        let mut commands = String::from("komi 6.5\n");
+        for row in 1..=7 {
+            commands.push_str(&format!("play black D{}\nplay white E{}\n", row, row));
+        }
+        commands.push_str("play black pass\nplay white pass\nfinal_score\n");
+        let mut output = Vec::new();
+        handler.run_with(commands.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.trim_end().ends_with("= B+0.5"), "{}", output);
+    }
+
+    #[test]
+    fn test_territory_scoring_with_seki() {
+        use gnugo_rs::engine::rules::{GameRules, ScoringRule};
+        use gnugo_rs::engine::scoring::{dead_stones, seki_stones};
+
+        // The black and white groups on the top edge share C7 and each
+        // have one eye, A7 and E7
+        let mut game = Game::with_rules(7, GameRules::from_name("japanese").unwrap());
+        game.board = Board::from_ascii(
+            "
+            .X.O.OX
+            XXOOOOX
+            OOXXXXX
+            .OX.X.X
+            OOXXXXX
+            .OX.X.X
+            OOXXXXX
+            ",
+        )
+        .unwrap();
+        let seki = seki_stones(&game.board);
+        assert_eq!(seki.len(), 9);
+        assert!(seki.contains(&(2, 1)) && seki.contains(&(4, 1)) && !seki.contains(&(7, 1)));
+        assert!(dead_stones(&game.board).is_empty());
+
+        // Only the outer groups' eyes count; the seki eyes and C7 are neutral
+        game.komi = 0.5;
+        let score = game.score();
+        assert_eq!((score.black, score.white), (4, 2));
+        assert_eq!(score.result(), "B+1.5");
+        game.rules.scoring = ScoringRule::Area;
+        assert_eq!((game.score().black, game.score().white), (31, 17));
+
+        // B7 and D7 share C7 with two liberties each, but Black can still
+        // take E7 from D7: a capturing race, not seki
+        let semeai = Board::from_ascii(
+            "
+            .X.O...
+            .O.X...
+            .......
+            .......
+            .......
+            .......
+            .......
+            ",
+        )
+        .unwrap();
+        assert!(seki_stones(&semeai).is_empty());
+    }
+
+    #[test]
+    fn test_aga_rules() {
+        use gnugo_rs::config::Config;
+        use gnugo_rs::engine::rules::{GameRules, ScoringRule};
+
+        let aga = GameRules::from_name("AGA").unwrap();
+        assert!(aga.pass_stones && aga.scoring == ScoringRule::Area);
+        let game = Game::with_rules(9, aga);
+        assert_eq!(game.komi, 7.5);
+
+        // A game cannot end on Black's pass
+        let mut game = Game::with_rules(9, aga);
+        game.make_move(5, 5).unwrap();
+        game.pass().unwrap();
+        game.pass().unwrap();
+        assert!(!game.is_game_over());
+        game.pass().unwrap();
+        assert!(game.is_game_over());
+        assert_eq!((game.pass_stones(Stone::Black), game.pass_stones(Stone::White)), (1, 2));
+
+        // Pass stones make territory counting agree with area counting
+        let mut game = Game::with_rules(7, aga);
+        game.board = Board::from_ascii(&"...XO..\n".repeat(7)).unwrap();
+        game.pass().unwrap();
+        game.pass().unwrap();
+        let area = game.score();
+        game.rules.scoring = ScoringRule::Territory;
+        assert_eq!(game.score().margin(), area.margin());
+        assert_eq!(area.result(), "W+0.5");
+
+        // The ruleset's komi applies unless komi is given
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let mut config = Config::default();
+        config.apply_args(&args(&["--ruleset", "aga"])).unwrap();
+        assert_eq!(config.new_game().komi, 7.5);
+        let mut config = Config::default();
+        config.apply_args(&args(&["--ruleset", "aga", "--komi", "0.5"])).unwrap();
+        assert_eq!(config.new_game().komi, 0.5);
+    }
+
+    #[test]
+    fn test_komi_decides_even_positions() {
+        // An empty board after two passes is decided by komi alone
+        for (komi, winner) in [(6.5, Some(Stone::White)), (0.0, None), (-0.5, Some(Stone::Black))] {
+            let mut game = Game::new(9);
+            game.komi = komi;
+            game.pass().unwrap();
+            game.pass().unwrap();
+            assert_eq!(game.winner(), winner, "komi {}", komi);
+        }
+
+        // A half point breaks what would otherwise be a tie
+        let mut game = Game::new(7);
+        game.board = Board::from_ascii(&"..X.O..\n".repeat(7)).unwrap();
+        assert_eq!(game.score().black, game.score().white);
+        game.komi = 0.5;
+        assert_eq!(game.score().result(), "W+0.5");
+    }
+
+    #[test]
+    fn test_game_result_strings() {
+        use gnugo_rs::engine::rules::Variant;
+        use gnugo_rs::gtp::GTPHandler;
+
+        let mut game = Game::new(9);
+        assert_eq!(game.result(), None);
+        game.resign().unwrap();
+        assert_eq!(game.result().as_deref(), Some("W+Resign"));
+
+        let mut game = Game::new(9);
+        game.make_move(5, 5).unwrap();
+        game.resign().unwrap();
+        assert_eq!(game.result().as_deref(), Some("B+Resign"));
+
+        let mut game = Game::new(9);
+        game.komi = 0.0;
+        game.pass().unwrap();
+        game.pass().unwrap();
+        assert_eq!(game.result().as_deref(), Some("0"));
+
+        let mut game = Game::new(9);
+        game.variant = Variant::Capture { target: 1 };
+        game.board.set_stone(1, 2, Stone::White);
+        game.make_move(1, 1).unwrap();
+        game.make_move(9, 9).unwrap();
+        game.make_move(2, 2).unwrap();
+        game.make_move(9, 8).unwrap();
+        game.make_move(1, 3).unwrap();
+        assert_eq!(game.result().as_deref(), Some("B+"));
+
+        let mut handler = GTPHandler::new(9);
+        let mut output = Vec::new();
+        handler.run_with("final_score\nkomi 0.5\nplay black pass\nplay white pass\nfinal_score\n".as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let replies: Vec<&str> = output.split("\n\n").collect();
+        assert!(replies[0].starts_with('?'));
+        assert_eq!(replies[4], "= W+0.5");
+    }
+
+    #[test]
+    fn test_undo_passes_and_resignations() {
+        use gnugo_rs::engine::game::Action;
+
+        let mut game = Game::new(9);
+        game.make_move(3, 3).unwrap();
+        game.pass().unwrap();
+        assert_eq!((game.current_player(), game.pass_count()), (Stone::Black, 1));
+        game.undo_move().unwrap();
+        assert_eq!((game.current_player(), game.pass_count()), (Stone::White, 0));
+
+        // Undoing the second pass reopens the game
+        game.pass().unwrap();
+        game.pass().unwrap();
+        assert!(game.is_game_over() && game.winner().is_some());
+        game.undo_move().unwrap();
+        assert!(!game.is_game_over() && game.winner().is_none());
+        assert_eq!((game.current_player(), game.pass_count()), (Stone::Black, 1));
+
+        game.resign().unwrap();
+        assert_eq!(game.winner(), Some(Stone::White));
+        game.undo_move().unwrap();
+        assert!(!game.is_game_over());
+        game.make_move(7, 7).unwrap();
+
+        assert_eq!(game.moves(), vec![(Stone::Black, Some((3, 3))), (Stone::White, None), (Stone::Black, Some((7, 7)))]);
+        assert_eq!(game.history.last().map(|state| state.action), Some(Action::Play(7, 7)));
+        let stats = game.statistics();
+        assert_eq!((stats.moves, stats.passes), (2, 1));
+        game.undo_move().unwrap();
+        game.undo_move().unwrap();
+        assert_eq!(game.moves(), vec![(Stone::Black, Some((3, 3)))]);
+    }
+
+    #[test]
+    fn test_game_move_list() {
+        use gnugo_rs::engine::game::GameMove;
+        use gnugo_rs::gtp::GTPHandler;
+
+        let mut game = Game::new(9);
+        assert!(game.last_move().is_none());
+        game.board.set_stone(1, 2, Stone::White);
+        game.make_move(1, 1).unwrap();
+        game.pass().unwrap();
+        game.make_move(1, 3).unwrap();
+        assert_eq!(
+            game.move_list(),
+            &[
+                GameMove { number: 1, color: Stone::Black, point: Some((1, 1)), captures: 0 },
+                GameMove { number: 2, color: Stone::White, point: None, captures: 0 },
+                GameMove { number: 3, color: Stone::Black, point: Some((1, 3)), captures: 0 },
+            ]
+        );
+        game.make_move(5, 5).unwrap();
+        game.make_move(2, 2).unwrap();
+        assert_eq!(game.last_move().map(|m| (m.number, m.captures)), Some((5, 1)));
+        game.undo_move().unwrap();
+        assert_eq!(game.last_move().map(|m| m.point), Some(Some((5, 5))));
+
+        let mut handler = GTPHandler::new(9);
+        let mut output = Vec::new();
+        handler.run_with("last_move\nplay black D4\nlast_move\nplay white pass\nlast_move\n".as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let replies: Vec<&str> = output.split("\n\n").collect();
+        assert!(replies[0].starts_with('?'));
+        assert_eq!((replies[2], replies[4]), ("= black D4", "= white pass"));
+    }
+
+    #[test]
+    fn test_non_alternating_play_and_setup() {
+        use gnugo_rs::gtp::GTPHandler;
+        use gnugo_rs::sgf::SGFHandler;
+
+        let mut game = Game::new(9);
+        game.play_move(Stone::Black, Some((3, 3))).unwrap();
+        game.play_move(Stone::Black, Some((4, 4))).unwrap();
+        assert_eq!(game.current_player(), Stone::White);
+        assert!(game.play_move(Stone::White, Some((3, 3))).is_err());
+        assert_eq!(game.current_player(), Stone::White);
+        game.play_move(Stone::White, None).unwrap();
+        assert!(game.play_move(Stone::Empty, Some((5, 5))).is_err());
+        assert_eq!(game.moves().len(), 3);
+
+        // Setup stones use no turn and are not moves
+        game.setup_stone(7, 7, Stone::White).unwrap();
+        game.setup_stone(3, 3, Stone::Empty).unwrap();
+        assert!(game.setup_stone(10, 1, Stone::Black).is_err());
+        assert_eq!((game.board.get_stone(7, 7), game.board.get_stone(3, 3)), (Stone::White, Stone::Empty));
+        assert_eq!((game.current_player(), game.moves().len()), (Stone::Black, 3));
+
+        let mut handler = GTPHandler::new(9);
+        let mut output = Vec::new();
+        handler.run_with("play black D4\nplay black E5\nplay w C3\nlast_move\n".as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(!output.contains('?'), "{}", output);
+        assert!(output.contains("= white C3"));
+
+        let sgf = "(;SZ[9]AB[aa][bb]AW[cc]PL[W];W[dd];W[ee];B[ff])";
+        let sgf_handler = SGFHandler::new();
+        let mut game = Game::new(19);
+        sgf_handler.apply_to_game(&sgf_handler.parse(sgf).unwrap(), &mut game).unwrap();
+        assert_eq!(game.board.get_stone(2, 2), Stone::Black);
+        assert_eq!(game.board.get_stone(5, 5), Stone::White);
+        assert_eq!(game.moves(), vec![(Stone::White, Some((4, 4))), (Stone::White, Some((5, 5))), (Stone::Black, Some((6, 6)))]);
+    }
+
+    #[test]
+    fn test_game_clock() {
+        use gnugo_rs::engine::clock::{Clock, TimeControl};
+        use gnugo_rs::gtp::GTPHandler;
+        use std::time::Duration;
+
+        // 10s main time, then 3 periods of 5s
+        let mut clock = Clock::new(TimeControl::japanese(10, 5, 3));
+        assert!(clock.charge(Stone::Black, Duration::from_secs(12)));
+        assert_eq!(clock.time_left(Stone::Black), Some((Duration::from_secs(5), 3)));
+        assert!(clock.charge(Stone::Black, Duration::from_secs(11)));
+        assert_eq!(clock.time_left(Stone::Black), Some((Duration::from_secs(5), 1)));
+        assert_eq!(clock.time_left(Stone::White), Some((Duration::from_secs(10), 0)));
+        assert!(!clock.charge(Stone::Black, Duration::from_secs(6)));
+        assert_eq!(Clock::default().move_budget(Stone::Black), None);
+
+        // Running out of absolute time loses the game
+        let mut game = Game::new(9);
+        game.clock = Clock::new(TimeControl::canadian(3, 0, 0));
+        game.make_move(3, 3).unwrap();
+        game.record_move_time(Duration::from_secs(2));
+        game.make_move(4, 4).unwrap();
+        game.record_move_time(Duration::from_secs(4));
+        assert_eq!(game.winner(), Some(Stone::Black));
+        assert_eq!(game.result().as_deref(), Some("B+Time"));
+
+        // Undo gives the time back along with the move
+        game.undo_move().unwrap();
+        assert_eq!(game.winner(), None);
+        assert_eq!(game.clock.time_left(Stone::White), Some((Duration::from_secs(3), 0)));
+        assert_eq!(game.statistics().average_move_time, Some(Duration::from_secs(2)));
+        game.undo_move().unwrap();
+        assert_eq!(game.clock.time_left(Stone::Black), Some((Duration::from_secs(3), 0)));
+        assert_eq!(game.statistics().average_move_time, None);
+
+        let mut handler = GTPHandler::new(9);
+        let mut output = Vec::new();
+        let commands = "time_settings 60 30 5\ntime_left white 20 3\ntime_left black 1e30 0\nkgs-time_settings byoyomi 0 30 x\ntime_settings 60 x 30 5\n";
+        handler.run_with(commands.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.matches("= ").count(), 0, "{}", output);
+        assert!(output.ends_with("syntax error\n\n") && output.matches("? syntax error").count() == 3);
+    }
+
+    #[test]
+    fn test_repeated_position_ends_without_result() {
+        use gnugo_rs::engine::rules::{GameRules, KoRule};
+
+        let rules = GameRules { ko_rule: KoRule::None, ..GameRules::default() };
+        let mut game = Game::with_rules(5, rules);
+        game.board = Board::from_ascii(".XO..\nXO.O.\n.XO..\n.....\n.....").unwrap();
+        for (x, y) in [(3, 2), (2, 2), (3, 2)] {
+            game.make_move(x, y).unwrap();
+        }
+        assert_eq!(game.repetitions(), 2);
+        assert!(!game.is_game_over());
+        game.make_move(2, 2).unwrap();
+        assert_eq!(game.status(), "No result");
+        assert_eq!((game.winner(), game.result().as_deref()), (None, Some("Void")));
+        assert!(game.make_move(3, 2).is_err());
+
+        game.undo_move().unwrap();
+        assert!(!game.is_game_over());
+    }
+
+    #[test]
+    fn test_stone_age() {
+        let mut game = Game::new(9);
+        game.setup_stone(1, 1, Stone::Black).unwrap();
+        game.make_move(5, 5).unwrap();
+        game.pass().unwrap();
+        game.make_move(2, 1).unwrap();
+        game.make_move(1, 2).unwrap();
+        assert_eq!(game.board.move_number(), 4);
+        assert_eq!(game.board.stone_age(5, 5), Some(1));
+        assert_eq!(game.board.stone_age(1, 1), None);
+        assert_eq!(game.board.stone_age(9, 9), None);
+        assert_eq!(game.board.recent_stones(3), vec![(1, 2, 4), (2, 1, 3)]);
+
+        // Search moves number on and take their numbers back
+        let mut board = game.board.clone();
+        assert!(board.try_move(6, 6, Stone::Black));
+        assert_eq!(board.stone_age(6, 6), Some(5));
+        assert!(board.pop_move());
+        assert_eq!((board.move_number(), board.stone_age(6, 6)), (4, None));
+
+        game.undo_move().unwrap();
+        assert_eq!(game.board.stone_age(1, 2), None);
+        assert_eq!(game.board.stone_age(2, 1), Some(3));
+    }
+
+    #[test]
+    fn test_last_move_marked() {
+        use gnugo_rs::gtp::GTPHandler;
+        use gnugo_rs::ui::board_renderer::{BoardRenderer, RenderConfig};
+
+        let mut game = Game::new(5);
+        game.make_move(2, 3).unwrap();
+        let last = game.last_move().and_then(|m| m.point);
+        assert_eq!(last, Some((2, 3)));
+        let renderer = BoardRenderer::new(RenderConfig::from_theme("ascii").unwrap());
+        let rows: Vec<String> = renderer.render_with_last_move(&game.board, last).lines().map(String::from).collect();
+        assert_eq!(rows[3], " 3 . (X) .  .  . ");
+        game.pass().unwrap();
+        assert_eq!(game.last_move().and_then(|m| m.point), None);
+
+        let mut handler = GTPHandler::new(9);
+        let mut output = Vec::new();
+        handler.run_with("play black D4\nshowboard\n".as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("\n . . .(X). . . . . \n"), "{}", output);
+    }
+
+    #[test]
+    fn test_final_status() {
+        use gnugo_rs::engine::final_status::{FinalStatus, StringStatus};
+
+        let board = Board::from_ascii(
+            "..X.O..\n\
+             ..X.O..\n\
+             .OX.O..\n\
+             ..X.O..\n\
+             ..X.O..\n\
+             ..X.O..\n\
+             ..X.O..",
+        )
+        .unwrap();
+        let status = FinalStatus::analyze(&board);
+        assert_eq!(status.status(2, 3), Some(StringStatus::Dead));
+        assert_eq!(status.status(3, 1), Some(StringStatus::Alive));
+        assert_eq!(status.status(5, 7), Some(StringStatus::Alive));
+        assert_eq!(status.status(4, 4), None);
+        assert_eq!(status.dead(), vec![(2, 3)]);
+        assert_eq!(status.stones(StringStatus::Seki), vec![]);
+        assert_eq!(StringStatus::from_name("SEKI").map(|s| s.name()), Some("seki"));
+
+        let mut game = Game::new(7);
+        game.board = board;
+        assert_eq!((game.score().black, game.score().white), (21, 21));
+    }
+
+    #[test]
+    fn test_aftermath() {
+        use gnugo_rs::engine::final_status::FinalStatus;
+        use gnugo_rs::gtp::GTPHandler;
+
+        let mut game = Game::new(7);
+        game.board = Board::from_ascii(
+            "..X.O..\n\
+             ..X.O..\n\
+             .OX.O..\n\
+             ..X.O..\n\
+             ..X.O..\n\
+             ..X.O..\n\
+             ..X.O..",
+        )
+        .unwrap();
+        assert!(game.play_aftermath().is_err());
+        game.pass().unwrap();
+        game.pass().unwrap();
+        let played = game.play_aftermath().unwrap();
+        assert!(played >= 8, "{}", played);
+        assert!(game.is_game_over());
+        assert_eq!(game.board.get_stone(2, 3), Stone::Empty);
+        assert!((1..=7).all(|y| game.board.get_stone(4, y) != Stone::Empty));
+        assert!(FinalStatus::analyze(&game.board).dead().is_empty());
+
+        let mut handler = GTPHandler::new(7);
+        let mut output = Vec::new();
+        let commands = "play w B5\nplay b C1\nplay b C2\nplay b C3\nplay b C4\nplay b C5\nplay b C6\nplay b C7\n\
+                        play w pass\nplay b pass\nkgs-genmove_cleanup b\n";
+        handler.run_with(commands.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.ends_with("= A5\n\n"), "{}", output);
+    }
+
+    #[test]
+    fn test_net_attack() {
+        use gnugo_rs::engine::eye::EyeAnalyzer;
+        use gnugo_rs::gtp::GTPHandler;
+
+        // The ladder runs into the stone at G2, but the diagonal net holds
+        let board = Board::from_ascii(
+            "........X\n\
+             .........\n\
+             .........\n\
+             ...OO....\n\
+             ..OX.....\n\
+             ..O......\n\
+             .........\n\
+             ......X..\n\
+             X.......X",
+        )
+        .unwrap();
+        let analyzer = EyeAnalyzer::new();
+        assert_eq!(analyzer.find_ladder_attack_point(&board, 4, 5, 60), None);
+        assert_eq!(analyzer.find_net_attack_point(&board, 4, 5, 2), Some((5, 6)));
+        assert!(!analyzer.is_net_attack(&board, 4, 5, 0));
+
+        let mut handler = GTPHandler::new(9);
+        let mut output = Vec::new();
+        let commands = "play w D6\nplay w E6\nplay w C5\nplay w C4\nplay b D5\nplay b G2\nplay b A1\nplay b J1\nplay b J9\nnet_attack D5\nnet_attack C4\n";
+        handler.run_with(commands.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("= 1 E4\n"), "{}", output);
+        assert!(output.contains("string must have 2 or 3 liberties"), "{}", output);
+    }
+
+    #[test]
+    fn test_owl_attack_and_defend() {
+        use gnugo_rs::engine::owl::{self, OwlStatus};
+        use gnugo_rs::gtp::GTPHandler;
+
+        // A straight three in the corner lives or dies on its middle point
+        let board = Board::from_ascii(
+            ".........\n\
+             .........\n\
+             .........\n\
+             .........\n\
+             .........\n\
+             .........\n\
+             XXXXX....\n\
+             OOOOX....\n\
+             ...OX....",
+        )
+        .unwrap();
+        let result = owl::analyze(&board, (1, 8), 10_000);
+        assert_eq!(result.status, OwlStatus::Critical);
+        assert_eq!(result.attack_point, Some((2, 9)));
+        assert_eq!(result.defense_point, Some((2, 9)));
+
+        let mut alive = board.clone();
+        alive.place_stone(2, 9, Stone::White).unwrap();
+        assert_eq!(owl::analyze(&alive, (1, 8), 10_000).status, OwlStatus::Alive);
+
+        let mut handler = GTPHandler::new(9);
+        let mut output = Vec::new();
+        let commands = "play b A3\nplay b B3\nplay b C3\nplay b D3\nplay b E3\nplay b E2\nplay b E1\n\
+                        play w A2\nplay w B2\nplay w C2\nplay w D2\nplay w D1\nowl_attack A2\nowl_defend D1\n\
+                        play b B1\nowl_defend A2\n";
+        handler.run_with(commands.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.ends_with("= 1 B1\n\n= 1 B1\n\n=\n\n= 0\n\n"), "{}", output);
+    }
+
+    #[test]
+    fn test_ko_threats() {
+        use gnugo_rs::engine::ai::{AIDifficulty, AI};
+        use gnugo_rs::engine::ko::{self, KoChoice, KoThreat};
+
+        let mut board = Board::from_ascii(
+            ".XO......\n\
+             XO.O.....\n\
+             .XO......\n\
+             .........\n\
+             .........\n\
+             ...OXO...\n\
+             ...OXO...\n\
+             .........\n\
+             .........",
+        )
+        .unwrap();
+        // Black takes the ko, so White needs a threat before retaking
+        board.place_stone(3, 2, Stone::Black).unwrap();
+        assert_eq!(
+            ko::ko_move(&board, Stone::White),
+            Some(KoChoice::Threat(KoThreat { point: (5, 5), size: 2 }))
+        );
+
+        // Two stones are worth no more than the ko: Black connects
+        let mut small = board.clone();
+        small.place_stone(5, 8, Stone::White).unwrap();
+        assert_eq!(ko::open_kos(&small, Stone::Black), vec![((2, 2), 2)]);
+        assert_eq!(ko::ko_move(&small, Stone::Black), Some(KoChoice::Resolve((2, 2))));
+        assert_eq!(AI::new(AIDifficulty::Intermediate).get_best_move(&small, Stone::Black), Some((2, 2)));
+
+        // Three stones are: Black saves them and leaves the ko open
+        let mut big = board.clone();
+        big.set_stone(5, 5, Stone::Black);
+        big.set_stone(4, 5, Stone::White);
+        big.set_stone(6, 5, Stone::White);
+        big.place_stone(5, 8, Stone::White).unwrap();
+        assert_eq!(ko::ko_move(&big, Stone::Black), Some(KoChoice::Answer((5, 4))));
+    }
+
+    #[test]
+    fn test_break_in() {
+        use gnugo_rs::engine::analysis::{ownership, OwnershipSettings};
+        use gnugo_rs::engine::breakin::{break_in, BreakIn, BreakInKind};
+
+        // The stone at C1 is short of liberties: capturing it opens the corner
+        let weak = Board::from_ascii(
+            ".........\n\
+             .......O.\n\
+             .........\n\
+             .........\n\
+             .........\n\
+             .........\n\
+             .........\n\
+             XXO......\n\
+             ..XO.....",
+        )
+        .unwrap();
+        let corner = [(1, 9), (2, 9)];
+        assert_eq!(break_in(&weak, &corner, Stone::Black), Some(BreakIn { point: (2, 9), kind: BreakInKind::Capture }));
+
+        let solid = Board::from_ascii(
+            ".........\n\
+             .......O.\n\
+             .........\n\
+             .........\n\
+             .........\n\
+             XXXX.....\n\
+             ...X.....\n\
+             ...X.....\n\
+             ...X.....",
+        )
+        .unwrap();
+        // Too well walled to capture, and too small to live in
+        let corner: Vec<(usize, usize)> = (7..=9).flat_map(|y| (1..=3).map(move |x| (x, y))).collect();
+        assert_eq!(break_in(&solid, &corner, Stone::Black), None);
+
+        // Only the corner that holds is counted as solid territory
+        let settings = OwnershipSettings::default();
+        assert_eq!(ownership(&solid, &settings)[8 * 9], 1.0);
+        assert!(ownership(&weak, &settings)[8 * 9] < 1.0);
+    }
+
+    #[test]
+    fn test_worm_data() {
+        use gnugo_rs::engine::worm::{Code, Worms};
+        use gnugo_rs::gtp::GTPHandler;
+
+        let mut game = Game::new(9);
+        for (color, x, y) in [(Stone::Black, 5, 5), (Stone::White, 4, 5), (Stone::White, 6, 5), (Stone::White, 5, 4), (Stone::Black, 2, 8), (Stone::Black, 2, 6)] {
+            game.play_move(color, Some((x, y))).unwrap();
+        }
+        let worms = game.worms();
+        let cut = worms.get(5, 5).unwrap();
+        assert_eq!((cut.size(), cut.liberties.len()), (1, 1));
+        assert!(cut.cutstone);
+        assert_eq!((cut.attack_code, cut.attack_point), (Code::Win, Some((5, 6))));
+        assert_eq!((cut.defense_code, cut.defense_point), (Code::Win, Some((5, 6))));
+        assert_eq!(worms.get(2, 8).unwrap().cut_points, vec![(2, 7)]);
+        assert_eq!(worms.get(2, 7), None);
+        assert_eq!(worms.iter().count(), 6);
+        assert_eq!(Worms::compute(&game.board), worms);
+
+        let mut handler = GTPHandler::new(9);
+        let mut output = Vec::new();
+        let commands = "play b E5\nplay w D5\nplay w F5\nplay w E6\nplay b B2\nplay b B4\nworm_data E5\nworm_data B3\n";
+        handler.run_with(commands.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("= color black\norigin E5\nsize 1\neffective_size 3.50\nliberties 1\ncutstone 1\ncut_points\n\
+                                 attack_code 5\nattack_point E4\ndefense_code 5\ndefense_point E4\n"), "{}", output);
+        assert!(output.ends_with("? vertex must not be empty\n\n"), "{}", output);
+    }
+
+    #[test]
+    fn test_dragons() {
+        use gnugo_rs::engine::ai::{AIDifficulty, AI};
+        use gnugo_rs::engine::dragon::Dragons;
+        use gnugo_rs::engine::final_status::{FinalStatus, StringStatus};
+        use gnugo_rs::engine::owl::OwlStatus;
+
+        let board = Board::from_ascii(
+            ".........\n\
+             .......X.\n\
+             ........X\n\
+             .........\n\
+             .........\n\
+             .........\n\
+             XXXXX....\n\
+             OOOOX....\n\
+             ...OX....",
+        )
+        .unwrap();
+        let dragons = Dragons::compute(&board);
+        // The diagonal pair cannot be cut apart
+        let pair = dragons.get(9, 3).unwrap();
+        assert_eq!(pair.worms, vec![(8, 2), (9, 3)]);
+        assert_eq!(pair.status, OwlStatus::Alive);
+
+        let corner = dragons.get(4, 9).unwrap();
+        assert_eq!((corner.stones.len(), corner.eyes, corner.escape), (5, 1, 0));
+        assert_eq!(corner.status, OwlStatus::Critical);
+        assert_eq!((corner.attack_point, corner.defense_point), (Some((2, 9)), Some((2, 9))));
+        assert_eq!(AI::new(AIDifficulty::Intermediate).get_best_move(&board, Stone::White), Some((2, 9)));
+
+        let mut dead = board.clone();
+        dead.place_stone(2, 9, Stone::Black).unwrap();
+        assert_eq!(Dragons::compute(&dead).get(1, 8).unwrap().status, OwlStatus::Dead);
+        assert_eq!(FinalStatus::analyze(&dead).status(1, 8), Some(StringStatus::Dead));
+    }
+
+    #[test]
+    fn test_influence_map() {
+        use gnugo_rs::engine::influence::{InfluenceMap, STONE_STRENGTH};
+        use gnugo_rs::gtp::GTPHandler;
+
+        let board = Board::from_ascii(
+            ".....\n\
+             .X...\n\
+             .XO..\n\
+             .....\n\
+             .....",
+        )
+        .unwrap();
+        let map = InfluenceMap::compute(&board);
+        assert_eq!(map.influence(3, 3, Stone::White), STONE_STRENGTH);
+        // White's stone blocks Black's influence from spreading through it
+        assert_eq!(map.influence(3, 3, Stone::Black), 0.0);
+        assert!(map.influence(1, 1, Stone::Black) > map.influence(5, 5, Stone::Black));
+        assert_eq!(map.territory(&board, 1, 2), Stone::Black);
+        assert_eq!(map.territory(&board, 5, 3), Stone::White);
+        assert_eq!(map.territory(&board, 2, 2), Stone::Empty);
+        assert_eq!(map.balance().len(), 25);
+
+        let mut handler = GTPHandler::new(5);
+        let mut output = Vec::new();
+        handler.run_with("play b C3\ninfluence white\ninfluence\ninfluence red\n".as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("=    0   0   0   0   0\n   0   0   0   0   0\n"), "{}", output);
+        assert!(output.contains("\n   8  16  32  16   8\n   4   8  16   8   4\n\n"), "{}", output);
+        assert!(output.ends_with("? invalid color\n\n"), "{}", output);
+    }
+
+    #[test]
+    fn test_moyo_map() {
+        use gnugo_rs::engine::influence::{InfluenceMap, Region};
+        use gnugo_rs::gtp::GTPHandler;
+
+        let mut board = Board::new(9);
+        for (x, y, color) in [(3, 7, Stone::Black), (7, 3, Stone::White), (3, 3, Stone::Black)] {
+            board.place_stone(x, y, color).unwrap();
+        }
+        let map = InfluenceMap::compute(&board);
+        assert_eq!(map.classify(&board, 3, 5), Region::Territory(Stone::Black));
+        assert_eq!(map.classify(&board, 1, 1), Region::Moyo(Stone::Black));
+        assert_eq!(map.classify(&board, 5, 5), Region::Moyo(Stone::Black));
+        assert_eq!(map.classify(&board, 8, 6), Region::Moyo(Stone::White));
+        assert_eq!(map.classify(&board, 5, 1), Region::Neutral);
+        assert_eq!(map.classify(&board, 3, 3), Region::Neutral);
+        let (black, white) = map.territory_count(&board);
+        assert!(black > white && white > 0);
+        assert_eq!(map.regions(&board)[2][2].symbol(), '.');
+
+        let mut handler = GTPHandler::new(9);
+        let mut output = Vec::new();
+        handler.run_with("play b C3\nplay w G7\nplay b C7\nmoyo\n".as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("= b B B B . W W W w\nB B B B . W W W W\nB B X B . W O W W\n"), "{}", output);
+    }
+
+    #[test]
+    fn test_group_strength() {
+        use gnugo_rs::engine::ai::{AIDifficulty, AI};
+        use gnugo_rs::engine::dragon::{group_strength, Dragons};
+
+        let board = Board::from_ascii(
+            ".X.X.O...\n\
+             XXXXXO...\n\
+             OOOOOO...\n\
+             .........\n\
+             ...OXO...\n\
+             ....O....\n\
+             .........\n\
+             .........\n\
+             .........",
+        )
+        .unwrap();
+        let dragons = Dragons::compute(&board);
+        let settled = group_strength(dragons.get(1, 2).unwrap());
+        let in_atari = group_strength(dragons.get(5, 5).unwrap());
+        let walls = group_strength(dragons.get(1, 3).unwrap());
+        assert_eq!(settled, 1.0);
+        assert!(in_atari < walls && walls < settled, "{} {} {}", in_atari, walls, settled);
+        // Black runs out of atari instead of playing elsewhere
+        assert_eq!(AI::new(AIDifficulty::Intermediate).get_best_move(&board, Stone::Black), Some((5, 4)));
+    }
+
+    #[test]
+    fn test_escape_route() {
+        use gnugo_rs::engine::escape::{escape_route, EscapeGoal};
+        use gnugo_rs::engine::influence::InfluenceMap;
+
+        let board = Board::from_ascii(
+            "X.O......\n\
+             .O.......\n\
+             O........\n\
+             .........\n\
+             .........\n\
+             ...O.O.X.\n\
+             ..OX.XO..\n\
+             ..OXXXO.X\n\
+             ..OOOOO..",
+        )
+        .unwrap();
+        let influence = InfluenceMap::compute(&board);
+        let stones = [(4, 7), (4, 8), (5, 8), (6, 8), (6, 7)];
+        let route = escape_route(&board, &stones, &influence).unwrap();
+        assert_eq!(route.goal, EscapeGoal::Center);
+        assert_eq!(route.path, vec![(5, 7), (5, 6)]);
+        assert_eq!(route.first_step(), (5, 7));
+
+        // A lone stone next to a friend connects to it
+        let route = escape_route(&board, &[(9, 8)], &influence).unwrap();
+        assert!(matches!(route.goal, EscapeGoal::Friend(_)), "{:?}", route);
+
+        // The cornered stone has nowhere to go
+        assert_eq!(escape_route(&board, &[(1, 1)], &influence), None);
+    }
+
+    #[test]
+    fn test_eye_spaces() {
+        use gnugo_rs::engine::eye::EyeAnalyzer;
+        use gnugo_rs::gtp::GTPHandler;
+
+        let board = Board::from_ascii(
+            "...X.O...\n\
+             XXXXXO...\n\
+             OOOOOO...\n\
+             .........\n\
+             .........\n\
+             .........\n\
+             .........\n\
+             .........\n\
+             .........",
+        )
+        .unwrap();
+        let analyzer = EyeAnalyzer::new();
+        let eye = analyzer.eye_data(&board, Stone::Black, 2, 1).unwrap();
+        assert_eq!((eye.origin, eye.esize, eye.msize, eye.neighbors), ((1, 1), 3, 0, 2));
+        assert_eq!(eye.value.to_string(), "1.2");
+        // Next to a white stone, the other space is marginal and no eye
+        let false_eye = analyzer.eye_data(&board, Stone::Black, 5, 1).unwrap();
+        assert!(false_eye.marginal);
+        assert_eq!((false_eye.esize, false_eye.msize, false_eye.value.to_string()), (1, 1, "0.0".to_string()));
+        // The open board is no eye space
+        assert!(analyzer.eye_data(&board, Stone::Black, 5, 5).is_none());
+
+        let mut output = Vec::new();
+        GTPHandler::new(9).run_with("play b A8\nplay b B9\nplay b B8\neye_data black A9\n".as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.ends_with(
+            "= origin 1 1\ncolor black\nesize 1\nmsize 0\nvalue 1.1\nmarginal 0\nneighbors 0\nmarginal_neighbors 0\n\n"
+        ), "{}", output);
+    }
+
+    #[test]
+    fn test_half_eyes() {
+        use gnugo_rs::engine::eye::{EyeAnalyzer, HalfEyeType};
+
+        let half = Board::from_ascii(
+            ".X.X.....\n\
+             XXX......\n\
+             .........\n\
+             .........\n\
+             .........\n\
+             .........\n\
+             .........\n\
+             .........\n\
+             .........",
+        )
+        .unwrap();
+        let analyzer = EyeAnalyzer::new();
+
+        let corner = analyzer.half_eye(&half, Stone::Black, 1, 1).unwrap();
+        assert_eq!((corner.eye_type, corner.attack_point, corner.defense_point), (HalfEyeType::Normal, None, None));
+
+        let eye = analyzer.half_eye(&half, Stone::Black, 3, 1).unwrap();
+        assert_eq!((eye.value, eye.eye_type), (1.5, HalfEyeType::Half));
+        assert_eq!((eye.attack_point, eye.defense_point), (Some((4, 2)), Some((4, 2))));
+        assert_eq!(analyzer.eye_data(&half, Stone::Black, 3, 1).unwrap().value.to_string(), "0.1");
+
+        // Once White takes the diagonal the eye is false
+        let mut spoiled = half.clone();
+        spoiled.place_stone(4, 2, Stone::White).unwrap();
+        let eye = analyzer.half_eye(&spoiled, Stone::Black, 3, 1).unwrap();
+        assert_eq!((eye.eye_type, eye.defense_point), (HalfEyeType::False, None));
+        assert_eq!(analyzer.eye_data(&spoiled, Stone::Black, 3, 1).unwrap().value.to_string(), "0.0");
+    }
+
+    #[test]
+    fn test_eye_shapes() {
+        use gnugo_rs::engine::eye::EyeAnalyzer;
+        use gnugo_rs::patterns::eyes::eye_shape;
+
+        let (square, vital) = eye_shape(&[(5, 5), (6, 5), (5, 6), (6, 6)]).unwrap();
+        assert_eq!((square.name, square.min_eyes, square.max_eyes, vital), ("square four", 1, 1, None));
+        // Bent three in any orientation is settled at its corner
+        let (bent, vital) = eye_shape(&[(2, 2), (2, 3), (3, 3)]).unwrap();
+        assert_eq!((bent.name, vital), ("bent three", Some((2, 3))));
+        assert!(eye_shape(&[(1, 1), (3, 3)]).is_none());
+
+        let board = Board::from_ascii(
+            "...X.....\n\
+             ..XX.....\n\
+             XXX......\n\
+             .........\n\
+             .........\n\
+             .........\n\
+             .........\n\
+             .........\n\
+             .........",
+        )
+        .unwrap();
+        let eye = EyeAnalyzer::new().eye_data(&board, Stone::Black, 1, 2).unwrap();
+        assert_eq!((eye.esize, eye.value.to_string(), eye.vital_point), (5, "1.2".to_string(), Some((2, 1))));
+    }
+
+    #[test]
+    fn test_vital_points() {
+        use gnugo_rs::engine::eye::EyeAnalyzer;
+        use gnugo_rs::engine::owl::{self, OwlStatus};
+
+        let board = Board::from_ascii(
+            "...XO....\n\
+             XXXXO....\n\
+             OOOOO....\n\
+             .........\n\
+             .........\n\
+             .........\n\
+             .........\n\
+             .........\n\
+             .........",
+        )
+        .unwrap();
+        let analyzer = EyeAnalyzer::new();
+        assert_eq!(analyzer.vital_points(&board, &[(1, 1), (2, 1), (3, 1)]), vec![(2, 1)]);
+        // Square four is one eye whoever plays first
+        assert!(analyzer.vital_points(&board, &[(6, 6), (7, 6), (6, 7), (7, 7)]).is_empty());
+        // An irregular space has its most connected point
+        let space = [(5, 5), (6, 5), (7, 5), (6, 6), (6, 7), (7, 7), (8, 7)];
+        assert_eq!(analyzer.vital_points(&board, &space), vec![(6, 5)]);
+
+        // The straight three lives or dies at its middle
+        let result = owl::analyze(&board, (1, 2), 2_000);
+        assert_eq!(result.status, OwlStatus::Critical);
+        assert_eq!((result.attack_point, result.defense_point), (Some((2, 1)), Some((2, 1))));
+    }
+
+    #[test]
+    fn test_topological_eye() {
+        use gnugo_rs::engine::eye::{EyeAnalyzer, HalfEyeType};
+
+        let mut board = Board::from_ascii(
+            ".X.X.....\n\
+             XXXO.....\n\
+             ....X....\n\
+             .........\n\
+             .........\n\
+             .........\n\
+             .........\n\
+             .........\n\
+             .........",
+        )
+        .unwrap();
+        let analyzer = EyeAnalyzer::new();
+        assert_eq!(analyzer.topological_eye(&board, Stone::Black, 1, 1), Some(1.0));
+        // The white diagonal can be caught in a ladder, so the eye is only half lost
+        assert_eq!(analyzer.topological_eye(&board, Stone::Black, 3, 1), Some(1.5));
+        let half = analyzer.half_eye(&board, Stone::Black, 3, 1).unwrap();
+        assert_eq!(half.eye_type, HalfEyeType::Half);
+        assert!(half.defense_point.is_some());
+
+        // Without the black stone at (5, 3) the ladder fails and the eye is false
+        board.set_stone(5, 3, Stone::Empty);
+        assert_eq!(analyzer.half_eye(&board, Stone::Black, 3, 1).unwrap().eye_type, HalfEyeType::False);
+        assert_eq!(analyzer.topological_eye(&board, Stone::White, 3, 1), None);
+    }
+
+    #[test]
+    fn test_estimate_score_regions() {
+        use gnugo_rs::engine::evaluation::Evaluator;
+
+        let rows = ["...X.O..."; 9].join("\n");
+        let board = Board::from_ascii(&rows).unwrap();
+        let estimate = Evaluator::estimate_score(&board);
+        // Stones count once; the open column between the walls is dame
+        assert_eq!((estimate.black, estimate.white), (36, 36));
+        assert_eq!(estimate.ownership[0][0], Stone::Black);
+        assert_eq!(estimate.ownership[4][4], Stone::Empty);
+        assert_eq!(estimate.ownership[8][8], Stone::White);
+
+        let estimate = Evaluator::estimate_score(&Board::new(9));
+        assert_eq!((estimate.black, estimate.white), (0, 0));
+    }
+
+    #[test]
+    fn test_evaluation_cache() {
+        use gnugo_rs::engine::evaluation::Evaluator;
+
+        Evaluator::clear_cache();
+        let mut board = Board::new(9);
+        board.place_stone(3, 3, Stone::Black).unwrap();
+        let score = Evaluator::evaluate_position(&board);
+        assert_eq!(Evaluator::evaluate_position(&board), score);
+        board.place_stone(7, 7, Stone::White).unwrap();
+        Evaluator::evaluate_position(&board);
+
+        let stats = Evaluator::cache_stats();
+        assert_eq!((stats.hits, stats.misses), (1, 2));
+        assert!((stats.hit_rate() - 1.0 / 3.0).abs() < 1e-9);
+        Evaluator::clear_cache();
+        assert_eq!(Evaluator::cache_stats().hit_rate(), 0.0);
+    }
+
+    #[test]
+    fn test_move_value() {
+        use gnugo_rs::engine::evaluation::Evaluator;
+
+        let rows = ["..X...O.."; 9].join("\n");
+        let board = Board::from_ascii(&rows).unwrap();
+        let center = Evaluator::move_value(&board, (5, 5), Stone::Black).unwrap();
+        let inside = Evaluator::move_value(&board, (1, 5), Stone::Black).unwrap();
+        // Taking the open middle swings points; filling one's own area does not
+        assert!(center > 2.0 && inside.abs() < 1.0, "{} {}", center, inside);
+        let white = Evaluator::move_value(&board, (5, 5), Stone::White).unwrap();
+        assert!((white - center).abs() < 1.0, "{} {}", white, center);
+        assert_eq!(Evaluator::move_value(&board, (3, 3), Stone::White), None);
+    }
+
+    #[test]
+    fn test_endgame_moves() {
+        use gnugo_rs::engine::ai::{AIDifficulty, AI};
+        use gnugo_rs::engine::endgame::{endgame_moves, is_endgame, EndgameKind};
+
+        let board = Board::from_ascii(
+            "...XO....\n\
+             ...XO....\n\
+             ...XO....\n\
+             ...XO....\n\
+             ...XO....\n\
+             ...XO....\n\
+             ...XO....\n\
+             ...X.O...\n\
+             .........",
+        )
+        .unwrap();
+        assert!(is_endgame(&board));
+        assert!(!is_endgame(&Board::new(9)));
+
+        let moves = endgame_moves(&board, Stone::Black, 2);
+        assert_eq!((moves[0].point, moves[0].kind, moves[0].sente), ((5, 8), EndgameKind::Push, true));
+        assert!(moves.iter().any(|m| m.point == (7, 9) && m.kind == EndgameKind::MonkeyJump));
+        // One ply is too shallow to see follow-ups
+        assert!(endgame_moves(&board, Stone::Black, 1).iter().all(|m| !m.sente));
+        assert_eq!(AI::new(AIDifficulty::Intermediate).get_best_move(&board, Stone::White), Some((5, 8)));
+    }
+
+    #[test]
+    fn test_opening_moves() {
+        use gnugo_rs::gtp::GTPHandler;
+        use gnugo_rs::patterns::fuseki::opening_move;
+
+        // Corners first, then the sides
+        let mut output = Vec::new();
+        let commands = "genmove black\ngenmove white\ngenmove black\ngenmove white\ngenmove black\ngenmove white\n";
+        GTPHandler::new(19).run_with(commands.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let replies: Vec<&str> = output.split("\n\n").filter(|r| !r.is_empty()).collect();
+        assert_eq!(replies, ["= Q16", "= D4", "= Q4", "= D16", "= K16", "= K4"]);
+
+        let mut output = Vec::new();
+        GTPHandler::new(9).run_with("genmove black\ngenmove white\n".as_bytes(), &mut output).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "= G7\n\n= C3\n\n");
+
+        // A corner with a stone in it is skipped, and the opening ends
+        // once the board fills up
+        let mut board = Board::new(9);
+        board.place_stone(6, 2, Stone::White).unwrap();
+        assert_eq!(opening_move(&board), Some((3, 7)));
+        board.place_stone(3, 7, Stone::Black).unwrap();
+        board.place_stone(8, 8, Stone::White).unwrap();
+        assert_eq!(opening_move(&board), None);
+        assert_eq!(opening_move(&Board::new(5)), None);
+    }
+
+    #[test]
+    fn test_genmove_resigns() {
+        use gnugo_rs::engine::ai::{AIDifficulty, AI};
+        use gnugo_rs::gtp::GTPHandler;
+
+        // White walls off all but two columns
+        let mut commands = String::new();
+        for row in 1..=9 {
+            commands += &format!("play black B{row}\nplay white C{row}\nplay white G{row}\n");
+        }
+        let mut board = Board::new(9);
+        for y in 1..=9 {
+            board.set_stone(2, y, Stone::Black);
+            board.set_stone(3, y, Stone::White);
+            board.set_stone(7, y, Stone::White);
+        }
+        let ai = AI::new(AIDifficulty::Intermediate);
+        assert!(ai.should_resign(&board, Stone::Black, 6.5));
+        assert!(!ai.should_resign(&board, Stone::White, 6.5));
+        assert!(!AI::new(AIDifficulty::Beginner).should_resign(&board, Stone::Black, 6.5));
+        assert!(!ai.should_resign(&Board::new(9), Stone::Black, 6.5));
+
+        let mut output = Vec::new();
+        commands += "genmove black\ngenmove white\n";
+        GTPHandler::new(9).run_with(commands.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let replies: Vec<&str> = output.split("\n\n").filter(|r| !r.is_empty()).collect();
+        assert_eq!(replies[27], "= resign");
+        assert!(replies[28].starts_with('?'));
+    }
+
+    #[test]
+    fn test_genmove_ko_and_pass() {
+        use gnugo_rs::config::Config;
+        use gnugo_rs::gtp::GTPHandler;
+
+        let run = |config: &Config, commands: &str| {
+            let mut output = Vec::new();
+            GTPHandler::with_config(config).run_with(commands.as_bytes(), &mut output).unwrap();
+            String::from_utf8(output).unwrap()
+        };
+
+        // Black takes the ko at C8 and both pass; under AGA rules the game
+        // goes on, and White's retake at B8 would repeat the position
+        // before Black's capture
+        let ko = "play b B9\nplay w C9\nplay b A8\nplay w D8\nplay b B7\nplay w C7\nplay b J1\nplay w B8\nplay b C8\n\
+                  play w pass\nplay b pass\n";
+        let aga = Config::from_toml("[game]\nruleset = \"aga\"\n").unwrap();
+        let output = run(&aga, &format!("{}is_legal white B8\ngenmove white\n", ko));
+        let replies: Vec<&str> = output.split("\n\n").filter(|r| !r.is_empty()).collect();
+        assert_eq!(replies[11], "= 0");
+        assert!(replies[12] != "= B8" && replies[12] != "= pass", "{}", output);
+        // Without superko only the ko ban counted, and the passes lifted it
+        let no_superko = Config::from_toml("[game]\nruleset = \"area\"\n").unwrap();
+        assert!(run(&no_superko, &format!("{}is_legal white B8\n", &ko[..ko.len() - "play b pass\n".len()])).ends_with("= 1\n\n"));
+
+        // Both sides alive with only their own eyes left: the engine passes
+        let mut finished = "boardsize 5\nclear_board\n".to_string();
+        for y in 1..=5 {
+            for x in 1..=5 {
+                if y % 2 == 0 || (x != 1 && x != 5) {
+                    let color = if x <= 3 { "b" } else { "w" };
+                    finished += &format!("play {} {}{}\n", color, (b'A' + x as u8 - 1) as char, y);
+                }
+            }
+        }
+        finished += "genmove black\ngenmove white\ngenmove black\n";
+        let output = run(&Config::default(), &finished);
+        assert!(output.ends_with("= pass\n\n= pass\n\n? game is over\n\n"), "{}", output);
+    }
+
+    #[test]
+    fn test_move_reasons() {
+        use gnugo_rs::engine::ai::{AIDifficulty, AI};
+        use gnugo_rs::engine::move_reasons::{MoveReasons, Reason};
+        use gnugo_rs::gtp::GTPHandler;
+
+        // White E5 in atari, free to run out at E4
+        let board = Board::from_ascii(
+            ".........\n\
+             .........\n\
+             .........\n\
+             ....X....\n\
+             ...XOX...\n\
+             .........\n\
+             .........\n\
+             .........\n\
+             .........",
+        )
+        .unwrap();
+        let collected = MoveReasons::collect(&board, Stone::Black);
+        assert!(collected.reasons(5, 6).iter().any(|r| r.reason == Reason::AttackWorm((5, 5))));
+        let defended = MoveReasons::collect(&board, Stone::White);
+        assert!(defended.reasons(5, 6).iter().any(|r| r.reason == Reason::DefendWorm((5, 5))));
+
+        // Genmove plays the vertex whose reasons add up to the most
+        let ai = AI::new(AIDifficulty::Intermediate);
+        let reasons = ai.move_reasons(&board, Stone::Black);
+        let (best, value) = reasons.ranked()[0];
+        assert_eq!(ai.get_best_move(&board, Stone::Black), Some(best));
+        assert_eq!(value, reasons.reasons(best.0, best.1).iter().map(|r| r.value).sum::<i32>());
+        assert!(reasons.reasons(best.0, best.1).iter().any(|r| r.reason == Reason::Territory));
+
+        let mut output = Vec::new();
+        let commands = "play black E6\nplay black D5\nplay black F5\nplay white E5\nmove_reasons\nmove_reasons E4\nmove_reasons Z9\n";
+        GTPHandler::new(9).run_with(commands.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let replies: Vec<&str> = output.split("\n\n").filter(|r| !r.is_empty()).collect();
+        assert_eq!(replies[4].lines().count(), 10);
+        assert!(replies[4].starts_with("= E4 "));
+        assert!(replies[5].lines().any(|line| line.ends_with("attack E5 +5")));
+        assert!(replies[6].starts_with('?'));
+    }
+
+    #[test]
+    fn test_alpha_beta_search() {
+        use gnugo_rs::engine::ai::{AIDifficulty, AI};
+        use gnugo_rs::engine::search::{alpha_beta, ordered_moves};
+
+        // White E5 in atari; Black's eye at A9 is never filled
+        let board = Board::from_ascii(
+            ".X.......\n\
+             X........\n\
+             .........\n\
+             ....X....\n\
+             ...XOX...\n\
+             .........\n\
+             .........\n\
+             .........\n\
+             .........",
+        )
+        .unwrap();
+        let ordered = ordered_moves(&board, Stone::Black, 8);
+        assert_eq!(ordered[0], (5, 6));
+        assert!(!ordered.contains(&(1, 1)));
+        assert_eq!(ordered_moves(&board, Stone::White, 8)[0], (5, 6));
+
+        let moves = [(1, 5), (5, 6), (9, 9)];
+        let result = alpha_beta(&board, Stone::Black, &moves, 3, 100_000);
+        assert_eq!(result.depth, 3);
+        assert_eq!(result.best().unwrap().0, (5, 6));
+        assert!(result.best().unwrap().1 > 0);
+        // A budget too small for the second iteration keeps the first
+        let shallow = alpha_beta(&board, Stone::Black, &moves, 3, 5);
+        assert_eq!(shallow.depth, 1);
+        assert!(shallow.nodes <= 5);
+
+        let ai = AI::new(AIDifficulty::Advanced);
+        assert_eq!(ai.get_best_move(&board, Stone::Black), Some((5, 6)));
+        assert_eq!(ai.get_best_move(&board, Stone::White), Some((5, 6)));
+    }
+
+    #[test]
+    fn test_mcts_rave() {
+        use gnugo_rs::engine::ai::{AIDifficulty, LevelProfile, AI, MAX_LEVEL};
+        use gnugo_rs::engine::mcts::{search, MctsSettings, NodeStats};
+        use gnugo_rs::engine::trace::SearchTrace;
+        use gnugo_rs::sgf::SGFHandler;
+
+        let fresh = NodeStats::default();
+        assert_eq!(fresh.beta(1000.0), 1.0);
+        assert_eq!(fresh.beta(0.0), 0.0);
+        let stats = NodeStats { visits: 1000, wins: 250.0, rave_visits: 4000, rave_wins: 3000.0 };
+        assert_eq!(stats.beta(1000.0), 0.5);
+        assert_eq!(stats.value(1000.0), 0.5);
+        assert_eq!(stats.value(0.0), 0.25);
+
+        // Twelve white stones in atari decide the game
+        let board = Board::from_ascii(
+            ".........\n\
+             .XXXXXX..\n\
+             XOOOOOOX.\n\
+             XOOOOOOX.\n\
+             .XXXXX.X.\n\
+             .........\n\
+             OOOOOOOOO\n\
+             .........\n\
+             .........",
+        )
+        .unwrap();
+        let settings = MctsSettings { playouts: 150, komi: 6.5, seed: Some(7), threads: 1, ..MctsSettings::default() };
+        let result = search(&board, Stone::Black, &settings);
+        assert_eq!(result.best(), Some((7, 5)));
+        // Move ordering tries the capture first, so plain UCT finds it too
+        let uct = search(&board, Stone::Black, &MctsSettings { rave_equivalence: 0.0, ..settings });
+        assert_eq!(uct.best(), Some((7, 5)));
+        assert_eq!(result.moves.iter().map(|(_, s)| s.visits).sum::<u32>(), 150);
+        assert!(result.moves.iter().all(|(_, s)| s.rave_visits >= s.visits));
+
+        // The strongest levels choose their moves by this search
+        assert_eq!(AIDifficulty::Advanced.profile().tree_playouts, 0);
+        assert!(LevelProfile::for_level(MAX_LEVEL).tree_playouts > 0);
+        let mut game = Game::new(9);
+        game.board = board.clone();
+        let mut trace = SearchTrace::new(&board, Stone::Black);
+        let ai = AI::for_level(MAX_LEVEL).with_seed(7);
+        assert_eq!(ai.get_best_move_traced(&game, Stone::Black, &mut trace), Some((7, 5)));
+        let written = SGFHandler::new().tree_to_sgf(&trace.to_sgf_tree());
+        assert!(written.contains(" visits, win rate ") && written.contains(", RAVE "), "{}", written);
+        assert_eq!(AI::for_level(MAX_LEVEL).get_game_move(&game, Stone::Black), Some((7, 5)));
+    }
+
+    #[test]
+    fn test_parallel_mcts() {
+        use gnugo_rs::config::Config;
+        use gnugo_rs::engine::ai::{AI, MAX_LEVEL};
+        use gnugo_rs::engine::mcts::{search, MctsSettings};
+        use gnugo_rs::engine::trace::SearchTrace;
+        use gnugo_rs::sgf::SGFHandler;
+
+        let mut config = Config::from_toml("[engine]\nthreads = 2").unwrap();
+        assert_eq!(config.limits.threads(), 2);
+        config.apply_args(&["--threads".to_string(), "3".to_string()]).unwrap();
+        assert_eq!(config.limits.threads(), 3);
+        assert!(Config::default().limits.threads() >= 1);
+
+        // Twelve white stones in atari decide the game
+        let board = Board::from_ascii(
+            ".........\n\
+             .XXXXXX..\n\
+             XOOOOOOX.\n\
+             XOOOOOOX.\n\
+             .XXXXX.X.\n\
+             .........\n\
+             OOOOOOOOO\n\
+             .........\n\
+             .........",
+        )
+        .unwrap();
+        let settings = MctsSettings { playouts: 400, threads: 4, ..MctsSettings::default() };
+        let result = search(&board, Stone::Black, &settings);
+        // Every virtual loss was taken back
+        assert_eq!(result.moves.iter().map(|(_, s)| s.visits).sum::<u32>(), 400);
+        assert_eq!(result.best(), Some((7, 5)));
+        assert_eq!(result.threads, 4);
+
+        // The configured thread count reaches genmove's tree search
+        let mut game = Game::new(9);
+        game.board = board.clone();
+        for threads in ["1", "3"] {
+            config.apply_args(&["--threads".to_string(), threads.to_string()]).unwrap();
+            let ai = AI::for_level(MAX_LEVEL).with_limits(config.limits);
+            assert_eq!(ai.mcts_settings(game.komi).threads.to_string(), threads);
+            let mut trace = SearchTrace::new(&board, Stone::Black);
+            assert_eq!(ai.get_best_move_traced(&game, Stone::Black, &mut trace), Some((7, 5)));
+            let written = SGFHandler::new().tree_to_sgf(&trace.to_sgf_tree());
+            assert!(written.contains(&format!("5000 playouts, {} threads", threads)), "{}", written);
+        }
+    }
+
+    #[test]
+    fn test_opening_book() {
+        use gnugo_rs::gtp::GTPHandler;
+        use gnugo_rs::sgf::book::{BookMove, OpeningBook};
+        use gnugo_rs::sgf::SGFHandler;
+
+        let handler = SGFHandler::new();
+        let mut book = OpeningBook::new();
+        for sgf in ["(;SZ[9];B[cc];W[gg];B[gc])", "(;SZ[9];B[cc];W[gc])", "(;SZ[9];B[gg];W[cc])"] {
+            book.add_game(&handler.parse(sgf).unwrap(), 2).unwrap();
+        }
+        // Only the first two moves of each game: the empty board and two replies to C7
+        assert_eq!(book.len(), 3);
+        let empty = Board::new(9);
+        assert_eq!(
+            book.moves(&empty, Stone::Black),
+            vec![BookMove { point: (3, 3), weight: 2 }, BookMove { point: (7, 7), weight: 1 }]
+        );
+        assert!(book.moves(&empty, Stone::White).is_empty());
+
+        let text = book.to_text();
+        assert_eq!(OpeningBook::from_text(&text).unwrap(), book);
+        assert!(OpeningBook::from_text("9 00ff B C7").is_err());
+
+        // Genmove answers C7 with one of the two replies from the games
+        let mut gtp = GTPHandler::new(9).with_book(book);
+        let mut output = Vec::new();
+        gtp.run_with("play black C7\ngenmove white\n".as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.ends_with("= G3\n\n") || output.ends_with("= G7\n\n"), "{}", output);
+    }
+
+    #[test]
+    fn test_time_management() {
+        use gnugo_rs::engine::ai::{AIDifficulty, AI, MAX_SEARCH_DEPTH};
+        use gnugo_rs::engine::clock::{Clock, TimeControl};
+        use gnugo_rs::engine::search::{alpha_beta_until, ordered_moves};
+        use std::time::{Duration, Instant};
+
+        // Byo-yomi budgets keep a second back for lag
+        let mut clock = Clock::new(TimeControl::japanese(0, 5, 3));
+        clock.set_time_left(Stone::Black, Duration::from_secs(5), 3).unwrap();
+        assert_eq!(clock.move_budget(Stone::Black), Some(Duration::from_secs(4)));
+        let mut clock = Clock::new(TimeControl::canadian(0, 30, 10));
+        clock.set_time_left(Stone::White, Duration::from_secs(20), 5).unwrap();
+        assert_eq!(clock.move_budget(Stone::White), Some(Duration::from_secs(3)));
+        let clock = Clock::new(TimeControl::canadian(u64::MAX, u64::MAX, 1));
+        assert_eq!(clock.move_budget(Stone::Black), Some(Duration::MAX));
+
+        // Plenty of time lets the search go as deep as it can; in a hurry
+        // only one ply is read
+        let advanced = AIDifficulty::Advanced.profile();
+        let relaxed = advanced.with_time(Duration::from_secs(20));
+        assert_eq!(relaxed.search_depth, MAX_SEARCH_DEPTH);
+        assert_eq!(relaxed.search_nodes, 4 * advanced.search_nodes);
+        let hurried = advanced.with_time(Duration::from_millis(300));
+        assert_eq!((hurried.search_depth, hurried.endgame_depth), (1, 1));
+        assert_eq!(AIDifficulty::Intermediate.profile().with_time(Duration::from_secs(20)).search_depth, 1);
+
+        // The deadline stops iterative deepening with the last full iteration
+        let board = Board::from_ascii(
+            ".........\n\
+             .........\n\
+             ..X...O..\n\
+             .........\n\
+             ....X....\n\
+             .........\n\
+             ..O...X..\n\
+             .........\n\
+             .........",
+        )
+        .unwrap();
+        let moves = ordered_moves(&board, Stone::White, 8);
+        let started = Instant::now();
+        let result = alpha_beta_until(&board, Stone::White, &moves, MAX_SEARCH_DEPTH, usize::MAX, Some(started + Duration::from_millis(50)));
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert!(result.depth >= 1 && result.depth < MAX_SEARCH_DEPTH, "{}", result.depth);
+        assert_eq!(result.values.len(), moves.len());
+
+        let ai = AI::new(AIDifficulty::Advanced).with_time_budget(Some(Duration::from_millis(200)));
+        assert!(ai.get_best_move(&board, Stone::White).is_some());
+    }
+
+    #[test]
+    fn test_resign_rule() {
+        use gnugo_rs::config::Config;
+        use gnugo_rs::engine::ai::{AIDifficulty, ResignRule, AI};
+        use gnugo_rs::gtp::GTPHandler;
+
+        let config = Config::from_toml("[engine]\nresign_threshold = 0.3\nresign_moves = 2\n").unwrap();
+        assert_eq!(config.resign, ResignRule { threshold: 0.3, moves: 2 });
+        assert!(Config::from_toml("[engine]\nresign_threshold = -1.0\n").is_err());
+
+        // White walls off all but two columns, Black answering on the B file
+        let mut game = Game::new(9);
+        for y in 1..=9 {
+            game.play_move(Stone::Black, Some((2, y))).unwrap();
+            game.play_move(Stone::White, Some((3, y))).unwrap();
+            game.play_move(Stone::White, Some((7, y))).unwrap();
+        }
+        game.play_move(Stone::Black, Some((1, 1))).unwrap();
+        game.play_move(Stone::White, Some((9, 9))).unwrap();
+        let rule = |moves| AI::new(AIDifficulty::Intermediate).with_resign_rule(ResignRule { threshold: 0.25, moves });
+        assert!(rule(1).should_resign_game(&game, Stone::Black));
+        assert!(rule(2).should_resign_game(&game, Stone::Black));
+        // Hopeless only since the wall closed, not for the whole game
+        assert!(!rule(9).should_resign_game(&game, Stone::Black));
+        assert!(!rule(0).should_resign_game(&game, Stone::Black));
+
+        let err = Config::from_toml("[engine]\nresign_moves = -1\n").unwrap_err();
+        assert!(err.contains("'engine.resign_moves' must be a non-negative integer"), "{}", err);
+        assert!(Config::from_toml("[engine]\nseed = -3\n").unwrap_err().contains("'engine.seed' must be a non-negative integer"));
+        let config = Config::from_toml("[engine]\nresign_moves = 0\n").unwrap();
+        let mut commands = String::new();
+        for row in 1..=9 {
+            commands += &format!("play black B{row}\nplay white C{row}\nplay white G{row}\n");
+        }
+        commands += "genmove black\n";
+        let mut output = Vec::new();
+        GTPHandler::with_config(&config).run_with(commands.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(!output.contains("resign"), "{}", output);
+    }
+
+    #[test]
+    fn test_numeric_levels() {
+        use gnugo_rs::config::Config;
+        use gnugo_rs::engine::ai::{AIDifficulty, LevelProfile, MAX_LEVEL};
+        use gnugo_rs::gtp::GTPHandler;
+
+        // The named difficulties sit at levels 1, 5 and 8
+        for difficulty in [AIDifficulty::Beginner, AIDifficulty::Intermediate, AIDifficulty::Advanced] {
+            assert_eq!(LevelProfile::for_level(difficulty.level()), difficulty.profile());
+            assert_eq!(AIDifficulty::from_level(difficulty.level()), difficulty);
+        }
+        assert!(!LevelProfile::for_level(2).dragons && !LevelProfile::for_level(2).endgame);
+        assert!(!LevelProfile::for_level(3).dragons && LevelProfile::for_level(3).endgame);
+        let depths: Vec<usize> = (6..=MAX_LEVEL).map(|level| LevelProfile::for_level(level).search_depth).collect();
+        assert!(depths.windows(2).all(|pair| pair[0] <= pair[1]) && depths[0] < depths[4]);
+
+        let config = Config::from_toml("[engine]\nlevel = 7\n").unwrap();
+        assert_eq!((config.strength, config.level), (7, AIDifficulty::Advanced));
+        let mut config = Config::default();
+        config.apply_args(&["--level".to_string(), "advanced".to_string()]).unwrap();
+        assert_eq!(config.strength, 8);
+        assert!(config.apply_args(&["--level".to_string(), "11".to_string()]).is_err());
+
+        let mut output = Vec::new();
+        GTPHandler::new(9).run_with("level 3\nlevel 11\nlevel\ngenmove black\n".as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let replies: Vec<&str> = output.split("\n\n").filter(|r| !r.is_empty()).collect();
+        assert_eq!(replies[0], "=");
+        assert!(replies[1].starts_with('?') && replies[2].starts_with('?'));
+        assert!(replies[3].starts_with("= "), "{}", output);
+    }
+
+    #[test]
+    fn test_random_seed() {
+        use gnugo_rs::config::Config;
+        use gnugo_rs::engine::ai::{AIDifficulty, AI};
+        use gnugo_rs::gtp::GTPHandler;
+
+        // The same seed plays the same random game
+        let play = |seed: u64| {
+            let ai = AI::new(AIDifficulty::Beginner).with_seed(seed);
+            let mut game = Game::new(9);
+            for _ in 0..10 {
+                let color = game.current_player();
+                let point = ai.get_best_move(&game.board, color);
+                game.play_move(color, point).unwrap();
+            }
+            game.moves()
+        };
+        assert_eq!(play(7), play(7));
+        assert_ne!(play(7), play(8));
+
+        let session = |config: &Config| {
+            let mut output = Vec::new();
+            let commands = "level 1\ngenmove black\ngenmove white\ngenmove black\nget_random_seed\n";
+            GTPHandler::with_config(config).run_with(commands.as_bytes(), &mut output).unwrap();
+            String::from_utf8(output).unwrap()
+        };
+        let mut config = Config::default();
+        config.apply_args(&["--seed".to_string(), "42".to_string()]).unwrap();
+        assert_eq!(config.seed, Some(42));
+        let first = session(&config);
+        assert_eq!(first, session(&config));
+        assert!(first.ends_with("= 42\n\n"), "{}", first);
+
+        let mut output = Vec::new();
+        GTPHandler::new(9).run_with("set_random_seed 42\nset_random_seed x\nget_random_seed\n".as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with("=\n\n?") && output.ends_with("= 42\n\n"), "{}", output);
+    }
+
+    #[test]
+    fn test_top_moves() {
+        use gnugo_rs::engine::ai::{AIDifficulty, AI, TOP_MOVES_DEPTH};
+        use gnugo_rs::gtp::GTPHandler;
+
+        // A white stone in atari: taking it heads the list
+        let mut board = Board::new(9);
+        board.place_stone(5, 5, Stone::White).unwrap();
+        board.place_stone(4, 5, Stone::Black).unwrap();
+        board.place_stone(6, 5, Stone::Black).unwrap();
+        board.place_stone(5, 4, Stone::Black).unwrap();
+        let top = AI::new(AIDifficulty::Intermediate).top_moves(&board, Stone::Black, 3);
+        assert_eq!(top.len(), 3);
+        assert_eq!(top[0].point, (5, 6));
+        assert!(top.windows(2).all(|pair| pair[0].value >= pair[1].value));
+        for m in &top {
+            assert_eq!(m.pv.first(), Some(&m.point));
+            assert_eq!(m.pv.len(), TOP_MOVES_DEPTH);
+            assert!(m.winrate > 0.0 && m.winrate < 1.0);
+        }
+        assert!(top[0].winrate > 0.5);
+
+        let mut output = Vec::new();
+        GTPHandler::new(9).run_with("top_moves 2\ntop_moves 0\n".as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let (first, second) = output.split_once("\n\n").unwrap();
+        assert_eq!(first.lines().count(), 2, "{}", output);
+        assert!(first.starts_with("= ") && first.contains(" winrate ") && first.contains(" pv "), "{}", output);
+        assert!(second.starts_with("?"), "{}", output);
+    }
+
+    #[test]
+    fn test_self_atari_and_eye_filters() {
+        use gnugo_rs::engine::ai::AI;
+        use gnugo_rs::engine::fast_board::FastBoard;
+        use gnugo_rs::engine::move_generation::{Move, MoveGenerator};
+        use gnugo_rs::engine::rules::GameRules;
+
+        let place = |stones: &[((usize, usize), Stone)]| {
+            let mut board = Board::new(9);
+            for &((x, y), stone) in stones {
+                board.place_stone(x, y, stone).unwrap();
+            }
+            board
+        };
+        let (b, w) = (Stone::Black, Stone::White);
+
+        // Black's corner eye is legal to fill but never sensible
+        let eye = place(&[((2, 1), b), ((1, 2), b), ((2, 2), b)]);
+        assert!(MoveGenerator::fills_own_eye(&eye, 1, 1, b));
+        assert!(!MoveGenerator::fills_own_eye(&eye, 1, 1, w));
+        let rules = GameRules::default();
+        assert!(MoveGenerator::generate_valid_moves(&eye, b, &rules, &[]).contains(&Move::new(1, 1)));
+        assert!(!MoveGenerator::generate_sensible_moves(&eye, b, &rules, &[]).contains(&Move::new(1, 1)));
+        assert!(!FastBoard::from_board(&eye).is_playout_move(1, 1, b));
+
+        // Extending a stone into atari
+        let atari = place(&[((5, 5), b), ((4, 5), w), ((6, 5), w), ((5, 4), w), ((4, 6), w), ((6, 6), w)]);
+        assert!(MoveGenerator::is_self_atari(&atari, 5, 6, b));
+        assert!(!MoveGenerator::is_sensible(&atari, 5, 6, b));
+        assert!(!FastBoard::from_board(&atari).is_playout_move(5, 6, b));
+        assert!(FastBoard::from_board(&atari).is_playout_move(5, 7, b));
+
+        // Nakade: filling White's straight three leaves one eye
+        let nakade = place(&[((1, 1), b), ((4, 1), w), ((1, 2), w), ((2, 2), w), ((3, 2), w), ((4, 2), w)]);
+        assert!(MoveGenerator::is_self_atari(&nakade, 2, 1, b));
+        assert!(MoveGenerator::is_sensible(&nakade, 2, 1, b));
+
+        // Snapback: the throw-in is taken back with five stones
+        let snapback = place(&[
+            ((1, 2), w), ((2, 2), w), ((3, 2), w), ((3, 1), w),
+            ((1, 3), b), ((2, 3), b), ((3, 3), b), ((4, 2), b), ((4, 1), b),
+        ]);
+        assert!(MoveGenerator::is_self_atari(&snapback, 1, 1, b));
+        assert!(MoveGenerator::is_sacrifice(&snapback, 1, 1, b));
+        assert!(MoveGenerator::is_sensible(&snapback, 1, 1, b));
+        assert!(FastBoard::from_board(&snapback).is_playout_move(1, 1, b));
+
+        // Both sides are alive with only eyes left: every level passes
+        // rather than fill one of its own
+        let mut finished = Board::new(5);
+        for y in 1..=5 {
+            for x in 1..=5 {
+                let eye = y % 2 == 1 && (x == 1 || x == 5);
+                if !eye {
+                    finished.set_stone(x, y, if x <= 3 { b } else { w });
+                }
+            }
+        }
+        for level in [1, 5, 8] {
+            for color in [b, w] {
+                assert_eq!(AI::for_level(level).get_best_move(&finished, color), None, "level {} {:?}", level, color);
+            }
+        }
+    }
+
+    #[test]
+    fn test_move_ordering() {
+        use gnugo_rs::engine::move_generation::Move;
+        use gnugo_rs::engine::search::{order_moves, ordered_moves};
+
+        let mut board = Board::new(9);
+        // A white stone to capture at E4, a black stone to save at J8
+        for (x, y, stone) in [(5, 5, Stone::White), (4, 5, Stone::Black), (6, 5, Stone::Black), (5, 4, Stone::Black)] {
+            board.place_stone(x, y, stone).unwrap();
+        }
+        board.place_stone(9, 1, Stone::Black).unwrap();
+        board.place_stone(8, 1, Stone::White).unwrap();
+        // White's last move at G3
+        board.place_stone(7, 7, Stone::White).unwrap();
+
+        let capture = Move::new(5, 6);
+        let rescue = Move::new(9, 2);
+        let near = Move::new(7, 9);
+        let far = Move::new(3, 7);
+        let ordered = order_moves(&board, Stone::Black, &[far, near, rescue, capture]);
+        assert_eq!(ordered, vec![capture, rescue, near, far]);
+
+        // Nearer the center wins once the last move is out of reach
+        let mut quiet = Board::new(9);
+        quiet.place_stone(1, 1, Stone::White).unwrap();
+        assert_eq!(order_moves(&quiet, Stone::Black, &[Move::new(7, 9), Move::new(3, 7)]), vec![Move::new(3, 7), Move::new(7, 9)]);
+
+        assert_eq!(ordered_moves(&board, Stone::Black, 2), vec![(5, 6), (9, 2)]);
+    }
+
+    #[test]
+    fn test_solve_life_death() {
+        use gnugo_rs::engine::tsumego::{solve_life_death, LifeStatus};
+
+        // White's straight three in the corner: the middle point decides
+        let mut board = Board::new(9);
+        for &(x, y) in &[(1, 3), (2, 3), (3, 3), (4, 3), (4, 2), (5, 1)] {
+            board.set_stone(x, y, Stone::Black);
+        }
+        for &(x, y) in &[(1, 2), (2, 2), (3, 2), (4, 1)] {
+            board.set_stone(x, y, Stone::White);
+        }
+        let corner: Vec<(usize, usize)> = (1..=2).flat_map(|y| (1..=4).map(move |x| (x, y))).collect();
+        let kill = solve_life_death(&board, &corner, Stone::Black).unwrap();
+        assert_eq!(kill.target, (1, 2));
+        assert_eq!(kill.status, LifeStatus::Dead);
+        assert_eq!(kill.solving_move, Some((2, 1)));
+        // White's best leaves a ko: Black takes the cut-off D9 from C9
+        let live = solve_life_death(&board, &corner, Stone::White).unwrap();
+        assert_eq!(live.status, LifeStatus::Ko);
+        assert_eq!(live.solving_move, Some((2, 1)));
+
+        // The white stone at B8 lives or dies by the ko at C8
+        let ko = Board::from_ascii(
+            ".XO......\n\
+             XO.O.....\n\
+             .XO......\n\
+             .........\n\
+             .........\n\
+             .........\n\
+             .........\n\
+             .........\n\
+             .........",
+        )
+        .unwrap();
+        let region = [(2, 2), (3, 2)];
+        let fight = solve_life_death(&ko, &region, Stone::Black).unwrap();
+        assert_eq!(fight.status, LifeStatus::Ko);
+        assert_eq!(fight.solving_move, Some((3, 2)));
+        assert_eq!(fight.status.name(), "ko");
+        let connect = solve_life_death(&ko, &region, Stone::White).unwrap();
+        assert_eq!(connect.status, LifeStatus::Alive);
+        assert_eq!(connect.solving_move, Some((3, 2)));
+
+        assert!(solve_life_death(&ko, &[(5, 5)], Stone::Black).is_err());
+    }
+
+    #[test]
+    fn test_tiny_board_solver() {
+        use gnugo_rs::engine::solver::{solve_game, DEFAULT_SOLVE_NODES};
+
+        // The known results under area scoring and positional superko
+        let two = solve_game(&Board::new(2), Stone::Black, 0.0, DEFAULT_SOLVE_NODES).unwrap();
+        assert_eq!(two.score, 1);
+        let three = solve_game(&Board::new(3), Stone::Black, 0.0, DEFAULT_SOLVE_NODES).unwrap();
+        assert_eq!((three.score, three.best_move), (9, Some((2, 2))));
+        assert_eq!(three.winner(), Some(Stone::Black));
+
+        // A point of komi evens out the smallest board; White to move wins it
+        let draw = solve_game(&Board::new(2), Stone::Black, 1.0, DEFAULT_SOLVE_NODES).unwrap();
+        assert_eq!(draw.winner(), None);
+        let white = solve_game(&Board::new(2), Stone::White, 0.0, DEFAULT_SOLVE_NODES).unwrap();
+        assert_eq!(white.score, -1);
+        assert_eq!(white.winner(), Some(Stone::White));
+
+        assert!(solve_game(&Board::new(3), Stone::Black, 0.0, 10).is_err());
+        assert!(solve_game(&Board::new(9), Stone::Black, 0.0, DEFAULT_SOLVE_NODES).is_err());
+    }
+
+    #[test]
+    fn test_temperature_choice() {
+        use gnugo_rs::engine::ai::{choose_by_temperature, AIDifficulty, LevelProfile};
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let moves = [((1, 1), 10), ((2, 2), 20), ((3, 3), 20), ((4, 4), -50)];
+        let mut rng = StdRng::seed_from_u64(1);
+        // At temperature 0 the first of the best is always played
+        assert_eq!(choose_by_temperature(&moves, 0.0, &mut rng), Some((2, 2)));
+        assert_eq!(choose_by_temperature(&[], 5.0, &mut rng), None);
+
+        let mut counts = [0; 4];
+        for _ in 0..1000 {
+            let (x, _) = choose_by_temperature(&moves, 5.0, &mut rng).unwrap();
+            counts[x - 1] += 1;
+        }
+        // Ten points worse is picked about e^-2 as often, seventy points never
+        assert!(counts[1] > 3 * counts[0] && counts[2] > 3 * counts[0], "{:?}", counts);
+        assert!(counts[0] > 0 && counts[3] == 0, "{:?}", counts);
+
+        // Weaker levels spread their choice more; stronger ones play the best
+        let temperatures: Vec<f32> = (1..=5).map(|level| LevelProfile::for_level(level).temperature).collect();
+        assert!(temperatures.windows(2).all(|w| w[0] > w[1]), "{:?}", temperatures);
+        assert_eq!(AIDifficulty::Intermediate.profile().temperature, 0.0);
+        assert_eq!(AIDifficulty::Advanced.profile().temperature, 0.0);
+    }
+
+    #[test]
+    fn test_parse_text_patterns() {
+        use gnugo_rs::patterns::pattern_parser::Edges;
+        use gnugo_rs::patterns::{parse_patterns, PatternElement, PatternType, PatternDatabase};
+
+        let text = "# From GNU Go's patterns.db\n\
+            attribute_map general\n\
+            \n\
+            Pattern CC2\n\
+            \n\
+            ?.X        connect against peep\n\
+            O*O\n\
+            ?X?\n\
+            \n\
+            :8,nBC,value(35),shape(1)\n\
+            \n\
+            ?aX\n\
+            b*c\n\
+            ?X?\n\
+            \n\
+            ;xplay_attack_either(*,a,a,b)\n\
+            ;|| xplay_attack(*,a,c)\n\
+            \n\
+            callback_data none\n\
+            \n\
+            Pattern EE1\n\
+            \n\
+            |.Ox\n\
+            |X*.\n\
+            +---\n\
+            \n\
+            :|,OX\n\
+            >add_cut(*)\n";
+        let patterns = parse_patterns(text).unwrap();
+        assert_eq!(patterns.len(), 2);
+        let peep = &patterns[0];
+        assert_eq!(peep.name, "CC2");
+        assert_eq!(peep.dimensions(), (3, 3));
+        assert_eq!(peep.anchor, Some((1, 1)));
+        assert_eq!(peep.grid[1], vec![PatternElement::Own, PatternElement::Move, PatternElement::Own]);
+        assert_eq!(peep.grid[0][0], PatternElement::Any);
+        assert_eq!((peep.symmetry, peep.class.as_str(), peep.value), ('8', "nBC", 35.0));
+        assert_eq!(peep.attributes, vec![("shape".to_string(), Some("1".to_string()))]);
+        assert_eq!(peep.labels, vec![('a', (0, 1)), ('b', (1, 0)), ('c', (1, 2))]);
+        assert_eq!(peep.constraint.as_deref(), Some("xplay_attack_either(*,a,a,b) || xplay_attack(*,a,c)"));
+
+        let edge = &patterns[1];
+        assert_eq!(edge.edges, Edges { top: false, bottom: true, left: true, right: false });
+        assert_eq!(edge.grid[0], vec![PatternElement::Empty, PatternElement::Own, PatternElement::OpponentOrEmpty]);
+        assert_eq!((edge.symmetry, edge.anchor, edge.action.as_deref()), ('|', Some((1, 1)), Some("add_cut(*)")));
+
+        assert!(parse_patterns("Pattern A\n\n.*.\n..\n\n:8,-\n").unwrap_err().contains("not rectangular"));
+        assert!(parse_patterns("Pattern A\n\n.*.\n\nPattern B\n").unwrap_err().contains("no ':' line"));
+        assert!(parse_patterns("not a database\n").is_err());
+
+        // The loader reads the text format when the binary header is missing
+        let dir = std::env::temp_dir().join(format!("gnugo_rs_text_patterns_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("attack.db");
+        std::fs::write(&path, text).unwrap();
+        let db = PatternDatabase::load_from_file(path.to_str().unwrap(), PatternType::Attack).unwrap();
+        assert_eq!(db.get_pattern_count(), 2);
+        assert_eq!(db.get_pattern_values(0).unwrap()[0].value, 35);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_pattern_grid_matching() {
+        use gnugo_rs::patterns::{find_patterns_at, parse_patterns, PatternDatabase, PatternMatcher, PatternType, Transformation};
+
+        let text = "Pattern T1\n\n.X.\nO*.\n\n:8,-,value(20)\n\nPattern E1\n\n|*O\n|..\n\n:8,-,value(5)\n";
+        let mut db = PatternDatabase::new("test", PatternType::Attack);
+        for (id, pattern) in parse_patterns(text).unwrap().iter().enumerate() {
+            db.add_text_pattern(id as u32, pattern);
+        }
+        let found = |board: &Board, x, y| {
+            let mut found: Vec<(u32, Stone, Transformation)> =
+                find_patterns_at(board, x, y, &db).iter().map(|m| (m.pattern_id, m.color, m.transform)).collect();
+            found.sort_by_key(|&(id, color, _)| (id, color == Stone::White));
+            found
+        };
+
+        let mut board = Board::new(9);
+        board.place_stone(3, 5, Stone::Black).unwrap();
+        board.place_stone(4, 4, Stone::White).unwrap();
+        assert_eq!(found(&board, 4, 5)[0], (0, Stone::Black, Transformation::Identity));
+        // Turned half round and with the colors swapped; mirrored along the
+        // diagonal it fits Black too
+        let mut turned = Board::new(9);
+        turned.place_stone(7, 5, Stone::White).unwrap();
+        turned.place_stone(6, 6, Stone::Black).unwrap();
+        assert_eq!(
+            found(&turned, 6, 5),
+            vec![(0, Stone::Black, Transformation::MirrorRot270), (0, Stone::White, Transformation::Rot180)]
+        );
+        assert!(found(&board, 6, 5).is_empty());
+
+        // The edge pattern needs the board edge beside it, on whichever side
+        let mut edge = Board::new(9);
+        edge.place_stone(2, 5, Stone::Black).unwrap();
+        edge.place_stone(8, 3, Stone::White).unwrap();
+        assert_eq!(found(&edge, 1, 5), vec![(1, Stone::Black, Transformation::Identity)]);
+        assert_eq!(found(&edge, 9, 3).iter().map(|&(id, color, _)| (id, color)).collect::<Vec<_>>(), vec![(1, Stone::White)]);
+        assert!(found(&edge, 3, 5).is_empty());
+
+        // The matcher reports every anchor point on the board
+        let dir = std::env::temp_dir().join(format!("gnugo_rs_grid_patterns_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("attack.db"), text).unwrap();
+        let mut matcher = PatternMatcher::new();
+        matcher.reload_databases(&dir);
+        let matches = matcher.find_matching_patterns(&edge, PatternType::Attack);
+        let mut points: Vec<(usize, usize)> = matches.iter().map(|m| m.position).collect();
+        points.sort();
+        points.dedup();
+        assert_eq!(points, vec![(1, 5), (9, 3)]);
+        assert!(matches.iter().all(|m| m.pattern_id == 1 && m.value == 5));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_fuseki_database() {
+        use gnugo_rs::gtp::GTPHandler;
+        use gnugo_rs::patterns::fuseki::{canonical_hash, FusekiDatabase, FusekiMove};
+        use gnugo_rs::sgf::SGFHandler;
+
+        let handler = SGFHandler::new();
+        let mut fuseki = FusekiDatabase::new();
+        // The same opening in three orientations, and a board size without fuseki
+        for sgf in ["(;SZ[9];B[cc];W[gg];B[gc])", "(;SZ[9];B[gg];W[cc])", "(;SZ[9];B[gc];W[cg])"] {
+            assert!(fuseki.add_game(&handler.parse(sgf).unwrap(), 2).unwrap());
+        }
+        assert!(!fuseki.add_game(&handler.parse("(;SZ[7];B[cc];W[ee])").unwrap(), 2).unwrap());
+        assert_eq!(fuseki.len(), 2);
+        let empty = Board::new(9);
+        assert_eq!(fuseki.moves(&empty, Stone::Black), vec![FusekiMove { point: (3, 3), count: 3 }]);
+
+        // Rotations and reflections share a key, and moves come back in the board's own orientation
+        let mut upper_right = Board::new(9);
+        upper_right.place_stone(7, 3, Stone::Black).unwrap();
+        let mut lower_left = Board::new(9);
+        lower_left.place_stone(3, 7, Stone::Black).unwrap();
+        assert_eq!(canonical_hash(&upper_right).0, canonical_hash(&lower_left).0);
+        assert_eq!(fuseki.moves(&upper_right, Stone::White), vec![FusekiMove { point: (3, 7), count: 3 }]);
+        assert_eq!(fuseki.moves(&lower_left, Stone::White), vec![FusekiMove { point: (7, 3), count: 3 }]);
+
+        let text = fuseki.to_text();
+        assert_eq!(FusekiDatabase::from_text(&text).unwrap(), fuseki);
+        assert!(FusekiDatabase::from_text("9 00ff B C7").is_err());
+
+        let mut gtp = GTPHandler::new(9).with_fuseki(fuseki);
+        let mut output = Vec::new();
+        gtp.run_with("play black G7\ngenmove white\n".as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.ends_with("= C3\n\n"), "{}", output);
+    }
+
+    #[test]
+    fn test_pattern_cache_follows_board() {
+        use gnugo_rs::patterns::{PatternMatchResult, PatternMatcher, PatternType};
+
+        // A stone with an empty point beside it
+        let dir = std::env::temp_dir().join(format!("gnugo_rs_cache_patterns_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("attack.db"), "Pattern S1\n\nO*\n\n:8,-,value(5)\n").unwrap();
+        let mut matcher = PatternMatcher::new();
+        matcher.reload_databases(&dir);
+        let summary = |matches: &[PatternMatchResult]| -> Vec<((usize, usize), Stone)> {
+            matches.iter().map(|m| (m.position, m.color)).collect()
+        };
+
+        let mut board = Board::new(9);
+        board.place_stone(5, 5, Stone::White).unwrap();
+        for (x, y) in [(4, 5), (6, 5), (5, 4)] {
+            board.place_stone(x, y, Stone::Black).unwrap();
+        }
+        let before = summary(&matcher.find_matching_patterns(&board, PatternType::Attack));
+        assert_eq!(before.iter().filter(|&&(_, color)| color == Stone::White).map(|&(point, _)| point).collect::<Vec<_>>(), vec![(5, 6)]);
+
+        // Capturing the white stone leaves nothing for White to match, as a fresh matcher also finds
+        board.place_stone(5, 6, Stone::Black).unwrap();
+        assert_eq!(board.get_stone(5, 5), Stone::Empty);
+        let after = summary(&matcher.find_matching_patterns(&board, PatternType::Attack));
+        assert!(after.iter().all(|&(_, color)| color == Stone::Black));
+        assert!(after.contains(&((5, 5), Stone::Black)));
+        let mut fresh = PatternMatcher::new();
+        fresh.reload_databases(&dir);
+        assert_eq!(summary(&fresh.find_matching_patterns(&board, PatternType::Attack)), after);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_pattern_tuning() {
+        use gnugo_rs::patterns::{parse_patterns, write_values, PatternTuner};
+        use gnugo_rs::sgf::review::Rank;
+        use gnugo_rs::sgf::SGFHandler;
+
+        let text = "# Test patterns\n\nPattern Extend\n\nO*\n\n:8,-,value(10)\n\nPattern Jump\n\nO.*\n\n:8,-,value(10)\n\nPattern Plain\n\nO*\n\n:8,-\n";
+        // Both players stretch a line one point at a time
+        let game = SGFHandler::new().parse("(;SZ[9]BR[2d]WR[3d];B[ee];W[cc];B[ef];W[cd];B[eg];W[ce];B[eh];W[cf])").unwrap();
+
+        let mut tuner = PatternTuner::new(parse_patterns(text).unwrap());
+        assert_eq!(tuner.add_game(&game).unwrap(), 8);
+        let counts = tuner.counts();
+        assert_eq!(counts[0].played, 6);
+        assert_eq!(counts[1].played, 0);
+        assert!(counts[1].matched >= 10);
+        let values = tuner.tuned_values();
+        assert!(values[0] > 10.0 && values[1] < 10.0 && values[2] == 0.0, "{:?}", values);
+
+        let tuned = write_values(text, &values).unwrap();
+        assert!(tuned.starts_with("# Test patterns\n"));
+        let reparsed: Vec<f32> = parse_patterns(&tuned).unwrap().iter().map(|p| p.value).collect();
+        assert_eq!(reparsed, values);
+        assert!(write_values(text, &values[..2]).is_err());
+
+        // Only players ranked high enough count
+        assert_eq!((Rank::parse("5k"), Rank::parse("3d"), Rank::parse("1p")), (Some(Rank::Kyu(5)), Some(Rank::Dan(3)), Some(Rank::Dan(9))));
+        let mut strong = PatternTuner::new(parse_patterns(text).unwrap()).with_min_rank(Rank::Dan(3));
+        assert_eq!(strong.add_game(&game).unwrap(), 4);
+        let mut stronger = PatternTuner::new(parse_patterns(text).unwrap()).with_min_rank(Rank::Dan(5));
+        assert_eq!(stronger.add_game(&game).unwrap(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "cdylib")]
+    fn test_c_api() {
+        use gnugo_rs::ffi::*;
+        use std::ffi::CString;
+        use std::ptr;
+
+        unsafe {
+            assert!(gg_board_new(1).is_null() && gg_board_new(26).is_null());
+            let board = gg_board_new(9);
+            assert_eq!(gg_board_size(board), 9);
+            gg_set_komi(board, 6.5);
+            assert_eq!(gg_get_komi(board), 6.5);
+
+            // (i, j) is the row from the top and the column, 0-based
+            assert_eq!(gg_play(board, 2, 3, GG_BLACK), 1);
+            assert_eq!(gg_get_color(board, 2, 3), GG_BLACK);
+            assert_eq!(gg_is_legal(board, 2, 3, GG_WHITE), 0);
+            assert_eq!(gg_play(board, 9, 0, GG_WHITE), 0);
+            assert_eq!(gg_countlib(board, 2, 3), 4);
+            assert_eq!(gg_to_move(board), GG_WHITE);
+
+            for level in [0, 11, -1] {
+                assert_eq!(gg_set_level(board, level), 0, "{}", level);
+            }
+            assert_eq!(gg_set_level(board, 1), 1);
+            let (mut i, mut j) = (0, 0);
+            assert_eq!(gg_genmove(board, GG_WHITE, &mut i, &mut j), 1);
+            assert!(gg_get_color(board, i, j) == GG_WHITE || (i, j) == (GG_PASS, GG_PASS));
+            assert_eq!(gg_undo(board), 1);
+            assert_eq!(gg_get_color(board, i, j), if (i, j) == (2, 3) { GG_BLACK } else { GG_EMPTY });
+
+            let (mut black, mut white) = (0.0, 0.0);
+            assert_eq!(gg_score(board, &mut black, &mut white), black - white - 6.5);
+            assert_eq!(gg_load_sgf(board, ptr::null()), 0);
+            let missing = CString::new("/nonexistent/game.sgf").unwrap();
+            assert_eq!(gg_load_sgf(board, missing.as_ptr()), 0);
+
+            gg_clear_board(board);
+            assert_eq!((gg_get_color(board, 2, 3), gg_get_komi(board)), (GG_EMPTY, 6.5));
+            gg_board_free(board);
+
+            // NULL handles are refused rather than dereferenced
+            assert_eq!((gg_board_size(ptr::null()), gg_play(ptr::null_mut(), 0, 0, GG_BLACK)), (0, 0));
+            gg_board_free(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "wasm")]
+    fn test_wasm_api() {
+        use gnugo_rs::wasm::WasmGame;
+
+        // Only the success paths: JsValue errors need a JavaScript host
+        let mut game = WasmGame::new(9, 6.5).unwrap();
+        assert_eq!((game.size(), game.komi(), game.to_move()), (9, 6.5, "black".to_string()));
+        game.play("E5").unwrap();
+        game.play("pass").unwrap();
+        assert_eq!(game.board()[4], "....X....");
+        assert_eq!(game.to_move(), "black");
+        assert_eq!(game.score(), 81.0 - 6.5);
+
+        // The engine answers with a vertex it has played
+        game.set_level("beginner").unwrap();
+        let vertex = game.genmove().unwrap();
+        assert!(vertex == "pass" || game.board().concat().matches('X').count() == 2, "{}", vertex);
+        assert_eq!(game.to_move(), "white");
+        assert!(game.undo() && game.undo() && game.undo() && !game.undo());
+        assert_eq!(game.board().concat(), ".".repeat(81));
+        assert!(!game.is_over());
+
+        // SGF round trip through the main line
+        let sgf = "(;GM[1]FF[4]SZ[9]KM[5.5];B[ee];W[cc];B[])";
+        let game = WasmGame::from_sgf(sgf).unwrap();
+        assert_eq!((game.size(), game.komi(), game.to_move()), (9, 5.5, "white".to_string()));
+        assert_eq!((game.board()[2].as_str(), game.board()[4].as_str()), ("..O......", "....X...."));
+        let copy = WasmGame::from_sgf(&game.to_sgf().unwrap()).unwrap();
+        assert_eq!(copy.board(), game.board());
+    }
 }